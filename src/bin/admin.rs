@@ -1,22 +1,39 @@
 //! Admin utility for SurrealMind database inspection and maintenance.
 //!
-//! This consolidates several debug/admin binaries into a single CLI with subcommands.
+//! This consolidates several debug/admin binaries into a single CLI with subcommands. The
+//! maintenance subcommands call the same library functions as the MCP `maintain`, `export`
+//! and `import` tools; `--json` prints their result as JSON instead of a key/value table, and
+//! the exit status is nonzero when a command fails or reports `ok: false`.
 //!
 //! Usage:
-//!   cargo run --bin admin -- inspect
-//!   cargo run --bin admin -- sanity-cosine
-//!   cargo run --bin admin -- db-check
-//!   cargo run --bin admin -- check-contents
-//!   cargo run --bin admin -- simple-test
-//!   cargo run --bin admin -- fix-dims
+//!   cargo run --bin surreal-mind-admin -- inspect
+//!   cargo run --bin surreal-mind-admin -- sanity-cosine
+//!   cargo run --bin surreal-mind-admin -- db-check
+//!   cargo run --bin surreal-mind-admin -- check-contents
+//!   cargo run --bin surreal-mind-admin -- simple-test
+//!   cargo run --bin surreal-mind-admin -- fix-dims
+//!   cargo run --bin surreal-mind-admin -- reembed --missing-only --dry-run
+//!   cargo run --bin surreal-mind-admin -- reembed-kg --limit 500
+//!   cargo run --bin surreal-mind-admin -- kg export ~/kg.jsonl --include-embeddings
+//!   cargo run --bin surreal-mind-admin -- kg import ~/kg.jsonl --mode rename
+//!   cargo run --bin surreal-mind-admin -- audit --sample 5
+//!   cargo run --bin surreal-mind-admin -- --json schema-check --apply
+//!   cargo run --bin surreal-mind-admin -- health
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use serde_json::{Value, json};
+use std::process::ExitCode;
+use surreal_mind::config::Config;
+use surreal_mind::server::connection::{DbConnection, DbSettings};
 
 #[derive(Parser)]
-#[command(name = "admin")]
+#[command(name = "surreal-mind-admin")]
 #[command(about = "SurrealMind admin utilities", long_about = None)]
 struct Cli {
+    /// Print results as JSON instead of a table
+    #[arg(long, global = true)]
+    json: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -35,19 +52,280 @@ enum Commands {
     SimpleTest,
     /// Fix embedding dimension mismatches by re-embedding
     FixDims,
+    /// Re-embed thoughts with the configured embedder
+    Reembed {
+        #[arg(long, default_value_t = 100)]
+        batch_size: usize,
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Only embed thoughts that have no embedding
+        #[arg(long)]
+        missing_only: bool,
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long, default_value_t = 1)]
+        concurrency: usize,
+    },
+    /// Re-embed KG entities and observations with the configured embedder
+    ReembedKg {
+        #[arg(long)]
+        limit: Option<usize>,
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Export or import the knowledge graph
+    Kg {
+        #[command(subcommand)]
+        command: KgCommand,
+    },
+    /// Audit embedding dimensions against the configured embedder
+    Audit {
+        /// Sample ids to list per problem group
+        #[arg(long, default_value_t = 0)]
+        sample: usize,
+    },
+    /// Compare the live schema with the expected one; exits nonzero on drift
+    SchemaCheck {
+        /// Add missing tables, fields and indexes
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Database reachability and embedding configuration; exits nonzero when unreachable
+    Health,
+}
+
+#[derive(Subcommand)]
+enum KgCommand {
+    /// Write entities, observations and relationships to a file
+    Export {
+        path: String,
+        /// jsonl or graphml
+        #[arg(long, default_value = "jsonl")]
+        format: String,
+        #[arg(long)]
+        include_embeddings: bool,
+        #[arg(long)]
+        page_size: Option<usize>,
+    },
+    /// Load a JSONL file written by `kg export`
+    Import {
+        path: String,
+        /// skip, overwrite or rename records whose id already exists
+        #[arg(long, default_value = "skip")]
+        mode: String,
+        #[arg(long)]
+        batch_size: Option<usize>,
+    },
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
+    surreal_mind::load_env();
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Inspect => inspect().await,
-        Commands::SanityCosine => sanity_cosine().await,
-        Commands::DbCheck => db_check().await,
-        Commands::CheckContents => check_contents().await,
-        Commands::SimpleTest => simple_test().await,
-        Commands::FixDims => fix_dims().await,
+    let result = match cli.command {
+        Commands::Inspect => inspect().await.map(|()| None),
+        Commands::SanityCosine => sanity_cosine().await.map(|()| None),
+        Commands::DbCheck => db_check().await.map(|()| None),
+        Commands::CheckContents => check_contents().await.map(|()| None),
+        Commands::SimpleTest => simple_test().await.map(|()| None),
+        Commands::FixDims => fix_dims().await.map(|()| None),
+        command => run_report(command).await.map(Some),
+    };
+
+    match result {
+        Ok(None) => ExitCode::SUCCESS,
+        Ok(Some(report)) => {
+            println!("{}", render(&report, cli.json));
+            if succeeded(&report) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            if cli.json {
+                println!(
+                    "{}",
+                    render(&json!({"ok": false, "error": format!("{:#}", e)}), true)
+                );
+            } else {
+                eprintln!("error: {:#}", e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run a subcommand that reports a JSON value.
+async fn run_report(command: Commands) -> Result<Value> {
+    use surreal_mind::embeddings::create_embedder;
+    use surreal_mind::server::progress::ProgressReporter;
+
+    let config = Config::load()?;
+    let report = match command {
+        Commands::Reembed {
+            batch_size,
+            limit,
+            missing_only,
+            dry_run,
+            concurrency,
+        } => serde_json::to_value(
+            surreal_mind::run_reembed(batch_size, limit, missing_only, dry_run, concurrency)
+                .await?,
+        )?,
+        Commands::ReembedKg { limit, dry_run } => {
+            serde_json::to_value(surreal_mind::run_reembed_kg(limit, dry_run).await?)?
+        }
+        Commands::Kg {
+            command:
+                KgCommand::Export {
+                    path,
+                    format,
+                    include_embeddings,
+                    page_size,
+                },
+        } => {
+            let params = serde_json::from_value(json!({
+                "path": path,
+                "format": format,
+                "include_embeddings": include_embeddings,
+                "page_size": page_size
+            }))?;
+            let db = connect(&config).await?;
+            serde_json::to_value(surreal_mind::tools::export::export_graph(&db, &params).await?)?
+        }
+        Commands::Kg {
+            command:
+                KgCommand::Import {
+                    path,
+                    mode,
+                    batch_size,
+                },
+        } => {
+            let params = serde_json::from_value(json!({
+                "path": path,
+                "mode": mode,
+                "batch_size": batch_size
+            }))?;
+            let dim = create_embedder(&config).await?.dimensions();
+            let db = connect(&config).await?;
+            serde_json::to_value(
+                surreal_mind::tools::import::import_graph(&db, &params, dim).await?,
+            )?
+        }
+        Commands::Audit { sample } => {
+            let dim = create_embedder(&config).await?.dimensions() as i64;
+            let db = connect(&config).await?;
+            serde_json::to_value(
+                surreal_mind::maintenance::embedding_audit::run_embedding_audit(
+                    &db,
+                    dim,
+                    sample,
+                    &ProgressReporter::disabled(),
+                )
+                .await?,
+            )?
+        }
+        Commands::SchemaCheck { apply } => {
+            let db = connect(&config).await?;
+            serde_json::to_value(
+                surreal_mind::maintenance::schema_check::check_schema(&db.client(), apply).await?,
+            )?
+        }
+        Commands::Health => health(&config).await,
+        _ => unreachable!("not a reporting subcommand"),
+    };
+    Ok(report)
+}
+
+async fn connect(config: &Config) -> Result<DbConnection> {
+    Ok(DbConnection::connect(DbSettings::from_config(config), 1).await?)
+}
+
+/// Like `maintain health` without the script: whether the database answers, plus the
+/// configured embedder. Never fails; `ok` is false when the database is unreachable.
+async fn health(config: &Config) -> Value {
+    let (reachable, reconnects, error) = match connect(config).await {
+        Ok(db) => match db.health().await {
+            Ok(()) => (true, Some(db.health_snapshot()), None),
+            Err(e) => (false, Some(db.health_snapshot()), Some(e.to_string())),
+        },
+        Err(e) => (false, None, Some(format!("{:#}", e))),
+    };
+    json!({
+        "ok": reachable,
+        "db": {
+            "url": config.system.database_url,
+            "namespace": config.system.database_ns,
+            "database": config.system.database_db,
+            "reachable": reachable,
+            "error": error,
+            "reconnects": reconnects
+        },
+        "embedding": {
+            "provider": config.system.embedding_provider,
+            "model": config.system.embedding_model,
+            "dimensions": config.system.embedding_dimensions
+        }
+    })
+}
+
+/// A report fails when it says `"ok": false`; reports without `ok` succeed.
+fn succeeded(report: &Value) -> bool {
+    report.get("ok").and_then(Value::as_bool) != Some(false)
+}
+
+/// Pretty JSON, or one `key  value` row per scalar with nested keys joined by dots.
+fn render(report: &Value, json: bool) -> String {
+    if json {
+        return serde_json::to_string_pretty(report).unwrap_or_else(|_| report.to_string());
+    }
+    let mut rows = Vec::new();
+    table_rows(String::new(), report, &mut rows);
+    let width = rows
+        .iter()
+        .map(|(k, _)| k.chars().count())
+        .max()
+        .unwrap_or(0);
+    rows.iter()
+        .map(|(key, value)| format!("{:<width$}  {}", key, value, width = width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn table_rows(key: String, value: &Value, rows: &mut Vec<(String, String)>) {
+    let child = |k: &str| {
+        if key.is_empty() {
+            k.to_string()
+        } else {
+            format!("{}.{}", key, k)
+        }
+    };
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                table_rows(child(k), v, rows);
+            }
+        }
+        Value::Array(items) if items.iter().any(|v| v.is_object() || v.is_array()) => {
+            for (i, v) in items.iter().enumerate() {
+                table_rows(format!("{}[{}]", key, i), v, rows);
+            }
+        }
+        Value::Array(items) => {
+            let joined = items.iter().map(cell).collect::<Vec<_>>().join(", ");
+            rows.push((key, joined));
+        }
+        _ => rows.push((key, cell(value))),
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
     }
 }
 
@@ -698,3 +976,80 @@ async fn fix_dims() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_parses_maintenance_subcommands() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "surreal-mind-admin",
+            "kg",
+            "export",
+            "out.graphml",
+            "--format",
+            "graphml",
+            "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        let Commands::Kg {
+            command: KgCommand::Export { path, format, .. },
+        } = cli.command
+        else {
+            panic!("expected kg export");
+        };
+        assert_eq!((path.as_str(), format.as_str()), ("out.graphml", "graphml"));
+
+        let cli = Cli::try_parse_from(["surreal-mind-admin", "reembed", "--missing-only"]).unwrap();
+        assert!(!cli.json);
+        assert!(matches!(
+            cli.command,
+            Commands::Reembed {
+                batch_size: 100,
+                limit: None,
+                missing_only: true,
+                dry_run: false,
+                concurrency: 1
+            }
+        ));
+        assert!(matches!(
+            Cli::try_parse_from(["surreal-mind-admin", "schema-check", "--apply"])
+                .unwrap()
+                .command,
+            Commands::SchemaCheck { apply: true }
+        ));
+        assert!(Cli::try_parse_from(["surreal-mind-admin", "kg", "import"]).is_err());
+        assert!(Cli::try_parse_from(["surreal-mind-admin", "audit", "--sample", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_render_json_and_table() {
+        let report = json!({
+            "ok": false,
+            "drift": {"missing_tables": ["thoughts", "kg_edges"], "statements": []},
+            "failed": [{"statement": "DEFINE TABLE x", "error": null}]
+        });
+        let out = render(&report, true);
+        assert_eq!(serde_json::from_str::<Value>(&out).unwrap(), report);
+        assert!(!succeeded(&report));
+        assert!(succeeded(&json!({"processed": 3})));
+
+        let table = render(&report, false);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "drift.missing_tables  thoughts, kg_edges",
+                "drift.statements      ",
+                "failed[0].error       -",
+                "failed[0].statement   DEFINE TABLE x",
+                "ok                    false",
+            ]
+        );
+    }
+}
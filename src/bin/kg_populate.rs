@@ -3,20 +3,37 @@
 //! Fetches unextracted thoughts from SurrealDB, batches them to Gemini for
 //! entity/relationship/observation extraction, parses JSON responses,
 //! upserts to KG tables, and marks thoughts as extracted.
+//!
+//! `KG_POPULATE_PROMPT_VERSION` picks the extraction prompt (default: latest registered), and
+//! the version is stored on every row written. `KG_POPULATE_REPROCESS_BATCH=<batch_id>`
+//! re-extracts exactly the thoughts of an earlier batch and tags that batch's KG rows with
+//! `superseded_by_batch`.
+//!
+//! A batch whose prompt would exceed `[gemini] max_prompt_chars` is split into chunks that
+//! fit, sent one by one in the same Gemini session, and their extractions merged. A chunk
+//! that fails is listed under "Chunks failed" (and as `errors` in the dry-run preview) and its
+//! thoughts stay unextracted for the next run, while the other chunks are still written.
 
 use anyhow::Result;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use surreal_mind::clients::gemini::{RetryPolicy, call_with_retry};
 use surreal_mind::clients::{CognitiveAgent, GeminiClient};
 use surreal_mind::config::Config;
+use surreal_mind::maintenance::{mark_batch_superseded, reopen_batch};
+use surreal_mind::prompts::extraction_prompt;
+use surreal_mind::server::connection::{DbEngine, engine_url};
+use surreal_mind::utils::lang::detect_lang;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::{Client as WsClient, Ws};
+use surrealdb::engine::any;
 use surrealdb::opt::auth::Root;
 use surrealdb::types::SurrealValue;
 
-const EXTRACTION_PROMPT_VERSION: &str = "v1";
 const DEFAULT_BATCH_SIZE: usize = 5;
 const DEFAULT_TIMEOUT_MS: u64 = 120_000;
+/// Max items per kind (entities, relationships, ...) shown in the dry-run preview
+const PREVIEW_MAX_ITEMS: usize = 25;
 
 // ============================================================================
 // Data Structures
@@ -31,20 +48,97 @@ struct ThoughtRecord {
 }
 
 /// Extraction result for a single thought
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize)]
 struct ThoughtExtraction {
     thought_id: String,
-    #[serde(default)]
     entities: Vec<ExtractedEntity>,
-    #[serde(default)]
     relationships: Vec<ExtractedRelationship>,
-    #[serde(default)]
     observations: Vec<ExtractedObservation>,
-    #[serde(default)]
     boundaries: Vec<ExtractedBoundary>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Per-thought extraction as returned by Gemini; items are validated one by one
+#[derive(Debug, Deserialize)]
+struct RawThoughtExtraction {
+    thought_id: String,
+    #[serde(default)]
+    entities: Vec<serde_json::Value>,
+    #[serde(default)]
+    relationships: Vec<serde_json::Value>,
+    #[serde(default)]
+    observations: Vec<serde_json::Value>,
+    #[serde(default)]
+    boundaries: Vec<serde_json::Value>,
+}
+
+impl RawThoughtExtraction {
+    /// Keep the well-formed items, recording a warning for each one skipped
+    fn into_extraction(self, warnings: &mut Vec<String>) -> ThoughtExtraction {
+        let tid = self.thought_id;
+        ThoughtExtraction {
+            entities: take_items(
+                &tid,
+                "entity",
+                self.entities,
+                warnings,
+                |e: &ExtractedEntity| !e.name.trim().is_empty() && !e.entity_type.trim().is_empty(),
+            ),
+            relationships: take_items(
+                &tid,
+                "relationship",
+                self.relationships,
+                warnings,
+                |r: &ExtractedRelationship| {
+                    !r.from.trim().is_empty()
+                        && !r.to.trim().is_empty()
+                        && !r.relation.trim().is_empty()
+                },
+            ),
+            observations: take_items(
+                &tid,
+                "observation",
+                self.observations,
+                warnings,
+                |o: &ExtractedObservation| !o.content.trim().is_empty(),
+            ),
+            boundaries: take_items(
+                &tid,
+                "boundary",
+                self.boundaries,
+                warnings,
+                |b: &ExtractedBoundary| !b.rejected.trim().is_empty(),
+            ),
+            thought_id: tid,
+        }
+    }
+}
+
+/// Deserialize each item on its own so one malformed item does not sink the batch
+fn take_items<T: DeserializeOwned>(
+    thought_id: &str,
+    kind: &str,
+    values: Vec<serde_json::Value>,
+    warnings: &mut Vec<String>,
+    is_valid: impl Fn(&T) -> bool,
+) -> Vec<T> {
+    let mut items = Vec::with_capacity(values.len());
+    for (idx, value) in values.into_iter().enumerate() {
+        match serde_json::from_value::<T>(value) {
+            Ok(item) if is_valid(&item) => items.push(item),
+            Ok(_) => warnings.push(format!(
+                "thought {}: skipped {} #{}: required field is empty",
+                thought_id, kind, idx
+            )),
+            Err(e) => warnings.push(format!(
+                "thought {}: skipped {} #{}: {}",
+                thought_id, kind, idx, e
+            )),
+        }
+    }
+    items
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 struct ExtractedEntity {
     name: String,
     #[serde(rename = "type")]
@@ -55,7 +149,7 @@ struct ExtractedEntity {
     confidence: f64,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 struct ExtractedRelationship {
     from: String,
     to: String,
@@ -66,7 +160,7 @@ struct ExtractedRelationship {
     confidence: f64,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 struct ExtractedObservation {
     content: String,
     #[serde(default)]
@@ -77,7 +171,7 @@ struct ExtractedObservation {
     confidence: f64,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 struct ExtractedBoundary {
     rejected: String,
     reason: String,
@@ -93,13 +187,39 @@ fn default_confidence() -> f64 {
 
 /// Full extraction response from Gemini
 #[derive(Debug, Deserialize)]
-struct ExtractionResponse {
+struct RawExtractionResponse {
     #[serde(default)]
-    extractions: Vec<ThoughtExtraction>,
+    extractions: Vec<serde_json::Value>,
     #[serde(default)]
     summary: String,
 }
 
+/// Validated extraction response plus warnings for the items that were skipped
+#[derive(Debug)]
+struct ExtractionResponse {
+    extractions: Vec<ThoughtExtraction>,
+    summary: String,
+    warnings: Vec<String>,
+}
+
+impl From<RawExtractionResponse> for ExtractionResponse {
+    fn from(raw: RawExtractionResponse) -> Self {
+        let mut warnings = Vec::new();
+        let mut extractions = Vec::with_capacity(raw.extractions.len());
+        for (idx, value) in raw.extractions.into_iter().enumerate() {
+            match serde_json::from_value::<RawThoughtExtraction>(value) {
+                Ok(raw_thought) => extractions.push(raw_thought.into_extraction(&mut warnings)),
+                Err(e) => warnings.push(format!("skipped extraction #{}: {}", idx, e)),
+            }
+        }
+        Self {
+            extractions,
+            summary: raw.summary,
+            warnings,
+        }
+    }
+}
+
 /// Statistics for the extraction run
 #[derive(Debug, Default, Serialize)]
 struct ExtractionStats {
@@ -113,6 +233,31 @@ struct ExtractionStats {
     observations_created: usize,
     observations_skipped: usize,
     boundaries_created: usize,
+    warnings: Vec<String>,
+    /// Chunks whose Gemini call or response failed; their thoughts stay unextracted
+    errors: Vec<ChunkError>,
+}
+
+/// A chunk of a batch that produced no extraction
+#[derive(Debug, Clone, Serialize)]
+struct ChunkError {
+    batch_id: String,
+    /// 1-based position of the chunk in its batch
+    chunk: usize,
+    thought_ids: Vec<String>,
+    error: String,
+}
+
+/// Extraction results merged across the chunks of one batch
+#[derive(Debug, Default)]
+struct BatchExtraction {
+    extractions: Vec<ThoughtExtraction>,
+    warnings: Vec<String>,
+    /// Thoughts sent in chunks that returned a parsable response
+    succeeded: Vec<String>,
+    errors: Vec<ChunkError>,
+    /// Gemini session shared by the batch's chunks
+    session_id: Option<String>,
 }
 
 // ============================================================================
@@ -149,8 +294,17 @@ async fn main() -> Result<()> {
         .unwrap_or(DEFAULT_BATCH_SIZE);
     println!("📊 Batch size: {}", batch_size);
 
+    let requested_version = std::env::var("KG_POPULATE_PROMPT_VERSION").ok();
+    let (prompt_version, prompt_template) = extraction_prompt(requested_version.as_deref())?;
+    println!("📝 Extraction prompt: {}", prompt_version);
+
+    let reprocess_batch = std::env::var("KG_POPULATE_REPROCESS_BATCH")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+
     // Connect to SurrealDB
-    let db = Surreal::new::<Ws>(&config.system.database_url).await?;
+    let db = any::connect(engine_url(&config.system.database_url)).await?;
     db.signin(Root {
         username: config.runtime.database_user.clone(),
         password: config.runtime.database_pass.clone(),
@@ -163,11 +317,38 @@ async fn main() -> Result<()> {
 
     let db = Arc::new(db);
     let mut stats = ExtractionStats::default();
+    let gemini = gemini_client(&config);
+    let retry_policy = RetryPolicy::from_env();
+    let max_prompt_chars = config.gemini.max_prompt_chars;
+
+    // A replayed batch is extracted as one unit: exactly the thoughts it held before
+    let mut replay = match &reprocess_batch {
+        Some(old_batch) => {
+            let thoughts = reopen_batch(&db, old_batch, dry_run).await?;
+            println!(
+                "♻️  Reprocessing batch {} ({} thoughts)",
+                old_batch,
+                thoughts.len()
+            );
+            thoughts
+                .into_iter()
+                .map(|t| ThoughtRecord {
+                    id: t.id,
+                    content: t.content,
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
 
     // Main processing loop
     loop {
         // Fetch unextracted thoughts
-        let thoughts = fetch_unextracted_thoughts(&db, batch_size).await?;
+        let thoughts = if reprocess_batch.is_some() {
+            std::mem::take(&mut replay)
+        } else {
+            fetch_unextracted_thoughts(&db, batch_size).await?
+        };
         if thoughts.is_empty() {
             println!("✅ No more unextracted thoughts found");
             break;
@@ -183,110 +364,146 @@ async fn main() -> Result<()> {
         // Generate batch ID for this extraction run
         let batch_id = uuid::Uuid::new_v4().to_string();
 
-        // Build prompt with thoughts
-        let prompt = build_extraction_prompt(&thoughts);
+        // Split the batch so each prompt fits the Gemini budget, one call per chunk
+        let chunks = chunk_thoughts(prompt_template, thoughts, max_prompt_chars);
+        if chunks.len() > 1 {
+            println!(
+                "  ✂️  Split into {} chunks (max {} prompt chars)",
+                chunks.len(),
+                max_prompt_chars
+            );
+        }
+        let extraction = extract_chunks(
+            &gemini,
+            prompt_template,
+            &chunks,
+            max_prompt_chars,
+            &batch_id,
+            &retry_policy,
+        )
+        .await;
+        for warning in &extraction.warnings {
+            eprintln!("  ⚠️  {}", warning);
+        }
+        stats.warnings.extend(extraction.warnings.iter().cloned());
+        for error in &extraction.errors {
+            eprintln!(
+                "  ❌ Chunk {} ({} thoughts) failed: {}",
+                error.chunk,
+                error.thought_ids.len(),
+                error.error
+            );
+            // Don't mark as extracted - will retry next run
+            stats.thoughts_failed += error.thought_ids.len();
+        }
+        stats.errors.extend(extraction.errors.iter().cloned());
+
+        if dry_run {
+            let preview = serde_json::json!({
+                "dry_run": true,
+                "batch_id": batch_id,
+                "prompt_version": prompt_version,
+                "reprocess_batch": reprocess_batch,
+                "chunks": chunks.len(),
+                "errors": extraction.errors,
+                "preview": build_preview(&extraction.extractions, PREVIEW_MAX_ITEMS),
+            });
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&preview).unwrap_or_default()
+            );
+        }
 
-        // Call Gemini for extraction
-        match call_gemini_extraction(&db, &prompt).await {
-            Ok(response) => {
-                // DEBUG: Log raw response
-                eprintln!("\n🔍 DEBUG: Raw Gemini response ({} chars)", response.len());
-
-                // Parse the response
-                match parse_extraction_response(&response) {
-                    Ok(extraction) => {
-                        println!(
-                            "  📊 Extracted {} thought results, summary: {}",
-                            extraction.extractions.len(),
-                            if extraction.summary.chars().count() > 80 {
-                                let truncated: String =
-                                    extraction.summary.chars().take(80).collect();
-                                format!("{}...", truncated)
-                            } else {
-                                extraction.summary.clone()
-                            }
-                        );
-
-                        // Process each thought's extraction
-                        for thought_extraction in &extraction.extractions {
-                            if !dry_run {
-                                match process_thought_extraction(
-                                    &db,
-                                    thought_extraction,
-                                    &batch_id,
-                                    &mut stats,
-                                )
-                                .await
-                                {
-                                    Ok(_) => {
-                                        // Mark thought as extracted
-                                        if let Err(e) = mark_thought_extracted(
-                                            &db,
-                                            &thought_extraction.thought_id,
-                                            &batch_id,
-                                        )
-                                        .await
-                                        {
-                                            eprintln!(
-                                                "  ⚠️  Failed to mark thought {} as extracted: {}",
-                                                thought_extraction.thought_id, e
-                                            );
-                                        }
-                                        stats.thoughts_processed += 1;
-                                    }
-                                    Err(e) => {
-                                        eprintln!(
-                                            "  ⚠️  Failed to process thought {}: {}",
-                                            thought_extraction.thought_id, e
-                                        );
-                                        stats.thoughts_failed += 1;
-                                    }
-                                }
-                            } else {
-                                stats.thoughts_processed += 1;
-                                stats.entities_created += thought_extraction.entities.len();
-                                stats.edges_created += thought_extraction.relationships.len();
-                                stats.observations_created += thought_extraction.observations.len();
-                                stats.boundaries_created += thought_extraction.boundaries.len();
-                            }
-                        }
+        if !extraction.succeeded.is_empty() {
+            if let Some(old_batch) = &reprocess_batch
+                && !dry_run
+            {
+                match mark_batch_superseded(&db, old_batch, &batch_id).await {
+                    Ok(n) => println!(
+                        "  ♻️  Batch {} superseded by {} ({} KG rows tagged)",
+                        old_batch, batch_id, n
+                    ),
+                    Err(e) => {
+                        eprintln!("  ⚠️  Failed to mark batch {} superseded: {}", old_batch, e)
+                    }
+                }
+            }
 
-                        // Mark any thoughts that weren't in the extraction response
-                        // (Gemini might have skipped some)
-                        for thought in &thoughts {
-                            let was_processed = extraction
-                                .extractions
-                                .iter()
-                                .any(|e| e.thought_id == thought.id);
-                            if !was_processed {
-                                if !dry_run {
-                                    // Still mark as extracted to avoid re-processing
-                                    if let Err(e) =
-                                        mark_thought_extracted(&db, &thought.id, &batch_id).await
-                                    {
-                                        eprintln!(
-                                            "  ⚠️  Failed to mark skipped thought {} as extracted: {}",
-                                            thought.id, e
-                                        );
-                                    }
-                                }
-                                stats.thoughts_processed += 1;
+            // Process each thought's extraction
+            for thought_extraction in &extraction.extractions {
+                if !dry_run {
+                    match process_thought_extraction(
+                        &db,
+                        thought_extraction,
+                        &batch_id,
+                        prompt_version,
+                        &mut stats,
+                    )
+                    .await
+                    {
+                        Ok(_) => {
+                            // Mark thought as extracted
+                            if let Err(e) = mark_thought_extracted(
+                                &db,
+                                &thought_extraction.thought_id,
+                                &batch_id,
+                            )
+                            .await
+                            {
+                                eprintln!(
+                                    "  ⚠️  Failed to mark thought {} as extracted: {}",
+                                    thought_extraction.thought_id, e
+                                );
                             }
+                            stats.thoughts_processed += 1;
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "  ⚠️  Failed to process thought {}: {}",
+                                thought_extraction.thought_id, e
+                            );
+                            stats.thoughts_failed += 1;
                         }
                     }
-                    Err(e) => {
-                        eprintln!("  ❌ Failed to parse extraction response: {}", e);
-                        stats.thoughts_failed += thoughts.len();
-                        // Don't mark as extracted - will retry next run
-                    }
+                } else {
+                    stats.thoughts_processed += 1;
+                    stats.entities_created += thought_extraction.entities.len();
+                    stats.edges_created += thought_extraction.relationships.len();
+                    stats.observations_created += thought_extraction.observations.len();
+                    stats.boundaries_created += thought_extraction.boundaries.len();
                 }
             }
-            Err(e) => {
-                eprintln!("  ❌ Gemini extraction failed: {}", e);
-                stats.thoughts_failed += thoughts.len();
-                // Don't mark as extracted - will retry next run
+
+            // Mark thoughts from successful chunks that weren't in the extraction response
+            // (Gemini might have skipped some)
+            for thought_id in &extraction.succeeded {
+                let was_processed = extraction
+                    .extractions
+                    .iter()
+                    .any(|e| &e.thought_id == thought_id);
+                if !was_processed {
+                    if !dry_run {
+                        // Still mark as extracted to avoid re-processing
+                        if let Err(e) = mark_thought_extracted(&db, thought_id, &batch_id).await {
+                            eprintln!(
+                                "  ⚠️  Failed to mark skipped thought {} as extracted: {}",
+                                thought_id, e
+                            );
+                        }
+                    }
+                    stats.thoughts_processed += 1;
+                }
             }
         }
+
+        // Nothing is marked extracted in a dry run, so the next fetch would return the same thoughts
+        if dry_run {
+            println!("🔎 Dry run: stopping after one batch");
+            break;
+        }
+        if reprocess_batch.is_some() {
+            break;
+        }
     }
 
     // Print summary
@@ -302,6 +519,20 @@ async fn main() -> Result<()> {
     println!("  Observations created:  {}", stats.observations_created);
     println!("  Observations skipped:  {}", stats.observations_skipped);
     println!("  Boundaries created:    {}", stats.boundaries_created);
+    println!("  Items skipped (warn):  {}", stats.warnings.len());
+    for warning in &stats.warnings {
+        println!("    - {}", warning);
+    }
+    println!("  Chunks failed:         {}", stats.errors.len());
+    for error in &stats.errors {
+        println!(
+            "    - batch {} chunk {} ({}): {}",
+            error.batch_id,
+            error.chunk,
+            error.thought_ids.join(", "),
+            error.error
+        );
+    }
     println!("{}", "=".repeat(60));
 
     Ok(())
@@ -313,7 +544,7 @@ async fn main() -> Result<()> {
 
 /// Fetch thoughts that haven't been extracted yet
 async fn fetch_unextracted_thoughts(
-    db: &Surreal<WsClient>,
+    db: &Surreal<DbEngine>,
     limit: usize,
 ) -> Result<Vec<ThoughtRecord>> {
     let sql = format!(
@@ -324,39 +555,137 @@ async fn fetch_unextracted_thoughts(
     Ok(rows)
 }
 
-/// Build the extraction prompt with embedded thoughts
-fn build_extraction_prompt(thoughts: &[ThoughtRecord]) -> String {
-    let prompt_template = include_str!("../prompts/kg_extraction_v1.md");
-
-    let mut prompt = prompt_template.to_string();
-    prompt.push_str("\n\n");
+/// One thought as it appears in the extraction prompt
+fn thought_entry(thought: &ThoughtRecord) -> String {
+    format!(
+        "---\nThought ID: {}\nContent:\n{}\n\n",
+        thought.id, thought.content
+    )
+}
 
+/// Build the extraction prompt from `template` with embedded thoughts
+fn build_extraction_prompt(template: &str, thoughts: &[ThoughtRecord]) -> String {
+    let mut prompt = format!("{}\n\n", template);
     for thought in thoughts {
-        prompt.push_str(&format!(
-            "---\nThought ID: {}\nContent:\n{}\n\n",
-            thought.id, thought.content
-        ));
+        prompt.push_str(&thought_entry(thought));
     }
-
     prompt
 }
 
-/// Call Gemini for extraction
-async fn call_gemini_extraction(_db: &Arc<Surreal<WsClient>>, prompt: &str) -> Result<String> {
-    // Load config to get the configured model
-    let config = Config::load().unwrap_or_default();
+/// Split `thoughts` in order into chunks whose prompts stay within `max_prompt_chars`
+/// (0 = no limit). A thought that does not fit even alone gets a chunk of its own.
+fn chunk_thoughts(
+    template: &str,
+    thoughts: Vec<ThoughtRecord>,
+    max_prompt_chars: usize,
+) -> Vec<Vec<ThoughtRecord>> {
+    let header_chars = template.chars().count() + 2;
+    let mut chunks: Vec<Vec<ThoughtRecord>> = Vec::new();
+    let mut chunk_chars = 0;
+    for thought in thoughts {
+        let entry_chars = thought_entry(&thought).chars().count();
+        match chunks.last_mut() {
+            Some(chunk)
+                if max_prompt_chars == 0 || chunk_chars + entry_chars <= max_prompt_chars =>
+            {
+                chunk.push(thought);
+                chunk_chars += entry_chars;
+            }
+            _ => {
+                chunks.push(vec![thought]);
+                chunk_chars = header_chars + entry_chars;
+            }
+        }
+    }
+    chunks
+}
+
+/// Gemini client for extraction; `KG_POPULATE_MODEL` and `KG_POPULATE_TIMEOUT_MS` override
+/// the configured model and the default timeout
+fn gemini_client(config: &Config) -> GeminiClient {
     let model =
         std::env::var("KG_POPULATE_MODEL").unwrap_or_else(|_| config.system.gemini_model.clone());
     let timeout = std::env::var("KG_POPULATE_TIMEOUT_MS")
         .ok()
         .and_then(|v| v.parse().ok())
         .unwrap_or(DEFAULT_TIMEOUT_MS);
+    GeminiClient::with_timeout_ms(model, timeout)
+}
 
-    let gemini = GeminiClient::with_timeout_ms(model.clone(), timeout);
+/// Call `agent` once per chunk, in order, and merge the parsed extractions. Every chunk after
+/// the first reuses the session of the first successful call; each prompt still carries the
+/// full instructions. A failed call or unparsable response is recorded in `errors` and the
+/// remaining chunks still run; transient failures retry per `policy`.
+async fn extract_chunks<A: CognitiveAgent + ?Sized>(
+    agent: &A,
+    template: &str,
+    chunks: &[Vec<ThoughtRecord>],
+    max_prompt_chars: usize,
+    batch_id: &str,
+    policy: &RetryPolicy,
+) -> BatchExtraction {
+    let mut merged = BatchExtraction::default();
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let prompt = build_extraction_prompt(template, chunk);
+        let prompt_chars = prompt.chars().count();
+        if max_prompt_chars > 0 && prompt_chars > max_prompt_chars {
+            merged.warnings.push(format!(
+                "thought {} alone needs {} prompt chars, over gemini.max_prompt_chars ({}); sent as its own chunk",
+                chunk[0].id, prompt_chars, max_prompt_chars
+            ));
+        }
+        println!(
+            "  📦 Chunk {}/{}: {} thoughts, {} chars",
+            idx + 1,
+            chunks.len(),
+            chunk.len(),
+            prompt_chars
+        );
 
-    // Call GeminiClient directly - each extraction batch is independent, no context needed
-    let response = gemini.call(prompt, None).await?;
-    Ok(response.response)
+        let (result, attempts) =
+            call_with_retry(agent, &prompt, merged.session_id.as_deref(), policy).await;
+        if attempts > 1 {
+            eprintln!("  🔁 Gemini extraction took {} attempts", attempts);
+        }
+        let parsed = match result {
+            Ok(response) => {
+                if merged.session_id.is_none() && !response.session_id.is_empty() {
+                    merged.session_id = Some(response.session_id);
+                }
+                eprintln!(
+                    "\n🔍 DEBUG: Raw Gemini response ({} chars)",
+                    response.response.len()
+                );
+                parse_extraction_response(&response.response)
+                    .map_err(|e| format!("failed to parse extraction response: {}", e))
+            }
+            Err(e) => Err(format!("Gemini extraction failed: {}", e)),
+        };
+        match parsed {
+            Ok(extraction) => {
+                println!(
+                    "  📊 Extracted {} thought results, summary: {}",
+                    extraction.extractions.len(),
+                    if extraction.summary.chars().count() > 80 {
+                        let truncated: String = extraction.summary.chars().take(80).collect();
+                        format!("{}...", truncated)
+                    } else {
+                        extraction.summary.clone()
+                    }
+                );
+                merged.extractions.extend(extraction.extractions);
+                merged.warnings.extend(extraction.warnings);
+                merged.succeeded.extend(chunk.iter().map(|t| t.id.clone()));
+            }
+            Err(error) => merged.errors.push(ChunkError {
+                batch_id: batch_id.to_string(),
+                chunk: idx + 1,
+                thought_ids: chunk.iter().map(|t| t.id.clone()).collect(),
+                error,
+            }),
+        }
+    }
+    merged
 }
 
 /// Parse the extraction response, handling markdown code fences and preamble text
@@ -381,13 +710,13 @@ fn parse_extraction_response(response: &str) -> Result<ExtractionResponse> {
         .trim();
 
     // Secondary attempt: if parsing fails, try to find the last } and truncate
-    match serde_json::from_str::<ExtractionResponse>(json_str) {
-        Ok(parsed) => Ok(parsed),
+    match serde_json::from_str::<RawExtractionResponse>(json_str) {
+        Ok(parsed) => Ok(parsed.into()),
         Err(e) => {
             if let Some(last_brace) = json_str.rfind('}') {
                 let truncated = &json_str[..=last_brace];
-                if let Ok(parsed) = serde_json::from_str::<ExtractionResponse>(truncated) {
-                    return Ok(parsed);
+                if let Ok(parsed) = serde_json::from_str::<RawExtractionResponse>(truncated) {
+                    return Ok(parsed.into());
                 }
             }
             let snippet: String = json_str.chars().take(500).collect();
@@ -400,11 +729,54 @@ fn parse_extraction_response(response: &str) -> Result<ExtractionResponse> {
     }
 }
 
-/// Process a single thought's extraction results
+/// Parsed extraction for display, keeping at most `max_items` of each kind across
+/// the batch. `truncated` reports whether anything was left out.
+fn build_preview(extractions: &[ThoughtExtraction], max_items: usize) -> serde_json::Value {
+    fn take<'a, T: Serialize>(
+        items: &'a [T],
+        budget: &mut usize,
+        truncated: &mut bool,
+    ) -> Vec<&'a T> {
+        let kept: Vec<&'a T> = items.iter().take(*budget).collect();
+        *budget -= kept.len();
+        *truncated |= kept.len() < items.len();
+        kept
+    }
+
+    let mut budgets = [max_items; 4];
+    let mut truncated = false;
+    let thoughts: Vec<serde_json::Value> = extractions
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "thought_id": e.thought_id,
+                "entities": take(&e.entities, &mut budgets[0], &mut truncated),
+                "relationships": take(&e.relationships, &mut budgets[1], &mut truncated),
+                "observations": take(&e.observations, &mut budgets[2], &mut truncated),
+                "boundaries": take(&e.boundaries, &mut budgets[3], &mut truncated),
+            })
+        })
+        .collect();
+    let total = |f: fn(&ThoughtExtraction) -> usize| extractions.iter().map(f).sum::<usize>();
+    serde_json::json!({
+        "thoughts": thoughts,
+        "totals": {
+            "entities": total(|e| e.entities.len()),
+            "relationships": total(|e| e.relationships.len()),
+            "observations": total(|e| e.observations.len()),
+            "boundaries": total(|e| e.boundaries.len()),
+        },
+        "truncated": truncated,
+    })
+}
+
+/// Process a single thought's extraction results. A failed write skips that item
+/// with a warning; the rest of the thought is still processed.
 async fn process_thought_extraction(
-    db: &Surreal<WsClient>,
+    db: &Surreal<DbEngine>,
     extraction: &ThoughtExtraction,
     batch_id: &str,
+    prompt_version: &str,
     stats: &mut ExtractionStats,
 ) -> Result<()> {
     let thought_id = extraction.thought_id.clone();
@@ -417,11 +789,16 @@ async fn process_thought_extraction(
             entity.clone(),
             thought_id.clone(),
             batch_id_owned.clone(),
+            prompt_version,
         )
-        .await?
+        .await
         {
-            true => stats.entities_created += 1,
-            false => stats.entities_skipped += 1,
+            Ok(true) => stats.entities_created += 1,
+            Ok(false) => stats.entities_skipped += 1,
+            Err(e) => stats.warnings.push(format!(
+                "thought {}: entity '{}' failed: {}",
+                thought_id, entity.name, e
+            )),
         }
     }
 
@@ -432,11 +809,23 @@ async fn process_thought_extraction(
             relationship.clone(),
             thought_id.clone(),
             batch_id_owned.clone(),
+            prompt_version,
         )
-        .await?
+        .await
         {
-            true => stats.edges_created += 1,
-            false => stats.edges_skipped += 1,
+            Ok(EdgeOutcome::Created) => stats.edges_created += 1,
+            Ok(EdgeOutcome::Existing) => stats.edges_skipped += 1,
+            Ok(EdgeOutcome::UnresolvedEndpoint) => {
+                stats.edges_skipped += 1;
+                stats.warnings.push(format!(
+                    "thought {}: relationship '{}' -[{}]-> '{}' skipped: endpoint entity not found",
+                    thought_id, relationship.from, relationship.relation, relationship.to
+                ));
+            }
+            Err(e) => stats.warnings.push(format!(
+                "thought {}: relationship '{}' -[{}]-> '{}' failed: {}",
+                thought_id, relationship.from, relationship.relation, relationship.to, e
+            )),
         }
     }
 
@@ -447,35 +836,55 @@ async fn process_thought_extraction(
             observation.clone(),
             thought_id.clone(),
             batch_id_owned.clone(),
+            prompt_version,
         )
-        .await?
+        .await
         {
-            true => stats.observations_created += 1,
-            false => stats.observations_skipped += 1,
+            Ok(true) => stats.observations_created += 1,
+            Ok(false) => stats.observations_skipped += 1,
+            Err(e) => stats
+                .warnings
+                .push(format!("thought {}: observation failed: {}", thought_id, e)),
         }
     }
 
     // Create boundaries
     for boundary in &extraction.boundaries {
-        create_boundary(
+        match create_boundary(
             db,
             boundary.clone(),
             thought_id.clone(),
             batch_id_owned.clone(),
+            prompt_version,
         )
-        .await?;
-        stats.boundaries_created += 1;
+        .await
+        {
+            Ok(()) => stats.boundaries_created += 1,
+            Err(e) => stats.warnings.push(format!(
+                "thought {}: boundary '{}' failed: {}",
+                thought_id, boundary.rejected, e
+            )),
+        }
     }
 
     Ok(())
 }
 
+/// Result of writing one extracted relationship
+#[derive(Debug, PartialEq, Eq)]
+enum EdgeOutcome {
+    Created,
+    Existing,
+    UnresolvedEndpoint,
+}
+
 /// Upsert an entity - returns true if created, false if already existed
 async fn upsert_entity(
-    db: &Surreal<WsClient>,
+    db: &Surreal<DbEngine>,
     entity: ExtractedEntity,
     thought_id: String,
     batch_id: String,
+    prompt_version: &str,
 ) -> Result<bool> {
     // Check if entity already exists by name
     let sql =
@@ -521,19 +930,20 @@ async fn upsert_entity(
         .bind(("thought_ids", vec![thought_id]))
         .bind(("batch_id", batch_id))
         .bind(("confidence", entity.confidence))
-        .bind(("version", EXTRACTION_PROMPT_VERSION.to_string()))
+        .bind(("version", prompt_version.to_string()))
         .await?;
 
     Ok(true)
 }
 
-/// Upsert an edge - returns true if created, false if already existed
+/// Upsert an edge between entities resolved by name
 async fn upsert_edge(
-    db: &Surreal<WsClient>,
+    db: &Surreal<DbEngine>,
     relationship: ExtractedRelationship,
     thought_id: String,
     batch_id: String,
-) -> Result<bool> {
+    prompt_version: &str,
+) -> Result<EdgeOutcome> {
     // First, resolve entity names to IDs
     let from_sql = "SELECT meta::id(id) as id FROM kg_entities WHERE name = $name LIMIT 1";
     let from_rows: Vec<serde_json::Value> = db
@@ -564,7 +974,7 @@ async fn upsert_edge(
         (Some(f), Some(t)) => (f, t),
         _ => {
             // Entities not found - skip edge creation
-            return Ok(false);
+            return Ok(EdgeOutcome::UnresolvedEndpoint);
         }
     };
 
@@ -600,7 +1010,7 @@ async fn upsert_edge(
                 .bind(("thought_ids", thought_ids))
                 .await?;
         }
-        return Ok(false);
+        return Ok(EdgeOutcome::Existing);
     }
 
     // Create new edge
@@ -616,18 +1026,19 @@ async fn upsert_edge(
         .bind(("thought_ids", vec![thought_id]))
         .bind(("batch_id", batch_id))
         .bind(("confidence", relationship.confidence))
-        .bind(("version", EXTRACTION_PROMPT_VERSION.to_string()))
+        .bind(("version", prompt_version.to_string()))
         .await?;
 
-    Ok(true)
+    Ok(EdgeOutcome::Created)
 }
 
 /// Upsert an observation - returns true if created, false if already existed
 async fn upsert_observation(
-    db: &Surreal<WsClient>,
+    db: &Surreal<DbEngine>,
     observation: ExtractedObservation,
     thought_id: String,
     batch_id: String,
+    prompt_version: &str,
 ) -> Result<bool> {
     // Generate a name from the content (first 50 chars)
     let name = if observation.content.chars().count() > 50 {
@@ -661,14 +1072,15 @@ async fn upsert_observation(
         "tags": observation.tags,
     });
 
-    db.query("CREATE kg_observations SET created_at = time::now(), name = $name, data = $data, source_thought_id = $src, confidence = $conf, source_thought_ids = $thought_ids, extraction_batch_id = $batch_id, extracted_at = time::now(), extraction_confidence = $conf, extraction_prompt_version = $version, embedding = NONE")
+    db.query("CREATE kg_observations SET created_at = time::now(), name = $name, data = $data, source_thought_id = $src, confidence = $conf, source_thought_ids = $thought_ids, extraction_batch_id = $batch_id, extracted_at = time::now(), extraction_confidence = $conf, extraction_prompt_version = $version, lang = $lang, embedding = NONE")
+        .bind(("lang", detect_lang(&observation.content)))
         .bind(("name", name))
         .bind(("data", data))
         .bind(("src", thought_id.clone()))
         .bind(("conf", observation.confidence))
         .bind(("thought_ids", vec![thought_id]))
         .bind(("batch_id", batch_id))
-        .bind(("version", EXTRACTION_PROMPT_VERSION.to_string()))
+        .bind(("version", prompt_version.to_string()))
         .await?;
 
     Ok(true)
@@ -676,10 +1088,11 @@ async fn upsert_observation(
 
 /// Create a boundary record
 async fn create_boundary(
-    db: &Surreal<WsClient>,
+    db: &Surreal<DbEngine>,
     boundary: ExtractedBoundary,
     thought_id: String,
     batch_id: String,
+    prompt_version: &str,
 ) -> Result<()> {
     db.query("CREATE kg_boundaries SET created_at = time::now(), source_thought_id = $src, rejected = $rejected, reason = $reason, context = $context, confidence = $conf, extraction_batch_id = $batch_id, extracted_at = time::now(), extraction_prompt_version = $version, embedding = NONE")
         .bind(("src", thought_id))
@@ -688,7 +1101,7 @@ async fn create_boundary(
         .bind(("context", boundary.context))
         .bind(("conf", boundary.confidence))
         .bind(("batch_id", batch_id))
-        .bind(("version", EXTRACTION_PROMPT_VERSION.to_string()))
+        .bind(("version", prompt_version.to_string()))
         .await?;
 
     Ok(())
@@ -696,7 +1109,7 @@ async fn create_boundary(
 
 /// Mark a thought as extracted
 async fn mark_thought_extracted(
-    db: &Surreal<WsClient>,
+    db: &Surreal<DbEngine>,
     thought_id: &str,
     batch_id: &str,
 ) -> Result<()> {
@@ -709,3 +1122,251 @@ async fn mark_thought_extracted(
         .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use surreal_mind::clients::{AgentError, AgentResponse};
+
+    const CANNED: &str = r#"Here is the extraction:
+```json
+{
+  "extractions": [
+    {
+      "thought_id": "t1",
+      "entities": [
+        {"name": "SurrealDB", "type": "Database", "description": "graph store", "confidence": 0.9},
+        {"name": "", "type": "tool"},
+        {"type": "person"}
+      ],
+      "relationships": [
+        {"from": "surreal-mind", "to": "SurrealDB", "relation": "uses", "confidence": 0.8},
+        {"from": "a", "to": "b"}
+      ],
+      "observations": [
+        {"content": "Reconnect uses exponential backoff", "tags": ["db"]},
+        {"content": 42}
+      ],
+      "boundaries": [
+        {"rejected": "Local embeddings", "reason": "dimension drift", "context": "embedding work"},
+        {"rejected": "  ", "reason": "blank"}
+      ]
+    },
+    {"entities": []}
+  ],
+  "summary": "one thought"
+}
+```"#;
+
+    #[test]
+    fn test_parse_extraction_keeps_valid_items_and_warns_on_malformed() {
+        let parsed = parse_extraction_response(CANNED).unwrap();
+        assert_eq!(parsed.summary, "one thought");
+        assert_eq!(parsed.extractions.len(), 1);
+
+        let t1 = &parsed.extractions[0];
+        assert_eq!(t1.thought_id, "t1");
+        assert_eq!(t1.entities.len(), 1);
+        assert_eq!(t1.entities[0].name, "SurrealDB");
+        assert_eq!(t1.relationships.len(), 1);
+        assert_eq!(t1.relationships[0].relation, "uses");
+        assert_eq!(t1.observations.len(), 1);
+        assert_eq!(t1.observations[0].confidence, 0.5);
+        assert_eq!(t1.boundaries.len(), 1);
+        assert_eq!(t1.boundaries[0].reason, "dimension drift");
+
+        // Two bad entities, one relationship, one observation, one boundary, one extraction
+        assert_eq!(parsed.warnings.len(), 6, "{:?}", parsed.warnings);
+        assert!(parsed.warnings[0].starts_with("thought t1: skipped entity #1"));
+        assert!(parsed.warnings[5].starts_with("skipped extraction #1"));
+    }
+
+    #[test]
+    fn test_build_preview_caps_items_per_kind() {
+        let parsed = parse_extraction_response(CANNED).unwrap();
+        let preview = build_preview(&parsed.extractions, 1);
+        assert_eq!(preview["truncated"], false);
+        assert_eq!(preview["thoughts"][0]["entities"][0]["type"], "Database");
+        assert_eq!(
+            preview["thoughts"][0]["relationships"][0]["confidence"],
+            0.8
+        );
+
+        let mut twice = parse_extraction_response(CANNED).unwrap().extractions;
+        twice.extend(parse_extraction_response(CANNED).unwrap().extractions);
+        let preview = build_preview(&twice, 1);
+        assert_eq!(preview["truncated"], true);
+        assert_eq!(preview["totals"]["entities"], 2);
+        assert_eq!(
+            preview["thoughts"][0]["entities"].as_array().unwrap().len(),
+            1
+        );
+        assert!(
+            preview["thoughts"][1]["entities"]
+                .as_array()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_build_extraction_prompt_uses_selected_template() {
+        let thoughts = vec![ThoughtRecord {
+            id: "t1".into(),
+            content: "Reconnect uses backoff".into(),
+        }];
+        let (version, template) = extraction_prompt(Some("v1")).unwrap();
+        let prompt = build_extraction_prompt(template, &thoughts);
+        assert_eq!(version, "v1");
+        assert!(prompt.starts_with(template));
+        assert!(prompt.ends_with("Thought ID: t1\nContent:\nReconnect uses backoff\n\n"));
+    }
+
+    #[test]
+    fn test_parse_extraction_rejects_non_json() {
+        assert!(parse_extraction_response("no json here").is_err());
+    }
+
+    fn thought(id: &str, chars: usize) -> ThoughtRecord {
+        ThoughtRecord {
+            id: id.into(),
+            content: "x".repeat(chars),
+        }
+    }
+
+    fn ids(chunks: &[Vec<ThoughtRecord>]) -> Vec<Vec<&str>> {
+        chunks
+            .iter()
+            .map(|c| c.iter().map(|t| t.id.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_thoughts_respects_prompt_budget() {
+        let template = "T".repeat(98); // 100 chars with the separator
+        let entry = |t: &ThoughtRecord| thought_entry(t).chars().count();
+        let small = thought("a", 100);
+        let budget = 100 + 2 * entry(&small);
+        let thoughts = vec![
+            thought("a", 100),
+            thought("b", 100),
+            thought("c", 100),
+            thought("huge", 5_000),
+            thought("d", 100),
+        ];
+
+        let chunks = chunk_thoughts(&template, thoughts, budget);
+        assert_eq!(
+            ids(&chunks),
+            vec![vec!["a", "b"], vec!["c"], vec!["huge"], vec!["d"]]
+        );
+        for chunk in chunks.iter().filter(|c| c[0].id != "huge") {
+            assert!(build_extraction_prompt(&template, chunk).chars().count() <= budget);
+        }
+
+        // Without a budget the batch stays one prompt
+        let one = chunk_thoughts(&template, vec![thought("a", 10), thought("b", 10)], 0);
+        assert_eq!(ids(&one), vec![vec!["a", "b"]]);
+        assert!(chunk_thoughts(&template, Vec::new(), budget).is_empty());
+    }
+
+    /// Extracts one entity per thought in the prompt; fails calls listed in `fail_calls`.
+    struct ChunkAgent {
+        fail_calls: Vec<usize>,
+        sessions: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CognitiveAgent for ChunkAgent {
+        async fn call(
+            &self,
+            prompt: &str,
+            session_id: Option<&str>,
+        ) -> Result<AgentResponse, AgentError> {
+            let call = {
+                let mut sessions = self.sessions.lock().unwrap();
+                sessions.push(session_id.map(String::from));
+                sessions.len()
+            };
+            if self.fail_calls.contains(&call) {
+                return Err(AgentError::CliError("context window exceeded".into()));
+            }
+            let extractions: Vec<serde_json::Value> = prompt
+                .lines()
+                .filter_map(|l| l.strip_prefix("Thought ID: "))
+                .map(|id| {
+                    serde_json::json!({"thought_id": id, "entities": [{"name": format!("E-{id}"), "type": "concept"}]})
+                })
+                .collect();
+            Ok(AgentResponse {
+                session_id: "sess-1".into(),
+                response: serde_json::json!({"extractions": extractions, "summary": "ok"})
+                    .to_string(),
+                exchange_id: None,
+                stream_events: None,
+            })
+        }
+    }
+
+    const NO_RETRY: RetryPolicy = RetryPolicy {
+        max_attempts: 1,
+        base_delay_ms: 1,
+    };
+
+    #[tokio::test]
+    async fn test_extract_chunks_reuses_session_and_keeps_partial_results() {
+        let template = "Extract entities.";
+        let thoughts = (1..=5).map(|i| thought(&format!("t{i}"), 300)).collect();
+        let budget = template.len() + 2 + 2 * thought_entry(&thought("t1", 300)).len();
+        let chunks = chunk_thoughts(template, thoughts, budget);
+        assert_eq!(
+            ids(&chunks),
+            vec![vec!["t1", "t2"], vec!["t3", "t4"], vec!["t5"]]
+        );
+
+        let agent = ChunkAgent {
+            fail_calls: vec![2],
+            sessions: std::sync::Mutex::new(Vec::new()),
+        };
+        let merged = extract_chunks(&agent, template, &chunks, budget, "b1", &NO_RETRY).await;
+
+        // One call per chunk; every call after the first resumes the first session
+        assert_eq!(
+            *agent.sessions.lock().unwrap(),
+            vec![None, Some("sess-1".to_string()), Some("sess-1".to_string())]
+        );
+        assert_eq!(merged.session_id.as_deref(), Some("sess-1"));
+
+        // The failed chunk's thoughts are reported, the others merged
+        assert_eq!(merged.succeeded, ["t1", "t2", "t5"]);
+        let extracted: Vec<&str> = merged
+            .extractions
+            .iter()
+            .map(|e| e.thought_id.as_str())
+            .collect();
+        assert_eq!(extracted, ["t1", "t2", "t5"]);
+        assert_eq!(merged.errors.len(), 1);
+        let error = &merged.errors[0];
+        assert_eq!((error.batch_id.as_str(), error.chunk), ("b1", 2));
+        assert_eq!(error.thought_ids, ["t3", "t4"]);
+        assert!(
+            error.error.contains("context window exceeded"),
+            "{}",
+            error.error
+        );
+        assert!(merged.warnings.is_empty(), "{:?}", merged.warnings);
+    }
+
+    #[tokio::test]
+    async fn test_extract_chunks_warns_about_oversized_thought() {
+        let chunks = chunk_thoughts("T", vec![thought("big", 500)], 100);
+        let agent = ChunkAgent {
+            fail_calls: Vec::new(),
+            sessions: std::sync::Mutex::new(Vec::new()),
+        };
+        let merged = extract_chunks(&agent, "T", &chunks, 100, "b1", &NO_RETRY).await;
+        assert_eq!(merged.succeeded, ["big"]);
+        assert_eq!(merged.warnings.len(), 1);
+        assert!(merged.warnings[0].starts_with("thought big alone needs"));
+    }
+}
@@ -1,8 +1,8 @@
 use anyhow::Result;
 // use chrono::Utc;
-use surreal_mind::embeddings::create_embedder;
-use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Ws;
+use surreal_mind::embeddings::{EmbedPurpose, create_embedder};
+use surreal_mind::server::connection::engine_url;
+use surrealdb::engine::any;
 use surrealdb::opt::auth::Root;
 
 #[tokio::main]
@@ -18,6 +18,10 @@ async fn main() -> Result<()> {
         e
     })?;
 
+    if std::env::args().any(|a| a == "--to-v2") {
+        return backfill_v2(&config).await;
+    }
+
     println!("🚀 Starting thought re-embedding process...");
     // Prefer OpenAI 1536; fallback to local BGE if unavailable
     let embedder = create_embedder(&config).await?;
@@ -28,7 +32,7 @@ async fn main() -> Result<()> {
     );
 
     // Connect to SurrealDB using config
-    let db = Surreal::new::<Ws>(&config.system.database_url).await?;
+    let db = any::connect(engine_url(&config.system.database_url)).await?;
     db.signin(Root {
         username: config.runtime.database_user.clone(),
         password: config.runtime.database_pass.clone(),
@@ -111,7 +115,10 @@ async fn main() -> Result<()> {
         }
 
         // Generate new embedding
-        match embedder.embed(&content).await {
+        match embedder
+            .embed_with_purpose(&content, EmbedPurpose::Reembed)
+            .await
+        {
             Ok(new_embedding) => {
                 // Update thought with new embedding and metadata
                 let (provider, model) = (
@@ -166,5 +173,54 @@ async fn main() -> Result<()> {
     println!("🎯 Target embedding dimensions: {}", embed_dims);
     println!("{}", "=".repeat(50));
 
+    // Token usage for `maintain embedding_usage_report`; a failed write only warns
+    if let Err(e) = surreal_mind::embeddings::usage::ledger().flush(&db).await {
+        eprintln!("⚠️  Failed to record embedding usage: {}", e);
+    }
+
+    Ok(())
+}
+
+/// `--to-v2`: fill `embedding_v2` from the `[embedding_migration]` target instead of
+/// re-embedding the primary field.
+async fn backfill_v2(config: &surreal_mind::config::Config) -> Result<()> {
+    let Some(target) = surreal_mind::maintenance::MigrationTarget::from_config(config).await?
+    else {
+        anyhow::bail!("--to-v2 needs [embedding_migration] target_provider and target_model");
+    };
+    let dry_run = std::env::args().any(|a| a == "--dry-run");
+    println!(
+        "🚀 Backfilling embedding_v2 ({}/{}, {} dims){}...",
+        target.provider,
+        target.model,
+        target.dimensions(),
+        if dry_run { " [dry run]" } else { "" }
+    );
+
+    let db = any::connect(engine_url(&config.system.database_url)).await?;
+    db.signin(Root {
+        username: config.runtime.database_user.clone(),
+        password: config.runtime.database_pass.clone(),
+    })
+    .await?;
+    db.use_ns(&config.system.database_ns)
+        .use_db(&config.system.database_db)
+        .await?;
+
+    let stats = surreal_mind::maintenance::backfill_embedding_v2(
+        &db,
+        &target,
+        100,
+        None,
+        dry_run,
+        &surreal_mind::server::progress::ProgressReporter::disabled(),
+    )
+    .await?;
+    println!("{}", serde_json::to_string_pretty(&stats)?);
+    // Token usage for `maintain embedding_usage_report`; a failed write only warns
+    if let Err(e) = surreal_mind::embeddings::usage::ledger().flush(&db).await {
+        eprintln!("⚠️  Failed to record embedding usage: {}", e);
+    }
+
     Ok(())
 }
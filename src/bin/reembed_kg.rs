@@ -47,5 +47,15 @@ async fn main() -> Result<()> {
         stats.provider, stats.model, stats.expected_dim
     );
 
+    // Token usage for `maintain embedding_usage_report`; a failed write only warns
+    match surreal_mind::config::Config::load() {
+        Ok(config) => {
+            if let Err(e) = surreal_mind::embeddings::usage::flush_to_configured_db(&config).await {
+                eprintln!("⚠️  Failed to record embedding usage: {}", e);
+            }
+        }
+        Err(e) => eprintln!("⚠️  Failed to record embedding usage: {}", e),
+    }
+
     Ok(())
 }
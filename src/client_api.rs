@@ -0,0 +1,449 @@
+//! Embedding API: typed access to the thought store and knowledge graph without MCP.
+//!
+//! [`SurrealMindClient`] is the supported way to use surreal-mind from another Rust
+//! service. It wraps a [`SurrealMindServer`] and exposes the core operations with plain
+//! param and result structs; no rmcp types appear in its signatures. The MCP tools run the
+//! same code paths: thoughts are written through [`ThoughtBuilder`], searches go through
+//! [`unified_search`], and KG items through the `remember` item writer.
+//!
+//! ```no_run
+//! use surreal_mind::client_api::{KgTarget, NewEntity, NewThought, SurrealMindClient};
+//! use surreal_mind::config::Config;
+//!
+//! # async fn run() -> surreal_mind::error::Result<()> {
+//! let client = SurrealMindClient::connect(&Config::load()?).await?;
+//!
+//! let thought = client
+//!     .create_thought(NewThought {
+//!         tags: vec!["db".into()],
+//!         ..NewThought::new("Reconnects use exponential backoff")
+//!     })
+//!     .await?;
+//! println!("stored {} ({})", thought.id, thought.embedding_status);
+//!
+//! client
+//!     .create_entity(NewEntity::new("SurrealDB").entity_type("tool"))
+//!     .await?;
+//! for hit in client.search_kg("SurrealDB", KgTarget::Entity, 5).await? {
+//!     println!("{} {:?}", hit.id, hit.name);
+//! }
+//! for hit in client.search_thoughts("reconnect backoff", 5).await? {
+//!     println!("{} {:?}", hit.id, hit.similarity);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::Config;
+use crate::error::{Result, SurrealMindError};
+use crate::server::SurrealMindServer;
+use crate::tools::thinking::{ContinuityResult, Reference, ThoughtBuilder, validate_thought_input};
+use crate::tools::unified_search::{UnifiedSearchParams, unified_search};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+/// Handle on a surreal-mind store. Cheap to clone; clones share the database connection.
+#[derive(Clone)]
+pub struct SurrealMindClient {
+    server: SurrealMindServer,
+}
+
+/// A thought to store. Unset options take the same defaults as `think`.
+#[derive(Debug, Clone, Default)]
+pub struct NewThought {
+    pub content: String,
+    /// Provenance recorded on the thought (default "human")
+    pub origin: Option<String>,
+    pub tags: Vec<String>,
+    pub significance: Option<f32>,
+    pub confidence: Option<f32>,
+    pub injection_scale: Option<u8>,
+    pub session_id: Option<String>,
+    pub chain_id: Option<String>,
+    pub previous_thought_id: Option<String>,
+    pub revises_thought: Option<String>,
+    pub branch_from: Option<String>,
+    /// A repeated key returns the existing thought instead of creating another
+    pub idempotency_key: Option<String>,
+    pub references: Vec<Reference>,
+    pub is_private: bool,
+    /// Inject into every matching `think` regardless of similarity
+    pub pinned: bool,
+}
+
+impl NewThought {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreatedThought {
+    pub id: String,
+    /// "complete", "pending", "failed", or "deduplicated"
+    pub embedding_status: String,
+    pub embedding_dim: usize,
+    pub continuity: ContinuityResult,
+    /// Secrets replaced per `[security.redaction]` before storing
+    pub redactions: usize,
+}
+
+/// A KG entity to create, or to match by name when `upsert` is set (the default).
+#[derive(Debug, Clone)]
+pub struct NewEntity {
+    pub name: String,
+    pub entity_type: Option<String>,
+    /// Extra fields stored in the entity's `data`
+    pub data: Map<String, Value>,
+    pub upsert: bool,
+}
+
+impl NewEntity {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entity_type: None,
+            data: Map::new(),
+            upsert: true,
+        }
+    }
+
+    pub fn entity_type(mut self, entity_type: impl Into<String>) -> Self {
+        self.entity_type = Some(entity_type.into());
+        self
+    }
+}
+
+/// A KG edge. `source` and `target` are record ids (`kg_entities:...`) or entity names.
+#[derive(Debug, Clone)]
+pub struct NewRelationship {
+    pub source: String,
+    pub target: String,
+    pub rel_type: String,
+    pub data: Map<String, Value>,
+    pub upsert: bool,
+}
+
+impl NewRelationship {
+    pub fn new(
+        source: impl Into<String>,
+        rel_type: impl Into<String>,
+        target: impl Into<String>,
+    ) -> Self {
+        Self {
+            source: source.into(),
+            target: target.into(),
+            rel_type: rel_type.into(),
+            data: Map::new(),
+            upsert: true,
+        }
+    }
+}
+
+/// A KG observation, optionally tied to the thought it came from.
+#[derive(Debug, Clone)]
+pub struct NewObservation {
+    pub name: String,
+    pub source_thought_id: Option<String>,
+    pub confidence: Option<f32>,
+    pub data: Map<String, Value>,
+    pub upsert: bool,
+}
+
+impl NewObservation {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source_thought_id: None,
+            confidence: None,
+            data: Map::new(),
+            upsert: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct KgCreated {
+    pub id: String,
+    /// False when `upsert` matched an existing record
+    pub created: bool,
+}
+
+/// Which KG records a search covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KgTarget {
+    Entity,
+    Relationship,
+    Observation,
+    #[default]
+    Mixed,
+}
+
+impl KgTarget {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KgTarget::Entity => "entity",
+            KgTarget::Relationship => "relationship",
+            KgTarget::Observation => "observation",
+            KgTarget::Mixed => "mixed",
+        }
+    }
+}
+
+/// A KG search hit. Fields beyond these vary by kind and are kept in `fields`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct MemoryHit {
+    #[serde(default)]
+    pub id: String,
+    /// "entity", "relationship", or "observation"
+    #[serde(default)]
+    pub kind: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub similarity: Option<f32>,
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
+}
+
+/// A thought search hit.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ThoughtHit {
+    pub id: String,
+    /// Absent when the search asked for `snippet_only`
+    pub content: Option<String>,
+    pub similarity: Option<f32>,
+    pub significance: Option<f32>,
+    pub session_id: Option<String>,
+    pub chain_id: Option<String>,
+    pub origin: Option<String>,
+    pub superseded_by: Option<String>,
+    pub substituted_for: Option<String>,
+    pub references: Vec<Reference>,
+    pub pinned: bool,
+    pub span_start: Option<usize>,
+    pub span_end: Option<usize>,
+    pub snippet: Option<String>,
+    /// Score parts, with `explain_scores`
+    pub score: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchResults {
+    pub memories: Vec<MemoryHit>,
+    pub thoughts: Vec<ThoughtHit>,
+    /// Superseded thoughts replaced by their latest revision
+    pub superseded_substituted: usize,
+    /// Set instead of hits when the search asked for `chain_summary`
+    pub chain_summary: Option<Value>,
+}
+
+impl SearchResults {
+    /// Read the JSON shape `unified_search` returns.
+    fn from_value(value: Value) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Raw {
+            #[serde(default)]
+            memories: Option<RawMemories>,
+            #[serde(default)]
+            thoughts: Option<RawThoughts>,
+            #[serde(default)]
+            chain_summary: Option<Value>,
+        }
+        #[derive(Deserialize)]
+        struct RawMemories {
+            #[serde(default)]
+            items: Vec<MemoryHit>,
+        }
+        #[derive(Deserialize)]
+        struct RawThoughts {
+            #[serde(default)]
+            results: Vec<ThoughtHit>,
+            #[serde(default)]
+            superseded_substituted: usize,
+        }
+
+        let raw: Raw = serde_json::from_value(value)?;
+        let thoughts = raw.thoughts.unwrap_or(RawThoughts {
+            results: Vec::new(),
+            superseded_substituted: 0,
+        });
+        Ok(Self {
+            memories: raw.memories.map(|m| m.items).unwrap_or_default(),
+            thoughts: thoughts.results,
+            superseded_substituted: thoughts.superseded_substituted,
+            chain_summary: raw.chain_summary,
+        })
+    }
+}
+
+impl SurrealMindClient {
+    /// Connect to the configured database and embedder.
+    pub async fn connect(config: &Config) -> Result<Self> {
+        Ok(Self::from_server(SurrealMindServer::new(config).await?))
+    }
+
+    /// Wrap an existing server, e.g. one that also serves MCP.
+    pub fn from_server(server: SurrealMindServer) -> Self {
+        Self { server }
+    }
+
+    pub fn server(&self) -> &SurrealMindServer {
+        &self.server
+    }
+
+    /// Store and embed a thought. Unlike `think`, no mode routing or memory injection runs.
+    pub async fn create_thought(&self, thought: NewThought) -> Result<CreatedThought> {
+        if thought.content.trim().is_empty() {
+            return Err(SurrealMindError::Validation {
+                message: "Thought content cannot be empty".into(),
+            });
+        }
+        validate_thought_input(&thought.content, &thought.references)?;
+        let origin = thought.origin.as_deref().unwrap_or("human");
+        let built = ThoughtBuilder::new(&self.server, &thought.content, origin)
+            .scale(thought.injection_scale)
+            .significance(thought.significance)
+            .tags(Some(thought.tags))
+            .confidence(thought.confidence)
+            .continuity(
+                thought.session_id,
+                thought.chain_id,
+                thought.previous_thought_id,
+                thought.revises_thought,
+                thought.branch_from,
+            )
+            .idempotency_key(thought.idempotency_key)
+            .references(thought.references)
+            .private(thought.is_private)
+            .pinned(thought.pinned)
+            .execute()
+            .await?;
+        Ok(CreatedThought {
+            id: built.id,
+            embedding_status: built.embedding_status,
+            embedding_dim: built.embedding.len(),
+            continuity: built.continuity,
+            redactions: built.redactions,
+        })
+    }
+
+    /// Run a search with the full `search` tool parameters.
+    pub async fn search(&self, params: UnifiedSearchParams) -> Result<SearchResults> {
+        SearchResults::from_value(unified_search(&self.server, params).await?)
+    }
+
+    /// Thoughts most similar to `query`.
+    pub async fn search_thoughts(&self, query: &str, top_k: usize) -> Result<Vec<ThoughtHit>> {
+        let params = UnifiedSearchParams {
+            query: Some(json!({"text": query})),
+            target: Some("thoughts".into()),
+            include_thoughts: Some(true),
+            top_k_thoughts: Some(top_k),
+            ..UnifiedSearchParams::default()
+        };
+        Ok(self.search(params).await?.thoughts)
+    }
+
+    /// KG records of `target` kind most similar to `query`.
+    pub async fn search_kg(
+        &self,
+        query: &str,
+        target: KgTarget,
+        top_k: usize,
+    ) -> Result<Vec<MemoryHit>> {
+        let params = UnifiedSearchParams {
+            query: Some(json!({"text": query})),
+            target: Some(target.as_str().into()),
+            top_k_memories: Some(top_k),
+            ..UnifiedSearchParams::default()
+        };
+        Ok(self.search(params).await?.memories)
+    }
+
+    pub async fn create_entity(&self, entity: NewEntity) -> Result<KgCreated> {
+        let mut data = entity.data;
+        data.insert("name".into(), json!(entity.name));
+        if let Some(entity_type) = entity.entity_type {
+            data.insert("entity_type".into(), json!(entity_type));
+        }
+        self.create_kg("entity", data, entity.upsert).await
+    }
+
+    pub async fn create_relationship(&self, relationship: NewRelationship) -> Result<KgCreated> {
+        let mut data = relationship.data;
+        data.insert("source".into(), json!(relationship.source));
+        data.insert("target".into(), json!(relationship.target));
+        data.insert("rel_type".into(), json!(relationship.rel_type));
+        self.create_kg("relationship", data, relationship.upsert)
+            .await
+    }
+
+    pub async fn create_observation(&self, observation: NewObservation) -> Result<KgCreated> {
+        let mut data = observation.data;
+        data.insert("name".into(), json!(observation.name));
+        if let Some(src) = observation.source_thought_id {
+            data.insert("source_thought_id".into(), json!(src));
+        }
+        if let Some(confidence) = observation.confidence {
+            data.insert("confidence".into(), json!(confidence));
+        }
+        self.create_kg("observation", data, observation.upsert)
+            .await
+    }
+
+    async fn create_kg(
+        &self,
+        kind: &str,
+        data: Map<String, Value>,
+        upsert: bool,
+    ) -> Result<KgCreated> {
+        let mut args = Map::new();
+        args.insert("kind".into(), json!(kind));
+        args.insert("data".into(), Value::Object(data));
+        args.insert("upsert".into(), json!(upsert));
+        let result = self
+            .server
+            .create_kg_item(&args, &mut HashMap::new())
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_results_read_unified_search_shape() {
+        let value = json!({
+            "memories": {"items": [
+                {"id": "e1", "kind": "entity", "name": "SurrealDB", "similarity": 0.9, "data": {"entity_type": "tool"}},
+                {"id": "r1", "kind": "relationship", "source_id": "e1", "target_id": "e2"}
+            ]},
+            "thoughts": {"total": 1, "top_k": 5, "superseded_substituted": 2, "results": [
+                {"id": "t1", "content": "backoff", "similarity": 0.8, "significance": 0.5,
+                 "references": [{"kind": "file", "value": "/src/lib.rs"}], "snippet": "backoff",
+                 "span_start": 0, "span_end": 7}
+            ]}
+        });
+        let results = SearchResults::from_value(value).unwrap();
+        assert_eq!(results.memories.len(), 2);
+        assert_eq!(results.memories[0].name.as_deref(), Some("SurrealDB"));
+        assert_eq!(results.memories[0].fields["data"]["entity_type"], "tool");
+        assert_eq!(results.memories[1].fields["source_id"], "e1");
+        assert_eq!(results.superseded_substituted, 2);
+        let thought = &results.thoughts[0];
+        assert_eq!((thought.id.as_str(), thought.span_end), ("t1", Some(7)));
+        assert_eq!(thought.references.len(), 1);
+
+        let chain = SearchResults::from_value(json!({"chain_summary": {"nodes": []}})).unwrap();
+        assert!(chain.memories.is_empty() && chain.thoughts.is_empty());
+        assert!(chain.chain_summary.is_some());
+    }
+}
@@ -1,4 +1,6 @@
+use std::collections::hash_map::RandomState;
 use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hasher};
 use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
@@ -20,6 +22,20 @@ const DEFAULT_TIMEOUT_MS: u64 = 120_000; // 120s inactivity threshold
 const DEFAULT_TOOL_TIMEOUT_MS: u64 = 300_000; // 300s per-tool timeout
 const DEFAULT_MODEL: &str = "gemini-3-flash-preview";
 const ACTIVITY_CHECK_INTERVAL_MS: u64 = 1000; // Check activity every second
+const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 1000;
+const MAX_RETRY_DELAY_MS: u64 = 30_000;
+
+/// Markers in CLI/API output that indicate a transient quota or capacity failure.
+const RATE_LIMIT_MARKERS: [&str; 7] = [
+    "429",
+    "503",
+    "resource_exhausted",
+    "rate limit",
+    "quota",
+    "unavailable",
+    "overloaded",
+];
 
 /// Streaming JSON event types from Gemini CLI
 #[derive(Debug, Deserialize, Clone, Serialize)]
@@ -224,6 +240,35 @@ pub struct GeminiClient {
     tool_timeout: Duration, // Per-tool execution timeout
     cwd: Option<PathBuf>,
     expose_stream: bool, // Whether to expose streaming events to callers
+    binary: PathBuf,
+}
+
+/// Outcome of [`GeminiClient::check_auth`]: the CLI version and where its credentials come
+/// from (`None` when none were found).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeminiAuthCheck {
+    pub version: String,
+    pub auth: Option<&'static str>,
+}
+
+/// Where the Gemini CLI would find credentials: an API key variable, Vertex AI, or the
+/// OAuth login cached under `<home>/.gemini`.
+pub fn gemini_auth_source(
+    env: impl Fn(&str) -> Option<String>,
+    home: Option<&std::path::Path>,
+) -> Option<&'static str> {
+    let set = |name: &str| env(name).is_some_and(|v| !v.trim().is_empty());
+    if set("GEMINI_API_KEY") {
+        Some("gemini_api_key")
+    } else if set("GOOGLE_API_KEY") {
+        Some("google_api_key")
+    } else if env("GOOGLE_GENAI_USE_VERTEXAI").is_some_and(|v| v == "true" || v == "1") {
+        Some("vertex_ai")
+    } else if home.is_some_and(|h| h.join(".gemini").join("oauth_creds.json").is_file()) {
+        Some("oauth")
+    } else {
+        None
+    }
 }
 
 impl Default for GeminiClient {
@@ -249,6 +294,7 @@ impl GeminiClient {
             tool_timeout: Duration::from_millis(tool_timeout_ms),
             cwd: None,
             expose_stream: false,
+            binary: PathBuf::from("gemini"),
         }
     }
 
@@ -259,6 +305,7 @@ impl GeminiClient {
             tool_timeout: Duration::from_millis(DEFAULT_TOOL_TIMEOUT_MS),
             cwd: None,
             expose_stream: false,
+            binary: PathBuf::from("gemini"),
         }
     }
 
@@ -279,6 +326,38 @@ impl GeminiClient {
         self.tool_timeout = Duration::from_millis(timeout_ms);
         self
     }
+
+    /// Run this executable instead of `gemini` from PATH
+    pub fn with_binary(mut self, binary: impl Into<PathBuf>) -> Self {
+        self.binary = binary.into();
+        self
+    }
+
+    /// Check that the CLI starts (`--version`) and that credentials are present, without
+    /// sending a prompt. Bounded by the client's inactivity timeout.
+    pub async fn check_auth(&self) -> Result<GeminiAuthCheck, AgentError> {
+        let mut cmd = Command::new(&self.binary);
+        cmd.kill_on_drop(true)
+            .env("NO_COLOR", "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .arg("--version");
+        let timeout_ms = self.timeout.as_millis() as u64;
+        let output = tokio::time::timeout(self.timeout, cmd.output())
+            .await
+            .map_err(|_| AgentError::Timeout { timeout_ms })?
+            .map_err(map_spawn_err)?;
+        if !output.status.success() {
+            let stderr = strip_ansi_codes(&String::from_utf8_lossy(&output.stderr));
+            return Err(AgentError::CliError(truncate_chars(stderr.trim(), 500)));
+        }
+        let version = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout))
+            .trim()
+            .to_string();
+        let auth = gemini_auth_source(|k| std::env::var(k).ok(), dirs::home_dir().as_deref());
+        Ok(GeminiAuthCheck { version, auth })
+    }
 }
 
 #[async_trait]
@@ -288,7 +367,7 @@ impl CognitiveAgent for GeminiClient {
         prompt: &str,
         session_id: Option<&str>,
     ) -> Result<AgentResponse, AgentError> {
-        let mut cmd = Command::new("gemini");
+        let mut cmd = Command::new(&self.binary);
         cmd.kill_on_drop(true)
             .env("CI", "true")
             .env("TERM", "dumb")
@@ -345,6 +424,7 @@ impl CognitiveAgent for GeminiClient {
         let mut session_id_from_stream = None;
         let mut content_buffer = String::new();
         let mut stream_events = Vec::new();
+        let mut stream_error: Option<String> = None;
 
         loop {
             tokio::select! {
@@ -413,6 +493,7 @@ impl CognitiveAgent for GeminiClient {
                                         tracing::debug!("Gemini result status: {}", status);
                                     }
                                     GeminiStreamEvent::Error { message, .. } => {
+                                        stream_error = Some(message.clone());
                                         if self.expose_stream {
                                             stream_events.push(event.clone());
                                         }
@@ -484,11 +565,11 @@ impl CognitiveAgent for GeminiClient {
 
                             if !status.success() {
                                 let stderr_str = String::from_utf8_lossy(&stderr_buf);
-                                return Err(AgentError::CliError(format!(
-                                    "gemini exit {}: {}",
-                                    status,
+                                return Err(classify_exit_failure(
+                                    &status.to_string(),
                                     stderr_str.trim(),
-                                )));
+                                    stream_error.as_deref(),
+                                ));
                             }
                             break;
                         }
@@ -565,7 +646,7 @@ impl CognitiveAgent for GeminiClient {
                 stderr_str.to_string()
             };
 
-            return Err(AgentError::CliError(format!(
+            return Err(AgentError::InvalidResponse(format!(
                 "Empty Gemini response: no content captured. stdout: {}, stderr: {}",
                 stdout_snippet.trim(),
                 stderr_snippet.trim()
@@ -594,6 +675,98 @@ impl CognitiveAgent for GeminiClient {
     }
 }
 
+/// Retry settings for transient Gemini failures (`GEMINI_RETRY_MAX_ATTEMPTS`,
+/// `GEMINI_RETRY_BASE_DELAY_MS`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first call (minimum 1).
+    pub max_attempts: u32,
+    /// First backoff delay; doubles per failed attempt up to 30s, with jitter.
+    pub base_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_RETRY_MAX_ATTEMPTS,
+            base_delay_ms: DEFAULT_RETRY_BASE_DELAY_MS,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_env() -> Self {
+        Self {
+            max_attempts: std::env::var("GEMINI_RETRY_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_RETRY_MAX_ATTEMPTS),
+            base_delay_ms: std::env::var("GEMINI_RETRY_BASE_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_RETRY_BASE_DELAY_MS),
+        }
+    }
+
+    /// Delay before retry `attempt` (1-based): a random point in the upper half of
+    /// base, 2x base, 4x base, ... capped at 30s.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        let ceiling = self
+            .base_delay_ms
+            .saturating_mul(factor)
+            .min(MAX_RETRY_DELAY_MS);
+        let half = ceiling / 2;
+        let jitter = RandomState::new().build_hasher().finish() % (half + 1);
+        Duration::from_millis(ceiling - half + jitter)
+    }
+}
+
+/// Call `agent`, retrying rate-limit and timeout failures per `policy`. The same
+/// `session_id` is reused on every attempt so transient failures keep the session.
+/// Returns the last result and the number of attempts made.
+pub async fn call_with_retry<A: CognitiveAgent + ?Sized>(
+    agent: &A,
+    prompt: &str,
+    session_id: Option<&str>,
+    policy: &RetryPolicy,
+) -> (Result<AgentResponse, AgentError>, u32) {
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match agent.call(prompt, session_id).await {
+            Ok(resp) => return (Ok(resp), attempt),
+            Err(e) if !e.is_retryable() || attempt >= max_attempts => return (Err(e), attempt),
+            Err(e) => {
+                let delay = policy.delay(attempt);
+                tracing::warn!(
+                    "Gemini attempt {} failed ({:?}): {}. Retrying in {}ms...",
+                    attempt,
+                    e.kind(),
+                    e,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Map a non-zero CLI exit to an error class using stderr and any stream `error` event.
+fn classify_exit_failure(status: &str, stderr: &str, stream_error: Option<&str>) -> AgentError {
+    let detail = match stream_error {
+        Some(msg) if !stderr.contains(msg) => format!("{} {}", msg, stderr).trim().to_string(),
+        _ => stderr.to_string(),
+    };
+    let lowered = detail.to_lowercase();
+    if RATE_LIMIT_MARKERS.iter().any(|m| lowered.contains(m)) {
+        AgentError::RateLimited(format!("gemini exit {}: {}", status, detail))
+    } else {
+        AgentError::CliError(format!("gemini exit {}: {}", status, detail))
+    }
+}
+
 fn map_spawn_err(err: std::io::Error) -> AgentError {
     if err.kind() == std::io::ErrorKind::NotFound {
         AgentError::NotFound
@@ -622,6 +795,7 @@ fn parse_gemini_response(output: &str) -> Result<GeminiResponse, AgentError> {
     )))
 }
 
+#[allow(clippy::collapsible_match)]
 fn extract_json_candidates(text: &str) -> Vec<String> {
     let mut candidates = Vec::new();
     let mut depth: u32 = 0;
@@ -688,6 +862,121 @@ fn truncate_chars(input: &str, max: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clients::traits::AgentErrorKind;
+    use std::sync::atomic::AtomicU32;
+
+    /// Fails with `RateLimited` for the first `failures` calls, then succeeds.
+    struct FlakyAgent {
+        failures: u32,
+        calls: AtomicU32,
+        sessions: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    impl FlakyAgent {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                calls: AtomicU32::new(0),
+                sessions: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CognitiveAgent for FlakyAgent {
+        async fn call(
+            &self,
+            _prompt: &str,
+            session_id: Option<&str>,
+        ) -> Result<AgentResponse, AgentError> {
+            self.sessions
+                .lock()
+                .unwrap()
+                .push(session_id.map(String::from));
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if n <= self.failures {
+                return Err(AgentError::RateLimited("429 Too Many Requests".into()));
+            }
+            Ok(AgentResponse {
+                session_id: "sess-1".into(),
+                response: "ok".into(),
+                exchange_id: None,
+                stream_events: None,
+            })
+        }
+    }
+
+    const FAST_RETRY: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        base_delay_ms: 1,
+    };
+
+    #[tokio::test]
+    async fn test_call_with_retry_recovers_from_rate_limits() {
+        let agent = FlakyAgent::new(2);
+        let (result, attempts) = call_with_retry(&agent, "hi", Some("sess-1"), &FAST_RETRY).await;
+        assert_eq!(result.unwrap().response, "ok");
+        assert_eq!(attempts, 3);
+        assert_eq!(
+            *agent.sessions.lock().unwrap(),
+            vec![Some("sess-1".to_string()); 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_with_retry_gives_up_after_max_attempts() {
+        let agent = FlakyAgent::new(5);
+        let (result, attempts) = call_with_retry(&agent, "hi", None, &FAST_RETRY).await;
+        assert_eq!(result.unwrap_err().kind(), AgentErrorKind::RateLimited);
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_call_with_retry_does_not_retry_fatal_errors() {
+        struct Broken;
+        #[async_trait]
+        impl CognitiveAgent for Broken {
+            async fn call(&self, _: &str, _: Option<&str>) -> Result<AgentResponse, AgentError> {
+                Err(AgentError::InvalidResponse("empty".into()))
+            }
+        }
+        let (result, attempts) = call_with_retry(&Broken, "hi", None, &FAST_RETRY).await;
+        assert_eq!(result.unwrap_err().kind(), AgentErrorKind::InvalidResponse);
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_classify_exit_failure() {
+        let err = classify_exit_failure("exit status: 1", "Error: 429 RESOURCE_EXHAUSTED", None);
+        assert_eq!(err.kind(), AgentErrorKind::RateLimited);
+        let err = classify_exit_failure("exit status: 1", "", Some("Model is overloaded"));
+        assert_eq!(err.kind(), AgentErrorKind::RateLimited);
+        let err = classify_exit_failure("exit status: 1", "bad flag --foo", None);
+        assert_eq!(err.kind(), AgentErrorKind::Fatal);
+        assert_eq!(
+            AgentError::Timeout { timeout_ms: 5 }.kind(),
+            AgentErrorKind::Timeout
+        );
+        assert_eq!(
+            AgentError::ParseError("x".into()).kind(),
+            AgentErrorKind::InvalidResponse
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_with_jitter_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay_ms: 1000,
+        };
+        for (attempt, ceiling) in [(1, 1000), (2, 2000), (3, 4000), (10, MAX_RETRY_DELAY_MS)] {
+            let ms = policy.delay(attempt).as_millis() as u64;
+            assert!(
+                ms >= ceiling / 2 && ms <= ceiling,
+                "attempt {attempt}: {ms}"
+            );
+        }
+    }
 
     #[test]
     fn test_stream_json_parser_legacy_content_events() {
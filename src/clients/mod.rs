@@ -7,4 +7,4 @@ pub mod vibe;
 pub use claude::ClaudeClient;
 pub use codex::CodexClient;
 pub use gemini::GeminiClient;
-pub use traits::{AgentError, AgentResponse, CognitiveAgent};
+pub use traits::{AgentError, AgentErrorKind, AgentResponse, CognitiveAgent};
@@ -23,6 +23,43 @@ pub enum AgentError {
     StdinError(String),
     #[error("cli executable not found")]
     NotFound,
+    #[error("rate limited: {0}")]
+    RateLimited(String),
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Coarse error class used to decide whether a call is worth retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentErrorKind {
+    RateLimited,
+    Timeout,
+    InvalidResponse,
+    Fatal,
+}
+
+impl AgentError {
+    pub fn kind(&self) -> AgentErrorKind {
+        match self {
+            AgentError::RateLimited(_) => AgentErrorKind::RateLimited,
+            AgentError::Timeout { .. } => AgentErrorKind::Timeout,
+            AgentError::InvalidResponse(_) | AgentError::ParseError(_) => {
+                AgentErrorKind::InvalidResponse
+            }
+            AgentError::CliError(_) | AgentError::StdinError(_) | AgentError::NotFound => {
+                AgentErrorKind::Fatal
+            }
+        }
+    }
+
+    /// Rate limits and timeouts are transient; everything else fails fast.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind(),
+            AgentErrorKind::RateLimited | AgentErrorKind::Timeout
+        )
+    }
 }
 
 #[async_trait]
@@ -26,6 +26,7 @@ pub(crate) fn split_sentences(s: &str) -> Vec<String> {
     out
 }
 
+#[allow(clippy::unnecessary_sort_by)]
 pub(crate) fn top_keywords(s: &str, n: usize) -> Vec<String> {
     use std::collections::HashMap;
     let mut freq: HashMap<String, usize> = HashMap::new();
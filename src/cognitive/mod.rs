@@ -12,11 +12,31 @@ pub mod socratic;
 pub mod systems;
 pub mod types;
 
+use crate::config::CognitiveProfiles;
 use framework::Framework;
 use once_cell::sync::Lazy;
 use std::sync::Arc;
 use types::FrameworkOutput;
 
+/// Config keys (as used in `[cognitive.profiles]`) and the framework names they map to.
+pub const FRAMEWORK_KEYS: [(&str, &str); 7] = [
+    ("ooda", "OODA"),
+    ("socratic", "Socratic"),
+    ("first_principles", "FirstPrinciples"),
+    ("root_cause", "RootCause"),
+    ("lateral", "Lateral"),
+    ("systems", "SystemsThinking"),
+    ("dialectical", "Dialectical"),
+];
+
+/// Resolve a config key (e.g. `root_cause`) to its framework name (e.g. `RootCause`).
+pub fn framework_name(key: &str) -> Option<&'static str> {
+    FRAMEWORK_KEYS
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, name)| *name)
+}
+
 /// Simple cognitive engine that runs available frameworks and blends their outputs
 /// according to integer weights per framework name.
 pub struct CognitiveEngine {
@@ -179,6 +199,20 @@ impl CognitiveEngine {
             },
         }
     }
+
+    /// Blend using the configured profile for `mode`, falling back to the built-in
+    /// default weights when the mode has no profile.
+    pub fn blend_for_mode(
+        &self,
+        mode: &str,
+        input: &str,
+        profiles: &CognitiveProfiles,
+    ) -> FrameworkOutput {
+        let weights = profiles
+            .weights_for(mode)
+            .unwrap_or_else(|| profile::mode_weights(mode));
+        self.blend(input, &weights)
+    }
 }
 
 #[cfg(test)]
@@ -301,4 +335,54 @@ mod tests {
             "Meta should contain weights_used"
         );
     }
+
+    #[test]
+    fn test_mode_weights_shift_allocation() {
+        use super::profile::mode_weights;
+
+        let debug = mode_weights("debug");
+        let plan = mode_weights("plan");
+        assert!(debug.get("RootCause").unwrap_or(&0) > plan.get("RootCause").unwrap_or(&0));
+        assert!(
+            plan.get("SystemsThinking").unwrap_or(&0) > debug.get("SystemsThinking").unwrap_or(&0)
+        );
+
+        let engine = CognitiveEngine::new();
+        let input = "The cache layer fails under load. How should we redesign the system?";
+        let debug_out = engine.blend(input, &debug);
+        let plan_out = engine.blend(input, &plan);
+        assert_ne!(debug_out.insights, plan_out.insights);
+    }
+
+    #[test]
+    fn test_blend_for_mode_uses_configured_profiles() {
+        use crate::config::CognitiveProfiles;
+        use std::collections::BTreeMap;
+
+        let profiles = CognitiveProfiles(BTreeMap::from([
+            (
+                "debug".to_string(),
+                BTreeMap::from([("root_cause".to_string(), 100)]),
+            ),
+            (
+                "plan".to_string(),
+                BTreeMap::from([("systems".to_string(), 100)]),
+            ),
+        ]));
+        let engine = CognitiveEngine::new();
+        let input = "The cache layer fails under load. How should we redesign the system?";
+
+        let debug_out = engine.blend_for_mode("debug", input, &profiles);
+        let plan_out = engine.blend_for_mode("plan", input, &profiles);
+        assert_ne!(debug_out.insights, plan_out.insights);
+        assert_eq!(debug_out.meta["weights_used"], r#"{"RootCause": 100}"#);
+        assert_eq!(plan_out.meta["weights_used"], r#"{"SystemsThinking": 100}"#);
+
+        // Modes without a configured profile use the built-in defaults
+        let stuck_out = engine.blend_for_mode("stuck", input, &profiles);
+        let stuck_used = &stuck_out.meta["weights_used"];
+        for name in super::profile::mode_weights("stuck").keys() {
+            assert!(stuck_used.contains(name), "{stuck_used}");
+        }
+    }
 }
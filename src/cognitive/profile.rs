@@ -158,3 +158,25 @@ pub fn profile_for(submode: Submode) -> SubmodeProfile {
         },
     }
 }
+
+/// Framework blend weights for a think mode (debug, build, plan, stuck, question, conclude).
+/// Unknown modes fall back to the problem-solving profile.
+pub fn mode_weights(mode: &str) -> HashMap<&'static str, u8> {
+    match mode.to_lowercase().as_str() {
+        "debug" => HashMap::from([("RootCause", 50), ("OODA", 35), ("SystemsThinking", 15)]),
+        "build" => HashMap::from([("OODA", 50), ("FirstPrinciples", 30), ("Lateral", 20)]),
+        "plan" => HashMap::from([
+            ("SystemsThinking", 45),
+            ("FirstPrinciples", 35),
+            ("Dialectical", 20),
+        ]),
+        "stuck" => HashMap::from([("Lateral", 40), ("Socratic", 30), ("FirstPrinciples", 30)]),
+        "question" => HashMap::from([("Socratic", 45), ("FirstPrinciples", 35), ("Lateral", 20)]),
+        "conclude" => HashMap::from([
+            ("Dialectical", 40),
+            ("SystemsThinking", 30),
+            ("Socratic", 30),
+        ]),
+        _ => profile_for(Submode::ProblemSolving).weights,
+    }
+}
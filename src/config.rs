@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 /// Main configuration structure loaded from surreal_mind.toml and environment variables
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -6,6 +7,39 @@ pub struct Config {
     pub system: SystemConfig,
     pub retrieval: RetrievalConfig,
     pub orbital_mechanics: OrbitalConfig,
+    /// Cognitive framework blend profiles per think mode
+    #[serde(default)]
+    pub cognitive: CognitiveConfig,
+    /// Maintenance jobs (`[maintenance]` section)
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    /// Per-tool enable flags (`[tools]` section)
+    #[serde(default)]
+    pub tools: ToolsConfig,
+    /// Dual-write embedding migration target (`[embedding_migration]` section)
+    #[serde(default)]
+    pub embedding_migration: EmbeddingMigrationConfig,
+    /// Knowledge graph entity type taxonomy (`[kg]` section)
+    #[serde(default)]
+    pub kg: KgConfig,
+    /// Memory injection policy (`[injection]` section)
+    #[serde(default)]
+    pub injection: InjectionConfig,
+    /// Embedding API client settings (`[embedding]` section)
+    #[serde(default)]
+    pub embedding: EmbeddingApiConfig,
+    /// Content safety settings (`[security]` section)
+    #[serde(default)]
+    pub security: SecurityConfig,
+    /// Per-call namespace/database overrides (`[multi_tenant]` section)
+    #[serde(default)]
+    pub multi_tenant: MultiTenantConfig,
+    /// Gemini extraction settings (`[gemini]` section)
+    #[serde(default)]
+    pub gemini: GeminiConfig,
+    /// Scratch database for `--self-test` (`[self_test]` section)
+    #[serde(default)]
+    pub self_test: SelfTestConfig,
     /// Runtime configuration loaded from environment variables
     #[serde(skip)]
     pub runtime: RuntimeConfig,
@@ -16,13 +50,21 @@ pub struct Config {
 pub struct SystemConfig {
     pub embedding_provider: String,
     pub embedding_model: String,
+    /// 0 with `openai_compat` means "discover at startup from a probe embedding"
+    #[serde(default)]
     pub embedding_dimensions: usize,
     pub embed_retries: u32,
+    /// Base URL of an OpenAI-compatible embeddings API (`openai_compat`), e.g. `http://localhost:8080/v1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_base_url: Option<String>,
     pub database_url: String,
     pub database_ns: String,
     pub database_db: String,
     pub inject_debounce: u64,
     pub gemini_model: String,
+    /// Define HNSW indexes on embeddings and use KNN queries for similarity search
+    #[serde(default)]
+    pub use_vector_index: bool,
 }
 
 /// Embedding configuration snapshot for use across components
@@ -50,6 +92,13 @@ pub struct RetrievalConfig {
     pub t3: f32,
     pub floor: f32,
     pub kg_moderation_threshold: f32,
+    /// Pinned thoughts injected ahead of similarity matches (0 disables pin injection)
+    #[serde(default = "default_max_pinned")]
+    pub max_pinned: usize,
+}
+
+fn default_max_pinned() -> usize {
+    3
 }
 
 /// Orbital mechanics for knowledge graph entity drifting and weighting
@@ -62,12 +111,775 @@ pub struct OrbitalConfig {
     pub access_weight: f32,
 }
 
+/// Cognitive framework configuration (`[cognitive]` section)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CognitiveConfig {
+    #[serde(default)]
+    pub profiles: CognitiveProfiles,
+}
+
+/// Embedding migration target (`[embedding_migration]`). While a target is set, new thoughts
+/// and KG records also get an `embedding_v2` from the target model; retrieval keeps using the
+/// primary `embedding` until `maintain finalize_migration` promotes the v2 fields.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EmbeddingMigrationConfig {
+    pub target_provider: Option<String>,
+    pub target_model: Option<String>,
+    /// 0 means the provider default (`openai`) or discovered at startup (`openai_compat`)
+    pub target_dim: usize,
+    /// Base URL for an `openai_compat` target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_base_url: Option<String>,
+}
+
+impl EmbeddingMigrationConfig {
+    pub fn is_active(&self) -> bool {
+        self.target_provider.is_some() || self.target_model.is_some()
+    }
+
+    /// A started migration needs a provider and model, and must change at least one of
+    /// provider, model, or dimension.
+    pub fn validate(&self, system: &SystemConfig) -> anyhow::Result<()> {
+        if !self.is_active() {
+            return Ok(());
+        }
+        let (Some(provider), Some(model)) = (&self.target_provider, &self.target_model) else {
+            anyhow::bail!(
+                "embedding_migration: target_provider and target_model must be set together"
+            );
+        };
+        if !["openai", "openai_compat"].contains(&provider.as_str()) {
+            anyhow::bail!(
+                "embedding_migration.target_provider: unknown provider '{}' (expected one of: openai, openai_compat)",
+                provider
+            );
+        }
+        if provider == "openai_compat" && self.target_base_url.is_none() {
+            anyhow::bail!("embedding_migration: openai_compat target requires target_base_url");
+        }
+        if *provider == system.embedding_provider
+            && *model == system.embedding_model
+            && (self.target_dim == 0 || self.target_dim == system.embedding_dimensions)
+        {
+            anyhow::bail!(
+                "embedding_migration: target {}/{} is already the primary embedding",
+                provider,
+                model
+            );
+        }
+        Ok(())
+    }
+
+    /// `system` with its embedding settings replaced by the migration target.
+    pub fn target_system(&self, system: &SystemConfig) -> Option<SystemConfig> {
+        let (Some(provider), Some(model)) = (&self.target_provider, &self.target_model) else {
+            return None;
+        };
+        Some(SystemConfig {
+            embedding_provider: provider.clone(),
+            embedding_model: model.clone(),
+            embedding_dimensions: self.target_dim,
+            embedding_base_url: self.target_base_url.clone(),
+            ..system.clone()
+        })
+    }
+}
+
+/// Embedding API client settings (`[embedding]` section); only the typed subsections are read
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct EmbeddingApiConfig {
+    #[serde(default)]
+    pub rate_limit: EmbeddingRateLimitConfig,
+    #[serde(default)]
+    pub cost: EmbeddingCostConfig,
+}
+
+/// Content safety settings (`[security]` section); only the typed subsections are read
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SecurityConfig {
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+}
+
+/// Gemini extraction prompt budget (`[gemini]`). `kg_populate` splits a batch of thoughts
+/// into chunks whose prompts stay within `max_prompt_chars` (template included) and calls
+/// Gemini once per chunk; 0 sends each batch as one prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GeminiConfig {
+    pub max_prompt_chars: usize,
+}
+
+impl Default for GeminiConfig {
+    fn default() -> Self {
+        Self {
+            max_prompt_chars: 200_000,
+        }
+    }
+}
+
+/// Scratch namespace/database that `surreal-mind --self-test` writes to (`[self_test]`).
+/// Must differ from the configured `database_ns`/`database_db` pair.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SelfTestConfig {
+    pub ns: String,
+    pub db: String,
+}
+
+impl Default for SelfTestConfig {
+    fn default() -> Self {
+        Self {
+            ns: "selftest".to_string(),
+            db: "selftest".to_string(),
+        }
+    }
+}
+
+impl SelfTestConfig {
+    /// Reject empty names and the configured pair, so a self-test never touches real data.
+    pub fn validate(&self, system: &SystemConfig) -> anyhow::Result<()> {
+        if self.ns.trim().is_empty() || self.db.trim().is_empty() {
+            anyhow::bail!("self_test: ns and db must be non-empty");
+        }
+        if self.ns == system.database_ns && self.db == system.database_db {
+            anyhow::bail!(
+                "self_test: ns/db ({}/{}) must differ from the configured database",
+                self.ns,
+                self.db
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Databases a tool call may target with `namespace`/`database` instead of the configured
+/// ones (`[multi_tenant]`), e.g. `allowed = [["work", "main"], ["personal", "main"]]`. The
+/// configured pair is always allowed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MultiTenantConfig {
+    pub allowed: Vec<(String, String)>,
+}
+
+impl MultiTenantConfig {
+    /// Reject empty names.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some((ns, db)) = self
+            .allowed
+            .iter()
+            .find(|(ns, db)| ns.trim().is_empty() || db.trim().is_empty())
+        {
+            anyhow::bail!(
+                "multi_tenant.allowed: namespace and database must be non-empty (got [{:?}, {:?}])",
+                ns,
+                db
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether a call may use `ns`/`db`; `system` is the configured pair.
+    pub fn allows(&self, system: &SystemConfig, ns: &str, db: &str) -> bool {
+        (ns == system.database_ns && db == system.database_db)
+            || self.allowed.iter().any(|(a, b)| a == ns && b == db)
+    }
+}
+
+/// Secret redaction for new thoughts (`[security.redaction]`). Every match of a pattern is
+/// replaced with `«redacted:<kind>»` before the thought is embedded or stored. A configured
+/// `patterns` list replaces the defaults.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    pub patterns: Vec<RedactionPattern>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RedactionPattern {
+    /// Shown in the replacement marker
+    pub kind: String,
+    /// Regex (`regex` crate syntax)
+    pub pattern: String,
+}
+
+impl RedactionPattern {
+    fn new(kind: &str, pattern: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: vec![
+                RedactionPattern::new("api_key", r"\bsk-[A-Za-z0-9_-]{20,}"),
+                RedactionPattern::new("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+                RedactionPattern::new(
+                    "github_token",
+                    r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})",
+                ),
+                RedactionPattern::new("bearer_token", r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]{16,}"),
+            ],
+        }
+    }
+}
+
+impl RedactionConfig {
+    /// Every pattern must compile and have a kind; errors name the offending pattern.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        crate::utils::redaction::Redactor::from_config(self).map(|_| ())
+    }
+}
+
+/// Client-side embedding rate limit (`[embedding.rate_limit]`). Calls wait for budget
+/// instead of failing; every embedder for the same endpoint and model shares one budget.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EmbeddingRateLimitConfig {
+    /// Requests per minute (0 = unlimited)
+    pub requests_per_minute: u32,
+    /// Estimated input tokens per minute (0 = unlimited)
+    pub tokens_per_minute: u32,
+    /// Longest pause honored from a 429's Retry-After, in seconds
+    pub max_retry_after_secs: u64,
+}
+
+impl Default for EmbeddingRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 3000,
+            tokens_per_minute: 1_000_000,
+            max_retry_after_secs: 60,
+        }
+    }
+}
+
+impl EmbeddingRateLimitConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.max_retry_after_secs == 0 {
+            anyhow::bail!("embedding.rate_limit.max_retry_after_secs must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// Prices for the estimated cost in `maintain embedding_usage_report` (`[embedding.cost]`),
+/// in USD per million tokens. Other providers are priced at 0.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EmbeddingCostConfig {
+    /// `openai`
+    pub openai_usd_per_million_tokens: f64,
+    /// `openai_compat` (0 for local servers such as BGE)
+    pub compat_usd_per_million_tokens: f64,
+}
+
+impl Default for EmbeddingCostConfig {
+    fn default() -> Self {
+        Self {
+            openai_usd_per_million_tokens: 0.02,
+            compat_usd_per_million_tokens: 0.0,
+        }
+    }
+}
+
+impl EmbeddingCostConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (field, rate) in [
+            (
+                "openai_usd_per_million_tokens",
+                self.openai_usd_per_million_tokens,
+            ),
+            (
+                "compat_usd_per_million_tokens",
+                self.compat_usd_per_million_tokens,
+            ),
+        ] {
+            if !rate.is_finite() || rate < 0.0 {
+                anyhow::bail!("embedding.cost.{} must be a non-negative number", field);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn usd_per_million_tokens(&self, provider: &str) -> f64 {
+        match provider {
+            "openai" => self.openai_usd_per_million_tokens,
+            "openai_compat" => self.compat_usd_per_million_tokens,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Knowledge graph settings (`[kg]`). `entity_types` is the taxonomy `remember` checks
+/// entity types against; with `enforce_taxonomy`, types outside it are rejected. With
+/// `require_observation_provenance`, observations must name at least one existing source
+/// thought; otherwise missing or unknown sources are logged and stored as given.
+/// `timeline_mention_threshold` and `timeline_max_mentions` bound the similarity scan that
+/// finds thoughts mentioning an entity in `search entity_timeline`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct KgConfig {
+    pub entity_types: Vec<String>,
+    pub enforce_taxonomy: bool,
+    pub require_observation_provenance: bool,
+    pub timeline_mention_threshold: f32,
+    pub timeline_max_mentions: usize,
+}
+
+impl Default for KgConfig {
+    fn default() -> Self {
+        Self {
+            entity_types: [
+                "person",
+                "project",
+                "concept",
+                "tool",
+                "system",
+                "organization",
+                "place",
+                "event",
+                "document",
+                "research_thread",
+            ]
+            .map(String::from)
+            .to_vec(),
+            enforce_taxonomy: false,
+            require_observation_provenance: false,
+            timeline_mention_threshold: 0.6,
+            timeline_max_mentions: 20,
+        }
+    }
+}
+
+impl KgConfig {
+    /// Taxonomy entries must be in stored form (lowercase, no spaces), and enforcement
+    /// needs at least one.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(bad) = self
+            .entity_types
+            .iter()
+            .find(|t| t.is_empty() || t.chars().any(|c| c.is_uppercase() || c.is_whitespace()))
+        {
+            anyhow::bail!(
+                "kg.entity_types: '{}' must be non-empty, lowercase, and use underscores for spaces",
+                bad
+            );
+        }
+        if self.enforce_taxonomy && self.entity_types.is_empty() {
+            anyhow::bail!("kg.enforce_taxonomy requires at least one entry in kg.entity_types");
+        }
+        if !(0.0..=1.0).contains(&self.timeline_mention_threshold) {
+            anyhow::bail!("kg.timeline_mention_threshold must be between 0.0 and 1.0");
+        }
+        Ok(())
+    }
+
+    pub fn has_entity_type(&self, entity_type: &str) -> bool {
+        self.entity_types.iter().any(|t| t == entity_type)
+    }
+}
+
+/// Memory injection policy (`[injection]` section), read by `inject_memories`.
+/// `scale_caps[i]` caps the memories injected at `injection_scale` i + 1; scales past the
+/// list use its last entry. `recency_days` keeps only KG candidates created within that many
+/// days, `include_kg = false` injects pinned thoughts only, and `min_similarity` replaces the
+/// per-scale `retrieval.t1`..`t3` thresholds. `submodes.<name>` overrides any of these for
+/// one submode, or for one think mode (`debug`, `plan`, `build`, `stuck`, `convo`, `batch`)
+/// when the call has no submode. The defaults reproduce the fixed 5/10/20 caps.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct InjectionConfig {
+    pub scale_caps: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recency_days: Option<u32>,
+    pub include_kg: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_similarity: Option<f32>,
+    pub submodes: BTreeMap<String, InjectionOverride>,
+}
+
+/// Per-submode replacements for [`InjectionConfig`] fields; unset fields keep the base value.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InjectionOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale_caps: Option<Vec<usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recency_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_kg: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_similarity: Option<f32>,
+}
+
+/// The injection settings one `inject_memories` call runs with.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InjectionPolicy {
+    /// `injection_scale` clamped to 0..=[`InjectionConfig::MAX_SCALE`]
+    pub scale: u8,
+    pub cap: usize,
+    pub recency_days: Option<u32>,
+    pub include_kg: bool,
+    pub min_similarity: Option<f32>,
+    /// Key of the applied `[injection.submodes]` entry
+    pub override_key: Option<String>,
+}
+
+impl Default for InjectionConfig {
+    fn default() -> Self {
+        Self {
+            scale_caps: vec![5, 10, 20],
+            recency_days: None,
+            include_kg: true,
+            min_similarity: None,
+            submodes: BTreeMap::new(),
+        }
+    }
+}
+
+impl InjectionConfig {
+    /// Highest `injection_scale` (the `maximum` preset)
+    pub const MAX_SCALE: u8 = 5;
+
+    /// The policy for `injection_scale` under `mode` (a submode or think mode).
+    pub fn policy(&self, injection_scale: i64, mode: Option<&str>) -> InjectionPolicy {
+        let scale = injection_scale.clamp(0, Self::MAX_SCALE as i64) as u8;
+        let (override_key, over) = match mode.and_then(|m| self.submodes.get_key_value(m)) {
+            Some((key, over)) => (Some(key.clone()), over.clone()),
+            None => (None, InjectionOverride::default()),
+        };
+        let caps = over.scale_caps.as_ref().unwrap_or(&self.scale_caps);
+        let cap = match scale {
+            0 => 0,
+            s => caps
+                .get(s as usize - 1)
+                .or(caps.last())
+                .copied()
+                .unwrap_or(0),
+        };
+        InjectionPolicy {
+            scale,
+            cap,
+            recency_days: over.recency_days.or(self.recency_days),
+            include_kg: over.include_kg.unwrap_or(self.include_kg),
+            min_similarity: over.min_similarity.or(self.min_similarity),
+            override_key,
+        }
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let check = |path: &str,
+                     caps: Option<&Vec<usize>>,
+                     recency_days: Option<u32>,
+                     min_similarity: Option<f32>| {
+            if let Some(caps) = caps
+                && (caps.is_empty() || caps.len() > Self::MAX_SCALE as usize)
+            {
+                anyhow::bail!(
+                    "{path}.scale_caps must have 1 to {} entries, got {}",
+                    Self::MAX_SCALE,
+                    caps.len()
+                );
+            }
+            if recency_days == Some(0) {
+                anyhow::bail!("{path}.recency_days must be at least 1 (omit it for no window)");
+            }
+            if let Some(m) = min_similarity
+                && !(0.0..=1.0).contains(&m)
+            {
+                anyhow::bail!("{path}.min_similarity must be between 0.0 and 1.0, got {m}");
+            }
+            Ok(())
+        };
+        check(
+            "injection",
+            Some(&self.scale_caps),
+            self.recency_days,
+            self.min_similarity,
+        )?;
+        for (name, over) in &self.submodes {
+            check(
+                &format!("injection.submodes.{name}"),
+                over.scale_caps.as_ref(),
+                over.recency_days,
+                over.min_similarity,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Maintenance configuration (`[maintenance]` section); only the typed subsections are read
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct MaintenanceConfig {
+    #[serde(default)]
+    pub decay: DecayConfig,
+    #[serde(default)]
+    pub snapshot: SnapshotConfig,
+    #[serde(default)]
+    pub summarize: SummarizeConfig,
+}
+
+/// Thought significance decay (`[maintenance.decay]`), applied by `maintain decay_thoughts`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DecayConfig {
+    /// Multiplier applied once per half-life without access (0 < factor <= 1)
+    pub decay_factor: f32,
+    /// Days without access over which `decay_factor` is applied once
+    pub half_life_days: f64,
+    /// Decay never lowers significance below this value
+    pub floor: f32,
+    /// Delete unreferenced thoughts whose decayed significance falls below this; must exceed
+    /// `floor` (disabled when unset)
+    pub prune_threshold: Option<f32>,
+    /// Thoughts read per page
+    pub batch_size: usize,
+}
+
+impl Default for DecayConfig {
+    fn default() -> Self {
+        Self {
+            decay_factor: 0.5,
+            half_life_days: 90.0,
+            floor: 0.05,
+            prune_threshold: None,
+            batch_size: 200,
+        }
+    }
+}
+
+impl DecayConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(self.decay_factor > 0.0 && self.decay_factor <= 1.0) {
+            anyhow::bail!(
+                "maintenance.decay.decay_factor must be in (0, 1], got {}",
+                self.decay_factor
+            );
+        }
+        if self.half_life_days <= 0.0 {
+            anyhow::bail!(
+                "maintenance.decay.half_life_days must be positive, got {}",
+                self.half_life_days
+            );
+        }
+        if self.batch_size == 0 {
+            anyhow::bail!("maintenance.decay.batch_size must be at least 1");
+        }
+        if let Some(t) = self.prune_threshold
+            && t <= self.floor
+        {
+            anyhow::bail!(
+                "maintenance.decay.prune_threshold ({}) must exceed floor ({}) or nothing is pruned",
+                t,
+                self.floor
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Namespace snapshots (`[maintenance.snapshot]`), written by `maintain snapshot_create`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    /// Directory that receives one timestamped subdirectory per snapshot
+    pub backup_dir: String,
+    /// Rows read or inserted per query
+    pub batch_size: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            backup_dir: "./backups".to_string(),
+            batch_size: 500,
+        }
+    }
+}
+
+impl SnapshotConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.backup_dir.trim().is_empty() {
+            anyhow::bail!("maintenance.snapshot.backup_dir must not be empty");
+        }
+        if self.batch_size == 0 {
+            anyhow::bail!("maintenance.snapshot.batch_size must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// Session summaries (`[maintenance.summarize]`), written by `maintain summarize_session`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SummarizeConfig {
+    /// "extractive" (local, deterministic) or "gemini"; a failed Gemini call falls back to
+    /// extractive
+    pub provider: String,
+    /// Most thoughts of one session read for its summary, oldest first
+    pub max_thoughts: usize,
+    /// Points kept by the extractive summary
+    pub max_points: usize,
+    /// Significance of the summary thought
+    pub significance: f32,
+    /// Multiply the significance of summarized thoughts by this, once per thought (disabled
+    /// when unset)
+    pub demote_factor: Option<f32>,
+}
+
+impl Default for SummarizeConfig {
+    fn default() -> Self {
+        Self {
+            provider: "extractive".to_string(),
+            max_thoughts: 200,
+            max_points: 6,
+            significance: 0.8,
+            demote_factor: None,
+        }
+    }
+}
+
+impl SummarizeConfig {
+    pub const PROVIDERS: [&'static str; 2] = ["extractive", "gemini"];
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !Self::PROVIDERS.contains(&self.provider.as_str()) {
+            anyhow::bail!(
+                "maintenance.summarize.provider must be one of {:?}, got '{}'",
+                Self::PROVIDERS,
+                self.provider
+            );
+        }
+        if self.max_thoughts == 0 || self.max_points == 0 {
+            anyhow::bail!("maintenance.summarize.max_thoughts and max_points must be at least 1");
+        }
+        if !(0.0..=1.0).contains(&self.significance) {
+            anyhow::bail!(
+                "maintenance.summarize.significance must be in [0, 1], got {}",
+                self.significance
+            );
+        }
+        if let Some(f) = self.demote_factor
+            && !(f > 0.0 && f < 1.0)
+        {
+            anyhow::bail!(
+                "maintenance.summarize.demote_factor must be in (0, 1), got {}",
+                f
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Tool names accepted in `[tools]`; every tool `list_tools` can expose.
+pub const TOOL_NAMES: &[&str] = &[
+    "think",
+    "think_batch",
+    "wander",
+    "maintain",
+    "journal",
+    "rethink",
+    "corrections",
+    "system_status",
+    "embedding_probe",
+    "test_notification",
+    "remember",
+    "forget",
+    "export",
+    "import",
+    "howto",
+    "call_gem",
+    "call_cc",
+    "call_vibe",
+    "search",
+    "call_status",
+    "call_jobs",
+    "call_cancel",
+];
+
+/// Per-tool enable flags (`[tools]`), e.g. `remember = false`. Tools not listed are enabled.
+/// Disabled tools are hidden from `list_tools` and rejected by `call_tool`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ToolsConfig(pub BTreeMap<String, bool>);
+
+impl ToolsConfig {
+    /// Reject keys that do not name a tool.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(name) = self.0.keys().find(|k| !TOOL_NAMES.contains(&k.as_str())) {
+            anyhow::bail!(
+                "tools.{}: unknown tool '{}' (expected one of: {})",
+                name,
+                name,
+                TOOL_NAMES.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, tool: &str) -> bool {
+        self.0.get(tool).copied().unwrap_or(true)
+    }
+}
+
+/// Per-mode framework weights (`[cognitive.profiles]`), e.g. `debug = { root_cause = 50, ooda = 50 }`.
+/// Keys are think modes; values map framework keys (see `cognitive::FRAMEWORK_KEYS`) to weights.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct CognitiveProfiles(pub BTreeMap<String, BTreeMap<String, u8>>);
+
+impl CognitiveProfiles {
+    /// Reject framework keys that do not name a registered framework.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (mode, weights) in &self.0 {
+            for key in weights.keys() {
+                if crate::cognitive::framework_name(key).is_none() {
+                    let known: Vec<&str> = crate::cognitive::FRAMEWORK_KEYS
+                        .iter()
+                        .map(|(k, _)| *k)
+                        .collect();
+                    return Err(anyhow::anyhow!(
+                        "cognitive.profiles.{}.{}: unknown framework '{}' (expected one of: {})",
+                        mode,
+                        key,
+                        key,
+                        known.join(", ")
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Weights for a mode keyed by framework name, or None when the mode has no profile.
+    pub fn weights_for(&self, mode: &str) -> Option<HashMap<&'static str, u8>> {
+        let weights = self.0.get(&mode.to_lowercase())?;
+        Some(
+            weights
+                .iter()
+                .filter_map(|(k, w)| crate::cognitive::framework_name(k).map(|name| (name, *w)))
+                .collect(),
+        )
+    }
+}
+
 /// Runtime configuration loaded from environment variables
 #[derive(Debug, Clone)]
 pub struct RuntimeConfig {
     pub database_user: String,
     pub database_pass: String,
     pub openai_api_key: Option<String>,
+    /// Bearer token for `openai_compat` endpoints; falls back to `openai_api_key`
+    pub embed_api_key: Option<String>,
     pub nomic_api_key: Option<String>,
     pub tool_timeout_ms: u64,
     pub mcp_no_log: bool,
@@ -88,14 +900,65 @@ pub struct RuntimeConfig {
     pub verify_topk: usize,
     pub verify_min_sim: f32,
     pub verify_evidence_limit: usize,
+    pub verify_negation_window: usize,
+    /// Which KG candidates verification scores first: `recent` (created_at) or `confidence`
+    /// (extraction_confidence, then created_at)
+    pub verify_candidate_order: String,
     pub persist_verification: bool,
+    // Cognitive framework enhancement for think
+    pub think_enhance: bool,
+    pub think_enhance_timeout_ms: u64,
+    pub db_reconnect_max_attempts: u32,
+    pub db_reconnect_base_delay_ms: u64,
+    /// Record every tool call (name, argument shape, latency, outcome) in `tool_audit`
+    pub audit_enabled: bool,
+    /// Reject tool calls whose arguments don't match the tool's input schema (unknown
+    /// properties, enum values, types) before they reach the handler
+    pub strict_params: bool,
+    /// Flag near-duplicate thoughts at creation time (hint only, never blocks)
+    pub duplicate_detect: bool,
+    pub duplicate_threshold: f32,
+    /// Most recent thoughts scanned when the vector index is unavailable
+    pub duplicate_scan_limit: usize,
+    /// Suggest tags from similar KG entities when `think` is called without `suggest_tags`
+    pub think_suggest_tags: bool,
+    /// Bump `access_count` / `last_accessed` on thoughts returned by search or injected
+    pub track_access: bool,
+    /// Expose `crate::metrics` as Prometheus text at HTTP `/metrics` and in `system_status`
+    pub metrics_enabled: bool,
+    /// Preload the `cache_warm` most recently accessed thoughts and the KG entity name index
+    /// at startup
+    pub warm_start: bool,
+    /// Max kg_entities rows loaded into the name index during warm start
+    pub warm_start_max_rows: usize,
+    /// Budget for the whole warm-start phase; whatever loaded by then is kept
+    pub warm_start_timeout_ms: u64,
+    /// Don't apply pending schema migrations at startup (read-only deployments); they are
+    /// only logged
+    pub skip_migrations: bool,
+    /// Minimum entity similarity for a tag suggestion
+    pub think_suggest_tags_threshold: f32,
+    /// `think` result size when the call has no `response_detail`
+    pub think_response_detail: crate::tools::thinking::ResponseDetail,
+    /// `think` without `session_id` joins the session of the latest thought with the same
+    /// origin if it is at most this old, else starts a new one; 0 disables inference
+    pub think_session_gap_minutes: u64,
+    /// With an inherited session and no `previous_thought_id`, link to that session's latest
+    /// thought
+    pub think_auto_link_previous: bool,
+    /// Minimum gap between MCP progress notifications from long-running maintenance calls
+    pub progress_interval_ms: u64,
     // HTTP transport configuration
     pub transport: String,
     pub http_bind: std::net::SocketAddr,
     pub http_path: String,
     pub bearer_token: Option<String>,
     pub allow_token_in_url: bool,
+    /// MCP endpoint mode: "streamable" (sessions keyed by `Mcp-Session-Id`, GET event
+    /// streams) or "basic" (stateless POST request/response)
+    pub http_mode: String,
     pub http_sse_keepalive_sec: u64,
+    /// Idle time after which a streamable session is closed; 0 keeps sessions forever
     pub http_session_ttl_sec: u64,
     pub http_request_timeout_ms: u64,
     pub http_mcp_op_timeout_ms: Option<u64>,
@@ -114,6 +977,7 @@ impl Default for RuntimeConfig {
             database_user: "root".to_string(),
             database_pass: "root".to_string(),
             openai_api_key: None,
+            embed_api_key: None,
             nomic_api_key: None,
             tool_timeout_ms: 15_000,
             mcp_no_log: false,
@@ -124,7 +988,30 @@ impl Default for RuntimeConfig {
             verify_topk: 100,
             verify_min_sim: 0.70,
             verify_evidence_limit: 10,
+            verify_negation_window: crate::tools::thinking::types::DEFAULT_NEGATION_WINDOW,
+            verify_candidate_order: "recent".to_string(),
             persist_verification: false,
+            think_enhance: true,
+            think_enhance_timeout_ms: 1500,
+            db_reconnect_max_attempts: 5,
+            db_reconnect_base_delay_ms: 500,
+            audit_enabled: true,
+            strict_params: false,
+            duplicate_detect: true,
+            duplicate_threshold: 0.95,
+            duplicate_scan_limit: 200,
+            think_suggest_tags: true,
+            track_access: true,
+            metrics_enabled: false,
+            warm_start: false,
+            warm_start_max_rows: 10_000,
+            warm_start_timeout_ms: 5_000,
+            skip_migrations: false,
+            think_suggest_tags_threshold: 0.6,
+            think_response_detail: crate::tools::thinking::ResponseDetail::Full,
+            think_session_gap_minutes: 45,
+            think_auto_link_previous: false,
+            progress_interval_ms: 1000,
             max_retries: 3,
             retry_delay_ms: 500,
             embed_strict: false,
@@ -142,6 +1029,7 @@ impl Default for RuntimeConfig {
             http_path: "/mcp".to_string(),
             bearer_token: None,
             allow_token_in_url: false,
+            http_mode: "streamable".to_string(),
             http_sse_keepalive_sec: 15,
             http_session_ttl_sec: 900,
             http_request_timeout_ms: 10000,
@@ -197,9 +1085,41 @@ impl Config {
         if let Ok(db_name) = std::env::var("SURR_DB_DB") {
             config.system.database_db = db_name;
         }
+        if let Ok(ns) = std::env::var("SURR_SELFTEST_NS") {
+            config.self_test.ns = ns;
+        }
+        if let Ok(db) = std::env::var("SURR_SELFTEST_DB") {
+            config.self_test.db = db;
+        }
+        if let Ok(base_url) = std::env::var("SURR_EMBED_BASE_URL") {
+            config.system.embedding_base_url = Some(base_url);
+        }
+        // Legacy requests-per-second knob
+        if let Some(rps) = std::env::var("SURR_EMBED_RPS")
+            .ok()
+            .and_then(|s| s.parse::<f32>().ok())
+        {
+            config.embedding.rate_limit.requests_per_minute = (rps.max(0.0) * 60.0).round() as u32;
+        }
 
         // Load runtime configuration from environment variables
         config.runtime = RuntimeConfig::load_from_env();
+        config.runtime.validate()?;
+
+        // Reject unknown framework names in cognitive profiles
+        config.cognitive.profiles.validate()?;
+        config.maintenance.decay.validate()?;
+        config.maintenance.snapshot.validate()?;
+        config.maintenance.summarize.validate()?;
+        config.tools.validate()?;
+        config.embedding_migration.validate(&config.system)?;
+        config.kg.validate()?;
+        config.injection.validate()?;
+        config.security.redaction.validate()?;
+        config.embedding.rate_limit.validate()?;
+        config.embedding.cost.validate()?;
+        config.multi_tenant.validate()?;
+        config.self_test.validate(&config.system)?;
 
         // Log env overrides for debugging (env-first confirmation)
         if std::env::var("SURR_DB_URL").is_ok() {
@@ -292,6 +1212,19 @@ impl Config {
                 ),
             },
 
+            "openai_compat" => {
+                if config
+                    .system
+                    .embedding_base_url
+                    .as_deref()
+                    .is_none_or(|u| u.trim().is_empty())
+                {
+                    return Err(anyhow::anyhow!(
+                        "embedding_provider 'openai_compat' requires system.embedding_base_url (or SURR_EMBED_BASE_URL)"
+                    ));
+                }
+            }
+
             _ => tracing::warn!(
                 "Unknown embedding provider '{}', validation skipped",
                 config.system.embedding_provider
@@ -320,11 +1253,13 @@ impl Default for Config {
                 embedding_model: "text-embedding-3-small".to_string(),
                 embedding_dimensions: 1536,
                 embed_retries: 3,
+                embedding_base_url: None,
                 database_url: "127.0.0.1:8000".to_string(),
                 database_ns: "surreal_mind".to_string(),
                 database_db: "consciousness".to_string(),
                 inject_debounce: 1000,
                 gemini_model: "gemini-3-flash-preview".to_string(),
+                use_vector_index: false,
             },
             retrieval: RetrievalConfig {
                 max_injection_scale: 3,
@@ -340,6 +1275,7 @@ impl Default for Config {
                 t3: 0.25,
                 floor: 0.15,
                 kg_moderation_threshold: 0.6,
+                max_pinned: default_max_pinned(),
             },
             orbital_mechanics: OrbitalConfig {
                 decay_rate: 0.1,
@@ -348,18 +1284,43 @@ impl Default for Config {
                 recency_weight: 0.4,
                 access_weight: 0.3,
             },
+            cognitive: CognitiveConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            tools: ToolsConfig::default(),
+            embedding_migration: EmbeddingMigrationConfig::default(),
+            kg: KgConfig::default(),
+            injection: InjectionConfig::default(),
+            embedding: EmbeddingApiConfig::default(),
+            security: SecurityConfig::default(),
+            multi_tenant: MultiTenantConfig::default(),
+            gemini: GeminiConfig::default(),
+            self_test: SelfTestConfig::default(),
             runtime: RuntimeConfig::default(),
         }
     }
 }
 
 impl RuntimeConfig {
+    pub const HTTP_MODES: [&'static str; 2] = ["streamable", "basic"];
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !Self::HTTP_MODES.contains(&self.http_mode.as_str()) {
+            anyhow::bail!(
+                "SURR_HTTP_MODE must be one of {:?}, got '{}'",
+                Self::HTTP_MODES,
+                self.http_mode
+            );
+        }
+        Ok(())
+    }
+
     /// Load runtime configuration from environment variables
     pub fn load_from_env() -> Self {
         let mut cfg = Self {
             database_user: std::env::var("SURR_DB_USER").unwrap_or_else(|_| "root".to_string()),
             database_pass: std::env::var("SURR_DB_PASS").unwrap_or_else(|_| "root".to_string()),
             openai_api_key: std::env::var("OPENAI_API_KEY").ok(),
+            embed_api_key: std::env::var("SURR_EMBED_API_KEY").ok(),
             nomic_api_key: std::env::var("NOMIC_API_KEY").ok(),
             tool_timeout_ms: std::env::var("SURR_TOOL_TIMEOUT_MS")
                 .ok()
@@ -394,9 +1355,89 @@ impl RuntimeConfig {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(10),
+            verify_negation_window: std::env::var("SURR_VERIFY_NEGATION_WINDOW")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(crate::tools::thinking::types::DEFAULT_NEGATION_WINDOW),
+            verify_candidate_order: std::env::var("SURR_VERIFY_CANDIDATE_ORDER")
+                .unwrap_or_else(|_| "recent".to_string()),
             persist_verification: std::env::var("SURR_PERSIST_VERIFICATION")
                 .ok()
                 .is_some_and(|v| v == "true" || v == "1"),
+            think_enhance: std::env::var("SURR_THINK_ENHANCE")
+                .ok()
+                .is_none_or(|v| v != "false" && v != "0"),
+            think_enhance_timeout_ms: std::env::var("SURR_THINK_ENHANCE_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1500),
+            db_reconnect_max_attempts: std::env::var("SURR_DB_RECONNECT_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+            db_reconnect_base_delay_ms: std::env::var("SURR_DB_RECONNECT_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(500),
+            audit_enabled: std::env::var("SURR_AUDIT_ENABLED")
+                .map(|v| v != "0" && !v.eq_ignore_ascii_case("false"))
+                .unwrap_or(true),
+            strict_params: std::env::var("SURR_STRICT_PARAMS")
+                .ok()
+                .is_some_and(|v| v == "true" || v == "1"),
+            duplicate_detect: std::env::var("SURR_DUPLICATE_DETECT")
+                .ok()
+                .is_none_or(|v| v != "false" && v != "0"),
+            duplicate_threshold: std::env::var("SURR_DUPLICATE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.95),
+            duplicate_scan_limit: std::env::var("SURR_DUPLICATE_SCAN_LIMIT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(200),
+            think_suggest_tags: std::env::var("SURR_THINK_SUGGEST_TAGS")
+                .ok()
+                .is_none_or(|v| v != "false" && v != "0"),
+            track_access: std::env::var("SURR_TRACK_ACCESS")
+                .ok()
+                .is_none_or(|v| v != "false" && v != "0"),
+            metrics_enabled: std::env::var("SURR_METRICS_ENABLED")
+                .ok()
+                .is_some_and(|v| v == "true" || v == "1"),
+            warm_start: std::env::var("SURR_WARM_START")
+                .ok()
+                .is_some_and(|v| v == "true" || v == "1"),
+            warm_start_max_rows: std::env::var("SURR_WARM_START_MAX_ROWS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10_000),
+            warm_start_timeout_ms: std::env::var("SURR_WARM_START_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5_000),
+            skip_migrations: std::env::var("SURR_SKIP_MIGRATIONS")
+                .ok()
+                .is_some_and(|v| v == "true" || v == "1"),
+            think_suggest_tags_threshold: std::env::var("SURR_THINK_SUGGEST_TAGS_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.6),
+            think_response_detail: std::env::var("SURR_THINK_RESPONSE_DETAIL")
+                .ok()
+                .and_then(|v| crate::tools::thinking::ResponseDetail::parse(&v))
+                .unwrap_or_default(),
+            think_session_gap_minutes: std::env::var("SURR_THINK_SESSION_GAP_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(45),
+            think_auto_link_previous: std::env::var("SURR_THINK_AUTO_LINK_PREVIOUS")
+                .ok()
+                .is_some_and(|v| v == "true" || v == "1"),
+            progress_interval_ms: std::env::var("SURR_PROGRESS_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1000),
             max_retries: std::env::var("SURR_EMBED_RETRIES")
                 .ok()
                 .and_then(|v| v.parse().ok())
@@ -442,6 +1483,7 @@ impl RuntimeConfig {
             http_path: "/mcp".to_string(),
             bearer_token: None,
             allow_token_in_url: false,
+            http_mode: "streamable".to_string(),
             http_sse_keepalive_sec: 15,
             http_session_ttl_sec: 900,
             http_request_timeout_ms: 10000,
@@ -470,6 +1512,8 @@ impl RuntimeConfig {
         if let Ok(allow) = std::env::var("SURR_ALLOW_TOKEN_IN_URL") {
             cfg.allow_token_in_url = allow == "1" || allow.to_lowercase() == "true";
         }
+        cfg.http_mode =
+            std::env::var("SURR_HTTP_MODE").unwrap_or_else(|_| "streamable".to_string());
         if let Some(sse) = std::env::var("SURR_HTTP_SSE_KEEPALIVE_SEC")
             .ok()
             .and_then(|v| v.parse::<u64>().ok())
@@ -514,4 +1558,350 @@ mod tests {
         let config = Config::load();
         assert!(config.is_ok() || config.is_err()); // Either way, method works
     }
+
+    #[test]
+    fn test_cognitive_profiles_round_trip() {
+        let mut config = Config::default();
+        config.cognitive.profiles.0.insert(
+            "debug".to_string(),
+            BTreeMap::from([("root_cause".to_string(), 60), ("ooda".to_string(), 40)]),
+        );
+        let toml_str = toml::to_string(&config).expect("serialize config");
+        let parsed: Config = toml::from_str(&toml_str).expect("parse config");
+        assert_eq!(parsed.cognitive.profiles, config.cognitive.profiles);
+        assert!(parsed.cognitive.profiles.validate().is_ok());
+
+        let weights = parsed.cognitive.profiles.weights_for("debug").unwrap();
+        assert_eq!(weights.get("RootCause"), Some(&60));
+        assert_eq!(weights.get("OODA"), Some(&40));
+        assert!(parsed.cognitive.profiles.weights_for("plan").is_none());
+    }
+
+    #[test]
+    fn test_cognitive_section_optional() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        value.as_table_mut().unwrap().remove("cognitive");
+        let parsed: Config = toml::from_str(&toml::to_string(&value).unwrap()).unwrap();
+        assert!(parsed.cognitive.profiles.0.is_empty());
+    }
+
+    #[test]
+    fn test_cognitive_profiles_reject_unknown_framework() {
+        let parsed: CognitiveProfiles = toml::from_str(
+            r#"
+            plan = { systems = 50, six_hats = 50 }
+            "#,
+        )
+        .unwrap();
+        let err = parsed.validate().unwrap_err().to_string();
+        assert!(err.contains("cognitive.profiles.plan.six_hats"), "{err}");
+    }
+
+    #[test]
+    fn test_tools_section_toggles_and_rejects_unknown_names() {
+        let parsed: ToolsConfig = toml::from_str("remember = false\nsearch = true").unwrap();
+        assert!(parsed.validate().is_ok());
+        assert!(!parsed.is_enabled("remember"));
+        assert!(parsed.is_enabled("search"));
+        assert!(parsed.is_enabled("think"), "unlisted tools stay enabled");
+
+        let unknown: ToolsConfig = toml::from_str("memories_create = false").unwrap();
+        let err = unknown.validate().unwrap_err().to_string();
+        assert!(err.contains("tools.memories_create"), "{err}");
+        assert!(err.contains("remember"), "error lists valid names: {err}");
+    }
+
+    #[test]
+    fn test_embedding_migration_section() {
+        let system = Config::default().system;
+        let migration: EmbeddingMigrationConfig = toml::from_str(
+            r#"
+            target_provider = "openai_compat"
+            target_model = "bge-small-en-v1.5"
+            target_dim = 384
+            target_base_url = "http://localhost:8080/v1"
+            "#,
+        )
+        .unwrap();
+        assert!(migration.is_active());
+        assert!(migration.validate(&system).is_ok());
+        let target = migration.target_system(&system).unwrap();
+        assert_eq!(target.embedding_model, "bge-small-en-v1.5");
+        assert_eq!(target.embedding_dimensions, 384);
+        assert_eq!(target.database_url, system.database_url);
+
+        assert!(!EmbeddingMigrationConfig::default().is_active());
+        assert!(
+            EmbeddingMigrationConfig::default()
+                .validate(&system)
+                .is_ok()
+        );
+
+        let model_only = EmbeddingMigrationConfig {
+            target_model: Some("text-embedding-3-large".into()),
+            ..Default::default()
+        };
+        assert!(model_only.validate(&system).is_err());
+
+        let same = EmbeddingMigrationConfig {
+            target_provider: Some(system.embedding_provider.clone()),
+            target_model: Some(system.embedding_model.clone()),
+            ..Default::default()
+        };
+        let err = same.validate(&system).unwrap_err().to_string();
+        assert!(err.contains("already the primary"), "{err}");
+    }
+
+    #[test]
+    fn test_maintenance_summarize_section() {
+        let parsed: MaintenanceConfig = toml::from_str(
+            r#"
+            [summarize]
+            provider = "gemini"
+            demote_factor = 0.5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed.summarize.provider, "gemini");
+        assert_eq!(parsed.summarize.max_points, 6);
+        assert!(parsed.summarize.validate().is_ok());
+
+        for bad in [
+            SummarizeConfig {
+                provider: "openai".into(),
+                ..SummarizeConfig::default()
+            },
+            SummarizeConfig {
+                demote_factor: Some(1.0),
+                ..SummarizeConfig::default()
+            },
+            SummarizeConfig {
+                max_points: 0,
+                ..SummarizeConfig::default()
+            },
+        ] {
+            assert!(bad.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_maintenance_snapshot_section() {
+        let parsed: MaintenanceConfig = toml::from_str(
+            r#"
+            [snapshot]
+            backup_dir = "/var/backups/surreal-mind"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed.snapshot.backup_dir, "/var/backups/surreal-mind");
+        assert_eq!(parsed.snapshot.batch_size, 500);
+        assert!(parsed.snapshot.validate().is_ok());
+        assert_eq!(
+            MaintenanceConfig::default().snapshot,
+            SnapshotConfig::default()
+        );
+
+        let bad = SnapshotConfig {
+            batch_size: 0,
+            ..SnapshotConfig::default()
+        };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_maintenance_decay_section() {
+        let parsed: MaintenanceConfig = toml::from_str(
+            r#"
+            retention_days = 365
+            [decay]
+            decay_factor = 0.8
+            prune_threshold = 0.1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed.decay.decay_factor, 0.8);
+        assert_eq!(parsed.decay.prune_threshold, Some(0.1));
+        assert_eq!(parsed.decay.half_life_days, 90.0);
+        assert!(parsed.decay.validate().is_ok());
+
+        let bad = DecayConfig {
+            decay_factor: 1.5,
+            ..DecayConfig::default()
+        };
+        assert!(
+            bad.validate()
+                .unwrap_err()
+                .to_string()
+                .contains("decay_factor")
+        );
+        let unreachable = DecayConfig {
+            prune_threshold: Some(0.01),
+            ..DecayConfig::default()
+        };
+        assert!(unreachable.validate().is_err());
+    }
+
+    #[test]
+    fn test_kg_taxonomy_parses_and_validates() {
+        let parsed: KgConfig =
+            toml::from_str("entity_types = [\"person\", \"tool\"]\nenforce_taxonomy = true")
+                .unwrap();
+        assert_eq!(parsed.entity_types, ["person", "tool"]);
+        assert!(parsed.enforce_taxonomy);
+        assert!(parsed.validate().is_ok());
+        assert!(parsed.has_entity_type("tool") && !parsed.has_entity_type("Tool"));
+        let defaults: KgConfig = toml::from_str("").unwrap();
+        assert_eq!(defaults, KgConfig::default());
+        assert!(!defaults.enforce_taxonomy && defaults.validate().is_ok());
+        assert!(!defaults.require_observation_provenance);
+
+        let bad = KgConfig {
+            entity_types: vec!["Person".into()],
+            ..KgConfig::default()
+        };
+        assert!(bad.validate().unwrap_err().to_string().contains("Person"));
+        let empty = KgConfig {
+            entity_types: Vec::new(),
+            enforce_taxonomy: true,
+            ..KgConfig::default()
+        };
+        assert!(empty.validate().is_err());
+        let threshold = KgConfig {
+            timeline_mention_threshold: 1.5,
+            ..KgConfig::default()
+        };
+        assert!(threshold.validate().is_err());
+    }
+
+    #[test]
+    fn test_injection_policy_caps_and_submode_overrides() {
+        let defaults: InjectionConfig = toml::from_str("").unwrap();
+        assert_eq!(defaults, InjectionConfig::default());
+        assert!(defaults.validate().is_ok());
+        let caps: Vec<usize> = (0..=6)
+            .map(|scale| defaults.policy(scale, None).cap)
+            .collect();
+        assert_eq!(caps, [0, 5, 10, 20, 20, 20, 20]);
+        assert_eq!(defaults.policy(-1, None).scale, 0);
+        let base = defaults.policy(2, Some("debug"));
+        assert!(base.include_kg && base.recency_days.is_none() && base.override_key.is_none());
+
+        let parsed: InjectionConfig = toml::from_str(
+            "scale_caps = [2, 4, 8, 16, 32]\nrecency_days = 30\nmin_similarity = 0.3\n\
+             [submodes.debug]\nrecency_days = 365\nscale_caps = [6]\n\
+             [submodes.convo]\ninclude_kg = false",
+        )
+        .unwrap();
+        assert!(parsed.validate().is_ok());
+        let plain = parsed.policy(5, Some("plan"));
+        assert_eq!((plain.cap, plain.recency_days), (32, Some(30)));
+        assert_eq!(plain.min_similarity, Some(0.3));
+        let debug = parsed.policy(3, Some("debug"));
+        assert_eq!((debug.cap, debug.recency_days), (6, Some(365)));
+        assert_eq!(debug.override_key.as_deref(), Some("debug"));
+        let convo = parsed.policy(1, Some("convo"));
+        assert!(!convo.include_kg);
+        assert_eq!((convo.cap, convo.recency_days), (2, Some(30)));
+
+        for bad in [
+            "scale_caps = []",
+            "scale_caps = [1, 2, 3, 4, 5, 6]",
+            "recency_days = 0",
+            "min_similarity = 1.5",
+            "[submodes.debug]\nmin_similarity = -0.1",
+        ] {
+            let config: InjectionConfig = toml::from_str(bad).unwrap();
+            assert!(config.validate().is_err(), "{bad}");
+        }
+        assert!(toml::from_str::<InjectionConfig>("[submodes.debug]\ncap = 3").is_err());
+    }
+
+    #[test]
+    fn test_embedding_rate_limit_section() {
+        let parsed: EmbeddingApiConfig =
+            toml::from_str("[rate_limit]\nrequests_per_minute = 500").unwrap();
+        assert_eq!(parsed.rate_limit.requests_per_minute, 500);
+        assert_eq!(parsed.rate_limit.tokens_per_minute, 1_000_000);
+        assert!(parsed.rate_limit.validate().is_ok());
+        let defaults: EmbeddingApiConfig = toml::from_str("").unwrap();
+        assert_eq!(defaults.rate_limit, EmbeddingRateLimitConfig::default());
+
+        let bad = EmbeddingRateLimitConfig {
+            max_retry_after_secs: 0,
+            ..EmbeddingRateLimitConfig::default()
+        };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_embedding_cost_section() {
+        let parsed: EmbeddingApiConfig =
+            toml::from_str("[cost]\ncompat_usd_per_million_tokens = 0.01").unwrap();
+        assert_eq!(parsed.cost.usd_per_million_tokens("openai"), 0.02);
+        assert_eq!(parsed.cost.usd_per_million_tokens("openai_compat"), 0.01);
+        assert_eq!(parsed.cost.usd_per_million_tokens("hash"), 0.0);
+        assert!(parsed.cost.validate().is_ok());
+
+        let bad = EmbeddingCostConfig {
+            openai_usd_per_million_tokens: -1.0,
+            ..EmbeddingCostConfig::default()
+        };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_multi_tenant_allowlist() {
+        let parsed: MultiTenantConfig =
+            toml::from_str("allowed = [[\"work\", \"main\"], [\"personal\", \"main\"]]").unwrap();
+        assert!(parsed.validate().is_ok());
+        let system = Config::default().system;
+        assert!(parsed.allows(&system, "personal", "main"));
+        assert!(!parsed.allows(&system, "personal", "other"));
+        // The configured pair needs no entry
+        assert!(MultiTenantConfig::default().allows(
+            &system,
+            &system.database_ns,
+            &system.database_db
+        ));
+
+        let bad = MultiTenantConfig {
+            allowed: vec![("work".into(), " ".into())],
+        };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_self_test_section_rejects_the_live_database() {
+        let system = Config::default().system;
+        assert!(SelfTestConfig::default().validate(&system).is_ok());
+        let parsed: SelfTestConfig = toml::from_str("ns = \"scratch\"").unwrap();
+        assert_eq!(parsed.db, "selftest");
+
+        let live = SelfTestConfig {
+            ns: system.database_ns.clone(),
+            db: system.database_db.clone(),
+        };
+        assert!(live.validate(&system).is_err());
+        let empty = SelfTestConfig {
+            ns: "".into(),
+            db: "x".into(),
+        };
+        assert!(empty.validate(&system).is_err());
+    }
+
+    #[test]
+    fn test_runtime_rejects_unknown_http_mode() {
+        assert!(RuntimeConfig::default().validate().is_ok());
+        let basic = RuntimeConfig {
+            http_mode: "basic".into(),
+            ..RuntimeConfig::default()
+        };
+        assert!(basic.validate().is_ok());
+        let sse = RuntimeConfig {
+            http_mode: "sse".into(),
+            ..RuntimeConfig::default()
+        };
+        let err = sse.validate().unwrap_err().to_string();
+        assert!(err.contains("'sse'"), "{err}");
+    }
 }
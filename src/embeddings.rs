@@ -1,30 +1,53 @@
+use crate::config::EmbeddingRateLimitConfig;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::sync::OnceLock;
-use std::sync::atomic::{AtomicU64, Ordering};
-use tokio::time::{Duration, Instant};
+use tokio::time::Duration;
 use tracing::{debug, info};
 
-static PROCESS_START: OnceLock<Instant> = OnceLock::new();
+pub mod hash;
+pub mod rate_limit;
+pub mod usage;
+
+pub use hash::HashEmbedder;
+pub use rate_limit::{RateLimitSnapshot, RateLimiter, estimate_tokens, shared_limiter};
+pub use usage::{EmbedPurpose, UsageLedger};
 
 #[async_trait]
 pub trait Embedder: Send + Sync {
     async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+    /// [`Embedder::embed`], attributing the request to `purpose` in usage accounting
+    async fn embed_with_purpose(&self, text: &str, purpose: EmbedPurpose) -> Result<Vec<f32>> {
+        let _ = purpose;
+        self.embed(text).await
+    }
     fn dimensions(&self) -> usize;
+    /// Client-side rate limiter state, for embedders that have one
+    fn rate_limit(&self) -> Option<RateLimitSnapshot> {
+        None
+    }
 }
 
-// OpenAI API implementation
+const OPENAI_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+
+/// Text embedded once at startup to learn an `openai_compat` model's dimensions.
+const DIMENSION_PROBE: &str = "dimension probe";
+
+// OpenAI API implementation; also serves OpenAI-compatible servers via `compatible`
 pub struct OpenAIEmbedder {
     client: reqwest::Client,
-    api_key: String,
+    api_key: Option<String>,
     model: String,
     dims: usize,
     retries: u32,
-    // Simple rate limiter: tokens per second
-    rps_limit: f32,
-    last_call: Arc<AtomicU64>,
+    /// Unlimited until [`OpenAIEmbedder::with_shared_rate_limit`]
+    limiter: Arc<RateLimiter>,
+    endpoint: String,
+    /// Set for OpenAI-compatible servers; named in error messages
+    base_url: Option<String>,
+    /// Where successful requests are counted; the process-wide ledger by default
+    ledger: Arc<UsageLedger>,
 }
 
 #[derive(Serialize)]
@@ -40,32 +63,54 @@ struct OpenAIResponseData {
     embedding: Vec<f32>,
 }
 
+#[derive(Deserialize)]
+struct OpenAIUsage {
+    #[serde(default)]
+    total_tokens: u64,
+}
+
 #[derive(Deserialize)]
 struct OpenAIResponse {
     data: Vec<OpenAIResponseData>,
+    /// Absent from some OpenAI-compatible servers; counted as 0 tokens
+    #[serde(default)]
+    usage: Option<OpenAIUsage>,
 }
 
-impl OpenAIEmbedder {
-    pub fn new(api_key: String, model: String, dims: Option<usize>, retries: u32) -> Result<Self> {
-        let mut ua = format!(
-            "surreal-mind/{} (component=embeddings; provider=openai)",
-            env!("CARGO_PKG_VERSION")
-        );
-        if let Ok(commit) = std::env::var("SURR_COMMIT_HASH") {
-            ua.push_str(&format!("; commit={}", &commit[..7.min(commit.len())]));
-        }
+fn build_client(provider: &str) -> Result<reqwest::Client> {
+    let mut ua = format!(
+        "surreal-mind/{} (component=embeddings; provider={})",
+        env!("CARGO_PKG_VERSION"),
+        provider
+    );
+    if let Ok(commit) = std::env::var("SURR_COMMIT_HASH") {
+        ua.push_str(&format!("; commit={}", &commit[..7.min(commit.len())]));
+    }
 
-        let rps_limit = std::env::var("SURR_EMBED_RPS")
-            .ok()
-            .and_then(|s| s.parse::<f32>().ok())
-            .unwrap_or(1.0);
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(20))
+        .user_agent(ua)
+        .build()
+        .context("Failed to build reqwest client with timeout")
+}
 
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(20))
-            .user_agent(ua)
-            .build()
-            .context("Failed to build reqwest client with timeout")?;
+/// Pause a 429 asks for: `retry-after-ms`, else `retry-after` in seconds. HTTP-date values
+/// are not honored.
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let number = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+    };
+    number("retry-after-ms")
+        .map(|ms| Duration::from_secs_f64(ms / 1000.0))
+        .or_else(|| number("retry-after").map(Duration::from_secs_f64))
+}
 
+impl OpenAIEmbedder {
+    pub fn new(api_key: String, model: String, dims: Option<usize>, retries: u32) -> Result<Self> {
         let dims = dims.unwrap_or(match model.as_str() {
             // Known OpenAI embedding dims
             "text-embedding-3-small" => 1536,
@@ -74,66 +119,131 @@ impl OpenAIEmbedder {
         });
 
         Ok(Self {
-            client,
-            api_key,
+            client: build_client("openai")?,
+            api_key: Some(api_key),
             model,
             dims,
             retries,
-            rps_limit,
-            last_call: Arc::new(AtomicU64::new(0)),
+            limiter: Arc::new(RateLimiter::unlimited()),
+            endpoint: OPENAI_EMBEDDINGS_URL.to_string(),
+            base_url: None,
+            ledger: usage::ledger(),
         })
     }
-}
 
-#[async_trait]
-impl Embedder for OpenAIEmbedder {
-    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+    /// Embedder for a server speaking the OpenAI embeddings API at `base_url`
+    /// (requests go to `{base_url}/embeddings`). With `dims` unset, call
+    /// [`Self::discover_dimensions`] before use.
+    pub fn compatible(
+        base_url: String,
+        api_key: Option<String>,
+        model: String,
+        dims: Option<usize>,
+        retries: u32,
+    ) -> Result<Self> {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        Ok(Self {
+            client: build_client("openai_compat")?,
+            api_key: api_key.filter(|k| !k.trim().is_empty()),
+            model,
+            dims: dims.unwrap_or(0),
+            retries,
+            limiter: Arc::new(RateLimiter::unlimited()),
+            endpoint: format!("{}/embeddings", base_url),
+            base_url: Some(base_url),
+            ledger: usage::ledger(),
+        })
+    }
+
+    /// Use the process-wide limiter for this endpoint and model, so every embedder talking
+    /// to the same API shares one budget.
+    pub fn with_shared_rate_limit(mut self, config: &EmbeddingRateLimitConfig) -> Self {
+        self.limiter = shared_limiter(&format!("{}#{}", self.endpoint, self.model), config);
+        self
+    }
+
+    /// Count usage in `ledger` instead of the process-wide one.
+    pub fn with_usage_ledger(mut self, ledger: Arc<UsageLedger>) -> Self {
+        self.ledger = ledger;
+        self
+    }
+
+    /// Provider name recorded with usage.
+    fn provider(&self) -> &'static str {
+        if self.base_url.is_some() {
+            "openai_compat"
+        } else {
+            "openai"
+        }
+    }
+
+    /// Embed a short probe string and adopt its length as this embedder's dimensions.
+    pub async fn discover_dimensions(&mut self) -> Result<usize> {
+        let probe = self.embed(DIMENSION_PROBE).await.with_context(|| {
+            format!(
+                "Failed to discover embedding dimensions from {}",
+                self.api_label()
+            )
+        })?;
+        if probe.is_empty() {
+            anyhow::bail!(
+                "{} returned an empty embedding for model '{}'; set embedding_dimensions explicitly",
+                self.api_label(),
+                self.model
+            );
+        }
+        self.dims = probe.len();
+        Ok(self.dims)
+    }
+
+    fn api_label(&self) -> String {
+        match &self.base_url {
+            Some(url) => format!("embedding endpoint {}", url),
+            None => "OpenAI API".to_string(),
+        }
+    }
+
+    /// Only OpenAI accepts `dimensions`, and only for non-default sizes.
+    fn requested_dimensions(&self) -> Option<usize> {
+        if self.base_url.is_some() || self.dims == 1536 || self.dims == 3072 {
+            None // Use default for standard sizes
+        } else {
+            Some(self.dims)
+        }
+    }
+
+    /// One embedding request with retries, returning the vector and the reported tokens;
+    /// [`Embedder::embed_with_purpose`] adds metrics and usage accounting around it.
+    async fn request_embedding(&self, text: &str) -> Result<(Vec<f32>, u64)> {
         debug!(
-            "Generating OpenAI embedding (model={}, chars={})",
+            "Generating embedding via {} (model={}, chars={})",
+            self.api_label(),
             self.model,
             text.len()
         );
 
-        // Simple rate limiting: wait if needed to respect RPS
-        if self.rps_limit > 0.0 {
-            let interval_ms = (1000.0 / self.rps_limit) as u64;
-            let process_start = PROCESS_START.get_or_init(Instant::now);
-            let now_ms = (Instant::now() - *process_start).as_millis() as u64;
-            let last = self.last_call.load(Ordering::SeqCst);
-            if now_ms < last.saturating_add(interval_ms) {
-                let delay = last.saturating_add(interval_ms).saturating_sub(now_ms);
-                debug!("Rate limiting OpenAI embedding, delaying {}ms", delay);
-                tokio::time::sleep(Duration::from_millis(delay)).await;
-            }
-            self.last_call.store(now_ms, Ordering::SeqCst);
-        }
-
         let body = OpenAIRequest {
             model: &self.model,
             input: text,
-            dimensions: if self.dims != 1536 && self.dims != 3072 {
-                Some(self.dims)
-            } else {
-                None // Use default for standard sizes
-            },
+            dimensions: self.requested_dimensions(),
         };
 
-        // Retry with simple exponential backoff
+        // Retry with simple exponential backoff; every attempt waits for rate limit budget
         let mut last_err: Option<anyhow::Error> = None;
         let attempts = self.retries;
+        let tokens = estimate_tokens(text);
         for i in 0..attempts {
-            let send_res = self
-                .client
-                .post("https://api.openai.com/v1/embeddings")
-                .bearer_auth(&self.api_key)
-                .json(&body)
-                .send()
-                .await
-                .context(format!(
-                    "Failed to send embedding request to OpenAI API for model '{}' ({} chars)",
-                    self.model,
-                    text.len()
-                ));
+            self.limiter.acquire(tokens).await;
+            let mut request = self.client.post(&self.endpoint).json(&body);
+            if let Some(key) = &self.api_key {
+                request = request.bearer_auth(key);
+            }
+            let send_res = request.send().await.context(format!(
+                "Failed to send embedding request to {} for model '{}' ({} chars)",
+                self.api_label(),
+                self.model,
+                text.len()
+            ));
             let response = match send_res {
                 Ok(resp) => resp,
                 Err(e) => {
@@ -146,36 +256,68 @@ impl Embedder for OpenAIEmbedder {
 
             if !response.status().is_success() {
                 let status = response.status();
-                let error_text = response
-                    .text()
-                    .await
-                    .context("Failed to read error response from OpenAI API")?;
+                let retry_after = retry_after(response.headers());
+                let error_text = response.text().await.with_context(|| {
+                    format!("Failed to read error response from {}", self.api_label())
+                })?;
+                // Wrong key or wrong URL will not fix itself; fail without retrying
+                match status {
+                    reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                        anyhow::bail!(
+                            "{} rejected the API key ({}) for model '{}': {}",
+                            self.api_label(),
+                            status,
+                            self.model,
+                            error_text
+                        );
+                    }
+                    reqwest::StatusCode::NOT_FOUND => {
+                        anyhow::bail!(
+                            "{} returned {} for {} (model '{}'); check the base URL and model name: {}",
+                            self.api_label(),
+                            status,
+                            self.endpoint,
+                            self.model,
+                            error_text
+                        );
+                    }
+                    _ => {}
+                }
                 last_err = Some(anyhow::anyhow!(
-                    "OpenAI API error {} for model '{}' ({} chars): {}",
+                    "{} error {} for model '{}' ({} chars): {}",
+                    self.api_label(),
                     status,
                     self.model,
                     text.len(),
                     error_text
                 ));
-                let delay_ms = 200u64 * (1u64 << i);
-                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                let delay = Duration::from_millis(200u64 * (1u64 << i));
+                if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    // Pause the shared limiter so concurrent callers back off too
+                    self.limiter.throttled(retry_after.unwrap_or(delay));
+                } else {
+                    tokio::time::sleep(delay).await;
+                }
                 continue;
             }
 
             let parse_res: Result<OpenAIResponse> = response.json().await.context(format!(
-                "Failed to parse JSON response from OpenAI API for model '{}' ({} chars)",
+                "Failed to parse JSON response from {} for model '{}' ({} chars)",
+                self.api_label(),
                 self.model,
                 text.len()
             ));
             match parse_res {
                 Ok(result) => {
+                    let tokens = result.usage.map_or(0, |u| u.total_tokens);
                     return result
                         .data
                         .into_iter()
                         .next()
-                        .map(|d| d.embedding)
+                        .map(|d| (d.embedding, tokens))
                         .context(format!(
-                            "No embedding data returned from OpenAI API for model '{}' ({} chars)",
+                            "No embedding data returned from {} for model '{}' ({} chars)",
+                            self.api_label(),
                             self.model,
                             text.len()
                         ));
@@ -190,16 +332,38 @@ impl Embedder for OpenAIEmbedder {
 
         Err(last_err.unwrap_or_else(|| {
             anyhow::anyhow!(
-                "Unknown error generating OpenAI embedding for model '{}' ({} chars)",
+                "Unknown error generating embedding via {} for model '{}' ({} chars)",
+                self.api_label(),
                 self.model,
                 text.len()
             )
         }))
     }
+}
+
+#[async_trait]
+impl Embedder for OpenAIEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_with_purpose(text, EmbedPurpose::Other).await
+    }
+
+    async fn embed_with_purpose(&self, text: &str, purpose: EmbedPurpose) -> Result<Vec<f32>> {
+        let start = std::time::Instant::now();
+        let result = self.request_embedding(text).await;
+        crate::metrics::global().record_embed(start.elapsed(), result.is_ok());
+        let (embedding, tokens) = result?;
+        self.ledger
+            .record(self.provider(), &self.model, purpose, tokens);
+        Ok(embedding)
+    }
 
     fn dimensions(&self) -> usize {
         self.dims
     }
+
+    fn rate_limit(&self) -> Option<RateLimitSnapshot> {
+        Some(self.limiter.snapshot())
+    }
 }
 
 // No per-call fallback wrapper. Selection happens at startup to avoid mixed dims.
@@ -209,10 +373,9 @@ pub async fn create_embedder(config: &crate::config::Config) -> Result<Arc<dyn E
     // Load .env file if it exists
     let _ = dotenvy::dotenv();
 
-    // Configuration: prefer OpenAI when key present; else Candle
     let provider = &config.system.embedding_provider;
-    // Allow explicit dimension override for custom models
-    let dim_override = Some(config.system.embedding_dimensions);
+    // Allow explicit dimension override for custom models (0 = unset)
+    let dim_override = Some(config.system.embedding_dimensions).filter(|&d| d > 0);
 
     // Helpers
     let is_placeholder = |s: &str| {
@@ -234,20 +397,55 @@ pub async fn create_embedder(config: &crate::config::Config) -> Result<Arc<dyn E
                     model,
                     dims.unwrap()
                 );
-                Ok(Arc::new(OpenAIEmbedder::new(
-                    key,
-                    model,
-                    dims,
-                    config.system.embed_retries,
-                )?))
+                Ok(Arc::new(
+                    OpenAIEmbedder::new(key, model, dims, config.system.embed_retries)?
+                        .with_shared_rate_limit(&config.embedding.rate_limit),
+                ))
             } else {
                 anyhow::bail!("OPENAI_API_KEY is not set or valid. Cannot Initialize Embeddings.");
             }
         }
+        "openai_compat" => {
+            let base_url = config
+                .system
+                .embedding_base_url
+                .clone()
+                .filter(|u| !u.trim().is_empty())
+                .context("embedding_provider 'openai_compat' requires embedding_base_url")?;
+            let key = config
+                .runtime
+                .embed_api_key
+                .clone()
+                .or_else(|| config.runtime.openai_api_key.clone())
+                .filter(|k| !is_placeholder(k));
+            let model = config.system.embedding_model.clone();
+            let mut embedder = OpenAIEmbedder::compatible(
+                base_url.clone(),
+                key,
+                model.clone(),
+                dim_override,
+                config.system.embed_retries,
+            )?
+            .with_shared_rate_limit(&config.embedding.rate_limit);
+            if dim_override.is_none() {
+                let dims = embedder.discover_dimensions().await?;
+                info!(
+                    "Discovered embedding dimensions from {} (model={}, dims={})",
+                    base_url, model, dims
+                );
+            }
+            info!(
+                "Using OpenAI-compatible embeddings (base_url={}, model={}, dims={})",
+                base_url,
+                model,
+                embedder.dimensions()
+            );
+            Ok(Arc::new(embedder))
+        }
         _ => {
             // Unknown provider - fail explicitly
             anyhow::bail!(
-                "Unknown or unsupported embedding provider: '{}'. Only 'openai' is supported.",
+                "Unknown or unsupported embedding provider: '{}'. Supported: 'openai', 'openai_compat'.",
                 provider
             );
         }
@@ -256,12 +454,250 @@ pub async fn create_embedder(config: &crate::config::Config) -> Result<Arc<dyn E
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use axum::response::{IntoResponse, Response};
+    use axum::{Json, Router, extract::State, http::HeaderMap, http::StatusCode, routing::post};
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use tokio::time::Instant;
+
+    #[derive(Default)]
+    struct MockEmbeddings {
+        requests: Mutex<Vec<(Option<String>, serde_json::Value)>>,
+        /// Leading requests answered with 429 before succeeding
+        rate_limited: AtomicUsize,
+        /// `retry-after-ms` sent with each 429 (none when 0)
+        retry_after_ms: AtomicU64,
+        /// Requests to any other path
+        not_found: AtomicUsize,
+        /// `usage.total_tokens` sent with each success (no `usage` when 0)
+        usage_tokens: AtomicU64,
+    }
+
+    async fn mock_embeddings(
+        State(mock): State<Arc<MockEmbeddings>>,
+        headers: HeaderMap,
+        Json(body): Json<serde_json::Value>,
+    ) -> Response {
+        let auth = headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        mock.requests.lock().unwrap().push((auth, body));
+        if mock
+            .rate_limited
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            let body = Json(serde_json::json!({"error": "slow down"}));
+            return match mock.retry_after_ms.load(Ordering::SeqCst) {
+                0 => (StatusCode::TOO_MANY_REQUESTS, body).into_response(),
+                ms => (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [("retry-after-ms", ms.to_string())],
+                    body,
+                )
+                    .into_response(),
+            };
+        }
+        let mut body = serde_json::json!({"data": [{"embedding": [0.1, 0.2, 0.3, 0.4, 0.5]}]});
+        match mock.usage_tokens.load(Ordering::SeqCst) {
+            0 => {}
+            tokens => {
+                body["usage"] = serde_json::json!({"prompt_tokens": tokens, "total_tokens": tokens})
+            }
+        }
+        (StatusCode::OK, Json(body)).into_response()
+    }
+
+    /// Serve the mock under `/v1/embeddings` and return the `/v1` base URL.
+    async fn spawn_mock(mock: Arc<MockEmbeddings>) -> String {
+        let app = Router::new()
+            .route("/v1/embeddings", post(mock_embeddings))
+            .fallback(|State(mock): State<Arc<MockEmbeddings>>| async move {
+                mock.not_found.fetch_add(1, Ordering::SeqCst);
+                StatusCode::NOT_FOUND
+            })
+            .with_state(mock);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{}/v1", addr)
+    }
+
+    fn compat(base_url: &str, api_key: Option<&str>, dims: Option<usize>) -> OpenAIEmbedder {
+        OpenAIEmbedder::compatible(
+            base_url.to_string(),
+            api_key.map(String::from),
+            "bge-large-en".to_string(),
+            dims,
+            3,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_compat_request_shape() {
+        let mock = Arc::new(MockEmbeddings::default());
+        let base_url = spawn_mock(mock.clone()).await;
+
+        let embedder = compat(&format!("{}/", base_url), Some("tei-key"), Some(5));
+        let embedding = embedder.embed("hello").await.unwrap();
+        assert_eq!(embedding.len(), 5);
+
+        let requests = mock.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let (auth, body) = &requests[0];
+        assert_eq!(auth.as_deref(), Some("Bearer tei-key"));
+        // Compatible servers get no `dimensions` field
+        assert_eq!(
+            body,
+            &serde_json::json!({"model": "bge-large-en", "input": "hello"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_usage_tokens_are_attributed_to_purpose() {
+        let mock = Arc::new(MockEmbeddings {
+            usage_tokens: AtomicU64::new(9),
+            ..Default::default()
+        });
+        let base_url = spawn_mock(mock.clone()).await;
+        let ledger = Arc::new(UsageLedger::default());
+        let embedder = compat(&base_url, None, Some(5)).with_usage_ledger(ledger.clone());
+
+        embedder
+            .embed_with_purpose("a thought", EmbedPurpose::ThoughtCreate)
+            .await
+            .unwrap();
+        embedder
+            .embed_with_purpose("another", EmbedPurpose::ThoughtCreate)
+            .await
+            .unwrap();
+        embedder.embed("probe").await.unwrap();
+        // A server that reports no usage still counts the call
+        mock.usage_tokens.store(0, Ordering::SeqCst);
+        embedder
+            .embed_with_purpose("query", EmbedPurpose::SearchQuery)
+            .await
+            .unwrap();
+
+        let pending: Vec<_> = ledger
+            .pending()
+            .into_iter()
+            .map(|(k, d)| (k.provider, k.model, k.purpose, d.calls, d.tokens))
+            .collect();
+        let row = |purpose, calls, tokens| {
+            (
+                "openai_compat".to_string(),
+                "bge-large-en".to_string(),
+                purpose,
+                calls,
+                tokens,
+            )
+        };
+        assert_eq!(
+            pending,
+            [
+                row(EmbedPurpose::ThoughtCreate, 2, 18),
+                row(EmbedPurpose::SearchQuery, 1, 0),
+                row(EmbedPurpose::Other, 1, 9),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compat_discovers_dimensions() {
+        let mock = Arc::new(MockEmbeddings::default());
+        let base_url = spawn_mock(mock.clone()).await;
+
+        let mut embedder = compat(&base_url, None, None);
+        assert_eq!(embedder.dimensions(), 0);
+        assert_eq!(embedder.discover_dimensions().await.unwrap(), 5);
+        assert_eq!(embedder.dimensions(), 5);
+
+        let requests = mock.requests.lock().unwrap();
+        assert_eq!(requests[0].0, None, "no key, no Authorization header");
+        assert_eq!(requests[0].1["input"], DIMENSION_PROBE);
+    }
+
+    #[tokio::test]
+    async fn test_compat_retries_on_429() {
+        let mock = Arc::new(MockEmbeddings {
+            rate_limited: AtomicUsize::new(2),
+            ..Default::default()
+        });
+        let base_url = spawn_mock(mock.clone()).await;
+
+        let embedder = compat(&base_url, None, Some(5));
+        assert_eq!(embedder.embed("retry me").await.unwrap().len(), 5);
+        assert_eq!(mock.requests.lock().unwrap().len(), 3);
+
+        // Out of attempts: the last 429 is reported
+        mock.rate_limited.store(5, Ordering::SeqCst);
+        let err = embedder.embed("give up").await.unwrap_err().to_string();
+        assert!(err.contains("429"), "{err}");
+        assert!(err.contains(&base_url), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_compat_404_names_base_url_without_retry() {
+        let mock = Arc::new(MockEmbeddings::default());
+        let base_url = spawn_mock(mock.clone()).await;
+        let wrong = format!("{}/nope", base_url);
+
+        let err = compat(&wrong, None, Some(5))
+            .embed("lost")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&wrong), "{err}");
+        assert!(err.contains("404"), "{err}");
+        assert_eq!(mock.not_found.load(Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
-    async fn test_rate_limiter_no_sleep_when_elapsed() {
-        let interval = 1000u64;
-        let last = 0u64;
-        let now = 2000u64;
-        // Simulate: if now >= last + interval, no sleep
-        assert!(now >= last.saturating_add(interval));
+    async fn test_429_retry_after_pauses_shared_limiter() {
+        let mock = Arc::new(MockEmbeddings {
+            rate_limited: AtomicUsize::new(1),
+            retry_after_ms: AtomicU64::new(700),
+            ..Default::default()
+        });
+        let base_url = spawn_mock(mock.clone()).await;
+        let limits = EmbeddingRateLimitConfig {
+            requests_per_minute: 600,
+            ..EmbeddingRateLimitConfig::default()
+        };
+        let embedder = compat(&base_url, None, Some(5)).with_shared_rate_limit(&limits);
+        let sibling = compat(&base_url, None, Some(5)).with_shared_rate_limit(&limits);
+
+        // The retry waits out Retry-After (not the 200ms backoff), and so does the sibling
+        let start = Instant::now();
+        let (first, second) = tokio::join!(embedder.embed("throttled"), async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            sibling.embed("queued behind the pause").await
+        });
+        assert_eq!(first.unwrap().len(), 5);
+        assert_eq!(second.unwrap().len(), 5);
+        assert!(start.elapsed() >= Duration::from_millis(700));
+        assert_eq!(mock.requests.lock().unwrap().len(), 3);
+
+        let snapshot = sibling.rate_limit().unwrap();
+        assert_eq!(snapshot.throttled_responses, 1);
+        assert_eq!(snapshot.requests_per_minute, 600);
+        assert_eq!(snapshot.queued_waiters, 0);
+        assert_eq!(snapshot.paused_ms, 0);
+    }
+
+    #[test]
+    fn test_retry_after_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert("retry-after", "2".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+        headers.insert("retry-after-ms", "250".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(250)));
+        headers.insert("retry-after-ms", "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
     }
 }
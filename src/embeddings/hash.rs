@@ -0,0 +1,69 @@
+//! Deterministic offline embedder
+//!
+//! Used by `--self-test` and the in-memory test harness, where no embedding API may be called.
+
+use super::Embedder;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Deterministic bag-of-words embedder: each lowercased word adds ±1 to a bucket picked by
+/// its FNV-1a hash, and the sum is L2-normalized. Texts sharing words score higher.
+pub struct HashEmbedder {
+    pub dim: usize,
+}
+
+impl HashEmbedder {
+    pub fn vector(&self, text: &str) -> Vec<f32> {
+        let mut v = vec![0.0_f32; self.dim];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            let hash = word
+                .to_lowercase()
+                .bytes()
+                .fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+                    (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+                });
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            v[(hash % self.dim as u64) as usize] += sign;
+        }
+        let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            v[0] = 1.0;
+        } else {
+            v.iter_mut().for_each(|x| *x /= norm);
+        }
+        v
+    }
+}
+
+#[async_trait]
+impl Embedder for HashEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(self.vector(text))
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::cosine_similarity;
+
+    #[test]
+    fn test_hash_embedder_is_deterministic_and_word_based() {
+        let e = HashEmbedder { dim: 64 };
+        let a = e.vector("SurrealDB stores thoughts");
+        assert_eq!(a, e.vector("surrealdb  stores, thoughts"));
+        assert!((cosine_similarity(&a, &a) - 1.0).abs() < 1e-5);
+        assert!(
+            cosine_similarity(&a, &e.vector("thoughts in SurrealDB"))
+                > cosine_similarity(&a, &e.vector("photography lenses"))
+        );
+        assert_eq!(e.vector("").len(), 64);
+    }
+}
@@ -0,0 +1,292 @@
+//! Client-side rate limiting for embedding API calls
+//!
+//! A [`RateLimiter`] keeps two token buckets, requests and estimated input tokens, each
+//! refilled continuously at its per-minute limit and holding at most one minute of budget.
+//! [`RateLimiter::acquire`] waits, first come first served, until both have room instead of
+//! failing. A 429 pauses the limiter for its Retry-After so every caller backs off together.
+//! [`shared_limiter`] hands out one limiter per endpoint and model for the whole process.
+
+use crate::config::EmbeddingRateLimitConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::time::{Duration, Instant};
+use tracing::debug;
+
+/// Budget shortfall below which a bucket counts as full enough (absorbs float rounding).
+const EPSILON: f64 = 1e-9;
+
+static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<RateLimiter>>>> = OnceLock::new();
+
+/// The process-wide limiter for `key` (endpoint and model), created from `config` on first
+/// use. Later callers share it whatever config they pass.
+pub fn shared_limiter(key: &str, config: &EmbeddingRateLimitConfig) -> Arc<RateLimiter> {
+    let mut limiters = LIMITERS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    limiters
+        .entry(key.to_string())
+        .or_insert_with(|| Arc::new(RateLimiter::new(config.clone())))
+        .clone()
+}
+
+/// Rough input token count of `text` (about four bytes per token).
+pub fn estimate_tokens(text: &str) -> u32 {
+    u32::try_from(text.len() / 4 + 1).unwrap_or(u32::MAX)
+}
+
+/// Current limiter state, as reported by `system_status`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RateLimitSnapshot {
+    pub requests_per_minute: u32,
+    pub tokens_per_minute: u32,
+    /// Requests that could start now; `None` when unlimited
+    pub available_requests: Option<u64>,
+    /// Estimated tokens that could be sent now; `None` when unlimited
+    pub available_tokens: Option<u64>,
+    /// Calls waiting for budget
+    pub queued_waiters: usize,
+    /// Remaining Retry-After pause, in milliseconds
+    pub paused_ms: u64,
+    /// 429 responses reported through [`RateLimiter::throttled`]
+    pub throttled_responses: u64,
+}
+
+struct Buckets {
+    requests: f64,
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Option<Instant>,
+}
+
+pub struct RateLimiter {
+    config: EmbeddingRateLimitConfig,
+    buckets: Mutex<Buckets>,
+    /// Held by the caller currently waiting for budget; tokio's mutex queues fairly
+    turn: tokio::sync::Mutex<()>,
+    waiters: AtomicUsize,
+    throttled: AtomicU64,
+}
+
+/// Counts a caller as queued until its `acquire` returns or is dropped.
+struct WaiterGuard<'a>(&'a AtomicUsize);
+
+impl Drop for WaiterGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl RateLimiter {
+    /// A limiter with full buckets.
+    pub fn new(config: EmbeddingRateLimitConfig) -> Self {
+        Self {
+            buckets: Mutex::new(Buckets {
+                requests: config.requests_per_minute as f64,
+                tokens: config.tokens_per_minute as f64,
+                refilled_at: Instant::now(),
+                paused_until: None,
+            }),
+            config,
+            turn: tokio::sync::Mutex::new(()),
+            waiters: AtomicUsize::new(0),
+            throttled: AtomicU64::new(0),
+        }
+    }
+
+    /// A limiter that only honors 429 pauses.
+    pub fn unlimited() -> Self {
+        Self::new(EmbeddingRateLimitConfig {
+            requests_per_minute: 0,
+            tokens_per_minute: 0,
+            ..EmbeddingRateLimitConfig::default()
+        })
+    }
+
+    /// Wait until one request of an estimated `tokens` input tokens fits the budget, then
+    /// take it. A request above a minute's worth of tokens waits for a full bucket.
+    pub async fn acquire(&self, tokens: u32) {
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        let _queued = WaiterGuard(&self.waiters);
+        let _turn = self.turn.lock().await;
+        while let Some(wait) = self.try_take(tokens, Instant::now()) {
+            debug!(
+                "Embedding rate limit reached, waiting {}ms",
+                wait.as_millis()
+            );
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Record a 429 and pause every caller for `retry_after`, capped at
+    /// `max_retry_after_secs`. An earlier pause that ends later is kept.
+    pub fn throttled(&self, retry_after: Duration) {
+        self.throttled.fetch_add(1, Ordering::SeqCst);
+        let until =
+            Instant::now() + retry_after.min(Duration::from_secs(self.config.max_retry_after_secs));
+        let mut buckets = self.lock();
+        buckets.paused_until = Some(buckets.paused_until.map_or(until, |p| p.max(until)));
+    }
+
+    pub fn snapshot(&self) -> RateLimitSnapshot {
+        let now = Instant::now();
+        let mut buckets = self.lock();
+        self.refill(&mut buckets, now);
+        let available = |limit: u32, level: f64| (limit > 0).then(|| level.max(0.0) as u64);
+        RateLimitSnapshot {
+            requests_per_minute: self.config.requests_per_minute,
+            tokens_per_minute: self.config.tokens_per_minute,
+            available_requests: available(self.config.requests_per_minute, buckets.requests),
+            available_tokens: available(self.config.tokens_per_minute, buckets.tokens),
+            queued_waiters: self.waiters.load(Ordering::SeqCst),
+            paused_ms: buckets
+                .paused_until
+                .map_or(0, |p| p.saturating_duration_since(now).as_millis() as u64),
+            throttled_responses: self.throttled.load(Ordering::SeqCst),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Buckets> {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn refill(&self, buckets: &mut Buckets, now: Instant) {
+        let minutes = now
+            .saturating_duration_since(buckets.refilled_at)
+            .as_secs_f64()
+            / 60.0;
+        buckets.refilled_at = now;
+        let rpm = self.config.requests_per_minute as f64;
+        let tpm = self.config.tokens_per_minute as f64;
+        buckets.requests = (buckets.requests + minutes * rpm).min(rpm);
+        buckets.tokens = (buckets.tokens + minutes * tpm).min(tpm);
+    }
+
+    /// Take the budget for one request, or return how long to wait before it fits.
+    fn try_take(&self, tokens: u32, now: Instant) -> Option<Duration> {
+        let mut buckets = self.lock();
+        self.refill(&mut buckets, now);
+        if let Some(until) = buckets.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            buckets.paused_until = None;
+        }
+
+        let rpm = self.config.requests_per_minute as f64;
+        let tpm = self.config.tokens_per_minute as f64;
+        let needed = (tokens as f64).min(tpm);
+        let mut wait_minutes: f64 = 0.0;
+        if rpm > 0.0 && buckets.requests < 1.0 - EPSILON {
+            wait_minutes = wait_minutes.max((1.0 - buckets.requests) / rpm);
+        }
+        if tpm > 0.0 && buckets.tokens < needed - EPSILON {
+            wait_minutes = wait_minutes.max((needed - buckets.tokens) / tpm);
+        }
+        if wait_minutes > 0.0 {
+            return Some(Duration::from_secs_f64(wait_minutes * 60.0));
+        }
+
+        if rpm > 0.0 {
+            buckets.requests -= 1.0;
+        }
+        if tpm > 0.0 {
+            buckets.tokens -= needed;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limited(requests_per_minute: u32, tokens_per_minute: u32) -> Arc<RateLimiter> {
+        Arc::new(RateLimiter::new(EmbeddingRateLimitConfig {
+            requests_per_minute,
+            tokens_per_minute,
+            max_retry_after_secs: 30,
+        }))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_is_paced_to_requests_per_minute() {
+        let limiter = limited(60, 0);
+        let start = Instant::now();
+        for _ in 0..60 {
+            limiter.acquire(1).await;
+        }
+        // The first minute's budget goes out at once; after that one request per second
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert_eq!(limiter.snapshot().available_requests, Some(0));
+        for _ in 0..3 {
+            limiter.acquire(1).await;
+        }
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_secs(3) && elapsed < Duration::from_millis(3100),
+            "{elapsed:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_budget_and_queued_waiters() {
+        let limiter = limited(0, 600);
+        limiter.acquire(500).await;
+
+        // 400 more tokens need 300 refilled at 10/s
+        let start = Instant::now();
+        let waiter = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire(400).await })
+        };
+        tokio::task::yield_now().await;
+        let snapshot = limiter.snapshot();
+        assert_eq!(snapshot.queued_waiters, 1);
+        assert_eq!(snapshot.available_requests, None);
+        waiter.await.unwrap();
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_secs(30) && elapsed < Duration::from_millis(30100),
+            "{elapsed:?}"
+        );
+        assert_eq!(limiter.snapshot().queued_waiters, 0);
+
+        // Oversized requests wait for a full bucket instead of forever
+        let start = Instant::now();
+        limiter.acquire(10_000).await;
+        assert!(start.elapsed() >= Duration::from_secs(59));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttled_pauses_every_caller_with_cap() {
+        let limiter = Arc::new(RateLimiter::unlimited());
+        limiter.throttled(Duration::from_secs(5));
+        limiter.throttled(Duration::from_secs(2));
+        assert_eq!(limiter.snapshot().paused_ms, 5000);
+        assert_eq!(limiter.snapshot().throttled_responses, 2);
+
+        let start = Instant::now();
+        tokio::join!(limiter.acquire(1), limiter.acquire(1));
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+
+        // Retry-After beyond max_retry_after_secs is capped
+        let capped = limited(0, 0);
+        capped.throttled(Duration::from_secs(600));
+        assert_eq!(capped.snapshot().paused_ms, 30_000);
+    }
+
+    #[test]
+    fn test_shared_limiter_is_per_key() {
+        let config = EmbeddingRateLimitConfig::default();
+        let a = shared_limiter("test://embeddings#model-a", &config);
+        let again = shared_limiter("test://embeddings#model-a", &config);
+        let b = shared_limiter("test://embeddings#model-b", &config);
+        assert!(Arc::ptr_eq(&a, &again));
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(estimate_tokens(""), 1);
+        assert_eq!(estimate_tokens(&"x".repeat(400)), 101);
+    }
+}
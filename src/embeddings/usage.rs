@@ -0,0 +1,292 @@
+//! Embedding token accounting
+//!
+//! Embedders report each successful request with [`UsageLedger::record`]: the call and the
+//! API's `usage.total_tokens` (0 when the server sends none) are counted per purpose in the
+//! metrics registry and added to a pending daily rollup keyed by day, provider, model, and
+//! purpose. The server drains the ledger into `embedding_usage` after each tool call
+//! ([`UsageLedger::flush`]); `maintain embedding_usage_report` reads the rollups back with an
+//! estimated cost from `[embedding.cost]`.
+
+use crate::config::EmbeddingCostConfig;
+use crate::server::connection::DbEngine;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use surrealdb::Surreal;
+
+/// What an embedding request was for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbedPurpose {
+    /// A new or edited thought
+    ThoughtCreate,
+    /// KG entities, observations, and edges
+    KgEmbed,
+    /// Re-embedding, backfills, and migration dual-writes
+    Reembed,
+    /// Query text for similarity search
+    SearchQuery,
+    /// Calls made through plain [`super::Embedder::embed`] (probes, diagnostics)
+    Other,
+}
+
+impl EmbedPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ThoughtCreate => "thought_create",
+            Self::KgEmbed => "kg_embed",
+            Self::Reembed => "reembed",
+            Self::SearchQuery => "search_query",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// One `embedding_usage` row.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UsageKey {
+    /// UTC date, `YYYY-MM-DD`
+    pub day: String,
+    pub provider: String,
+    pub model: String,
+    pub purpose: EmbedPurpose,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageDelta {
+    pub calls: u64,
+    pub tokens: u64,
+}
+
+/// Adds a pending delta to the row for its key, creating the row on first use.
+const UPSERT_SQL: &str = "UPSERT type::record('embedding_usage', [$day, $provider, $model, $purpose]) SET \
+     day = $day, provider = $provider, model = $model, purpose = $purpose, \
+     calls = (calls ?? 0) + $calls, tokens = (tokens ?? 0) + $tokens, \
+     updated_at = time::now() RETURN NONE";
+
+/// Rollup rows for `maintain embedding_usage_report`; binds optional `$since` / `$until` days.
+pub const REPORT_SQL: &str = "SELECT day, provider, model, purpose, calls, tokens FROM embedding_usage \
+     WHERE ($since = NONE OR day >= $since) AND ($until = NONE OR day <= $until)";
+
+/// Usage not yet written to `embedding_usage`.
+#[derive(Debug, Default)]
+pub struct UsageLedger {
+    pending: Mutex<BTreeMap<UsageKey, UsageDelta>>,
+}
+
+static LEDGER: Lazy<Arc<UsageLedger>> = Lazy::new(Arc::default);
+
+/// The process-wide ledger embedders record into by default.
+pub fn ledger() -> Arc<UsageLedger> {
+    LEDGER.clone()
+}
+
+impl UsageLedger {
+    /// Count one successful request of `tokens` for today (UTC).
+    pub fn record(&self, provider: &str, model: &str, purpose: EmbedPurpose, tokens: u64) {
+        crate::metrics::global().record_embed_usage(purpose.as_str(), tokens);
+        let key = UsageKey {
+            day: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            purpose,
+        };
+        self.add(key, UsageDelta { calls: 1, tokens });
+    }
+
+    fn add(&self, key: UsageKey, delta: UsageDelta) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = pending.entry(key).or_default();
+        entry.calls += delta.calls;
+        entry.tokens += delta.tokens;
+    }
+
+    /// Pending deltas, without draining them.
+    pub fn pending(&self) -> Vec<(UsageKey, UsageDelta)> {
+        let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.iter().map(|(k, d)| (k.clone(), *d)).collect()
+    }
+
+    /// Write pending deltas to `embedding_usage`, returning how many rows were updated.
+    /// Deltas that fail to write are kept for the next flush.
+    pub async fn flush(&self, db: &Surreal<DbEngine>) -> surrealdb::Result<usize> {
+        let drained = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut rows = drained.into_iter();
+        let mut written = 0;
+        while let Some((key, delta)) = rows.next() {
+            let result = db
+                .query(UPSERT_SQL)
+                .bind(("day", key.day.clone()))
+                .bind(("provider", key.provider.clone()))
+                .bind(("model", key.model.clone()))
+                .bind(("purpose", key.purpose.as_str()))
+                .bind(("calls", delta.calls as i64))
+                .bind(("tokens", delta.tokens as i64))
+                .await
+                .and_then(|r| r.check());
+            if let Err(e) = result {
+                self.add(key, delta);
+                rows.for_each(|(k, d)| self.add(k, d));
+                return Err(e);
+            }
+            written += 1;
+        }
+        Ok(written)
+    }
+}
+
+/// Flush the process-wide ledger over a new connection to the configured database, for
+/// binaries that embed through the HTTP SQL maintenance functions.
+pub async fn flush_to_configured_db(config: &crate::config::Config) -> anyhow::Result<usize> {
+    use crate::server::connection::{DbConnection, DbSettings};
+    if ledger().pending().is_empty() {
+        return Ok(0);
+    }
+    let db = DbConnection::connect(DbSettings::from_config(config), 1).await?;
+    Ok(ledger().flush(&db.client()).await?)
+}
+
+/// A rollup row as read back by [`REPORT_SQL`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct UsageRow {
+    pub day: String,
+    pub provider: String,
+    pub model: String,
+    pub purpose: String,
+    pub calls: u64,
+    pub tokens: u64,
+}
+
+#[derive(Default)]
+struct Totals {
+    calls: u64,
+    tokens: u64,
+    cost: f64,
+}
+
+impl Totals {
+    fn add(&mut self, row: &UsageRow, cost: f64) {
+        self.calls += row.calls;
+        self.tokens += row.tokens;
+        self.cost += cost;
+    }
+
+    fn json(&self) -> Value {
+        json!({
+            "calls": self.calls,
+            "tokens": self.tokens,
+            "estimated_cost_usd": (self.cost * 1e6).round() / 1e6,
+        })
+    }
+}
+
+/// Totals overall and per purpose, day, and provider/model, with estimated costs.
+pub fn summarize(rows: &[UsageRow], cost: &EmbeddingCostConfig) -> Value {
+    let mut total = Totals::default();
+    let mut by_purpose: BTreeMap<&str, Totals> = BTreeMap::new();
+    let mut by_day: BTreeMap<&str, Totals> = BTreeMap::new();
+    let mut by_model: BTreeMap<(&str, &str), Totals> = BTreeMap::new();
+    for row in rows {
+        let usd = row.tokens as f64 / 1e6 * cost.usd_per_million_tokens(&row.provider);
+        total.add(row, usd);
+        by_purpose.entry(&row.purpose).or_default().add(row, usd);
+        by_day.entry(&row.day).or_default().add(row, usd);
+        by_model
+            .entry((&row.provider, &row.model))
+            .or_default()
+            .add(row, usd);
+    }
+    let keyed = |key: &str, value: &str, t: &Totals| {
+        let mut v = t.json();
+        v[key] = json!(value);
+        v
+    };
+    json!({
+        "total": total.json(),
+        "by_purpose": by_purpose.iter().map(|(p, t)| keyed("purpose", p, t)).collect::<Vec<_>>(),
+        "by_day": by_day.iter().map(|(d, t)| keyed("day", d, t)).collect::<Vec<_>>(),
+        "by_model": by_model
+            .iter()
+            .map(|((p, m), t)| {
+                let mut v = keyed("provider", p, t);
+                v["model"] = json!(m);
+                v
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(day: &str, provider: &str, purpose: &str, calls: u64, tokens: u64) -> UsageRow {
+        UsageRow {
+            day: day.into(),
+            provider: provider.into(),
+            model: "m".into(),
+            purpose: purpose.into(),
+            calls,
+            tokens,
+        }
+    }
+
+    #[test]
+    fn test_summarize_prices_tokens_per_provider() {
+        let cost = EmbeddingCostConfig {
+            openai_usd_per_million_tokens: 0.02,
+            compat_usd_per_million_tokens: 0.0,
+        };
+        let rows = [
+            row("2026-10-01", "openai", "thought_create", 10, 500_000),
+            row("2026-10-02", "openai", "reembed", 4, 1_500_000),
+            row("2026-10-02", "openai_compat", "reembed", 7, 0),
+        ];
+        let report = summarize(&rows, &cost);
+        assert_eq!(report["total"]["calls"], 21);
+        assert_eq!(report["total"]["tokens"], 2_000_000);
+        assert_eq!(report["total"]["estimated_cost_usd"], 0.04);
+        let reembed = &report["by_purpose"][0];
+        assert_eq!(reembed["purpose"], "reembed");
+        assert_eq!(reembed["calls"], 11);
+        assert_eq!(reembed["estimated_cost_usd"], 0.03);
+        assert_eq!(report["by_day"][1]["day"], "2026-10-02");
+        // Local servers still count calls at zero cost
+        let compat = &report["by_model"][1];
+        assert_eq!(compat["provider"], "openai_compat");
+        assert_eq!(compat["calls"], 7);
+        assert_eq!(compat["estimated_cost_usd"], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_flush_accumulates_daily_rollup_rows() {
+        let server = crate::test_support::mem_server().await;
+        let db = server.db.client();
+        let ledger = UsageLedger::default();
+        ledger.record("openai", "m", EmbedPurpose::ThoughtCreate, 12);
+        ledger.record("openai", "m", EmbedPurpose::ThoughtCreate, 8);
+        ledger.record("openai", "m", EmbedPurpose::SearchQuery, 3);
+        assert_eq!(ledger.flush(&db).await.unwrap(), 2);
+        assert!(ledger.pending().is_empty());
+        ledger.record("openai", "m", EmbedPurpose::ThoughtCreate, 5);
+        assert_eq!(ledger.flush(&db).await.unwrap(), 1);
+
+        let rows: Vec<UsageRow> = db
+            .query(REPORT_SQL)
+            .bind(("since", None::<String>))
+            .bind(("until", None::<String>))
+            .await
+            .unwrap()
+            .take::<Vec<Value>>(0)
+            .unwrap()
+            .into_iter()
+            .map(|v| serde_json::from_value(v).unwrap())
+            .collect();
+        let thought = rows.iter().find(|r| r.purpose == "thought_create").unwrap();
+        assert_eq!((thought.calls, thought.tokens), (3, 25));
+        let search = rows.iter().find(|r| r.purpose == "search_query").unwrap();
+        assert_eq!((search.calls, search.tokens), (1, 3));
+    }
+}
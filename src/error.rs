@@ -1,6 +1,12 @@
 //! Domain-specific error types for surreal-mind
+//!
+//! Every handler failure is a [`SurrealMindError`]. The variant decides the MCP error code
+//! (see the `From<SurrealMindError> for rmcp::ErrorData` impl) and the machine-readable
+//! `data` sent with it: `class` (the variant, see [`SurrealMindError::class`]), `retryable`,
+//! `details`, and variant fields such as the offending `field`, the missing `resource`/`id`,
+//! the unavailable `provider`, or a failed task's `partial` output.
 
-use serde_json::json;
+use serde_json::{Value, json};
 use thiserror::Error;
 
 /// Main error type for the surreal-mind MCP server
@@ -12,6 +18,10 @@ pub enum SurrealMindError {
     #[error("Database error: {message}")]
     Database { message: String },
 
+    /// The database connection is gone; the same call may succeed after a reconnect
+    #[error("Database unavailable: {message}")]
+    DatabaseUnavailable { message: String },
+
     #[error("Embedding provider error: {message}")]
     Embedding { message: String },
 
@@ -39,14 +49,47 @@ pub enum SurrealMindError {
     #[error("Feature disabled: {message}")]
     FeatureDisabled { message: String },
 
+    /// Kept for existing callers; reported in the `provider_unavailable` class. New code
+    /// uses `ProviderUnavailable`.
     #[error("Embedder unavailable: {message}")]
     EmbedderUnavailable { message: String },
 
+    /// An embedder or agent provider (Gemini, ...) could not serve the call
+    #[error("Provider unavailable: {provider}: {message}")]
+    ProviderUnavailable {
+        provider: String,
+        message: String,
+        retryable: bool,
+    },
+
+    /// Bad arguments; `field` names the offending parameter when known
     #[error("Invalid parameters: {message}")]
-    InvalidParams { message: String },
+    InvalidParams {
+        message: String,
+        field: Option<String>,
+    },
+
+    #[error("{resource} not found: {id}")]
+    NotFound { resource: String, id: String },
+
+    /// The request is valid but the target's current state refuses it; `data` carries the
+    /// blocking state
+    #[error("Conflict: {message}")]
+    Conflict {
+        message: String,
+        data: Option<Value>,
+    },
+
+    #[error("Cancelled: {operation}")]
+    Cancelled { operation: String },
 
+    /// A tool or task ran and failed; `partial` carries whatever it reported before failing
     #[error("Tool execution failed: {tool} error: {error}")]
-    ToolExecutionFailed { tool: String, error: String },
+    ToolExecutionFailed {
+        tool: String,
+        error: String,
+        partial: Option<Value>,
+    },
 }
 
 impl From<anyhow::Error> for SurrealMindError {
@@ -67,8 +110,14 @@ impl From<serde_json::Error> for SurrealMindError {
 
 impl From<surrealdb::Error> for SurrealMindError {
     fn from(err: surrealdb::Error) -> Self {
-        SurrealMindError::Database {
-            message: err.to_string(),
+        if crate::server::connection::is_connection_error(&err) {
+            SurrealMindError::DatabaseUnavailable {
+                message: err.to_string(),
+            }
+        } else {
+            SurrealMindError::Database {
+                message: err.to_string(),
+            }
         }
     }
 }
@@ -97,92 +146,372 @@ impl From<chrono::ParseError> for SurrealMindError {
     }
 }
 
+impl SurrealMindError {
+    /// Invalid parameter `field`
+    pub fn invalid_field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        SurrealMindError::InvalidParams {
+            message: message.into(),
+            field: Some(field.into()),
+        }
+    }
+
+    pub fn not_found(resource: impl Into<String>, id: impl Into<String>) -> Self {
+        SurrealMindError::NotFound {
+            resource: resource.into(),
+            id: id.into(),
+        }
+    }
+
+    /// Stable snake_case name of the error variant, reported as `data.class` and in `tool_audit`
+    pub fn class(&self) -> &'static str {
+        match self {
+            SurrealMindError::Config { .. } => "config",
+            SurrealMindError::Database { .. } => "database",
+            SurrealMindError::DatabaseUnavailable { .. } => "database_unavailable",
+            SurrealMindError::Embedding { .. } => "embedding",
+            SurrealMindError::Mcp { .. } => "mcp",
+            SurrealMindError::Cognitive { .. } => "cognitive",
+            SurrealMindError::KnowledgeGraph { .. } => "knowledge_graph",
+            SurrealMindError::Serialization { .. } => "serialization",
+            SurrealMindError::Timeout { .. } => "timeout",
+            SurrealMindError::Validation { .. } => "validation",
+            SurrealMindError::Internal { .. } => "internal",
+            SurrealMindError::FeatureDisabled { .. } => "feature_disabled",
+            SurrealMindError::EmbedderUnavailable { .. }
+            | SurrealMindError::ProviderUnavailable { .. } => "provider_unavailable",
+            SurrealMindError::InvalidParams { .. } => "invalid_params",
+            SurrealMindError::NotFound { .. } => "not_found",
+            SurrealMindError::Conflict { .. } => "conflict",
+            SurrealMindError::Cancelled { .. } => "cancelled",
+            SurrealMindError::ToolExecutionFailed { .. } => "tool_execution_failed",
+        }
+    }
+
+    /// Whether the same call may succeed if simply repeated later
+    pub fn retryable(&self) -> bool {
+        match self {
+            SurrealMindError::DatabaseUnavailable { .. }
+            | SurrealMindError::Timeout { .. }
+            | SurrealMindError::EmbedderUnavailable { .. }
+            | SurrealMindError::Cancelled { .. } => true,
+            SurrealMindError::ProviderUnavailable { retryable, .. } => *retryable,
+            _ => false,
+        }
+    }
+}
+
 /// Convert SurrealMindError to MCP error
 impl From<SurrealMindError> for rmcp::ErrorData {
     fn from(err: SurrealMindError) -> Self {
+        use rmcp::model::ErrorCode;
+
+        let class = err.class();
+        let retryable = err.retryable();
+        let mut extra = serde_json::Map::new();
         let (code, label, details) = match err {
-            SurrealMindError::Config { message } => (
-                rmcp::model::ErrorCode::INVALID_PARAMS,
-                "Configuration error",
-                message,
-            ),
-            SurrealMindError::Database { message } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
-                "Database error",
-                message,
-            ),
-            SurrealMindError::Embedding { message } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
-                "Embedding error",
-                message,
-            ),
-            SurrealMindError::Mcp { message } => (
-                rmcp::model::ErrorCode::INVALID_PARAMS,
-                "MCP protocol error",
-                message,
-            ),
+            SurrealMindError::Config { message } => {
+                (ErrorCode::INTERNAL_ERROR, "Configuration error", message)
+            }
+            SurrealMindError::Database { message } => {
+                (ErrorCode::INTERNAL_ERROR, "Database error", message)
+            }
+            SurrealMindError::DatabaseUnavailable { message } => {
+                (ErrorCode::INTERNAL_ERROR, "Database unavailable", message)
+            }
+            SurrealMindError::Embedding { message } => {
+                (ErrorCode::INTERNAL_ERROR, "Embedding error", message)
+            }
+            SurrealMindError::Mcp { message } => {
+                (ErrorCode::INVALID_PARAMS, "MCP protocol error", message)
+            }
             SurrealMindError::Cognitive { message } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
+                ErrorCode::INTERNAL_ERROR,
                 "Cognitive framework error",
                 message,
             ),
-            SurrealMindError::KnowledgeGraph { message } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
-                "Knowledge graph error",
-                message,
-            ),
-            SurrealMindError::Serialization { message } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
-                "Serialization error",
-                message,
-            ),
+            SurrealMindError::KnowledgeGraph { message } => {
+                (ErrorCode::INTERNAL_ERROR, "Knowledge graph error", message)
+            }
+            SurrealMindError::Serialization { message } => {
+                (ErrorCode::INTERNAL_ERROR, "Serialization error", message)
+            }
             SurrealMindError::Timeout {
                 operation,
                 timeout_ms,
-            } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
-                "Operation timeout",
-                format!("{operation} timed out after {timeout_ms}ms"),
-            ),
-            SurrealMindError::Validation { message } => (
-                rmcp::model::ErrorCode::INVALID_PARAMS,
-                "Validation error",
-                message,
-            ),
-            SurrealMindError::Internal { message } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
-                "Internal error",
-                message,
-            ),
-            SurrealMindError::FeatureDisabled { message } => (
-                rmcp::model::ErrorCode::INVALID_PARAMS,
-                "Feature disabled",
-                message,
-            ),
-            SurrealMindError::EmbedderUnavailable { message } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
-                "Embedder unavailable",
-                message,
-            ),
-            SurrealMindError::InvalidParams { message } => (
-                rmcp::model::ErrorCode::INVALID_PARAMS,
-                "Invalid parameters",
-                message,
-            ),
-            SurrealMindError::ToolExecutionFailed { tool, error } => (
-                rmcp::model::ErrorCode::INTERNAL_ERROR,
-                "Tool execution failed",
-                format!("Tool '{}' failed: {}", tool, error),
-            ),
+            } => {
+                extra.insert("operation".into(), json!(operation));
+                extra.insert("timeout_ms".into(), json!(timeout_ms));
+                (
+                    ErrorCode::INTERNAL_ERROR,
+                    "Operation timeout",
+                    format!("{operation} timed out after {timeout_ms}ms"),
+                )
+            }
+            SurrealMindError::Validation { message } => {
+                (ErrorCode::INVALID_PARAMS, "Validation error", message)
+            }
+            SurrealMindError::Internal { message } => {
+                (ErrorCode::INTERNAL_ERROR, "Internal error", message)
+            }
+            SurrealMindError::FeatureDisabled { message } => {
+                (ErrorCode::INVALID_REQUEST, "Feature disabled", message)
+            }
+            SurrealMindError::EmbedderUnavailable { message } => {
+                extra.insert("provider".into(), json!("embedder"));
+                (ErrorCode::INTERNAL_ERROR, "Embedder unavailable", message)
+            }
+            SurrealMindError::ProviderUnavailable {
+                provider, message, ..
+            } => {
+                extra.insert("provider".into(), json!(provider));
+                (
+                    ErrorCode::INTERNAL_ERROR,
+                    "Provider unavailable",
+                    format!("{provider}: {message}"),
+                )
+            }
+            SurrealMindError::InvalidParams { message, field } => {
+                if let Some(field) = field {
+                    extra.insert("field".into(), json!(field));
+                }
+                (ErrorCode::INVALID_PARAMS, "Invalid parameters", message)
+            }
+            SurrealMindError::NotFound { resource, id } => {
+                let details = format!("{resource} '{id}' not found");
+                extra.insert("resource".into(), json!(resource));
+                extra.insert("id".into(), json!(id));
+                (ErrorCode::RESOURCE_NOT_FOUND, "Not found", details)
+            }
+            SurrealMindError::Conflict { message, data } => {
+                if let Some(Value::Object(data)) = data {
+                    extra.extend(data);
+                }
+                (ErrorCode::INVALID_REQUEST, "Conflict", message)
+            }
+            SurrealMindError::Cancelled { operation } => {
+                extra.insert("operation".into(), json!(operation));
+                (
+                    ErrorCode::INTERNAL_ERROR,
+                    "Cancelled",
+                    format!("{operation} was cancelled by the client"),
+                )
+            }
+            SurrealMindError::ToolExecutionFailed {
+                tool,
+                error,
+                partial,
+            } => {
+                extra.insert("tool".into(), json!(tool));
+                if let Some(partial) = partial {
+                    extra.insert("partial".into(), partial);
+                }
+                (
+                    ErrorCode::INTERNAL_ERROR,
+                    "Tool execution failed",
+                    format!("Tool '{}' failed: {}", tool, error),
+                )
+            }
         };
 
+        let mut data = json!({ "details": details, "class": class, "retryable": retryable });
+        if let Value::Object(map) = &mut data {
+            map.extend(extra);
+        }
         rmcp::ErrorData {
             code,
             message: format!("{label}: {details}").into(),
-            data: Some(json!({ "details": details })),
+            data: Some(data),
         }
     }
 }
 
 /// Result type alias for SurrealMind operations
 pub type Result<T> = std::result::Result<T, SurrealMindError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::ErrorCode;
+
+    #[test]
+    fn test_every_variant_maps_to_code_class_and_retryable() {
+        let msg = || "m".to_string();
+        let cases: Vec<(SurrealMindError, ErrorCode, &str, bool)> = vec![
+            (
+                SurrealMindError::Config { message: msg() },
+                ErrorCode::INTERNAL_ERROR,
+                "config",
+                false,
+            ),
+            (
+                SurrealMindError::Database { message: msg() },
+                ErrorCode::INTERNAL_ERROR,
+                "database",
+                false,
+            ),
+            (
+                SurrealMindError::DatabaseUnavailable { message: msg() },
+                ErrorCode::INTERNAL_ERROR,
+                "database_unavailable",
+                true,
+            ),
+            (
+                SurrealMindError::Embedding { message: msg() },
+                ErrorCode::INTERNAL_ERROR,
+                "embedding",
+                false,
+            ),
+            (
+                SurrealMindError::Mcp { message: msg() },
+                ErrorCode::INVALID_PARAMS,
+                "mcp",
+                false,
+            ),
+            (
+                SurrealMindError::Cognitive { message: msg() },
+                ErrorCode::INTERNAL_ERROR,
+                "cognitive",
+                false,
+            ),
+            (
+                SurrealMindError::KnowledgeGraph { message: msg() },
+                ErrorCode::INTERNAL_ERROR,
+                "knowledge_graph",
+                false,
+            ),
+            (
+                SurrealMindError::Serialization { message: msg() },
+                ErrorCode::INTERNAL_ERROR,
+                "serialization",
+                false,
+            ),
+            (
+                SurrealMindError::Timeout {
+                    operation: "embed".into(),
+                    timeout_ms: 5,
+                },
+                ErrorCode::INTERNAL_ERROR,
+                "timeout",
+                true,
+            ),
+            (
+                SurrealMindError::Validation { message: msg() },
+                ErrorCode::INVALID_PARAMS,
+                "validation",
+                false,
+            ),
+            (
+                SurrealMindError::Internal { message: msg() },
+                ErrorCode::INTERNAL_ERROR,
+                "internal",
+                false,
+            ),
+            (
+                SurrealMindError::FeatureDisabled { message: msg() },
+                ErrorCode::INVALID_REQUEST,
+                "feature_disabled",
+                false,
+            ),
+            (
+                SurrealMindError::ProviderUnavailable {
+                    provider: "gemini".into(),
+                    message: msg(),
+                    retryable: true,
+                },
+                ErrorCode::INTERNAL_ERROR,
+                "provider_unavailable",
+                true,
+            ),
+            (
+                SurrealMindError::InvalidParams {
+                    message: msg(),
+                    field: None,
+                },
+                ErrorCode::INVALID_PARAMS,
+                "invalid_params",
+                false,
+            ),
+            (
+                SurrealMindError::not_found("thought", "t1"),
+                ErrorCode::RESOURCE_NOT_FOUND,
+                "not_found",
+                false,
+            ),
+            (
+                SurrealMindError::Conflict {
+                    message: msg(),
+                    data: None,
+                },
+                ErrorCode::INVALID_REQUEST,
+                "conflict",
+                false,
+            ),
+            (
+                SurrealMindError::Cancelled {
+                    operation: "maintain".into(),
+                },
+                ErrorCode::INTERNAL_ERROR,
+                "cancelled",
+                true,
+            ),
+            (
+                SurrealMindError::ToolExecutionFailed {
+                    tool: "kg_populate".into(),
+                    error: msg(),
+                    partial: None,
+                },
+                ErrorCode::INTERNAL_ERROR,
+                "tool_execution_failed",
+                false,
+            ),
+        ];
+        for (err, code, class, retryable) in cases {
+            let mcp = rmcp::ErrorData::from(err);
+            let data = mcp.data.unwrap();
+            assert_eq!(mcp.code, code, "{class}");
+            assert_eq!(data["class"], class);
+            assert_eq!(data["retryable"], retryable, "{class}");
+        }
+    }
+
+    #[test]
+    fn test_variant_details_are_machine_readable() {
+        let data = |err: SurrealMindError| rmcp::ErrorData::from(err).data.unwrap();
+
+        let field = data(SurrealMindError::invalid_field("limit", "must be positive"));
+        assert_eq!(field["field"], "limit");
+
+        let missing = data(SurrealMindError::not_found("thought", "t1"));
+        assert_eq!(
+            (missing["resource"].as_str(), missing["id"].as_str()),
+            (Some("thought"), Some("t1"))
+        );
+
+        let conflict = data(SurrealMindError::Conflict {
+            message: "still referenced".into(),
+            data: Some(json!({"blocking_edges": ["e1"]})),
+        });
+        assert_eq!(conflict["blocking_edges"], json!(["e1"]));
+
+        let provider = data(SurrealMindError::ProviderUnavailable {
+            provider: "gemini".into(),
+            message: "cli not found".into(),
+            retryable: false,
+        });
+        assert_eq!(provider["provider"], "gemini");
+        assert_eq!(provider["retryable"], false);
+
+        let embedder = data(SurrealMindError::EmbedderUnavailable {
+            message: "connection refused".into(),
+        });
+        assert_eq!(embedder["class"], "provider_unavailable");
+        assert_eq!(embedder["provider"], "embedder");
+
+        let failed = data(SurrealMindError::ToolExecutionFailed {
+            tool: "kg_populate".into(),
+            error: "exit status 1".into(),
+            partial: Some(json!({"stdout": "3 batches"})),
+        });
+        assert_eq!(failed["partial"]["stdout"], "3 batches");
+    }
+}
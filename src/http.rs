@@ -2,24 +2,38 @@
 //!
 //! Provides Axum-based HTTP server with bearer authentication.
 //! Implements Axum-based HTTP server with bearer authentication and MCP over
-//! Streamable HTTP. Health, info, and metrics are plain JSON.
+//! Streamable HTTP. Health and info are plain JSON; metrics are JSON, or Prometheus text
+//! with `SURR_METRICS_ENABLED`.
 
 use axum::{
     Router,
     body::Body,
     extract::State,
-    http::{HeaderMap, StatusCode, header},
+    http::{HeaderMap, Method, StatusCode, header},
     middleware,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::get,
 };
+use futures_util::StreamExt;
+use rmcp::ServerHandler;
+use rmcp::transport::common::http_header::HEADER_SESSION_ID;
 use rmcp::transport::streamable_http_server::{
+    SessionManager,
     session::local::LocalSessionManager,
     tower::{StreamableHttpServerConfig, StreamableHttpService},
 };
 use serde_json::json;
-use std::{cmp::Ordering, sync::Arc, time::Duration};
-use surreal_mind::{config::Config, error::Result, server::SurrealMindServer};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+use surreal_mind::{
+    config::{Config, RuntimeConfig},
+    error::Result,
+    server::SurrealMindServer,
+};
 use tokio::sync::Mutex;
 use tower_http::cors::{Any, CorsLayer};
 
@@ -60,6 +74,183 @@ impl HttpMetrics {
     }
 }
 
+/// Last request time of each streamable MCP session, for idle expiry
+#[derive(Debug, Default)]
+pub struct SessionActivity {
+    last_seen: HashMap<String, Instant>,
+    /// Response bodies still streaming per session, alive while their token is held
+    streams: HashMap<String, Vec<Weak<()>>>,
+}
+
+impl SessionActivity {
+    /// Record a request on `id`. Returns true the first time the session is seen.
+    pub fn touch(&mut self, id: &str, now: Instant) -> bool {
+        self.last_seen.insert(id.to_string(), now).is_none()
+    }
+
+    /// Register a response body streaming on `id`. The session counts as active until the
+    /// returned token is dropped.
+    pub fn open_stream(&mut self, id: &str) -> Arc<()> {
+        let token = Arc::new(());
+        let streams = self.streams.entry(id.to_string()).or_default();
+        streams.retain(|s| s.strong_count() > 0);
+        streams.push(Arc::downgrade(&token));
+        token
+    }
+
+    pub fn forget(&mut self, id: &str) {
+        self.last_seen.remove(id);
+        self.streams.remove(id);
+    }
+
+    /// Remove and return the sessions with no request for at least `ttl`. A session with
+    /// an open stream is seen at `now`, so its TTL restarts when the last stream closes.
+    pub fn take_expired(&mut self, now: Instant, ttl: Duration) -> Vec<String> {
+        self.streams.retain(|id, streams| {
+            streams.retain(|s| s.strong_count() > 0);
+            if streams.is_empty() {
+                return false;
+            }
+            if let Some(seen) = self.last_seen.get_mut(id) {
+                *seen = now;
+            }
+            true
+        });
+        let expired: Vec<String> = self
+            .last_seen
+            .iter()
+            .filter(|(_, seen)| now.saturating_duration_since(**seen) >= ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &expired {
+            self.last_seen.remove(id);
+        }
+        expired
+    }
+}
+
+/// MCP endpoint for `runtime.http_mode`. "streamable" keeps one server instance per
+/// `Mcp-Session-Id`, serves resumable GET event streams, and closes sessions idle for
+/// `http_session_ttl_sec`; "basic" answers each POST statelessly.
+pub fn mcp_router<S, T>(
+    server: S,
+    runtime: &RuntimeConfig,
+    session_mgr: Arc<LocalSessionManager>,
+    metrics: Arc<Mutex<HttpMetrics>>,
+) -> Router<T>
+where
+    S: ServerHandler + Clone + Send + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    // RuntimeConfig::validate admits only "streamable" and "basic"
+    let stateful_mode = runtime.http_mode != "basic";
+    let service: StreamableHttpService<S, LocalSessionManager> = StreamableHttpService::new(
+        move || Ok(server.clone()),
+        session_mgr.clone(),
+        StreamableHttpServerConfig {
+            stateful_mode,
+            sse_keep_alive: Some(Duration::from_secs(runtime.http_sse_keepalive_sec)),
+            ..Default::default()
+        },
+    );
+    let router = Router::new().nest_service(runtime.http_path.as_str(), service);
+    if !stateful_mode {
+        return router;
+    }
+
+    let activity = Arc::new(Mutex::new(SessionActivity::default()));
+    if runtime.http_session_ttl_sec > 0 {
+        spawn_session_reaper(
+            session_mgr,
+            activity.clone(),
+            Duration::from_secs(runtime.http_session_ttl_sec),
+        );
+    }
+    router.layer(middleware::from_fn_with_state(
+        (activity, metrics),
+        track_session_activity,
+    ))
+}
+
+/// Session activity and the metrics that count new sessions
+type SessionTracking = (Arc<Mutex<SessionActivity>>, Arc<Mutex<HttpMetrics>>);
+
+/// Note the session each MCP request belongs to, counting sessions created by `initialize`.
+/// Event-stream responses (GET streams, and POSTs answered over SSE) keep their session
+/// active until the body ends.
+async fn track_session_activity(
+    State((activity, metrics)): State<SessionTracking>,
+    req: axum::http::Request<Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    let sent = req
+        .headers()
+        .get(HEADER_SESSION_ID)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let closing = req.method() == Method::DELETE;
+    let resp = next.run(req).await;
+    let id = sent.or_else(|| {
+        resp.headers()
+            .get(HEADER_SESSION_ID)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    });
+    let Some(id) = id else {
+        return resp;
+    };
+    if resp.status() == StatusCode::UNAUTHORIZED {
+        return resp;
+    }
+    let mut activity = activity.lock().await;
+    if closing {
+        activity.forget(&id);
+        return resp;
+    }
+    if activity.touch(&id, Instant::now()) {
+        let mut m = metrics.lock().await;
+        m.http_total_sessions = m.http_total_sessions.saturating_add(1);
+    }
+    let streaming = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    if !streaming {
+        return resp;
+    }
+    let token = activity.open_stream(&id);
+    let (parts, body) = resp.into_parts();
+    let body = Body::from_stream(body.into_data_stream().map(move |chunk| {
+        // The stream owns the token, so dropping the body releases the session
+        let _ = &token;
+        chunk
+    }));
+    Response::from_parts(parts, body)
+}
+
+/// Close sessions idle for `ttl`, checking a few times per TTL (at most once a minute apart).
+fn spawn_session_reaper(
+    session_mgr: Arc<LocalSessionManager>,
+    activity: Arc<Mutex<SessionActivity>>,
+    ttl: Duration,
+) {
+    let sweep = (ttl / 4).clamp(Duration::from_millis(100), Duration::from_secs(60));
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(sweep);
+        loop {
+            tick.tick().await;
+            let expired = activity.lock().await.take_expired(Instant::now(), ttl);
+            for id in expired {
+                match session_mgr.close_session(&id.as_str().into()).await {
+                    Ok(()) => tracing::debug!("Closed idle MCP session {}", id),
+                    Err(e) => tracing::warn!("Failed to close idle MCP session {}: {}", id, e),
+                }
+            }
+        }
+    });
+}
+
 // require_bearer implemented as a from_fn_with_state layer below
 
 /// Health check endpoint
@@ -139,8 +330,12 @@ pub async fn info_handler(State(state): State<HttpState>) -> impl IntoResponse {
     )
 }
 
-/// Metrics endpoint
-pub async fn metrics_handler(State(state): State<HttpState>) -> impl IntoResponse {
+/// Metrics endpoint: Prometheus text from `surreal_mind::metrics` when
+/// `SURR_METRICS_ENABLED` is set, otherwise the JSON HTTP summary
+pub async fn metrics_handler(State(state): State<HttpState>) -> Response {
+    if state.config.runtime.metrics_enabled {
+        return prometheus_response();
+    }
     let metrics = state.metrics.lock().await.clone();
     // Read active sessions from session manager
     let active_sessions = state.session_mgr.sessions.read().await.len();
@@ -183,6 +378,20 @@ pub async fn metrics_handler(State(state): State<HttpState>) -> impl IntoRespons
         })
         .to_string(),
     )
+        .into_response()
+}
+
+/// The process-wide registry in the Prometheus text exposition format.
+fn prometheus_response() -> Response {
+    (
+        StatusCode::OK,
+        [(
+            header::CONTENT_TYPE,
+            surreal_mind::metrics::PROMETHEUS_CONTENT_TYPE,
+        )],
+        surreal_mind::metrics::global().render_prometheus(),
+    )
+        .into_response()
 }
 
 /// DB health endpoint (optional, gated by SURR_DB_STATS=1)
@@ -228,18 +437,13 @@ pub async fn start_http_server(server: SurrealMindServer) -> Result<()> {
         db_ping_cache: Arc::new(Mutex::new(None)),
     };
 
-    // Build MCP streamable HTTP service mounted at configured path
+    // Build MCP HTTP service mounted at configured path
     let path = server.config.runtime.http_path.clone();
-    let keepalive = Duration::from_secs(server.config.runtime.http_sse_keepalive_sec);
-    let server_factory = server.clone();
-    let mcp_service: StreamableHttpService<SurrealMindServer, _> = StreamableHttpService::new(
-        move || Ok(server_factory.clone()),
-        session_mgr.clone(),
-        StreamableHttpServerConfig {
-            stateful_mode: true,
-            sse_keep_alive: Some(keepalive),
-            ..Default::default()
-        },
+    let mcp_routes = mcp_router(
+        server.clone(),
+        &server.config.runtime,
+        session_mgr,
+        state.metrics.clone(),
     );
 
     // Authenticated routes (health, info, metrics, MCP) with auth + metrics layers
@@ -248,7 +452,7 @@ pub async fn start_http_server(server: SurrealMindServer) -> Result<()> {
         .route("/info", get(info_handler))
         .route("/metrics", get(metrics_handler))
         .route("/db_health", get(db_health_handler))
-        .nest_service(path.as_str(), mcp_service)
+        .merge(mcp_routes)
         .layer(middleware::from_fn_with_state(
             (state.metrics.clone(), path.clone()),
             |State((metrics, base)): State<(Arc<Mutex<HttpMetrics>>, String)>,
@@ -405,9 +609,10 @@ pub async fn start_http_server(server: SurrealMindServer) -> Result<()> {
         .map_err(|e| anyhow::anyhow!("Failed to bind HTTP listener: {}", e))?;
 
     tracing::info!(
-        "Starting HTTP server on {} (MCP at {})",
+        "Starting HTTP server on {} (MCP at {}, {} mode)",
         server.config.runtime.http_bind,
-        server.config.runtime.http_path
+        server.config.runtime.http_path,
+        server.config.runtime.http_mode
     );
 
     axum::serve(listener, app)
@@ -530,3 +735,352 @@ async fn get_db_counts_params(
     };
     (thoughts_count, recalls_count)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::{
+        ErrorData as McpError,
+        model::{
+            CallToolRequestParams, CallToolResult, Content, ListToolsResult,
+            PaginatedRequestParams, ServerCapabilities, ServerInfo, Tool,
+        },
+        service::{RequestContext, RoleServer},
+    };
+    use serde_json::Value;
+
+    /// Minimal MCP server with a single `echo` tool.
+    #[derive(Clone)]
+    struct EchoServer;
+
+    impl ServerHandler for EchoServer {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo {
+                capabilities: ServerCapabilities::builder().enable_tools().build(),
+                ..Default::default()
+            }
+        }
+
+        async fn list_tools(
+            &self,
+            _request: Option<PaginatedRequestParams>,
+            _context: RequestContext<RoleServer>,
+        ) -> std::result::Result<ListToolsResult, McpError> {
+            Ok(ListToolsResult {
+                tools: vec![Tool::new(
+                    "echo",
+                    "Echo the text argument",
+                    Arc::new(serde_json::Map::new()),
+                )],
+                ..Default::default()
+            })
+        }
+
+        async fn call_tool(
+            &self,
+            request: CallToolRequestParams,
+            _context: RequestContext<RoleServer>,
+        ) -> std::result::Result<CallToolResult, McpError> {
+            let text = request
+                .arguments
+                .and_then(|args| args.get("text").and_then(|v| v.as_str()).map(String::from))
+                .unwrap_or_default();
+            Ok(CallToolResult::success(vec![Content::text(text)]))
+        }
+    }
+
+    /// Serve [`EchoServer`] on an ephemeral port, returning the MCP endpoint URL.
+    async fn serve(mode: &str, ttl_sec: u64) -> String {
+        let runtime = RuntimeConfig {
+            http_mode: mode.to_string(),
+            http_session_ttl_sec: ttl_sec,
+            ..RuntimeConfig::default()
+        };
+        let app: Router = mcp_router(
+            EchoServer,
+            &runtime,
+            Arc::new(LocalSessionManager::default()),
+            Arc::new(Mutex::new(HttpMetrics::new())),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}{}", addr, runtime.http_path)
+    }
+
+    /// POST a JSON-RPC message, returning the status, session header, and the response
+    /// message (if any) from a JSON or SSE body.
+    async fn post(
+        url: &str,
+        session: Option<&str>,
+        body: Value,
+    ) -> (StatusCode, Option<String>, Option<Value>) {
+        let mut req = reqwest::Client::new()
+            .post(url)
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(&body);
+        if let Some(id) = session {
+            req = req.header(HEADER_SESSION_ID, id);
+        }
+        let resp = req.send().await.unwrap();
+        let status = StatusCode::from_u16(resp.status().as_u16()).unwrap();
+        let session = resp
+            .headers()
+            .get(HEADER_SESSION_ID)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let text = tokio::time::timeout(Duration::from_secs(5), resp.text())
+            .await
+            .unwrap()
+            .unwrap();
+        let message = std::iter::once(text.as_str())
+            .chain(text.lines().filter_map(|l| l.strip_prefix("data:")))
+            .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+            .find(|v| v.get("id").is_some());
+        (status, session, message)
+    }
+
+    fn initialize_request() -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": {"name": "http-test", "version": "0"}
+            }
+        })
+    }
+
+    #[test]
+    fn test_session_activity_expires_idle_sessions() {
+        let start = Instant::now();
+        let mut activity = SessionActivity::default();
+        assert!(activity.touch("a", start));
+        assert!(activity.touch("b", start));
+        assert!(!activity.touch("b", start + Duration::from_secs(5)));
+        let expired =
+            activity.take_expired(start + Duration::from_secs(10), Duration::from_secs(8));
+        assert_eq!(expired, vec!["a".to_string()]);
+        activity.forget("b");
+        assert!(
+            activity
+                .take_expired(start + Duration::from_secs(60), Duration::from_secs(8))
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streamable_session_lifecycle_and_expiry() {
+        let url = serve("streamable", 1).await;
+
+        let (status, session, init) = post(&url, None, initialize_request()).await;
+        assert_eq!(status, StatusCode::OK);
+        let session = session.expect("initialize should assign Mcp-Session-Id");
+        assert!(init.unwrap()["result"]["capabilities"]["tools"].is_object());
+
+        let initialized = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        let (status, _, _) = post(&url, Some(&session), initialized).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let list = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"});
+        let (_, _, tools) = post(&url, Some(&session), list.clone()).await;
+        assert_eq!(tools.unwrap()["result"]["tools"][0]["name"], json!("echo"));
+
+        let call = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": {"name": "echo", "arguments": {"text": "hello"}}
+        });
+        let (_, _, called) = post(&url, Some(&session), call).await;
+        assert_eq!(
+            called.unwrap()["result"]["content"][0]["text"],
+            json!("hello")
+        );
+
+        let stream = reqwest::Client::new()
+            .get(&url)
+            .header(header::ACCEPT, "text/event-stream")
+            .header(HEADER_SESSION_ID, &session)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(stream.status().as_u16(), 200);
+        drop(stream);
+
+        // Idle past the 1s TTL plus a sweep
+        tokio::time::sleep(Duration::from_millis(1600)).await;
+        let (status, _, _) = post(&url, Some(&session), list).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_open_stream_keeps_session_alive() {
+        let start = Instant::now();
+        let ttl = Duration::from_secs(8);
+        let mut activity = SessionActivity::default();
+        activity.touch("a", start);
+        let token = activity.open_stream("a");
+        assert!(
+            activity
+                .take_expired(start + Duration::from_secs(10), ttl)
+                .is_empty()
+        );
+        drop(token);
+        // The TTL restarts from the last sweep that saw the stream open
+        assert!(
+            activity
+                .take_expired(start + Duration::from_secs(15), ttl)
+                .is_empty()
+        );
+        let expired = activity.take_expired(start + Duration::from_secs(20), ttl);
+        assert_eq!(expired, vec!["a".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_open_get_stream_outlives_ttl() {
+        let url = serve("streamable", 1).await;
+
+        let (_, session, _) = post(&url, None, initialize_request()).await;
+        let session = session.expect("initialize should assign Mcp-Session-Id");
+        let initialized = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        post(&url, Some(&session), initialized).await;
+
+        let stream = reqwest::Client::new()
+            .get(&url)
+            .header(header::ACCEPT, "text/event-stream")
+            .header(HEADER_SESSION_ID, &session)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(stream.status().as_u16(), 200);
+
+        // Idle on requests past the 1s TTL, but the GET stream is still open
+        tokio::time::sleep(Duration::from_millis(1600)).await;
+        let list = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"});
+        let (status, _, tools) = post(&url, Some(&session), list.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(tools.unwrap()["result"]["tools"][0]["name"], json!("echo"));
+
+        drop(stream);
+        tokio::time::sleep(Duration::from_millis(1600)).await;
+        let (status, _, _) = post(&url, Some(&session), list).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_basic_mode_is_stateless() {
+        let url = serve("basic", 1).await;
+
+        let (status, session, init) = post(&url, None, initialize_request()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(session.is_none());
+        assert!(init.is_some());
+
+        let list = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"});
+        let (_, _, tools) = post(&url, None, list).await;
+        assert_eq!(tools.unwrap()["result"]["tools"][0]["name"], json!("echo"));
+
+        let stream = reqwest::Client::new()
+            .get(&url)
+            .header(header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(stream.status().as_u16(), 405);
+    }
+
+    /// Serve just `/metrics` on an ephemeral port.
+    async fn serve_metrics(metrics_enabled: bool) -> String {
+        let mut config = Config::default();
+        config.runtime.metrics_enabled = metrics_enabled;
+        let state = HttpState {
+            config: Arc::new(config),
+            metrics: Arc::new(Mutex::new(HttpMetrics::new())),
+            session_mgr: Arc::new(LocalSessionManager::default()),
+            db_ping_cache: Arc::new(Mutex::new(None)),
+        };
+        let app = Router::new()
+            .route("/metrics", get(metrics_handler))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/metrics", addr)
+    }
+
+    /// Every sample line is `name[{labels}] value` for a metric declared by a preceding
+    /// `# TYPE` line (histograms own their `_bucket`, `_sum`, and `_count` series).
+    fn assert_valid_exposition(text: &str) {
+        let mut declared: Vec<String> = Vec::new();
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = rest.split_once(' ').expect("TYPE needs a kind");
+                assert!(
+                    ["counter", "gauge", "histogram"].contains(&kind),
+                    "{}",
+                    line
+                );
+                declared.push(name.to_string());
+                continue;
+            }
+            if line.starts_with("# HELP ") {
+                continue;
+            }
+            let (series, value) = line.rsplit_once(' ').expect("sample needs a value");
+            assert!(value.parse::<f64>().is_ok(), "bad value in {:?}", line);
+            let name = series.split('{').next().unwrap();
+            if let Some(labels) = series.strip_prefix(name) {
+                assert!(
+                    labels.is_empty() || (labels.starts_with('{') && labels.ends_with('}')),
+                    "bad labels in {:?}",
+                    line
+                );
+            }
+            let base = ["_bucket", "_sum", "_count"]
+                .iter()
+                .find_map(|suffix| name.strip_suffix(suffix))
+                .filter(|base| declared.iter().any(|d| d == base))
+                .unwrap_or(name);
+            assert_eq!(declared.last().map(String::as_str), Some(base), "{}", line);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_prometheus_when_enabled() {
+        let tool = format!("fake_{}", uuid::Uuid::new_v4().simple());
+        let registry = surreal_mind::metrics::global();
+        registry.record_tool_call(&tool, Duration::from_millis(3), true);
+        registry.record_tool_call(&tool, Duration::from_millis(40), true);
+        registry.record_tool_call(&tool, Duration::from_millis(700), false);
+
+        let resp = reqwest::get(serve_metrics(true).await).await.unwrap();
+        assert_eq!(
+            resp.headers()[header::CONTENT_TYPE],
+            surreal_mind::metrics::PROMETHEUS_CONTENT_TYPE
+        );
+        let text = resp.text().await.unwrap();
+        assert_valid_exposition(&text);
+        for expected in [
+            format!("surreal_mind_tool_calls_total{{tool=\"{tool}\"}} 3"),
+            format!("surreal_mind_tool_errors_total{{tool=\"{tool}\"}} 1"),
+            format!("surreal_mind_tool_duration_seconds_bucket{{tool=\"{tool}\",le=\"0.005\"}} 1"),
+            format!("surreal_mind_tool_duration_seconds_bucket{{tool=\"{tool}\",le=\"0.05\"}} 2"),
+            format!("surreal_mind_tool_duration_seconds_bucket{{tool=\"{tool}\",le=\"+Inf\"}} 3"),
+            format!("surreal_mind_tool_duration_seconds_count{{tool=\"{tool}\"}} 3"),
+        ] {
+            assert!(text.lines().any(|l| l == expected), "missing {}", expected);
+        }
+
+        // Disabled: the JSON summary, as before
+        let json: Value = reqwest::get(serve_metrics(false).await)
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(json["metrics_version"], "1");
+    }
+}
@@ -56,6 +56,7 @@ pub fn get_expected_indexes() -> Vec<TableIndexes> {
                 IndexType::Single("created_at".into()),
                 IndexType::Single("status".into()),
                 IndexType::Single("embedding_model".into()),
+                IndexType::Single("idempotency_key".into()),
             ],
             optional: vec![
                 // Performance index for think_search filtering
@@ -117,6 +118,22 @@ pub fn get_expected_indexes() -> Vec<TableIndexes> {
             ],
             optional: vec![],
         },
+        TableIndexes {
+            table: "kg_deletions".into(),
+            required: vec![
+                IndexType::Single("created_at".into()),
+                IndexType::Composite(vec!["source_table".into(), "record_id".into()]),
+            ],
+            optional: vec![],
+        },
+        TableIndexes {
+            table: "tool_audit".into(),
+            required: vec![
+                IndexType::Single("created_at".into()),
+                IndexType::Composite(vec!["tool".into(), "created_at".into()]),
+            ],
+            optional: vec![],
+        },
         TableIndexes {
             table: "kg_blocklist".into(),
             required: vec![IndexType::Single("item".into())],
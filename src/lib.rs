@@ -1,3 +1,7 @@
+// The search tool schema is one json! literal deep enough to need more than the default 128
+#![recursion_limit = "256"]
+
+pub mod client_api;
 pub mod clients;
 pub mod cognitive;
 pub mod config;
@@ -6,10 +10,15 @@ pub mod embeddings;
 pub mod error;
 pub mod indexes;
 pub mod maintenance;
+pub mod metrics;
+pub mod prompts;
 pub mod registry;
 pub mod schemas;
+pub mod selftest;
 pub mod serializers;
 pub mod server;
+#[cfg(test)]
+pub(crate) mod test_support;
 pub mod tools;
 pub mod utils;
 pub mod workspace;
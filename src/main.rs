@@ -41,6 +41,23 @@ async fn main() -> Result<()> {
         );
     }
 
+    // `--self-test` (or SURR_SELF_TEST=1): exercise the tools against the [self_test] scratch
+    // database with an offline embedder, print the report, and exit nonzero on any failure
+    let self_test = std::env::args().skip(1).any(|a| a == "--self-test")
+        || std::env::var("SURR_SELF_TEST")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+    if self_test {
+        let report = surreal_mind::selftest::run_self_test(&config)
+            .await
+            .with_context(|| "Self-test could not reach the scratch database")?;
+        print!("{}", report.render());
+        if !report.passed {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Create server using the new modular architecture
     let server = SurrealMindServer::new(&config)
         .await
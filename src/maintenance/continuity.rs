@@ -0,0 +1,419 @@
+//! Continuity link repair.
+//!
+//! `think` keeps links to thoughts that do not exist yet as plain strings and records
+//! `"string"` for them in the thought's `link_status`. [`repair_continuity_links`] re-checks
+//! those targets, flips the status to `"record"` once the thought exists, and fills the
+//! `superseded_by` back-pointer for revisions. Thoughts written before `link_status` existed
+//! are backfilled the same way.
+
+use crate::server::connection::DbEngine;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use surrealdb::Surreal;
+use surrealdb::types::{RecordId, Value};
+
+/// Continuity link fields on `thoughts`, in resolution order.
+pub const LINK_FIELDS: [&str; 3] = ["previous_thought_id", "revises_thought", "branch_from"];
+
+/// Thoughts with an unresolved link, or with links but no `link_status` yet. Link targets
+/// are returned as bare ids whether stored as records or strings.
+const PAGE_SQL: &str = "SELECT meta::id(id) AS id, link_status, \
+    time::unix(created_at) AS created_unix, \
+    IF type::is_record(previous_thought_id) THEN meta::id(previous_thought_id) ELSE previous_thought_id END AS previous_thought_id, \
+    IF type::is_record(revises_thought) THEN meta::id(revises_thought) ELSE revises_thought END AS revises_thought, \
+    IF type::is_record(branch_from) THEN meta::id(branch_from) ELSE branch_from END AS branch_from \
+    FROM thoughts \
+    WHERE link_status.previous_thought_id = 'string' OR link_status.revises_thought = 'string' \
+    OR link_status.branch_from = 'string' \
+    OR (link_status = NONE AND (previous_thought_id != NONE OR revises_thought != NONE OR branch_from != NONE)) \
+    ORDER BY created_unix ASC LIMIT $limit START $start";
+
+/// Thoughts fetched per page.
+const PAGE_SIZE: usize = 200;
+
+const EXISTING_SQL: &str = "SELECT VALUE meta::id(id) FROM thoughts WHERE id IN $recs";
+
+const UPDATE_SQL: &str =
+    "UPDATE type::record('thoughts', $id) SET link_status = $status RETURN NONE";
+
+/// Only the first revision claims the back-pointer, matching creation-time behavior.
+const SUPERSEDE_SQL: &str = "UPDATE type::record('thoughts', $id) SET superseded_by = $new \
+    WHERE superseded_by = NONE RETURN VALUE meta::id(id)";
+
+/// Options for one repair run.
+#[derive(Debug, Clone, Copy)]
+pub struct RepairOptions {
+    /// Max thoughts to scan, oldest first.
+    pub limit: usize,
+    /// List still-missing links whose thought is at least this many days old.
+    pub dangling_after_days: Option<u32>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct RepairStats {
+    pub dry_run: bool,
+    pub scanned: usize,
+    /// Links flipped from `string` to `record`
+    pub repaired: usize,
+    pub still_missing: usize,
+    /// Thoughts without `link_status` that had it filled in
+    pub backfilled: usize,
+    pub superseded_filled: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dangling: Vec<DanglingLink>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DanglingLink {
+    pub thought_id: String,
+    pub link: &'static str,
+    pub target: String,
+    pub age_days: i64,
+}
+
+/// `link_status` object: link field name to `record`, `string`, or `dropped_*`.
+type LinkStatus = serde_json::Map<String, serde_json::Value>;
+
+#[derive(Debug, Deserialize)]
+struct LinkRow {
+    id: String,
+    #[serde(default)]
+    link_status: Option<LinkStatus>,
+    #[serde(default)]
+    created_unix: Option<i64>,
+    #[serde(default)]
+    previous_thought_id: Option<String>,
+    #[serde(default)]
+    revises_thought: Option<String>,
+    #[serde(default)]
+    branch_from: Option<String>,
+}
+
+impl LinkRow {
+    /// Bare id of the thought `field` points at.
+    fn target(&self, field: &str) -> Option<&str> {
+        let raw = match field {
+            "previous_thought_id" => &self.previous_thought_id,
+            "revises_thought" => &self.revises_thought,
+            "branch_from" => &self.branch_from,
+            _ => &None,
+        };
+        raw.as_deref()
+            .map(|t| t.strip_prefix("thoughts:").unwrap_or(t))
+    }
+
+    /// Links still to be checked: those marked `string`, or every link when the thought has
+    /// no `link_status`.
+    fn pending(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        LINK_FIELDS.into_iter().filter_map(|field| {
+            let target = self.target(field)?;
+            let pending = match &self.link_status {
+                Some(status) => status.get(field).and_then(|s| s.as_str()) == Some("string"),
+                None => true,
+            };
+            pending.then_some((field, target))
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum LinkCheck {
+    Resolved,
+    Missing,
+}
+
+/// The thought's updated `link_status` and the outcome for each pending link.
+fn check_links<'a>(
+    row: &'a LinkRow,
+    existing: &HashSet<String>,
+) -> (LinkStatus, Vec<(&'static str, &'a str, LinkCheck)>) {
+    let mut status = row.link_status.clone().unwrap_or_default();
+    let checks: Vec<_> = row
+        .pending()
+        .map(|(field, target)| {
+            let check = if existing.contains(target) {
+                LinkCheck::Resolved
+            } else {
+                LinkCheck::Missing
+            };
+            let kind = match check {
+                LinkCheck::Resolved => "record",
+                LinkCheck::Missing => "string",
+            };
+            status.insert(field.to_string(), serde_json::Value::String(kind.into()));
+            (field, target, check)
+        })
+        .collect();
+    (status, checks)
+}
+
+/// Re-check unresolved continuity links on up to `opts.limit` thoughts as of `now`, page by
+/// page. Links that are still missing stay `string` and are counted (and listed once older
+/// than `dangling_after_days`).
+pub async fn repair_continuity_links(
+    db: &Surreal<DbEngine>,
+    opts: RepairOptions,
+    now: DateTime<Utc>,
+) -> Result<RepairStats> {
+    let mut stats = RepairStats {
+        dry_run: opts.dry_run,
+        ..RepairStats::default()
+    };
+    // Rows that keep a missing link still match PAGE_SQL after the update
+    let mut start = 0;
+    loop {
+        let take = opts.limit.saturating_sub(stats.scanned).min(PAGE_SIZE);
+        if take == 0 {
+            break;
+        }
+        let rows: Vec<serde_json::Value> = db
+            .query(PAGE_SQL)
+            .bind(("limit", take as i64))
+            .bind(("start", start as i64))
+            .await?
+            .check()?
+            .take(0)?;
+        if rows.is_empty() {
+            break;
+        }
+        let fetched = rows.len();
+        let rows: Vec<LinkRow> = rows
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<std::result::Result<_, _>>()?;
+        let existing = existing_targets(db, &rows).await?;
+        let mut still_matching = 0;
+
+        for row in &rows {
+            stats.scanned += 1;
+            let (status, checks) = check_links(row, &existing);
+            let backfill = row.link_status.is_none();
+            let mut changed = backfill;
+            let mut missing = false;
+            for (field, target, check) in checks {
+                match check {
+                    LinkCheck::Resolved => {
+                        if !backfill {
+                            stats.repaired += 1;
+                            changed = true;
+                        }
+                        if field == "revises_thought"
+                            && fill_superseded(db, target, &row.id, opts.dry_run).await?
+                        {
+                            stats.superseded_filled += 1;
+                        }
+                    }
+                    LinkCheck::Missing => {
+                        missing = true;
+                        stats.still_missing += 1;
+                        let age_days = row
+                            .created_unix
+                            .map(|c| (now.timestamp() - c) / 86_400)
+                            .unwrap_or(0);
+                        if opts
+                            .dangling_after_days
+                            .is_some_and(|days| age_days >= days as i64)
+                        {
+                            stats.dangling.push(DanglingLink {
+                                thought_id: row.id.clone(),
+                                link: field,
+                                target: format!("thoughts:{}", target),
+                                age_days,
+                            });
+                        }
+                    }
+                }
+            }
+            if backfill {
+                stats.backfilled += 1;
+            }
+            if changed && !opts.dry_run {
+                db.query(UPDATE_SQL)
+                    .bind(("id", row.id.clone()))
+                    .bind(("status", serde_json::Value::Object(status)))
+                    .await?
+                    .check()?;
+            }
+            if missing || opts.dry_run {
+                still_matching += 1;
+            }
+        }
+
+        start += still_matching;
+        if fetched < take {
+            break;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Bare ids among the rows' pending link targets that exist as thoughts.
+async fn existing_targets(db: &Surreal<DbEngine>, rows: &[LinkRow]) -> Result<HashSet<String>> {
+    let targets: HashSet<&str> = rows.iter().flat_map(|r| r.pending().map(|p| p.1)).collect();
+    if targets.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let recs: Vec<Value> = targets
+        .iter()
+        .map(|t| Value::RecordId(RecordId::new("thoughts", *t)))
+        .collect();
+    let ids: Vec<String> = db
+        .query(EXISTING_SQL)
+        .bind(("recs", recs))
+        .await?
+        .check()?
+        .take(0)?;
+    Ok(ids.into_iter().collect())
+}
+
+/// Point `revised` at `new_thought_id` unless it already has a successor. A dry run
+/// assumes it would.
+async fn fill_superseded(
+    db: &Surreal<DbEngine>,
+    revised: &str,
+    new_thought_id: &str,
+    dry_run: bool,
+) -> Result<bool> {
+    if dry_run {
+        return Ok(true);
+    }
+    let hit: Vec<String> = db
+        .query(SUPERSEDE_SQL)
+        .bind(("id", revised.to_string()))
+        .bind(("new", new_thought_id.to_string()))
+        .await?
+        .check()?
+        .take(0)?;
+    Ok(!hit.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(status: Option<serde_json::Value>, prev: Option<&str>, rev: Option<&str>) -> LinkRow {
+        serde_json::from_value(serde_json::json!({
+            "id": "child",
+            "link_status": status,
+            "previous_thought_id": prev,
+            "revises_thought": rev,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_string_link_flips_once_target_exists() {
+        let r = row(
+            Some(serde_json::json!({"previous_thought_id": "string", "revises_thought": "record"})),
+            Some("thoughts:parent"),
+            Some("thoughts:old"),
+        );
+        // Only the `string` link is pending; the resolved revision is not re-checked
+        let (status, checks) = check_links(&r, &HashSet::from(["parent".to_string()]));
+        assert_eq!(
+            checks,
+            vec![("previous_thought_id", "parent", LinkCheck::Resolved)]
+        );
+        assert_eq!(status["previous_thought_id"], "record");
+        assert_eq!(status["revises_thought"], "record");
+
+        let (status, checks) = check_links(&r, &HashSet::new());
+        assert_eq!(
+            checks,
+            vec![("previous_thought_id", "parent", LinkCheck::Missing)]
+        );
+        assert_eq!(status["previous_thought_id"], "string");
+    }
+
+    #[test]
+    fn test_legacy_thought_is_backfilled() {
+        let r = row(None, Some("parent"), Some("thoughts:gone"));
+        let (status, checks) = check_links(&r, &HashSet::from(["parent".to_string()]));
+        assert_eq!(
+            checks,
+            vec![
+                ("previous_thought_id", "parent", LinkCheck::Resolved),
+                ("revises_thought", "gone", LinkCheck::Missing),
+            ]
+        );
+        assert_eq!(
+            serde_json::Value::Object(status),
+            serde_json::json!({"previous_thought_id": "record", "revises_thought": "string"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repair_resolves_backfills_and_lists_dangling_links() {
+        let server = crate::test_support::mem_server().await;
+        let thought = |id: &str, days_ago: i64, extra: &str| {
+            format!(
+                "CREATE thoughts:{id} SET content = '{id}', created_at = time::now() - {days_ago}d, \
+                 injection_scale = 0, significance = 0.5, access_count = 0, \
+                 injected_memories = []{extra};"
+            )
+        };
+        let sql = [
+            thought("parent", 50, ""),
+            thought(
+                "child",
+                40,
+                ", previous_thought_id = 'parent', \
+                 link_status = {previous_thought_id: 'string'}",
+            ),
+            thought(
+                "orphan",
+                40,
+                ", revises_thought = 'ghost', link_status = {revises_thought: 'string'}",
+            ),
+            thought("legacy", 1, ", revises_thought = 'parent'"),
+        ]
+        .concat();
+        server.db.query(sql).await.unwrap().check().unwrap();
+
+        let db = server.db.client();
+        let opts = RepairOptions {
+            limit: 10,
+            dangling_after_days: Some(30),
+            dry_run: false,
+        };
+        let stats = repair_continuity_links(&db, opts, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            RepairStats {
+                dry_run: false,
+                scanned: 3,
+                repaired: 1,
+                still_missing: 1,
+                backfilled: 1,
+                superseded_filled: 1,
+                dangling: vec![DanglingLink {
+                    thought_id: "orphan".into(),
+                    link: "revises_thought",
+                    target: "thoughts:ghost".into(),
+                    age_days: 40,
+                }],
+            }
+        );
+
+        let superseded: Vec<Option<String>> = server
+            .db
+            .query("SELECT VALUE superseded_by FROM thoughts:parent")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(superseded, vec![Some("legacy".to_string())]);
+
+        // Only the dangling link is left to re-check
+        let again = repair_continuity_links(&db, opts, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!((again.scanned, again.still_missing), (1, 1));
+    }
+}
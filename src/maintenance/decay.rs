@@ -0,0 +1,292 @@
+//! Thought significance decay.
+//!
+//! Lowers `significance` on thoughts that have not been accessed recently and optionally
+//! prunes the ones that decay below `prune_threshold`. Decay is measured from the later of
+//! `last_accessed` and the previous decay run (`decayed_at`, falling back to `created_at`),
+//! so repeated runs compound correctly instead of re-applying the full idle time.
+
+use crate::config::DecayConfig;
+use crate::server::connection::DbEngine;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::Surreal;
+use surrealdb::types::{Datetime, RecordId};
+
+/// Page of thoughts with the unix time decay is measured from.
+const PAGE_SQL: &str = "SELECT meta::id(id) AS id, significance, \
+    time::unix(IF last_accessed != NONE AND (decayed_at = NONE OR last_accessed > decayed_at) \
+    THEN last_accessed ELSE (decayed_at ?? created_at) END) AS since_unix, created_at \
+    FROM thoughts ORDER BY created_at ASC LIMIT $limit START $start";
+
+/// Any thought whose continuity links point at `$refs` (record or string forms).
+const REFERENCED_SQL: &str = "SELECT VALUE meta::id(id) FROM thoughts \
+    WHERE previous_thought_id IN $refs OR revises_thought IN $refs OR branch_from IN $refs LIMIT 1";
+
+const UPDATE_SQL: &str = "UPDATE type::record('thoughts', $id) \
+    SET significance = $significance, decayed_at = $now RETURN NONE";
+
+const DELETE_SQL: &str = "DELETE type::record('thoughts', $id) RETURN NONE";
+
+/// Options for one decay run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecayOptions {
+    /// Max thoughts to scan (all when None).
+    pub limit: Option<usize>,
+    /// Thoughts to skip before scanning, in `created_at` order.
+    pub offset: usize,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DecayStats {
+    pub dry_run: bool,
+    pub scanned: usize,
+    pub decayed: usize,
+    pub unchanged: usize,
+    pub pruned: usize,
+    pub skipped_referenced: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct DecayRow {
+    id: String,
+    #[serde(default)]
+    significance: Option<f32>,
+    #[serde(default)]
+    since_unix: Option<i64>,
+}
+
+/// `significance * decay_factor^(idle_days / half_life_days)`, never below `floor` and never
+/// above the current value (thoughts already under the floor are left alone).
+pub fn decayed_significance(significance: f32, idle_days: f64, cfg: &DecayConfig) -> f32 {
+    if idle_days <= 0.0 || significance <= cfg.floor {
+        return significance;
+    }
+    let factor = (cfg.decay_factor as f64).powf(idle_days / cfg.half_life_days);
+    ((significance as f64 * factor) as f32).max(cfg.floor)
+}
+
+/// Whether a thought at `significance` should be pruned.
+pub fn below_prune_threshold(significance: f32, cfg: &DecayConfig) -> bool {
+    cfg.prune_threshold.is_some_and(|t| significance < t)
+}
+
+/// Decay thoughts page by page as of `now`. Thoughts below `prune_threshold` are deleted
+/// unless another thought links to them via `previous_thought_id`, `revises_thought`, or
+/// `branch_from`; referenced ones are decayed but kept.
+pub async fn decay_thoughts(
+    db: &Surreal<DbEngine>,
+    cfg: &DecayConfig,
+    opts: DecayOptions,
+    now: DateTime<Utc>,
+) -> Result<DecayStats> {
+    let mut stats = DecayStats {
+        dry_run: opts.dry_run,
+        ..DecayStats::default()
+    };
+    let limit_total = opts.limit.unwrap_or(usize::MAX);
+    let mut start = opts.offset;
+    let now_dt = Datetime::from(now);
+
+    loop {
+        let take = limit_total
+            .saturating_sub(stats.scanned)
+            .min(cfg.batch_size);
+        if take == 0 {
+            break;
+        }
+        let rows: Vec<serde_json::Value> = db
+            .query(PAGE_SQL)
+            .bind(("limit", take as i64))
+            .bind(("start", start as i64))
+            .await?
+            .check()?
+            .take(0)?;
+        if rows.is_empty() {
+            break;
+        }
+        let fetched = rows.len();
+        let rows: Vec<DecayRow> = rows
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<std::result::Result<_, _>>()?;
+        let mut deleted_in_page = 0;
+
+        for row in rows {
+            stats.scanned += 1;
+            let idle_days = row
+                .since_unix
+                .map(|since| (now.timestamp() - since) as f64 / 86_400.0)
+                .unwrap_or(0.0);
+            let significance = row.significance.unwrap_or(0.0);
+            let new_sig = decayed_significance(significance, idle_days, cfg);
+
+            if below_prune_threshold(new_sig, cfg) {
+                if is_referenced(db, &row.id).await? {
+                    stats.skipped_referenced += 1;
+                } else {
+                    if !opts.dry_run {
+                        db.query(DELETE_SQL)
+                            .bind(("id", row.id.clone()))
+                            .await?
+                            .check()?;
+                        deleted_in_page += 1;
+                    }
+                    stats.pruned += 1;
+                    continue;
+                }
+            }
+
+            if new_sig < significance {
+                if !opts.dry_run {
+                    db.query(UPDATE_SQL)
+                        .bind(("id", row.id.clone()))
+                        .bind(("significance", new_sig as f64))
+                        .bind(("now", now_dt))
+                        .await?
+                        .check()?;
+                }
+                stats.decayed += 1;
+            } else {
+                stats.unchanged += 1;
+            }
+        }
+
+        // Deleted rows no longer occupy offsets
+        start += fetched - deleted_in_page;
+        if fetched < take {
+            break;
+        }
+    }
+
+    Ok(stats)
+}
+
+async fn is_referenced(db: &Surreal<DbEngine>, id: &str) -> Result<bool> {
+    let refs = vec![
+        surrealdb::types::Value::RecordId(RecordId::new("thoughts", id)),
+        surrealdb::types::Value::String(id.to_string()),
+        surrealdb::types::Value::String(format!("thoughts:{}", id)),
+    ];
+    let hits: Vec<String> = db
+        .query(REFERENCED_SQL)
+        .bind(("refs", refs))
+        .await?
+        .check()?
+        .take(0)?;
+    Ok(!hits.is_empty())
+}
+
+/// Load config, connect, and run [`decay_thoughts`] with the configured `[maintenance.decay]`
+/// settings. Intended for cron wrappers that run outside the MCP server.
+pub async fn run_decay_thoughts(opts: DecayOptions) -> Result<DecayStats> {
+    let config = crate::config::Config::load()?;
+    let db = crate::server::connection::DbConnection::connect(
+        crate::server::connection::DbSettings::from_config(&config),
+        1,
+    )
+    .await?;
+    decay_thoughts(&db.client(), &config.maintenance.decay, opts, Utc::now()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(prune: Option<f32>) -> DecayConfig {
+        DecayConfig {
+            decay_factor: 0.5,
+            half_life_days: 30.0,
+            floor: 0.1,
+            prune_threshold: prune,
+            batch_size: 10,
+        }
+    }
+
+    #[test]
+    fn test_decay_halves_per_half_life_and_clamps_at_floor() {
+        let c = cfg(None);
+        assert_eq!(decayed_significance(0.8, 0.0, &c), 0.8);
+        assert!((decayed_significance(0.8, 30.0, &c) - 0.4).abs() < 1e-6);
+        assert!((decayed_significance(0.8, 60.0, &c) - 0.2).abs() < 1e-6);
+        assert!((decayed_significance(0.8, 15.0, &c) - 0.8 * 0.5f32.sqrt()).abs() < 1e-6);
+        assert_eq!(decayed_significance(0.8, 3650.0, &c), 0.1);
+        // Already under the floor: never raised
+        assert_eq!(decayed_significance(0.05, 100.0, &c), 0.05);
+    }
+
+    #[test]
+    fn test_decay_compounds_across_runs() {
+        let c = cfg(None);
+        let once = decayed_significance(0.9, 40.0, &c);
+        let twice = decayed_significance(decayed_significance(0.9, 25.0, &c), 15.0, &c);
+        assert!((once - twice).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_prune_threshold() {
+        assert!(!below_prune_threshold(0.1, &cfg(None)));
+        // Long-idle thoughts settle at the floor, which is below the threshold
+        let settled = decayed_significance(0.9, 3650.0, &cfg(Some(0.15)));
+        assert!(below_prune_threshold(settled, &cfg(Some(0.15))));
+        assert!(!below_prune_threshold(0.15, &cfg(Some(0.15))));
+    }
+
+    #[tokio::test]
+    async fn test_decay_pages_prunes_and_keeps_referenced_thoughts() {
+        let server = crate::test_support::mem_server().await;
+        let thought = |id: &str, days_ago: i64, significance: f64, extra: &str| {
+            format!(
+                "CREATE thoughts:{id} SET content = '{id}', created_at = time::now() - {days_ago}d, \
+                 injection_scale = 0, significance = {significance}, access_count = 0, \
+                 injected_memories = []{extra};"
+            )
+        };
+        let sql = [
+            thought("kept", 600, 0.9, ""),
+            thought("stale", 590, 0.9, ", previous_thought_id = 'kept'"),
+            thought("fresh", 0, 0.5, ""),
+        ]
+        .concat();
+        server.db.query(sql).await.unwrap().check().unwrap();
+
+        // batch_size 2 makes the run span two pages
+        let c = DecayConfig {
+            batch_size: 2,
+            ..cfg(Some(0.15))
+        };
+        let stats = decay_thoughts(&server.db.client(), &c, DecayOptions::default(), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            DecayStats {
+                dry_run: false,
+                scanned: 3,
+                decayed: 1,
+                unchanged: 1,
+                pruned: 1,
+                skipped_referenced: 1,
+            }
+        );
+
+        let left: Vec<(String, f64)> = server
+            .db
+            .query("SELECT meta::id(id) AS id, significance FROM thoughts ORDER BY id")
+            .await
+            .unwrap()
+            .take::<Vec<serde_json::Value>>(0)
+            .unwrap()
+            .iter()
+            .map(|r| {
+                let id = r["id"].as_str().unwrap().to_string();
+                (id, r["significance"].as_f64().unwrap())
+            })
+            .collect();
+        assert_eq!(left.len(), 2);
+        assert_eq!(left[0], ("fresh".to_string(), 0.5));
+        assert_eq!(left[1].0, "kept");
+        assert!((left[1].1 - 0.1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,223 @@
+//! Embedding dimension audit.
+//!
+//! Groups records by (embedding_provider, embedding_model, embedding_dim, actual length) and
+//! flags the groups that need attention. The grouping itself runs in SurrealQL (see
+//! [`group_sql`]) so embeddings never leave the database.
+
+use crate::server::connection::DbConnection;
+use crate::server::progress::ProgressReporter;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Tables covered by `maintain embedding_audit`.
+pub const AUDIT_TABLES: [&str; 3] = ["thoughts", "kg_entities", "kg_observations"];
+
+/// Expression for the actual embedding length (0 when missing or not an array).
+pub const EMBEDDING_LEN_EXPR: &str =
+    "(IF type::is_array(embedding) THEN array::len(embedding) ELSE 0 END)";
+
+/// Aggregate query for one table: one row per (provider, model, dim, length) with a count.
+pub fn group_sql(table: &str) -> String {
+    format!(
+        "SELECT provider, model, dim, len, count() AS count FROM \
+         (SELECT embedding_provider AS provider, embedding_model AS model, embedding_dim AS dim, {} AS len FROM {}) \
+         GROUP BY provider, model, dim, len",
+        EMBEDDING_LEN_EXPR, table
+    )
+}
+
+/// Sample ids for one group; binds `$provider`, `$model`, `$dim`, `$len`, `$n`.
+pub fn sample_sql(table: &str) -> String {
+    format!(
+        "SELECT VALUE meta::id(id) FROM {} WHERE embedding_provider = $provider \
+         AND embedding_model = $model AND embedding_dim = $dim AND {} = $len LIMIT $n",
+        table, EMBEDDING_LEN_EXPR
+    )
+}
+
+/// One aggregated row from [`group_sql`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EmbeddingGroup {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub dim: Option<i64>,
+    #[serde(default)]
+    pub len: i64,
+    #[serde(default)]
+    pub count: i64,
+}
+
+impl EmbeddingGroup {
+    /// Problems with this group relative to the active embedder dimension.
+    pub fn issues(&self, expected_dim: i64) -> Vec<&'static str> {
+        let mut issues = Vec::new();
+        if self.len == 0 {
+            issues.push("missing_embedding");
+        } else {
+            if self.len != expected_dim {
+                issues.push("wrong_active_dim");
+            }
+            if self.dim.is_some_and(|d| d != self.len) {
+                issues.push("dim_disagrees_with_length");
+            }
+        }
+        issues
+    }
+}
+
+/// A group with its issues and (optionally) sample ids.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupReport {
+    #[serde(flatten)]
+    pub group: EmbeddingGroup,
+    pub issues: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_ids: Option<Vec<String>>,
+}
+
+/// Per-table audit summary.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TableAudit {
+    pub total: i64,
+    pub null_or_empty: i64,
+    pub dim_disagrees_with_length: i64,
+    pub wrong_active_dim: i64,
+    pub groups: Vec<GroupReport>,
+}
+
+/// Summarize grouped rows; groups are sorted by descending count.
+pub fn summarize_groups(mut groups: Vec<EmbeddingGroup>, expected_dim: i64) -> TableAudit {
+    groups.sort_by_key(|g| std::cmp::Reverse(g.count));
+    let mut audit = TableAudit::default();
+    for group in groups {
+        let issues = group.issues(expected_dim);
+        audit.total += group.count;
+        if issues.contains(&"missing_embedding") {
+            audit.null_or_empty += group.count;
+        }
+        if issues.contains(&"dim_disagrees_with_length") {
+            audit.dim_disagrees_with_length += group.count;
+        }
+        if issues.contains(&"wrong_active_dim") {
+            audit.wrong_active_dim += group.count;
+        }
+        audit.groups.push(GroupReport {
+            group,
+            issues,
+            sample_ids: None,
+        });
+    }
+    audit
+}
+
+/// Audit of every table in [`AUDIT_TABLES`] against the active embedder dimension.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmbeddingAudit {
+    pub expected_dim: i64,
+    pub tables: BTreeMap<String, TableAudit>,
+}
+
+/// Audit [`AUDIT_TABLES`], attaching up to `sample` ids to each problem group and reporting
+/// one progress step per table.
+pub async fn run_embedding_audit(
+    db: &DbConnection,
+    expected_dim: i64,
+    sample: usize,
+    progress: &ProgressReporter,
+) -> Result<EmbeddingAudit> {
+    let mut tables = BTreeMap::new();
+    for table in AUDIT_TABLES {
+        let rows: Vec<serde_json::Value> = db.query(group_sql(table)).await?.check()?.take(0)?;
+        let groups: Vec<EmbeddingGroup> = rows
+            .into_iter()
+            .filter_map(|row| serde_json::from_value(row).ok())
+            .collect();
+        let mut audit = summarize_groups(groups, expected_dim);
+
+        if sample > 0 {
+            for report in audit.groups.iter_mut().filter(|r| !r.issues.is_empty()) {
+                let g = &report.group;
+                let ids: Vec<String> = db
+                    .query(sample_sql(table))
+                    .bind(("provider", g.provider.clone()))
+                    .bind(("model", g.model.clone()))
+                    .bind(("dim", g.dim))
+                    .bind(("len", g.len))
+                    .bind(("n", sample as i64))
+                    .await?
+                    .take(0)?;
+                report.sample_ids = Some(ids);
+            }
+        }
+        tables.insert(table.to_string(), audit);
+        progress
+            .report(
+                tables.len(),
+                Some(AUDIT_TABLES.len()),
+                format!("embedding_audit: {} audited", table),
+            )
+            .await;
+    }
+    Ok(EmbeddingAudit {
+        expected_dim,
+        tables,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn group(model: Option<&str>, dim: Option<i64>, len: i64, count: i64) -> EmbeddingGroup {
+        EmbeddingGroup {
+            provider: model.map(|_| "openai".to_string()),
+            model: model.map(String::from),
+            dim,
+            len,
+            count,
+        }
+    }
+
+    #[test]
+    fn test_summarize_groups_flags_problem_groups() {
+        let audit = summarize_groups(
+            vec![
+                group(Some("text-embedding-3-small"), Some(1536), 1536, 40),
+                group(Some("legacy"), Some(768), 768, 5),
+                group(Some("text-embedding-3-small"), Some(1536), 768, 2),
+                group(None, None, 0, 3),
+            ],
+            1536,
+        );
+        assert_eq!(audit.total, 50);
+        assert_eq!(audit.null_or_empty, 3);
+        assert_eq!(audit.dim_disagrees_with_length, 2);
+        assert_eq!(audit.wrong_active_dim, 7);
+        let issues: Vec<_> = audit.groups.iter().map(|g| g.issues.clone()).collect();
+        assert_eq!(
+            issues,
+            vec![
+                vec![],
+                vec!["wrong_active_dim"],
+                vec!["missing_embedding"],
+                vec!["wrong_active_dim", "dim_disagrees_with_length"],
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&audit.groups[1]).unwrap(),
+            json!({"provider": "openai", "model": "legacy", "dim": 768, "len": 768, "count": 5, "issues": ["wrong_active_dim"]})
+        );
+    }
+
+    #[test]
+    fn test_group_rows_deserialize_with_missing_metadata() {
+        let row = json!({"provider": null, "len": 0, "count": 4});
+        let g: EmbeddingGroup = serde_json::from_value(row).unwrap();
+        assert_eq!(g, group(None, None, 0, 4));
+    }
+}
@@ -0,0 +1,552 @@
+//! Dual-write embedding migration.
+//!
+//! With `[embedding_migration]` set, `think` and KG writes store a second vector from the
+//! target model in `embedding_v2` (with `embedding_v2_provider/model/dim`) while retrieval
+//! keeps reading the primary `embedding`. [`backfill_embedding_v2`] fills `embedding_v2` on
+//! existing rows, and [`finalize_migration`] promotes the v2 fields to primary in batches once
+//! every embedded row has one. After finalizing, point `[system]` at the target model and
+//! remove `[embedding_migration]`. Search can read either field ([`EmbeddingField`]); the
+//! models stored in each are listed by [`stored_embeddings`].
+
+use crate::config::Config;
+use crate::embeddings::{EmbedPurpose, Embedder};
+use crate::server::connection::DbEngine;
+use crate::server::progress::ProgressReporter;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::types::RecordId;
+
+/// Tables whose embeddings are migrated, in processing order.
+pub const MIGRATION_TABLES: [&str; 3] = ["thoughts", "kg_entities", "kg_observations"];
+
+const SET_V2_SQL: &str = "UPDATE type::record($tb, $id) SET embedding_v2 = $emb, \
+    embedding_v2_provider = $prov, embedding_v2_model = $model, embedding_v2_dim = $dim \
+    RETURN NONE";
+
+/// Rows without a v2 vector from the current target.
+const NEEDS_V2_WHERE: &str =
+    "(embedding_v2 = NONE OR embedding_v2_model != $model OR embedding_v2_dim != $dim)";
+
+/// Promotion of one batch. A single statement runs in one transaction, so each batch is
+/// swapped all or nothing.
+const PROMOTE_SQL: &str = "SET embedding = embedding_v2, \
+    embedding_provider = embedding_v2_provider, embedding_model = embedding_v2_model, \
+    embedding_dim = embedding_v2_dim, embedded_at = time::now(), embedding_v2 = NONE, \
+    embedding_v2_provider = NONE, embedding_v2_model = NONE, embedding_v2_dim = NONE \
+    WHERE id IN $recs AND embedding_v2 != NONE RETURN VALUE meta::id(id)";
+
+/// Vector column a similarity query reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingField {
+    /// The primary `embedding`
+    #[default]
+    Embedding,
+    /// The dual-write `embedding_v2`
+    EmbeddingV2,
+}
+
+impl EmbeddingField {
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Embedding => "embedding",
+            Self::EmbeddingV2 => "embedding_v2",
+        }
+    }
+
+    pub fn provider_column(self) -> &'static str {
+        match self {
+            Self::Embedding => "embedding_provider",
+            Self::EmbeddingV2 => "embedding_v2_provider",
+        }
+    }
+
+    pub fn model_column(self) -> &'static str {
+        match self {
+            Self::Embedding => "embedding_model",
+            Self::EmbeddingV2 => "embedding_v2_model",
+        }
+    }
+
+    pub fn dim_column(self) -> &'static str {
+        match self {
+            Self::Embedding => "embedding_dim",
+            Self::EmbeddingV2 => "embedding_v2_dim",
+        }
+    }
+}
+
+/// A provider/model/dim stored in one vector field, with the rows carrying it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoredEmbedding {
+    pub provider: String,
+    pub model: String,
+    pub dim: usize,
+    pub rows: usize,
+}
+
+/// Models stored in `field` across [`MIGRATION_TABLES`], most rows first.
+pub async fn stored_embeddings(
+    db: &Surreal<DbEngine>,
+    field: EmbeddingField,
+) -> Result<Vec<StoredEmbedding>> {
+    let mut stored: Vec<StoredEmbedding> = Vec::new();
+    for table in MIGRATION_TABLES {
+        let sql = format!(
+            "SELECT provider, model, dim, count() AS rows FROM \
+             (SELECT {} AS provider, {} AS model, {} AS dim FROM {} WHERE {} != NONE) \
+             GROUP BY provider, model, dim",
+            field.provider_column(),
+            field.model_column(),
+            field.dim_column(),
+            table,
+            field.column()
+        );
+        let groups: Vec<Value> = db.query(sql).await?.check()?.take(0)?;
+        for group in groups {
+            let text = |key: &str| group.get(key).and_then(|v| v.as_str()).unwrap_or("");
+            let number = |key: &str| group.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let (provider, model, dim) = (text("provider"), text("model"), number("dim"));
+            match stored
+                .iter_mut()
+                .find(|s| s.provider == provider && s.model == model && s.dim == dim)
+            {
+                Some(existing) => existing.rows += number("rows"),
+                None => stored.push(StoredEmbedding {
+                    provider: provider.to_string(),
+                    model: model.to_string(),
+                    dim,
+                    rows: number("rows"),
+                }),
+            }
+        }
+    }
+    stored.sort_by_key(|s| std::cmp::Reverse(s.rows));
+    Ok(stored)
+}
+
+/// The most common stored model that one of `configured` (model, dim) can produce, with the
+/// index of that embedder.
+pub fn pick_stored_embedding<'a>(
+    stored: &'a [StoredEmbedding],
+    configured: &[(&str, usize)],
+) -> Option<(&'a StoredEmbedding, usize)> {
+    stored.iter().find_map(|s| {
+        configured
+            .iter()
+            .position(|(model, dim)| *model == s.model && *dim == s.dim)
+            .map(|i| (s, i))
+    })
+}
+
+/// The embedder being migrated to and the metadata stored with its vectors.
+#[derive(Clone)]
+pub struct MigrationTarget {
+    pub embedder: Arc<dyn Embedder>,
+    pub provider: String,
+    pub model: String,
+}
+
+impl MigrationTarget {
+    /// The configured migration target, or `None` when no migration is in progress.
+    pub async fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(system) = config.embedding_migration.target_system(&config.system) else {
+            return Ok(None);
+        };
+        let mut target_config = config.clone();
+        target_config.system = system;
+        let embedder = crate::embeddings::create_embedder(&target_config)
+            .await
+            .context("Failed to create embedding migration target")?;
+        Ok(Some(Self {
+            embedder,
+            provider: target_config.system.embedding_provider,
+            model: target_config.system.embedding_model,
+        }))
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.embedder.dimensions()
+    }
+
+    /// Embed `text` with the target model and store it as the record's `embedding_v2`.
+    pub async fn write(
+        &self,
+        db: &Surreal<DbEngine>,
+        table: &str,
+        id: &str,
+        text: &str,
+    ) -> Result<()> {
+        let embedding = self
+            .embedder
+            .embed_with_purpose(text, EmbedPurpose::Reembed)
+            .await?;
+        if embedding.len() != self.dimensions() {
+            anyhow::bail!(
+                "Embedding dimension mismatch: expected {}, got {}",
+                self.dimensions(),
+                embedding.len()
+            );
+        }
+        db.query(SET_V2_SQL)
+            .bind(("tb", table.to_string()))
+            .bind(("id", id.to_string()))
+            .bind(("emb", embedding))
+            .bind(("prov", self.provider.clone()))
+            .bind(("model", self.model.clone()))
+            .bind(("dim", self.dimensions() as i64))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
+
+/// Text embedded for a row of `table`: thought `content`, entity `name (entity_type)`, or
+/// observation `name - description`, matching what the primary embedding was built from.
+pub fn embedding_text(table: &str, row: &Value) -> String {
+    if table == "thoughts" {
+        return row
+            .get("content")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+    }
+    let name = row.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let data = row.get("data").unwrap_or(&Value::Null);
+    kg_embedding_text(table, name, data)
+}
+
+/// Text embedded for a KG entity or observation.
+pub fn kg_embedding_text(table: &str, name: &str, data: &Value) -> String {
+    let mut text = name.to_string();
+    if table == "kg_entities" {
+        if let Some(entity_type) = data.get("entity_type").and_then(|v| v.as_str()) {
+            text.push_str(&format!(" ({})", entity_type));
+        }
+    } else if table == "kg_observations"
+        && let Some(description) = data.get("description").and_then(|v| v.as_str())
+    {
+        text.push_str(&format!(" - {}", description));
+    }
+    text
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TableBackfill {
+    pub table: &'static str,
+    pub scanned: usize,
+    /// Rows given an `embedding_v2` (or that would be, on a dry run)
+    pub updated: usize,
+    /// Rows with no text to embed
+    pub skipped: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct BackfillStats {
+    pub dry_run: bool,
+    pub provider: String,
+    pub model: String,
+    pub target_dim: usize,
+    pub tables: Vec<TableBackfill>,
+}
+
+/// Fill `embedding_v2` from `target` on up to `limit` rows missing one (or holding one from
+/// another target), `batch_size` rows per page, reporting progress after each page.
+pub async fn backfill_embedding_v2(
+    db: &Surreal<DbEngine>,
+    target: &MigrationTarget,
+    batch_size: usize,
+    limit: Option<usize>,
+    dry_run: bool,
+    progress: &ProgressReporter,
+) -> Result<BackfillStats> {
+    let mut stats = BackfillStats {
+        dry_run,
+        provider: target.provider.clone(),
+        model: target.model.clone(),
+        target_dim: target.dimensions(),
+        tables: Vec::new(),
+    };
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut scanned = 0;
+
+    for table in MIGRATION_TABLES {
+        let mut table_stats = TableBackfill {
+            table,
+            ..TableBackfill::default()
+        };
+        // Updated rows stop matching; skipped and dry-run rows are paged past
+        let mut start = 0;
+        loop {
+            let take = limit.saturating_sub(scanned).min(batch_size.max(1));
+            if take == 0 {
+                break;
+            }
+            let sql = format!(
+                "SELECT meta::id(id) AS id, content, name, data, created_at FROM {table} \
+                 WHERE embedding != NONE AND {NEEDS_V2_WHERE} \
+                 ORDER BY created_at ASC LIMIT $limit START $start"
+            );
+            let rows: Vec<Value> = db
+                .query(sql)
+                .bind(("model", target.model.clone()))
+                .bind(("dim", target.dimensions() as i64))
+                .bind(("limit", take as i64))
+                .bind(("start", start as i64))
+                .await?
+                .check()?
+                .take(0)?;
+            if rows.is_empty() {
+                break;
+            }
+            let fetched = rows.len();
+            for row in &rows {
+                scanned += 1;
+                table_stats.scanned += 1;
+                let id = row.get("id").and_then(|v| v.as_str()).unwrap_or("");
+                let text = embedding_text(table, row);
+                if id.is_empty() || text.trim().is_empty() {
+                    table_stats.skipped += 1;
+                    start += 1;
+                    continue;
+                }
+                if dry_run {
+                    start += 1;
+                } else {
+                    target.write(db, table, id, &text).await?;
+                }
+                table_stats.updated += 1;
+            }
+            progress
+                .report(
+                    scanned,
+                    None,
+                    format!(
+                        "{}: {} scanned, {} updated",
+                        table, table_stats.scanned, table_stats.updated
+                    ),
+                )
+                .await;
+            if fetched < take {
+                break;
+            }
+        }
+        stats.tables.push(table_stats);
+    }
+    Ok(stats)
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TableFinalize {
+    pub table: &'static str,
+    /// Embedded rows still without an `embedding_v2`
+    pub pending: usize,
+    /// Rows whose v2 fields were promoted (or would be, on a dry run)
+    pub promoted: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct FinalizeStats {
+    pub dry_run: bool,
+    pub tables: Vec<TableFinalize>,
+}
+
+/// Promote `embedding_v2` to the primary embedding fields on `ids` in `table` and clear the
+/// v2 fields. Returns the ids that had a v2 vector to promote.
+pub async fn promote_embedding_v2(
+    db: &Surreal<DbEngine>,
+    table: &str,
+    ids: &[String],
+) -> Result<Vec<String>> {
+    if !MIGRATION_TABLES.contains(&table) {
+        anyhow::bail!("Table '{}' does not hold migrated embeddings", table);
+    }
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let recs: Vec<surrealdb::types::Value> = ids
+        .iter()
+        .map(|id| surrealdb::types::Value::RecordId(RecordId::new(table, id.as_str())))
+        .collect();
+    let promoted: Vec<String> = db
+        .query(format!("UPDATE {table} {PROMOTE_SQL}"))
+        .bind(("recs", recs))
+        .await?
+        .check()?
+        .take(0)?;
+    Ok(promoted)
+}
+
+/// Swap every `embedding_v2` into the primary embedding, `batch_size` rows at a time.
+/// Refuses (and changes nothing) while any embedded row still lacks a v2 vector, so the
+/// primary field never mixes dimensions.
+pub async fn finalize_migration(
+    db: &Surreal<DbEngine>,
+    batch_size: usize,
+    dry_run: bool,
+) -> Result<FinalizeStats> {
+    let mut stats = FinalizeStats {
+        dry_run,
+        tables: Vec::new(),
+    };
+    for table in MIGRATION_TABLES {
+        let sql = format!(
+            "SELECT count(embedding != NONE AND embedding_v2 = NONE) AS pending, \
+             count(embedding_v2 != NONE) AS ready FROM {table} GROUP ALL"
+        );
+        let counts: Vec<Value> = db.query(sql).await?.check()?.take(0)?;
+        let count = |key: &str| {
+            counts
+                .first()
+                .and_then(|c| c.get(key))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize
+        };
+        stats.tables.push(TableFinalize {
+            table,
+            pending: count("pending"),
+            promoted: count("ready"),
+        });
+    }
+    let pending: usize = stats.tables.iter().map(|t| t.pending).sum();
+    if pending > 0 {
+        let detail: Vec<String> = stats
+            .tables
+            .iter()
+            .filter(|t| t.pending > 0)
+            .map(|t| format!("{} {}", t.pending, t.table))
+            .collect();
+        anyhow::bail!(
+            "{} embedded rows have no embedding_v2 ({}); run `maintain reembed` with to_v2 first",
+            pending,
+            detail.join(", ")
+        );
+    }
+    if dry_run {
+        return Ok(stats);
+    }
+
+    for table_stats in &mut stats.tables {
+        let table = table_stats.table;
+        table_stats.promoted = 0;
+        loop {
+            let ids: Vec<String> = db
+                .query(format!(
+                    "SELECT VALUE meta::id(id) FROM {table} WHERE embedding_v2 != NONE LIMIT $limit"
+                ))
+                .bind(("limit", batch_size.max(1) as i64))
+                .await?
+                .check()?
+                .take(0)?;
+            if ids.is_empty() {
+                break;
+            }
+            let promoted = promote_embedding_v2(db, table, &ids).await?;
+            if promoted.is_empty() {
+                break;
+            }
+            table_stats.promoted += promoted.len();
+        }
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_embedding_text_per_table() {
+        let thought = json!({"content": "a thought", "name": "ignored"});
+        assert_eq!(embedding_text("thoughts", &thought), "a thought");
+        let entity = json!({"name": "SurrealDB", "data": {"entity_type": "database"}});
+        assert_eq!(
+            embedding_text("kg_entities", &entity),
+            "SurrealDB (database)"
+        );
+        let observation = json!({"name": "latency", "data": {"description": "p95 doubled"}});
+        assert_eq!(
+            embedding_text("kg_observations", &observation),
+            "latency - p95 doubled"
+        );
+        assert_eq!(
+            embedding_text("kg_entities", &json!({"name": "bare"})),
+            "bare"
+        );
+    }
+
+    #[test]
+    fn test_pick_stored_embedding_prefers_common_producible_models() {
+        let stored = |model: &str, dim: usize, rows: usize| StoredEmbedding {
+            provider: "openai".into(),
+            model: model.into(),
+            dim,
+            rows,
+        };
+        let stored = vec![
+            stored("legacy-model", 768, 40),
+            stored("text-embedding-3-small", 1536, 30),
+            stored("bge-small-en-v1.5", 384, 5),
+        ];
+        let configured = [("bge-small-en-v1.5", 384), ("text-embedding-3-small", 1536)];
+        let (picked, embedder) = pick_stored_embedding(&stored, &configured).unwrap();
+        assert_eq!(picked.model, "text-embedding-3-small");
+        assert_eq!(embedder, 1);
+
+        // Same model name at another dimension is a different vector space
+        assert!(pick_stored_embedding(&stored, &[("text-embedding-3-small", 512)]).is_none());
+        assert!(pick_stored_embedding(&[], &configured).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_backfill_pages_through_embedded_thoughts() {
+        let server = crate::test_support::mem_server().await;
+        let thought = |id: &str, content: &str, minutes_ago: i64| {
+            format!(
+                "CREATE thoughts:{id} SET content = '{content}', embedding = [1.0, 0.0], \
+                 created_at = time::now() - {minutes_ago}m, injection_scale = 0, \
+                 significance = 0.5, access_count = 0, injected_memories = [];"
+            )
+        };
+        let sql = [
+            thought("a", "first", 3),
+            thought("blank", "", 2),
+            thought("b", "second", 1),
+        ]
+        .concat();
+        server.db.query(sql).await.unwrap().check().unwrap();
+
+        let target = MigrationTarget {
+            embedder: Arc::new(crate::embeddings::HashEmbedder { dim: 8 }),
+            provider: "test".into(),
+            model: "hash-8".into(),
+        };
+        let db = server.db.client();
+        // One row per page
+        let stats =
+            backfill_embedding_v2(&db, &target, 1, None, false, &ProgressReporter::disabled())
+                .await
+                .unwrap();
+        let thoughts = &stats.tables[0];
+        assert_eq!(
+            (thoughts.scanned, thoughts.updated, thoughts.skipped),
+            (3, 2, 1)
+        );
+
+        let models: Vec<Option<String>> = server
+            .db
+            .query("SELECT VALUE embedding_v2_model FROM thoughts ORDER BY id")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(
+            models,
+            vec![Some("hash-8".into()), Some("hash-8".into()), None]
+        );
+    }
+}
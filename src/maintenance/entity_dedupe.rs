@@ -0,0 +1,380 @@
+//! Knowledge graph entity deduplication.
+//!
+//! Entities created before upserts matched names consistently ("surreal-mind", "Surreal Mind",
+//! "surreal_mind") are grouped by `normalize_entity_name` and normalized `entity_type`, and
+//! optionally widened to names within a Jaro-Winkler threshold. Each group keeps one survivor:
+//! `kg_edges` endpoints are moved onto it, absorbed `data` keys it lacks are merged in, and the
+//! absorbed entities are snapshotted into `kg_deletions` and deleted, one transaction per group.
+
+use crate::server::connection::DbEngine;
+use crate::tools::knowledge_graph::{normalize_entity_name, normalize_entity_type};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use surrealdb::Surreal;
+use surrealdb::types::{RecordId, Value as DbValue};
+
+const ENTITIES_SQL: &str = "SELECT meta::id(id) AS id, name, \
+    (entity_type ?? data.entity_type ?? '') AS entity_type, data, \
+    time::unix(created_at) AS created_unix FROM kg_entities";
+
+const INBOUND_SQL: &str =
+    "SELECT VALUE meta::id(target) FROM kg_edges WHERE type::is_record(target, 'kg_entities')";
+
+/// Edges touching an absorbed entity: moved to the survivor, or dropped when both endpoints
+/// are in the group (they would become self-loops).
+const EDGE_COUNTS_SQL: &str = "SELECT count(!(source IN $group AND target IN $group)) AS rewired, \
+    count(source IN $group AND target IN $group) AS dropped \
+    FROM kg_edges WHERE source IN $absorbed OR target IN $absorbed GROUP ALL";
+
+const MERGE_SQL: &str = "BEGIN TRANSACTION;
+    DELETE kg_edges WHERE source IN $group AND target IN $group AND (source IN $absorbed OR target IN $absorbed) RETURN NONE;
+    UPDATE kg_edges SET source = $survivor WHERE source IN $absorbed RETURN NONE;
+    UPDATE kg_edges SET target = $survivor WHERE target IN $absorbed RETURN NONE;
+    UPDATE $survivor SET data = $data RETURN NONE;
+    FOR $dup IN $absorbed {
+        LET $rec = (SELECT * OMIT embedding FROM $dup)[0];
+        CREATE kg_deletions SET created_at = time::now(), kind = 'entity', source_table = 'kg_entities', record_id = meta::id($dup), name = $rec.name, record = $rec, cascade_of = NONE, reason = $reason;
+        DELETE $dup RETURN NONE;
+    };
+    COMMIT TRANSACTION;";
+
+/// Which entity in a group is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SurvivorRule {
+    /// Earliest `created_at`
+    Oldest,
+    /// Most inbound `kg_edges`, then earliest
+    MostEdges,
+}
+
+impl SurvivorRule {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "oldest" => Some(Self::Oldest),
+            "most_edges" => Some(Self::MostEdges),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DedupeOptions {
+    pub survivor: SurvivorRule,
+    /// Also group names whose normalized forms have at least this Jaro-Winkler similarity
+    pub fuzzy_threshold: Option<f64>,
+    /// Max groups merged per run
+    pub limit: usize,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntityRow {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub entity_type: Option<String>,
+    #[serde(default)]
+    pub data: Value,
+    #[serde(default)]
+    pub created_unix: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergeGroup {
+    pub entity_type: String,
+    /// Normalized name of the survivor
+    pub key: String,
+    pub survivor: String,
+    pub survivor_name: String,
+    pub absorbed: Vec<String>,
+    pub absorbed_names: Vec<String>,
+    pub edges_rewired: usize,
+    pub edges_dropped: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DedupeStats {
+    pub dry_run: bool,
+    pub scanned: usize,
+    /// Duplicate groups found, including any beyond `limit`
+    pub groups_found: usize,
+    pub merged: usize,
+    pub absorbed: usize,
+    pub edges_rewired: usize,
+    pub groups: Vec<MergeGroup>,
+}
+
+/// Minimal union-find over indices.
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+/// Group duplicate entities, survivor first in each group. Groups of one are dropped.
+pub fn plan_merges(
+    rows: &[EntityRow],
+    inbound: &HashMap<String, usize>,
+    survivor: SurvivorRule,
+    fuzzy_threshold: Option<f64>,
+) -> Vec<Vec<usize>> {
+    // Exact (entity_type, normalized name) buckets
+    let mut buckets: BTreeMap<(String, String), Vec<usize>> = BTreeMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        let name = normalize_entity_name(row.name.as_deref().unwrap_or(""));
+        if name.is_empty() {
+            continue;
+        }
+        let entity_type = normalize_entity_type(row.entity_type.as_deref().unwrap_or(""));
+        buckets.entry((entity_type, name)).or_default().push(i);
+    }
+
+    // Optionally join buckets of the same type whose names are close
+    let keys: Vec<(String, String)> = buckets.keys().cloned().collect();
+    let mut parent: Vec<usize> = (0..keys.len()).collect();
+    if let Some(threshold) = fuzzy_threshold {
+        for a in 0..keys.len() {
+            for b in a + 1..keys.len() {
+                if keys[a].0 == keys[b].0
+                    && strsim::jaro_winkler(&keys[a].1, &keys[b].1) >= threshold
+                {
+                    let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+                    parent[rb] = ra;
+                }
+            }
+        }
+    }
+    let mut joined: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (k, members) in buckets.values().enumerate() {
+        let root = find(&mut parent, k);
+        joined.entry(root).or_default().extend(members);
+    }
+
+    let created = |i: usize| rows[i].created_unix.unwrap_or(i64::MAX);
+    joined
+        .into_values()
+        .filter(|g| g.len() > 1)
+        .map(|mut group| {
+            group.sort_by(|&a, &b| {
+                let edges = |i: usize| inbound.get(&rows[i].id).copied().unwrap_or(0);
+                let by_rule = match survivor {
+                    SurvivorRule::Oldest => std::cmp::Ordering::Equal,
+                    SurvivorRule::MostEdges => edges(b).cmp(&edges(a)),
+                };
+                by_rule
+                    .then(created(a).cmp(&created(b)))
+                    .then(rows[a].id.cmp(&rows[b].id))
+            });
+            group
+        })
+        .collect()
+}
+
+/// Survivor `data` plus keys only the absorbed entities have, earlier entities first.
+pub fn merge_data(survivor: &Value, absorbed: &[&Value]) -> Value {
+    let mut merged = match survivor {
+        Value::Object(map) => map.clone(),
+        Value::Null => serde_json::Map::new(),
+        other => return other.clone(),
+    };
+    for data in absorbed {
+        if let Value::Object(map) = data {
+            for (k, v) in map {
+                if !v.is_null() {
+                    merged.entry(k.clone()).or_insert_with(|| v.clone());
+                }
+            }
+        }
+    }
+    Value::Object(merged)
+}
+
+fn record(id: &str) -> DbValue {
+    DbValue::RecordId(RecordId::new("kg_entities", id))
+}
+
+/// Find duplicate entity groups and, unless `dry_run`, merge up to `opts.limit` of them.
+pub async fn dedupe_entities(db: &Surreal<DbEngine>, opts: DedupeOptions) -> Result<DedupeStats> {
+    let rows: Vec<Value> = db.query(ENTITIES_SQL).await?.check()?.take(0)?;
+    let rows: Vec<EntityRow> = rows
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<std::result::Result<_, _>>()?;
+    let targets: Vec<String> = db.query(INBOUND_SQL).await?.check()?.take(0)?;
+    let mut inbound: HashMap<String, usize> = HashMap::new();
+    for t in targets {
+        *inbound.entry(t).or_default() += 1;
+    }
+
+    let plan = plan_merges(&rows, &inbound, opts.survivor, opts.fuzzy_threshold);
+    let mut stats = DedupeStats {
+        dry_run: opts.dry_run,
+        scanned: rows.len(),
+        groups_found: plan.len(),
+        ..DedupeStats::default()
+    };
+
+    for group in plan.into_iter().take(opts.limit) {
+        let survivor = &rows[group[0]];
+        let absorbed: Vec<&EntityRow> = group[1..].iter().map(|&i| &rows[i]).collect();
+        let group_recs: Vec<DbValue> = group.iter().map(|&i| record(&rows[i].id)).collect();
+        let absorbed_recs: Vec<DbValue> = absorbed.iter().map(|r| record(&r.id)).collect();
+
+        let counts: Vec<Value> = db
+            .query(EDGE_COUNTS_SQL)
+            .bind(("group", group_recs.clone()))
+            .bind(("absorbed", absorbed_recs.clone()))
+            .await?
+            .check()?
+            .take(0)?;
+        let count = |key: &str| {
+            counts
+                .first()
+                .and_then(|c| c.get(key))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize
+        };
+        let merge = MergeGroup {
+            entity_type: normalize_entity_type(survivor.entity_type.as_deref().unwrap_or("")),
+            key: normalize_entity_name(survivor.name.as_deref().unwrap_or("")),
+            survivor: survivor.id.clone(),
+            survivor_name: survivor.name.clone().unwrap_or_default(),
+            absorbed: absorbed.iter().map(|r| r.id.clone()).collect(),
+            absorbed_names: absorbed
+                .iter()
+                .map(|r| r.name.clone().unwrap_or_default())
+                .collect(),
+            edges_rewired: count("rewired"),
+            edges_dropped: count("dropped"),
+        };
+
+        if !opts.dry_run {
+            let absorbed_data: Vec<&Value> = absorbed.iter().map(|r| &r.data).collect();
+            db.query(MERGE_SQL)
+                .bind(("group", group_recs))
+                .bind(("absorbed", absorbed_recs))
+                .bind(("survivor", record(&survivor.id)))
+                .bind(("data", merge_data(&survivor.data, &absorbed_data)))
+                .bind((
+                    "reason",
+                    format!("dedupe_entities: merged into kg_entities:{}", survivor.id),
+                ))
+                .await?
+                .check()?;
+        }
+        stats.merged += 1;
+        stats.absorbed += merge.absorbed.len();
+        stats.edges_rewired += merge.edges_rewired;
+        stats.groups.push(merge);
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entity(id: &str, name: &str, entity_type: &str, created: i64) -> EntityRow {
+        EntityRow {
+            id: id.into(),
+            name: Some(name.into()),
+            entity_type: Some(entity_type.into()),
+            data: Value::Null,
+            created_unix: Some(created),
+        }
+    }
+
+    fn ids(rows: &[EntityRow], plan: &[Vec<usize>]) -> Vec<Vec<String>> {
+        plan.iter()
+            .map(|g| g.iter().map(|&i| rows[i].id.clone()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_normalize_entity_name_collapses_separators() {
+        for name in [
+            "surreal-mind",
+            "Surreal Mind",
+            "surreal_mind",
+            "  Surreal--Mind! ",
+        ] {
+            assert_eq!(normalize_entity_name(name), "surreal mind", "{name}");
+        }
+    }
+
+    #[test]
+    fn test_plan_merges_groups_exact_normalized_names_per_type() {
+        let rows = vec![
+            entity("b", "Surreal Mind", "project", 20),
+            entity("a", "surreal-mind", "project", 10),
+            entity("c", "surreal_mind", "Project", 30),
+            entity("d", "surreal mind", "tool", 5),
+            entity("e", "SurrealDB", "project", 1),
+        ];
+        let plan = plan_merges(&rows, &HashMap::new(), SurvivorRule::Oldest, None);
+        assert_eq!(ids(&rows, &plan), vec![vec!["a", "b", "c"]]);
+
+        let inbound = HashMap::from([("c".to_string(), 3), ("b".to_string(), 1)]);
+        let plan = plan_merges(&rows, &inbound, SurvivorRule::MostEdges, None);
+        assert_eq!(ids(&rows, &plan), vec![vec!["c", "b", "a"]]);
+    }
+
+    #[test]
+    fn test_plan_merges_fuzzy_threshold_widens_groups() {
+        let rows = vec![
+            entity("a", "surreal mind", "project", 1),
+            entity("b", "surrealmind", "project", 2),
+            entity("c", "gardening", "project", 3),
+        ];
+        assert!(plan_merges(&rows, &HashMap::new(), SurvivorRule::Oldest, None).is_empty());
+        let plan = plan_merges(&rows, &HashMap::new(), SurvivorRule::Oldest, Some(0.9));
+        assert_eq!(ids(&rows, &plan), vec![vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn test_merge_data_keeps_survivor_values() {
+        let survivor = json!({"entity_type": "project", "description": "kept"});
+        let older = json!({"description": "dropped", "repo": "8agana/surreal-mind", "empty": null});
+        let newer = json!({"repo": "other", "language": "rust"});
+        assert_eq!(
+            merge_data(&survivor, &[&older, &newer]),
+            json!({"entity_type": "project", "description": "kept", "repo": "8agana/surreal-mind", "language": "rust"})
+        );
+        assert_eq!(merge_data(&Value::Null, &[&newer]), newer);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_counts_inbound_edges_past_legacy_string_targets() {
+        let server = crate::test_support::mem_server().await;
+        server
+            .db
+            .query(
+                "CREATE kg_entities:old SET name = 'Ada', entity_type = 'person', data = {}, created_at = d'2026-01-01T00:00:00Z';
+                 CREATE kg_entities:new SET name = 'ada', entity_type = 'person', data = {}, created_at = d'2026-02-01T00:00:00Z';
+                 CREATE kg_edges SET source = kg_entities:old, target = kg_entities:new, rel_type = 'knows', data = {};
+                 CREATE kg_edges SET source = kg_entities:old, target = 'Legacy Tool', rel_type = 'uses', data = {};",
+            )
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        let opts = DedupeOptions {
+            survivor: SurvivorRule::MostEdges,
+            fuzzy_threshold: None,
+            limit: 10,
+            dry_run: true,
+        };
+        let stats = dedupe_entities(&server.db.client(), opts).await.unwrap();
+        assert_eq!(stats.groups_found, 1);
+        assert_eq!(stats.groups[0].survivor, "new");
+        assert_eq!(stats.groups[0].absorbed, ["old"]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Knowledge graph entity type normalization.
+//!
+//! Entity types drift ("person", "Person", "people") when nothing checks them against the
+//! `[kg]` taxonomy. [`normalize_entity_types`] groups entities whose stored type is not a
+//! taxonomy entry in stored form and, unless dry-running, rewrites each group to its
+//! normalized form when that is in the taxonomy, or else to the nearest entry by
+//! Jaro-Winkler when it is close enough. The original value is kept in
+//! `data.entity_type_raw`; groups with no close entry are only reported.
+
+use crate::config::KgConfig;
+use crate::server::connection::DbEngine;
+use crate::tools::knowledge_graph::{nearest_entity_type, normalize_entity_type};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use surrealdb::Surreal;
+use surrealdb::types::{RecordId, Value};
+
+/// Similarity an off-taxonomy type needs to its nearest entry to be rewritten to it.
+pub const DEFAULT_FIX_THRESHOLD: f64 = 0.85;
+
+const TYPES_SQL: &str = "SELECT meta::id(id) AS id, entity_type, \
+    data.entity_type AS data_type FROM kg_entities";
+
+const FIX_SQL: &str = "UPDATE kg_entities SET entity_type = $to, data.entity_type = $to, \
+    data.entity_type_raw = $raw WHERE id IN $recs RETURN VALUE meta::id(id)";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypeRow {
+    pub id: String,
+    #[serde(default)]
+    pub entity_type: Option<String>,
+    #[serde(default)]
+    pub data_type: Option<String>,
+}
+
+/// Entities sharing one off-taxonomy type.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeGroup {
+    /// Stored type: `data.entity_type`, else the top-level `entity_type`
+    pub entity_type: String,
+    pub count: usize,
+    pub nearest: Option<String>,
+    pub similarity: Option<f64>,
+    /// Taxonomy type the group is rewritten to; `None` when no entry is close enough
+    pub fix: Option<String>,
+    #[serde(skip)]
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct EntityTypeStats {
+    pub dry_run: bool,
+    pub scanned: usize,
+    /// Entities whose type is already a taxonomy entry
+    pub on_taxonomy: usize,
+    /// Entities with no type at all
+    pub untyped: usize,
+    /// Entities rewritten to a taxonomy type (or that would be, on a dry run)
+    pub fixed: usize,
+    pub groups: Vec<TypeGroup>,
+}
+
+/// Sort rows into taxonomy, untyped, and off-taxonomy groups (ordered by stored type).
+pub fn plan_entity_types(rows: &[TypeRow], kg: &KgConfig, threshold: f64) -> EntityTypeStats {
+    let mut stats = EntityTypeStats {
+        scanned: rows.len(),
+        ..EntityTypeStats::default()
+    };
+    let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for row in rows {
+        let stored = [&row.data_type, &row.entity_type]
+            .into_iter()
+            .flatten()
+            .map(|t| t.as_str())
+            .find(|t| !t.trim().is_empty());
+        match stored {
+            None => stats.untyped += 1,
+            Some(t) if kg.has_entity_type(t) => stats.on_taxonomy += 1,
+            Some(t) => groups.entry(t).or_default().push(row.id.clone()),
+        }
+    }
+    stats.groups = groups
+        .into_iter()
+        .map(|(entity_type, ids)| {
+            let nearest = nearest_entity_type(entity_type, &kg.entity_types);
+            let normalized = normalize_entity_type(entity_type);
+            let fix = if kg.has_entity_type(&normalized) {
+                Some(normalized)
+            } else {
+                nearest
+                    .filter(|(_, sim)| *sim >= threshold)
+                    .map(|(t, _)| t.to_string())
+            };
+            TypeGroup {
+                entity_type: entity_type.to_string(),
+                count: ids.len(),
+                nearest: nearest.map(|(t, _)| t.to_string()),
+                similarity: nearest.map(|(_, sim)| sim),
+                fix,
+                ids,
+            }
+        })
+        .collect();
+    stats.fixed = stats
+        .groups
+        .iter()
+        .filter(|g| g.fix.is_some())
+        .map(|g| g.count)
+        .sum();
+    stats
+}
+
+/// Report off-taxonomy entity types and, unless `dry_run`, rewrite the fixable ones.
+pub async fn normalize_entity_types(
+    db: &Surreal<DbEngine>,
+    kg: &KgConfig,
+    threshold: f64,
+    dry_run: bool,
+) -> Result<EntityTypeStats> {
+    let rows: Vec<serde_json::Value> = db.query(TYPES_SQL).await?.check()?.take(0)?;
+    let rows: Vec<TypeRow> = rows
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<std::result::Result<_, _>>()?;
+    let mut stats = plan_entity_types(&rows, kg, threshold);
+    stats.dry_run = dry_run;
+    if dry_run {
+        return Ok(stats);
+    }
+
+    stats.fixed = 0;
+    for group in &stats.groups {
+        let Some(to) = &group.fix else { continue };
+        let recs: Vec<Value> = group
+            .ids
+            .iter()
+            .map(|id| Value::RecordId(RecordId::new("kg_entities", id.as_str())))
+            .collect();
+        let updated: Vec<String> = db
+            .query(FIX_SQL)
+            .bind(("to", to.clone()))
+            .bind(("raw", group.entity_type.clone()))
+            .bind(("recs", recs))
+            .await?
+            .check()?
+            .take(0)?;
+        stats.fixed += updated.len();
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: &str, entity_type: Option<&str>, data_type: Option<&str>) -> TypeRow {
+        TypeRow {
+            id: id.into(),
+            entity_type: entity_type.map(String::from),
+            data_type: data_type.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_plan_entity_types_groups_off_taxonomy_values() {
+        let rows = vec![
+            row("a", Some("person"), Some("person")),
+            row("b", Some("person"), Some("Person")),
+            row("c", None, Some("Person")),
+            row("d", Some("persons"), None),
+            row("e", Some("galaxy"), None),
+            row("f", Some(""), None),
+        ];
+        let stats = plan_entity_types(&rows, &KgConfig::default(), DEFAULT_FIX_THRESHOLD);
+        assert_eq!((stats.scanned, stats.on_taxonomy, stats.untyped), (6, 1, 1));
+
+        let summary: Vec<(&str, usize, Option<&str>)> = stats
+            .groups
+            .iter()
+            .map(|g| (g.entity_type.as_str(), g.count, g.fix.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Person", 2, Some("person")),
+                ("galaxy", 1, None),
+                ("persons", 1, Some("person")),
+            ]
+        );
+        assert_eq!(stats.groups[0].ids, ["b", "c"]);
+        assert!(stats.groups[1].nearest.is_some());
+        assert_eq!(stats.fixed, 3);
+    }
+}
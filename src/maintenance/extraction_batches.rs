@@ -0,0 +1,91 @@
+//! KG extraction batch replay.
+//!
+//! `kg_populate` stamps `extraction_batch_id` on every thought and KG row it writes.
+//! [`reopen_batch`] finds the thoughts of one batch and clears their `extracted_to_kg` flag
+//! so they can be extracted again, and [`mark_batch_superseded`] tags the rows the old batch
+//! produced with the batch that replaced them.
+
+use crate::server::connection::DbEngine;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::Surreal;
+
+/// Tables holding rows written by an extraction batch.
+pub const EXTRACTION_TABLES: [&str; 4] = [
+    "kg_entities",
+    "kg_edges",
+    "kg_observations",
+    "kg_boundaries",
+];
+
+const BATCH_THOUGHTS_SQL: &str = "SELECT meta::id(id) AS id, content, created_at FROM thoughts \
+    WHERE extraction_batch_id = $batch ORDER BY created_at ASC";
+
+const REOPEN_SQL: &str = "UPDATE thoughts SET extracted_to_kg = false \
+    WHERE extraction_batch_id = $batch RETURN NONE";
+
+/// A thought extracted in a batch.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BatchThought {
+    pub id: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+/// Thoughts extracted in `batch_id`, oldest first.
+pub async fn batch_thoughts(db: &Surreal<DbEngine>, batch_id: &str) -> Result<Vec<BatchThought>> {
+    let rows: Vec<serde_json::Value> = db
+        .query(BATCH_THOUGHTS_SQL)
+        .bind(("batch", batch_id.to_string()))
+        .await?
+        .check()?
+        .take(0)?;
+    Ok(rows
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<std::result::Result<_, _>>()?)
+}
+
+/// Thoughts of `batch_id`, with `extracted_to_kg` cleared unless `dry_run`. Errors when the
+/// batch has no thoughts.
+pub async fn reopen_batch(
+    db: &Surreal<DbEngine>,
+    batch_id: &str,
+    dry_run: bool,
+) -> Result<Vec<BatchThought>> {
+    let thoughts = batch_thoughts(db, batch_id).await?;
+    if thoughts.is_empty() {
+        anyhow::bail!("No thoughts were extracted in batch '{}'", batch_id);
+    }
+    if !dry_run {
+        db.query(REOPEN_SQL)
+            .bind(("batch", batch_id.to_string()))
+            .await?
+            .check()?;
+    }
+    Ok(thoughts)
+}
+
+/// Set `superseded_by_batch = new_batch` on KG rows written by `old_batch`, returning how
+/// many rows were tagged.
+pub async fn mark_batch_superseded(
+    db: &Surreal<DbEngine>,
+    old_batch: &str,
+    new_batch: &str,
+) -> Result<usize> {
+    let mut tagged = 0;
+    for table in EXTRACTION_TABLES {
+        let ids: Vec<String> = db
+            .query(format!(
+                "UPDATE {table} SET superseded_by_batch = $new \
+                 WHERE extraction_batch_id = $old RETURN VALUE meta::id(id)"
+            ))
+            .bind(("old", old_batch.to_string()))
+            .bind(("new", new_batch.to_string()))
+            .await?
+            .check()?
+            .take(0)?;
+        tagged += ids.len();
+    }
+    Ok(tagged)
+}
@@ -0,0 +1,203 @@
+//! Language backfill for rows written before detection.
+//!
+//! New thoughts and observations get a `lang` from [`detect_lang`] when they are written.
+//! [`backfill_lang`] fills it in on older rows, `batch_size` rows per query, reading thought
+//! `content` and observation text ([`observation_text`]). Rows that already have a `lang`
+//! are untouched, so repeated runs backfill nothing.
+
+use crate::maintenance::observations::observation_text;
+use crate::server::connection::DbEngine;
+use crate::utils::lang::detect_lang;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use surrealdb::Surreal;
+
+/// Tables carrying a detected `lang`.
+pub const LANG_TABLES: [&str; 2] = ["thoughts", "kg_observations"];
+
+const UPDATE_SQL: &str =
+    "FOR $row IN $rows { UPDATE type::record($tb, $row.id) SET lang = $row.lang RETURN NONE; };";
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TableLangStats {
+    pub table: &'static str,
+    /// Rows given a `lang` (or that would be, on a dry run)
+    pub updated: usize,
+    /// Those rows per detected code
+    pub languages: BTreeMap<&'static str, usize>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct LangBackfillStats {
+    pub dry_run: bool,
+    pub batch_size: usize,
+    pub tables: Vec<TableLangStats>,
+}
+
+fn select_sql(table: &str) -> String {
+    let fields = if table == "kg_observations" {
+        "content, name, data"
+    } else {
+        "content"
+    };
+    format!(
+        "SELECT meta::id(id) AS id, {fields} FROM {table} WHERE lang = NONE \
+         ORDER BY id START $start LIMIT $batch"
+    )
+}
+
+/// Detect and store `lang` on every thought and observation missing one.
+pub async fn backfill_lang(
+    db: &Surreal<DbEngine>,
+    batch_size: usize,
+    dry_run: bool,
+) -> Result<LangBackfillStats> {
+    let batch_size = batch_size.max(1);
+    let mut stats = LangBackfillStats {
+        dry_run,
+        batch_size,
+        tables: Vec::new(),
+    };
+    for table in LANG_TABLES {
+        let mut table_stats = TableLangStats {
+            table,
+            ..Default::default()
+        };
+        let sql = select_sql(table);
+        loop {
+            // Written rows drop out of `lang = NONE`; a dry run has to page past them
+            let start = if dry_run { table_stats.updated } else { 0 };
+            let rows: Vec<Value> = db
+                .query(sql.as_str())
+                .bind(("start", start as i64))
+                .bind(("batch", batch_size as i64))
+                .await?
+                .check()?
+                .take(0)?;
+            if rows.is_empty() {
+                break;
+            }
+            let detected: Vec<Value> = rows
+                .iter()
+                .filter_map(|row| {
+                    let id = row.get("id").and_then(|v| v.as_str())?;
+                    let text = if table == "kg_observations" {
+                        observation_text(row)
+                    } else {
+                        row.get("content")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string()
+                    };
+                    let lang = detect_lang(&text);
+                    *table_stats.languages.entry(lang).or_default() += 1;
+                    Some(json!({"id": id, "lang": lang}))
+                })
+                .collect();
+            if detected.is_empty() {
+                break;
+            }
+            table_stats.updated += detected.len();
+            if !dry_run {
+                db.query(UPDATE_SQL)
+                    .bind(("tb", table))
+                    .bind(("rows", detected))
+                    .await?
+                    .check()?;
+            }
+            if rows.len() < batch_size {
+                break;
+            }
+        }
+        stats.tables.push(table_stats);
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_backfill_lang_detects_missing_rows_in_batches() {
+        let server = crate::test_support::mem_server().await;
+        let db = server.db.client();
+        let thought = |id: &str, content: &str, lang: &str| {
+            format!(
+                "CREATE thoughts:{id} SET content = '{content}', lang = {lang}, \
+                 created_at = time::now(), injection_scale = 0, significance = 0.5, \
+                 access_count = 0, injected_memories = [];"
+            )
+        };
+        let sql = [
+            thought(
+                "en",
+                "The index was rebuilt and the migration is done for this table.",
+                "NONE",
+            ),
+            thought(
+                "de",
+                "Der Index ist neu gebaut und die Migration ist fertig.",
+                "NONE",
+            ),
+            thought("short", "ok", "NONE"),
+            thought(
+                "kept",
+                "The index was rebuilt and the migration is done.",
+                "'fr'",
+            ),
+            "CREATE kg_observations:o1 SET name = 'Migration', \
+             data = {content: 'Миграция завершена, индексы перестроены, это быстро.'};"
+                .to_string(),
+        ]
+        .concat();
+        db.query(sql).await.unwrap().check().unwrap();
+
+        let dry = backfill_lang(&db, 2, true).await.unwrap();
+        assert_eq!(dry.tables[0].updated, 3);
+        assert_eq!(dry.tables[1].updated, 1);
+        let pending: Vec<String> = db
+            .query("SELECT VALUE meta::id(id) FROM thoughts WHERE lang = NONE")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(pending.len(), 3);
+
+        let stats = backfill_lang(&db, 2, false).await.unwrap();
+        assert_eq!(stats, dry_as_live(dry));
+        let langs: Vec<Value> = db
+            .query("SELECT meta::id(id) AS id, lang FROM thoughts ORDER BY id")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        let lang_of = |id: &str| {
+            langs.iter().find(|r| r["id"] == id).unwrap()["lang"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(lang_of("en"), "en");
+        assert_eq!(lang_of("de"), "de");
+        assert_eq!(lang_of("short"), "und");
+        assert_eq!(lang_of("kept"), "fr");
+        let obs: Vec<String> = db
+            .query("SELECT VALUE lang FROM kg_observations:o1")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(obs, vec!["ru".to_string()]);
+
+        let again = backfill_lang(&db, 2, false).await.unwrap();
+        assert!(again.tables.iter().all(|t| t.updated == 0));
+    }
+
+    fn dry_as_live(mut stats: LangBackfillStats) -> LangBackfillStats {
+        stats.dry_run = false;
+        stats
+    }
+}
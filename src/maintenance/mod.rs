@@ -1,11 +1,55 @@
 //! Maintenance operations module.
 //!
 //! This module contains maintenance and administrative functions for the surreal-mind system,
-//! including re-embedding operations for thoughts and knowledge graph entities.
+//! including re-embedding operations for thoughts and knowledge graph entities, the
+//! embedding dimension audit, thought significance decay, continuity link repair,
+//! observation text normalization and provenance reporting, thought soft delete, privacy,
+//! and pinning, content language backfill, provider liveness probes, dual-write embedding
+//! migration, KG entity deduplication and entity type normalization, KG extraction
+//! batch replay, schema drift checks, and namespace snapshots.
 
+pub mod continuity;
+pub mod decay;
+pub mod embedding_audit;
+pub mod embedding_migration;
+pub mod entity_dedupe;
+pub mod entity_types;
+pub mod extraction_batches;
+pub mod lang;
+pub mod observation_provenance;
+pub mod observations;
+pub mod pinning;
+pub mod privacy;
+pub mod providers;
 pub mod reembed;
+pub mod schema_check;
+pub mod snapshot;
+pub mod soft_delete;
 
 // Re-export public items for backwards compatibility
+pub use continuity::{RepairOptions, RepairStats, repair_continuity_links};
+pub use decay::{DecayOptions, DecayStats, decay_thoughts, run_decay_thoughts};
+pub use embedding_migration::{
+    BackfillStats, EmbeddingField, FinalizeStats, MigrationTarget, StoredEmbedding,
+    backfill_embedding_v2, finalize_migration, stored_embeddings,
+};
+pub use entity_dedupe::{DedupeOptions, DedupeStats, SurvivorRule, dedupe_entities};
+pub use entity_types::{EntityTypeStats, normalize_entity_types};
+pub use extraction_batches::{BatchThought, mark_batch_superseded, reopen_batch};
+pub use lang::{LangBackfillStats, backfill_lang};
+pub use observation_provenance::{ProvenanceReport, observation_provenance_report};
+pub use observations::{NormalizeStats, normalize_observations};
+pub use pinning::{PinStats, PinnedThought, set_thought_pinned};
+pub use privacy::{PrivacyStats, set_thought_privacy};
 pub use reembed::{
-    KgEmbedStats, ReembedKgStats, ReembedStats, run_kg_embed, run_reembed, run_reembed_kg,
+    EmbedEdgesStats, KgEmbedStats, ReembedKgStats, ReembedStats, embed_edges, run_kg_embed,
+    run_reembed, run_reembed_kg, run_reembed_with_progress,
+};
+pub use schema_check::{SchemaCheckReport, SchemaDrift, check_schema};
+pub use snapshot::{
+    RestoreOptions, SnapshotCreated, SnapshotEmbedding, SnapshotManifest, SnapshotRestored,
+    create_snapshot, restore_snapshot,
+};
+pub use soft_delete::{
+    PurgeStats, SoftDeleteStats, purge_deleted_thoughts, restore_thoughts, soft_delete_thoughts,
 };
@@ -0,0 +1,210 @@
+//! Observation provenance: the thoughts a `kg_observations` row was derived from.
+//!
+//! New observations store bare thought ids in `source_thought_ids`. Older rows may only carry
+//! a scalar `source_thought_id` at the top level or in `data`; [`observation_source_ids`] reads
+//! every shape. [`resolve_thoughts`] looks the ids up in one query, which `remember` uses to
+//! validate new observations and search uses for the `provenance` of each observation hit
+//! ([`attach_provenance`]). [`observation_provenance_report`] lists rows with no source thought
+//! that still exists.
+
+use super::soft_delete::{normalize_thought_ids, records};
+use crate::server::connection::DbEngine;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use surrealdb::Surreal;
+
+/// Rows read per page by [`observation_provenance_report`]
+const REPORT_PAGE_SIZE: usize = 500;
+
+/// Bare, de-duplicated source thought ids from `source_thought_ids` (array) and
+/// `source_thought_id` (string) on each of `sources`, in order.
+pub fn observation_source_ids(sources: &[&Value]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for source in sources {
+        if let Some(list) = source.get("source_thought_ids").and_then(|v| v.as_array()) {
+            ids.extend(list.iter().filter_map(|v| v.as_str()).map(String::from));
+        }
+        if let Some(id) = source.get("source_thought_id").and_then(|v| v.as_str()) {
+            ids.push(id.to_string());
+        }
+    }
+    normalize_thought_ids(&ids)
+}
+
+/// `created_at` of each id in `ids` that names an existing thought, in one query.
+pub async fn resolve_thoughts(
+    db: &Surreal<DbEngine>,
+    ids: &[String],
+) -> Result<HashMap<String, String>> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let rows: Vec<Value> = db
+        .query(
+            "SELECT meta::id(id) AS id, type::string(created_at) AS created_at \
+             FROM thoughts WHERE id IN $recs",
+        )
+        .bind(("recs", records(ids)))
+        .await?
+        .take(0)?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let id = row.get("id")?.as_str()?;
+            let created_at = row.get("created_at").and_then(|v| v.as_str()).unwrap_or("");
+            Some((id.to_string(), created_at.to_string()))
+        })
+        .collect())
+}
+
+/// `{thought_ids, thoughts: [{id, created_at}], unresolved}` for one observation; ids missing
+/// from `resolved` are listed in `unresolved`.
+pub fn provenance(ids: &[String], resolved: &HashMap<String, String>) -> Value {
+    let (found, unresolved): (Vec<&String>, Vec<&String>) =
+        ids.iter().partition(|id| resolved.contains_key(*id));
+    json!({
+        "thought_ids": ids,
+        "thoughts": found
+            .iter()
+            .map(|id| json!({"id": format!("thoughts:{}", id), "created_at": resolved[*id]}))
+            .collect::<Vec<_>>(),
+        "unresolved": unresolved,
+    })
+}
+
+/// Add a `provenance` object to every observation row, resolving all their source thoughts in
+/// one query. The raw `source_thought_id(s)` columns are dropped from the rows.
+pub async fn attach_provenance(db: &Surreal<DbEngine>, rows: &mut [Value]) -> Result<()> {
+    let ids: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| observation_source_ids(&[row, &row["data"]]))
+        .collect();
+    let all: Vec<String> = normalize_thought_ids(&ids.concat());
+    let resolved = resolve_thoughts(db, &all).await?;
+    for (row, ids) in rows.iter_mut().zip(&ids) {
+        if let Some(obj) = row.as_object_mut() {
+            obj.remove("source_thought_ids");
+            obj.remove("source_thought_id");
+            obj.insert("provenance".into(), provenance(ids, &resolved));
+        }
+    }
+    Ok(())
+}
+
+/// An observation without a resolvable source thought
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnprovenancedObservation {
+    pub id: String,
+    pub name: String,
+    pub source_thought_ids: Vec<String>,
+    /// "missing" (no source ids) or "dangling" (none of its ids is an existing thought)
+    pub reason: &'static str,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ProvenanceReport {
+    pub scanned: usize,
+    pub missing: usize,
+    pub dangling: usize,
+    /// Up to `limit` of the missing and dangling observations, in id order
+    pub observations: Vec<UnprovenancedObservation>,
+    pub truncated: bool,
+}
+
+/// Scan every observation and report those with no source thought that still exists. At most
+/// `limit` rows are listed (0 lists all); the counts always cover the whole table.
+pub async fn observation_provenance_report(
+    db: &Surreal<DbEngine>,
+    limit: usize,
+) -> Result<ProvenanceReport> {
+    let mut report = ProvenanceReport::default();
+    let mut start = 0;
+    loop {
+        let rows: Vec<Value> = db
+            .query(
+                "SELECT meta::id(id) AS id, name, source_thought_ids, source_thought_id, \
+                 { source_thought_id: data.source_thought_id, source_thought_ids: data.source_thought_ids } AS data \
+                 FROM kg_observations ORDER BY id LIMIT $page START $start",
+            )
+            .bind(("page", REPORT_PAGE_SIZE as i64))
+            .bind(("start", start as i64))
+            .await?
+            .take(0)?;
+        let page_len = rows.len();
+        let ids: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| observation_source_ids(&[row, &row["data"]]))
+            .collect();
+        let resolved = resolve_thoughts(db, &normalize_thought_ids(&ids.concat())).await?;
+
+        for (row, ids) in rows.iter().zip(ids) {
+            report.scanned += 1;
+            let reason = if ids.is_empty() {
+                report.missing += 1;
+                "missing"
+            } else if !ids.iter().any(|id| resolved.contains_key(id)) {
+                report.dangling += 1;
+                "dangling"
+            } else {
+                continue;
+            };
+            if limit != 0 && report.observations.len() >= limit {
+                report.truncated = true;
+                continue;
+            }
+            let text = |field: &str| row.get(field).and_then(|v| v.as_str()).unwrap_or("");
+            report.observations.push(UnprovenancedObservation {
+                id: text("id").to_string(),
+                name: text("name").to_string(),
+                source_thought_ids: ids,
+                reason,
+            });
+        }
+
+        if page_len < REPORT_PAGE_SIZE {
+            break;
+        }
+        start += page_len;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observation_source_ids_reads_every_shape() {
+        let row = json!({
+            "source_thought_ids": ["a1", "thoughts:b2"],
+            "source_thought_id": "thoughts:a1",
+            "data": {"source_thought_id": "c3"}
+        });
+        assert_eq!(
+            observation_source_ids(&[&row, &row["data"]]),
+            ["a1", "b2", "c3"]
+        );
+        let legacy = json!({"source_thought_id": "", "data": {}});
+        assert!(observation_source_ids(&[&legacy, &legacy["data"]]).is_empty());
+    }
+
+    #[test]
+    fn test_provenance_splits_resolved_and_unresolved() {
+        let resolved = HashMap::from([("a1".to_string(), "2026-01-02T03:04:05Z".to_string())]);
+        let ids = vec!["a1".to_string(), "gone".to_string()];
+        assert_eq!(
+            provenance(&ids, &resolved),
+            json!({
+                "thought_ids": ["a1", "gone"],
+                "thoughts": [{"id": "thoughts:a1", "created_at": "2026-01-02T03:04:05Z"}],
+                "unresolved": ["gone"]
+            })
+        );
+        assert_eq!(
+            provenance(&[], &resolved),
+            json!({"thought_ids": [], "thoughts": [], "unresolved": []})
+        );
+    }
+}
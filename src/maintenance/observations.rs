@@ -0,0 +1,120 @@
+//! Observation text normalization.
+//!
+//! `kg_observations` rows carry their payload in one of three legacy shapes: a top-level
+//! `content`, a `data.content`, or only `name` plus `data.description`. Search and memory
+//! injection read them through [`OBSERVATION_TEXT_SQL`] (mirrored by [`observation_text`]) so
+//! every path sees the same text, and [`normalize_observations`] backfills that text into `content` on rows
+//! missing it.
+
+use crate::server::connection::DbEngine;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use surrealdb::Surreal;
+
+/// Searchable text of a `kg_observations` row in SurrealQL: `content`, else `data.content`,
+/// else `name - data.description` (or just `name`). Mirrors [`observation_text`].
+pub const OBSERVATION_TEXT_SQL: &str = "(IF type::is_string(content) AND content != '' THEN content \
+    ELSE IF type::is_string(data.content) AND data.content != '' THEN data.content \
+    ELSE IF type::is_string(data.description) AND data.description != '' \
+    THEN string::concat(name ?? '', ' - ', data.description) \
+    ELSE name ?? '' END)";
+
+/// Rows with no usable top-level `content` but some other text to promote.
+const PENDING_WHERE: &str = "(content = NONE OR content = NULL OR content = '')";
+
+/// Searchable text of an observation row (`{content?, name?, data?}`).
+pub fn observation_text(row: &Value) -> String {
+    fn non_empty(v: Option<&Value>) -> Option<&str> {
+        v.and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+    }
+    let data = row.get("data");
+    if let Some(content) = non_empty(row.get("content")) {
+        return content.to_string();
+    }
+    if let Some(content) = non_empty(data.and_then(|d| d.get("content"))) {
+        return content.to_string();
+    }
+    let name = row.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    match non_empty(data.and_then(|d| d.get("description"))) {
+        Some(desc) => format!("{} - {}", name, desc),
+        None => name.to_string(),
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct NormalizeStats {
+    pub dry_run: bool,
+    /// Rows given a `content` field (or that would be, on a dry run)
+    pub backfilled: usize,
+    /// Rows without `content` and with no other text to promote
+    pub empty: usize,
+}
+
+/// Copy each observation's searchable text into `content` where it is missing. Rows that
+/// already have `content` are untouched, so repeated runs backfill nothing.
+pub async fn normalize_observations(
+    db: &Surreal<DbEngine>,
+    dry_run: bool,
+) -> Result<NormalizeStats> {
+    let count_sql = format!(
+        "SELECT count({text} != '') AS backfill, count({text} = '') AS empty \
+         FROM kg_observations WHERE {PENDING_WHERE} GROUP ALL",
+        text = OBSERVATION_TEXT_SQL
+    );
+    let counts: Vec<Value> = db.query(count_sql).await?.check()?.take(0)?;
+    let count = |key: &str| {
+        counts
+            .first()
+            .and_then(|c| c.get(key))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize
+    };
+    let mut stats = NormalizeStats {
+        dry_run,
+        backfilled: count("backfill"),
+        empty: count("empty"),
+    };
+    if dry_run || stats.backfilled == 0 {
+        return Ok(stats);
+    }
+
+    let update_sql = format!(
+        "UPDATE kg_observations SET content = {text} \
+         WHERE {PENDING_WHERE} AND {text} != '' RETURN VALUE meta::id(id)",
+        text = OBSERVATION_TEXT_SQL
+    );
+    let updated: Vec<String> = db.query(update_sql).await?.check()?.take(0)?;
+    stats.backfilled = updated.len();
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_observation_text_covers_legacy_shapes() {
+        let cases = [
+            (
+                json!({"content": "top", "name": "n", "data": {"content": "nested"}}),
+                "top",
+            ),
+            (
+                json!({"content": "", "name": "n", "data": {"content": "nested"}}),
+                "nested",
+            ),
+            (
+                json!({"name": "n", "data": {"description": "desc"}}),
+                "n - desc",
+            ),
+            (json!({"name": "n", "data": {"description": ""}}), "n"),
+            (json!({"data": {"description": "desc"}}), " - desc"),
+            (json!({}), ""),
+        ];
+        for (row, expected) in cases {
+            assert_eq!(observation_text(&row), expected, "row: {row}");
+        }
+    }
+}
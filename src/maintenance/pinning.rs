@@ -0,0 +1,173 @@
+//! Thought pinning.
+//!
+//! Pinned thoughts (`pinned = true`) are injected into every `think` whose submode and tags
+//! they match, ahead of and regardless of KG similarity; see [`pinned_for_injection`].
+//! `think` sets the flag at creation and [`set_thought_pinned`] flips it on existing
+//! thoughts. Private and soft-deleted thoughts are never injected.
+
+use crate::maintenance::soft_delete::{normalize_thought_ids, records};
+use crate::server::connection::DbEngine;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use surrealdb::Surreal;
+
+/// Which of the requested thoughts exist and whether each is pinned.
+const STATE_SQL: &str = "SELECT meta::id(id) AS id, pinned = true AS pinned \
+    FROM thoughts WHERE id IN $recs";
+
+const SET_SQL: &str =
+    "UPDATE thoughts SET pinned = $pinned WHERE id IN $recs RETURN VALUE meta::id(id)";
+
+/// Injectable pinned thoughts, newest first.
+const PINNED_SQL: &str = "SELECT meta::id(id) AS id, content, submode, tags, created_at \
+    FROM thoughts WHERE pinned = true AND deleted_at = NONE AND is_private != true \
+    ORDER BY created_at DESC";
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct PinStats {
+    pub dry_run: bool,
+    /// The flag value requested
+    pub pinned: bool,
+    pub requested: usize,
+    /// Thoughts whose flag changed (or would, on a dry run)
+    pub updated: Vec<String>,
+    /// Thoughts already set as requested
+    pub unchanged: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateRow {
+    id: String,
+    pinned: bool,
+}
+
+/// A pinned thought as memory injection sees it.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PinnedThought {
+    pub id: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub submode: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+impl PinnedThought {
+    /// A pin applies when its submode (if any) is the current one and, if it has tags and
+    /// the current thought does too, they share one. Untagged pins apply everywhere.
+    pub fn matches(&self, submode: Option<&str>, tags: &[String]) -> bool {
+        let submode_ok = match (self.submode.as_deref(), submode) {
+            (Some(pin), Some(current)) => pin == current,
+            _ => true,
+        };
+        let pin_tags = self.tags.as_deref().unwrap_or_default();
+        let tags_ok =
+            pin_tags.is_empty() || tags.is_empty() || pin_tags.iter().any(|t| tags.contains(t));
+        submode_ok && tags_ok
+    }
+}
+
+/// Set `pinned` to `pinned` on `ids`.
+pub async fn set_thought_pinned(
+    db: &Surreal<DbEngine>,
+    ids: &[String],
+    pinned: bool,
+    dry_run: bool,
+) -> Result<PinStats> {
+    let ids = normalize_thought_ids(ids);
+    let mut stats = PinStats {
+        dry_run,
+        pinned,
+        requested: ids.len(),
+        ..PinStats::default()
+    };
+    if ids.is_empty() {
+        return Ok(stats);
+    }
+
+    let rows: Vec<serde_json::Value> = db
+        .query(STATE_SQL)
+        .bind(("recs", records(&ids)))
+        .await?
+        .check()?
+        .take(0)?;
+    let rows: Vec<StateRow> = rows
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<std::result::Result<_, _>>()?;
+    let found: HashSet<&str> = rows.iter().map(|r| r.id.as_str()).collect();
+    stats.missing = ids
+        .iter()
+        .filter(|id| !found.contains(id.as_str()))
+        .cloned()
+        .collect();
+
+    let (pending, unchanged): (Vec<StateRow>, Vec<StateRow>) =
+        rows.into_iter().partition(|r| r.pinned != pinned);
+    stats.unchanged = unchanged.into_iter().map(|r| r.id).collect();
+    let pending: Vec<String> = pending.into_iter().map(|r| r.id).collect();
+    stats.updated = if dry_run || pending.is_empty() {
+        pending
+    } else {
+        db.query(SET_SQL)
+            .bind(("recs", records(&pending)))
+            .bind(("pinned", pinned))
+            .await?
+            .check()?
+            .take(0)?
+    };
+    Ok(stats)
+}
+
+/// Up to `max` newest pinned thoughts matching `submode` and `tags`, other than `exclude_id`.
+pub async fn pinned_for_injection(
+    db: &Surreal<DbEngine>,
+    exclude_id: &str,
+    submode: Option<&str>,
+    tags: &[String],
+    max: usize,
+) -> Result<Vec<PinnedThought>> {
+    if max == 0 {
+        return Ok(Vec::new());
+    }
+    let rows: Vec<serde_json::Value> = db.query(PINNED_SQL).await?.check()?.take(0)?;
+    let rows: Vec<PinnedThought> = rows
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<std::result::Result<_, _>>()?;
+    Ok(rows
+        .into_iter()
+        .filter(|p| p.id != exclude_id && p.matches(submode, tags))
+        .take(max)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pin(submode: Option<&str>, tags: &[&str]) -> PinnedThought {
+        PinnedThought {
+            id: "p".into(),
+            content: "never force-push main".into(),
+            submode: submode.map(String::from),
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_pin_matches_submode_and_tags() {
+        let tags = vec!["git".to_string(), "ci".to_string()];
+        assert!(pin(None, &[]).matches(Some("debug"), &tags));
+        assert!(pin(None, &["git"]).matches(None, &tags));
+        assert!(!pin(None, &["db"]).matches(None, &tags));
+        // An untagged thought sees every pin
+        assert!(pin(None, &["db"]).matches(None, &[]));
+        assert!(pin(Some("debug"), &[]).matches(Some("debug"), &tags));
+        assert!(!pin(Some("plan"), &[]).matches(Some("debug"), &tags));
+        assert!(pin(Some("plan"), &[]).matches(None, &tags));
+    }
+}
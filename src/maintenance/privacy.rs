@@ -0,0 +1,214 @@
+//! Thought privacy.
+//!
+//! Thoughts with `is_private = true` are left out of search, chain summaries, and wander
+//! unless the caller passes `include_private` (those queries share [`privacy_filter`]), and
+//! are never offered as near-duplicates. `think` sets the flag at creation and
+//! [`set_thought_privacy`] flips it on existing thoughts.
+
+use crate::maintenance::soft_delete::{normalize_thought_ids, records};
+use crate::server::connection::DbEngine;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use surrealdb::Surreal;
+
+/// Which of the requested thoughts exist and whether each is private.
+const STATE_SQL: &str = "SELECT meta::id(id) AS id, is_private = true AS private \
+    FROM thoughts WHERE id IN $recs";
+
+const SET_SQL: &str =
+    "UPDATE thoughts SET is_private = $private WHERE id IN $recs RETURN VALUE meta::id(id)";
+
+/// SurrealQL condition that hides private rows unless `include_private`. Rows of other
+/// tables have no `is_private` and always pass.
+pub fn privacy_filter(include_private: bool) -> &'static str {
+    if include_private {
+        "true"
+    } else {
+        "is_private != true"
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct PrivacyStats {
+    pub dry_run: bool,
+    /// The flag value requested
+    pub private: bool,
+    pub requested: usize,
+    /// Thoughts whose flag changed (or would, on a dry run)
+    pub updated: Vec<String>,
+    /// Thoughts already set as requested
+    pub unchanged: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateRow {
+    id: String,
+    private: bool,
+}
+
+/// Set `is_private` to `private` on `ids`.
+pub async fn set_thought_privacy(
+    db: &Surreal<DbEngine>,
+    ids: &[String],
+    private: bool,
+    dry_run: bool,
+) -> Result<PrivacyStats> {
+    let ids = normalize_thought_ids(ids);
+    let mut stats = PrivacyStats {
+        dry_run,
+        private,
+        requested: ids.len(),
+        ..PrivacyStats::default()
+    };
+    if ids.is_empty() {
+        return Ok(stats);
+    }
+
+    let rows: Vec<serde_json::Value> = db
+        .query(STATE_SQL)
+        .bind(("recs", records(&ids)))
+        .await?
+        .check()?
+        .take(0)?;
+    let rows: Vec<StateRow> = rows
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<std::result::Result<_, _>>()?;
+    let found: HashSet<&str> = rows.iter().map(|r| r.id.as_str()).collect();
+    stats.missing = ids
+        .iter()
+        .filter(|id| !found.contains(id.as_str()))
+        .cloned()
+        .collect();
+
+    let (pending, unchanged): (Vec<StateRow>, Vec<StateRow>) =
+        rows.into_iter().partition(|r| r.private != private);
+    stats.unchanged = unchanged.into_iter().map(|r| r.id).collect();
+    let pending: Vec<String> = pending.into_iter().map(|r| r.id).collect();
+    stats.updated = if dry_run || pending.is_empty() {
+        pending
+    } else {
+        db.query(SET_SQL)
+            .bind(("recs", records(&pending)))
+            .bind(("private", private))
+            .await?
+            .check()?
+            .take(0)?
+    };
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::SurrealMindServer;
+    use crate::test_support::{HashEmbedder, TEST_EMBED_DIM, call, mem_server};
+    use serde_json::json;
+
+    #[test]
+    fn test_privacy_filter() {
+        assert_eq!(privacy_filter(false), "is_private != true");
+        assert_eq!(privacy_filter(true), "true");
+    }
+
+    async fn think(server: &SurrealMindServer, is_private: bool) -> String {
+        let out = server
+            .handle_legacymind_think(call(
+                "think",
+                json!({"content": format!("A note on cache sizing, private: {is_private}"),
+                       "session_id": "s1", "chain_id": "c1", "pinned": true,
+                       "injection_scale": 0, "is_private": is_private}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        out["delegated_result"]["thought_id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    async fn search(server: &SurrealMindServer, args: serde_json::Value) -> serde_json::Value {
+        server
+            .handle_unified_search(call("search", args))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap()
+    }
+
+    async fn search_ids(server: &SurrealMindServer, include_private: bool) -> Vec<String> {
+        let out = search(
+            server,
+            json!({"include_thoughts": true, "target": "thoughts", "session_id": "s1",
+                   "include_private": include_private}),
+        )
+        .await;
+        let mut ids: Vec<String> = out["thoughts"]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    async fn injected_pins(server: &SurrealMindServer) -> Vec<String> {
+        let emb = HashEmbedder {
+            dim: TEST_EMBED_DIM,
+        }
+        .vector("cache sizing");
+        let mut pinned = server
+            .inject_memories("other", &emb, 1, None, &[], None)
+            .await
+            .unwrap()
+            .pinned;
+        pinned.sort();
+        pinned
+    }
+
+    #[tokio::test]
+    async fn test_private_thoughts_hidden_unless_included() {
+        let server = mem_server().await;
+        let public = think(&server, false).await;
+        let private = think(&server, true).await;
+        let mut both = vec![public.clone(), private.clone()];
+        both.sort();
+
+        assert_eq!(search_ids(&server, false).await, vec![public.clone()]);
+        assert_eq!(search_ids(&server, true).await, both);
+        for (include_private, count) in [(false, 1), (true, 2)] {
+            let out = search(
+                &server,
+                json!({"chain_summary": true, "chain_id": "c1", "include_private": include_private}),
+            )
+            .await;
+            assert_eq!(out["chain_summary"]["count"], json!(count));
+        }
+        assert_eq!(
+            injected_pins(&server).await,
+            vec![format!("thoughts:{public}")]
+        );
+
+        let out = server
+            .handle_maintenance_ops(call(
+                "maintain",
+                json!({"subcommand": "set_privacy", "ids": [format!("thoughts:{private}"), "missing"],
+                       "private": false}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        assert_eq!(out["stats"]["updated"], json!([private]));
+        assert_eq!(out["stats"]["missing"], json!(["missing"]));
+        assert_eq!(search_ids(&server, false).await, both);
+        let mut pins: Vec<String> = both.iter().map(|id| format!("thoughts:{id}")).collect();
+        pins.sort();
+        assert_eq!(injected_pins(&server).await, pins);
+    }
+}
@@ -0,0 +1,342 @@
+//! Provider liveness probes for `maintain providers_check`
+//!
+//! Each probe is the cheapest call that proves a provider still works: a one-word embed for
+//! the embedder, and `gemini --version` plus a credential lookup for the Gemini CLI (no
+//! prompt is sent). Every probe is bounded by a timeout and reports `configured`,
+//! `reachable`, `latency_ms`, and on failure an `error_class` with a scrubbed `error`.
+//! Configured key values and anything matching the default `[security.redaction]` patterns
+//! are removed from error text, so the report never echoes a secret.
+
+use crate::clients::gemini::GeminiClient;
+use crate::clients::traits::AgentError;
+use crate::config::RedactionConfig;
+use crate::embeddings::Embedder;
+use crate::utils::redaction::Redactor;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+
+/// Providers `providers_check` knows how to probe, in report order.
+pub const PROVIDERS: [&str; 2] = ["embedder", "gemini"];
+
+/// Upper bound on a single probe.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProviderStatus {
+    pub provider: &'static str,
+    pub configured: bool,
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_class: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<Value>,
+}
+
+impl ProviderStatus {
+    fn healthy(&self) -> bool {
+        self.skipped || (self.configured && self.reachable && self.error_class.is_none())
+    }
+}
+
+/// Removes secrets from probe error text.
+#[derive(Debug, Clone)]
+pub struct SecretScrubber {
+    redactor: Redactor,
+    secrets: Vec<String>,
+}
+
+impl SecretScrubber {
+    /// Scrub the exact `secrets` (blank ones are ignored) and the default redaction patterns.
+    pub fn new(secrets: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            redactor: Redactor::from_config(&RedactionConfig::default()).unwrap_or_default(),
+            secrets: secrets
+                .into_iter()
+                .filter(|s| !s.trim().is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn scrub(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for secret in &self.secrets {
+            out = out.replace(secret.as_str(), "«redacted:secret»");
+        }
+        self.redactor.redact(&out).0
+    }
+}
+
+/// Coarse failure class from an embedder error chain.
+fn embed_error_class(message: &str) -> &'static str {
+    let lower = message.to_ascii_lowercase();
+    if lower.contains("rejected the api key") || lower.contains("api_key is not set") {
+        "auth"
+    } else if lower.contains("429") || lower.contains("rate limit") {
+        "rate_limited"
+    } else if lower.contains("404") {
+        "not_found"
+    } else if lower.contains("failed to send") {
+        "unreachable"
+    } else {
+        "error"
+    }
+}
+
+/// Embed one word through `embedder` and check the vector's dimension.
+pub async fn probe_embedder(
+    embedder: &dyn Embedder,
+    provider: &str,
+    timeout: Duration,
+    scrubber: &SecretScrubber,
+) -> ProviderStatus {
+    let mut status = ProviderStatus {
+        provider: "embedder",
+        configured: !provider.is_empty(),
+        detail: Some(json!({"provider": provider, "dimensions": embedder.dimensions()})),
+        ..ProviderStatus::default()
+    };
+    let start = Instant::now();
+    let result = tokio::time::timeout(timeout, embedder.embed("ping")).await;
+    status.latency_ms = Some(start.elapsed().as_millis() as u64);
+    match result {
+        Ok(Ok(vector)) => {
+            status.reachable = true;
+            if vector.len() != embedder.dimensions() {
+                status.error_class = Some("dimension_mismatch");
+                status.error = Some(format!(
+                    "returned {} dimensions, expected {}",
+                    vector.len(),
+                    embedder.dimensions()
+                ));
+            }
+        }
+        Ok(Err(e)) => {
+            let message = format!("{:#}", e);
+            status.error_class = Some(embed_error_class(&message));
+            status.error = Some(scrubber.scrub(&message));
+        }
+        Err(_) => {
+            status.error_class = Some("timeout");
+            status.error = Some(format!("no response within {}ms", timeout.as_millis()));
+        }
+    }
+    status
+}
+
+/// Run the Gemini CLI's version and credential check.
+pub async fn probe_gemini(client: &GeminiClient, scrubber: &SecretScrubber) -> ProviderStatus {
+    let mut status = ProviderStatus {
+        provider: "gemini",
+        ..ProviderStatus::default()
+    };
+    let start = Instant::now();
+    let result = client.check_auth().await;
+    status.latency_ms = Some(start.elapsed().as_millis() as u64);
+    match result {
+        Ok(check) => {
+            status.reachable = true;
+            status.configured = check.auth.is_some();
+            if check.auth.is_none() {
+                status.error_class = Some("no_credentials");
+                status.error = Some(
+                    "no GEMINI_API_KEY, GOOGLE_API_KEY, Vertex AI, or cached OAuth login".into(),
+                );
+            }
+            status.detail = Some(json!({"version": check.version, "auth": check.auth}));
+        }
+        Err(e) => {
+            status.error_class = Some(match e {
+                AgentError::NotFound => "not_installed",
+                AgentError::Timeout { .. } => "timeout",
+                _ => "cli_error",
+            });
+            status.error = Some(scrubber.scrub(&e.to_string()));
+        }
+    }
+    status
+}
+
+/// Probe every provider in [`PROVIDERS`] not named in `skip`, concurrently, and report
+/// `{ok, providers}` where `ok` means every probed provider is configured and reachable.
+pub async fn providers_check(
+    embedder: &dyn Embedder,
+    embedding_provider: &str,
+    gemini: &GeminiClient,
+    skip: &[String],
+    scrubber: &SecretScrubber,
+) -> Value {
+    let skipped = |name: &'static str| ProviderStatus {
+        provider: name,
+        skipped: true,
+        ..ProviderStatus::default()
+    };
+    let is_skipped = |name: &str| skip.iter().any(|s| s == name);
+    let embed = async {
+        if is_skipped("embedder") {
+            skipped("embedder")
+        } else {
+            probe_embedder(embedder, embedding_provider, PROBE_TIMEOUT, scrubber).await
+        }
+    };
+    let gem = async {
+        if is_skipped("gemini") {
+            skipped("gemini")
+        } else {
+            probe_gemini(gemini, scrubber).await
+        }
+    };
+    let (embed, gem) = tokio::join!(embed, gem);
+    let providers = [embed, gem];
+    json!({
+        "ok": providers.iter().all(ProviderStatus::healthy),
+        "providers": providers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::HashEmbedder;
+    use async_trait::async_trait;
+    use std::os::unix::fs::PermissionsExt;
+
+    const SECRET: &str = "sk-live-0123456789abcdefghijklmnop";
+
+    struct RejectingEmbedder;
+
+    #[async_trait]
+    impl Embedder for RejectingEmbedder {
+        async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+            anyhow::bail!(
+                "OpenAI API rejected the API key (401 Unauthorized) for model 'm': Incorrect API key provided: {}",
+                SECRET
+            )
+        }
+
+        fn dimensions(&self) -> usize {
+            8
+        }
+    }
+
+    /// A fake `gemini` executable; each gets its own file so none is rewritten while running.
+    fn script(dir: &tempfile::TempDir, body: &str) -> std::path::PathBuf {
+        static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let path = dir.path().join(format!("gemini-{}", n));
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn client(binary: std::path::PathBuf) -> GeminiClient {
+        GeminiClient::with_timeout_ms("auto", 5_000).with_binary(binary)
+    }
+
+    #[tokio::test]
+    async fn test_embedder_probe_reports_success_and_scrubbed_failure() {
+        let scrubber = SecretScrubber::new([SECRET.to_string()]);
+        let ok = probe_embedder(&HashEmbedder { dim: 8 }, "openai", PROBE_TIMEOUT, &scrubber).await;
+        assert!(ok.configured && ok.reachable && ok.error_class.is_none());
+        assert!(ok.latency_ms.is_some());
+
+        let failed = probe_embedder(&RejectingEmbedder, "openai", PROBE_TIMEOUT, &scrubber).await;
+        assert!(failed.configured && !failed.reachable);
+        assert_eq!(failed.error_class, Some("auth"));
+        let error = failed.error.unwrap();
+        assert!(!error.contains(SECRET), "{error}");
+        assert!(error.contains("«redacted:secret»"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_gemini_probe_runs_version_check_without_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let ok = probe_gemini(
+            &client(script(&dir, "echo 0.9.1")),
+            &SecretScrubber::new([]),
+        )
+        .await;
+        assert!(ok.reachable, "{ok:?}");
+        assert_eq!(ok.detail.as_ref().unwrap()["version"], "0.9.1");
+
+        let key = "AIzaSyD-0123456789abcdefghijklmnopqrs";
+        let broken = script(
+            &dir,
+            &format!(
+                "echo 'invalid key {} (Bearer abcdefghijklmnopqrstuv)' >&2; exit 1",
+                key
+            ),
+        );
+        let failed = probe_gemini(&client(broken), &SecretScrubber::new([key.to_string()])).await;
+        assert!(!failed.reachable);
+        assert_eq!(failed.error_class, Some("cli_error"));
+        let error = failed.error.unwrap();
+        assert!(
+            !error.contains(key) && !error.contains("abcdefghijklmnopqrstuv"),
+            "{error}"
+        );
+
+        let missing = probe_gemini(
+            &client(dir.path().join("no-such-gemini")),
+            &SecretScrubber::new([]),
+        )
+        .await;
+        assert_eq!(missing.error_class, Some("not_installed"));
+        assert!(!missing.configured && !missing.reachable);
+    }
+
+    #[test]
+    fn test_gemini_auth_source_prefers_keys_then_oauth() {
+        use crate::clients::gemini::gemini_auth_source;
+        let home = tempfile::tempdir().unwrap();
+        let none = |_: &str| None;
+        assert_eq!(gemini_auth_source(none, Some(home.path())), None);
+        let key = |k: &str| (k == "GOOGLE_API_KEY").then(|| "x".to_string());
+        assert_eq!(gemini_auth_source(key, None), Some("google_api_key"));
+        std::fs::create_dir(home.path().join(".gemini")).unwrap();
+        std::fs::write(home.path().join(".gemini/oauth_creds.json"), "{}").unwrap();
+        assert_eq!(gemini_auth_source(none, Some(home.path())), Some("oauth"));
+    }
+
+    #[tokio::test]
+    async fn test_providers_check_shape_and_skip() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = providers_check(
+            &HashEmbedder { dim: 8 },
+            "openai",
+            &client(script(&dir, "exit 3")),
+            &["gemini".to_string()],
+            &SecretScrubber::new([]),
+        )
+        .await;
+        assert_eq!(report["ok"], true);
+        let providers = report["providers"].as_array().unwrap();
+        assert_eq!(providers.len(), PROVIDERS.len());
+        assert_eq!(providers[0]["provider"], "embedder");
+        assert_eq!(providers[0]["reachable"], true);
+        assert!(providers[0]["latency_ms"].is_u64());
+        assert_eq!(
+            providers[1],
+            json!({"provider": "gemini", "configured": false, "reachable": false, "skipped": true})
+        );
+
+        let report = providers_check(
+            &RejectingEmbedder,
+            "openai",
+            &client(script(&dir, "exit 3")),
+            &[],
+            &SecretScrubber::new([]),
+        )
+        .await;
+        assert_eq!(report["ok"], false);
+        assert_eq!(report["providers"][0]["error_class"], "auth");
+        assert_eq!(report["providers"][1]["error_class"], "cli_error");
+        assert!(!report.to_string().contains(SECRET));
+    }
+}
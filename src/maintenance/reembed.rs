@@ -2,14 +2,20 @@
 //!
 //! This module provides functions to re-embed existing records in the database,
 //! including thoughts (via run_reembed), knowledge graph entities/observations/edges
-//! (via run_reembed_kg), and missing-only embedding (via run_kg_embed).
+//! (via run_reembed_kg), missing-only embedding (via run_kg_embed), and edge-only
+//! embedding over an open connection (via embed_edges).
 
+use crate::embeddings::{EmbedPurpose, Embedder};
+use crate::server::connection::DbEngine;
+use crate::server::progress::ProgressReporter;
 use anyhow::Result;
+use surrealdb::Surreal;
 
 #[derive(Debug, serde::Serialize)]
 pub struct ReembedStats {
     pub expected_dim: usize,
     pub batch_size: usize,
+    pub concurrency: usize,
     pub dry_run: bool,
     pub missing_only: bool,
     pub processed: usize,
@@ -54,11 +60,241 @@ pub struct KgEmbedStats {
     pub edges_skipped: usize,
 }
 
+/// Stats for `embed_edges`, mirroring the edge counters of `ReembedKgStats`
+#[derive(Debug, Default, PartialEq, serde::Serialize)]
+pub struct EmbedEdgesStats {
+    pub expected_dim: usize,
+    pub provider: String,
+    pub model: String,
+    pub dry_run: bool,
+    pub edges_updated: usize,
+    /// Edges already embedded by the current model at the current dimension
+    pub edges_skipped: usize,
+    pub edges_missing: usize,
+    pub edges_mismatched: usize,
+    /// Edges left unembedded because an endpoint no longer resolves to a named record
+    pub edges_dangling: usize,
+}
+
+/// Projection of a `kg_edges` row with its endpoint names resolved, for [`EdgeTextRow`].
+pub const EDGE_TEXT_FIELDS: &str = "meta::id(id) AS id, source.name AS source_name, \
+    target.name AS target_name, rel_type, data, \
+    (IF type::is_array(embedding) THEN array::len(embedding) ELSE 0 END) AS emb_len, embedding_model";
+
+/// Edges per page in `embed_edges`.
+const EDGE_PAGE: usize = 100;
+
+/// A `kg_edges` row selected with [`EDGE_TEXT_FIELDS`].
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct EdgeTextRow {
+    pub id: String,
+    #[serde(default)]
+    pub source_name: Option<String>,
+    #[serde(default)]
+    pub target_name: Option<String>,
+    #[serde(default)]
+    pub rel_type: Option<String>,
+    #[serde(default)]
+    pub data: serde_json::Value,
+    #[serde(default)]
+    pub emb_len: usize,
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+}
+
+/// Text embedded for an edge: "<source name> <rel_type> <target name>", plus
+/// " - <data.description>" when present. `None` when either endpoint has no name, i.e. the
+/// edge is dangling (deleted endpoint or legacy string endpoint).
+pub fn edge_embedding_text(row: &EdgeTextRow) -> Option<String> {
+    fn name(n: &Option<String>) -> Option<&str> {
+        n.as_deref().map(str::trim).filter(|s| !s.is_empty())
+    }
+    let source = name(&row.source_name)?;
+    let target = name(&row.target_name)?;
+    let rel_type = row
+        .rel_type
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .unwrap_or("related_to");
+    let mut text = format!("{} {} {}", source, rel_type, target);
+    if let Some(desc) = row
+        .data
+        .get("description")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+    {
+        text.push_str(" - ");
+        text.push_str(desc);
+    }
+    Some(text)
+}
+
+/// Embed one edge's text and store the vector with its provider/model/dim. Returns `false`
+/// (writing nothing) for a dangling edge.
+pub async fn embed_edge(
+    db: &Surreal<DbEngine>,
+    embedder: &dyn Embedder,
+    provider: &str,
+    model: &str,
+    row: &EdgeTextRow,
+) -> Result<bool> {
+    let Some(text) = edge_embedding_text(row) else {
+        return Ok(false);
+    };
+    let emb = embedder
+        .embed_with_purpose(&text, EmbedPurpose::KgEmbed)
+        .await?;
+    db.query(
+        "UPDATE type::record('kg_edges', $id) SET embedding = $emb, embedding_provider = $prov, \
+         embedding_model = $model, embedding_dim = $dim, embedded_at = time::now() RETURN NONE",
+    )
+    .bind(("id", row.id.clone()))
+    .bind(("dim", emb.len() as i64))
+    .bind(("emb", emb))
+    .bind(("prov", provider.to_string()))
+    .bind(("model", model.to_string()))
+    .await?
+    .check()?;
+    Ok(true)
+}
+
+/// Embed up to `limit` edges lacking a current embedding (missing, or from another model or
+/// dimension), skipping dangling edges.
+pub async fn embed_edges(
+    db: &Surreal<DbEngine>,
+    embedder: &dyn Embedder,
+    provider: &str,
+    model: &str,
+    limit: Option<usize>,
+    dry_run: bool,
+) -> Result<EmbedEdgesStats> {
+    let dims = embedder.dimensions();
+    let mut stats = EmbedEdgesStats {
+        expected_dim: dims,
+        provider: provider.to_string(),
+        model: model.to_string(),
+        dry_run,
+        ..EmbedEdgesStats::default()
+    };
+    let mut remaining = limit.unwrap_or(usize::MAX);
+    let mut start = 0usize;
+    while remaining > 0 {
+        let take = remaining.min(EDGE_PAGE);
+        let rows: Vec<serde_json::Value> = db
+            .query(format!(
+                "SELECT {EDGE_TEXT_FIELDS} FROM kg_edges ORDER BY id LIMIT $limit START $start"
+            ))
+            .bind(("limit", take as i64))
+            .bind(("start", start as i64))
+            .await?
+            .check()?
+            .take(0)?;
+        let fetched = rows.len();
+        for row in rows {
+            let row: EdgeTextRow = serde_json::from_value(row)?;
+            remaining -= 1;
+            if row.emb_len == dims && row.embedding_model.as_deref() == Some(model) {
+                stats.edges_skipped += 1;
+                continue;
+            }
+            if edge_embedding_text(&row).is_none() {
+                stats.edges_dangling += 1;
+                continue;
+            }
+            if row.emb_len == 0 {
+                stats.edges_missing += 1;
+            } else {
+                stats.edges_mismatched += 1;
+            }
+            if dry_run || embed_edge(db, embedder, provider, model, &row).await? {
+                stats.edges_updated += 1;
+            }
+        }
+        if fetched < take {
+            break;
+        }
+        start += fetched;
+    }
+    Ok(stats)
+}
+
+/// A thought row selected for re-embedding, with its current embedding length.
+struct PendingRow {
+    id: String,
+    content: String,
+    cur_len: usize,
+}
+
+/// Counters from one page of concurrent embed+update work.
+#[derive(Debug, Default, PartialEq)]
+struct PageCounts {
+    updated: usize,
+    missing: usize,
+    mismatched: usize,
+}
+
+/// Run `embed_update` for each row with at most `concurrency` in flight. Counters are folded
+/// as results arrive; the first error stops the page and is returned (rows already updated
+/// stay updated).
+async fn reembed_page<F, Fut>(
+    rows: Vec<PendingRow>,
+    concurrency: usize,
+    expected_dim: usize,
+    embed_update: F,
+) -> Result<PageCounts>
+where
+    F: Fn(PendingRow) -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    use futures_util::stream::{self, StreamExt};
+
+    let mut results = stream::iter(rows)
+        .map(|row| {
+            let cur_len = row.cur_len;
+            let fut = embed_update(row);
+            async move { fut.await.map(|()| cur_len) }
+        })
+        .buffer_unordered(concurrency.max(1));
+
+    let mut counts = PageCounts::default();
+    while let Some(result) = results.next().await {
+        let cur_len = result?;
+        if cur_len == 0 {
+            counts.missing += 1;
+        } else if cur_len != expected_dim {
+            counts.mismatched += 1;
+        }
+        counts.updated += 1;
+    }
+    Ok(counts)
+}
+
 pub async fn run_reembed(
     batch_size: usize,
     limit: Option<usize>,
     missing_only: bool,
     dry_run: bool,
+    concurrency: usize,
+) -> Result<ReembedStats> {
+    run_reembed_with_progress(
+        batch_size,
+        limit,
+        missing_only,
+        dry_run,
+        concurrency,
+        &ProgressReporter::disabled(),
+    )
+    .await
+}
+
+/// [`run_reembed`], reporting processed/total thoughts to `progress` after each batch.
+pub async fn run_reembed_with_progress(
+    batch_size: usize,
+    limit: Option<usize>,
+    missing_only: bool,
+    dry_run: bool,
+    concurrency: usize,
+    progress: &ProgressReporter,
 ) -> Result<ReembedStats> {
     // Load configuration
     let config = crate::config::Config::load()?;
@@ -84,8 +320,41 @@ pub async fn run_reembed(
     let mut skipped: usize = 0;
     let mut mismatched: usize = 0;
     let mut missing: usize = 0;
+    let mut batch: usize = 0;
     let limit_total = limit.unwrap_or(usize::MAX);
 
+    // Total is only worth a count query when someone is listening
+    let total = if progress.is_enabled() {
+        let count_sql = format!(
+            "USE NS {} DB {}; SELECT count() AS n FROM thoughts GROUP ALL;",
+            ns, dbname
+        );
+        let count = match http
+            .post(&sql_url)
+            .basic_auth(&user, Some(&pass))
+            .header("Accept", "application/json")
+            .header("Content-Type", "application/surrealql")
+            .body(count_sql)
+            .send()
+            .await
+        {
+            Ok(resp) => resp
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|blocks| {
+                    blocks
+                        .as_array()?
+                        .iter()
+                        .find_map(|b| b.get("result")?.as_array()?.first()?.get("n")?.as_u64())
+                }),
+            Err(_) => None,
+        };
+        count.map(|n| (n as usize).min(limit_total))
+    } else {
+        None
+    };
+
     loop {
         let remaining = limit_total.saturating_sub(processed);
         if remaining == 0 {
@@ -123,6 +392,7 @@ pub async fn run_reembed(
             break;
         }
 
+        let mut pending = Vec::new();
         for item in result.iter() {
             let id_raw = item.get("id").and_then(|v| v.as_str()).unwrap_or("");
             let content = item
@@ -136,9 +406,9 @@ pub async fn run_reembed(
             } else {
                 true
             };
+            processed += 1;
             if !needs_update {
                 skipped += 1;
-                processed += 1;
                 continue;
             }
             if dry_run {
@@ -147,51 +417,73 @@ pub async fn run_reembed(
                 } else if cur_len != expected_dim {
                     mismatched += 1;
                 }
-                processed += 1;
                 continue;
             }
-            let new_emb = embedder.embed(&content).await?;
-            if new_emb.len() != expected_dim {
-                anyhow::bail!(
-                    "Embedding dimension mismatch: expected {}, got {}",
-                    expected_dim,
-                    new_emb.len()
-                );
-            }
-            let emb_json = serde_json::to_string(&new_emb)?;
-            let update_sql = format!(
-                "USE NS {} DB {}; UPDATE thoughts SET embedding = {}, embedding_provider = '{}', embedding_model = '{}', embedding_dim = {}, embedded_at = time::now() WHERE id = '{}' RETURN NONE;",
-                ns, dbname, emb_json, provider, model, expected_dim, id_raw
-            );
-            let uresp = http
-                .post(&sql_url)
-                .basic_auth(&user, Some(&pass))
-                .header("Accept", "application/json")
-                .header("Content-Type", "application/surrealql")
-                .body(update_sql)
-                .send()
-                .await?;
-            if !uresp.status().is_success() {
-                anyhow::bail!(
-                    "HTTP update failed: {}",
-                    uresp.text().await.unwrap_or_default()
+            pending.push(PendingRow {
+                id: id_raw.to_string(),
+                content,
+                cur_len,
+            });
+        }
+
+        let counts = reembed_page(pending, concurrency, expected_dim, |row| {
+            let (embedder, http) = (&embedder, &http);
+            let (sql_url, user, pass, ns, dbname, provider, model) =
+                (&sql_url, &user, &pass, &ns, &dbname, &provider, &model);
+            async move {
+                let new_emb = embedder.embed_with_purpose(&row.content, EmbedPurpose::Reembed).await?;
+                if new_emb.len() != expected_dim {
+                    anyhow::bail!(
+                        "Embedding dimension mismatch: expected {}, got {}",
+                        expected_dim,
+                        new_emb.len()
+                    );
+                }
+                let emb_json = serde_json::to_string(&new_emb)?;
+                let update_sql = format!(
+                    "USE NS {} DB {}; UPDATE thoughts SET embedding = {}, embedding_provider = '{}', embedding_model = '{}', embedding_dim = {}, embedded_at = time::now() WHERE id = '{}' RETURN NONE;",
+                    ns, dbname, emb_json, provider, model, expected_dim, row.id
                 );
+                let uresp = http
+                    .post(sql_url)
+                    .basic_auth(user, Some(pass))
+                    .header("Accept", "application/json")
+                    .header("Content-Type", "application/surrealql")
+                    .body(update_sql)
+                    .send()
+                    .await?;
+                if !uresp.status().is_success() {
+                    anyhow::bail!(
+                        "HTTP update failed: {}",
+                        uresp.text().await.unwrap_or_default()
+                    );
+                }
+                Ok(())
             }
-            if cur_len == 0 {
-                missing += 1;
-            } else if cur_len != expected_dim {
-                mismatched += 1;
-            }
-            updated += 1;
-            processed += 1;
-        }
+        })
+        .await?;
+        updated += counts.updated;
+        missing += counts.missing;
+        mismatched += counts.mismatched;
 
         start += result.len();
+        batch += 1;
+        progress
+            .report(
+                processed,
+                total,
+                format!(
+                    "reembed batch {}: {} processed, {} updated",
+                    batch, processed, updated
+                ),
+            )
+            .await;
     }
 
     Ok(ReembedStats {
         expected_dim,
         batch_size,
+        concurrency: concurrency.max(1),
         dry_run,
         missing_only,
         processed,
@@ -307,7 +599,9 @@ pub async fn run_reembed_kg(limit: Option<usize>, dry_run: bool) -> Result<Reemb
             } else {
                 format!("{} ({})", name, etype)
             };
-            let emb = embedder.embed(&text).await?;
+            let emb = embedder
+                .embed_with_purpose(&text, EmbedPurpose::Reembed)
+                .await?;
             if !dry_run {
                 let ts = Utc::now().to_rfc3339();
                 let q = format!(
@@ -381,7 +675,9 @@ pub async fn run_reembed_kg(limit: Option<usize>, dry_run: bool) -> Result<Reemb
                 text.push_str(" - ");
                 text.push_str(desc);
             }
-            let emb = embedder.embed(&text).await?;
+            let emb = embedder
+                .embed_with_purpose(&text, EmbedPurpose::Reembed)
+                .await?;
             if !dry_run {
                 let ts = Utc::now().to_rfc3339();
                 let q = format!(
@@ -463,7 +759,9 @@ pub async fn run_reembed_kg(limit: Option<usize>, dry_run: bool) -> Result<Reemb
                 text.push_str(desc);
             }
 
-            let emb = embedder.embed(&text).await?;
+            let emb = embedder
+                .embed_with_purpose(&text, EmbedPurpose::Reembed)
+                .await?;
             if !dry_run {
                 let ts = Utc::now().to_rfc3339();
                 let q = format!(
@@ -652,7 +950,9 @@ pub async fn run_kg_embed(limit: Option<usize>, dry_run: bool) -> Result<KgEmbed
                 continue;
             }
 
-            let emb = embedder.embed(&text).await?;
+            let emb = embedder
+                .embed_with_purpose(&text, EmbedPurpose::KgEmbed)
+                .await?;
             let ts = Utc::now().to_rfc3339();
 
             // Idempotent update: only update if embedding is still NULL
@@ -762,7 +1062,9 @@ pub async fn run_kg_embed(limit: Option<usize>, dry_run: bool) -> Result<KgEmbed
                 continue;
             }
 
-            let emb = embedder.embed(text).await?;
+            let emb = embedder
+                .embed_with_purpose(text, EmbedPurpose::KgEmbed)
+                .await?;
             let ts = Utc::now().to_rfc3339();
 
             let q = format!(
@@ -887,7 +1189,9 @@ pub async fn run_kg_embed(limit: Option<usize>, dry_run: bool) -> Result<KgEmbed
                 continue;
             }
 
-            let emb = embedder.embed(&text).await?;
+            let emb = embedder
+                .embed_with_purpose(&text, EmbedPurpose::KgEmbed)
+                .await?;
             let ts = Utc::now().to_rfc3339();
 
             let q = format!(
@@ -928,3 +1232,131 @@ pub async fn run_kg_embed(limit: Option<usize>, dry_run: bool) -> Result<KgEmbed
         edges_skipped,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn rows(lens: &[usize]) -> Vec<PendingRow> {
+        lens.iter()
+            .enumerate()
+            .map(|(i, &cur_len)| PendingRow {
+                id: format!("t{}", i),
+                content: format!("thought {}", i),
+                cur_len,
+            })
+            .collect()
+    }
+
+    /// Mock embed+update call that tracks how many calls are in flight at once.
+    async fn mock_call(
+        in_flight: &AtomicUsize,
+        peak: &AtomicUsize,
+        fail_id: Option<&str>,
+        row: PendingRow,
+    ) -> Result<()> {
+        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        peak.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        in_flight.fetch_sub(1, Ordering::SeqCst);
+        if fail_id == Some(row.id.as_str()) {
+            anyhow::bail!("HTTP update failed: {}", row.id);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reembed_page_runs_embeds_concurrently() {
+        let (in_flight, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let counts = reembed_page(
+            rows(&[0, 0, 768, 1536, 768, 0, 1536, 1536]),
+            4,
+            1536,
+            |row| mock_call(&in_flight, &peak, None, row),
+        )
+        .await
+        .unwrap();
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+        assert_eq!(
+            counts,
+            PageCounts {
+                updated: 8,
+                missing: 3,
+                mismatched: 2
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reembed_page_default_concurrency_is_sequential() {
+        let (in_flight, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let counts = reembed_page(rows(&[0, 0, 0]), 1, 1536, |row| {
+            mock_call(&in_flight, &peak, None, row)
+        })
+        .await
+        .unwrap();
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+        assert_eq!(counts.updated, 3);
+    }
+
+    fn edge(source: Option<&str>, target: Option<&str>, data: serde_json::Value) -> EdgeTextRow {
+        EdgeTextRow {
+            id: "e1".into(),
+            source_name: source.map(String::from),
+            target_name: target.map(String::from),
+            rel_type: Some("depends_on".into()),
+            data,
+            ..EdgeTextRow::default()
+        }
+    }
+
+    #[test]
+    fn test_edge_embedding_text_uses_resolved_names() {
+        let row = edge(
+            Some("surreal-mind"),
+            Some("SurrealDB"),
+            serde_json::Value::Null,
+        );
+        assert_eq!(
+            edge_embedding_text(&row).as_deref(),
+            Some("surreal-mind depends_on SurrealDB")
+        );
+        let described = edge(
+            Some("surreal-mind"),
+            Some("SurrealDB"),
+            serde_json::json!({"description": "storage backend"}),
+        );
+        assert_eq!(
+            edge_embedding_text(&described).as_deref(),
+            Some("surreal-mind depends_on SurrealDB - storage backend")
+        );
+        let untyped = EdgeTextRow {
+            rel_type: None,
+            ..edge(Some("a"), Some("b"), serde_json::Value::Null)
+        };
+        assert_eq!(
+            edge_embedding_text(&untyped).as_deref(),
+            Some("a related_to b")
+        );
+    }
+
+    #[test]
+    fn test_edge_embedding_text_skips_dangling_endpoints() {
+        let null = serde_json::Value::Null;
+        assert!(edge_embedding_text(&edge(None, Some("SurrealDB"), null.clone())).is_none());
+        assert!(edge_embedding_text(&edge(Some("surreal-mind"), None, null.clone())).is_none());
+        assert!(edge_embedding_text(&edge(Some(" "), Some("SurrealDB"), null)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reembed_page_first_error_aborts() {
+        let (in_flight, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let err = reembed_page(rows(&[0, 0, 0, 0]), 2, 1536, |row| {
+            mock_call(&in_flight, &peak, Some("t1"), row)
+        })
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("t1"));
+    }
+}
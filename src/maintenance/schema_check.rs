@@ -0,0 +1,374 @@
+//! Schema drift detection.
+//!
+//! [`expected_schema`] reads the tables, fields, and indexes that the schema
+//! [`MIGRATIONS`](crate::server::schema::MIGRATIONS) define. [`check_schema`] compares them
+//! with `INFO FOR DB` / `INFO FOR TABLE` on the live connection and reports what is missing,
+//! plus `*_candidates` tables the schema does not know. With `apply` it runs only the
+//! `DEFINE` statements for missing items; nothing is ever removed.
+
+use crate::server::connection::DbEngine;
+use crate::server::schema::MIGRATIONS;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use surrealdb::Surreal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionKind {
+    Table,
+    Field,
+    Index,
+}
+
+/// One `DEFINE` statement of the expected schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedDefinition {
+    pub kind: DefinitionKind,
+    pub table: String,
+    /// Field or index name; the table name for tables
+    pub name: String,
+    pub statement: String,
+}
+
+/// A missing field or index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaGap {
+    pub table: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SchemaDrift {
+    pub missing_tables: Vec<String>,
+    /// Fields missing from tables that exist (a missing table's fields are implied)
+    pub missing_fields: Vec<SchemaGap>,
+    pub missing_indexes: Vec<SchemaGap>,
+    /// Live `*_candidates` tables the expected schema does not define
+    pub unexpected_candidate_tables: Vec<String>,
+    /// Statements that would add every missing item, in schema order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub statements: Vec<String>,
+}
+
+impl SchemaDrift {
+    /// No table, field, or index is missing. Unexpected candidate tables are only reported.
+    pub fn is_clean(&self) -> bool {
+        self.missing_tables.is_empty()
+            && self.missing_fields.is_empty()
+            && self.missing_indexes.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedStatement {
+    pub statement: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchemaCheckReport {
+    /// Nothing is missing (after applying, when `apply` is set)
+    pub ok: bool,
+    pub apply: bool,
+    pub expected: ExpectedCounts,
+    pub drift: SchemaDrift,
+    pub applied: Vec<String>,
+    pub failed: Vec<FailedStatement>,
+    /// Drift left after applying; absent unless `apply` ran statements
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<SchemaDrift>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ExpectedCounts {
+    pub tables: usize,
+    pub fields: usize,
+    pub indexes: usize,
+}
+
+/// Table names with their defined field and index names, as the live database reports them.
+#[derive(Debug, Default)]
+pub struct LiveSchema {
+    pub tables: BTreeMap<String, LiveTable>,
+}
+
+#[derive(Debug, Default)]
+pub struct LiveTable {
+    pub fields: BTreeSet<String>,
+    pub indexes: BTreeSet<String>,
+}
+
+/// Parse one `DEFINE TABLE|FIELD|INDEX [OVERWRITE | IF NOT EXISTS] name [ON [TABLE] table]`.
+fn parse_definition(statement: &str) -> Option<ExpectedDefinition> {
+    let mut words = statement.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("DEFINE") {
+        return None;
+    }
+    let kind = match words.next()?.to_ascii_uppercase().as_str() {
+        "TABLE" => DefinitionKind::Table,
+        "FIELD" => DefinitionKind::Field,
+        "INDEX" => DefinitionKind::Index,
+        _ => return None,
+    };
+    let mut name = words.next()?;
+    if name.eq_ignore_ascii_case("OVERWRITE") {
+        name = words.next()?;
+    } else if name.eq_ignore_ascii_case("IF") {
+        words.next(); // NOT
+        words.next(); // EXISTS
+        name = words.next()?;
+    }
+    let table = if kind == DefinitionKind::Table {
+        name
+    } else {
+        if !words.next()?.eq_ignore_ascii_case("ON") {
+            return None;
+        }
+        match words.next()? {
+            t if t.eq_ignore_ascii_case("TABLE") => words.next()?,
+            t => t,
+        }
+    };
+    Some(ExpectedDefinition {
+        kind,
+        table: table.to_string(),
+        name: name.to_string(),
+        statement: format!("{};", statement.trim()),
+    })
+}
+
+/// The definitions in `sql`, in order; `--` comments and other statements are skipped.
+pub fn expected_schema(sql: &str) -> Vec<ExpectedDefinition> {
+    let uncommented: String = sql
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n");
+    uncommented
+        .split(';')
+        .filter_map(|s| parse_definition(s.trim()))
+        .collect()
+}
+
+/// Compare `expected` with `live`.
+pub fn diff_schema(expected: &[ExpectedDefinition], live: &LiveSchema) -> SchemaDrift {
+    let mut drift = SchemaDrift::default();
+    let missing_tables: BTreeSet<&str> = expected
+        .iter()
+        .filter(|d| d.kind == DefinitionKind::Table && !live.tables.contains_key(&d.table))
+        .map(|d| d.table.as_str())
+        .collect();
+    for def in expected {
+        let table = live.tables.get(&def.table);
+        let missing = match (def.kind, table) {
+            (_, None) => missing_tables.contains(def.table.as_str()),
+            (DefinitionKind::Table, Some(_)) => false,
+            (DefinitionKind::Field, Some(t)) => !t.fields.contains(&def.name),
+            (DefinitionKind::Index, Some(t)) => !t.indexes.contains(&def.name),
+        };
+        if !missing {
+            continue;
+        }
+        let gap = || SchemaGap {
+            table: def.table.clone(),
+            name: def.name.clone(),
+        };
+        match def.kind {
+            DefinitionKind::Table => drift.missing_tables.push(def.table.clone()),
+            DefinitionKind::Field if table.is_some() => drift.missing_fields.push(gap()),
+            DefinitionKind::Index if table.is_some() => drift.missing_indexes.push(gap()),
+            _ => {}
+        }
+        drift.statements.push(def.statement.clone());
+    }
+    let known: BTreeSet<&str> = expected.iter().map(|d| d.table.as_str()).collect();
+    drift.unexpected_candidate_tables = live
+        .tables
+        .keys()
+        .filter(|t| t.ends_with("_candidates") && !known.contains(t.as_str()))
+        .cloned()
+        .collect();
+    drift
+}
+
+/// Keys of the `key` object in an `INFO FOR ...` row.
+fn info_keys(info: Option<&serde_json::Value>, key: &str) -> BTreeSet<String> {
+    info.and_then(|i| i.get(key))
+        .and_then(|v| v.as_object())
+        .map(|o| o.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Live tables, with fields and indexes for those in `tables`.
+pub async fn load_live_schema(
+    db: &Surreal<DbEngine>,
+    tables: &BTreeSet<&str>,
+) -> Result<LiveSchema> {
+    let info: Vec<serde_json::Value> = db.query("INFO FOR DB").await?.check()?.take(0)?;
+    let mut live = LiveSchema::default();
+    for table in info_keys(info.first(), "tables") {
+        let mut live_table = LiveTable::default();
+        if tables.contains(table.as_str()) {
+            let info: Vec<serde_json::Value> = db
+                .query(format!("INFO FOR TABLE {}", table))
+                .await?
+                .check()?
+                .take(0)?;
+            // INFO spells array element fields `a.*.b`; the schema declares them as `a[*].b`
+            live_table.fields = info_keys(info.first(), "fields")
+                .into_iter()
+                .map(|f| f.replace(".*", "[*]"))
+                .collect();
+            live_table.indexes = info_keys(info.first(), "indexes");
+        }
+        live.tables.insert(table, live_table);
+    }
+    Ok(live)
+}
+
+/// Report drift from the migrated schema; with `apply`, run the missing definitions and
+/// re-check.
+pub async fn check_schema(db: &Surreal<DbEngine>, apply: bool) -> Result<SchemaCheckReport> {
+    let expected: Vec<ExpectedDefinition> = MIGRATIONS
+        .iter()
+        .flat_map(|m| expected_schema(m.sql))
+        .collect();
+    let count = |kind| expected.iter().filter(|d| d.kind == kind).count();
+    let counts = ExpectedCounts {
+        tables: count(DefinitionKind::Table),
+        fields: count(DefinitionKind::Field),
+        indexes: count(DefinitionKind::Index),
+    };
+    let tables: BTreeSet<&str> = expected.iter().map(|d| d.table.as_str()).collect();
+    let drift = diff_schema(&expected, &load_live_schema(db, &tables).await?);
+
+    let mut report = SchemaCheckReport {
+        ok: drift.is_clean(),
+        apply,
+        expected: counts,
+        drift,
+        applied: Vec::new(),
+        failed: Vec::new(),
+        remaining: None,
+    };
+    if !apply || report.drift.statements.is_empty() {
+        return Ok(report);
+    }
+
+    for statement in &report.drift.statements {
+        match db.query(statement.as_str()).await.and_then(|r| r.check()) {
+            Ok(_) => report.applied.push(statement.clone()),
+            Err(e) => report.failed.push(FailedStatement {
+                statement: statement.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    let remaining = diff_schema(&expected, &load_live_schema(db, &tables).await?);
+    report.ok = remaining.is_clean();
+    report.remaining = Some(remaining);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::schema::SCHEMA_SQL;
+
+    fn live(tables: &[(&str, &[&str], &[&str])]) -> LiveSchema {
+        let set = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        LiveSchema {
+            tables: tables
+                .iter()
+                .map(|(t, fields, indexes)| {
+                    let table = LiveTable {
+                        fields: set(fields),
+                        indexes: set(indexes),
+                    };
+                    (t.to_string(), table)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_expected_schema_parses_schema_sql() {
+        let expected = expected_schema(SCHEMA_SQL);
+        let find = |kind, table: &str, name: &str| {
+            expected
+                .iter()
+                .find(|d| d.kind == kind && d.table == table && d.name == name)
+        };
+        assert!(
+            find(
+                DefinitionKind::Table,
+                "kg_entity_candidates",
+                "kg_entity_candidates"
+            )
+            .is_some()
+        );
+        assert!(find(DefinitionKind::Field, "thoughts", "superseded_by").is_some());
+        assert!(find(DefinitionKind::Field, "thoughts", "references[*].kind").is_some());
+        let overwrite = find(DefinitionKind::Field, "thoughts", "embedding").unwrap();
+        assert!(
+            overwrite
+                .statement
+                .starts_with("DEFINE FIELD OVERWRITE embedding")
+        );
+        assert!(find(DefinitionKind::Index, "agent_jobs", "idx_jobs_job_id").is_some());
+        // Every DEFINE statement in the schema is recognized
+        assert_eq!(expected.len(), SCHEMA_SQL.matches("DEFINE ").count());
+    }
+
+    #[test]
+    fn test_diff_schema_reports_missing_and_unexpected() {
+        let expected = expected_schema(
+            "DEFINE TABLE a SCHEMAFULL; DEFINE FIELD x ON TABLE a TYPE int; -- note; here
+             DEFINE FIELD y ON a TYPE int; DEFINE INDEX a_x ON TABLE a FIELDS x;
+             DEFINE TABLE b_candidates SCHEMALESS; DEFINE INDEX b_i ON TABLE b_candidates FIELDS z;",
+        );
+        assert_eq!(expected.len(), 6);
+
+        let drift = diff_schema(
+            &expected,
+            &live(&[
+                ("a", &["x"], &[]),
+                ("old_candidates", &[], &[]),
+                ("misc", &[], &[]),
+            ]),
+        );
+        assert_eq!(drift.missing_tables, ["b_candidates"]);
+        let names = |gaps: &[SchemaGap]| gaps.iter().map(|g| g.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&drift.missing_fields), ["y"]);
+        assert_eq!(names(&drift.missing_indexes), ["a_x"]);
+        assert_eq!(drift.unexpected_candidate_tables, ["old_candidates"]);
+        assert_eq!(
+            drift.statements,
+            [
+                "DEFINE FIELD y ON a TYPE int;",
+                "DEFINE INDEX a_x ON TABLE a FIELDS x;",
+                "DEFINE TABLE b_candidates SCHEMALESS;",
+                "DEFINE INDEX b_i ON TABLE b_candidates FIELDS z;",
+            ]
+        );
+        assert!(!drift.is_clean());
+
+        let clean = diff_schema(
+            &expected,
+            &live(&[
+                ("a", &["x", "y"], &["a_x"]),
+                ("b_candidates", &[], &["b_i"]),
+            ]),
+        );
+        assert!(clean.is_clean() && clean.statements.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_schema_is_clean_on_a_migrated_database() {
+        let server = crate::test_support::mem_server().await;
+        let report = check_schema(&server.db.client(), false).await.unwrap();
+        assert!(report.ok, "{:?}", report.drift.statements);
+        assert!(report.drift.statements.is_empty());
+    }
+}
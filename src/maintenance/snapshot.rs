@@ -0,0 +1,584 @@
+//! Namespace snapshots for `maintain snapshot_create` / `snapshot_restore`.
+//!
+//! A snapshot is a timestamped directory holding one gzip JSONL file per table plus a
+//! versioned `manifest.json` with row counts and the embedding provider/model/dim in use.
+//! Each line is a serde-encoded SurrealDB value, so datetimes, record links, and ids
+//! round-trip exactly. Restore checks the manifest version, embedding dimension,
+//! per-file row counts, and that every line decodes before anything is written, and
+//! refuses to load into tables that already hold rows unless forced (which clears them
+//! first). Each table is cleared and reloaded in one transaction, so a failed load leaves
+//! that table as it was.
+
+use crate::server::connection::DbEngine;
+use crate::server::progress::ProgressReporter;
+use anyhow::{Context, Result, bail};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use surrealdb::Surreal;
+use surrealdb::types::Value as DbValue;
+
+/// Bumped whenever the manifest or line encoding changes incompatibly
+pub const MANIFEST_VERSION: u32 = 1;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Tables captured by a snapshot, in restore order. Operational logs (`agent_jobs`,
+/// `tool_audit`) are left out.
+pub const SNAPSHOT_TABLES: &[&str] = &[
+    "thoughts",
+    "recalls",
+    "correction_events",
+    "hypothesis_history",
+    "kg_entities",
+    "kg_edges",
+    "kg_observations",
+    "kg_boundaries",
+    "kg_blocklist",
+    "kg_deletions",
+    "kg_entity_candidates",
+    "kg_edge_candidates",
+    "agent_exchanges",
+    "tool_sessions",
+    "embedding_usage",
+];
+
+/// Embedding configuration the snapshot was taken under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEmbedding {
+    pub provider: String,
+    pub model: String,
+    pub dim: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotTable {
+    pub table: String,
+    /// File name relative to the manifest's directory
+    pub file: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub created_at: String,
+    pub namespace: String,
+    pub database: String,
+    pub embedding: SnapshotEmbedding,
+    pub tables: Vec<SnapshotTable>,
+}
+
+impl SnapshotManifest {
+    pub fn total_rows(&self) -> usize {
+        self.tables.iter().map(|t| t.count).sum()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotCreated {
+    pub snapshot_dir: String,
+    pub manifest_path: String,
+    pub manifest: SnapshotManifest,
+    pub rows: usize,
+    pub batches: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestoredTable {
+    pub table: String,
+    pub expected: usize,
+    /// Rows deleted first because the table was not empty (`force`)
+    pub cleared: usize,
+    pub loaded: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotRestored {
+    pub manifest_path: String,
+    pub version: u32,
+    pub created_at: String,
+    pub tables: Vec<RestoredTable>,
+    pub rows: usize,
+    pub batches: usize,
+    pub dry_run: bool,
+}
+
+/// Options for [`restore_snapshot`]
+#[derive(Debug, Clone)]
+pub struct RestoreOptions {
+    /// Dimension of the active embedder; the snapshot's must match
+    pub expected_dim: usize,
+    /// Clear non-empty tables instead of refusing
+    pub force: bool,
+    pub batch_size: usize,
+    /// Verify only; nothing is cleared or loaded
+    pub dry_run: bool,
+}
+
+/// Directory name for a snapshot taken at `now`
+pub fn snapshot_dir_name(now: chrono::DateTime<chrono::Utc>) -> String {
+    format!("snapshot-{}", now.format("%Y%m%dT%H%M%S%3fZ"))
+}
+
+fn table_file(table: &str) -> String {
+    format!("{}.jsonl.gz", table)
+}
+
+/// Rows currently in `table` (0 when it does not exist)
+async fn count_rows(db: &Surreal<DbEngine>, table: &str) -> Result<usize> {
+    let counts: Vec<serde_json::Value> = db
+        .query(format!("SELECT count() AS n FROM {} GROUP ALL", table))
+        .await?
+        .take(0)?;
+    Ok(counts.first().and_then(|r| r["n"].as_u64()).unwrap_or(0) as usize)
+}
+
+/// Write every snapshot table into a new timestamped directory under `backup_dir`, reading
+/// `batch_size` rows per query.
+pub async fn create_snapshot(
+    db: &Surreal<DbEngine>,
+    backup_dir: &Path,
+    namespace: &str,
+    database: &str,
+    embedding: SnapshotEmbedding,
+    batch_size: usize,
+    progress: &ProgressReporter,
+) -> Result<SnapshotCreated> {
+    let batch_size = batch_size.max(1);
+    let now = chrono::Utc::now();
+    let dir = backup_dir.join(snapshot_dir_name(now));
+    fs::create_dir_all(backup_dir)
+        .with_context(|| format!("cannot create backup_dir {}", backup_dir.display()))?;
+    fs::create_dir(&dir).with_context(|| format!("cannot create {}", dir.display()))?;
+
+    let total = if progress.is_enabled() {
+        let mut total = 0;
+        for table in SNAPSHOT_TABLES {
+            total += count_rows(db, table).await?;
+        }
+        Some(total)
+    } else {
+        None
+    };
+
+    let mut tables = Vec::with_capacity(SNAPSHOT_TABLES.len());
+    let (mut rows, mut batches) = (0, 0);
+    for table in SNAPSHOT_TABLES {
+        let file = table_file(table);
+        let mut out = GzEncoder::new(
+            BufWriter::new(File::create(dir.join(&file))?),
+            Compression::default(),
+        );
+        let mut count = 0;
+        loop {
+            let page: Vec<DbValue> = db
+                .query(format!(
+                    "SELECT * FROM {} ORDER BY id LIMIT $limit START $start",
+                    table
+                ))
+                .bind(("limit", batch_size as i64))
+                .bind(("start", count as i64))
+                .await?
+                .take(0)?;
+            if page.is_empty() {
+                break;
+            }
+            for row in &page {
+                serde_json::to_writer(&mut out, row)?;
+                out.write_all(b"\n")?;
+            }
+            count += page.len();
+            rows += page.len();
+            batches += 1;
+            progress
+                .report(rows, total, format!("snapshot {}: {} rows", table, count))
+                .await;
+            if page.len() < batch_size {
+                break;
+            }
+        }
+        out.finish()?.flush()?;
+        tables.push(SnapshotTable {
+            table: table.to_string(),
+            file,
+            count,
+        });
+    }
+
+    let manifest = SnapshotManifest {
+        version: MANIFEST_VERSION,
+        created_at: now.to_rfc3339(),
+        namespace: namespace.to_string(),
+        database: database.to_string(),
+        embedding,
+        tables,
+    };
+    let manifest_path = dir.join(MANIFEST_FILE);
+    fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+
+    Ok(SnapshotCreated {
+        snapshot_dir: dir.display().to_string(),
+        manifest_path: manifest_path.display().to_string(),
+        manifest,
+        rows,
+        batches,
+    })
+}
+
+/// Parse `manifest_path` and check it can be restored under `expected_dim`.
+pub fn read_manifest(manifest_path: &Path, expected_dim: usize) -> Result<SnapshotManifest> {
+    let raw = fs::read(manifest_path)
+        .with_context(|| format!("cannot read manifest {}", manifest_path.display()))?;
+    let manifest: SnapshotManifest =
+        serde_json::from_slice(&raw).context("invalid snapshot manifest")?;
+    validate_manifest(&manifest, expected_dim)?;
+    Ok(manifest)
+}
+
+fn validate_manifest(manifest: &SnapshotManifest, expected_dim: usize) -> Result<()> {
+    if manifest.version != MANIFEST_VERSION {
+        bail!(
+            "unsupported snapshot manifest version {} (expected {})",
+            manifest.version,
+            MANIFEST_VERSION
+        );
+    }
+    if manifest.embedding.dim != expected_dim {
+        bail!(
+            "snapshot embedding dimension {} ({}/{}) does not match the active dimension {}",
+            manifest.embedding.dim,
+            manifest.embedding.provider,
+            manifest.embedding.model,
+            expected_dim
+        );
+    }
+    for entry in &manifest.tables {
+        if !SNAPSHOT_TABLES.contains(&entry.table.as_str()) {
+            bail!("manifest lists unknown table '{}'", entry.table);
+        }
+        if entry.file.is_empty() || entry.file.contains(['/', '\\']) || entry.file == ".." {
+            bail!(
+                "manifest file '{}' for {} must be a plain file name",
+                entry.file,
+                entry.table
+            );
+        }
+    }
+    Ok(())
+}
+
+fn open_lines(path: &Path) -> Result<impl Iterator<Item = std::io::Result<String>>> {
+    let file = File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+    Ok(BufReader::new(GzDecoder::new(file))
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |l| !l.trim().is_empty())))
+}
+
+/// Decode every row in one snapshot file; `file` names it in errors
+fn read_rows(path: &Path, file: &str) -> Result<Vec<DbValue>> {
+    let mut rows = Vec::new();
+    for line in open_lines(path)? {
+        let line = line.with_context(|| format!("cannot read {}", path.display()))?;
+        let row = serde_json::from_str(&line)
+            .with_context(|| format!("{} line {}: invalid row", file, rows.len() + 1))?;
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Load the snapshot at `manifest_path`. Every file is decoded and checked against its
+/// manifest count before any table is touched; non-empty tables fail the restore unless
+/// `force` is set. Each table's clear and load run in one transaction.
+pub async fn restore_snapshot(
+    db: &Surreal<DbEngine>,
+    manifest_path: &Path,
+    options: &RestoreOptions,
+    progress: &ProgressReporter,
+) -> Result<SnapshotRestored> {
+    let manifest = read_manifest(manifest_path, options.expected_dim)?;
+    let dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let batch_size = options.batch_size.max(1);
+
+    let files: Vec<PathBuf> = manifest.tables.iter().map(|t| dir.join(&t.file)).collect();
+    for (entry, path) in manifest.tables.iter().zip(&files) {
+        let found = read_rows(path, &entry.file)?.len();
+        if found != entry.count {
+            bail!(
+                "{} holds {} rows but the manifest records {}",
+                entry.file,
+                found,
+                entry.count
+            );
+        }
+    }
+
+    let mut existing = Vec::with_capacity(manifest.tables.len());
+    for entry in &manifest.tables {
+        existing.push(count_rows(db, &entry.table).await?);
+    }
+    let occupied: Vec<String> = manifest
+        .tables
+        .iter()
+        .zip(&existing)
+        .filter(|(_, n)| **n > 0)
+        .map(|(entry, n)| format!("{} ({} rows)", entry.table, n))
+        .collect();
+    if !occupied.is_empty() && !options.force {
+        bail!(
+            "refusing to restore into a non-empty namespace: {}; pass force: true to replace them",
+            occupied.join(", ")
+        );
+    }
+
+    let total = manifest.total_rows();
+    let mut restored = Vec::with_capacity(manifest.tables.len());
+    let (mut rows, mut batches) = (0, 0);
+    for ((entry, path), cleared) in manifest.tables.iter().zip(&files).zip(existing) {
+        let mut loaded = 0;
+        if !options.dry_run && (cleared > 0 || entry.count > 0) {
+            let mut table_rows = read_rows(path, &entry.file)?.into_iter().peekable();
+            let mut chunks: Vec<Vec<DbValue>> = Vec::new();
+            while table_rows.peek().is_some() {
+                chunks.push(table_rows.by_ref().take(batch_size).collect());
+            }
+            let n: usize = chunks.iter().map(Vec::len).sum();
+            let mut sql = String::from("BEGIN TRANSACTION;");
+            if cleared > 0 {
+                sql.push_str(&format!(" DELETE {} RETURN NONE;", entry.table));
+            }
+            for i in 0..chunks.len() {
+                sql.push_str(&format!(
+                    " INSERT INTO {} $rows_{} RETURN NONE;",
+                    entry.table, i
+                ));
+            }
+            sql.push_str(" COMMIT TRANSACTION;");
+            batches += chunks.len();
+            let mut query = db.query(sql);
+            for (i, chunk) in chunks.into_iter().enumerate() {
+                query = query.bind((format!("rows_{}", i), chunk));
+            }
+            query.await?.check().with_context(|| {
+                format!("restoring {} failed; the table is unchanged", entry.table)
+            })?;
+            loaded = n;
+            rows += n;
+            progress
+                .report(
+                    rows,
+                    Some(total),
+                    format!("restore {}: {} rows", entry.table, loaded),
+                )
+                .await;
+        }
+        restored.push(RestoredTable {
+            table: entry.table.clone(),
+            expected: entry.count,
+            cleared: if options.dry_run { 0 } else { cleared },
+            loaded,
+        });
+    }
+
+    Ok(SnapshotRestored {
+        manifest_path: manifest_path.display().to_string(),
+        version: manifest.version,
+        created_at: manifest.created_at,
+        tables: restored,
+        rows,
+        batches,
+        dry_run: options.dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use surrealdb::types::{Datetime, Object, RecordId};
+
+    fn manifest(dim: usize) -> SnapshotManifest {
+        SnapshotManifest {
+            version: MANIFEST_VERSION,
+            created_at: "2026-01-02T03:04:05+00:00".into(),
+            namespace: "surreal_mind".into(),
+            database: "consciousness".into(),
+            embedding: SnapshotEmbedding {
+                provider: "openai".into(),
+                model: "text-embedding-3-small".into(),
+                dim,
+            },
+            tables: vec![SnapshotTable {
+                table: "thoughts".into(),
+                file: table_file("thoughts"),
+                count: 2,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_validate_manifest_checks_version_dim_and_files() {
+        assert!(validate_manifest(&manifest(1536), 1536).is_ok());
+
+        let err = validate_manifest(&manifest(768), 1536).unwrap_err();
+        assert!(err.to_string().contains("dimension 768"));
+
+        let mut future = manifest(1536);
+        future.version = MANIFEST_VERSION + 1;
+        assert!(
+            validate_manifest(&future, 1536)
+                .unwrap_err()
+                .to_string()
+                .contains("unsupported snapshot manifest version")
+        );
+
+        let mut escaping = manifest(1536);
+        escaping.tables[0].file = "../thoughts.jsonl.gz".into();
+        assert!(validate_manifest(&escaping, 1536).is_err());
+
+        let mut unknown = manifest(1536);
+        unknown.tables[0].table = "agent_jobs".into();
+        assert!(validate_manifest(&unknown, 1536).is_err());
+    }
+
+    #[test]
+    fn test_rows_round_trip_through_gzip_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(table_file("thoughts"));
+        let row = |id: &str| {
+            let mut obj = Object::new();
+            obj.insert("id", RecordId::new("thoughts", id));
+            obj.insert("content", id.to_string());
+            obj.insert(
+                "created_at",
+                "2026-01-02T03:04:05Z".parse::<Datetime>().unwrap(),
+            );
+            DbValue::Object(obj)
+        };
+        let rows = vec![row("a"), row("b")];
+        let mut out = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        for row in &rows {
+            serde_json::to_writer(&mut out, row).unwrap();
+            out.write_all(b"\n").unwrap();
+        }
+        out.finish().unwrap();
+
+        assert_eq!(read_rows(&path, "thoughts.jsonl.gz").unwrap(), rows);
+    }
+
+    #[tokio::test]
+    async fn test_count_rows_counts_an_empty_and_a_filled_table() {
+        let server = crate::test_support::mem_server().await;
+        let db = server.db.client();
+        assert_eq!(count_rows(&db, "kg_entities").await.unwrap(), 0);
+        server
+            .db
+            .query("CREATE kg_entities:a SET name = 'a'; CREATE kg_entities:b SET name = 'b';")
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        assert_eq!(count_rows(&db, "kg_entities").await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_restore_leaves_tables_as_they_were() {
+        let server = crate::test_support::mem_server().await;
+        let db = server.db.client();
+        let thought = |id: &str| {
+            format!(
+                "CREATE thoughts:{id} SET content = '{id}', created_at = time::now(), \
+                 injection_scale = 0, significance = 0.5, access_count = 0, \
+                 injected_memories = [];"
+            )
+        };
+        let seed = [thought("a"), thought("b")].concat() + "CREATE kg_entities:e SET name = 'e';";
+        db.query(seed).await.unwrap().check().unwrap();
+        let backup = tempfile::tempdir().unwrap();
+        let embedding = SnapshotEmbedding {
+            provider: "test".into(),
+            model: "hash".into(),
+            dim: crate::test_support::TEST_EMBED_DIM,
+        };
+        let progress = ProgressReporter::disabled();
+        let created = create_snapshot(&db, backup.path(), "ns", "db", embedding, 1, &progress)
+            .await
+            .unwrap();
+        let manifest_path = PathBuf::from(&created.manifest_path);
+        let dir = manifest_path.parent().unwrap();
+        let write_lines = |table: &str, lines: &[String]| {
+            let mut out = GzEncoder::new(
+                File::create(dir.join(table_file(table))).unwrap(),
+                Compression::default(),
+            );
+            for line in lines {
+                writeln!(out, "{}", line).unwrap();
+            }
+            out.finish().unwrap();
+        };
+        let entity_file = dir.join(table_file("kg_entities"));
+        let entity_lines: Vec<String> = open_lines(&entity_file)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        let thought_lines: Vec<String> = open_lines(&dir.join(table_file("thoughts")))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        // Changes after the snapshot show whether a table was cleared or reloaded
+        db.query(thought("c")).await.unwrap().check().unwrap();
+        let thought_ids = || async {
+            let mut ids: Vec<String> = db
+                .query("SELECT VALUE meta::id(id) FROM thoughts")
+                .await
+                .unwrap()
+                .take(0)
+                .unwrap();
+            ids.sort();
+            ids
+        };
+        let options = RestoreOptions {
+            expected_dim: crate::test_support::TEST_EMBED_DIM,
+            force: true,
+            batch_size: 1,
+            dry_run: false,
+        };
+
+        // A corrupt file in a later table fails before any table is cleared
+        write_lines("kg_entities", &["{not json".to_string()]);
+        let err = restore_snapshot(&db, &manifest_path, &options, &progress)
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("kg_entities.jsonl.gz line 1: invalid row"),
+            "{err:#}"
+        );
+        assert_eq!(thought_ids().await, ["a", "b", "c"]);
+
+        // A batch the schema rejects rolls back that table's clear and earlier batches
+        write_lines("kg_entities", &entity_lines);
+        let mut bad_row: serde_json::Value = serde_json::from_str(&thought_lines[1]).unwrap();
+        bad_row["Object"]["significance"] = serde_json::json!({"String": "high"});
+        write_lines("thoughts", &[thought_lines[0].clone(), bad_row.to_string()]);
+        let err = restore_snapshot(&db, &manifest_path, &options, &progress)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("restoring thoughts failed"),
+            "{err:#}"
+        );
+        assert_eq!(thought_ids().await, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_snapshot_dir_name_is_sortable() {
+        let at = chrono::DateTime::parse_from_rfc3339("2026-01-02T03:04:05.678Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(snapshot_dir_name(at), "snapshot-20260102T030405678Z");
+    }
+}
@@ -0,0 +1,276 @@
+//! Thought soft delete, restore, and purge.
+//!
+//! [`soft_delete_thoughts`] stamps `deleted_at` on thoughts so search, wander, and duplicate
+//! detection skip them at once, while the rows stay recoverable through [`restore_thoughts`].
+//! [`purge_deleted_thoughts`] removes rows soft-deleted before a cutoff for good.
+
+use crate::server::connection::DbEngine;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use surrealdb::Surreal;
+use surrealdb::types::{Datetime, RecordId, Value};
+
+/// SurrealQL condition that hides soft-deleted thoughts. Rows of other tables have no
+/// `deleted_at` and always pass.
+pub const LIVE_FILTER: &str = "deleted_at = NONE";
+
+/// Which of the requested thoughts exist and whether each is soft-deleted.
+const STATE_SQL: &str =
+    "SELECT meta::id(id) AS id, deleted_at != NONE AS deleted FROM thoughts WHERE id IN $recs";
+
+const DELETE_SQL: &str = "UPDATE thoughts SET deleted_at = time::now() \
+    WHERE id IN $recs AND deleted_at = NONE RETURN VALUE meta::id(id)";
+
+const RESTORE_SQL: &str = "UPDATE thoughts SET deleted_at = NONE \
+    WHERE id IN $recs AND deleted_at != NONE RETURN VALUE meta::id(id)";
+
+const PURGE_CANDIDATES_SQL: &str = "SELECT VALUE meta::id(id) FROM thoughts \
+    WHERE deleted_at != NONE AND deleted_at < $cutoff";
+
+const PURGE_SQL: &str = "DELETE thoughts WHERE id IN $recs AND deleted_at != NONE RETURN NONE";
+
+/// Outcome of a soft delete or restore.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct SoftDeleteStats {
+    pub dry_run: bool,
+    pub requested: usize,
+    /// Thoughts whose `deleted_at` changed (or would, on a dry run)
+    pub updated: Vec<String>,
+    /// Thoughts already in the requested state
+    pub unchanged: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct PurgeStats {
+    pub dry_run: bool,
+    pub older_than_days: u64,
+    /// Thoughts removed (or that would be, on a dry run)
+    pub purged: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateRow {
+    id: String,
+    deleted: bool,
+}
+
+/// Bare, de-duplicated thought ids; `thoughts:` prefixes and blanks are dropped.
+pub fn normalize_thought_ids(ids: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    ids.iter()
+        .map(|id| id.trim())
+        .map(|id| id.strip_prefix("thoughts:").unwrap_or(id))
+        .filter(|id| !id.is_empty() && seen.insert(id.to_string()))
+        .map(str::to_string)
+        .collect()
+}
+
+pub(crate) fn records(ids: &[String]) -> Vec<Value> {
+    ids.iter()
+        .map(|id| Value::RecordId(RecordId::new("thoughts", id.as_str())))
+        .collect()
+}
+
+/// Mark `ids` as deleted so retrieval skips them.
+pub async fn soft_delete_thoughts(
+    db: &Surreal<DbEngine>,
+    ids: &[String],
+    dry_run: bool,
+) -> Result<SoftDeleteStats> {
+    set_deleted(db, ids, true, dry_run).await
+}
+
+/// Clear `deleted_at` on `ids`, returning them to retrieval.
+pub async fn restore_thoughts(
+    db: &Surreal<DbEngine>,
+    ids: &[String],
+    dry_run: bool,
+) -> Result<SoftDeleteStats> {
+    set_deleted(db, ids, false, dry_run).await
+}
+
+async fn set_deleted(
+    db: &Surreal<DbEngine>,
+    ids: &[String],
+    deleted: bool,
+    dry_run: bool,
+) -> Result<SoftDeleteStats> {
+    let ids = normalize_thought_ids(ids);
+    let mut stats = SoftDeleteStats {
+        dry_run,
+        requested: ids.len(),
+        ..SoftDeleteStats::default()
+    };
+    if ids.is_empty() {
+        return Ok(stats);
+    }
+
+    let rows: Vec<serde_json::Value> = db
+        .query(STATE_SQL)
+        .bind(("recs", records(&ids)))
+        .await?
+        .check()?
+        .take(0)?;
+    let rows: Vec<StateRow> = rows
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<std::result::Result<_, _>>()?;
+    let found: HashSet<&str> = rows.iter().map(|r| r.id.as_str()).collect();
+    stats.missing = ids
+        .iter()
+        .filter(|id| !found.contains(id.as_str()))
+        .cloned()
+        .collect();
+
+    let (pending, unchanged): (Vec<StateRow>, Vec<StateRow>) =
+        rows.into_iter().partition(|r| r.deleted != deleted);
+    stats.unchanged = unchanged.into_iter().map(|r| r.id).collect();
+    let pending: Vec<String> = pending.into_iter().map(|r| r.id).collect();
+    stats.updated = if dry_run || pending.is_empty() {
+        pending
+    } else {
+        let sql = if deleted { DELETE_SQL } else { RESTORE_SQL };
+        db.query(sql)
+            .bind(("recs", records(&pending)))
+            .await?
+            .check()?
+            .take(0)?
+    };
+    Ok(stats)
+}
+
+/// Permanently delete thoughts soft-deleted more than `older_than_days` before `now`.
+pub async fn purge_deleted_thoughts(
+    db: &Surreal<DbEngine>,
+    older_than_days: u64,
+    dry_run: bool,
+    now: DateTime<Utc>,
+) -> Result<PurgeStats> {
+    let cutoff = i64::try_from(older_than_days)
+        .ok()
+        .and_then(Duration::try_days)
+        .and_then(|age| now.checked_sub_signed(age))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let cutoff = Datetime::from(cutoff);
+    let purged: Vec<String> = db
+        .query(PURGE_CANDIDATES_SQL)
+        .bind(("cutoff", cutoff))
+        .await?
+        .check()?
+        .take(0)?;
+    if !dry_run && !purged.is_empty() {
+        db.query(PURGE_SQL)
+            .bind(("recs", records(&purged)))
+            .await?
+            .check()?;
+    }
+    Ok(PurgeStats {
+        dry_run,
+        older_than_days,
+        purged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::SurrealMindServer;
+    use crate::test_support::{call, mem_server};
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_thought_ids_strips_prefix_and_dedupes() {
+        let ids = ["thoughts:a", " b ", "a", "", "thoughts:", "c"]
+            .map(str::to_string)
+            .to_vec();
+        assert_eq!(normalize_thought_ids(&ids), vec!["a", "b", "c"]);
+    }
+
+    async fn tool(
+        server: &SurrealMindServer,
+        name: &str,
+        args: serde_json::Value,
+    ) -> serde_json::Value {
+        let request = call(name, args);
+        let result = match name {
+            "think" => server.handle_legacymind_think(request).await,
+            "search" => server.handle_unified_search(request).await,
+            _ => server.handle_maintenance_ops(request).await,
+        };
+        result.unwrap().structured_content.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_hides_restores_and_purges() {
+        let server = mem_server().await;
+        let think = tool(
+            &server,
+            "think",
+            json!({"content": "Soft delete probe", "injection_scale": 0}),
+        )
+        .await;
+        let id = think["delegated_result"]["thought_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server.warm_start().await;
+        assert!(server.thoughts.read().await.contains(&id));
+
+        let found = || async {
+            let out = tool(
+                &server,
+                "search",
+                json!({"thoughts_content": "Soft delete probe", "include_thoughts": true}),
+            )
+            .await;
+            out["thoughts"]["results"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|t| t["id"] == json!(id))
+        };
+        let maintain = |subcommand: &str| {
+            tool(
+                &server,
+                "maintain",
+                json!({"subcommand": subcommand, "ids": [format!("thoughts:{id}")], "older_than_days": 0}),
+            )
+        };
+        assert!(found().await);
+
+        let cached = server.thoughts.read().await.peek(&id).cloned().unwrap();
+        let deleted = maintain("soft_delete_thoughts").await;
+        assert_eq!(deleted["stats"]["updated"], json!([id]));
+        assert!(!server.thoughts.read().await.contains(&id));
+        assert!(!found().await);
+        let again = maintain("soft_delete_thoughts").await;
+        assert_eq!(again["stats"]["unchanged"], json!([id]));
+
+        let restored = maintain("restore_thoughts").await;
+        assert_eq!(restored["stats"]["updated"], json!([id]));
+        assert!(found().await);
+
+        // Only thoughts deleted before the cutoff are purged, and purging evicts them
+        maintain("soft_delete_thoughts").await;
+        let kept = purge_deleted_thoughts(&server.db.client(), 30, false, Utc::now())
+            .await
+            .unwrap();
+        assert!(kept.purged.is_empty());
+        server.thoughts.write().await.put(id.clone(), cached);
+        let purged = maintain("purge_deleted").await;
+        assert_eq!(purged["stats"]["purged"], json!([id]));
+        assert!(!server.thoughts.read().await.contains(&id));
+        let rows: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT id FROM type::record('thoughts', $id)")
+            .bind(("id", id.clone()))
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert!(rows.is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+//! In-process telemetry rendered in the Prometheus text exposition format.
+//!
+//! Counters and histograms are plain atomics in a process-wide [`Metrics`] registry
+//! ([`global`]); recording never blocks on I/O. Tool calls are recorded by the router,
+//! embedder calls (and their tokens per purpose) by the embedder, query errors by the database connection, and cache lookups
+//! by the thought cache. Nothing is exposed unless `SURR_METRICS_ENABLED` is set: then the
+//! HTTP transport serves [`Metrics::render_prometheus`] at `/metrics` and `system_status`
+//! includes the same text.
+
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Histogram bucket upper bounds, in seconds.
+pub const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Content type of [`Metrics::render_prometheus`].
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+static GLOBAL: Lazy<Metrics> = Lazy::new(Metrics::default);
+
+/// The process-wide registry.
+pub fn global() -> &'static Metrics {
+    &GLOBAL
+}
+
+/// Cumulative duration histogram over [`BUCKETS`].
+#[derive(Debug, Default)]
+pub struct Histogram {
+    /// Non-cumulative counts per bucket; the last slot is `+Inf`.
+    buckets: [AtomicU64; BUCKETS.len() + 1],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        let slot = BUCKETS
+            .iter()
+            .position(|&le| secs <= le)
+            .unwrap_or(BUCKETS.len());
+        self.buckets[slot].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0;
+        for (i, le) in BUCKETS.iter().enumerate() {
+            cumulative += self.buckets[i].load(Ordering::Relaxed);
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{{labels}{sep}le=\"{le}\"}} {cumulative}"
+            );
+        }
+        cumulative += self.buckets[BUCKETS.len()].load(Ordering::Relaxed);
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{labels}{sep}le=\"+Inf\"}} {cumulative}"
+        );
+        let braces = |s: &str| {
+            if s.is_empty() {
+                String::new()
+            } else {
+                format!("{{{s}}}")
+            }
+        };
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6;
+        let _ = writeln!(out, "{name}_sum{} {sum}", braces(labels));
+        let _ = writeln!(out, "{name}_count{} {}", braces(labels), self.count());
+    }
+}
+
+/// Calls, errors, and durations for one tool.
+#[derive(Debug, Default)]
+pub struct ToolMetrics {
+    pub calls: AtomicU64,
+    pub errors: AtomicU64,
+    pub duration: Histogram,
+}
+
+/// Successful embedding requests and their API-reported tokens for one purpose.
+#[derive(Debug, Default)]
+pub struct EmbedUsageMetrics {
+    pub calls: AtomicU64,
+    pub tokens: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    tools: Mutex<BTreeMap<String, Arc<ToolMetrics>>>,
+    embed_usage: Mutex<BTreeMap<String, Arc<EmbedUsageMetrics>>>,
+    pub embed_calls: AtomicU64,
+    pub embed_errors: AtomicU64,
+    pub embed_duration: Histogram,
+    pub db_query_errors: AtomicU64,
+    pub cache_hits: AtomicU64,
+    pub cache_misses: AtomicU64,
+}
+
+impl Metrics {
+    /// Counters for `tool`, created on first use.
+    pub fn tool(&self, tool: &str) -> Arc<ToolMetrics> {
+        let mut tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        tools.entry(tool.to_string()).or_default().clone()
+    }
+
+    pub fn record_tool_call(&self, tool: &str, elapsed: Duration, ok: bool) {
+        let t = self.tool(tool);
+        t.calls.fetch_add(1, Ordering::Relaxed);
+        if !ok {
+            t.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        t.duration.observe(elapsed);
+    }
+
+    pub fn record_embed(&self, elapsed: Duration, ok: bool) {
+        self.embed_calls.fetch_add(1, Ordering::Relaxed);
+        if !ok {
+            self.embed_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.embed_duration.observe(elapsed);
+    }
+
+    pub fn record_embed_usage(&self, purpose: &str, tokens: u64) {
+        let u = {
+            let mut usage = self.embed_usage.lock().unwrap_or_else(|e| e.into_inner());
+            usage.entry(purpose.to_string()).or_default().clone()
+        };
+        u.calls.fetch_add(1, Ordering::Relaxed);
+        u.tokens.fetch_add(tokens, Ordering::Relaxed);
+    }
+
+    /// `(calls, tokens)` per purpose since startup.
+    pub fn embed_usage(&self) -> BTreeMap<String, (u64, u64)> {
+        let usage = self.embed_usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage
+            .iter()
+            .map(|(purpose, u)| {
+                (
+                    purpose.clone(),
+                    (
+                        u.calls.load(Ordering::Relaxed),
+                        u.tokens.load(Ordering::Relaxed),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    pub fn record_db_query_error(&self) {
+        self.db_query_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cache_lookup(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Every metric in the Prometheus text exposition format (version 0.0.4).
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        let tools: Vec<(String, Arc<ToolMetrics>)> = self
+            .tools
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, t)| (name.clone(), t.clone()))
+            .collect();
+
+        header(
+            &mut out,
+            "surreal_mind_tool_calls_total",
+            "counter",
+            "MCP tool calls by tool",
+        );
+        for (name, t) in &tools {
+            let _ = writeln!(
+                out,
+                "surreal_mind_tool_calls_total{{tool=\"{}\"}} {}",
+                escape_label(name),
+                t.calls.load(Ordering::Relaxed)
+            );
+        }
+        header(
+            &mut out,
+            "surreal_mind_tool_errors_total",
+            "counter",
+            "MCP tool calls that returned an error",
+        );
+        for (name, t) in &tools {
+            let _ = writeln!(
+                out,
+                "surreal_mind_tool_errors_total{{tool=\"{}\"}} {}",
+                escape_label(name),
+                t.errors.load(Ordering::Relaxed)
+            );
+        }
+        header(
+            &mut out,
+            "surreal_mind_tool_duration_seconds",
+            "histogram",
+            "MCP tool call duration",
+        );
+        for (name, t) in &tools {
+            let labels = format!("tool=\"{}\"", escape_label(name));
+            t.duration
+                .render(&mut out, "surreal_mind_tool_duration_seconds", &labels);
+        }
+
+        counter(
+            &mut out,
+            "surreal_mind_embedder_calls_total",
+            "Embedding requests",
+            &self.embed_calls,
+        );
+        counter(
+            &mut out,
+            "surreal_mind_embedder_errors_total",
+            "Embedding requests that failed after retries",
+            &self.embed_errors,
+        );
+        header(
+            &mut out,
+            "surreal_mind_embedder_duration_seconds",
+            "histogram",
+            "Embedding request duration, retries included",
+        );
+        self.embed_duration
+            .render(&mut out, "surreal_mind_embedder_duration_seconds", "");
+        let usage = self.embed_usage();
+        header(
+            &mut out,
+            "surreal_mind_embedder_purpose_calls_total",
+            "counter",
+            "Successful embedding requests by purpose",
+        );
+        for (purpose, (calls, _)) in &usage {
+            let _ = writeln!(
+                out,
+                "surreal_mind_embedder_purpose_calls_total{{purpose=\"{}\"}} {}",
+                escape_label(purpose),
+                calls
+            );
+        }
+        header(
+            &mut out,
+            "surreal_mind_embedder_tokens_total",
+            "counter",
+            "Embedding tokens reported by the provider, by purpose",
+        );
+        for (purpose, (_, tokens)) in &usage {
+            let _ = writeln!(
+                out,
+                "surreal_mind_embedder_tokens_total{{purpose=\"{}\"}} {}",
+                escape_label(purpose),
+                tokens
+            );
+        }
+        counter(
+            &mut out,
+            "surreal_mind_db_query_errors_total",
+            "Database queries that failed to execute",
+            &self.db_query_errors,
+        );
+        counter(
+            &mut out,
+            "surreal_mind_cache_hits_total",
+            "Thought cache hits",
+            &self.cache_hits,
+        );
+        counter(
+            &mut out,
+            "surreal_mind_cache_misses_total",
+            "Thought cache misses",
+            &self.cache_misses,
+        );
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: &AtomicU64) {
+    header(out, name, "counter", help);
+    let _ = writeln!(out, "{name} {}", value.load(Ordering::Relaxed));
+}
+
+/// Escape a label value per the exposition format: backslash, double quote, and newline.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let h = Histogram::default();
+        h.observe(Duration::from_millis(3));
+        h.observe(Duration::from_millis(40));
+        h.observe(Duration::from_secs(30));
+        let mut out = String::new();
+        h.render(&mut out, "x", "tool=\"t\"");
+        assert!(out.contains("x_bucket{tool=\"t\",le=\"0.005\"} 1\n"));
+        assert!(out.contains("x_bucket{tool=\"t\",le=\"0.05\"} 2\n"));
+        assert!(out.contains("x_bucket{tool=\"t\",le=\"10\"} 2\n"));
+        assert!(out.contains("x_bucket{tool=\"t\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("x_count{tool=\"t\"} 3\n"));
+        assert!(out.contains("x_sum{tool=\"t\"} 30.043\n"));
+    }
+
+    #[test]
+    fn test_render_counts_tools_and_escapes_labels() {
+        let m = Metrics::default();
+        m.record_tool_call("search", Duration::from_millis(20), true);
+        m.record_tool_call("search", Duration::from_millis(20), false);
+        m.record_tool_call("we\"ird", Duration::ZERO, true);
+        m.record_cache_lookup(true);
+        m.record_db_query_error();
+        m.record_embed_usage("search_query", 7);
+        m.record_embed_usage("search_query", 5);
+        let out = m.render_prometheus();
+        assert!(out.contains("surreal_mind_tool_calls_total{tool=\"search\"} 2\n"));
+        assert!(out.contains("surreal_mind_tool_errors_total{tool=\"search\"} 1\n"));
+        assert!(out.contains("surreal_mind_tool_calls_total{tool=\"we\\\"ird\"} 1\n"));
+        assert!(out.contains("surreal_mind_cache_hits_total 1\n"));
+        assert!(out.contains("surreal_mind_db_query_errors_total 1\n"));
+        assert!(
+            out.contains("surreal_mind_embedder_purpose_calls_total{purpose=\"search_query\"} 2\n")
+        );
+        assert!(out.contains("surreal_mind_embedder_tokens_total{purpose=\"search_query\"} 12\n"));
+        assert!(out.contains("surreal_mind_embedder_duration_seconds_count 0\n"));
+        assert!(out.ends_with('\n'));
+    }
+}
@@ -0,0 +1,62 @@
+//! Versioned LLM prompt templates.
+//!
+//! KG extraction prompts are registered by version so a batch can be re-run with the prompt
+//! that produced it or with a newer one. A new version is a new file plus a registry entry;
+//! released templates are never edited in place.
+
+use crate::error::{Result, SurrealMindError};
+
+/// Knowledge-graph extraction prompts, oldest first.
+pub const EXTRACTION_PROMPTS: &[(&str, &str)] = &[("v1", include_str!("kg_extraction_v1.md"))];
+
+/// Newest registered extraction prompt version, used when none is requested.
+pub fn latest_extraction_version() -> &'static str {
+    EXTRACTION_PROMPTS
+        .last()
+        .map(|(version, _)| *version)
+        .unwrap_or_default()
+}
+
+/// Version and template of the extraction prompt `version` (latest when `None` or blank).
+pub fn extraction_prompt(version: Option<&str>) -> Result<(&'static str, &'static str)> {
+    let wanted = version
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or(latest_extraction_version());
+    EXTRACTION_PROMPTS
+        .iter()
+        .find(|(v, _)| *v == wanted)
+        .copied()
+        .ok_or_else(|| SurrealMindError::Validation {
+            message: format!(
+                "Unknown extraction prompt version '{}' (known: {})",
+                wanted,
+                EXTRACTION_PROMPTS
+                    .iter()
+                    .map(|(v, _)| *v)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extraction_prompt_defaults_to_latest_and_rejects_unknown() {
+        let (latest, template) = extraction_prompt(None).unwrap();
+        assert_eq!(latest, latest_extraction_version());
+        assert_eq!(extraction_prompt(Some(" ")).unwrap().0, latest);
+
+        let (v1, v1_template) = extraction_prompt(Some("v1")).unwrap();
+        assert_eq!(v1, "v1");
+        assert!(v1_template.starts_with("# Knowledge Graph Extraction Prompt v1"));
+        if latest == "v1" {
+            assert_eq!(template, v1_template);
+        }
+
+        assert!(extraction_prompt(Some("v0")).is_err());
+    }
+}
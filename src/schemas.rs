@@ -1,6 +1,23 @@
 use serde_json::{Map, Value, json};
 use std::sync::Arc;
 
+pub mod validate;
+
+/// Add the `namespace`/`database` overrides of `server::tenants::TENANT_TOOLS`.
+fn with_tenant_override(mut schema: Value) -> Arc<Map<String, Value>> {
+    if let Some(properties) = schema["properties"].as_object_mut() {
+        properties.insert(
+            "namespace".into(),
+            json!({"type": "string", "description": "Run against this namespace instead of the configured one; the pair must be in [multi_tenant] allowed"}),
+        );
+        properties.insert(
+            "database".into(),
+            json!({"type": "string", "description": "Run against this database instead of the configured one; the pair must be in [multi_tenant] allowed"}),
+        );
+    }
+    Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
+}
+
 pub fn think_schema() -> Arc<Map<String, Value>> {
     let schema = json!({
         "type": "object",
@@ -22,11 +39,81 @@ pub fn think_schema() -> Arc<Map<String, Value>> {
             "verify_top_k": {"type": "integer", "minimum": 1, "maximum": 500},
             "min_similarity": {"type": "number", "minimum": 0.0, "maximum": 1.0},
             "evidence_limit": {"type": "integer", "minimum": 1, "maximum": 25},
-            "contradiction_patterns": {"type": "array", "items": {"type": "string"}}
+            "contradiction_patterns": {"type": "array", "items": {"type": "string"}},
+            "scoring": {"type": "string", "enum": ["simple", "weighted"], "default": "simple", "description": "Verification confidence: simple (supporting share) or weighted (similarity-weighted vote)"},
+            "idempotency_key": {"type": "string", "description": "Retry key; repeating a call with the same key returns the original thought"},
+            "suggest_tags": {"type": "boolean", "description": "Return suggested_tags from similar KG entities (default from SURR_THINK_SUGGEST_TAGS)"},
+            "is_private": {"type": "boolean", "default": false, "description": "Hide the thought from search, chain summaries, and wander unless include_private is passed"},
+            "pinned": {"type": "boolean", "default": false, "description": "Inject this thought into later thinks whose submode and tags it matches, regardless of similarity"},
+            "response_detail": {"type": "string", "enum": ["full", "compact", "ids_only"], "description": "Result size: full (default from SURR_THINK_RESPONSE_DETAIL), compact (thought_id, mode_selected, memories_injected, links summary, verification counts), or ids_only (thought_id)"},
+            "references": {
+                "type": "array",
+                "maxItems": 20,
+                "description": "Files (absolute paths), URLs, or commit SHAs this thought is about",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "kind": {"type": "string", "enum": ["file", "url", "commit"]},
+                        "value": {"type": "string"},
+                        "note": {"type": "string"}
+                    },
+                    "required": ["kind", "value"]
+                }
+            }
         },
         "required": ["content"]
     });
-    Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
+    with_tenant_override(schema)
+}
+
+pub fn think_batch_schema() -> Arc<Map<String, Value>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "thoughts": {
+                "type": "array",
+                "minItems": 1,
+                "maxItems": 20,
+                "description": "Thoughts created in one transaction: all are stored or none",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "content": {"type": "string"},
+                        "local_ref": {"type": "string", "description": "Name later items can use in previous_thought_id, revises_thought, or branch_from"},
+                        "hint": {"type": "string", "enum": ["debug", "build", "plan", "stuck", "question", "conclude"]},
+                        "injection_scale": {"type": ["integer", "string"]},
+                        "tags": {"type": "array", "items": {"type": "string"}},
+                        "significance": {"type": ["number", "string"]},
+                        "session_id": {"type": "string"},
+                        "chain_id": {"type": "string"},
+                        "previous_thought_id": {"type": "string"},
+                        "revises_thought": {"type": "string"},
+                        "branch_from": {"type": "string"},
+                        "confidence": {"type": "number", "minimum": 0.0, "maximum": 1.0},
+                        "idempotency_key": {"type": "string", "description": "Stored on the thought; a key that already exists fails the whole batch"},
+                        "is_private": {"type": "boolean", "default": false},
+                        "pinned": {"type": "boolean", "default": false},
+                        "references": {
+                            "type": "array",
+                            "maxItems": 20,
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "kind": {"type": "string", "enum": ["file", "url", "commit"]},
+                                    "value": {"type": "string"},
+                                    "note": {"type": "string"}
+                                },
+                                "required": ["kind", "value"]
+                            }
+                        }
+                    },
+                    "required": ["content"]
+                }
+            }
+        },
+        "required": ["thoughts"]
+    });
+    with_tenant_override(schema)
 }
 
 pub fn call_gem_schema() -> Arc<Map<String, Value>> {
@@ -187,9 +274,70 @@ pub fn remember_schema() -> Arc<Map<String, Value>> {
             "data": {"type": "object"},
             "upsert": {"type": "boolean", "default": true},
             "source_thought_id": {"type": "string"},
-            "confidence": {"type": "number", "minimum": 0.0, "maximum": 1.0}
+            "source_thought_ids": {"type": "array", "items": {"type": "string"}, "description": "Observations: the thoughts this observation came from"},
+            "confidence": {"type": "number", "minimum": 0.0, "maximum": 1.0},
+            "items": {
+                "type": "array",
+                "description": "Batch of {kind, data, upsert} items processed in order; relationships may reference names created earlier in the batch",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "kind": {"type": "string", "enum": ["entity", "relationship", "observation"], "default": "entity"},
+                        "data": {"type": "object"},
+                        "upsert": {"type": "boolean"}
+                    },
+                    "required": ["kind", "data"]
+                }
+            },
+            "atomic": {"type": "boolean", "default": false, "description": "Batch only: stop at the first failure and roll back items created by the batch"}
+        },
+        "anyOf": [
+            {"required": ["kind", "data"]},
+            {"required": ["items"]}
+        ]
+    });
+    with_tenant_override(schema)
+}
+
+pub fn forget_schema() -> Arc<Map<String, Value>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "kind": {"type": "string", "enum": ["entity", "relationship", "observation"], "default": "entity"},
+            "id": {"type": "string", "description": "Record id (bare or table-prefixed)"},
+            "name": {"type": "string", "description": "Entity/observation name when id is not known"},
+            "entity_type": {"type": "string", "description": "Disambiguates name lookups"},
+            "cascade": {"type": "boolean", "default": false, "description": "Also delete edges that reference the record"},
+            "reason": {"type": "string", "description": "Stored in the kg_deletions audit record"}
         },
-        "required": ["kind", "data"]
+        "required": ["kind"]
+    });
+    with_tenant_override(schema)
+}
+
+pub fn export_schema() -> Arc<Map<String, Value>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "path": {"type": "string", "description": "Absolute output file path (written via temp file + rename)"},
+            "format": {"type": "string", "enum": ["jsonl", "graphml"], "default": "jsonl"},
+            "include_embeddings": {"type": "boolean", "default": false, "description": "Keep embedding vectors in JSONL output"},
+            "page_size": {"type": "integer", "minimum": 1, "maximum": 5000, "default": 500, "description": "Rows fetched per database page"}
+        },
+        "required": ["path"]
+    });
+    Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
+}
+
+pub fn import_schema() -> Arc<Map<String, Value>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "path": {"type": "string", "description": "Absolute path to a JSONL file written by export"},
+            "mode": {"type": "string", "enum": ["skip", "overwrite", "rename"], "default": "skip", "description": "What to do when a record id already exists"},
+            "batch_size": {"type": "integer", "minimum": 1, "maximum": 1000, "default": 100, "description": "Records written per database round trip"}
+        },
+        "required": ["path"]
     });
     Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
 }
@@ -198,23 +346,8 @@ pub fn howto_schema() -> Arc<Map<String, Value>> {
     let schema = json!({
         "type": "object",
         "properties": {
-            "tool": {"type": "string", "enum": [
-                "think",
-                "remember",
-                "search",
-                "maintain",
-                "call_gem",
-                "call_cc",
-                "call_vibe",
-                "call_status",
-                "call_jobs",
-                "call_cancel",
-                "wander",
-                "howto",
-                "journal",
-                "rethink",
-                "corrections"
-            ]},
+            "tool": {"type": "string", "enum": crate::tools::howto::tool_names()},
+            "section": {"type": "string", "enum": ["params", "examples", "all"], "default": "all", "description": "Part of the tool's help to return"},
             "format": {"type": "string", "enum": ["compact", "full"], "default": "full"}
         }
     });
@@ -225,14 +358,41 @@ pub fn maintain_schema() -> Arc<Map<String, Value>> {
     let schema = json!({
         "type": "object",
         "properties": {
-            "subcommand": {"type": "string", "enum": ["list_removal_candidates", "export_removals", "finalize_removal", "health_check_embeddings", "health_check_indexes", "reembed", "reembed_kg", "embed_pending", "ensure_continuity_fields", "echo_config", "corrections", "rethink", "consolidate", "populate", "embed", "wander", "health", "report", "tasks"], "description": "Maintenance operation to perform"},
+            "subcommand": {"type": "string", "enum": ["list_removal_candidates", "export_removals", "finalize_removal", "health_check_embeddings", "health_check_indexes", "embedding_audit", "rebuild_vector_index", "decay_thoughts", "repair_continuity_links", "normalize_observations", "backfill_lang", "observation_provenance_report", "normalize_entity_types", "dedupe_entities", "soft_delete_thoughts", "restore_thoughts", "update_thought", "summarize_session", "hypothesis_trend", "set_privacy", "pin_thoughts", "unpin_thoughts", "purge_deleted", "audit_summary", "embedding_usage_report", "providers_check", "reembed", "finalize_migration", "reembed_kg", "embed_edges", "embed_pending", "ensure_continuity_fields", "schema_check", "migration_status", "snapshot_create", "snapshot_restore", "echo_config", "corrections", "rethink", "consolidate", "populate", "embed", "wander", "health", "report", "tasks"], "description": "Maintenance operation to perform"},
             "dry_run": {"type": "boolean", "default": false, "description": "Simulate operation without making changes"},
             "limit": {"type": ["integer", "number", "string"], "default": 100, "description": "Maximum number of thoughts to process"},
             "format": {"type": "string", "enum": ["json", "parquet"], "default": "json", "description": "Export format"},
             "output_dir": {"type": "string", "default": "./archive", "description": "Directory for export files"},
             "tasks": {"type": "string", "description": "Comma-separated tasks for subcommand 'tasks'"},
             "target_id": {"type": "string", "description": "Optional target filter (corrections subcommand)"},
-            "rethink_types": {"type": "string", "description": "Comma-separated mark types (rethink subcommand)"}
+            "rethink_types": {"type": "string", "description": "Comma-separated mark types (rethink subcommand)"},
+            "concurrency": {"type": ["integer", "number", "string"], "minimum": 1, "default": 1, "description": "Parallel embedding requests (reembed subcommand)"},
+            "sample": {"type": ["integer", "number", "string"], "minimum": 0, "default": 0, "description": "Example ids per problematic group (embedding_audit subcommand)"},
+            "offset": {"type": ["integer", "number", "string"], "minimum": 0, "default": 0, "description": "Thoughts to skip before scanning (decay_thoughts subcommand)"},
+            "dangling_after_days": {"type": ["integer", "number", "string"], "minimum": 0, "description": "List links still missing on thoughts at least this old (repair_continuity_links subcommand)"},
+            "since": {"type": "string", "description": "Window start, RFC 3339 inclusive (audit_summary subcommand); first day YYYY-MM-DD (embedding_usage_report subcommand)"},
+            "until": {"type": "string", "description": "Window end, RFC 3339 exclusive (audit_summary subcommand); last day YYYY-MM-DD, inclusive (embedding_usage_report subcommand)"},
+            "ids": {"type": ["array", "string"], "items": {"type": "string"}, "description": "Thought ids (soft_delete_thoughts / restore_thoughts / set_privacy / pin_thoughts / unpin_thoughts subcommands)"},
+            "older_than_days": {"type": ["integer", "number", "string"], "minimum": 0, "description": "Purge thoughts soft-deleted at least this many days ago, default 30 (purge_deleted subcommand)"},
+            "private": {"type": "boolean", "default": true, "description": "Value to set on is_private (set_privacy subcommand)"},
+            "prompt_version": {"type": "string", "description": "Extraction prompt version for populate (default: latest)"},
+            "reprocess_batch": {"type": "string", "description": "Extraction batch id whose thoughts populate re-extracts; the old batch's KG rows are tagged superseded_by_batch"},
+            "apply": {"type": "boolean", "default": false, "description": "Run the DEFINE statements for missing tables, fields, and indexes (schema_check subcommand); never drops anything"},
+            "manifest_path": {"type": "string", "description": "manifest.json of the snapshot to load (snapshot_restore subcommand)"},
+            "confirm": {"type": "boolean", "default": false, "description": "Required for snapshot_restore to write anything"},
+            "force": {"type": "boolean", "default": false, "description": "Let snapshot_restore clear tables that already hold rows instead of refusing, or update_thought edit a synthesized (inner_voice) thought"},
+            "thought_id": {"type": "string", "description": "Thought to edit (update_thought subcommand)"},
+            "mode": {"type": "string", "enum": ["replace_content", "append"], "description": "Replace the content or append after a blank line (update_thought subcommand)"},
+            "content": {"type": "string", "description": "New content, or the text to append (update_thought subcommand)"},
+            "reason": {"type": "string", "description": "Why the thought was edited; stored with the revision (update_thought subcommand)"},
+            "session_id": {"type": "string", "description": "Session whose thoughts are rolled up into one summary thought (summarize_session subcommand)"},
+            "demote_factor": {"type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Multiply summarized thoughts' significance by this the first time a summary covers them; overrides [maintenance.summarize] (summarize_session subcommand)"},
+            "hypothesis_hash": {"type": "string", "description": "verification.hypothesis_hash of a verified hypothesis (hypothesis_trend subcommand)"},
+            "hypothesis": {"type": "string", "description": "Hypothesis text, hashed like think verification when hypothesis_hash is absent (hypothesis_trend subcommand)"},
+            "to_v2": {"type": "boolean", "description": "Backfill embedding_v2 from the [embedding_migration] target instead of re-embedding the primary field (reembed subcommand)"},
+            "survivor": {"type": "string", "enum": ["oldest", "most_edges"], "description": "Which duplicate entity survives, default oldest (dedupe_entities subcommand)"},
+            "skip": {"type": ["array", "string"], "items": {"type": "string", "enum": ["embedder", "gemini"]}, "description": "Providers not to probe (providers_check subcommand)"},
+            "similarity_threshold": {"type": "number", "minimum": 0, "maximum": 1, "description": "Also group names with at least this Jaro-Winkler similarity (dedupe_entities subcommand); for normalize_entity_types, the similarity to the nearest taxonomy type needed to rewrite an off-taxonomy type (default 0.85)"}
         },
         "required": ["subcommand"]
     });
@@ -256,15 +416,45 @@ pub fn search_schema() -> Arc<Map<String, Value>> {
             "revises_thought": {"type": "string"},
             "branch_from": {"type": "string"},
             "origin": {"type": "string"},
+            "lang": {"type": "string", "description": "Detected thought language: ISO 639-1 code (en, de, ru, ja, ...) or 'und'"},
+            "min_significance": {"type": "number", "minimum": 0.0, "maximum": 1.0},
             "confidence_gte": {"type": "number", "minimum": 0.0, "maximum": 1.0},
             "confidence_lte": {"type": "number", "minimum": 0.0, "maximum": 1.0},
-            "date_from": {"type": "string", "pattern": "^\\d{4}-\\d{2}-\\d{2}$"},
-            "date_to": {"type": "string", "pattern": "^\\d{4}-\\d{2}-\\d{2}$"},
-            "order": {"type": "string", "enum": ["created_at_asc", "created_at_desc"]},
-            "forensic": {"type": "boolean", "description": "Return provenance: correction chain, derivatives, sources"}
+            "date_from": {"type": "string", "description": "Thoughts created at or after: YYYY-MM-DD (start of day UTC), RFC 3339, now, today, yesterday, or -N[h|d|w]"},
+            "date_to": {"type": "string", "description": "Thoughts created at or before: YYYY-MM-DD (end of day UTC), RFC 3339, now, today, yesterday, or -N[h|d|w]"},
+            "order": {"type": "string", "enum": ["created_at_asc", "created_at_desc", "final_score"]},
+            "forensic": {"type": "boolean", "description": "Return provenance: correction chain, derivatives, sources"},
+            "chain_summary": {"type": "boolean", "default": false, "description": "Return the thought chain for chain_id or thought_id (ordered thoughts, continuity links, adjacency) instead of searching"},
+            "thought_id": {"type": "string", "description": "With chain_summary: discover the chain by walking previous_thought_id from this thought"},
+            "max_thoughts": {"type": ["integer", "number", "string"], "minimum": 1, "maximum": 500, "default": 50},
+            "entity_timeline": {"type": "boolean", "default": false, "description": "Return the chronological events (created, edge_added, observation, mention) of entity instead of searching"},
+            "entity": {"type": "string", "description": "With entity_timeline: entity id (bare or kg_entities:...) or exact name"},
+            "max_events": {"type": ["integer", "number", "string"], "minimum": 1, "maximum": 500, "default": 50, "description": "Events per timeline page"},
+            "max_mentions": {"type": ["integer", "number", "string"], "minimum": 0, "maximum": 500, "description": "Cap on thoughts found by the entity similarity scan (default [kg] timeline_max_mentions; 0 skips the scan)"},
+            "mention_threshold": {"type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Cosine similarity at which a thought counts as a mention (default [kg] timeline_mention_threshold)"},
+            "cursor": {"type": "string", "description": "Timeline page start: next_cursor from the previous page (events strictly after it)"},
+            "expand_graph": {"type": "boolean", "default": false, "description": "Add entities linked to entity hits via kg_edges"},
+            "include_names": {"type": "boolean", "default": true, "description": "Attach source_name/target_name (and resolved) to relationship hits with one extra entity lookup"},
+            "graph_depth": {"type": ["integer", "number", "string"], "minimum": 1, "maximum": 3, "default": 1},
+            "graph_boost": {"type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Neighbor score as a fraction of the hit's similarity (default SURR_KG_GRAPH_BOOST)"},
+            "min_edge_strength": {"type": "number", "description": "Skip edges whose strength is below this (default SURR_KG_MIN_EDGE_STRENGTH)"},
+            "as_of": {"type": "string", "description": "Only follow and return relationships whose valid_from/valid_to window contains this date (YYYY-MM-DD, RFC 3339, now, today, yesterday, or -N[h|d|w])"},
+            "include_superseded": {"type": "boolean", "default": false, "description": "Return revised thoughts as-is instead of substituting their latest revision"},
+            "has_reference": {"type": "boolean", "description": "Only thoughts with (true) or without (false) references"},
+            "reference_contains": {"type": "string", "description": "Only thoughts with a reference whose value contains this substring"},
+            "code_ref_contains": {"type": "string", "description": "Only thoughts whose content mentions a file path, error code, or URL containing this substring (case-insensitive; 'file.rs:42' matches the line too)"},
+            "include_private": {"type": "boolean", "default": false, "description": "Also return thoughts marked is_private (thought search and chain_summary)"},
+            "only_pinned": {"type": "boolean", "default": false, "description": "Only return pinned thoughts (thought search)"},
+            "tag_expr": {"type": "string", "description": "Tag filter with AND, OR, NOT, and parentheses, e.g. \"(rust AND surrealdb) OR mcp\"; applies to thought tags and memory data.tags. Quote tags that are keywords or contain spaces"},
+            "recency_half_life_days": {"type": "number", "minimum": 0, "description": "Halve a thought's similarity per this many days of age; 0 or unset disables"},
+            "significance_weight": {"type": "number", "description": "Add this times significance to each thought's score (default 0)"},
+            "explain_scores": {"type": "boolean", "default": false, "description": "Attach score {sim, recency_factor, significance_bonus, final} to each thought"},
+            "snippet_window": {"type": ["integer", "number", "string"], "minimum": 1, "default": 240, "description": "Characters in each thought hit's highlight window (span_start/span_end/snippet)"},
+            "snippet_only": {"type": "boolean", "default": false, "description": "Return each thought hit's snippet instead of its full content"},
+            "embedding_field": {"type": "string", "enum": ["embedding", "embedding_v2"], "description": "Vector field to search; the query is embedded with the configured model stored in that field (default: embedding with the active embedder)"}
         }
     });
-    Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
+    with_tenant_override(schema)
 }
 
 pub fn test_notification_schema() -> Arc<Map<String, Value>> {
@@ -290,6 +480,7 @@ pub struct Snippet {
     pub text: String,
     pub score: f32,
     pub content_hash: String,
+    /// Byte offsets of the highlighted span of `text` (see `utils::highlight::best_window`)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub span_start: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -309,6 +500,7 @@ pub fn wander_schema() -> Arc<Map<String, Value>> {
             "mode": {"type": "string", "enum": ["random", "semantic", "meta", "marks"], "description": "Traversal mode"},
             "visited_ids": {"type": "array", "items": {"type": "string"}, "description": "IDs to avoid preventing loops"},
             "recency_bias": {"type": "boolean", "default": false, "description": "Whether to prioritize recent memories"},
+            "include_private": {"type": "boolean", "default": false, "description": "Also wander onto thoughts marked is_private"},
             "for": {"type": "string", "enum": ["cc", "codex", "sam", "gemini", "dt", "gem"], "description": "Filter marks assigned to a specific federation member (marks mode only)"}
         },
         "required": ["mode"]
@@ -327,6 +519,105 @@ pub fn corrections_schema() -> Arc<Map<String, Value>> {
     Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
 }
 
+pub fn system_status_schema() -> Arc<Map<String, Value>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "deep": {"type": "boolean", "default": false, "description": "Also embed a short probe string to verify the embedding provider (bounded by a 5s timeout)"}
+        }
+    });
+    Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
+}
+
+pub fn embedding_probe_schema() -> Arc<Map<String, Value>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "text_a": {"type": "string", "description": "Text to embed and compare"},
+            "text_b": {"type": "string", "description": "Second text; use this or table + id"},
+            "table": {"type": "string", "enum": ["thoughts", "kg_entities", "kg_observations"], "description": "Table of a stored record to compare against"},
+            "id": {"type": "string", "description": "Record id (bare or table:id); its stored embedding is reused when the dimension matches"}
+        },
+        "required": ["text_a"]
+    });
+    Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
+}
+
+/// Output schema for `system_status`; every key is always present.
+pub fn system_status_output_schema() -> Arc<Map<String, Value>> {
+    let count = json!({"type": ["integer", "null"]});
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "ok": {"type": "boolean"},
+            "database": {
+                "type": "object",
+                "properties": {
+                    "ok": {"type": "boolean"},
+                    "latency_ms": {"type": "integer"},
+                    "error": {"type": ["string", "null"]},
+                    "connection": {"type": "object"}
+                },
+                "required": ["ok", "latency_ms", "error", "connection"]
+            },
+            "embedder": {
+                "type": "object",
+                "properties": {
+                    "provider": {"type": "string"},
+                    "model": {"type": "string"},
+                    "dimensions": {"type": "integer"},
+                    "rate_limit": {
+                        "type": ["object", "null"],
+                        "properties": {
+                            "requests_per_minute": {"type": "integer"},
+                            "tokens_per_minute": {"type": "integer"},
+                            "available_requests": count,
+                            "available_tokens": count,
+                            "queued_waiters": {"type": "integer"},
+                            "paused_ms": {"type": "integer"},
+                            "throttled_responses": {"type": "integer"}
+                        }
+                    },
+                    "deep_check": {
+                        "type": ["object", "null"],
+                        "properties": {
+                            "ok": {"type": "boolean"},
+                            "latency_ms": {"type": "integer"},
+                            "dimensions": {"type": ["integer", "null"]},
+                            "error": {"type": ["string", "null"]}
+                        }
+                    }
+                },
+                "required": ["provider", "model", "dimensions", "rate_limit", "deep_check"]
+            },
+            "cache": {
+                "type": "object",
+                "properties": {
+                    "size": {"type": "integer"},
+                    "capacity": {"type": "integer"},
+                    "hits": {"type": "integer"},
+                    "misses": {"type": "integer"},
+                    "hit_rate": {"type": "number"}
+                },
+                "required": ["size", "capacity", "hits", "misses", "hit_rate"]
+            },
+            "tables": {
+                "type": "object",
+                "properties": {
+                    "thoughts": count,
+                    "kg_entities": count,
+                    "kg_edges": count,
+                    "kg_observations": count,
+                    "kg_entity_candidates": count,
+                    "kg_edge_candidates": count
+                }
+            }
+        },
+        "required": ["ok", "database", "embedder", "cache", "tables"]
+    });
+    Arc::new(schema.as_object().cloned().unwrap_or_else(Map::new))
+}
+
 pub fn journal_schema() -> Arc<Map<String, Value>> {
     let schema = json!({
         "type": "object",
@@ -0,0 +1,158 @@
+//! Validation of tool arguments against the input schemas in [`crate::schemas`]
+//!
+//! Covers the JSON Schema subset those schemas use: `type` (one or a list), `enum`,
+//! `minimum`/`maximum`, `minItems`/`maxItems`, `items`, `required`, `anyOf`, and
+//! `properties`. An object schema with `properties` is closed unless it sets
+//! `additionalProperties: true`; one without `properties` (such as `remember`'s `data`)
+//! takes anything. Errors name the offending field as a path like `search.target` or
+//! `think_batch.thoughts[2].content`.
+
+use serde_json::Value;
+
+/// Check `value` against `schema`; `path` names `value` in the error.
+pub fn validate(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(types) = schema.get("type") {
+        let types: Vec<&str> = match types {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        let matches = |t: &str| match t {
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            "null" => value.is_null(),
+            _ => false,
+        };
+        if !types.iter().any(|t| matches(t)) {
+            return Err(format!("{path}: {value} is not {types:?}"));
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        return Err(format!("{path}: {value} is not one of {allowed:?}"));
+    }
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+            && n < min
+        {
+            return Err(format!("{path}: {n} < minimum {min}"));
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+            && n > max
+        {
+            return Err(format!("{path}: {n} > maximum {max}"));
+        }
+    }
+    if let Some(items) = value.as_array() {
+        let len = items.len() as u64;
+        if schema
+            .get("minItems")
+            .and_then(Value::as_u64)
+            .is_some_and(|m| len < m)
+            || schema
+                .get("maxItems")
+                .and_then(Value::as_u64)
+                .is_some_and(|m| len > m)
+        {
+            return Err(format!("{path}: {len} items is outside minItems/maxItems"));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                validate(item_schema, item, &format!("{path}[{i}]"))?;
+            }
+        }
+    }
+    if let Some(object) = value.as_object() {
+        let missing = |required: &Value| {
+            required
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .find(|key| !object.contains_key(*key))
+                .map(str::to_string)
+        };
+        if let Some(key) = schema.get("required").and_then(missing) {
+            return Err(format!("{path}: missing required '{key}'"));
+        }
+        if let Some(any_of) = schema.get("anyOf").and_then(Value::as_array)
+            && !any_of.iter().any(|alt| validate(alt, value, path).is_ok())
+        {
+            return Err(format!("{path}: matches none of anyOf"));
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            let open = schema.get("additionalProperties") == Some(&Value::Bool(true));
+            for (key, field) in object {
+                match properties.get(key) {
+                    Some(property) => validate(property, field, &format!("{path}.{key}"))?,
+                    None if open => {}
+                    None => return Err(format!("{path}: unknown property '{key}'")),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_rejects_schema_violations() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "mode": {"type": "string", "enum": ["a", "b"]},
+                "limit": {"type": "integer", "minimum": 1, "maximum": 10}
+            },
+            "required": ["mode"]
+        });
+        assert!(validate(&schema, &json!({"mode": "a", "limit": 3}), "t").is_ok());
+        for bad in [
+            json!({"limit": 3}),
+            json!({"mode": "c"}),
+            json!({"mode": "a", "limit": 11}),
+            json!({"mode": "a", "limit": 1.5}),
+            json!({"mode": "a", "extra": true}),
+        ] {
+            assert!(validate(&schema, &bad, "t").is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_validate_names_nested_fields_and_honors_additional_properties() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "items": {"type": "array", "items": {
+                    "type": "object",
+                    "properties": {"kind": {"type": "string", "enum": ["entity"]}}
+                }},
+                "data": {"type": "object"},
+                "meta": {"type": "object", "properties": {}, "additionalProperties": true}
+            }
+        });
+        let err = validate(
+            &schema,
+            &json!({"items": [{"kind": "entity"}, {"kind": "entitty"}]}),
+            "remember",
+        )
+        .unwrap_err();
+        assert!(err.starts_with("remember.items[1].kind:"), "{err}");
+        assert!(
+            validate(
+                &schema,
+                &json!({"data": {"anything": 1}, "meta": {"free": true}}),
+                "t"
+            )
+            .is_ok()
+        );
+    }
+}
@@ -0,0 +1,475 @@
+//! `surreal-mind --self-test`: end-to-end smoke run against a scratch database
+//!
+//! Connects to the `[self_test]` namespace/database (never the configured one), applies the
+//! migrations, then drives the tool handlers in order: think, search, remember (entities and
+//! a relationship), KG search, and a re-embed of the probe thought. Embeddings come from
+//! [`HashEmbedder`], so nothing but SurrealDB is contacted. Each step reports pass/fail/skip
+//! with its timing; a step whose input came from a failed step is skipped. The rows the run
+//! creates are removed at the end.
+
+use crate::config::Config;
+use crate::embeddings::HashEmbedder;
+use crate::server::SurrealMindServer;
+use crate::server::connection::{DbConnection, DbSettings};
+use crate::tools::thinking::update::EditMode;
+use rmcp::model::{CallToolRequestParams, CallToolResult};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Dimensions used when `embedding_dimensions` is unset (0).
+const FALLBACK_DIM: usize = 1536;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepReport {
+    pub step: &'static str,
+    pub status: StepStatus,
+    pub ms: u64,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub ns: String,
+    pub db: String,
+    pub passed: bool,
+    pub total_ms: u64,
+    pub steps: Vec<StepReport>,
+}
+
+impl SelfTestReport {
+    /// One line per step, then a PASS/FAIL summary.
+    pub fn render(&self) -> String {
+        let mut out = format!("surreal-mind self-test on {}/{}\n", self.ns, self.db);
+        for s in &self.steps {
+            let status = match s.status {
+                StepStatus::Pass => "pass",
+                StepStatus::Fail => "FAIL",
+                StepStatus::Skip => "skip",
+            };
+            out.push_str(&format!(
+                "  {:<22} {:<4} {:>6}ms  {}\n",
+                s.step, status, s.ms, s.detail
+            ));
+        }
+        let failed = self
+            .steps
+            .iter()
+            .filter(|s| s.status == StepStatus::Fail)
+            .count();
+        out.push_str(&format!(
+            "{} ({} failed, {}ms)\n",
+            if self.passed { "PASS" } else { "FAIL" },
+            failed,
+            self.total_ms
+        ));
+        out
+    }
+}
+
+/// `config` pointed at the `[self_test]` database with the hash embedder's provider/model.
+pub fn self_test_config(config: &Config) -> Config {
+    let mut scratch = config.clone();
+    scratch.system.database_ns = config.self_test.ns.clone();
+    scratch.system.database_db = config.self_test.db.clone();
+    scratch.system.embedding_provider = "hash".to_string();
+    scratch.system.embedding_model = "hash".to_string();
+    if scratch.system.embedding_dimensions == 0 {
+        scratch.system.embedding_dimensions = FALLBACK_DIM;
+    }
+    scratch.embedding_migration = Default::default();
+    scratch.runtime.warm_start = false;
+    scratch
+}
+
+/// Connect to the scratch database and run every step.
+pub async fn run_self_test(config: &Config) -> anyhow::Result<SelfTestReport> {
+    config.self_test.validate(&config.system)?;
+    let scratch = self_test_config(config);
+    let started = Instant::now();
+    let db = DbConnection::connect(DbSettings::from_config(&scratch), 1).await?;
+    let embedder = Arc::new(HashEmbedder {
+        dim: scratch.system.embedding_dimensions,
+    });
+    let server = SurrealMindServer::from_parts(&scratch, db, embedder, None).await?;
+    let mut report = run_steps(&server).await;
+    report.total_ms = started.elapsed().as_millis() as u64;
+    Ok(report)
+}
+
+/// Steps in order, recording each outcome.
+#[derive(Default)]
+struct Steps {
+    steps: Vec<StepReport>,
+}
+
+impl Steps {
+    async fn run<T>(
+        &mut self,
+        step: &'static str,
+        fut: impl Future<Output = Result<(T, String), String>>,
+    ) -> Option<T> {
+        let start = Instant::now();
+        let result = fut.await;
+        let ms = start.elapsed().as_millis() as u64;
+        let (status, detail, value) = match result {
+            Ok((v, detail)) => (StepStatus::Pass, detail, Some(v)),
+            Err(e) => (StepStatus::Fail, e, None),
+        };
+        self.steps.push(StepReport {
+            step,
+            status,
+            ms,
+            detail,
+        });
+        value
+    }
+
+    fn skip(&mut self, step: &'static str, detail: &str) {
+        self.steps.push(StepReport {
+            step,
+            status: StepStatus::Skip,
+            ms: 0,
+            detail: detail.to_string(),
+        });
+    }
+}
+
+fn tool_call(name: &str, args: Value) -> CallToolRequestParams {
+    CallToolRequestParams {
+        meta: None,
+        name: name.to_string().into(),
+        arguments: args.as_object().cloned(),
+        task: None,
+    }
+}
+
+fn structured(result: crate::error::Result<CallToolResult>, tool: &str) -> Result<Value, String> {
+    result
+        .map_err(|e| format!("{} failed: {}", tool, e))?
+        .structured_content
+        .ok_or_else(|| format!("{} returned no structured content", tool))
+}
+
+fn bare_id(id: &str) -> &str {
+    id.split_once(':').map_or(id, |(_, rest)| rest)
+}
+
+/// Run the steps on `server`, which should sit on a scratch database.
+pub async fn run_steps(server: &SurrealMindServer) -> SelfTestReport {
+    let started = Instant::now();
+    let mut steps = Steps::default();
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let content = format!("self-test probe {} checks the thinking pipeline", nonce);
+    let dim = server.embedder.dimensions();
+
+    steps
+        .run("schema", async {
+            let status = crate::server::schema::migration_status(
+                &server.db.client(),
+                crate::server::schema::MIGRATIONS,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+            if !status.pending.is_empty() {
+                return Err(format!("pending migrations: {:?}", status.pending));
+            }
+            Ok(((), format!("{} migrations applied", status.applied.len())))
+        })
+        .await;
+
+    let thought_id = steps
+        .run("think", async {
+            let out = structured(
+                server
+                    .handle_legacymind_think(tool_call("think", json!({"content": content})))
+                    .await,
+                "think",
+            )?;
+            let id = out["delegated_result"]["thought_id"]
+                .as_str()
+                .ok_or("think: no delegated_result.thought_id")?
+                .to_string();
+            let embedded = out["delegated_result"]["embedding_dim"].as_u64();
+            if embedded.is_some_and(|d| d as usize != dim) {
+                return Err(format!("think: embedding_dim {:?} != {}", embedded, dim));
+            }
+            let detail = format!("thought {}", id);
+            Ok((id, detail))
+        })
+        .await;
+
+    match &thought_id {
+        Some(id) => {
+            steps
+                .run("search_thoughts", async {
+                    let out = structured(
+                        server
+                            .handle_unified_search(tool_call(
+                                "search",
+                                json!({"thoughts_content": content, "include_thoughts": true, "top_k_thoughts": 5}),
+                            ))
+                            .await,
+                        "search",
+                    )?;
+                    let results = out["thoughts"]["results"]
+                        .as_array()
+                        .ok_or("search: no thoughts.results")?;
+                    if !results
+                        .iter()
+                        .filter_map(|r| r["id"].as_str())
+                        .any(|r| bare_id(r) == bare_id(id))
+                    {
+                        return Err(format!(
+                            "search: thought {} not among {} results",
+                            id,
+                            results.len()
+                        ));
+                    }
+                    Ok(((), format!("{} results", results.len())))
+                })
+                .await;
+        }
+        None => steps.skip("search_thoughts", "think failed"),
+    }
+
+    let source = format!("SelfTest Source {}", nonce);
+    let target = format!("SelfTest Target {}", nonce);
+    let remember = |args: Value| async move {
+        let out = structured(
+            server
+                .handle_knowledgegraph_create(tool_call("remember", args))
+                .await,
+            "remember",
+        )?;
+        out["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("remember: no id in {}", out))
+    };
+    let entities = steps
+        .run("remember_entities", async {
+            let mut ids = Vec::new();
+            for name in [&source, &target] {
+                ids.push(
+                    remember(
+                        json!({"kind": "entity", "data": {"name": name, "entity_type": "concept"}}),
+                    )
+                    .await?,
+                );
+            }
+            let detail = ids.join(", ");
+            Ok((ids, detail))
+        })
+        .await;
+
+    let edge = match &entities {
+        Some(_) => {
+            steps
+                .run("remember_relationship", async {
+                    let id = remember(json!({"kind": "relationship", "data": {
+                        "source": source, "target": target, "rel_type": "related_to"
+                    }}))
+                    .await?;
+                    let detail = format!("edge {}", id);
+                    Ok((id, detail))
+                })
+                .await
+        }
+        None => {
+            steps.skip("remember_relationship", "remember_entities failed");
+            None
+        }
+    };
+
+    match &entities {
+        Some(ids) => {
+            steps
+                .run("search_entities", async {
+                    let out = structured(
+                        server
+                            .handle_knowledgegraph_search(tool_call(
+                                "search",
+                                json!({"target": "entity", "query": {"name": source}}),
+                            ))
+                            .await,
+                        "search",
+                    )?;
+                    let items = out["items"].as_array().ok_or("search: no items")?;
+                    if !items
+                        .iter()
+                        .filter_map(|i| i["id"].as_str())
+                        .any(|i| bare_id(i) == bare_id(&ids[0]))
+                    {
+                        return Err(format!("search: entity {} not found", ids[0]));
+                    }
+                    Ok(((), format!("{} items", items.len())))
+                })
+                .await;
+        }
+        None => steps.skip("search_entities", "remember_entities failed"),
+    }
+
+    steps.skip(
+        "moderate_candidate",
+        "no candidate moderation tool in this build",
+    );
+    steps.skip("inner_voice", "inner_voice is not part of this build");
+
+    match &thought_id {
+        Some(id) => {
+            steps
+                .run("reembed", async {
+                    let edit = server
+                        .update_thought(
+                            id,
+                            EditMode::Append,
+                            "re-embedded by self-test",
+                            Some("self-test".into()),
+                            false,
+                            false,
+                        )
+                        .await
+                        .map_err(|e| format!("update_thought failed: {}", e))?;
+                    if edit.embedding_status.as_deref() != Some("complete")
+                        || edit.embedding_dim != Some(dim)
+                    {
+                        return Err(format!(
+                            "re-embed left status {:?}, dim {:?} (expected complete, {})",
+                            edit.embedding_status, edit.embedding_dim, dim
+                        ));
+                    }
+                    Ok(((), format!("{} dims", dim)))
+                })
+                .await;
+        }
+        None => steps.skip("reembed", "think failed"),
+    }
+
+    steps
+        .run("cleanup", async {
+            let mut removed = 0;
+            if let Some(ids) = &entities {
+                for id in ids {
+                    structured(
+                        server
+                            .handle_knowledgegraph_delete(tool_call(
+                                "forget",
+                                json!({"kind": "entity", "id": id, "cascade": true}),
+                            ))
+                            .await,
+                        "forget",
+                    )?;
+                    removed += 1;
+                }
+            }
+            if edge.is_some() {
+                removed += 1;
+            }
+            if let Some(id) = &thought_id {
+                server
+                    .db
+                    .query("DELETE type::record('thoughts', $id)")
+                    .bind(("id", bare_id(id).to_string()))
+                    .await
+                    .and_then(|r| r.check())
+                    .map_err(|e| format!("delete thought failed: {}", e))?;
+                removed += 1;
+            }
+            Ok(((), format!("{} records removed", removed)))
+        })
+        .await;
+
+    let passed = steps.steps.iter().all(|s| s.status != StepStatus::Fail);
+    SelfTestReport {
+        ns: server.config.system.database_ns.clone(),
+        db: server.config.system.database_db.clone(),
+        passed,
+        total_ms: started.elapsed().as_millis() as u64,
+        steps: steps.steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mem_server, test_config};
+
+    #[tokio::test]
+    async fn test_self_test_steps_pass_on_a_fresh_database() {
+        let server = mem_server().await;
+        let report = run_steps(&server).await;
+        assert!(report.passed, "{}", report.render());
+        let names: Vec<_> = report.steps.iter().map(|s| s.step).collect();
+        assert_eq!(
+            names,
+            [
+                "schema",
+                "think",
+                "search_thoughts",
+                "remember_entities",
+                "remember_relationship",
+                "search_entities",
+                "moderate_candidate",
+                "inner_voice",
+                "reembed",
+                "cleanup"
+            ]
+        );
+
+        // Cleanup leaves the scratch database as it found it
+        let mut left = server
+            .db
+            .query(
+                "SELECT count() AS n FROM thoughts GROUP ALL; SELECT count() AS n FROM kg_entities GROUP ALL; SELECT count() AS n FROM kg_edges GROUP ALL",
+            )
+            .await
+            .unwrap();
+        for i in 0..3 {
+            let rows: Vec<Value> = left.take(i).unwrap();
+            assert!(rows.is_empty() || rows[0]["n"] == json!(0), "{:?}", rows);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_skips_its_dependents_and_fails_the_run() {
+        let server = mem_server().await;
+        // A thoughts table that rejects every write breaks think and what depends on it
+        server
+            .db
+            .query("DEFINE EVENT OVERWRITE reject ON thoughts WHEN $event = 'CREATE' THEN { THROW 'self-test rejection' }")
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        let report = run_steps(&server).await;
+        assert!(!report.passed);
+        let status = |name: &str| report.steps.iter().find(|s| s.step == name).unwrap().status;
+        assert_eq!(status("think"), StepStatus::Fail);
+        assert_eq!(status("search_thoughts"), StepStatus::Skip);
+        assert_eq!(status("reembed"), StepStatus::Skip);
+        assert_eq!(status("remember_entities"), StepStatus::Pass);
+        assert!(report.render().contains("FAIL"));
+    }
+
+    #[test]
+    fn test_self_test_config_targets_the_scratch_database() {
+        let mut config = test_config();
+        config.system.embedding_dimensions = 0;
+        let scratch = self_test_config(&config);
+        assert_eq!(scratch.system.database_ns, "selftest");
+        assert_eq!(scratch.system.database_db, "selftest");
+        assert_eq!(scratch.system.embedding_provider, "hash");
+        assert_eq!(scratch.system.embedding_dimensions, FALLBACK_DIM);
+    }
+}
@@ -0,0 +1,119 @@
+//! Thought access tracking.
+//!
+//! Thoughts returned by `search` or injected into a `think` count as accessed:
+//! [`SurrealMindServer::track_access`] bumps `access_count` and stamps `last_accessed` with one
+//! background UPDATE per retrieval when `runtime.track_access` is set. [`orbital_proximity`]
+//! turns the two into the score carried by `ThoughtMatch`.
+
+use crate::config::OrbitalConfig;
+use crate::maintenance::soft_delete::{normalize_thought_ids, records};
+use crate::server::SurrealMindServer;
+use tokio::task::JoinHandle;
+
+/// One access for every thought in `$recs`.
+pub const TRACK_SQL: &str = "UPDATE thoughts SET access_count = (access_count ?? 0) + 1, \
+    last_accessed = time::now() WHERE id IN $recs RETURN NONE";
+
+/// How close a thought orbits, in 0..=1, from how recently and how often it was accessed:
+///
+/// ```text
+/// recency   = exp(-decay_rate * days_since_access)     (0 if never accessed)
+/// frequency = 1 - exp(-access_boost * access_count)
+/// proximity = (recency_weight * recency + access_weight * frequency)
+///             / (recency_weight + access_weight)
+/// ```
+///
+/// With the default `[orbital_mechanics]` (decay 0.1/day, boost 0.2) a thought accessed a
+/// week ago keeps half its recency, and five accesses give about two thirds of the
+/// frequency term. Zero weights give 0.
+pub fn orbital_proximity(
+    access_count: u32,
+    days_since_access: Option<f64>,
+    orbital: &OrbitalConfig,
+) -> f32 {
+    let weights = orbital.recency_weight + orbital.access_weight;
+    if weights <= 0.0 {
+        return 0.0;
+    }
+    let recency = days_since_access.map_or(0.0, |days| {
+        (-(orbital.decay_rate as f64) * days.max(0.0)).exp()
+    });
+    let frequency = 1.0 - (-(orbital.access_boost as f64) * access_count as f64).exp();
+    let proximity = (orbital.recency_weight as f64 * recency
+        + orbital.access_weight as f64 * frequency)
+        / weights as f64;
+    proximity.clamp(0.0, 1.0) as f32
+}
+
+impl SurrealMindServer {
+    /// Record one access for each distinct thought in `ids` (`thoughts:` prefixes allowed)
+    /// in a single background UPDATE. Returns the task, or `None` when tracking is off or
+    /// there is nothing to record; callers don't wait on it. Failures are only logged.
+    pub fn track_access(&self, ids: &[String]) -> Option<JoinHandle<()>> {
+        if !self.config.runtime.track_access {
+            return None;
+        }
+        let ids = normalize_thought_ids(ids);
+        if ids.is_empty() {
+            return None;
+        }
+        let db = self.db.clone();
+        Some(tokio::spawn(async move {
+            let result = db
+                .query(TRACK_SQL)
+                .bind(("recs", records(&ids)))
+                .await
+                .and_then(|r| r.check());
+            if let Err(e) = result {
+                tracing::warn!("Failed to record access for {} thoughts: {}", ids.len(), e);
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orbital() -> OrbitalConfig {
+        OrbitalConfig {
+            decay_rate: 0.1,
+            access_boost: 0.2,
+            significance_weight: 0.3,
+            recency_weight: 0.4,
+            access_weight: 0.3,
+        }
+    }
+
+    #[test]
+    fn test_orbital_proximity_rises_with_recency_and_access() {
+        let o = orbital();
+        assert_eq!(orbital_proximity(0, None, &o), 0.0);
+        let fresh = orbital_proximity(1, Some(0.0), &o);
+        let week_old = orbital_proximity(1, Some(7.0), &o);
+        let frequent = orbital_proximity(10, Some(7.0), &o);
+        assert!(
+            fresh > week_old && frequent > week_old,
+            "{fresh} {week_old} {frequent}"
+        );
+        assert!((orbital_proximity(0, Some(0.0), &o) - 0.4 / 0.7).abs() < 1e-6);
+        assert!(orbital_proximity(u32::MAX, Some(0.0), &o) <= 1.0);
+
+        let zero = OrbitalConfig {
+            recency_weight: 0.0,
+            access_weight: 0.0,
+            ..o
+        };
+        assert_eq!(orbital_proximity(5, Some(0.0), &zero), 0.0);
+    }
+
+    #[test]
+    fn test_access_batch_coalesces_ids() {
+        let ids: Vec<String> = ["thoughts:a", "a", " b ", "", "thoughts:b", "c"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(normalize_thought_ids(&ids), ["a", "b", "c"]);
+        assert_eq!(records(&normalize_thought_ids(&ids)).len(), 3);
+    }
+}
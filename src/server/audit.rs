@@ -0,0 +1,305 @@
+//! Tool call audit trail.
+//!
+//! `call_tool` wraps every handler with [`audited`], which times the call and produces a
+//! [`ToolAuditRecord`]; the server then writes it to `tool_audit` when
+//! `runtime.audit_enabled` is set. Arguments are reduced to key names, JSON types, and
+//! sizes by [`summarize_args`] so no content is ever stored.
+
+use rmcp::ErrorData as McpError;
+use rmcp::model::{CallToolResult, JsonObject};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Instant;
+
+/// Insert statement for one audit row.
+pub const INSERT_SQL: &str = "CREATE tool_audit SET created_at = time::now(), tool = $tool, \
+     args = $args, duration_ms = $duration_ms, outcome = $outcome, \
+     error_class = $error_class, result_bytes = $result_bytes RETURN NONE";
+
+/// Rows for `maintain audit_summary`; binds optional `$since` / `$until` datetimes.
+pub const SUMMARY_SQL: &str = "SELECT tool, duration_ms, outcome FROM tool_audit \
+     WHERE ($since = NONE OR created_at >= $since) AND ($until = NONE OR created_at < $until)";
+
+/// One audited tool call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolAuditRecord {
+    pub tool: String,
+    /// Per-argument `{type, size}`; see [`summarize_args`].
+    pub args: Value,
+    pub duration_ms: u64,
+    /// `ok` or `error`.
+    pub outcome: &'static str,
+    pub error_class: Option<String>,
+    /// Serialized size of the result, when the call succeeded.
+    pub result_bytes: Option<u64>,
+}
+
+/// Reduce tool arguments to key names with their JSON type and size (string chars,
+/// array items, object keys). Values themselves are dropped.
+pub fn summarize_args(args: Option<&JsonObject>) -> Value {
+    let Some(args) = args else {
+        return json!({});
+    };
+    let summary: serde_json::Map<String, Value> = args
+        .iter()
+        .map(|(key, value)| {
+            let (kind, size) = match value {
+                Value::Null => ("null", None),
+                Value::Bool(_) => ("bool", None),
+                Value::Number(_) => ("number", None),
+                Value::String(s) => ("string", Some(s.chars().count())),
+                Value::Array(a) => ("array", Some(a.len())),
+                Value::Object(o) => ("object", Some(o.len())),
+            };
+            let mut entry = json!({ "type": kind });
+            if let Some(size) = size {
+                entry["size"] = json!(size);
+            }
+            (key.clone(), entry)
+        })
+        .collect();
+    Value::Object(summary)
+}
+
+/// Error class for an MCP error: the `SurrealMindError` class carried in `data.class`,
+/// falling back to the protocol error code.
+pub fn error_class(err: &McpError) -> String {
+    if let Some(class) = err
+        .data
+        .as_ref()
+        .and_then(|d| d.get("class"))
+        .and_then(|c| c.as_str())
+    {
+        return class.to_string();
+    }
+    match err.code {
+        rmcp::model::ErrorCode::METHOD_NOT_FOUND => "unknown_tool".to_string(),
+        rmcp::model::ErrorCode::INVALID_PARAMS => "invalid_params".to_string(),
+        rmcp::model::ErrorCode::INTERNAL_ERROR => "internal".to_string(),
+        code => format!("code_{}", code.0),
+    }
+}
+
+/// Run `call`, timing it and describing the outcome. `args` is the [`summarize_args`]
+/// output; the result is passed through untouched.
+pub async fn audited<F>(
+    tool: &str,
+    args: Value,
+    call: F,
+) -> (Result<CallToolResult, McpError>, ToolAuditRecord)
+where
+    F: Future<Output = Result<CallToolResult, McpError>>,
+{
+    let started = Instant::now();
+    let result = call.await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (outcome, error_class, result_bytes) = match &result {
+        Ok(res) if res.is_error == Some(true) => (
+            "error",
+            Some("tool_error".to_string()),
+            serde_json::to_vec(res).ok().map(|b| b.len() as u64),
+        ),
+        Ok(res) => (
+            "ok",
+            None,
+            serde_json::to_vec(res).ok().map(|b| b.len() as u64),
+        ),
+        Err(e) => ("error", Some(error_class(e)), None),
+    };
+
+    let record = ToolAuditRecord {
+        tool: tool.to_string(),
+        args,
+        duration_ms,
+        outcome,
+        error_class,
+        result_bytes,
+    };
+    (result, record)
+}
+
+/// One row read back for the summary.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditRow {
+    pub tool: String,
+    #[serde(default)]
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub outcome: String,
+}
+
+/// Per-tool call statistics.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+}
+
+/// Nearest-rank percentile of an ascending slice (0 when empty).
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Group rows by tool; tools are sorted by descending call count, then name.
+pub fn summarize(rows: Vec<AuditRow>) -> Vec<ToolStats> {
+    let mut by_tool: BTreeMap<String, (Vec<u64>, u64)> = BTreeMap::new();
+    for row in rows {
+        let entry = by_tool.entry(row.tool).or_default();
+        entry.0.push(row.duration_ms);
+        if row.outcome != "ok" {
+            entry.1 += 1;
+        }
+    }
+    let mut stats: Vec<ToolStats> = by_tool
+        .into_iter()
+        .map(|(tool, (mut durations, errors))| {
+            durations.sort_unstable();
+            let calls = durations.len() as u64;
+            ToolStats {
+                tool,
+                calls,
+                errors,
+                error_rate: errors as f64 / calls as f64,
+                p50_ms: percentile(&durations, 50.0),
+                p95_ms: percentile(&durations, 95.0),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.tool.cmp(&b.tool)));
+    stats
+}
+
+impl crate::server::SurrealMindServer {
+    /// Write an audit row. Failures are logged and never surface to the caller.
+    pub async fn record_tool_audit(&self, record: &ToolAuditRecord) {
+        let result = self
+            .db
+            .query(INSERT_SQL)
+            .bind(("tool", record.tool.clone()))
+            .bind(("args", record.args.clone()))
+            .bind(("duration_ms", record.duration_ms as i64))
+            .bind(("outcome", record.outcome.to_string()))
+            .bind(("error_class", record.error_class.clone()))
+            .bind(("result_bytes", record.result_bytes.map(|b| b as i64)))
+            .await
+            .and_then(|r| r.check());
+        if let Err(e) = result {
+            tracing::warn!("Failed to write tool_audit row for {}: {}", record.tool, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SurrealMindError;
+
+    fn args() -> JsonObject {
+        json!({
+            "content": "secret plan for the launch",
+            "tags": ["a", "b"],
+            "significance": 0.9,
+            "data": {"name": "x"},
+            "chain_id": null
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn test_summarize_args_redacts_values() {
+        let summary = summarize_args(Some(&args()));
+        assert_eq!(
+            summary,
+            json!({
+                "content": {"type": "string", "size": 26},
+                "tags": {"type": "array", "size": 2},
+                "significance": {"type": "number"},
+                "data": {"type": "object", "size": 1},
+                "chain_id": {"type": "null"}
+            })
+        );
+        let text = summary.to_string();
+        assert!(!text.contains("secret"));
+        assert!(!text.contains("\"x\""));
+        assert_eq!(summarize_args(None), json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_audited_records_success() {
+        let (result, record) = audited("think", summarize_args(Some(&args())), async {
+            Ok(CallToolResult::structured(json!({"thought_id": "abc"})))
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(record.tool, "think");
+        assert_eq!(record.outcome, "ok");
+        assert_eq!(record.error_class, None);
+        assert!(record.result_bytes.unwrap() > 0);
+        assert_eq!(record.args["content"]["size"], 26);
+    }
+
+    #[tokio::test]
+    async fn test_audited_records_error_class() {
+        let (result, record) = audited("search", json!({}), async {
+            Err(SurrealMindError::Database {
+                message: "boom".into(),
+            }
+            .into())
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(record.outcome, "error");
+        assert_eq!(record.error_class.as_deref(), Some("database"));
+        assert_eq!(record.result_bytes, None);
+
+        let unknown = McpError {
+            code: rmcp::model::ErrorCode::METHOD_NOT_FOUND,
+            message: "Unknown tool: nope".into(),
+            data: None,
+        };
+        assert_eq!(error_class(&unknown), "unknown_tool");
+    }
+
+    #[test]
+    fn test_summarize_percentiles_and_error_rate() {
+        let mut rows: Vec<AuditRow> = (1..=20)
+            .map(|ms| AuditRow {
+                tool: "think".into(),
+                duration_ms: ms * 10,
+                outcome: if ms <= 2 { "error" } else { "ok" }.into(),
+            })
+            .collect();
+        rows.push(AuditRow {
+            tool: "search".into(),
+            duration_ms: 7,
+            outcome: "ok".into(),
+        });
+        let stats = summarize(rows);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[0],
+            ToolStats {
+                tool: "think".into(),
+                calls: 20,
+                errors: 2,
+                error_rate: 0.1,
+                p50_ms: 100,
+                p95_ms: 190,
+            }
+        );
+        assert_eq!((stats[1].p50_ms, stats[1].p95_ms), (7, 7));
+    }
+}
@@ -0,0 +1,475 @@
+//! Reconnect-aware SurrealDB connection.
+//!
+//! Wraps the WebSocket client so a dropped connection (e.g. a SurrealDB restart) is
+//! re-established with signin + `use_ns`/`use_db` from the retained config, and the failed
+//! query is retried once, instead of every later tool call failing until the server restarts.
+//! Connections go through SurrealDB's `any` engine ([`DbEngine`]): `ws://`/`wss://` for a
+//! server, or `mem://` for the in-memory engine that tests enable through the `kv-mem`
+//! dev-dependency feature (see `test_support`).
+
+use crate::error::{Result, SurrealMindError};
+use serde::Serialize;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use surrealdb::engine::any;
+use surrealdb::method::IntoVariables;
+use surrealdb::types::{ConnectionError, Variables};
+use surrealdb::{IndexedResults, Surreal};
+use tracing::{info, warn};
+
+/// Engine behind every [`DbConnection`], chosen by the URL scheme.
+pub type DbEngine = any::Any;
+
+/// Upper bound for a single backoff delay between connection attempts.
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// Connection parameters retained for reconnects.
+#[derive(Debug, Clone)]
+pub struct DbSettings {
+    pub url: String,
+    pub user: String,
+    pub pass: String,
+    pub ns: String,
+    pub db: String,
+    /// Connection attempts per reconnect (0 disables reconnecting).
+    pub max_attempts: u32,
+    /// First backoff delay; doubles per failed attempt up to 30s.
+    pub base_delay_ms: u64,
+}
+
+impl DbSettings {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            url: engine_url(&config.system.database_url),
+            user: config.runtime.database_user.clone(),
+            pass: config.runtime.database_pass.clone(),
+            ns: config.system.database_ns.clone(),
+            db: config.system.database_db.clone(),
+            max_attempts: config.runtime.db_reconnect_max_attempts,
+            base_delay_ms: config.runtime.db_reconnect_base_delay_ms,
+        }
+    }
+}
+
+/// URL for the `any` engine: `mem://` is kept, HTTP schemes become their WebSocket
+/// equivalents, and a bare `host:port` is taken as `ws://`.
+pub fn engine_url(s: &str) -> String {
+    if s.starts_with("mem://") || s.starts_with("ws://") || s.starts_with("wss://") {
+        s.to_string()
+    } else if let Some(rest) = s.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else if let Some(rest) = s.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else {
+        format!("ws://{}", s)
+    }
+}
+
+/// Reconnect bookkeeping, reported as `db_health` by `maintain health`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DbHealth {
+    pub reconnect_enabled: bool,
+    pub reconnects: u64,
+    pub last_reconnect_at: Option<String>,
+    pub last_reconnect_attempts: u32,
+    pub total_attempts: u64,
+    pub last_error: Option<String>,
+}
+
+/// Whether an error means the WebSocket is gone (as opposed to a query/validation failure).
+pub(crate) fn is_connection_error(err: &surrealdb::Error) -> bool {
+    err.is_connection()
+        && !matches!(
+            err.connection_details(),
+            Some(ConnectionError::AlreadyConnected)
+        )
+}
+
+/// Delay before retry `attempt` (1-based): base, 2x base, 4x base, ... capped at 30s.
+pub(crate) fn backoff_delay(base_delay_ms: u64, attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis(base_delay_ms.saturating_mul(factor).min(MAX_BACKOFF_MS))
+}
+
+/// Call `connect` up to `max_attempts` times with exponential backoff between failures.
+/// Returns the last result and the number of attempts made.
+pub(crate) async fn connect_with_backoff<T, E, F, Fut>(
+    max_attempts: u32,
+    base_delay_ms: u64,
+    mut connect: F,
+) -> (std::result::Result<T, E>, u32)
+where
+    E: std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, E>>,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match connect().await {
+            Ok(v) => return (Ok(v), attempt),
+            Err(e) if attempt >= max_attempts => return (Err(e), attempt),
+            Err(e) => {
+                let delay = backoff_delay(base_delay_ms, attempt);
+                warn!(
+                    "SurrealDB connection attempt {} failed: {}. Retrying in {}ms...",
+                    attempt,
+                    e,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Run `op`; if it fails with a connection error, run `reconnect` and, when that succeeds,
+/// retry `op` exactly once.
+pub(crate) async fn retry_after_reconnect<T, Op, OpFut, Re, ReFut>(
+    mut op: Op,
+    reconnect: Re,
+) -> surrealdb::Result<T>
+where
+    Op: FnMut() -> OpFut,
+    OpFut: Future<Output = surrealdb::Result<T>>,
+    Re: FnOnce() -> ReFut,
+    ReFut: Future<Output = bool>,
+{
+    match op().await {
+        Err(e) if is_connection_error(&e) => {
+            warn!("SurrealDB connection error, reconnecting: {}", e);
+            if reconnect().await {
+                op().await
+            } else {
+                Err(e)
+            }
+        }
+        other => other,
+    }
+}
+
+/// Open a new connection, sign in (not for `mem://`, which has no users), and select
+/// namespace/database.
+async fn open(settings: &DbSettings) -> Result<Surreal<DbEngine>> {
+    let db = any::connect(settings.url.clone())
+        .await
+        .map_err(|e| SurrealMindError::Database {
+            message: format!("Failed to connect to SurrealDB at {}: {}", settings.url, e),
+        })?;
+    if !settings.url.starts_with("mem://") {
+        db.signin(surrealdb::opt::auth::Root {
+            username: settings.user.clone(),
+            password: settings.pass.clone(),
+        })
+        .await
+        .map_err(|e| SurrealMindError::Database {
+            message: format!(
+                "Failed to authenticate with SurrealDB as user '{}': {}",
+                settings.user, e
+            ),
+        })?;
+    }
+    db.use_ns(&settings.ns)
+        .use_db(&settings.db)
+        .await
+        .map_err(|e| SurrealMindError::Database {
+            message: format!(
+                "Failed to select namespace '{}' / database '{}': {}",
+                settings.ns, settings.db, e
+            ),
+        })?;
+    Ok(db)
+}
+
+/// Shared SurrealDB handle that transparently replaces its client after a connection drop.
+pub struct DbConnection {
+    client: RwLock<Arc<Surreal<DbEngine>>>,
+    settings: DbSettings,
+    /// Bumped on every successful reconnect so queued callers can skip a redundant one.
+    generation: AtomicU64,
+    /// Serializes reconnects so concurrent tool calls don't stampede the server.
+    reconnect_lock: tokio::sync::Mutex<()>,
+    health: Mutex<DbHealth>,
+}
+
+impl DbConnection {
+    /// Connect, making up to `attempts` tries with backoff.
+    pub async fn connect(settings: DbSettings, attempts: u32) -> Result<Self> {
+        let (result, made) =
+            connect_with_backoff(attempts, settings.base_delay_ms, || open(&settings)).await;
+        let db = result.map_err(|e| match e {
+            SurrealMindError::Database { message } => SurrealMindError::Database {
+                message: format!("{} (after {} attempts)", message, made),
+            },
+            other => other,
+        })?;
+        if made > 1 {
+            info!(
+                "Successfully connected to SurrealDB after {} attempts",
+                made
+            );
+        }
+        let health = DbHealth {
+            reconnect_enabled: settings.max_attempts > 0,
+            ..DbHealth::default()
+        };
+        Ok(Self {
+            client: RwLock::new(Arc::new(db)),
+            settings,
+            generation: AtomicU64::new(0),
+            reconnect_lock: tokio::sync::Mutex::new(()),
+            health: Mutex::new(health),
+        })
+    }
+
+    /// Current client. Prefer [`DbConnection::query`], which reconnects on failure.
+    pub fn client(&self) -> Arc<Surreal<DbEngine>> {
+        self.client
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Start a query that is retried once after a reconnect if the connection dropped.
+    pub fn query(&self, sql: impl Into<String>) -> DbQuery<'_> {
+        DbQuery {
+            conn: self,
+            sql: sql.into(),
+            variables: Ok(Variables::new()),
+        }
+    }
+
+    /// Ping the server.
+    pub async fn health(&self) -> surrealdb::Result<()> {
+        self.run(|db| async move { db.health().await }).await
+    }
+
+    /// Snapshot of reconnect bookkeeping.
+    pub fn health_snapshot(&self) -> DbHealth {
+        self.health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Run `op` against the current client, reconnecting and retrying once on a connection error.
+    pub async fn run<T, F, Fut>(&self, mut op: F) -> surrealdb::Result<T>
+    where
+        F: FnMut(Arc<Surreal<DbEngine>>) -> Fut,
+        Fut: Future<Output = surrealdb::Result<T>>,
+    {
+        let generation = self.generation.load(Ordering::Acquire);
+        retry_after_reconnect(
+            || op(self.client()),
+            || async move {
+                match self.reconnect(generation).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        warn!("SurrealDB reconnect failed: {}", e);
+                        false
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    /// Re-establish the connection unless another caller already did since `seen_generation`.
+    async fn reconnect(&self, seen_generation: u64) -> Result<()> {
+        if self.settings.max_attempts == 0 {
+            return Err(SurrealMindError::Database {
+                message: "reconnect disabled (SURR_DB_RECONNECT_MAX_ATTEMPTS=0)".into(),
+            });
+        }
+        let _guard = self.reconnect_lock.lock().await;
+        if self.generation.load(Ordering::Acquire) != seen_generation {
+            return Ok(());
+        }
+
+        let (result, attempts) = connect_with_backoff(
+            self.settings.max_attempts,
+            self.settings.base_delay_ms,
+            || open(&self.settings),
+        )
+        .await;
+
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        health.last_reconnect_attempts = attempts;
+        health.total_attempts += u64::from(attempts);
+        match result {
+            Ok(db) => {
+                *self.client.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(db);
+                self.generation.fetch_add(1, Ordering::AcqRel);
+                health.reconnects += 1;
+                health.last_reconnect_at = Some(chrono::Utc::now().to_rfc3339());
+                health.last_error = None;
+                info!("Reconnected to SurrealDB after {} attempts", attempts);
+                Ok(())
+            }
+            Err(e) => {
+                health.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Query builder mirroring `surrealdb::method::Query` (`bind`, `.await`) but executed
+/// through [`DbConnection::run`].
+#[must_use = "queries do nothing unless you `.await` them"]
+pub struct DbQuery<'a> {
+    conn: &'a DbConnection,
+    sql: String,
+    variables: surrealdb::Result<Variables>,
+}
+
+impl DbQuery<'_> {
+    pub fn bind(mut self, vars: impl IntoVariables) -> Self {
+        self.variables = match (self.variables, vars.into_variables()) {
+            (Ok(mut a), Ok(b)) => {
+                a.extend(b);
+                Ok(a)
+            }
+            (Err(e), _) | (_, Err(e)) => Err(e),
+        };
+        self
+    }
+}
+
+impl<'a> IntoFuture for DbQuery<'a> {
+    type Output = surrealdb::Result<IndexedResults>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let DbQuery {
+                conn,
+                sql,
+                variables,
+            } = self;
+            let variables = variables?;
+            conn.run(|db| {
+                let sql = sql.clone();
+                let variables = variables.clone();
+                async move { db.query(sql).bind(variables).await }
+            })
+            .await
+            .inspect_err(|_| crate::metrics::global().record_db_query_error())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn dropped() -> surrealdb::Error {
+        surrealdb::Error::connection(
+            "WebSocket error: connection closed".into(),
+            ConnectionError::ConnectionFailed,
+        )
+    }
+
+    #[test]
+    fn test_is_connection_error() {
+        assert!(is_connection_error(&dropped()));
+        assert!(is_connection_error(&surrealdb::Error::connection(
+            "Connection uninitialised".into(),
+            Some(ConnectionError::Uninitialised),
+        )));
+        assert!(!is_connection_error(&surrealdb::Error::connection(
+            "already connected".into(),
+            Some(ConnectionError::AlreadyConnected),
+        )));
+        assert!(!is_connection_error(&surrealdb::Error::internal(
+            "parse error".into()
+        )));
+    }
+
+    #[test]
+    fn test_engine_url_schemes() {
+        assert_eq!(engine_url("127.0.0.1:8000"), "ws://127.0.0.1:8000");
+        assert_eq!(engine_url("ws://db:8000"), "ws://db:8000");
+        assert_eq!(engine_url("http://db:8000"), "ws://db:8000");
+        assert_eq!(engine_url("https://db.example"), "wss://db.example");
+        assert_eq!(engine_url("mem://"), "mem://");
+    }
+
+    #[test]
+    fn test_backoff_delay_doubles_and_caps() {
+        assert_eq!(backoff_delay(500, 1), Duration::from_millis(500));
+        assert_eq!(backoff_delay(500, 2), Duration::from_millis(1000));
+        assert_eq!(backoff_delay(500, 3), Duration::from_millis(2000));
+        assert_eq!(
+            backoff_delay(500, 40),
+            Duration::from_millis(MAX_BACKOFF_MS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_reconnect_errors_once_then_succeeds() {
+        let calls = AtomicUsize::new(0);
+        let reconnects = AtomicUsize::new(0);
+        let result = retry_after_reconnect(
+            || async {
+                if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(dropped())
+                } else {
+                    Ok(42)
+                }
+            },
+            || async {
+                reconnects.fetch_add(1, Ordering::SeqCst);
+                true
+            },
+        )
+        .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(reconnects.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_reconnect_skips_query_errors_and_failed_reconnects() {
+        let calls = AtomicUsize::new(0);
+        let result: surrealdb::Result<()> = retry_after_reconnect(
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(surrealdb::Error::internal("bad query".into()))
+            },
+            || async { panic!("query errors must not reconnect") },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let result: surrealdb::Result<()> =
+            retry_after_reconnect(|| async { Err(dropped()) }, || async { false }).await;
+        assert!(is_connection_error(&result.unwrap_err()));
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_backoff_counts_attempts() {
+        let calls = AtomicUsize::new(0);
+        let (result, attempts) = connect_with_backoff(3, 1, || async {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err("refused")
+            } else {
+                Ok("connected")
+            }
+        })
+        .await;
+        assert_eq!(result, Ok("connected"));
+        assert_eq!(attempts, 2);
+
+        let (result, attempts) =
+            connect_with_backoff(3, 1, || async { Err::<(), _>("refused") }).await;
+        assert_eq!(result, Err("refused"));
+        assert_eq!(attempts, 3);
+    }
+}
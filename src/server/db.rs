@@ -1,5 +1,7 @@
 use crate::error::{Result, SurrealMindError};
+use crate::maintenance::observations::OBSERVATION_TEXT_SQL;
 use crate::server::SurrealMindServer;
+use crate::server::connection::{DbConnection, DbSettings};
 use anyhow::Context;
 use lru::LruCache;
 use std::num::NonZeroUsize;
@@ -12,88 +14,18 @@ impl SurrealMindServer {
     pub async fn new(config: &crate::config::Config) -> Result<Self> {
         info!("Connecting to SurrealDB service via WebSocket");
 
-        // Use the provided configuration directly instead of setting global env vars.
-        // Embedder factory will read from the environment, but we keep the existing behaviour.
-
-        // Normalize URL for SurrealDB Ws engine (expects host:port, no scheme)
-        fn normalize_ws_url(s: &str) -> String {
-            s.strip_prefix("ws://")
-                .or_else(|| s.strip_prefix("wss://"))
-                .or_else(|| s.strip_prefix("http://"))
-                .or_else(|| s.strip_prefix("https://"))
-                .unwrap_or(s)
-                .to_string()
-        }
-
-        // Connect to SurrealDB instance
-        // DB connection values from config
-        let url = normalize_ws_url(&config.system.database_url);
-        let user = &config.runtime.database_user;
-        let pass = &config.runtime.database_pass;
-        let ns = &config.system.database_ns;
-        let dbname = &config.system.database_db;
-
-        // Optional reconnection strategy with backoff
+        // Optional startup retries; runtime reconnects are governed by
+        // SURR_DB_RECONNECT_MAX_ATTEMPTS (see server::connection).
         let db_reconnect_enabled = std::env::var("SURR_DB_RECONNECT")
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
             .unwrap_or(false);
-        let max_retries = if db_reconnect_enabled { 5 } else { 0 };
-
-        let mut db = None;
-        for attempt in 0..=max_retries {
-            match surrealdb::Surreal::new::<surrealdb::engine::remote::ws::Ws>(url.clone()).await {
-                Ok(conn) => {
-                    db = Some(conn);
-                    if attempt > 0 {
-                        info!(
-                            "Successfully reconnected to SurrealDB after {} attempts",
-                            attempt + 1
-                        );
-                    }
-                    break;
-                }
-                Err(e) => {
-                    if attempt == max_retries {
-                        return Err(SurrealMindError::Database {
-                            message: format!(
-                                "Failed to connect to SurrealDB at {} after {} attempts: {}",
-                                config.system.database_url,
-                                max_retries + 1,
-                                e
-                            ),
-                        });
-                    } else {
-                        let delay_ms = (1000 * (1u64 << attempt.min(5))).min(60000); // 1s, 2s, 4s, 8s, 16s, then 60s max
-                        warn!(
-                            "SurrealDB connection attempt {} failed: {}. Retrying in {}ms...",
-                            attempt + 1,
-                            e,
-                            delay_ms
-                        );
-                        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                    }
-                }
-            }
-        }
-
-        let db = db.expect("database should be initialized");
-
-        // Sign in with credentials
-        db.signin(surrealdb::opt::auth::Root {
-            username: user.clone(),
-            password: pass.clone(),
-        })
-        .await
-        .with_context(|| format!("Failed to authenticate with SurrealDB as user '{}'", user))?;
-
-        // Select namespace and database
-        db.use_ns(ns)
-            .await
-            .with_context(|| format!("Failed to select namespace '{}'", ns))?;
-
-        db.use_db(dbname)
-            .await
-            .with_context(|| format!("Failed to select database '{}'", dbname))?;
+        let settings = DbSettings::from_config(config);
+        let startup_attempts = if db_reconnect_enabled {
+            settings.max_attempts.max(1)
+        } else {
+            1
+        };
+        let db = DbConnection::connect(settings, startup_attempts).await?;
 
         // Initialize embedder
         let embedder = crate::embeddings::create_embedder(config)
@@ -103,6 +35,29 @@ impl SurrealMindServer {
             "Embedder initialized with {} dimensions",
             embedder.dimensions()
         );
+        let embedding_target = crate::maintenance::MigrationTarget::from_config(config).await?;
+        if let Some(target) = &embedding_target {
+            info!(
+                "Embedding migration active: dual-writing embedding_v2 ({}/{}, {} dimensions)",
+                target.provider,
+                target.model,
+                target.dimensions()
+            );
+        }
+
+        Self::from_parts(config, db, embedder, embedding_target).await
+    }
+
+    /// Build the server around an open connection and embedder, then set up the schema,
+    /// vector indexes, and warm start. [`SurrealMindServer::new`] connects per `config`;
+    /// tests pass an in-memory connection and a deterministic embedder.
+    pub async fn from_parts(
+        config: &crate::config::Config,
+        db: DbConnection,
+        embedder: Arc<dyn crate::embeddings::Embedder>,
+        embedding_target: Option<crate::maintenance::MigrationTarget>,
+    ) -> Result<Self> {
+        let redactor = crate::utils::redaction::Redactor::from_config(&config.security.redaction)?;
 
         // Initialize bounded in-memory cache (LRU)
         let cache_max: usize = std::env::var("SURR_CACHE_MAX")
@@ -124,9 +79,17 @@ impl SurrealMindServer {
         let server = Self {
             db: Arc::new(db),
             thoughts: Arc::new(RwLock::new(thoughts_cache)),
+            cache_stats: Arc::new(super::CacheStats::default()),
             embedder,
+            embedding_target,
             config: Arc::new(config.clone()),
             job_semaphore,
+            vector_index_ready: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            redactor: Arc::new(redactor),
+            entity_index: Arc::new(RwLock::new(super::entity_index::EntityIndex::new(
+                config.runtime.warm_start,
+            ))),
+            tenants: Arc::default(),
         };
 
         server
@@ -136,6 +99,19 @@ impl SurrealMindServer {
                 message: e.message.to_string(),
             })?;
 
+        if config.system.use_vector_index
+            && let Err(e) = server.ensure_vector_indexes().await
+        {
+            warn!(
+                "vector_index: setup failed, using full-scan similarity: {}",
+                e
+            );
+        }
+
+        if config.runtime.warm_start {
+            server.warm_start().await;
+        }
+
         // Note: Delegation tools (call_gem, call_cc) are synchronous - no background workers needed
 
         Ok(server)
@@ -149,21 +125,72 @@ impl SurrealMindServer {
         (provider, model, dim)
     }
 
+    /// Write pending embedding usage to `embedding_usage`; failures are logged and the usage
+    /// stays pending for the next call.
+    pub async fn flush_embedding_usage(&self) {
+        if let Err(e) = crate::embeddings::usage::ledger()
+            .flush(&self.db.client())
+            .await
+        {
+            warn!("Failed to write embedding_usage rollup: {}", e);
+        }
+    }
+
+    /// Dual-write `text`'s embedding from the migration target into the record's
+    /// `embedding_v2`. A no-op without `[embedding_migration]`; failures are logged and left
+    /// for `maintain reembed` with `to_v2` to backfill.
+    pub async fn write_embedding_v2(&self, table: &str, id: &str, text: &str) {
+        let Some(target) = &self.embedding_target else {
+            return;
+        };
+        if let Err(e) = target.write(&self.db.client(), table, id, text).await {
+            warn!(table, id, error = %e, "embedding_migration.dual_write_failed");
+        }
+    }
+
+    /// Look up a thought in the LRU cache, counting the hit or miss.
+    pub async fn cached_thought(&self, id: &str) -> Option<super::Thought> {
+        let found = self.thoughts.write().await.get(id).cloned();
+        let counter = if found.is_some() {
+            &self.cache_stats.hits
+        } else {
+            &self.cache_stats.misses
+        };
+        counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        crate::metrics::global().record_cache_lookup(found.is_some());
+        found
+    }
+
+    /// Drop `ids` from the LRU cache after their rows changed outside the cache.
+    pub async fn evict_cached_thoughts(&self, ids: &[String]) {
+        let mut cache = self.thoughts.write().await;
+        for id in ids {
+            cache.pop(id);
+        }
+    }
+
     /// Calculate cosine similarity between two vectors (delegates to utils)
     #[allow(dead_code)]
     pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
         crate::utils::cosine_similarity(a, b)
     }
 
-    /// Perform KG-only memory injection: find similar KG entities and attach their IDs.
+    /// Perform memory injection: pinned thoughts matching `submode` and `tags` first (up to
+    /// `retrieval.max_pinned`, whatever their similarity), then the most similar KG
+    /// entities and observations in the remaining slots. Caps, the recency window, and
+    /// whether KG rows are considered come from the `[injection]` policy. Injected ids are
+    /// stored on the thought; pinned ones (`thoughts:<id>`) are also listed in
+    /// `pinned_memories`, and the applied policy with its candidate count in
+    /// `injection_policy`.
     pub async fn inject_memories(
         &self,
         thought_id: &str,
         embedding: &[f32],
         injection_scale: i64,
         submode: Option<&str>,
+        tags: &[String],
         tool_name: Option<&str>,
-    ) -> crate::error::Result<(usize, Option<String>)> {
+    ) -> crate::error::Result<crate::server::MemoryInjection> {
         let inject_start = std::time::Instant::now();
         let should_trace_info = tool_name
             .map(|name| name.starts_with("think_"))
@@ -178,10 +205,14 @@ impl SurrealMindServer {
             );
         }
         tracing::debug!("inject_memories: query embedding dims: {}", embedding.len());
-        // Orbital mechanics: determine limit and threshold from scale
-        let scale = injection_scale.clamp(0, 3) as u8;
-        if scale == 0 {
-            return Ok((0, None));
+        // Orbital mechanics: determine limit and threshold from scale, under the
+        // [injection] policy for this submode or think mode
+        let policy = self.config.injection.policy(
+            injection_scale,
+            submode.or_else(|| tool_name.and_then(|t| t.strip_prefix("think_"))),
+        );
+        if policy.scale == 0 {
+            return Ok(Default::default());
         }
         // Thresholds from config.retrieval.t1, with optional env override and warn
         let t1 = std::env::var("SURR_INJECT_T1")
@@ -211,16 +242,31 @@ impl SurrealMindServer {
                 }
                 self.config.retrieval.t3
             });
-        let (limit, mut prox_thresh) = match scale {
-            0 => (0usize, 1.0f32),
-            1 => (5usize, t1),
-            2 => (10usize, t2),
-            _ => (20usize, t3),
-        };
+        let limit = policy.cap;
+        let mut prox_thresh = policy.min_similarity.unwrap_or(match policy.scale {
+            1 => t1,
+            2 => t2,
+            _ => t3,
+        });
         if limit == 0 {
-            return Ok((0, None));
+            return Ok(Default::default());
         }
 
+        // Pinned thoughts take the first slots regardless of similarity
+        let pinned = crate::maintenance::pinning::pinned_for_injection(
+            &self.db.client(),
+            thought_id,
+            submode,
+            tags,
+            self.config.retrieval.max_pinned.min(limit),
+        )
+        .await
+        .unwrap_or_else(|e| {
+            warn!("inject_memories: pinned thought lookup failed: {}", e);
+            Vec::new()
+        });
+        let limit = limit - pinned.len();
+
         // Optional: submode-aware retrieval tweaks
         // Use config flag, with optional env override and warn
         if std::env::var("SURR_SUBMODE_RETRIEVAL").ok().as_deref() == Some("true")
@@ -266,32 +312,67 @@ impl SurrealMindServer {
         // Fetch scored candidates from entities and observations using DB-side cosine.
         // We avoid selecting raw embedding vectors to reduce payload size and sidestep
         // websocket decode issues observed after 3.x migration.
+        // With the HNSW index ready, KNN preselects candidates instead of scanning every row.
         let q_dim = embedding.len() as i64;
         let fetch_start = std::time::Instant::now();
-        let mut q = self
-            .db
-            .query(
-                "SELECT meta::id(id) as id, name, \
-                        data.entity_type AS entity_type, data.description AS description, \
-                        vector::similarity::cosine(embedding, $q) AS similarity \
-                 FROM kg_entities \
-                 WHERE embedding_dim = $dim AND embedding IS NOT NULL \
-                 ORDER BY similarity DESC LIMIT $lim; \
-                 SELECT meta::id(id) as id, name, \
-                        data.entity_type AS entity_type, data.description AS description, \
-                        vector::similarity::cosine(embedding, $q) AS similarity \
-                 FROM kg_observations \
-                 WHERE embedding_dim = $dim AND embedding IS NOT NULL \
-                 ORDER BY similarity DESC LIMIT $lim;",
-            )
-            .bind(("dim", q_dim))
-            .bind(("lim", retrieve as i64))
-            .bind(("q", embedding.to_vec()))
-            .await?;
-        let mut rows: Vec<serde_json::Value> = q.take(0).unwrap_or_default();
-        let mut rows2: Vec<serde_json::Value> = q.take(1).unwrap_or_default();
-        let total_candidates = rows.len() + rows2.len();
-        rows.append(&mut rows2);
+        // Recency window on KG candidates, as an RFC 3339 cutoff
+        let since = policy
+            .recency_days
+            .map(|days| (chrono::Utc::now() - chrono::Duration::days(days as i64)).to_rfc3339());
+        let recency = if since.is_some() {
+            " AND created_at >= <datetime>$since"
+        } else {
+            ""
+        };
+        let rows: Vec<serde_json::Value> = if policy.include_kg {
+            let mut use_knn = self.vector_index_ready();
+            let mut q = loop {
+                let filter = self.embedding_filter(retrieve, use_knn);
+                let sql = format!(
+                    "SELECT meta::id(id) as id, name, \
+                            data.entity_type AS entity_type, data.description AS description, \
+                            vector::similarity::cosine(embedding, $q) AS similarity \
+                     FROM kg_entities \
+                     WHERE embedding_dim = $dim AND {filter}{recency} \
+                     ORDER BY similarity DESC LIMIT $lim; \
+                     SELECT meta::id(id) as id, {OBSERVATION_TEXT_SQL} AS name, \
+                            data.entity_type AS entity_type, \
+                            vector::similarity::cosine(embedding, $q) AS similarity \
+                     FROM kg_observations \
+                     WHERE embedding_dim = $dim AND {filter}{recency} \
+                     ORDER BY similarity DESC LIMIT $lim;"
+                );
+                let result = self
+                    .db
+                    .query(sql)
+                    .bind(("dim", q_dim))
+                    .bind(("lim", retrieve as i64))
+                    .bind(("q", embedding.to_vec()))
+                    .bind(("since", since.clone()))
+                    .await;
+                // Surface statement errors only for KNN so they can trigger the scan fallback
+                let result = if use_knn {
+                    result.and_then(|r| r.check())
+                } else {
+                    result
+                };
+                match result {
+                    Ok(r) => break r,
+                    Err(e) if use_knn => {
+                        self.disable_vector_index(&e);
+                        use_knn = false;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            };
+            let mut rows: Vec<serde_json::Value> = q.take(0).unwrap_or_default();
+            let mut rows2: Vec<serde_json::Value> = q.take(1).unwrap_or_default();
+            rows.append(&mut rows2);
+            rows
+        } else {
+            Vec::new()
+        };
+        let total_candidates = rows.len();
         if should_trace_info {
             tracing::info!(
                 thought_id = %thought_id,
@@ -359,7 +440,15 @@ impl SurrealMindServer {
                 .take(limit)
                 .collect();
         }
-        let memory_ids: Vec<String> = selected.iter().map(|(id, _, _, _)| id.clone()).collect();
+        let pinned_ids: Vec<String> = pinned
+            .iter()
+            .map(|p| format!("thoughts:{}", p.id))
+            .collect();
+        let memory_ids: Vec<String> = pinned_ids
+            .iter()
+            .cloned()
+            .chain(selected.iter().map(|(id, _, _, _)| id.clone()))
+            .collect();
         tracing::debug!(
             "inject_memories: Top {} matches: {:?}",
             selected.len(),
@@ -371,12 +460,21 @@ impl SurrealMindServer {
         );
 
         // Optional enrichment with names/types
-        let enriched = if !selected.is_empty() {
+        let enriched = if !memory_ids.is_empty() {
             let mut s = String::new();
             if let Some(sm) = submode {
                 s.push_str(&format!("Submode: {}\n", sm));
             }
-            s.push_str("Nearby entities:\n");
+            if !pinned.is_empty() {
+                s.push_str("Pinned thoughts:\n");
+                for (id, p) in pinned_ids.iter().zip(&pinned) {
+                    let preview: String = p.content.chars().take(200).collect();
+                    s.push_str(&format!("- [{}] {}\n", id, preview));
+                }
+            }
+            if !selected.is_empty() {
+                s.push_str("Nearby entities:\n");
+            }
             for (i, (id, sim, name, etype)) in selected.iter().take(5).enumerate() {
                 // Include full entity ID for click-to-expand context injection
                 if etype.is_empty() {
@@ -393,16 +491,27 @@ impl SurrealMindServer {
             None
         };
 
-        // Persist to the thought
+        // Persist to the thought, with the policy applied so the selection can be audited
+        let mut applied_policy = serde_json::to_value(&policy)?;
+        applied_policy["threshold"] = serde_json::json!(prox_thresh);
+        applied_policy["candidates"] = serde_json::json!(total_candidates);
         let q = self
             .db
-            .query("UPDATE type::record($tb, $id) SET injected_memories = $mems, enriched_content = $enr RETURN meta::id(id) as id")
+            .query("UPDATE type::record($tb, $id) SET injected_memories = $mems, pinned_memories = $pinned, enriched_content = $enr, injection_policy = $policy RETURN meta::id(id) as id")
             .bind(("tb", "thoughts"))
+            .bind(("policy", applied_policy))
             .bind(("id", thought_id.to_string()))
             .bind(("mems", memory_ids.clone()))
+            .bind(("pinned", pinned_ids.clone()))
             .bind(("enr", enriched.clone().unwrap_or_default()));
         // Note: empty string will act like clearing or setting to empty; acceptable for now
         let _: Vec<serde_json::Value> = q.await?.take(0)?;
+        let injected_thoughts: Vec<String> = memory_ids
+            .iter()
+            .filter(|id| id.starts_with("thoughts:"))
+            .cloned()
+            .collect();
+        self.track_access(&injected_thoughts);
         if should_trace_info {
             tracing::info!(
                 thought_id = %thought_id,
@@ -418,10 +527,15 @@ impl SurrealMindServer {
             enriched.as_ref().map_or(0, |s| s.len())
         );
 
-        Ok((memory_ids.len(), enriched))
+        Ok(crate::server::MemoryInjection {
+            count: memory_ids.len(),
+            pinned: pinned_ids,
+            enriched,
+        })
     }
 
-    /// Check for mixed embedding dimensions across thoughts and KG tables
+    /// Check for mixed embedding dimensions across thoughts and KG tables, and that
+    /// stored dimensions match the active embedder
     pub async fn check_embedding_dims(&self) -> Result<()> {
         // Query distinct embedding dimensions in thoughts
         let thoughts_dims: Vec<i64> = self
@@ -478,6 +592,105 @@ impl SurrealMindServer {
             });
         }
 
+        // Stored vectors must also match the active embedder (configured or discovered)
+        let active = self.embedder.dimensions() as i64;
+        if let Some(stored) = unique_dims.iter().find(|&&d| d != active) {
+            return Err(SurrealMindError::Database {
+                message: format!(
+                    "Stored embedding dimension {} does not match active embedder dimension {}. Re-embed to fix.",
+                    stored, active
+                ),
+            });
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::InjectionOverride;
+    use crate::test_support::{TEST_EMBED_DIM, mem_server_with, test_config};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_inject_memories_applies_caps_recency_and_submode_overrides() {
+        let mut config = test_config();
+        config.injection.scale_caps = vec![2, 4];
+        config.injection.recency_days = Some(35);
+        config.injection.submodes.insert(
+            "debug".into(),
+            InjectionOverride {
+                scale_caps: Some(vec![6]),
+                recency_days: Some(365),
+                ..Default::default()
+            },
+        );
+        let server = mem_server_with(config).await;
+        let mut query = vec![0.0f32; TEST_EMBED_DIM];
+        query[0] = 1.0;
+        // Eight matching entities, one every 10 days back; four fall inside 35 days
+        let mut sql = String::new();
+        for i in 0..8 {
+            sql.push_str(&format!(
+                "CREATE kg_entities:e{i} SET name = 'entity {i}', data = {{}}, \
+                 embedding = $q, embedding_dim = $dim, created_at = time::now() - {days}d;",
+                days = i * 10
+            ));
+        }
+        for t in ["plain", "wide", "debug"] {
+            sql.push_str(&format!(
+                "CREATE thoughts:{t} SET content = '{t}', created_at = time::now(), \
+                 injection_scale = 1, significance = 0.5, access_count = 0, \
+                 injected_memories = [];"
+            ));
+        }
+        server
+            .db
+            .query(sql)
+            .bind(("q", query.clone()))
+            .bind(("dim", TEST_EMBED_DIM as i64))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let inject = |id: &'static str, scale: i64, tool: Option<&'static str>| {
+            let server = &server;
+            let query = &query;
+            async move {
+                let injection = server
+                    .inject_memories(id, query, scale, None, &[], tool)
+                    .await
+                    .unwrap();
+                let policy: Vec<serde_json::Value> = server
+                    .db
+                    .query("SELECT VALUE injection_policy FROM type::record('thoughts', $id)")
+                    .bind(("id", id))
+                    .await
+                    .unwrap()
+                    .take(0)
+                    .unwrap();
+                (injection.count, policy[0].clone())
+            }
+        };
+
+        let (count, policy) = inject("plain", 1, None).await;
+        assert_eq!(count, 2);
+        assert_eq!(policy["cap"], 2);
+        assert_eq!(policy["recency_days"], 35);
+        assert_eq!(policy["candidates"], 4);
+        assert_eq!(policy["override_key"], json!(null));
+
+        // Scales past the list use its last cap
+        let (count, policy) = inject("wide", 3, Some("think_convo")).await;
+        assert_eq!((count, policy["cap"].clone()), (4, json!(4)));
+
+        let (count, policy) = inject("debug", 1, Some("think_debug")).await;
+        assert_eq!(count, 6);
+        assert_eq!(policy["override_key"], "debug");
+        assert_eq!(policy["recency_days"], 365);
+        assert_eq!(policy["candidates"], 8);
+        assert_eq!(policy["include_kg"], true);
+    }
+}
@@ -0,0 +1,240 @@
+//! In-memory KG entity name index
+//!
+//! With `runtime.warm_start` the server loads up to `warm_start_max_rows` kg_entities into an
+//! [`EntityIndex`] (see [`super::warm_start`]). Exact-name lookups that otherwise query
+//! kg_entities (the entity upsert in `remember` and relationship endpoint resolution) check it
+//! first and fall back to SQL on a miss, adding the row they find. Entities created, merged
+//! away, or deleted through this server keep it current; rows written by other processes are
+//! only picked up through that fallback. Without `warm_start` the index stays empty and every
+//! lookup goes to the database as before.
+
+use crate::error::Result;
+use crate::server::SurrealMindServer;
+use crate::tools::knowledge_graph::normalize_entity_name;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+use tokio::sync::RwLock;
+
+/// One row per entity, newest first, for the warm-start load.
+pub const LOAD_SQL: &str = "SELECT meta::id(id) AS id, name, \
+    (entity_type ?? data.entity_type ?? '') AS entity_type, created_at FROM kg_entities \
+    WHERE name != NONE ORDER BY created_at DESC LIMIT $limit";
+
+/// Fallback for an index miss; same match as the upsert's `name = $name`.
+pub const LOOKUP_SQL: &str = "SELECT meta::id(id) AS id, name, \
+    (entity_type ?? data.entity_type ?? '') AS entity_type FROM kg_entities \
+    WHERE name = $name LIMIT 1";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct IndexedEntity {
+    /// Bare kg_entities key
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub entity_type: String,
+}
+
+/// Entities keyed by `normalize_entity_name`; each bucket keeps the exact names.
+#[derive(Debug, Default)]
+pub struct EntityIndex {
+    enabled: bool,
+    by_name: HashMap<String, Vec<IndexedEntity>>,
+    /// id → normalized name, so removals and renames find their bucket
+    keys: HashMap<String, String>,
+}
+
+impl EntityIndex {
+    /// An index that accepts entries; [`EntityIndex::default`] ignores them.
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Add or replace (by id) one entity. No-op when disabled.
+    pub fn insert(&mut self, entity: IndexedEntity) {
+        if !self.enabled || entity.id.is_empty() {
+            return;
+        }
+        self.remove(&entity.id);
+        let key = normalize_entity_name(&entity.name);
+        self.keys.insert(entity.id.clone(), key.clone());
+        self.by_name.entry(key).or_default().push(entity);
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        let Some(key) = self.keys.remove(id) else {
+            return;
+        };
+        if let Some(bucket) = self.by_name.get_mut(&key) {
+            bucket.retain(|e| e.id != id);
+            if bucket.is_empty() {
+                self.by_name.remove(&key);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.by_name.clear();
+        self.keys.clear();
+    }
+
+    /// The entity named exactly `name`, first indexed wins.
+    pub fn get(&self, name: &str) -> Option<&IndexedEntity> {
+        self.similar(name).iter().find(|e| e.name == name)
+    }
+
+    /// Every entity whose name normalizes like `name` ("Surreal Mind", "surreal_mind", ...).
+    pub fn similar(&self, name: &str) -> &[IndexedEntity] {
+        self.by_name
+            .get(&normalize_entity_name(name))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+/// Id of the entity named exactly `name`: from `index` when it has one, otherwise from `fetch`
+/// (a single SQL lookup), whose result is added to the index.
+pub async fn resolve_name<F, Fut>(
+    index: &RwLock<EntityIndex>,
+    name: &str,
+    fetch: F,
+) -> Result<Option<String>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Option<IndexedEntity>>>,
+{
+    if let Some(hit) = index.read().await.get(name) {
+        return Ok(Some(hit.id.clone()));
+    }
+    let found = fetch().await?;
+    if let Some(entity) = &found {
+        index.write().await.insert(entity.clone());
+    }
+    Ok(found.map(|e| e.id))
+}
+
+impl SurrealMindServer {
+    /// Id of the kg_entities row named exactly `name`, consulting the entity index first.
+    pub async fn entity_id_by_name(&self, name: &str) -> Result<Option<String>> {
+        resolve_name(&self.entity_index, name, || async {
+            let rows: Vec<serde_json::Value> = self
+                .db
+                .query(LOOKUP_SQL)
+                .bind(("name", name.to_string()))
+                .await?
+                .take(0)?;
+            Ok(rows
+                .into_iter()
+                .next()
+                .and_then(|row| serde_json::from_value(row).ok()))
+        })
+        .await
+    }
+
+    /// Record an entity created through this server.
+    pub async fn index_entity(&self, id: &str, name: &str, entity_type: &str) {
+        self.entity_index.write().await.insert(IndexedEntity {
+            id: id.to_string(),
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+        });
+    }
+
+    /// Forget entities that were deleted or merged away.
+    pub async fn unindex_entities(&self, ids: &[String]) {
+        let mut index = self.entity_index.write().await;
+        for id in ids {
+            index.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn entity(id: &str, name: &str) -> IndexedEntity {
+        IndexedEntity {
+            id: id.into(),
+            name: name.into(),
+            entity_type: "project".into(),
+        }
+    }
+
+    #[test]
+    fn test_lookups_match_exact_names_within_a_normalized_bucket() {
+        let mut index = EntityIndex::new(true);
+        index.insert(entity("a", "Surreal Mind"));
+        index.insert(entity("b", "surreal_mind"));
+
+        assert_eq!(index.get("surreal_mind").map(|e| e.id.as_str()), Some("b"));
+        assert!(index.get("surreal-mind").is_none());
+        assert_eq!(index.similar("SURREAL-MIND").len(), 2);
+
+        // Re-inserting an id moves it to its new name
+        index.insert(entity("a", "Photography"));
+        assert_eq!(index.similar("surreal mind").len(), 1);
+        assert_eq!(index.get("Photography").map(|e| e.id.as_str()), Some("a"));
+
+        index.remove("b");
+        assert!(index.similar("surreal mind").is_empty());
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_disabled_index_stays_empty() {
+        let mut index = EntityIndex::default();
+        index.insert(entity("a", "Surreal Mind"));
+        assert!(index.is_empty());
+        assert!(!index.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_index_hits_skip_the_query() {
+        let index = RwLock::new(EntityIndex::new(true));
+        let queries = AtomicUsize::new(0);
+        let fetch = |found: Option<IndexedEntity>| {
+            let queries = &queries;
+            move || async move {
+                queries.fetch_add(1, Ordering::SeqCst);
+                Ok(found)
+            }
+        };
+
+        // A miss queries once and learns the row; the repeat is served from memory
+        let first = resolve_name(&index, "Sam", fetch(Some(entity("sam1", "Sam"))))
+            .await
+            .unwrap();
+        assert_eq!(first.as_deref(), Some("sam1"));
+        let again = resolve_name(&index, "Sam", fetch(None)).await.unwrap();
+        assert_eq!(again.as_deref(), Some("sam1"));
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+        // Unknown names still fall through to the database every time
+        for _ in 0..2 {
+            assert!(
+                resolve_name(&index, "Nobody", fetch(None))
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        assert_eq!(queries.load(Ordering::SeqCst), 3);
+    }
+}
@@ -4,14 +4,22 @@ use crate::embeddings::Embedder;
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::{RwLock, Semaphore};
 
 // Submodules
+pub mod access;
+pub mod audit;
+pub mod connection;
 pub mod db;
+pub mod entity_index;
+pub mod progress;
+pub mod resources;
 pub mod router;
 pub mod schema;
+pub mod tenants;
+pub mod vector_index;
+pub mod warm_start;
 
 /// Custom deserializer for SurrealDB Thing to String
 pub fn deserialize_thing_to_string<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
@@ -93,9 +101,31 @@ pub struct Thought {
 pub struct ThoughtMatch {
     pub thought: Thought,
     pub similarity_score: f32,
+    /// See [`access::orbital_proximity`]
     pub orbital_proximity: f32,
 }
 
+impl ThoughtMatch {
+    pub fn new(
+        thought: Thought,
+        similarity_score: f32,
+        orbital: &crate::config::OrbitalConfig,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        let days_since_access = thought
+            .last_accessed
+            .as_ref()
+            .map(|at| (now - **at).num_seconds().max(0) as f64 / 86_400.0);
+        let orbital_proximity =
+            access::orbital_proximity(thought.access_count, days_since_access, orbital);
+        Self {
+            thought,
+            similarity_score,
+            orbital_proximity,
+        }
+    }
+}
+
 /// KG-only retrieval memory item
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KGMemory {
@@ -108,6 +138,16 @@ pub struct KGMemory {
     pub neighbors: Vec<String>,
 }
 
+/// Outcome of memory injection for one thought
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryInjection {
+    /// Pinned thoughts and KG memories injected
+    pub count: usize,
+    /// Injected pinned thoughts, as `thoughts:<id>`
+    pub pinned: Vec<String>,
+    pub enriched: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct DateRangeParam {
     #[serde(default)]
@@ -116,6 +156,21 @@ pub struct DateRangeParam {
     pub to: Option<String>,
 }
 
+impl DateRangeParam {
+    /// Resolve both bounds (see [`crate::utils::dates`]), relative forms against `now`
+    pub fn resolve(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> crate::error::Result<crate::utils::dates::DateRange> {
+        crate::utils::dates::parse_date_range(
+            self.from.as_deref(),
+            self.to.as_deref(),
+            ["date_range.from", "date_range.to"],
+            now,
+        )
+    }
+}
+
 // Types are available for import from this module
 
 #[derive(Debug, Deserialize)]
@@ -145,12 +200,42 @@ pub struct SearchThoughtsParams {
     pub sort_by: Option<String>,
 }
 
+/// Hit/miss counters for the `thoughts` LRU cache, reported by `system_status`.
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    pub hits: AtomicU64,
+    pub misses: AtomicU64,
+}
+
+impl CacheStats {
+    /// Fraction of lookups served from the cache (0 before any lookup).
+    pub fn hit_rate(&self) -> f64 {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let total = hits + self.misses.load(Ordering::Relaxed);
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+}
+
 /// Main SurrealMind server implementation
 #[derive(Clone)]
 pub struct SurrealMindServer {
-    pub db: Arc<Surreal<Client>>,
+    pub db: Arc<connection::DbConnection>, // Reconnect-aware SurrealDB handle
     pub thoughts: Arc<RwLock<LruCache<String, Thought>>>, // Bounded in-memory cache (LRU)
+    pub cache_stats: Arc<CacheStats>,      // Hit/miss counters for `thoughts`
     pub embedder: Arc<dyn Embedder>,
+    /// `[embedding_migration]` target; new embeddings are also written to `embedding_v2`
+    pub embedding_target: Option<crate::maintenance::MigrationTarget>,
     pub config: Arc<crate::config::Config>, // Retain config to avoid future env reads
     pub job_semaphore: Arc<Semaphore>,      // Limit concurrent async jobs (default: 4)
+    pub vector_index_ready: Arc<std::sync::atomic::AtomicBool>, // HNSW indexes usable for KNN
+    /// Compiled `[security.redaction]` patterns applied to new thoughts
+    pub redactor: Arc<crate::utils::redaction::Redactor>,
+    /// KG entity names loaded by `runtime.warm_start`; see [`entity_index`]
+    pub entity_index: Arc<RwLock<entity_index::EntityIndex>>,
+    /// Servers for `[multi_tenant]` databases, opened on first use; see [`tenants`]
+    pub tenants: tenants::TenantPool,
 }
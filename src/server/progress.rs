@@ -0,0 +1,178 @@
+//! MCP progress notifications for long-running tool calls.
+//!
+//! When a client sends `_meta.progressToken` with a tool call, `call_tool` builds a
+//! [`ProgressReporter`] from the request context and long-running handlers (re-embeds,
+//! audits) call [`ProgressReporter::report`] as they work. Without a token the reporter is
+//! disabled and every call is a no-op. Updates are throttled to `runtime.progress_interval_ms`,
+//! and send failures are logged and ignored so they never abort the operation.
+
+use async_trait::async_trait;
+use rmcp::model::{Meta, ProgressNotificationParam, ProgressToken};
+use rmcp::service::{Peer, RequestContext, RoleServer};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Where progress notifications go; the MCP peer in production, a recorder in tests.
+#[async_trait]
+pub trait ProgressSink: Send + Sync {
+    async fn send(&self, param: ProgressNotificationParam) -> Result<(), String>;
+}
+
+#[async_trait]
+impl ProgressSink for Peer<RoleServer> {
+    async fn send(&self, param: ProgressNotificationParam) -> Result<(), String> {
+        self.notify_progress(param).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Throttled progress notifications for one tool call.
+pub struct ProgressReporter {
+    target: Option<(Arc<dyn ProgressSink>, ProgressToken)>,
+    interval: Duration,
+    last_sent: Mutex<Option<Instant>>,
+}
+
+impl ProgressReporter {
+    /// A reporter that never sends anything.
+    pub fn disabled() -> Self {
+        Self {
+            target: None,
+            interval: Duration::ZERO,
+            last_sent: Mutex::new(None),
+        }
+    }
+
+    pub fn new(sink: Arc<dyn ProgressSink>, token: ProgressToken, interval: Duration) -> Self {
+        Self {
+            target: Some((sink, token)),
+            interval,
+            last_sent: Mutex::new(None),
+        }
+    }
+
+    /// Report to the calling client when it supplied a progress token, either on the request
+    /// context or on the tool call's own `_meta`.
+    pub fn from_context(
+        context: &RequestContext<RoleServer>,
+        request_meta: Option<&Meta>,
+        interval: Duration,
+    ) -> Self {
+        let token = context
+            .meta
+            .get_progress_token()
+            .or_else(|| request_meta.and_then(|m| m.get_progress_token()));
+        match token {
+            Some(token) => Self::new(Arc::new(context.peer.clone()), token, interval),
+            None => Self::disabled(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Send `progress` (of `total`, when known) unless an update went out less than
+    /// `interval` ago. The final update (`progress >= total`) is always sent.
+    pub async fn report(&self, progress: usize, total: Option<usize>, message: impl Into<String>) {
+        let Some((sink, token)) = &self.target else {
+            return;
+        };
+        let done = total.is_some_and(|t| progress >= t);
+        {
+            let mut last = self.last_sent.lock().unwrap_or_else(|e| e.into_inner());
+            if !done && last.is_some_and(|at| at.elapsed() < self.interval) {
+                return;
+            }
+            *last = Some(Instant::now());
+        }
+        let param = ProgressNotificationParam {
+            progress_token: token.clone(),
+            progress: progress as f64,
+            total: total.map(|t| t as f64),
+            message: Some(message.into()),
+        };
+        if let Err(e) = sink.send(param).await {
+            tracing::debug!(error = %e, "progress.notify_failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::NumberOrString;
+
+    /// Records every notification; fails all sends when `fail` is set.
+    #[derive(Default)]
+    struct RecordingSink {
+        sent: Mutex<Vec<ProgressNotificationParam>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl ProgressSink for RecordingSink {
+        async fn send(&self, param: ProgressNotificationParam) -> Result<(), String> {
+            self.sent.lock().unwrap().push(param);
+            if self.fail {
+                Err("client went away".into())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn token() -> ProgressToken {
+        ProgressToken(NumberOrString::Number(7))
+    }
+
+    #[tokio::test]
+    async fn test_reports_every_update_without_interval() {
+        let sink = Arc::new(RecordingSink::default());
+        let reporter = ProgressReporter::new(sink.clone(), token(), Duration::ZERO);
+        for batch in 1..=3 {
+            reporter
+                .report(batch * 10, Some(30), format!("batch {}", batch))
+                .await;
+        }
+        let sent = sink.sent.lock().unwrap();
+        let progress: Vec<f64> = sent.iter().map(|p| p.progress).collect();
+        assert_eq!(progress, vec![10.0, 20.0, 30.0]);
+        assert_eq!(sent[2].total, Some(30.0));
+        assert_eq!(sent[2].progress_token, token());
+        assert_eq!(sent[2].message.as_deref(), Some("batch 3"));
+    }
+
+    #[tokio::test]
+    async fn test_throttles_but_always_sends_final_update() {
+        let sink = Arc::new(RecordingSink::default());
+        let reporter = ProgressReporter::new(sink.clone(), token(), Duration::from_secs(3600));
+        reporter.report(1, Some(4), "first").await;
+        reporter.report(2, Some(4), "throttled").await;
+        reporter.report(3, None, "throttled").await;
+        reporter.report(4, Some(4), "done").await;
+        let messages: Vec<String> = sink
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|p| p.message.clone())
+            .collect();
+        assert_eq!(messages, vec!["first", "done"]);
+    }
+
+    #[tokio::test]
+    async fn test_send_failures_and_disabled_reporter_are_silent() {
+        let sink = Arc::new(RecordingSink {
+            fail: true,
+            ..Default::default()
+        });
+        let reporter = ProgressReporter::new(sink.clone(), token(), Duration::ZERO);
+        reporter.report(1, Some(2), "a").await;
+        reporter.report(2, Some(2), "b").await;
+        assert_eq!(sink.sent.lock().unwrap().len(), 2);
+
+        let disabled = ProgressReporter::disabled();
+        assert!(!disabled.is_enabled());
+        disabled.report(1, Some(1), "nobody listening").await;
+    }
+}
@@ -0,0 +1,674 @@
+//! MCP resources: recent thoughts, single thoughts, KG entities, and chains
+//!
+//! Clients that browse resources rather than call tools read these URIs:
+//!
+//! - `surreal-mind://thoughts/recent?limit=20&offset=0`: JSON page of recent thoughts
+//! - `surreal-mind://thoughts/{id}`: one thought as markdown
+//! - `surreal-mind://kg/entities/{id}`: one entity as JSON, without its embedding
+//! - `surreal-mind://chains/{chain_id}?limit=20&offset=0`: a chain's thoughts in order, as markdown
+//!
+//! Private and soft-deleted thoughts are never listed or read; asking for one is the same
+//! `resource_not_found` as asking for a missing id. `resources/list` pages through recent
+//! thoughts and entities with an offset cursor.
+
+use crate::error::SurrealMindError;
+use crate::maintenance::privacy::privacy_filter;
+use crate::server::SurrealMindServer;
+use rmcp::{
+    ErrorData as McpError,
+    model::{
+        AnnotateAble, ListResourceTemplatesResult, ListResourcesResult, RawResource,
+        RawResourceTemplate, ReadResourceResult, Resource, ResourceContents, ResourceTemplate,
+    },
+};
+use serde_json::{Value, json};
+
+pub const SCHEME: &str = "surreal-mind://";
+
+/// Default and maximum `limit` on the paged URIs.
+pub const DEFAULT_RESOURCE_LIMIT: usize = 20;
+pub const MAX_RESOURCE_LIMIT: usize = 100;
+
+/// Thoughts and entities on each `resources/list` page.
+const LIST_PAGE: usize = 25;
+
+/// Characters of content kept in list previews.
+const PREVIEW_CHARS: usize = 160;
+
+const JSON_MIME: &str = "application/json";
+const MARKDOWN_MIME: &str = "text/markdown";
+
+const THOUGHT_FIELDS: &str = "meta::id(id) AS id, content, type::string(created_at) AS created_at, \
+    chain_id, session_id, previous_thought_id, submode, tags, significance, lang";
+
+/// A parsed `surreal-mind://` URI.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceUri {
+    RecentThoughts {
+        limit: usize,
+        offset: usize,
+    },
+    Thought(String),
+    Entity(String),
+    Chain {
+        chain_id: String,
+        limit: usize,
+        offset: usize,
+    },
+}
+
+fn not_found(uri: &str) -> McpError {
+    McpError::resource_not_found(
+        format!("Resource not found: {uri}"),
+        Some(json!({ "uri": uri })),
+    )
+}
+
+fn db_error(e: surrealdb::Error) -> McpError {
+    SurrealMindError::from(e).into()
+}
+
+fn thought_uri(id: &str) -> String {
+    format!("{SCHEME}thoughts/{id}")
+}
+
+fn entity_uri(id: &str) -> String {
+    format!("{SCHEME}kg/entities/{id}")
+}
+
+fn preview(content: &str) -> String {
+    let mut out: String = content.chars().take(PREVIEW_CHARS).collect();
+    if content.chars().count() > PREVIEW_CHARS {
+        out.push('…');
+    }
+    out
+}
+
+fn str_field<'a>(row: &'a Value, key: &str) -> &'a str {
+    row.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// `limit` and `offset` from a query string; anything else is rejected.
+fn parse_page(uri: &str, query: Option<&str>) -> Result<(usize, usize), McpError> {
+    let (mut limit, mut offset) = (DEFAULT_RESOURCE_LIMIT, 0);
+    for pair in query
+        .unwrap_or_default()
+        .split('&')
+        .filter(|p| !p.is_empty())
+    {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let parsed = value.parse::<usize>().map_err(|_| {
+            McpError::invalid_params(
+                format!("'{key}' must be a non-negative integer in {uri}"),
+                Some(json!({ "uri": uri })),
+            )
+        });
+        match key {
+            "limit" => limit = parsed?.clamp(1, MAX_RESOURCE_LIMIT),
+            "offset" => offset = parsed?,
+            _ => {
+                return Err(McpError::invalid_params(
+                    format!("Unknown query parameter '{key}' in {uri}"),
+                    Some(json!({ "uri": uri, "allowed": ["limit", "offset"] })),
+                ));
+            }
+        }
+    }
+    Ok((limit, offset))
+}
+
+impl ResourceUri {
+    pub fn parse(uri: &str) -> Result<Self, McpError> {
+        let rest = uri.strip_prefix(SCHEME).ok_or_else(|| not_found(uri))?;
+        let (path, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (rest, None),
+        };
+        let segments: Vec<&str> = path.split('/').collect();
+        let parsed = match segments.as_slice() {
+            ["thoughts", "recent"] => {
+                let (limit, offset) = parse_page(uri, query)?;
+                return Ok(Self::RecentThoughts { limit, offset });
+            }
+            ["chains", chain_id] if !chain_id.is_empty() => {
+                let (limit, offset) = parse_page(uri, query)?;
+                return Ok(Self::Chain {
+                    chain_id: chain_id.to_string(),
+                    limit,
+                    offset,
+                });
+            }
+            ["thoughts", id] if !id.is_empty() => Self::Thought(id.to_string()),
+            ["kg", "entities", id] if !id.is_empty() => Self::Entity(id.to_string()),
+            _ => return Err(not_found(uri)),
+        };
+        // Single records take no paging
+        if query.is_some_and(|q| !q.is_empty()) {
+            return Err(McpError::invalid_params(
+                format!("{uri} takes no query parameters"),
+                Some(json!({ "uri": uri })),
+            ));
+        }
+        Ok(parsed)
+    }
+}
+
+/// URI templates for every resource form.
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    let template = |uri_template: &str, name: &str, description: &str, mime: &str| {
+        RawResourceTemplate {
+            uri_template: format!("{SCHEME}{uri_template}"),
+            name: name.to_string(),
+            title: None,
+            description: Some(description.to_string()),
+            mime_type: Some(mime.to_string()),
+            icons: None,
+        }
+        .no_annotation()
+    };
+    vec![
+        template(
+            "thoughts/recent{?limit,offset}",
+            "recent_thoughts",
+            "Most recent thoughts, newest first",
+            JSON_MIME,
+        ),
+        template(
+            "thoughts/{id}",
+            "thought",
+            "A single thought",
+            MARKDOWN_MIME,
+        ),
+        template(
+            "kg/entities/{id}",
+            "kg_entity",
+            "A knowledge graph entity",
+            JSON_MIME,
+        ),
+        template(
+            "chains/{chain_id}{?limit,offset}",
+            "chain",
+            "A chain's thoughts in order",
+            MARKDOWN_MIME,
+        ),
+    ]
+}
+
+fn text_contents(uri: &str, mime: &str, text: String) -> ReadResourceResult {
+    ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(mime.to_string()),
+            text,
+            meta: None,
+        }],
+    }
+}
+
+fn thought_markdown(row: &Value) -> String {
+    let mut out = format!("# Thought {}\n\n", str_field(row, "id"));
+    for key in [
+        "created_at",
+        "chain_id",
+        "session_id",
+        "previous_thought_id",
+        "submode",
+        "lang",
+    ] {
+        let value = str_field(row, key);
+        if !value.is_empty() {
+            out.push_str(&format!("- {key}: {value}\n"));
+        }
+    }
+    if let Some(tags) = row.get("tags").and_then(Value::as_array)
+        && !tags.is_empty()
+    {
+        let tags: Vec<&str> = tags.iter().filter_map(Value::as_str).collect();
+        out.push_str(&format!("- tags: {}\n", tags.join(", ")));
+    }
+    out.push('\n');
+    out.push_str(str_field(row, "content"));
+    out.push('\n');
+    out
+}
+
+impl SurrealMindServer {
+    /// Visible thoughts, newest first.
+    async fn recent_thought_rows(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Value>, McpError> {
+        let sql = format!(
+            "SELECT {THOUGHT_FIELDS} FROM thoughts WHERE deleted_at = NONE AND {} \
+             ORDER BY created_at DESC START $start LIMIT $limit",
+            privacy_filter(false)
+        );
+        self.db
+            .query(sql)
+            .bind(("start", offset as i64))
+            .bind(("limit", limit as i64))
+            .await
+            .and_then(|r| r.check())
+            .and_then(|mut r| r.take(0))
+            .map_err(db_error)
+    }
+
+    /// One page of `resources/list`: the recent-thoughts listing, then thoughts and
+    /// entities from `cursor` (an offset) on.
+    pub async fn list_resource_page(
+        &self,
+        cursor: Option<&str>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let offset = match cursor {
+            Some(c) => c.parse::<usize>().map_err(|_| {
+                McpError::invalid_params(
+                    format!("Invalid cursor '{c}'"),
+                    Some(json!({ "cursor": c })),
+                )
+            })?,
+            None => 0,
+        };
+        let mut resources: Vec<Resource> = Vec::new();
+        if offset == 0 {
+            let mut recent =
+                RawResource::new(format!("{SCHEME}thoughts/recent"), "recent_thoughts");
+            recent.description = Some("Most recent thoughts, newest first".to_string());
+            recent.mime_type = Some(JSON_MIME.to_string());
+            resources.push(recent.no_annotation());
+        }
+
+        let thoughts = self.recent_thought_rows(LIST_PAGE, offset).await?;
+        let entities: Vec<Value> = self
+            .db
+            .query(
+                "SELECT meta::id(id) AS id, name, data.entity_type AS entity_type, created_at \
+                 FROM kg_entities ORDER BY created_at DESC START $start LIMIT $limit",
+            )
+            .bind(("start", offset as i64))
+            .bind(("limit", LIST_PAGE as i64))
+            .await
+            .and_then(|r| r.check())
+            .and_then(|mut r| r.take(0))
+            .map_err(db_error)?;
+
+        for row in &thoughts {
+            let id = str_field(row, "id");
+            let mut resource = RawResource::new(thought_uri(id), format!("thought {id}"));
+            resource.description = Some(preview(str_field(row, "content")));
+            resource.mime_type = Some(MARKDOWN_MIME.to_string());
+            resources.push(resource.no_annotation());
+        }
+        for row in &entities {
+            let id = str_field(row, "id");
+            let mut resource = RawResource::new(entity_uri(id), str_field(row, "name"));
+            let entity_type = str_field(row, "entity_type");
+            if !entity_type.is_empty() {
+                resource.description = Some(entity_type.to_string());
+            }
+            resource.mime_type = Some(JSON_MIME.to_string());
+            resources.push(resource.no_annotation());
+        }
+
+        let more = thoughts.len() == LIST_PAGE || entities.len() == LIST_PAGE;
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: more.then(|| (offset + LIST_PAGE).to_string()),
+            ..Default::default()
+        })
+    }
+
+    pub fn list_resource_template_page(&self) -> ListResourceTemplatesResult {
+        ListResourceTemplatesResult {
+            resource_templates: resource_templates(),
+            ..Default::default()
+        }
+    }
+
+    /// Render the resource at `uri`.
+    pub async fn read_resource_uri(&self, uri: &str) -> Result<ReadResourceResult, McpError> {
+        match ResourceUri::parse(uri)? {
+            ResourceUri::RecentThoughts { limit, offset } => {
+                let rows = self.recent_thought_rows(limit, offset).await?;
+                let next = (rows.len() == limit).then(|| {
+                    format!(
+                        "{SCHEME}thoughts/recent?limit={limit}&offset={}",
+                        offset + limit
+                    )
+                });
+                let thoughts: Vec<Value> = rows
+                    .iter()
+                    .map(|row| {
+                        json!({
+                            "id": row["id"],
+                            "uri": thought_uri(str_field(row, "id")),
+                            "created_at": row["created_at"],
+                            "chain_id": row["chain_id"],
+                            "session_id": row["session_id"],
+                            "preview": preview(str_field(row, "content")),
+                        })
+                    })
+                    .collect();
+                let body = json!({
+                    "thoughts": thoughts,
+                    "limit": limit,
+                    "offset": offset,
+                    "next": next,
+                });
+                Ok(text_contents(uri, JSON_MIME, body.to_string()))
+            }
+            ResourceUri::Thought(id) => {
+                let sql = format!(
+                    "SELECT {THOUGHT_FIELDS} FROM type::record('thoughts', $id) \
+                     WHERE deleted_at = NONE AND {}",
+                    privacy_filter(false)
+                );
+                let rows: Vec<Value> = self
+                    .db
+                    .query(sql)
+                    .bind(("id", id))
+                    .await
+                    .and_then(|r| r.check())
+                    .and_then(|mut r| r.take(0))
+                    .map_err(db_error)?;
+                let row = rows.first().ok_or_else(|| not_found(uri))?;
+                Ok(text_contents(uri, MARKDOWN_MIME, thought_markdown(row)))
+            }
+            ResourceUri::Entity(id) => {
+                let rows: Vec<Value> = self
+                    .db
+                    .query(
+                        "SELECT meta::id(id) AS id, name, data, \
+                         type::string(created_at) AS created_at \
+                         FROM type::record('kg_entities', $id)",
+                    )
+                    .bind(("id", id))
+                    .await
+                    .and_then(|r| r.check())
+                    .and_then(|mut r| r.take(0))
+                    .map_err(db_error)?;
+                let row = rows.into_iter().next().ok_or_else(|| not_found(uri))?;
+                Ok(text_contents(uri, JSON_MIME, row.to_string()))
+            }
+            ResourceUri::Chain {
+                chain_id,
+                limit,
+                offset,
+            } => {
+                let sql = format!(
+                    "SELECT {THOUGHT_FIELDS} FROM thoughts \
+                     WHERE chain_id = $chain_id AND deleted_at = NONE AND {} \
+                     ORDER BY created_at ASC START $start LIMIT $limit",
+                    privacy_filter(false)
+                );
+                let rows: Vec<Value> = self
+                    .db
+                    .query(sql)
+                    .bind(("chain_id", chain_id.clone()))
+                    .bind(("start", offset as i64))
+                    .bind(("limit", limit as i64))
+                    .await
+                    .and_then(|r| r.check())
+                    .and_then(|mut r| r.take(0))
+                    .map_err(db_error)?;
+                if rows.is_empty() && offset == 0 {
+                    return Err(not_found(uri));
+                }
+                let mut out = format!("# Chain {chain_id}\n");
+                for (i, row) in rows.iter().enumerate() {
+                    out.push_str(&format!(
+                        "\n## {}. {} ({})\n\n{}\n",
+                        offset + i + 1,
+                        str_field(row, "id"),
+                        str_field(row, "created_at"),
+                        str_field(row, "content"),
+                    ));
+                }
+                if rows.len() == limit {
+                    out.push_str(&format!(
+                        "\nNext page: {SCHEME}chains/{chain_id}?limit={limit}&offset={}\n",
+                        offset + limit
+                    ));
+                }
+                Ok(text_contents(uri, MARKDOWN_MIME, out))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines, ReadHalf};
+
+    /// A JSON-RPC client for a [`SurrealMindServer`] served over an in-memory pipe, so
+    /// requests go through its `ServerHandler` impl.
+    struct Client {
+        lines: Lines<BufReader<ReadHalf<DuplexStream>>>,
+        writer: tokio::io::WriteHalf<DuplexStream>,
+        next_id: u64,
+    }
+
+    impl Client {
+        async fn connect(server: SurrealMindServer) -> Self {
+            let (client_io, server_io) = tokio::io::duplex(1 << 16);
+            tokio::spawn(async move {
+                if let Ok(running) = rmcp::serve_server(server, server_io).await {
+                    let _ = running.waiting().await;
+                }
+            });
+            let (reader, writer) = tokio::io::split(client_io);
+            let mut client = Self {
+                lines: BufReader::new(reader).lines(),
+                writer,
+                next_id: 0,
+            };
+            let init = client
+                .request(
+                    "initialize",
+                    json!({
+                        "protocolVersion": "2025-06-18",
+                        "capabilities": {},
+                        "clientInfo": {"name": "resources-test", "version": "0"},
+                    }),
+                )
+                .await;
+            assert!(init["result"]["capabilities"]["resources"].is_object());
+            client
+                .send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+                .await;
+            client
+        }
+
+        async fn send(&mut self, message: Value) {
+            let line = format!("{message}\n");
+            self.writer.write_all(line.as_bytes()).await.unwrap();
+        }
+
+        async fn request(&mut self, method: &str, params: Value) -> Value {
+            self.next_id += 1;
+            let id = self.next_id;
+            self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+                .await;
+            loop {
+                let line =
+                    tokio::time::timeout(std::time::Duration::from_secs(5), self.lines.next_line())
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .unwrap();
+                let message: Value = serde_json::from_str(&line).unwrap();
+                if message["id"] == id {
+                    return message;
+                }
+            }
+        }
+
+        async fn read(&mut self, uri: &str) -> Value {
+            self.request("resources/read", json!({ "uri": uri })).await
+        }
+    }
+
+    fn text(response: &Value) -> &str {
+        response["result"]["contents"][0]["text"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_parse_resource_uris() {
+        assert_eq!(
+            ResourceUri::parse("surreal-mind://thoughts/recent").unwrap(),
+            ResourceUri::RecentThoughts {
+                limit: DEFAULT_RESOURCE_LIMIT,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            ResourceUri::parse("surreal-mind://chains/c1?limit=500&offset=3").unwrap(),
+            ResourceUri::Chain {
+                chain_id: "c1".into(),
+                limit: MAX_RESOURCE_LIMIT,
+                offset: 3
+            }
+        );
+        assert_eq!(
+            ResourceUri::parse("surreal-mind://kg/entities/e1").unwrap(),
+            ResourceUri::Entity("e1".into())
+        );
+        let code = |uri: &str| ResourceUri::parse(uri).unwrap_err().code;
+        assert_eq!(
+            code("surreal-mind://memories/x"),
+            rmcp::model::ErrorCode::RESOURCE_NOT_FOUND
+        );
+        assert_eq!(
+            code("file:///etc/passwd"),
+            rmcp::model::ErrorCode::RESOURCE_NOT_FOUND
+        );
+        assert_eq!(
+            code("surreal-mind://thoughts/recent?limit=ten"),
+            rmcp::model::ErrorCode::INVALID_PARAMS
+        );
+        assert_eq!(
+            code("surreal-mind://thoughts/recent?sort=asc"),
+            rmcp::model::ErrorCode::INVALID_PARAMS
+        );
+        assert_eq!(
+            code("surreal-mind://thoughts/t1?limit=1"),
+            rmcp::model::ErrorCode::INVALID_PARAMS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resources_list_and_read_through_handler() {
+        let server = crate::test_support::mem_server().await;
+        let thought = |id: &str, content: &str, minutes_ago: i64, extra: &str| {
+            format!(
+                "CREATE thoughts:{id} SET content = '{content}', \
+                 created_at = time::now() - {minutes_ago}m, injection_scale = 0, \
+                 significance = 0.5, access_count = 0, injected_memories = []{extra};"
+            )
+        };
+        let sql = [
+            thought("t1", "First step of the plan", 30, ", chain_id = 'c1'"),
+            thought(
+                "t2",
+                "Second step of the plan",
+                20,
+                ", chain_id = 'c1', tags = ['plan']",
+            ),
+            thought(
+                "secret",
+                "Private note",
+                10,
+                ", chain_id = 'c1', is_private = true",
+            ),
+            thought(
+                "gone",
+                "Deleted note",
+                5,
+                ", chain_id = 'c1', deleted_at = time::now()",
+            ),
+            "CREATE kg_entities:e1 SET name = 'SurrealDB', data = {entity_type: 'tool'}, \
+             embedding = [0.1, 0.2], created_at = time::now();"
+                .to_string(),
+        ]
+        .concat();
+        server.db.query(sql).await.unwrap().check().unwrap();
+        let mut client = Client::connect(server).await;
+
+        let list = client.request("resources/list", json!({})).await;
+        let uris: Vec<&str> = list["result"]["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["uri"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            uris,
+            vec![
+                "surreal-mind://thoughts/recent",
+                "surreal-mind://thoughts/t2",
+                "surreal-mind://thoughts/t1",
+                "surreal-mind://kg/entities/e1",
+            ]
+        );
+        assert!(list["result"].get("nextCursor").is_none());
+
+        let templates = client.request("resources/templates/list", json!({})).await;
+        assert_eq!(
+            templates["result"]["resourceTemplates"]
+                .as_array()
+                .unwrap()
+                .len(),
+            4
+        );
+
+        let recent = client.read("surreal-mind://thoughts/recent?limit=1").await;
+        assert_eq!(
+            recent["result"]["contents"][0]["mimeType"],
+            "application/json"
+        );
+        let page: Value = serde_json::from_str(text(&recent)).unwrap();
+        assert_eq!(page["thoughts"][0]["id"], "t2");
+        assert_eq!(
+            page["next"],
+            "surreal-mind://thoughts/recent?limit=1&offset=1"
+        );
+        let page: Value = serde_json::from_str(text(
+            &client
+                .read("surreal-mind://thoughts/recent?limit=1&offset=1")
+                .await,
+        ))
+        .unwrap();
+        assert_eq!(page["thoughts"][0]["id"], "t1");
+
+        let one = client.read("surreal-mind://thoughts/t2").await;
+        assert_eq!(one["result"]["contents"][0]["mimeType"], "text/markdown");
+        assert!(text(&one).starts_with("# Thought t2"));
+        assert!(text(&one).contains("- tags: plan"));
+        assert!(text(&one).contains("Second step of the plan"));
+
+        let entity = client.read("surreal-mind://kg/entities/e1").await;
+        let entity: Value = serde_json::from_str(text(&entity)).unwrap();
+        assert_eq!(entity["name"], "SurrealDB");
+        assert!(entity.get("embedding").is_none());
+
+        let chain = client.read("surreal-mind://chains/c1").await;
+        let chain = text(&chain);
+        assert!(chain.contains("## 1. t1"));
+        assert!(chain.contains("## 2. t2"));
+        assert!(!chain.contains("Private note"));
+        assert!(!chain.contains("Deleted note"));
+
+        for uri in [
+            "surreal-mind://thoughts/secret",
+            "surreal-mind://thoughts/gone",
+            "surreal-mind://thoughts/missing",
+            "surreal-mind://kg/entities/missing",
+            "surreal-mind://chains/none",
+            "surreal-mind://unknown/path",
+        ] {
+            let err = client.read(uri).await;
+            assert_eq!(err["error"]["code"], -32002, "{uri}");
+        }
+    }
+}
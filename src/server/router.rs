@@ -1,11 +1,13 @@
-use crate::server::SurrealMindServer;
+use crate::server::progress::ProgressReporter;
+use crate::server::{SurrealMindServer, audit, tenants};
 use rmcp::{
     ErrorData as McpError,
     handler::server::ServerHandler,
     model::{
         CallToolRequestParams, CallToolResult, Implementation, InitializeRequestParams,
-        InitializeResult, ListToolsResult, PaginatedRequestParams, ProtocolVersion,
-        ServerCapabilities, ServerInfo, ToolsCapability,
+        InitializeResult, ListResourceTemplatesResult, ListResourcesResult, ListToolsResult,
+        PaginatedRequestParams, ProtocolVersion, ReadResourceRequestParams, ReadResourceResult,
+        ResourcesCapability, ServerCapabilities, ServerInfo, Tool, ToolsCapability,
     },
     service::{RequestContext, RoleServer},
 };
@@ -19,6 +21,10 @@ impl ServerHandler for SurrealMindServer {
                 tools: Some(ToolsCapability {
                     list_changed: Some(false),
                 }),
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(false),
+                    list_changed: Some(false),
+                }),
                 ..Default::default()
             },
             server_info: Implementation {
@@ -50,237 +56,467 @@ impl ServerHandler for SurrealMindServer {
     ) -> std::result::Result<ListToolsResult, McpError> {
         info!("tools/list requested");
 
-        // use crate::tools::unified_search::SearchQuery; // Removed as likely internal or unused in this scope
-        // use crate::tools::unified_search::UnifiedSearchParams; // Unused
-
-        use rmcp::model::Tool;
-
-        // Input schemas
-        let think_schema_map = crate::schemas::think_schema();
-        let maintain_schema_map = crate::schemas::maintain_schema();
-        let remember_schema_map = crate::schemas::remember_schema();
-        let howto_schema_map = crate::schemas::howto_schema();
-        let search_schema_map = crate::schemas::search_schema();
-        let wander_schema_map = crate::schemas::wander_schema();
-        let journal_schema_map = crate::schemas::journal_schema();
-        let rethink_schema_map = crate::schemas::rethink_schema();
-        let corrections_schema_map = crate::schemas::corrections_schema();
-        let test_notification_schema_map = crate::schemas::test_notification_schema();
-
-        let call_gem_schema = crate::schemas::call_gem_schema();
-        let call_cc_schema = crate::schemas::call_cc_schema();
-        let call_vibe_schema = crate::schemas::call_vibe_schema();
-        let call_status_schema = crate::schemas::call_status_schema();
-        let call_jobs_schema = crate::schemas::call_jobs_schema();
-        let call_cancel_schema = crate::schemas::call_cancel_schema();
-
-        // Output schemas (rmcp 0.11.0+)
-        // Output schemas removed as they are no longer used or needed for simple tool defs
-
-        let mut tools = vec![
-            Tool {
-                name: "think".into(),
-                title: Some("Think".into()),
-                description: Some("Unified thinking tool with automatic mode routing (Plan, Build, Debug, Stuck)".into()),
-                input_schema: think_schema_map.clone(),
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-            Tool {
-                name: "wander".into(),
-                title: Some("Wander".into()),
-                description: Some("Explore the knowledge graph to form new connections, provide context, and verify information. Use this for curiosity-driven exploration, not goal-directed search.".into()),
-                input_schema: wander_schema_map,
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-            Tool {
-                name: "maintain".into(),
-                title: Some("Maintain".into()),
-                description: Some("Maintenance operations for archival, cleanup, and health checks".into()),
-                input_schema: maintain_schema_map,
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-            Tool {
-                name: "journal".into(),
-                title: Some("Journal".into()),
-                description: Some(
-                    "Research thread management — create threads, add entries, view dashboard, update status. A looking glass over the KG for structured research."
-                        .into(),
-                ),
-                input_schema: journal_schema_map,
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-            Tool {
-                name: "rethink".into(),
-                title: Some("Rethink".into()),
-                description: Some("Mark records for revision or correction by federation members".into()),
-                input_schema: rethink_schema_map,
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-            Tool {
-                name: "corrections".into(),
-                title: Some("Corrections".into()),
-                description: Some("List correction events with optional target filter".into()),
-                input_schema: corrections_schema_map,
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-            Tool {
-                name: "test_notification".into(),
-                title: Some("Test Notification".into()),
-                description: Some("Send a test logging notification to the client".into()),
-                input_schema: test_notification_schema_map,
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-            // (legacy think_search removed — use legacymind_search)
-            Tool {
-                name: "remember".into(),
-                title: Some("Remember".into()),
-                description: Some("Create entities, relationships, or observations in the knowledge graph".into()),
-                input_schema: remember_schema_map,
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-            // (legacy memories_search removed — use legacymind_search)
-            Tool {
-                name: "howto".into(),
-                title: Some("How To".into()),
-                description: Some("Get detailed help and usage examples for available tools".into()),
-                input_schema: howto_schema_map,
-                icons: None,
-                annotations: None,
-                output_schema: None,
-                execution: None,
-                meta: None,
-            },
-        ];
+        let mut tools = tool_definitions();
+        tools.retain(|tool| self.config.tools.is_enabled(&tool.name));
 
-        tools.push(Tool {
-            name: "call_gem".into(),
-            title: Some("Call Gem".into()),
-            description: Some(
-                "Delegate a task to Gemini CLI with full context and tracking".into(),
-            ),
-            input_schema: call_gem_schema.clone(),
+        Ok(ListToolsResult {
+            tools,
+            ..Default::default()
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let tool = request.name.to_string();
+        let start = std::time::Instant::now();
+        let result = if self.config.runtime.audit_enabled {
+            let args = audit::summarize_args(request.arguments.as_ref());
+            let (result, record) =
+                audit::audited(&tool, args, self.dispatch_tool(request, context)).await;
+            self.record_tool_audit(&record).await;
+            result
+        } else {
+            self.dispatch_tool(request, context).await
+        };
+        self.flush_embedding_usage().await;
+        let ok = result.as_ref().is_ok_and(|r| r.is_error != Some(true));
+        crate::metrics::global().record_tool_call(&tool, start.elapsed(), ok);
+        result
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListResourcesResult, McpError> {
+        let cursor = request.and_then(|r| r.cursor);
+        self.list_resource_page(cursor.as_deref()).await
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListResourceTemplatesResult, McpError> {
+        Ok(self.list_resource_template_page())
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ReadResourceResult, McpError> {
+        info!("resources/read {}", request.uri);
+        self.read_resource_uri(&request.uri).await
+    }
+}
+
+/// Every tool this server can expose, before `[tools]` config filtering
+pub fn tool_definitions() -> Vec<Tool> {
+    // Input schemas
+    let think_schema_map = crate::schemas::think_schema();
+    let think_batch_schema_map = crate::schemas::think_batch_schema();
+    let maintain_schema_map = crate::schemas::maintain_schema();
+    let remember_schema_map = crate::schemas::remember_schema();
+    let forget_schema_map = crate::schemas::forget_schema();
+    let export_schema_map = crate::schemas::export_schema();
+    let import_schema_map = crate::schemas::import_schema();
+    let howto_schema_map = crate::schemas::howto_schema();
+    let search_schema_map = crate::schemas::search_schema();
+    let wander_schema_map = crate::schemas::wander_schema();
+    let journal_schema_map = crate::schemas::journal_schema();
+    let rethink_schema_map = crate::schemas::rethink_schema();
+    let corrections_schema_map = crate::schemas::corrections_schema();
+    let test_notification_schema_map = crate::schemas::test_notification_schema();
+    let system_status_schema_map = crate::schemas::system_status_schema();
+    let embedding_probe_schema_map = crate::schemas::embedding_probe_schema();
+
+    let call_gem_schema = crate::schemas::call_gem_schema();
+    let call_cc_schema = crate::schemas::call_cc_schema();
+    let call_vibe_schema = crate::schemas::call_vibe_schema();
+    let call_status_schema = crate::schemas::call_status_schema();
+    let call_jobs_schema = crate::schemas::call_jobs_schema();
+    let call_cancel_schema = crate::schemas::call_cancel_schema();
+
+    // Output schemas (rmcp 0.11.0+)
+    // Output schemas removed as they are no longer used or needed for simple tool defs
+
+    let mut tools = vec![
+        Tool {
+            name: "think".into(),
+            title: Some("Think".into()),
+            description: Some("Unified thinking tool with automatic mode routing (Plan, Build, Debug, Stuck)".into()),
+            input_schema: think_schema_map.clone(),
             icons: None,
             annotations: None,
             output_schema: None,
             execution: None,
             meta: None,
-        });
-
-        tools.push(Tool {
-            name: "call_cc".into(),
-            title: Some("Call Claude Code".into()),
+        },
+        Tool {
+            name: "think_batch".into(),
+            title: Some("Think Batch".into()),
+            description: Some("Store several related thoughts atomically; later items can link to earlier ones by local_ref".into()),
+            input_schema: think_batch_schema_map,
+            icons: None,
+            annotations: None,
+            output_schema: None,
+            execution: None,
+            meta: None,
+        },
+        Tool {
+            name: "wander".into(),
+            title: Some("Wander".into()),
+            description: Some("Explore the knowledge graph to form new connections, provide context, and verify information. Use this for curiosity-driven exploration, not goal-directed search.".into()),
+            input_schema: wander_schema_map,
+            icons: None,
+            annotations: None,
+            output_schema: None,
+            execution: None,
+            meta: None,
+        },
+        Tool {
+            name: "maintain".into(),
+            title: Some("Maintain".into()),
+            description: Some("Maintenance operations for archival, cleanup, and health checks".into()),
+            input_schema: maintain_schema_map,
+            icons: None,
+            annotations: None,
+            output_schema: None,
+            execution: None,
+            meta: None,
+        },
+        Tool {
+            name: "journal".into(),
+            title: Some("Journal".into()),
             description: Some(
-                "Delegate a task to Claude Code CLI with full context and tracking".into(),
+                "Research thread management — create threads, add entries, view dashboard, update status. A looking glass over the KG for structured research."
+                    .into(),
             ),
-            input_schema: call_cc_schema.clone(),
+            input_schema: journal_schema_map,
             icons: None,
             annotations: None,
             output_schema: None,
             execution: None,
             meta: None,
-        });
-
-        tools.push(Tool {
-            name: "call_vibe".into(),
-            title: Some("Call Vibe".into()),
-            description: Some("Delegate a task to Vibe CLI with full context and tracking".into()),
-            input_schema: call_vibe_schema.clone(),
+        },
+        Tool {
+            name: "rethink".into(),
+            title: Some("Rethink".into()),
+            description: Some("Mark records for revision or correction by federation members".into()),
+            input_schema: rethink_schema_map,
             icons: None,
             annotations: None,
             output_schema: None,
             execution: None,
             meta: None,
-        });
-
-        tools.push(Tool {
-            name: "search".into(),
-            title: Some("Search".into()),
-            description: Some("Unified search for entities, observations, and thoughts".into()),
-            input_schema: search_schema_map,
+        },
+        Tool {
+            name: "corrections".into(),
+            title: Some("Corrections".into()),
+            description: Some("List correction events with optional target filter".into()),
+            input_schema: corrections_schema_map,
             icons: None,
             annotations: None,
             output_schema: None,
             execution: None,
             meta: None,
-        });
-
-        tools.push(Tool {
-            name: "call_status".into(),
-            title: Some("Call Status".into()),
-            description: Some("Check the status and results of a delegated agent job".into()),
-            input_schema: call_status_schema.clone(),
+        },
+        Tool {
+            name: "system_status".into(),
+            title: Some("System Status".into()),
+            description: Some("Report database connectivity and latency, embedder configuration (deep=true runs a bounded embed round-trip), thoughts cache stats, and table row counts".into()),
+            input_schema: system_status_schema_map,
+            icons: None,
+            annotations: None,
+            output_schema: Some(crate::schemas::system_status_output_schema()),
+            execution: None,
+            meta: None,
+        },
+        Tool {
+            name: "embedding_probe".into(),
+            title: Some("Embedding Probe".into()),
+            description: Some("Cosine similarity of text_a against text_b or a stored thought/entity/observation (table + id), with embedding dims and the first 8 components of each vector".into()),
+            input_schema: embedding_probe_schema_map,
             icons: None,
             annotations: None,
             output_schema: None,
             execution: None,
             meta: None,
-        });
-
-        tools.push(Tool {
-            name: "call_jobs".into(),
-            title: Some("Call Jobs".into()),
-            description: Some("List active or completed delegated agent jobs".into()),
-            input_schema: call_jobs_schema.clone(),
+        },
+        Tool {
+            name: "test_notification".into(),
+            title: Some("Test Notification".into()),
+            description: Some("Send a test logging notification to the client".into()),
+            input_schema: test_notification_schema_map,
             icons: None,
             annotations: None,
             output_schema: None,
             execution: None,
             meta: None,
-        });
-
-        tools.push(Tool {
-            name: "call_cancel".into(),
-            title: Some("Call Cancel".into()),
-            description: Some("Cancel an active delegated agent job".into()),
-            input_schema: call_cancel_schema.clone(),
+        },
+        // (legacy think_search removed — use legacymind_search)
+        Tool {
+            name: "remember".into(),
+            title: Some("Remember".into()),
+            description: Some("Create entities, relationships, or observations in the knowledge graph".into()),
+            input_schema: remember_schema_map,
+            icons: None,
+            annotations: None,
+            output_schema: None,
+            execution: None,
+            meta: None,
+        },
+        Tool {
+            name: "forget".into(),
+            title: Some("Forget".into()),
+            description: Some("Delete an entity, relationship, or observation from the knowledge graph (audited in kg_deletions)".into()),
+            input_schema: forget_schema_map,
             icons: None,
             annotations: None,
             output_schema: None,
             execution: None,
             meta: None,
-        });
+        },
+        Tool {
+            name: "export".into(),
+            title: Some("Export".into()),
+            description: Some("Export KG entities, observations, and relationships to a JSONL or GraphML file".into()),
+            input_schema: export_schema_map,
+            icons: None,
+            annotations: None,
+            output_schema: None,
+            execution: None,
+            meta: None,
+        },
+        Tool {
+            name: "import".into(),
+            title: Some("Import".into()),
+            description: Some("Import a JSONL export back into the knowledge graph with skip/overwrite/rename collision handling".into()),
+            input_schema: import_schema_map,
+            icons: None,
+            annotations: None,
+            output_schema: None,
+            execution: None,
+            meta: None,
+        },
+        // (legacy memories_search removed — use legacymind_search)
+        Tool {
+            name: "howto".into(),
+            title: Some("How To".into()),
+            description: Some("Get detailed help and usage examples for available tools".into()),
+            input_schema: howto_schema_map,
+            icons: None,
+            annotations: None,
+            output_schema: None,
+            execution: None,
+            meta: None,
+        },
+    ];
 
-        // (photography tools removed from this server)
+    tools.push(Tool {
+        name: "call_gem".into(),
+        title: Some("Call Gem".into()),
+        description: Some("Delegate a task to Gemini CLI with full context and tracking".into()),
+        input_schema: call_gem_schema.clone(),
+        icons: None,
+        annotations: None,
+        output_schema: None,
+        execution: None,
+        meta: None,
+    });
 
-        Ok(ListToolsResult {
-            tools,
-            ..Default::default()
-        })
+    tools.push(Tool {
+        name: "call_cc".into(),
+        title: Some("Call Claude Code".into()),
+        description: Some(
+            "Delegate a task to Claude Code CLI with full context and tracking".into(),
+        ),
+        input_schema: call_cc_schema.clone(),
+        icons: None,
+        annotations: None,
+        output_schema: None,
+        execution: None,
+        meta: None,
+    });
+
+    tools.push(Tool {
+        name: "call_vibe".into(),
+        title: Some("Call Vibe".into()),
+        description: Some("Delegate a task to Vibe CLI with full context and tracking".into()),
+        input_schema: call_vibe_schema.clone(),
+        icons: None,
+        annotations: None,
+        output_schema: None,
+        execution: None,
+        meta: None,
+    });
+
+    tools.push(Tool {
+        name: "search".into(),
+        title: Some("Search".into()),
+        description: Some("Unified search for entities, observations, and thoughts".into()),
+        input_schema: search_schema_map,
+        icons: None,
+        annotations: None,
+        output_schema: None,
+        execution: None,
+        meta: None,
+    });
+
+    tools.push(Tool {
+        name: "call_status".into(),
+        title: Some("Call Status".into()),
+        description: Some("Check the status and results of a delegated agent job".into()),
+        input_schema: call_status_schema.clone(),
+        icons: None,
+        annotations: None,
+        output_schema: None,
+        execution: None,
+        meta: None,
+    });
+
+    tools.push(Tool {
+        name: "call_jobs".into(),
+        title: Some("Call Jobs".into()),
+        description: Some("List active or completed delegated agent jobs".into()),
+        input_schema: call_jobs_schema.clone(),
+        icons: None,
+        annotations: None,
+        output_schema: None,
+        execution: None,
+        meta: None,
+    });
+
+    tools.push(Tool {
+        name: "call_cancel".into(),
+        title: Some("Call Cancel".into()),
+        description: Some("Cancel an active delegated agent job".into()),
+        input_schema: call_cancel_schema.clone(),
+        icons: None,
+        annotations: None,
+        output_schema: None,
+        execution: None,
+        meta: None,
+    });
+
+    // (photography tools removed from this server)
+    tools
+}
+
+/// Reject calls to tools disabled in `[tools]`, so hiding them from `list_tools` cannot be
+/// bypassed.
+fn check_tool_enabled(
+    tools: &crate::config::ToolsConfig,
+    name: &str,
+) -> std::result::Result<(), McpError> {
+    if tools.is_enabled(name) {
+        return Ok(());
     }
+    Err(McpError {
+        code: rmcp::model::ErrorCode::METHOD_NOT_FOUND,
+        message: format!("Tool disabled by configuration: {}", name).into(),
+        data: None,
+    })
+}
 
-    async fn call_tool(
+/// Check a call's arguments against the tool's input schema (`runtime.strict_params`).
+/// Handlers still parse their own arguments; this only catches typos and unknown fields
+/// that their defaults would otherwise hide.
+fn check_params(
+    name: &str,
+    arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> std::result::Result<(), McpError> {
+    let Some(tool) = tool_definitions().into_iter().find(|t| t.name == name) else {
+        return Ok(());
+    };
+    let schema = serde_json::Value::Object((*tool.input_schema).clone());
+    let args = serde_json::Value::Object(arguments.cloned().unwrap_or_default());
+    crate::schemas::validate::validate(&schema, &args, name).map_err(|message| {
+        crate::error::SurrealMindError::InvalidParams {
+            field: param_field(name, &message),
+            message,
+        }
+        .into()
+    })
+}
+
+/// Argument a schema validation message is about: the path after the tool name
+/// (`search.target: ...` is `target`), or the quoted property of a top-level
+/// `unknown property 'x'` / `missing required 'x'`.
+fn param_field(tool: &str, message: &str) -> Option<String> {
+    let (path, detail) = message.split_once(": ")?;
+    let path = path
+        .strip_prefix(tool)
+        .unwrap_or(path)
+        .trim_start_matches('.');
+    if !path.is_empty() {
+        return Some(path.to_string());
+    }
+    let (_, quoted) = detail.split_once('\'')?;
+    quoted.split_once('\'').map(|(field, _)| field.to_string())
+}
+
+/// Run `call` until it finishes or the client cancels the request (`notifications/cancelled`),
+/// in which case the call is dropped and `operation` reported as cancelled.
+async fn until_cancelled<F>(
+    ct: &tokio_util::sync::CancellationToken,
+    operation: &str,
+    call: F,
+) -> std::result::Result<CallToolResult, McpError>
+where
+    F: Future<Output = std::result::Result<CallToolResult, McpError>>,
+{
+    tokio::select! {
+        biased;
+        _ = ct.cancelled() => Err(crate::error::SurrealMindError::Cancelled {
+            operation: operation.to_string(),
+        }
+        .into()),
+        result = call => result,
+    }
+}
+
+impl SurrealMindServer {
+    /// Route a tool call to its handler, stopping it if the client cancels
+    async fn dispatch_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let ct = context.ct.clone();
+        let tool = request.name.to_string();
+        until_cancelled(&ct, &tool, self.dispatch_checked(request, context)).await
+    }
+
+    async fn dispatch_checked(
+        &self,
+        mut request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        check_tool_enabled(&self.config.tools, &request.name)?;
+        if self.config.runtime.strict_params {
+            check_params(&request.name, request.arguments.as_ref())?;
+        }
+        // namespace/database overrides run the call on that database's server
+        if tenants::TENANT_TOOLS.contains(&request.name.as_ref()) {
+            let system = &self.config.system;
+            let target = tenants::take_override(
+                request.arguments.as_mut(),
+                (&system.database_ns, &system.database_db),
+            )?;
+            if let Some((ns, db)) = target {
+                let tenant = self.tenant(&ns, &db).await?;
+                return tenant.route_tool(request, context).await;
+            }
+        }
+        self.route_tool(request, context).await
+    }
+
+    async fn route_tool(
         &self,
         request: CallToolRequestParams,
         context: RequestContext<RoleServer>,
@@ -292,6 +528,7 @@ impl ServerHandler for SurrealMindServer {
                 .handle_legacymind_think(request)
                 .await
                 .map_err(|e| e.into()),
+            "think_batch" => self.handle_think_batch(request).await.map_err(|e| e.into()),
 
             // Test tool
             "test_notification" => self
@@ -299,13 +536,28 @@ impl ServerHandler for SurrealMindServer {
                 .await
                 .map_err(|e| e.into()),
 
+            "system_status" => self
+                .handle_system_status(request)
+                .await
+                .map_err(|e| e.into()),
+            "embedding_probe" => self
+                .handle_embedding_probe(request)
+                .await
+                .map_err(|e| e.into()),
+
             // Intelligence and utility
             "wander" => self.handle_wander(request).await.map_err(|e| e.into()),
             "corrections" => self.handle_corrections(request).await.map_err(|e| e.into()),
-            "maintain" => self
-                .handle_maintenance_ops(request)
-                .await
-                .map_err(|e| e.into()),
+            "maintain" => {
+                let progress = ProgressReporter::from_context(
+                    &context,
+                    request.meta.as_ref(),
+                    std::time::Duration::from_millis(self.config.runtime.progress_interval_ms),
+                );
+                self.handle_maintenance_ops_with_progress(request, &progress)
+                    .await
+                    .map_err(|e| e.into())
+            }
             "journal" => self.handle_journal(request).await.map_err(|e| e.into()),
             "rethink" => self.handle_rethink(request).await.map_err(|e| e.into()),
             // Memory tools
@@ -313,6 +565,12 @@ impl ServerHandler for SurrealMindServer {
                 .handle_knowledgegraph_create(request)
                 .await
                 .map_err(|e| e.into()),
+            "forget" => self
+                .handle_knowledgegraph_delete(request)
+                .await
+                .map_err(|e| e.into()),
+            "export" => self.handle_export(request).await.map_err(|e| e.into()),
+            "import" => self.handle_import(request).await.map_err(|e| e.into()),
             "call_gem" => self.handle_call_gem(request).await.map_err(|e| e.into()),
             "call_cc" => self.handle_call_cc(request).await.map_err(|e| e.into()),
             "call_vibe" => self.handle_call_vibe(request).await.map_err(|e| e.into()),
@@ -345,3 +603,104 @@ impl ServerHandler for SurrealMindServer {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::config::{TOOL_NAMES, ToolsConfig};
+
+    /// `tool_definitions` builds the `call_gem` and `call_cc` model enums from these.
+    pub(crate) fn model_env() {
+        for var in [
+            "GEMINI_MODELS",
+            "GEMINI_MODEL",
+            "ANTHROPIC_MODELS",
+            "ANTHROPIC_MODEL",
+        ] {
+            if std::env::var(var).is_err() {
+                unsafe { std::env::set_var(var, "test-model") };
+            }
+        }
+    }
+
+    fn disabled(names: &[&str]) -> ToolsConfig {
+        ToolsConfig(names.iter().map(|n| (n.to_string(), false)).collect())
+    }
+
+    #[test]
+    fn test_tool_names_match_definitions() {
+        model_env();
+        let defined: Vec<String> = tool_definitions()
+            .iter()
+            .map(|t| t.name.to_string())
+            .collect();
+        let mut sorted = defined.clone();
+        sorted.sort();
+        let mut known: Vec<String> = TOOL_NAMES.iter().map(|n| n.to_string()).collect();
+        known.sort();
+        assert_eq!(sorted, known);
+    }
+
+    #[test]
+    fn test_disabled_tools_are_hidden_and_rejected() {
+        model_env();
+        let tools = disabled(&["remember", "forget", "maintain"]);
+        let mut listed = tool_definitions();
+        listed.retain(|t| tools.is_enabled(&t.name));
+        let names: Vec<&str> = listed.iter().map(|t| t.name.as_ref()).collect();
+        assert!(names.contains(&"search"));
+        assert!(names.contains(&"think"));
+        for hidden in ["remember", "forget", "maintain"] {
+            assert!(!names.contains(&hidden), "{hidden} should be hidden");
+        }
+
+        let err = check_tool_enabled(&tools, "remember").unwrap_err();
+        assert_eq!(err.code, rmcp::model::ErrorCode::METHOD_NOT_FOUND);
+        assert!(err.message.contains("remember"));
+        assert!(check_tool_enabled(&tools, "search").is_ok());
+    }
+
+    #[test]
+    fn test_check_params_accepts_examples_and_rejects_typos() {
+        model_env();
+        for tool in tool_definitions() {
+            let help = crate::tools::howto::lookup(&tool.name).unwrap();
+            for example in help.examples.as_array().unwrap() {
+                let args = example["call"].as_object();
+                assert!(check_params(&tool.name, args).is_ok(), "{example}");
+            }
+        }
+        assert!(check_params("system_status", None).is_ok());
+
+        let misspelled = serde_json::json!({"target": "entitty"});
+        let err = check_params("search", misspelled.as_object()).unwrap_err();
+        assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("search.target"), "{}", err.message);
+
+        let unknown = serde_json::json!({"kind": "entity", "data": {}, "upsrt": true});
+        let err = check_params("remember", unknown.as_object()).unwrap_err();
+        assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("'upsrt'"), "{}", err.message);
+        assert_eq!(err.data.unwrap()["field"], "upsrt");
+
+        let err = check_params("search", misspelled.as_object()).unwrap_err();
+        assert_eq!(err.data.unwrap()["field"], "target");
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_call_returns_cancelled_error() {
+        let ct = tokio_util::sync::CancellationToken::new();
+        let done =
+            until_cancelled(&ct, "think", async { Ok(CallToolResult::success(vec![])) }).await;
+        assert!(done.is_ok());
+
+        ct.cancel();
+        let err = until_cancelled(&ct, "maintain", std::future::pending())
+            .await
+            .unwrap_err();
+        let data = err.data.unwrap();
+        assert_eq!(data["class"], "cancelled");
+        assert_eq!(data["operation"], "maintain");
+        assert_eq!(data["retryable"], true);
+    }
+}
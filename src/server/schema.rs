@@ -1,243 +1,604 @@
+//! Database schema and its migrations
+//!
+//! Schema changes are [`Migration`]s listed in order in [`MIGRATIONS`]. At startup
+//! [`run_migrations`] applies the ones not yet recorded in `schema_migrations`, each in its
+//! own transaction together with its record, so a failed migration leaves nothing behind and
+//! is retried on the next start. `runtime.skip_migrations` only reports what is pending.
+
+use crate::error::{Result, SurrealMindError};
 use crate::server::SurrealMindServer;
+use crate::server::connection::DbEngine;
 use rmcp::ErrorData as McpError;
-use tracing::info;
+use serde::{Deserialize, Serialize};
+use surrealdb::Surreal;
+use tracing::{info, warn};
 
-impl SurrealMindServer {
-    /// Initialize the database schema
-    pub async fn initialize_schema(&self) -> std::result::Result<(), McpError> {
-        info!("Initializing consciousness graph schema");
+/// Tables, fields, and indexes every database is expected to have: migration 0001. Statements
+/// are additive and idempotent, so databases created before migrations were recorded take it
+/// cleanly; `maintain schema_check` compares a live database against them.
+/// HNSW vector indexes are optional (system.use_vector_index); see server::vector_index.
+pub const SCHEMA_SQL: &str = r#"
+    DEFINE TABLE IF NOT EXISTS thoughts SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS content ON TABLE thoughts TYPE string;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE thoughts TYPE datetime;
+    DEFINE FIELD OVERWRITE embedding ON TABLE thoughts TYPE option<array<float>>;
+    DEFINE FIELD IF NOT EXISTS injected_memories ON TABLE thoughts TYPE array<string>;
+    DEFINE FIELD IF NOT EXISTS enriched_content ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS injection_scale ON TABLE thoughts TYPE int;
+    DEFINE FIELD IF NOT EXISTS significance ON TABLE thoughts TYPE float;
+    DEFINE FIELD IF NOT EXISTS access_count ON TABLE thoughts TYPE int;
+    DEFINE FIELD IF NOT EXISTS last_accessed ON TABLE thoughts TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS decayed_at ON TABLE thoughts TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS submode ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS framework_enhanced ON TABLE thoughts TYPE option<bool>;
+    DEFINE FIELD OVERWRITE framework_analysis ON TABLE thoughts TYPE option<object> FLEXIBLE;
+    DEFINE FIELD IF NOT EXISTS status ON TABLE thoughts TYPE option<string>;
+    -- Origin and privacy fields for retrieval
+    DEFINE FIELD IF NOT EXISTS origin ON TABLE thoughts TYPE option<string>;
+    -- Provenance fields for agent synthesis
+    DEFINE FIELD IF NOT EXISTS source_exchange_id ON TABLE thoughts TYPE option<record<agent_exchanges>>;
+    DEFINE FIELD IF NOT EXISTS synthesis_type ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS tags ON TABLE thoughts TYPE option<array<string>>;
+    DEFINE FIELD IF NOT EXISTS is_private ON TABLE thoughts TYPE option<bool>;
+    -- Pinned thoughts are injected regardless of similarity; see maintenance::pinning
+    DEFINE FIELD IF NOT EXISTS pinned ON TABLE thoughts TYPE option<bool>;
+    DEFINE FIELD IF NOT EXISTS pinned_memories ON TABLE thoughts TYPE option<array<string>>;
+    -- Embedding metadata for future re-embedding
+    DEFINE FIELD IF NOT EXISTS embedding_model ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS embedding_provider ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS embedding_dim ON TABLE thoughts TYPE option<int>;
+    DEFINE FIELD IF NOT EXISTS embedded_at ON TABLE thoughts TYPE option<datetime>;
+    -- Second vector written during an [embedding_migration]
+    DEFINE FIELD IF NOT EXISTS embedding_v2 ON TABLE thoughts TYPE option<array<float>>;
+    DEFINE FIELD IF NOT EXISTS embedding_v2_provider ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS embedding_v2_model ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS embedding_v2_dim ON TABLE thoughts TYPE option<int>;
+    -- Embedding status for graceful degradation (pending/complete/failed)
+    DEFINE FIELD IF NOT EXISTS embedding_status ON TABLE thoughts TYPE option<string> DEFAULT "complete";
+    DEFINE FIELD IF NOT EXISTS extracted_to_kg ON TABLE thoughts TYPE bool DEFAULT false;
+    DEFINE FIELD IF NOT EXISTS extraction_batch_id ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS extracted_at ON TABLE thoughts TYPE option<datetime>;
+    -- Mark fields for REMini correction system
+    DEFINE FIELD IF NOT EXISTS marked_for ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS mark_type ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS mark_note ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS marked_at ON TABLE thoughts TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS marked_by ON TABLE thoughts TYPE option<string>;
+    -- Continuity fields for thought chaining
+    DEFINE FIELD IF NOT EXISTS session_id ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS chain_id ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS previous_thought_id ON TABLE thoughts TYPE option<record<thoughts> | string>;
+    DEFINE FIELD IF NOT EXISTS revises_thought ON TABLE thoughts TYPE option<record<thoughts> | string>;
+    DEFINE FIELD IF NOT EXISTS superseded_by ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS branch_from ON TABLE thoughts TYPE option<record<thoughts> | string>;
+    -- Per-link resolution at creation (record/string/dropped_*); see maintain repair_continuity_links
+    DEFINE FIELD OVERWRITE link_status ON TABLE thoughts TYPE option<object> FLEXIBLE;
+    DEFINE FIELD IF NOT EXISTS confidence ON TABLE thoughts TYPE option<float>;
+    DEFINE FIELD IF NOT EXISTS idempotency_key ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS duplicate_of_hint ON TABLE thoughts TYPE option<string>;
+    -- Structured file/url/commit references from think
+    DEFINE FIELD IF NOT EXISTS references ON TABLE thoughts TYPE option<array<object>>;
+    DEFINE FIELD IF NOT EXISTS references[*].kind ON TABLE thoughts TYPE string;
+    DEFINE FIELD IF NOT EXISTS references[*].value ON TABLE thoughts TYPE string;
+    DEFINE FIELD IF NOT EXISTS references[*].note ON TABLE thoughts TYPE option<string>;
+    -- Soft delete: retrieval skips rows with deleted_at; see maintain soft_delete_thoughts
+    DEFINE FIELD IF NOT EXISTS deleted_at ON TABLE thoughts TYPE option<datetime>;
+    DEFINE INDEX IF NOT EXISTS thoughts_status_idx ON TABLE thoughts FIELDS status;
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_created ON TABLE thoughts FIELDS created_at;
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_embedding_model ON TABLE thoughts FIELDS embedding_model;
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_embedding_dim ON TABLE thoughts FIELDS embedding_dim;
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_embedding_status ON TABLE thoughts FIELDS embedding_status;
+    -- Continuity indexes
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_session ON TABLE thoughts FIELDS session_id, created_at;
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_chain ON TABLE thoughts FIELDS chain_id, created_at;
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_idempotency_key ON TABLE thoughts FIELDS idempotency_key UNIQUE;
+
+    DEFINE TABLE IF NOT EXISTS recalls SCHEMALESS;
+    DEFINE INDEX IF NOT EXISTS idx_recalls_created ON TABLE recalls FIELDS created_at;
+
+    DEFINE TABLE IF NOT EXISTS kg_entities SCHEMALESS;
+    DEFINE FIELD IF NOT EXISTS source_thought_ids ON TABLE kg_entities TYPE option<array<string>>;
+    DEFINE FIELD IF NOT EXISTS extraction_batch_id ON TABLE kg_entities TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS extracted_at ON TABLE kg_entities TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS extraction_confidence ON TABLE kg_entities TYPE option<float>;
+    DEFINE FIELD IF NOT EXISTS extraction_prompt_version ON TABLE kg_entities TYPE option<string>;
+    DEFINE INDEX IF NOT EXISTS idx_kge_created ON TABLE kg_entities FIELDS created_at;
+    DEFINE INDEX IF NOT EXISTS idx_kge_name ON TABLE kg_entities FIELDS name;
+    DEFINE INDEX IF NOT EXISTS idx_kge_name_type ON TABLE kg_entities FIELDS name, data.entity_type;
+    DEFINE INDEX IF NOT EXISTS idx_kge_extraction_batch ON TABLE kg_entities FIELDS extraction_batch_id;
+    -- Mark fields for REMini correction system
+    DEFINE FIELD IF NOT EXISTS marked_for ON TABLE kg_entities TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS mark_type ON TABLE kg_entities TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS mark_note ON TABLE kg_entities TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS marked_at ON TABLE kg_entities TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS marked_by ON TABLE kg_entities TYPE option<string>;
+    -- Confidence decay fields
+    DEFINE FIELD IF NOT EXISTS volatility ON TABLE kg_entities TYPE option<string> DEFAULT "medium";
+    DEFINE FIELD IF NOT EXISTS last_refreshed ON TABLE kg_entities TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS refresh_count ON TABLE kg_entities TYPE option<int> DEFAULT 0;
+    DEFINE FIELD IF NOT EXISTS decay_confidence ON TABLE kg_entities TYPE option<float>;
+
+    -- Journal thread fields
+    DEFINE FIELD IF NOT EXISTS thread_status ON TABLE kg_entities TYPE option<string>;
+    DEFINE INDEX IF NOT EXISTS idx_kge_entity_type_status ON TABLE kg_entities FIELDS entity_type, thread_status;
+
+    DEFINE TABLE IF NOT EXISTS kg_edges SCHEMALESS;
+    DEFINE FIELD IF NOT EXISTS source_thought_ids ON TABLE kg_edges TYPE option<array<string>>;
+    DEFINE FIELD IF NOT EXISTS extraction_batch_id ON TABLE kg_edges TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS extracted_at ON TABLE kg_edges TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS extraction_confidence ON TABLE kg_edges TYPE option<float>;
+    DEFINE FIELD IF NOT EXISTS extraction_prompt_version ON TABLE kg_edges TYPE option<string>;
+    DEFINE INDEX IF NOT EXISTS idx_kged_created ON TABLE kg_edges FIELDS created_at;
+    DEFINE INDEX IF NOT EXISTS idx_kged_triplet ON TABLE kg_edges FIELDS source, target, rel_type;
+    DEFINE INDEX IF NOT EXISTS idx_kged_extraction_batch ON TABLE kg_edges FIELDS extraction_batch_id;
+
+    DEFINE TABLE IF NOT EXISTS kg_observations SCHEMALESS;
+    DEFINE FIELD IF NOT EXISTS source_thought_ids ON TABLE kg_observations TYPE option<array<string>>;
+    DEFINE FIELD IF NOT EXISTS extraction_batch_id ON TABLE kg_observations TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS extracted_at ON TABLE kg_observations TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS extraction_confidence ON TABLE kg_observations TYPE option<float>;
+    DEFINE FIELD IF NOT EXISTS extraction_prompt_version ON TABLE kg_observations TYPE option<string>;
+    DEFINE INDEX IF NOT EXISTS idx_kgo_created ON TABLE kg_observations FIELDS created_at;
+    DEFINE INDEX IF NOT EXISTS idx_kgo_name ON TABLE kg_observations FIELDS name;
+    DEFINE INDEX IF NOT EXISTS idx_kgo_name_src ON TABLE kg_observations FIELDS name, source_thought_id;
+    DEFINE INDEX IF NOT EXISTS idx_kgo_extraction_batch ON TABLE kg_observations FIELDS extraction_batch_id;
+    -- Journal entry fields
+    DEFINE FIELD IF NOT EXISTS author ON TABLE kg_observations TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS observation_type ON TABLE kg_observations TYPE option<string>;
+    DEFINE INDEX IF NOT EXISTS idx_kgo_author ON TABLE kg_observations FIELDS author;
+    DEFINE INDEX IF NOT EXISTS idx_kgo_obs_type ON TABLE kg_observations FIELDS observation_type;
+    -- Mark fields for REMini correction system
+    DEFINE FIELD IF NOT EXISTS marked_for ON TABLE kg_observations TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS mark_type ON TABLE kg_observations TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS mark_note ON TABLE kg_observations TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS marked_at ON TABLE kg_observations TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS marked_by ON TABLE kg_observations TYPE option<string>;
+    -- Confidence decay fields
+    DEFINE FIELD IF NOT EXISTS volatility ON TABLE kg_observations TYPE option<string> DEFAULT "medium";
+    DEFINE FIELD IF NOT EXISTS last_refreshed ON TABLE kg_observations TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS refresh_count ON TABLE kg_observations TYPE option<int> DEFAULT 0;
+    DEFINE FIELD IF NOT EXISTS decay_confidence ON TABLE kg_observations TYPE option<float>;
+
+    -- CorrectionEvent table for REMini correction system
+    DEFINE TABLE IF NOT EXISTS correction_events SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS timestamp ON TABLE correction_events TYPE datetime DEFAULT time::now();
+    DEFINE FIELD IF NOT EXISTS target_id ON TABLE correction_events TYPE string;
+    DEFINE FIELD IF NOT EXISTS target_table ON TABLE correction_events TYPE string;
+    DEFINE FIELD IF NOT EXISTS previous_state ON TABLE correction_events TYPE object;
+    DEFINE FIELD IF NOT EXISTS new_state ON TABLE correction_events TYPE object;
+    DEFINE FIELD IF NOT EXISTS initiated_by ON TABLE correction_events TYPE string;
+    DEFINE FIELD IF NOT EXISTS reasoning ON TABLE correction_events TYPE string;
+    DEFINE FIELD IF NOT EXISTS sources ON TABLE correction_events TYPE array<string>;
+    DEFINE FIELD IF NOT EXISTS verification_status ON TABLE correction_events TYPE string DEFAULT "auto_applied";
+    DEFINE FIELD IF NOT EXISTS corrects_previous ON TABLE correction_events TYPE option<record<correction_events>>;
+    DEFINE FIELD IF NOT EXISTS spawned_by ON TABLE correction_events TYPE option<record<correction_events>>;
+    DEFINE INDEX IF NOT EXISTS idx_correction_events_target ON TABLE correction_events FIELDS target_id, target_table;
+    DEFINE INDEX IF NOT EXISTS idx_correction_events_timestamp ON TABLE correction_events FIELDS timestamp;
+
+    -- Agent exchange logging
+    DEFINE TABLE IF NOT EXISTS agent_exchanges SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS agent_source ON TABLE agent_exchanges TYPE string;
+    DEFINE FIELD IF NOT EXISTS agent_instance ON TABLE agent_exchanges TYPE string;
+    DEFINE FIELD IF NOT EXISTS prompt ON TABLE agent_exchanges TYPE string;
+    DEFINE FIELD IF NOT EXISTS response ON TABLE agent_exchanges TYPE string;
+    DEFINE FIELD IF NOT EXISTS tool_name ON TABLE agent_exchanges TYPE string;
+    DEFINE FIELD IF NOT EXISTS session_id ON TABLE agent_exchanges TYPE string;
+    DEFINE FIELD IF NOT EXISTS metadata ON TABLE agent_exchanges TYPE object;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE agent_exchanges TYPE datetime DEFAULT time::now();
+    DEFINE INDEX IF NOT EXISTS idx_exchanges_session ON TABLE agent_exchanges FIELDS session_id;
+    DEFINE INDEX IF NOT EXISTS idx_exchanges_tool ON TABLE agent_exchanges FIELDS tool_name;
+
+    -- Tool session tracking
+    DEFINE TABLE IF NOT EXISTS tool_sessions SCHEMALESS;
+    DEFINE FIELD IF NOT EXISTS tool_name ON TABLE tool_sessions TYPE string;
+    DEFINE FIELD IF NOT EXISTS last_agent_session_id ON TABLE tool_sessions TYPE string;
+    DEFINE FIELD IF NOT EXISTS last_exchange_id ON TABLE tool_sessions TYPE record<agent_exchanges>;
+    DEFINE FIELD IF NOT EXISTS exchange_count ON TABLE tool_sessions TYPE int DEFAULT 0;
+    DEFINE FIELD IF NOT EXISTS last_updated ON TABLE tool_sessions TYPE datetime DEFAULT time::now();
+    DEFINE INDEX IF NOT EXISTS idx_sessions_tool ON TABLE tool_sessions FIELDS tool_name UNIQUE;
+
+    -- Approval workflow candidate tables
+    DEFINE TABLE IF NOT EXISTS kg_entity_candidates SCHEMALESS;
+    DEFINE INDEX IF NOT EXISTS idx_kgec_status_created ON TABLE kg_entity_candidates FIELDS status, created_at;
+    DEFINE INDEX IF NOT EXISTS idx_kgec_confidence ON TABLE kg_entity_candidates FIELDS confidence;
+    DEFINE INDEX IF NOT EXISTS idx_kgec_name_type ON TABLE kg_entity_candidates FIELDS name, entity_type, status;
+
+    DEFINE TABLE IF NOT EXISTS kg_edge_candidates SCHEMALESS;
+    DEFINE INDEX IF NOT EXISTS idx_kgedc_status_created ON TABLE kg_edge_candidates FIELDS status, created_at;
+    DEFINE INDEX IF NOT EXISTS idx_kgedc_confidence ON TABLE kg_edge_candidates FIELDS confidence;
+    DEFINE INDEX IF NOT EXISTS idx_kgedc_triplet ON TABLE kg_edge_candidates FIELDS source_name, target_name, rel_type, status;
+
+    -- KG deletion audit (snapshots of records removed via forget)
+    DEFINE TABLE IF NOT EXISTS kg_deletions SCHEMALESS;
+    DEFINE INDEX IF NOT EXISTS idx_kgdel_created ON TABLE kg_deletions FIELDS created_at;
+    DEFINE INDEX IF NOT EXISTS idx_kgdel_record ON TABLE kg_deletions FIELDS source_table, record_id;
+
+    -- Tool call audit trail (argument shapes only, never content)
+    DEFINE TABLE IF NOT EXISTS tool_audit SCHEMALESS;
+    DEFINE INDEX IF NOT EXISTS idx_tool_audit_created ON TABLE tool_audit FIELDS created_at;
+    DEFINE INDEX IF NOT EXISTS idx_tool_audit_tool_created ON TABLE tool_audit FIELDS tool, created_at;
+
+    -- Optional feedback helpers
+    DEFINE TABLE IF NOT EXISTS kg_blocklist SCHEMALESS;
+    DEFINE INDEX IF NOT EXISTS idx_kgb_item ON TABLE kg_blocklist FIELDS item;
+
+    -- KG boundaries (rejected extractions with context)
+    DEFINE TABLE IF NOT EXISTS kg_boundaries SCHEMALESS;
+    DEFINE FIELD IF NOT EXISTS source_thought_id ON TABLE kg_boundaries TYPE string;
+    DEFINE FIELD IF NOT EXISTS rejected ON TABLE kg_boundaries TYPE string;
+    DEFINE FIELD IF NOT EXISTS reason ON TABLE kg_boundaries TYPE string;
+    DEFINE FIELD IF NOT EXISTS context ON TABLE kg_boundaries TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS confidence ON TABLE kg_boundaries TYPE option<float>;
+    DEFINE FIELD IF NOT EXISTS extraction_batch_id ON TABLE kg_boundaries TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS extracted_at ON TABLE kg_boundaries TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS extraction_prompt_version ON TABLE kg_boundaries TYPE option<string>;
+    DEFINE INDEX IF NOT EXISTS idx_kgbnd_created ON TABLE kg_boundaries FIELDS created_at;
+    DEFINE INDEX IF NOT EXISTS idx_kgbnd_thought ON TABLE kg_boundaries FIELDS source_thought_id;
+    DEFINE INDEX IF NOT EXISTS idx_kgbnd_batch ON TABLE kg_boundaries FIELDS extraction_batch_id;
+
+    -- Agent job tracking for async tool execution
+    DEFINE TABLE IF NOT EXISTS agent_jobs SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS job_id ON TABLE agent_jobs TYPE string;
+    DEFINE FIELD IF NOT EXISTS tool_name ON TABLE agent_jobs TYPE string;
+    DEFINE FIELD IF NOT EXISTS agent_source ON TABLE agent_jobs TYPE string;
+    DEFINE FIELD IF NOT EXISTS agent_instance ON TABLE agent_jobs TYPE string;
+    DEFINE FIELD IF NOT EXISTS status ON TABLE agent_jobs TYPE string;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE agent_jobs TYPE datetime DEFAULT time::now();
+    DEFINE FIELD IF NOT EXISTS started_at ON TABLE agent_jobs TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS completed_at ON TABLE agent_jobs TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS duration_ms ON TABLE agent_jobs TYPE option<int>;
+    DEFINE FIELD IF NOT EXISTS error ON TABLE agent_jobs TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS session_id ON TABLE agent_jobs TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS exchange_id ON TABLE agent_jobs TYPE option<record<agent_exchanges>>;
+    DEFINE FIELD IF NOT EXISTS metadata ON TABLE agent_jobs TYPE option<object>;
+    DEFINE FIELD IF NOT EXISTS prompt ON TABLE agent_jobs TYPE string;
+    DEFINE FIELD IF NOT EXISTS task_name ON TABLE agent_jobs TYPE string;
+    DEFINE FIELD IF NOT EXISTS model_override ON TABLE agent_jobs TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS cwd ON TABLE agent_jobs TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS timeout_ms ON TABLE agent_jobs TYPE option<int>;
+    DEFINE FIELD IF NOT EXISTS tool_timeout_ms ON TABLE agent_jobs TYPE option<int>;
+    DEFINE FIELD IF NOT EXISTS expose_stream ON TABLE agent_jobs TYPE option<bool>;
+    DEFINE FIELD IF NOT EXISTS resume_session_id ON TABLE agent_jobs TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS continue_latest ON TABLE agent_jobs TYPE option<bool>;
+    DEFINE FIELD IF NOT EXISTS fire_and_forget ON TABLE agent_jobs TYPE option<bool>;
+    DEFINE INDEX IF NOT EXISTS idx_jobs_job_id ON TABLE agent_jobs FIELDS job_id UNIQUE;
+    DEFINE INDEX IF NOT EXISTS idx_jobs_status ON TABLE agent_jobs FIELDS status;
+    DEFINE INDEX IF NOT EXISTS idx_jobs_created ON TABLE agent_jobs FIELDS created_at;
+    DEFINE INDEX IF NOT EXISTS idx_jobs_tool ON TABLE agent_jobs FIELDS tool_name;
+"#;
+
+/// One schema change. Applied migrations are recorded by name and never rerun, so a released
+/// migration must not be edited; add a new one instead.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// Paths, error codes, and URLs found in thought content (see `utils::code_refs`).
+const THOUGHT_CODE_REFS_SQL: &str = "
+    DEFINE FIELD IF NOT EXISTS code_refs ON TABLE thoughts TYPE option<array<object>>;
+    DEFINE FIELD IF NOT EXISTS code_refs[*].kind ON TABLE thoughts TYPE string;
+    DEFINE FIELD IF NOT EXISTS code_refs[*].value ON TABLE thoughts TYPE string;
+    DEFINE FIELD IF NOT EXISTS code_refs[*].line ON TABLE thoughts TYPE option<int>;";
+
+/// Content history kept by `maintain update_thought`.
+const THOUGHT_REVISIONS_SQL: &str = "
+    DEFINE FIELD IF NOT EXISTS revisions ON TABLE thoughts TYPE option<array<object>>;
+    DEFINE FIELD IF NOT EXISTS revisions[*].content ON TABLE thoughts TYPE string;
+    DEFINE FIELD IF NOT EXISTS revisions[*].edited_at ON TABLE thoughts TYPE datetime;
+    DEFINE FIELD IF NOT EXISTS revisions[*].reason ON TABLE thoughts TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS revisions[*].mode ON TABLE thoughts TYPE string;
+    DEFINE FIELD IF NOT EXISTS revision_count ON TABLE thoughts TYPE option<int>;";
+
+/// Verification scores per hypothesis over time (`maintain hypothesis_trend`).
+const HYPOTHESIS_HISTORY_SQL: &str = "
+    DEFINE TABLE IF NOT EXISTS hypothesis_history SCHEMALESS;
+    DEFINE INDEX IF NOT EXISTS idx_hypothesis_history_hash ON TABLE hypothesis_history FIELDS hypothesis_hash, created_at;";
+
+const EMBEDDING_USAGE_SQL: &str = "
+    DEFINE TABLE IF NOT EXISTS embedding_usage SCHEMALESS;
+    DEFINE INDEX IF NOT EXISTS idx_embedding_usage_day ON TABLE embedding_usage FIELDS day;";
+
+/// Detected content language, ISO 639-1 or `und` (see `utils::lang`).
+const CONTENT_LANG_SQL: &str = "
+    DEFINE FIELD IF NOT EXISTS lang ON TABLE thoughts TYPE option<string>;
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_lang ON TABLE thoughts FIELDS lang;
+    DEFINE INDEX IF NOT EXISTS idx_kgo_lang ON TABLE kg_observations FIELDS lang;";
+
+/// The `[injection]` policy each memory injection ran with, plus its candidate count.
+const INJECTION_POLICY_SQL: &str = "
+    DEFINE FIELD IF NOT EXISTS injection_policy ON TABLE thoughts TYPE option<object> FLEXIBLE;";
+
+/// Session summaries: the thoughts a summary covers, and the summary covering a thought.
+const SESSION_SUMMARY_SQL: &str = "
+    DEFINE FIELD IF NOT EXISTS summarizes ON TABLE thoughts TYPE option<array<string>>;
+    DEFINE FIELD IF NOT EXISTS summarized_by ON TABLE thoughts TYPE option<string>;
+    DEFINE INDEX IF NOT EXISTS idx_thoughts_origin_session ON TABLE thoughts FIELDS origin, session_id;";
+
+/// Relationship strength and validity window; `data.strength` from older writers is promoted.
+const EDGE_ATTRIBUTES_SQL: &str = "
+    DEFINE FIELD IF NOT EXISTS strength ON TABLE kg_edges TYPE option<float>;
+    DEFINE FIELD IF NOT EXISTS valid_from ON TABLE kg_edges TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS valid_to ON TABLE kg_edges TYPE option<datetime>;
+    UPDATE kg_edges SET strength = <float> data.strength
+        WHERE strength = NONE AND type::is_number(data.strength) RETURN NONE;";
+
+/// Every migration, in the order it applies.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        name: "0001_baseline",
+        sql: SCHEMA_SQL,
+    },
+    Migration {
+        name: "0002_thought_code_refs",
+        sql: THOUGHT_CODE_REFS_SQL,
+    },
+    Migration {
+        name: "0003_thought_revisions",
+        sql: THOUGHT_REVISIONS_SQL,
+    },
+    Migration {
+        name: "0004_hypothesis_history",
+        sql: HYPOTHESIS_HISTORY_SQL,
+    },
+    Migration {
+        name: "0005_embedding_usage",
+        sql: EMBEDDING_USAGE_SQL,
+    },
+    Migration {
+        name: "0006_content_lang",
+        sql: CONTENT_LANG_SQL,
+    },
+    Migration {
+        name: "0007_injection_policy",
+        sql: INJECTION_POLICY_SQL,
+    },
+    Migration {
+        name: "0008_session_summaries",
+        sql: SESSION_SUMMARY_SQL,
+    },
+    Migration {
+        name: "0009_edge_attributes",
+        sql: EDGE_ATTRIBUTES_SQL,
+    },
+];
+
+const MIGRATIONS_TABLE_SQL: &str = "
+    DEFINE TABLE IF NOT EXISTS schema_migrations SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS name ON TABLE schema_migrations TYPE string;
+    DEFINE FIELD IF NOT EXISTS applied_at ON TABLE schema_migrations TYPE datetime;
+    DEFINE INDEX IF NOT EXISTS idx_schema_migrations_name ON TABLE schema_migrations FIELDS name UNIQUE;";
+
+const APPLIED_SQL: &str = "SELECT name, <string> applied_at AS applied_at \
+    FROM schema_migrations ORDER BY applied_at ASC, name ASC";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    pub name: String,
+    pub applied_at: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MigrationStatus {
+    pub applied: Vec<AppliedMigration>,
+    pub pending: Vec<String>,
+    /// Recorded migrations this build does not define (applied by a newer build)
+    pub unknown: Vec<String>,
+}
 
-        // Minimal schema to ensure required tables exist
-        // Note: SurrealDB 2.x requires vector index definitions to include DIMENSION.
-        // We derive the active embedding dimension from the embedder to avoid drift.
-        let dim = self.embedder.dimensions();
-        let schema_sql = format!(
-            r#"
-            DEFINE TABLE thoughts SCHEMAFULL;
-            DEFINE FIELD content ON TABLE thoughts TYPE string;
-            DEFINE FIELD created_at ON TABLE thoughts TYPE datetime;
-            DEFINE FIELD OVERWRITE embedding ON TABLE thoughts TYPE option<array<float>>;
-            DEFINE FIELD injected_memories ON TABLE thoughts TYPE array<string>;
-            DEFINE FIELD enriched_content ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD injection_scale ON TABLE thoughts TYPE int;
-            DEFINE FIELD significance ON TABLE thoughts TYPE float;
-            DEFINE FIELD access_count ON TABLE thoughts TYPE int;
-            DEFINE FIELD last_accessed ON TABLE thoughts TYPE option<datetime>;
-            DEFINE FIELD submode ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD framework_enhanced ON TABLE thoughts TYPE option<bool>;
-            DEFINE FIELD OVERWRITE framework_analysis ON TABLE thoughts TYPE option<object> FLEXIBLE;
-            DEFINE FIELD status ON TABLE thoughts TYPE option<string>;
-            -- Origin and privacy fields for retrieval
-            DEFINE FIELD origin ON TABLE thoughts TYPE option<string>;
-            -- Provenance fields for agent synthesis
-            DEFINE FIELD source_exchange_id ON TABLE thoughts TYPE option<record<agent_exchanges>>;
-            DEFINE FIELD synthesis_type ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD tags ON TABLE thoughts TYPE option<array<string>>;
-            DEFINE FIELD is_private ON TABLE thoughts TYPE option<bool>;
-            -- Embedding metadata for future re-embedding
-            DEFINE FIELD embedding_model ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD embedding_provider ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD embedding_dim ON TABLE thoughts TYPE option<int>;
-            DEFINE FIELD embedded_at ON TABLE thoughts TYPE option<datetime>;
-            -- Embedding status for graceful degradation (pending/complete/failed)
-            DEFINE FIELD embedding_status ON TABLE thoughts TYPE option<string> DEFAULT "complete";
-            DEFINE FIELD extracted_to_kg ON TABLE thoughts TYPE bool DEFAULT false;
-            DEFINE FIELD extraction_batch_id ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD extracted_at ON TABLE thoughts TYPE option<datetime>;
-            -- Mark fields for REMini correction system
-            DEFINE FIELD marked_for ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD mark_type ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD mark_note ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD marked_at ON TABLE thoughts TYPE option<datetime>;
-            DEFINE FIELD marked_by ON TABLE thoughts TYPE option<string>;
-            -- Continuity fields for thought chaining
-            DEFINE FIELD session_id ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD chain_id ON TABLE thoughts TYPE option<string>;
-            DEFINE FIELD previous_thought_id ON TABLE thoughts TYPE option<record<thoughts> | string>;
-            DEFINE FIELD revises_thought ON TABLE thoughts TYPE option<record<thoughts> | string>;
-            DEFINE FIELD branch_from ON TABLE thoughts TYPE option<record<thoughts> | string>;
-            DEFINE FIELD confidence ON TABLE thoughts TYPE option<float>;
-            DEFINE INDEX thoughts_embedding_idx ON TABLE thoughts FIELDS embedding HNSW DIMENSION {dim};
-            DEFINE INDEX thoughts_status_idx ON TABLE thoughts FIELDS status;
-            DEFINE INDEX idx_thoughts_created ON TABLE thoughts FIELDS created_at;
-            DEFINE INDEX idx_thoughts_embedding_model ON TABLE thoughts FIELDS embedding_model;
-            DEFINE INDEX idx_thoughts_embedding_dim ON TABLE thoughts FIELDS embedding_dim;
-            DEFINE INDEX idx_thoughts_embedding_status ON TABLE thoughts FIELDS embedding_status;
-            -- Continuity indexes
-            DEFINE INDEX idx_thoughts_session ON TABLE thoughts FIELDS session_id, created_at;
-            DEFINE INDEX idx_thoughts_chain ON TABLE thoughts FIELDS chain_id, created_at;
-
-            DEFINE TABLE recalls SCHEMALESS;
-            DEFINE INDEX idx_recalls_created ON TABLE recalls FIELDS created_at;
-
-            DEFINE TABLE kg_entities SCHEMALESS;
-            DEFINE FIELD source_thought_ids ON TABLE kg_entities TYPE option<array<string>>;
-            DEFINE FIELD extraction_batch_id ON TABLE kg_entities TYPE option<string>;
-            DEFINE FIELD extracted_at ON TABLE kg_entities TYPE option<datetime>;
-            DEFINE FIELD extraction_confidence ON TABLE kg_entities TYPE option<float>;
-            DEFINE FIELD extraction_prompt_version ON TABLE kg_entities TYPE option<string>;
-            DEFINE INDEX idx_kge_created ON TABLE kg_entities FIELDS created_at;
-            DEFINE INDEX idx_kge_name ON TABLE kg_entities FIELDS name;
-            DEFINE INDEX idx_kge_name_type ON TABLE kg_entities FIELDS name, data.entity_type;
-            DEFINE INDEX idx_kge_extraction_batch ON TABLE kg_entities FIELDS extraction_batch_id;
-            -- Mark fields for REMini correction system
-            DEFINE FIELD marked_for ON TABLE kg_entities TYPE option<string>;
-            DEFINE FIELD mark_type ON TABLE kg_entities TYPE option<string>;
-            DEFINE FIELD mark_note ON TABLE kg_entities TYPE option<string>;
-            DEFINE FIELD marked_at ON TABLE kg_entities TYPE option<datetime>;
-            DEFINE FIELD marked_by ON TABLE kg_entities TYPE option<string>;
-            -- Confidence decay fields
-            DEFINE FIELD volatility ON TABLE kg_entities TYPE option<string> DEFAULT "medium";
-            DEFINE FIELD last_refreshed ON TABLE kg_entities TYPE option<datetime>;
-            DEFINE FIELD refresh_count ON TABLE kg_entities TYPE option<int> DEFAULT 0;
-            DEFINE FIELD decay_confidence ON TABLE kg_entities TYPE option<float>;
-
-            -- Journal thread fields
-            DEFINE FIELD thread_status ON TABLE kg_entities TYPE option<string>;
-            DEFINE INDEX idx_kge_entity_type_status ON TABLE kg_entities FIELDS entity_type, thread_status;
-
-            DEFINE TABLE kg_edges SCHEMALESS;
-            DEFINE FIELD source_thought_ids ON TABLE kg_edges TYPE option<array<string>>;
-            DEFINE FIELD extraction_batch_id ON TABLE kg_edges TYPE option<string>;
-            DEFINE FIELD extracted_at ON TABLE kg_edges TYPE option<datetime>;
-            DEFINE FIELD extraction_confidence ON TABLE kg_edges TYPE option<float>;
-            DEFINE FIELD extraction_prompt_version ON TABLE kg_edges TYPE option<string>;
-            DEFINE INDEX idx_kged_created ON TABLE kg_edges FIELDS created_at;
-            DEFINE INDEX idx_kged_triplet ON TABLE kg_edges FIELDS source, target, rel_type;
-            DEFINE INDEX idx_kged_extraction_batch ON TABLE kg_edges FIELDS extraction_batch_id;
-
-            DEFINE TABLE kg_observations SCHEMALESS;
-            DEFINE FIELD source_thought_ids ON TABLE kg_observations TYPE option<array<string>>;
-            DEFINE FIELD extraction_batch_id ON TABLE kg_observations TYPE option<string>;
-            DEFINE FIELD extracted_at ON TABLE kg_observations TYPE option<datetime>;
-            DEFINE FIELD extraction_confidence ON TABLE kg_observations TYPE option<float>;
-            DEFINE FIELD extraction_prompt_version ON TABLE kg_observations TYPE option<string>;
-            DEFINE INDEX idx_kgo_created ON TABLE kg_observations FIELDS created_at;
-            DEFINE INDEX idx_kgo_name ON TABLE kg_observations FIELDS name;
-            DEFINE INDEX idx_kgo_name_src ON TABLE kg_observations FIELDS name, source_thought_id;
-            DEFINE INDEX idx_kgo_extraction_batch ON TABLE kg_observations FIELDS extraction_batch_id;
-            -- Journal entry fields
-            DEFINE FIELD author ON TABLE kg_observations TYPE option<string>;
-            DEFINE FIELD observation_type ON TABLE kg_observations TYPE option<string>;
-            DEFINE INDEX idx_kgo_author ON TABLE kg_observations FIELDS author;
-            DEFINE INDEX idx_kgo_obs_type ON TABLE kg_observations FIELDS observation_type;
-            -- Mark fields for REMini correction system
-            DEFINE FIELD marked_for ON TABLE kg_observations TYPE option<string>;
-            DEFINE FIELD mark_type ON TABLE kg_observations TYPE option<string>;
-            DEFINE FIELD mark_note ON TABLE kg_observations TYPE option<string>;
-            DEFINE FIELD marked_at ON TABLE kg_observations TYPE option<datetime>;
-            DEFINE FIELD marked_by ON TABLE kg_observations TYPE option<string>;
-            -- Confidence decay fields
-            DEFINE FIELD volatility ON TABLE kg_observations TYPE option<string> DEFAULT "medium";
-            DEFINE FIELD last_refreshed ON TABLE kg_observations TYPE option<datetime>;
-            DEFINE FIELD refresh_count ON TABLE kg_observations TYPE option<int> DEFAULT 0;
-            DEFINE FIELD decay_confidence ON TABLE kg_observations TYPE option<float>;
-
-            -- CorrectionEvent table for REMini correction system
-            DEFINE TABLE correction_events SCHEMAFULL;
-            DEFINE FIELD timestamp ON TABLE correction_events TYPE datetime DEFAULT time::now();
-            DEFINE FIELD target_id ON TABLE correction_events TYPE string;
-            DEFINE FIELD target_table ON TABLE correction_events TYPE string;
-            DEFINE FIELD previous_state ON TABLE correction_events TYPE object;
-            DEFINE FIELD new_state ON TABLE correction_events TYPE object;
-            DEFINE FIELD initiated_by ON TABLE correction_events TYPE string;
-            DEFINE FIELD reasoning ON TABLE correction_events TYPE string;
-            DEFINE FIELD sources ON TABLE correction_events TYPE array<string>;
-            DEFINE FIELD verification_status ON TABLE correction_events TYPE string DEFAULT "auto_applied";
-            DEFINE FIELD corrects_previous ON TABLE correction_events TYPE option<record<correction_events>>;
-            DEFINE FIELD spawned_by ON TABLE correction_events TYPE option<record<correction_events>>;
-            DEFINE INDEX idx_correction_events_target ON TABLE correction_events FIELDS target_id, target_table;
-            DEFINE INDEX idx_correction_events_timestamp ON TABLE correction_events FIELDS timestamp;
-
-            -- Agent exchange logging
-            DEFINE TABLE agent_exchanges SCHEMAFULL;
-            DEFINE FIELD agent_source ON TABLE agent_exchanges TYPE string;
-            DEFINE FIELD agent_instance ON TABLE agent_exchanges TYPE string;
-            DEFINE FIELD prompt ON TABLE agent_exchanges TYPE string;
-            DEFINE FIELD response ON TABLE agent_exchanges TYPE string;
-            DEFINE FIELD tool_name ON TABLE agent_exchanges TYPE string;
-            DEFINE FIELD session_id ON TABLE agent_exchanges TYPE string;
-            DEFINE FIELD metadata ON TABLE agent_exchanges TYPE object;
-            DEFINE FIELD created_at ON TABLE agent_exchanges TYPE datetime DEFAULT time::now();
-            DEFINE INDEX idx_exchanges_session ON TABLE agent_exchanges FIELDS session_id;
-            DEFINE INDEX idx_exchanges_tool ON TABLE agent_exchanges FIELDS tool_name;
-
-            -- Tool session tracking
-            DEFINE TABLE tool_sessions SCHEMALESS;
-            DEFINE FIELD tool_name ON TABLE tool_sessions TYPE string;
-            DEFINE FIELD last_agent_session_id ON TABLE tool_sessions TYPE string;
-            DEFINE FIELD last_exchange_id ON TABLE tool_sessions TYPE record<agent_exchanges>;
-            DEFINE FIELD exchange_count ON TABLE tool_sessions TYPE int DEFAULT 0;
-            DEFINE FIELD last_updated ON TABLE tool_sessions TYPE datetime DEFAULT time::now();
-            DEFINE INDEX idx_sessions_tool ON TABLE tool_sessions FIELDS tool_name UNIQUE;
-
-            -- Approval workflow candidate tables
-            DEFINE TABLE kg_entity_candidates SCHEMALESS;
-            DEFINE INDEX idx_kgec_status_created ON TABLE kg_entity_candidates FIELDS status, created_at;
-            DEFINE INDEX idx_kgec_confidence ON TABLE kg_entity_candidates FIELDS confidence;
-            DEFINE INDEX idx_kgec_name_type ON TABLE kg_entity_candidates FIELDS name, entity_type, status;
-
-            DEFINE TABLE kg_edge_candidates SCHEMALESS;
-            DEFINE INDEX idx_kgedc_status_created ON TABLE kg_edge_candidates FIELDS status, created_at;
-            DEFINE INDEX idx_kgedc_confidence ON TABLE kg_edge_candidates FIELDS confidence;
-            DEFINE INDEX idx_kgedc_triplet ON TABLE kg_edge_candidates FIELDS source_name, target_name, rel_type, status;
-
-            -- Optional feedback helpers
-            DEFINE TABLE kg_blocklist SCHEMALESS;
-            DEFINE INDEX idx_kgb_item ON TABLE kg_blocklist FIELDS item;
-
-            -- KG boundaries (rejected extractions with context)
-            DEFINE TABLE kg_boundaries SCHEMALESS;
-            DEFINE FIELD source_thought_id ON TABLE kg_boundaries TYPE string;
-            DEFINE FIELD rejected ON TABLE kg_boundaries TYPE string;
-            DEFINE FIELD reason ON TABLE kg_boundaries TYPE string;
-            DEFINE FIELD context ON TABLE kg_boundaries TYPE option<string>;
-            DEFINE FIELD confidence ON TABLE kg_boundaries TYPE option<float>;
-            DEFINE FIELD extraction_batch_id ON TABLE kg_boundaries TYPE option<string>;
-            DEFINE FIELD extracted_at ON TABLE kg_boundaries TYPE option<datetime>;
-            DEFINE FIELD extraction_prompt_version ON TABLE kg_boundaries TYPE option<string>;
-            DEFINE INDEX idx_kgbnd_created ON TABLE kg_boundaries FIELDS created_at;
-            DEFINE INDEX idx_kgbnd_thought ON TABLE kg_boundaries FIELDS source_thought_id;
-            DEFINE INDEX idx_kgbnd_batch ON TABLE kg_boundaries FIELDS extraction_batch_id;
-
-            -- Agent job tracking for async tool execution
-            DEFINE TABLE agent_jobs SCHEMAFULL;
-            DEFINE FIELD job_id ON TABLE agent_jobs TYPE string;
-            DEFINE FIELD tool_name ON TABLE agent_jobs TYPE string;
-            DEFINE FIELD agent_source ON TABLE agent_jobs TYPE string;
-            DEFINE FIELD agent_instance ON TABLE agent_jobs TYPE string;
-            DEFINE FIELD status ON TABLE agent_jobs TYPE string;
-            DEFINE FIELD created_at ON TABLE agent_jobs TYPE datetime DEFAULT time::now();
-            DEFINE FIELD started_at ON TABLE agent_jobs TYPE option<datetime>;
-            DEFINE FIELD completed_at ON TABLE agent_jobs TYPE option<datetime>;
-            DEFINE FIELD duration_ms ON TABLE agent_jobs TYPE option<int>;
-            DEFINE FIELD error ON TABLE agent_jobs TYPE option<string>;
-            DEFINE FIELD session_id ON TABLE agent_jobs TYPE option<string>;
-            DEFINE FIELD exchange_id ON TABLE agent_jobs TYPE option<record<agent_exchanges>>;
-            DEFINE FIELD metadata ON TABLE agent_jobs TYPE option<object>;
-            DEFINE FIELD prompt ON TABLE agent_jobs TYPE string;
-            DEFINE FIELD task_name ON TABLE agent_jobs TYPE string;
-            DEFINE FIELD model_override ON TABLE agent_jobs TYPE option<string>;
-            DEFINE FIELD cwd ON TABLE agent_jobs TYPE option<string>;
-            DEFINE FIELD timeout_ms ON TABLE agent_jobs TYPE option<int>;
-            DEFINE FIELD tool_timeout_ms ON TABLE agent_jobs TYPE option<int>;
-            DEFINE FIELD expose_stream ON TABLE agent_jobs TYPE option<bool>;
-            DEFINE FIELD resume_session_id ON TABLE agent_jobs TYPE option<string>;
-            DEFINE FIELD continue_latest ON TABLE agent_jobs TYPE option<bool>;
-            DEFINE FIELD fire_and_forget ON TABLE agent_jobs TYPE option<bool>;
-            DEFINE INDEX idx_jobs_job_id ON TABLE agent_jobs FIELDS job_id UNIQUE;
-            DEFINE INDEX idx_jobs_status ON TABLE agent_jobs FIELDS status;
-            DEFINE INDEX idx_jobs_created ON TABLE agent_jobs FIELDS created_at;
-            DEFINE INDEX idx_jobs_tool ON TABLE agent_jobs FIELDS tool_name;
-        "#
+/// Split `migrations` against the `applied` records.
+pub fn plan_migrations(
+    migrations: &[Migration],
+    applied: Vec<AppliedMigration>,
+) -> MigrationStatus {
+    let known = |name: &str| migrations.iter().any(|m| m.name == name);
+    MigrationStatus {
+        pending: migrations
+            .iter()
+            .filter(|m| !applied.iter().any(|a| a.name == m.name))
+            .map(|m| m.name.to_string())
+            .collect(),
+        unknown: applied
+            .iter()
+            .filter(|a| !known(&a.name))
+            .map(|a| a.name.clone())
+            .collect(),
+        applied,
+    }
+}
+
+/// Which of `migrations` the database has applied. Read-only: a database that never ran
+/// migrations reports all of them pending.
+pub async fn migration_status(
+    db: &Surreal<DbEngine>,
+    migrations: &[Migration],
+) -> Result<MigrationStatus> {
+    let rows: Vec<serde_json::Value> = db.query(APPLIED_SQL).await?.take(0).unwrap_or_default();
+    let applied = rows
+        .into_iter()
+        .filter_map(|row| serde_json::from_value(row).ok())
+        .collect();
+    Ok(plan_migrations(migrations, applied))
+}
+
+/// Apply the pending `migrations` in order and return their names. Stops at the first failure,
+/// since later migrations may depend on it.
+pub async fn run_migrations(
+    db: &Surreal<DbEngine>,
+    migrations: &[Migration],
+) -> Result<Vec<String>> {
+    db.query(MIGRATIONS_TABLE_SQL).await?.check()?;
+    let status = migration_status(db, migrations).await?;
+    let mut applied = Vec::new();
+    for migration in migrations
+        .iter()
+        .filter(|m| status.pending.iter().any(|p| p == m.name))
+    {
+        let sql = format!(
+            "BEGIN TRANSACTION; {}; \
+             CREATE schema_migrations CONTENT {{ name: $name, applied_at: time::now() }}; \
+             COMMIT TRANSACTION;",
+            migration.sql.trim().trim_end_matches(';')
         );
+        db.query(sql)
+            .bind(("name", migration.name))
+            .await
+            .and_then(|r| r.check())
+            .map_err(|e| SurrealMindError::Database {
+                message: format!("migration {} failed: {}", migration.name, e),
+            })?;
+        info!("schema: applied migration {}", migration.name);
+        applied.push(migration.name.to_string());
+    }
+    Ok(applied)
+}
 
-        self.db.query(schema_sql).await.map_err(|e| McpError {
+impl SurrealMindServer {
+    /// Bring the database schema up to date, or with `runtime.skip_migrations` only report
+    /// pending migrations
+    pub async fn initialize_schema(&self) -> std::result::Result<(), McpError> {
+        let internal = |e: SurrealMindError| McpError {
             code: rmcp::model::ErrorCode::INTERNAL_ERROR,
             message: format!("Schema init failed: {}", e).into(),
             data: None,
-        })?;
+        };
+        let db = self.db.client();
+
+        if self.config.runtime.skip_migrations {
+            let status = migration_status(&db, MIGRATIONS).await.map_err(internal)?;
+            if !status.pending.is_empty() {
+                warn!(
+                    "schema: skip_migrations set; not applying {}",
+                    status.pending.join(", ")
+                );
+            }
+            return Ok(());
+        }
 
+        info!("Initializing consciousness graph schema");
+        run_migrations(&db, MIGRATIONS).await.map_err(internal)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(name: &str) -> AppliedMigration {
+        AppliedMigration {
+            name: name.to_string(),
+            applied_at: "2026-10-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_plan_keeps_declared_order_and_flags_unknown_records() {
+        let migrations = [
+            Migration {
+                name: "0001_a",
+                sql: "",
+            },
+            Migration {
+                name: "0002_b",
+                sql: "",
+            },
+            Migration {
+                name: "0003_c",
+                sql: "",
+            },
+        ];
+        let status = plan_migrations(&migrations, vec![applied("0002_b"), applied("0009_new")]);
+        assert_eq!(status.pending, ["0001_a", "0003_c"]);
+        assert_eq!(status.unknown, ["0009_new"]);
+        assert_eq!(status.applied.len(), 2);
+
+        let none = plan_migrations(&migrations, Vec::new());
+        assert_eq!(none.pending.len(), 3);
+    }
+
+    #[test]
+    fn test_migration_names_are_unique_and_ordered() {
+        let names: Vec<&str> = MIGRATIONS.iter().map(|m| m.name).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(names, sorted);
+        assert_eq!(MIGRATIONS[0].name, "0001_baseline");
+    }
+
+    #[test]
+    fn test_schema_statements_are_idempotent() {
+        // Databases created before migrations were recorded take 0001 again, so every
+        // statement must tolerate an existing definition
+        for statement in SCHEMA_SQL.split(';').map(str::trim) {
+            let statement = statement
+                .lines()
+                .filter(|l| !l.trim_start().starts_with("--"))
+                .collect::<String>();
+            let statement = statement.trim();
+            if statement.is_empty() {
+                continue;
+            }
+            assert!(
+                statement.contains(" IF NOT EXISTS ") || statement.contains(" OVERWRITE "),
+                "{statement}"
+            );
+        }
+    }
+
+    async fn recorded(db: &Surreal<DbEngine>) -> Vec<String> {
+        db.query("SELECT VALUE name FROM schema_migrations ORDER BY name")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_migrations_applies_each_once_in_order() {
+        let config = crate::test_support::test_config();
+        let db = crate::test_support::mem_db(&config).await.client();
+        let names: Vec<String> = MIGRATIONS.iter().map(|m| m.name.to_string()).collect();
+
+        assert_eq!(run_migrations(&db, MIGRATIONS).await.unwrap(), names);
+        assert_eq!(recorded(&db).await, names);
+        // A second run (another startup) applies nothing
+        assert!(run_migrations(&db, MIGRATIONS).await.unwrap().is_empty());
+        assert_eq!(recorded(&db).await, names);
+
+        // A migration added later applies on its own
+        const LATER: Migration = Migration {
+            name: "9001_test_probe",
+            sql: "DEFINE FIELD IF NOT EXISTS migration_probe ON TABLE thoughts TYPE option<string>;",
+        };
+        let extended = [MIGRATIONS, &[LATER]].concat();
+        assert_eq!(
+            run_migrations(&db, &extended).await.unwrap(),
+            ["9001_test_probe"]
+        );
+        let mut with_later = names.clone();
+        with_later.push("9001_test_probe".to_string());
+        assert_eq!(recorded(&db).await, with_later);
+        let status = migration_status(&db, MIGRATIONS).await.unwrap();
+        assert_eq!(status.unknown, ["9001_test_probe"]);
+
+        // A failing migration leaves neither its definitions nor a record behind
+        const BROKEN: Migration = Migration {
+            name: "9002_test_broken",
+            sql: "DEFINE FIELD IF NOT EXISTS broken_probe ON TABLE thoughts TYPE option<string>; THROW 'boom';",
+        };
+        let with_broken = [extended.as_slice(), &[BROKEN]].concat();
+        assert!(run_migrations(&db, &with_broken).await.is_err());
+        assert_eq!(recorded(&db).await, with_later);
+        let info: Vec<serde_json::Value> = db
+            .query("INFO FOR TABLE thoughts")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert!(info[0]["fields"].get("migration_probe").is_some());
+        assert!(info[0]["fields"].get("broken_probe").is_none());
+    }
+}
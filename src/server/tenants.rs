@@ -0,0 +1,197 @@
+//! Per-call namespace/database overrides
+//!
+//! The tools in [`TENANT_TOOLS`] accept `namespace` and `database`. A pair other than the
+//! configured one must be listed in `[multi_tenant] allowed`; the call then runs on a server
+//! for that database, opened on first use and kept in [`SurrealMindServer::tenants`]. It
+//! shares the embedder, config, and job limits with the default server but has its own
+//! connection (same URL and credentials), thought cache, entity index, and vector index
+//! state. Opening one applies the schema migrations and the embedding dimension check, so
+//! `runtime.embed_strict` refuses a database whose stored vectors don't match the embedder.
+
+use crate::error::{Result, SurrealMindError};
+use crate::server::SurrealMindServer;
+use crate::server::connection::{DbConnection, DbSettings};
+use lru::LruCache;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+/// Tools whose calls can target another database.
+pub const TENANT_TOOLS: &[&str] = &["think", "think_batch", "search", "remember", "forget"];
+
+/// Open tenant servers by (namespace, database).
+pub type TenantPool = Arc<Mutex<HashMap<(String, String), SurrealMindServer>>>;
+
+/// Remove `namespace`/`database` from `arguments` and return the pair they select, with a
+/// missing one taken from `default`. `None` when neither is given.
+pub fn take_override(
+    arguments: Option<&mut Map<String, Value>>,
+    default: (&str, &str),
+) -> Result<Option<(String, String)>> {
+    let Some(arguments) = arguments else {
+        return Ok(None);
+    };
+    let mut take = |key: &str| -> Result<Option<String>> {
+        match arguments.remove(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) if !s.trim().is_empty() => Ok(Some(s)),
+            Some(other) => Err(SurrealMindError::InvalidParams {
+                message: format!("{} must be a non-empty string, got {}", key, other),
+                field: None,
+            }),
+        }
+    };
+    let (ns, db) = (take("namespace")?, take("database")?);
+    if ns.is_none() && db.is_none() {
+        return Ok(None);
+    }
+    Ok(Some((
+        ns.unwrap_or_else(|| default.0.to_string()),
+        db.unwrap_or_else(|| default.1.to_string()),
+    )))
+}
+
+impl SurrealMindServer {
+    /// The server that serves calls for `namespace`/`database`: this one for the configured
+    /// pair, otherwise the pooled server for an allowlisted pair.
+    pub async fn tenant(&self, namespace: &str, database: &str) -> Result<SurrealMindServer> {
+        let system = &self.config.system;
+        if namespace == system.database_ns && database == system.database_db {
+            return Ok(self.clone());
+        }
+        if !self.config.multi_tenant.allows(system, namespace, database) {
+            return Err(SurrealMindError::InvalidParams {
+                message: format!(
+                    "namespace/database '{}/{}' is not listed in [multi_tenant] allowed",
+                    namespace, database
+                ),
+                field: None,
+            });
+        }
+
+        let key = (namespace.to_string(), database.to_string());
+        if let Some(server) = self.tenants.lock().await.get(&key) {
+            return Ok(server.clone());
+        }
+        // Connect without holding the pool, so calls for open tenants aren't held up. If
+        // a concurrent call opened the same tenant meanwhile, keep the pooled one.
+        let server = self.open_tenant(namespace, database).await?;
+        Ok(self
+            .tenants
+            .lock()
+            .await
+            .entry(key)
+            .or_insert(server)
+            .clone())
+    }
+
+    async fn open_tenant(&self, namespace: &str, database: &str) -> Result<SurrealMindServer> {
+        let settings = DbSettings {
+            ns: namespace.to_string(),
+            db: database.to_string(),
+            ..DbSettings::from_config(&self.config)
+        };
+        let db = DbConnection::connect(settings, 1).await?;
+        let mut config = (*self.config).clone();
+        config.system.database_ns = namespace.to_string();
+        config.system.database_db = database.to_string();
+        let cap = self.thoughts.read().await.cap();
+
+        let server = Self {
+            db: Arc::new(db),
+            thoughts: Arc::new(RwLock::new(LruCache::new(cap))),
+            cache_stats: Arc::default(),
+            config: Arc::new(config),
+            vector_index_ready: Arc::default(),
+            entity_index: Arc::new(RwLock::new(super::entity_index::EntityIndex::new(
+                self.config.runtime.warm_start,
+            ))),
+            tenants: Arc::default(),
+            ..self.clone()
+        };
+        server
+            .initialize_schema()
+            .await
+            .map_err(|e| SurrealMindError::Mcp {
+                message: e.message.to_string(),
+            })?;
+        if server.config.system.use_vector_index
+            && let Err(e) = server.ensure_vector_indexes().await
+        {
+            warn!(
+                "vector_index: setup failed for {}/{}, using full-scan similarity: {}",
+                namespace, database, e
+            );
+        }
+        if let Err(e) = server.check_embedding_dims().await {
+            if server.config.runtime.embed_strict {
+                return Err(SurrealMindError::Embedding {
+                    message: format!("{}/{}: {}", namespace, database, e),
+                });
+            }
+            warn!(
+                "Embedding dimension hygiene issue in {}/{}: {}. Re-embed to fix.",
+                namespace, database, e
+            );
+        }
+        info!("multi_tenant: opened {}/{}", namespace, database);
+        Ok(server)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_take_override_strips_and_fills_defaults() {
+        let default = ("surreal_mind", "consciousness");
+        let mut call = args(json!({"content": "x", "database": "personal"}));
+        assert_eq!(
+            take_override(Some(&mut call), default).unwrap(),
+            Some(("surreal_mind".to_string(), "personal".to_string()))
+        );
+        assert_eq!(call, args(json!({"content": "x"})));
+
+        let mut plain = args(json!({"content": "x", "namespace": null}));
+        assert_eq!(take_override(Some(&mut plain), default).unwrap(), None);
+        assert_eq!(take_override(None, default).unwrap(), None);
+
+        for bad in [json!(""), json!(7)] {
+            let mut call = args(json!({"namespace": bad}));
+            assert!(matches!(
+                take_override(Some(&mut call), default),
+                Err(SurrealMindError::InvalidParams { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_first_calls_share_one_tenant() {
+        let mut config = crate::test_support::test_config();
+        config.multi_tenant.allowed = vec![("other".to_string(), "personal".to_string())];
+        let server = crate::test_support::mem_server_with(config).await;
+
+        let (a, b) = tokio::join!(
+            server.tenant("other", "personal"),
+            server.tenant("other", "personal")
+        );
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert!(Arc::ptr_eq(&a.db, &b.db));
+        assert_eq!(server.tenants.lock().await.len(), 1);
+        let again = server.tenant("other", "personal").await.unwrap();
+        assert!(Arc::ptr_eq(&a.db, &again.db));
+
+        assert!(matches!(
+            server.tenant("other", "unlisted").await,
+            Err(SurrealMindError::InvalidParams { .. })
+        ));
+    }
+}
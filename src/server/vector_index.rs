@@ -0,0 +1,154 @@
+//! Optional HNSW vector indexes (`system.use_vector_index`) for KNN similarity search.
+//!
+//! When the indexes exist at the active embedding dimension, candidate fetches use the
+//! `<|K,EF|>` KNN operator instead of scoring every row. Otherwise they fall back to the
+//! full scan with DB-side cosine.
+
+use crate::error::Result;
+use crate::server::SurrealMindServer;
+use serde_json::json;
+use std::sync::atomic::Ordering;
+
+/// Tables covered by the vector index and the HNSW index name on each.
+pub const VECTOR_INDEXES: [(&str, &str); 3] = [
+    ("thoughts", "thoughts_embedding_idx"),
+    ("kg_entities", "idx_kge_embedding_hnsw"),
+    ("kg_observations", "idx_kgo_embedding_hnsw"),
+];
+
+/// Minimum HNSW search breadth (`ef`) for KNN queries.
+const KNN_EF: usize = 100;
+
+/// Extract the DIMENSION from an HNSW index definition as reported by INFO FOR TABLE.
+pub(crate) fn hnsw_dimension(definition: &str) -> Option<usize> {
+    let rest = definition.split_once("HNSW DIMENSION ")?.1;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// KNN filter selecting the `k` nearest neighbours of `$q`.
+pub(crate) fn knn_clause(k: usize) -> String {
+    let k = k.max(1);
+    format!("embedding <|{},{}|> $q", k, k.max(KNN_EF))
+}
+
+impl SurrealMindServer {
+    /// Whether KNN queries can use the HNSW indexes.
+    pub fn vector_index_ready(&self) -> bool {
+        self.vector_index_ready.load(Ordering::Relaxed)
+    }
+
+    /// Embedding filter for a similarity query returning up to `k` rows: the KNN operator
+    /// when the vector index is ready, otherwise a plain presence check (full scan).
+    pub(crate) fn embedding_filter(&self, k: usize, use_knn: bool) -> String {
+        if use_knn {
+            knn_clause(k)
+        } else {
+            "embedding IS NOT NONE".to_string()
+        }
+    }
+
+    /// Stop using KNN queries after a failure; the next `rebuild_vector_index` re-enables them.
+    pub(crate) fn disable_vector_index(&self, error: &dyn std::fmt::Display) {
+        if self.vector_index_ready.swap(false, Ordering::Relaxed) {
+            tracing::warn!(
+                "vector_index: KNN query failed, falling back to full scan: {}",
+                error
+            );
+        }
+    }
+
+    /// Define any missing HNSW indexes at the active dimension, then refresh readiness.
+    pub async fn ensure_vector_indexes(&self) -> Result<()> {
+        let dim = self.embedder.dimensions();
+        let sql: String = VECTOR_INDEXES
+            .iter()
+            .map(|(table, index)| {
+                format!(
+                    "DEFINE INDEX IF NOT EXISTS {index} ON TABLE {table} FIELDS embedding HNSW DIMENSION {dim} DIST COSINE;\n"
+                )
+            })
+            .collect();
+        self.db.query(sql).await?.check()?;
+        self.refresh_vector_index_state().await?;
+        Ok(())
+    }
+
+    /// Compare existing HNSW index dimensions with the embedder and record readiness.
+    /// Returns per-table status.
+    pub async fn refresh_vector_index_state(&self) -> Result<Vec<serde_json::Value>> {
+        let dim = self.embedder.dimensions();
+        let mut statuses = Vec::with_capacity(VECTOR_INDEXES.len());
+        let mut ready = self.config.system.use_vector_index;
+
+        for (table, index) in VECTOR_INDEXES {
+            let info: Vec<serde_json::Value> = self
+                .db
+                .query(format!("INFO FOR TABLE {}", table))
+                .await?
+                .take(0)?;
+            let index_dim = info
+                .first()
+                .and_then(|v| v.get("indexes"))
+                .and_then(|v| v.get(index))
+                .and_then(|v| v.as_str())
+                .and_then(hnsw_dimension);
+            let status = match index_dim {
+                Some(d) if d == dim => "ok",
+                Some(_) => "dimension_mismatch",
+                None => "missing",
+            };
+            if status != "ok" {
+                ready = false;
+            }
+            statuses.push(json!({
+                "table": table,
+                "index": index,
+                "index_dimension": index_dim,
+                "embedder_dimension": dim,
+                "status": status
+            }));
+        }
+
+        self.vector_index_ready.store(ready, Ordering::Relaxed);
+        tracing::info!(ready, dim, "vector_index.state");
+        Ok(statuses)
+    }
+
+    /// Drop and redefine the HNSW indexes at the active dimension (e.g. after a model change).
+    pub async fn rebuild_vector_indexes(&self) -> Result<Vec<serde_json::Value>> {
+        let dim = self.embedder.dimensions();
+        let sql: String = VECTOR_INDEXES
+            .iter()
+            .map(|(table, index)| {
+                format!(
+                    "REMOVE INDEX IF EXISTS {index} ON TABLE {table};\n\
+                     DEFINE INDEX {index} ON TABLE {table} FIELDS embedding HNSW DIMENSION {dim} DIST COSINE;\n"
+                )
+            })
+            .collect();
+        self.db.query(sql).await?.check()?;
+        self.refresh_vector_index_state().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hnsw_dimension_parses_info_definition() {
+        let def = "DEFINE INDEX thoughts_embedding_idx ON thoughts FIELDS embedding HNSW DIMENSION 1536 DIST COSINE TYPE F32 EFC 150 M 12";
+        assert_eq!(hnsw_dimension(def), Some(1536));
+        assert_eq!(
+            hnsw_dimension("DEFINE INDEX idx_kge_name ON kg_entities FIELDS name"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_knn_clause_uses_minimum_ef() {
+        assert_eq!(knn_clause(10), "embedding <|10,100|> $q");
+        assert_eq!(knn_clause(500), "embedding <|500,500|> $q");
+        assert_eq!(knn_clause(0), "embedding <|1,100|> $q");
+    }
+}
@@ -0,0 +1,160 @@
+//! Startup preloading behind `runtime.warm_start`
+//!
+//! Loads the `cache_warm` most recently accessed thoughts into the LRU cache, then up to
+//! `warm_start_max_rows` kg_entities into the [`super::entity_index`]. Both steps share one
+//! `warm_start_timeout_ms` budget; a step still running when it expires is abandoned and the
+//! server starts with whatever was already loaded.
+
+use crate::server::SurrealMindServer;
+use crate::server::entity_index::{IndexedEntity, LOAD_SQL};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+// Bare ids: the record id renders escaped (`thoughts:`<uuid>``), which no cache lookup matches
+const THOUGHTS_SQL: &str = "SELECT *, meta::id(id) AS id OMIT embedding_v2 FROM thoughts WHERE deleted_at = NONE \
+    ORDER BY last_accessed DESC, created_at DESC LIMIT $limit";
+
+#[derive(Debug, Default, Serialize)]
+pub struct WarmStartStats {
+    pub thoughts: usize,
+    pub entities: usize,
+    pub timed_out: bool,
+    pub elapsed_ms: u64,
+}
+
+impl SurrealMindServer {
+    /// Run the warm-start phase. Failures are logged; the server works without it.
+    pub async fn warm_start(&self) -> WarmStartStats {
+        let runtime = &self.config.runtime;
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(runtime.warm_start_timeout_ms);
+        let mut stats = WarmStartStats::default();
+
+        let limit = runtime
+            .cache_warm
+            .min(self.thoughts.read().await.cap().get());
+        match tokio::time::timeout_at(deadline.into(), self.preload_thoughts(limit)).await {
+            Ok(Ok(n)) => stats.thoughts = n,
+            Ok(Err(e)) => warn!("warm_start: thought preload failed: {}", e),
+            Err(_) => stats.timed_out = true,
+        }
+
+        if !stats.timed_out {
+            let load = self.load_entity_index(runtime.warm_start_max_rows);
+            match tokio::time::timeout_at(deadline.into(), load).await {
+                Ok(Ok(n)) => stats.entities = n,
+                Ok(Err(e)) => warn!("warm_start: entity index load failed: {}", e),
+                Err(_) => stats.timed_out = true,
+            }
+        }
+
+        stats.elapsed_ms = started.elapsed().as_millis() as u64;
+        if stats.timed_out {
+            warn!(
+                "warm_start: stopped after {}ms with {} thoughts and {} entities loaded",
+                stats.elapsed_ms, stats.thoughts, stats.entities
+            );
+        } else {
+            info!(
+                "warm_start: {} thoughts and {} entities loaded in {}ms",
+                stats.thoughts, stats.entities, stats.elapsed_ms
+            );
+        }
+        stats
+    }
+
+    async fn preload_thoughts(&self, limit: usize) -> crate::error::Result<usize> {
+        if limit == 0 {
+            return Ok(0);
+        }
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(THOUGHTS_SQL)
+            .bind(("limit", limit as i64))
+            .await?
+            .take(0)?;
+        let thoughts: Vec<super::Thought> = rows
+            .into_iter()
+            .filter_map(|row| serde_json::from_value(row).ok())
+            .collect();
+        let loaded = thoughts.len();
+        let mut cache = self.thoughts.write().await;
+        // Least recent first, so the most recent end up at the front of the LRU
+        for thought in thoughts.into_iter().rev() {
+            cache.put(thought.id.clone(), thought);
+        }
+        Ok(loaded)
+    }
+
+    async fn load_entity_index(&self, max_rows: usize) -> crate::error::Result<usize> {
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(LOAD_SQL)
+            .bind(("limit", max_rows as i64))
+            .await?
+            .take(0)?;
+        let mut index = self.entity_index.write().await;
+        // Oldest first, so `get` prefers the oldest of identically named entities, like the
+        // default `dedupe_entities` survivor
+        for row in rows.into_iter().rev() {
+            if let Ok(entity) = serde_json::from_value::<IndexedEntity>(row) {
+                index.insert(entity);
+            }
+        }
+        Ok(index.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{mem_server_with, test_config};
+
+    #[tokio::test]
+    async fn test_warm_start_fills_the_thought_cache_and_entity_index() {
+        let mut config = test_config();
+        config.runtime.warm_start = true;
+        let server = mem_server_with(config).await;
+        let thought = server
+            .handle_legacymind_think(crate::test_support::call(
+                "think",
+                serde_json::json!({"content": "Warm me", "injection_scale": 0}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap()["delegated_result"]["thought_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server.thoughts.write().await.clear();
+        server
+            .db
+            .query(
+                "CREATE kg_entities:sam_old SET name = 'Sam', data = {entity_type: 'person'}, \
+                     created_at = time::now() - 2d; \
+                 CREATE kg_entities:sam_new SET name = 'Sam', data = {entity_type: 'person'}, \
+                     created_at = time::now() - 1d; \
+                 CREATE kg_entities:engine SET name = 'Engine', entity_type = 'project', \
+                     created_at = time::now();",
+            )
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let stats = server.warm_start().await;
+        assert!(!stats.timed_out);
+        assert_eq!(stats.entities, 3);
+        assert_eq!(stats.thoughts, 1);
+        assert!(server.cached_thought(&thought).await.is_some());
+
+        let index = server.entity_index.read().await;
+        // The oldest of identically named entities wins
+        assert_eq!(index.get("Sam").map(|e| e.id.as_str()), Some("sam_old"));
+        assert_eq!(
+            index.get("Engine").map(|e| e.entity_type.as_str()),
+            Some("project")
+        );
+    }
+}
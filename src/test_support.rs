@@ -0,0 +1,97 @@
+//! In-memory fixtures for handler tests
+//!
+//! [`mem_server`] builds a [`SurrealMindServer`] on SurrealDB's in-memory engine (`mem://`,
+//! enabled for test builds by the `kv-mem` dev-dependency feature) with the migrations
+//! applied, and a [`HashEmbedder`] in place of the embedding API. Each call gets its own empty
+//! datastore, so tests need no running SurrealDB, no API key, and no cleanup, and they run
+//! by default with `cargo test`.
+//!
+//! New handlers should come with a test in their module's `#[cfg(test)] mod tests` along
+//! these lines:
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn test_handler_round_trip() {
+//!     let server = crate::test_support::mem_server().await;
+//!     let out = server.handle_x(call("x", json!({...}))).await.unwrap();
+//!     ...
+//! }
+//! ```
+//!
+//! `tests/mcp_integration.rs` still covers behavior that needs a real server (WebSocket
+//! reconnects, HNSW index tuning, multi-process access) behind `RUN_DB_TESTS`.
+
+use crate::config::Config;
+pub use crate::embeddings::HashEmbedder;
+use crate::server::SurrealMindServer;
+use crate::server::connection::{DbConnection, DbSettings};
+use rmcp::model::CallToolRequestParams;
+use std::sync::Arc;
+
+/// Dimension of [`HashEmbedder`] vectors in [`test_config`].
+pub const TEST_EMBED_DIM: usize = 64;
+
+/// Defaults with an in-memory database and the hash embedder's provider/model/dimensions.
+pub fn test_config() -> Config {
+    let mut config = Config::default();
+    config.system.database_url = "mem://".to_string();
+    config.system.embedding_provider = "test".to_string();
+    config.system.embedding_model = "hash".to_string();
+    config.system.embedding_dimensions = TEST_EMBED_DIM;
+    config
+}
+
+/// A server on a fresh in-memory database, configured by [`test_config`].
+pub async fn mem_server() -> SurrealMindServer {
+    mem_server_with(test_config()).await
+}
+
+/// A fresh in-memory database for `config`, with no migrations applied.
+pub async fn mem_db(config: &Config) -> DbConnection {
+    let settings = DbSettings {
+        max_attempts: 0,
+        ..DbSettings::from_config(config)
+    };
+    DbConnection::connect(settings, 1)
+        .await
+        .expect("in-memory SurrealDB")
+}
+
+/// A server on a fresh in-memory database with `config` (keep `database_url = "mem://"`).
+pub async fn mem_server_with(config: Config) -> SurrealMindServer {
+    let db = mem_db(&config).await;
+    let embedder = Arc::new(HashEmbedder {
+        dim: config.system.embedding_dimensions,
+    });
+    SurrealMindServer::from_parts(&config, db, embedder, None)
+        .await
+        .expect("server on in-memory SurrealDB")
+}
+
+/// A tool call with `args` (a JSON object).
+pub fn call(name: &str, args: serde_json::Value) -> CallToolRequestParams {
+    CallToolRequestParams {
+        meta: None,
+        name: name.to_string().into(),
+        arguments: Some(args.as_object().cloned().expect("object arguments")),
+        task: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mem_server_applies_migrations() {
+        let server = mem_server().await;
+        let status = crate::server::schema::migration_status(
+            &server.db.client(),
+            crate::server::schema::MIGRATIONS,
+        )
+        .await
+        .unwrap();
+        assert!(status.pending.is_empty(), "{:?}", status.pending);
+        assert_eq!(server.embedder.dimensions(), TEST_EMBED_DIM);
+    }
+}
@@ -2,11 +2,10 @@
 
 use crate::error::{Result, SurrealMindError};
 use crate::server::SurrealMindServer;
+use crate::server::connection::DbConnection;
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde::Deserialize;
 use serde_json::{Value, json};
-use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client as WsClient;
 
 /// Parameters for the agent_job_status tool
 #[derive(Debug, Deserialize)]
@@ -27,6 +26,7 @@ impl SurrealMindServer {
             serde_json::from_value(Value::Object(args)).map_err(|e| {
                 SurrealMindError::InvalidParams {
                     message: format!("Invalid parameters: {}", e),
+                    field: None,
                 }
             })?;
 
@@ -34,6 +34,7 @@ impl SurrealMindServer {
         if job_id.is_empty() {
             return Err(SurrealMindError::InvalidParams {
                 message: "job_id cannot be empty".into(),
+                field: None,
             });
         }
 
@@ -43,7 +44,7 @@ impl SurrealMindServer {
     }
 }
 
-async fn fetch_job_status(db: &Surreal<WsClient>, job_id: String) -> Result<Value> {
+async fn fetch_job_status(db: &DbConnection, job_id: String) -> Result<Value> {
     // Query all job fields that exist in the schema.
     // For exchange_id (Record<agent_exchanges>), use IF THEN ELSE to safely convert to string
     // when the value exists, or return null when it's NONE.
@@ -82,9 +83,9 @@ async fn fetch_job_status(db: &Surreal<WsClient>, job_id: String) -> Result<Valu
         }
     };
 
-    let row = raw_rows.first().ok_or_else(|| SurrealMindError::Mcp {
-        message: format!("Job not found: {}", job_id),
-    })?;
+    let row = raw_rows
+        .first()
+        .ok_or_else(|| SurrealMindError::not_found("job", &job_id))?;
 
     let job_id_val = row
         .get("job_id")
@@ -92,9 +93,7 @@ async fn fetch_job_status(db: &Surreal<WsClient>, job_id: String) -> Result<Valu
         .unwrap_or("")
         .to_string();
     if job_id_val.is_empty() {
-        return Err(SurrealMindError::Mcp {
-            message: format!("Job not found: {}", job_id),
-        });
+        return Err(SurrealMindError::not_found("job", job_id));
     }
 
     let mut metadata: Option<Value> = row.get("metadata").cloned();
@@ -157,7 +156,7 @@ fn metadata_is_empty(metadata: &Option<Value>) -> bool {
 }
 
 async fn fetch_exchange_response(
-    db: &Surreal<WsClient>,
+    db: &DbConnection,
     exchange_id: &str,
 ) -> Result<Option<(Option<String>, Option<Value>)>> {
     let sql = "SELECT response, metadata FROM agent_exchanges WHERE id = type::record($exchange_id) LIMIT 1;";
@@ -59,6 +59,7 @@ impl SurrealMindServer {
         let params: CallCcParams = serde_json::from_value(Value::Object(args)).map_err(|e| {
             SurrealMindError::InvalidParams {
                 message: format!("Invalid parameters: {}", e),
+                field: None,
             }
         })?;
 
@@ -66,6 +67,7 @@ impl SurrealMindServer {
         if prompt.is_empty() {
             return Err(SurrealMindError::InvalidParams {
                 message: "prompt cannot be empty".into(),
+                field: None,
             });
         }
 
@@ -83,6 +85,7 @@ impl SurrealMindServer {
         let cwd_input = normalize_optional_string(params.cwd).ok_or_else(|| {
             SurrealMindError::InvalidParams {
                 message: "cwd is required and cannot be empty".into(),
+                field: None,
             }
         })?;
 
@@ -94,6 +97,7 @@ impl SurrealMindServer {
         if resume_session_id.is_some() && params.continue_latest {
             return Err(SurrealMindError::InvalidParams {
                 message: "resume_session_id and continue_latest cannot both be set".into(),
+                field: None,
             });
         }
 
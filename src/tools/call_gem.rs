@@ -1,6 +1,6 @@
 //! delegate_gemini tool handler to call Gemini CLI - now synchronous
 
-use crate::clients::traits::CognitiveAgent;
+use crate::clients::gemini::{RetryPolicy, call_with_retry};
 use crate::clients::{AgentError, GeminiClient};
 use crate::error::{Result, SurrealMindError};
 use crate::server::SurrealMindServer;
@@ -54,6 +54,7 @@ impl SurrealMindServer {
             serde_json::from_value(Value::Object(args)).map_err(|e| {
                 SurrealMindError::InvalidParams {
                     message: format!("Invalid parameters: {}", e),
+                    field: None,
                 }
             })?;
 
@@ -61,6 +62,7 @@ impl SurrealMindServer {
         if prompt.is_empty() {
             return Err(SurrealMindError::InvalidParams {
                 message: "prompt cannot be empty".into(),
+                field: None,
             });
         }
 
@@ -94,7 +96,7 @@ impl SurrealMindServer {
         });
 
         // Execute synchronously - call GeminiClient directly
-        let result = execute_gemini_call(GeminiCallParams {
+        let (result, attempts) = execute_gemini_call(GeminiCallParams {
             prompt: &prompt,
             model_override: model_override.as_deref(),
             cwd: cwd.as_deref(),
@@ -114,27 +116,43 @@ impl SurrealMindServer {
                     "status": "completed",
                     "session_id": response.session_id,
                     "response": truncated_response,
+                    "attempts": attempts,
                 });
                 if let Some(events) = response.stream_events {
                     result_json["stream_events"] = serde_json::to_value(events).unwrap_or_default();
                 }
                 Ok(CallToolResult::structured(result_json))
             }
-            Err(e) => {
-                let error_msg = match e {
-                    AgentError::Timeout { timeout_ms } => {
-                        format!("Gemini execution timed out after {}ms", timeout_ms)
-                    }
-                    AgentError::CliError(msg) => format!("Gemini CLI error: {}", msg),
-                    AgentError::NotFound => "Gemini CLI not found".to_string(),
-                    AgentError::ParseError(msg) => format!("Parse error: {}", msg),
-                    AgentError::StdinError(msg) => format!("Stdin error: {}", msg),
-                };
-                Err(SurrealMindError::Mcp {
-                    message: format!("Gemini execution failed: {}", error_msg),
-                })
-            }
+            Err(e) => Err(gemini_error(e, attempts)),
+        }
+    }
+}
+
+/// Classify a failed Gemini call: timeouts stay timeouts, everything else means the
+/// provider is unavailable, retryable when the agent error is (rate limits).
+fn gemini_error(e: AgentError, attempts: u32) -> SurrealMindError {
+    let retryable = e.is_retryable();
+    let error_msg = match e {
+        AgentError::Timeout { timeout_ms } => {
+            return SurrealMindError::Timeout {
+                operation: "gemini".to_string(),
+                timeout_ms,
+            };
         }
+        AgentError::CliError(msg) => format!("Gemini CLI error: {}", msg),
+        AgentError::NotFound => "Gemini CLI not found".to_string(),
+        AgentError::ParseError(msg) => format!("Parse error: {}", msg),
+        AgentError::StdinError(msg) => format!("Stdin error: {}", msg),
+        AgentError::RateLimited(msg) => format!("Gemini rate limited: {}", msg),
+        AgentError::InvalidResponse(msg) => format!("Invalid response: {}", msg),
+    };
+    SurrealMindError::ProviderUnavailable {
+        provider: "gemini".to_string(),
+        message: format!(
+            "Gemini execution failed after {} attempt(s): {}",
+            attempts, error_msg
+        ),
+        retryable,
     }
 }
 
@@ -201,7 +219,10 @@ struct GeminiCallParams<'a> {
 
 async fn execute_gemini_call(
     params: GeminiCallParams<'_>,
-) -> std::result::Result<crate::clients::traits::AgentResponse, AgentError> {
+) -> (
+    std::result::Result<crate::clients::traits::AgentResponse, AgentError>,
+    u32,
+) {
     // Determine session to resume:
     // 1. Explicit resume_session_id takes priority
     // 2. continue_latest means use --resume without ID (CLI auto-selects latest)
@@ -233,7 +254,36 @@ async fn execute_gemini_call(
 
     // Pass session_id to GeminiClient
     // Empty string triggers --resume (latest), non-empty triggers --resume <id>
-    gemini.call(params.prompt, resume_session.as_deref()).await
+    // Transient failures retry against the same session.
+    call_with_retry(
+        &gemini,
+        params.prompt,
+        resume_session.as_deref(),
+        &RetryPolicy::from_env(),
+    )
+    .await
 }
 
-// Tests for synchronous call_gem would go here if needed
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gemini_errors_map_to_timeout_and_provider_classes() {
+        let err = rmcp::ErrorData::from(gemini_error(AgentError::Timeout { timeout_ms: 5_000 }, 3));
+        let data = err.data.unwrap();
+        assert_eq!(data["class"], "timeout");
+        assert_eq!(data["timeout_ms"], 5_000);
+        assert_eq!(data["retryable"], true);
+
+        let err = rmcp::ErrorData::from(gemini_error(AgentError::RateLimited("429".into()), 3));
+        let data = err.data.unwrap();
+        assert_eq!(data["class"], "provider_unavailable");
+        assert_eq!(data["provider"], "gemini");
+        assert_eq!(data["retryable"], true);
+        assert!(err.message.contains("3 attempt(s)"), "{}", err.message);
+
+        let err = rmcp::ErrorData::from(gemini_error(AgentError::NotFound, 1));
+        assert_eq!(err.data.unwrap()["retryable"], false);
+    }
+}
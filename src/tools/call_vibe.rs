@@ -42,6 +42,7 @@ impl SurrealMindServer {
         let params: CallVibeParams = serde_json::from_value(Value::Object(args)).map_err(|e| {
             SurrealMindError::InvalidParams {
                 message: format!("Invalid parameters: {}", e),
+                field: None,
             }
         })?;
 
@@ -49,6 +50,7 @@ impl SurrealMindServer {
         if prompt.is_empty() {
             return Err(SurrealMindError::InvalidParams {
                 message: "prompt cannot be empty".into(),
+                field: None,
             });
         }
 
@@ -56,6 +58,7 @@ impl SurrealMindServer {
         if cwd_input.is_empty() {
             return Err(SurrealMindError::InvalidParams {
                 message: "cwd is required and cannot be empty".into(),
+                field: None,
             });
         }
         let cwd =
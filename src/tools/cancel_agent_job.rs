@@ -3,11 +3,10 @@
 use crate::error::{Result, SurrealMindError};
 use crate::registry;
 use crate::server::SurrealMindServer;
+use crate::server::connection::DbConnection;
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde::Deserialize;
 use serde_json::{Value, json};
-use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client as WsClient;
 
 /// Parameters for the cancel_agent_job tool
 #[derive(Debug, Deserialize)]
@@ -30,6 +29,7 @@ impl SurrealMindServer {
             serde_json::from_value(Value::Object(args)).map_err(|e| {
                 SurrealMindError::InvalidParams {
                     message: format!("Invalid parameters: {}", e),
+                    field: None,
                 }
             })?;
 
@@ -37,6 +37,7 @@ impl SurrealMindServer {
         if job_id.is_empty() {
             return Err(SurrealMindError::InvalidParams {
                 message: "job_id cannot be empty".into(),
+                field: None,
             });
         }
 
@@ -46,15 +47,15 @@ impl SurrealMindServer {
     }
 }
 
-async fn cancel_job(db: &Surreal<WsClient>, job_id: String) -> Result<Value> {
+async fn cancel_job(db: &DbConnection, job_id: String) -> Result<Value> {
     // First check current status
     let check_sql = "SELECT status FROM agent_jobs WHERE job_id = $job_id LIMIT 1;";
     let mut response = db.query(check_sql).bind(("job_id", job_id.clone())).await?;
     let rows: Vec<serde_json::Value> = response.take(0)?;
 
-    let current_row = rows.first().ok_or_else(|| SurrealMindError::Mcp {
-        message: format!("Job not found: {}", job_id),
-    })?;
+    let current_row = rows
+        .first()
+        .ok_or_else(|| SurrealMindError::not_found("job", &job_id))?;
     let current_status = current_row
         .get("status")
         .and_then(|v| v.as_str())
@@ -63,8 +64,9 @@ async fn cancel_job(db: &Surreal<WsClient>, job_id: String) -> Result<Value> {
 
     // Don't cancel already completed/failed jobs
     if current_status == "completed" || current_status == "failed" {
-        return Err(SurrealMindError::InvalidParams {
+        return Err(SurrealMindError::Conflict {
             message: format!("Cannot cancel job in '{}' status", current_status),
+            data: Some(json!({ "job_id": job_id, "status": current_status })),
         });
     }
     if current_status == "cancelled" {
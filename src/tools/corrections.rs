@@ -29,6 +29,7 @@ impl SurrealMindServer {
         let params: CorrectionsParams = serde_json::from_value(serde_json::Value::Object(args))
             .map_err(|e| SurrealMindError::InvalidParams {
                 message: format!("Invalid parameters: {}", e),
+                field: None,
             })?;
 
         let mut q = String::from(
@@ -0,0 +1,249 @@
+//! embedding_probe tool: cosine similarity of two texts, or of a text and a stored record
+//!
+//! For debugging retrieval ranking. `text_a` is embedded with the server's embedder and
+//! compared against `text_b`, or against a `thoughts`/`kg_entities`/`kg_observations` record
+//! given by `table` + `id`. A record's stored embedding is reused when its dimension matches
+//! the embedder; otherwise the record's text is re-embedded. Inputs share `think`'s
+//! `MAX_CONTENT_SIZE` cap.
+
+use crate::embeddings::Embedder;
+use crate::error::{Result, SurrealMindError};
+use crate::maintenance::embedding_migration::{MIGRATION_TABLES, embedding_text};
+use crate::server::SurrealMindServer;
+use crate::tools::thinking::MAX_CONTENT_SIZE;
+use crate::utils::math::cosine_similarity;
+use rmcp::model::{CallToolRequestParams, CallToolResult};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// Leading vector components returned for each side.
+pub const PREVIEW_COMPONENTS: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingProbeParams {
+    pub text_a: String,
+    #[serde(default)]
+    pub text_b: Option<String>,
+    /// Record table for the second side: thoughts, kg_entities, or kg_observations
+    #[serde(default)]
+    pub table: Option<String>,
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+/// The second side of a probe.
+#[derive(Debug, PartialEq)]
+pub enum ProbeTarget {
+    Text(String),
+    /// A stored record's embedding (if any) and the text it was built from
+    Record {
+        embedding: Option<Vec<f32>>,
+        text: String,
+    },
+}
+
+/// Similarity of `text_a` and `target`, embedding only what has no usable stored vector.
+pub async fn probe(embedder: &dyn Embedder, text_a: &str, target: ProbeTarget) -> Result<Value> {
+    let a = embedder.embed(text_a).await?;
+    let (b, source) = match target {
+        ProbeTarget::Text(text) => (embedder.embed(&text).await?, "embedded"),
+        ProbeTarget::Record {
+            embedding: Some(stored),
+            ..
+        } if stored.len() == embedder.dimensions() => (stored, "stored"),
+        ProbeTarget::Record { text, .. } => {
+            if text.trim().is_empty() {
+                return Err(SurrealMindError::Validation {
+                    message: "Record has no usable embedding and no text to embed".into(),
+                });
+            }
+            (embedder.embed(&text).await?, "reembedded")
+        }
+    };
+    let preview = |v: &[f32]| {
+        v.iter()
+            .take(PREVIEW_COMPONENTS)
+            .copied()
+            .collect::<Vec<_>>()
+    };
+    Ok(json!({
+        "similarity": cosine_similarity(&a, &b),
+        "dimensions": embedder.dimensions(),
+        "a": {"dimensions": a.len(), "head": preview(&a)},
+        "b": {"dimensions": b.len(), "head": preview(&b), "source": source},
+    }))
+}
+
+fn check_size(field: &str, text: &str) -> Result<()> {
+    if text.len() > MAX_CONTENT_SIZE {
+        return Err(SurrealMindError::Validation {
+            message: format!(
+                "{} exceeds maximum size of {}KB",
+                field,
+                MAX_CONTENT_SIZE / 1024
+            ),
+        });
+    }
+    Ok(())
+}
+
+impl SurrealMindServer {
+    pub async fn handle_embedding_probe(
+        &self,
+        request: CallToolRequestParams,
+    ) -> Result<CallToolResult> {
+        let args = request.arguments.ok_or_else(|| SurrealMindError::Mcp {
+            message: "Missing parameters".into(),
+        })?;
+        let params: EmbeddingProbeParams =
+            serde_json::from_value(Value::Object(args)).map_err(|e| {
+                SurrealMindError::InvalidParams {
+                    message: format!("Invalid parameters: {}", e),
+                    field: None,
+                }
+            })?;
+        check_size("text_a", &params.text_a)?;
+
+        let target = match (params.text_b, params.table, params.id) {
+            (Some(text_b), None, None) => {
+                check_size("text_b", &text_b)?;
+                ProbeTarget::Text(text_b)
+            }
+            (None, Some(table), Some(id)) => self.probe_record(&table, &id).await?,
+            _ => {
+                return Err(SurrealMindError::InvalidParams {
+                    message: "Provide either text_b or table + id".into(),
+                    field: None,
+                });
+            }
+        };
+        let result = probe(self.embedder.as_ref(), &params.text_a, target).await?;
+        Ok(CallToolResult::structured(result))
+    }
+
+    /// Stored embedding and text of a record, accepting `table:id` or bare ids.
+    async fn probe_record(&self, table: &str, id: &str) -> Result<ProbeTarget> {
+        if !MIGRATION_TABLES.contains(&table) {
+            return Err(SurrealMindError::InvalidParams {
+                message: format!("table must be one of: {}", MIGRATION_TABLES.join(", ")),
+                field: None,
+            });
+        }
+        let id = id.strip_prefix(&format!("{}:", table)).unwrap_or(id);
+        let rows: Vec<Value> = self
+            .db
+            .query("SELECT embedding, content, name, data FROM type::record($tb, $id)")
+            .bind(("tb", table.to_string()))
+            .bind(("id", id.to_string()))
+            .await?
+            .take(0)?;
+        let row = rows
+            .into_iter()
+            .next()
+            .ok_or_else(|| SurrealMindError::not_found(table, id))?;
+        let embedding = row.get("embedding").and_then(|v| {
+            v.as_array()?
+                .iter()
+                .map(|x| x.as_f64().map(|f| f as f32))
+                .collect::<Option<Vec<f32>>>()
+        });
+        Ok(ProbeTarget::Record {
+            embedding,
+            text: embedding_text(table, &row),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Maps known texts to fixed vectors and records every text it embeds.
+    struct MockEmbedder {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl MockEmbedder {
+        fn new() -> Self {
+            Self {
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Embedder for MockEmbedder {
+        async fn embed(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+            self.calls.lock().unwrap().push(text.to_string());
+            Ok(match text {
+                "rust" => vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                "cargo" => vec![1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                _ => vec![0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            })
+        }
+
+        fn dimensions(&self) -> usize {
+            10
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_embeds_both_texts() {
+        let embedder = MockEmbedder::new();
+        let result = probe(&embedder, "rust", ProbeTarget::Text("cargo".into()))
+            .await
+            .unwrap();
+        let similarity = result["similarity"].as_f64().unwrap();
+        assert!((similarity - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert_eq!(result["dimensions"], json!(10));
+        assert_eq!(
+            result["a"]["head"].as_array().unwrap().len(),
+            PREVIEW_COMPONENTS
+        );
+        assert_eq!(result["b"]["source"], json!("embedded"));
+        assert_eq!(*embedder.calls.lock().unwrap(), vec!["rust", "cargo"]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_reuses_stored_embedding_of_matching_dimension() {
+        let embedder = MockEmbedder::new();
+        let mut stored = vec![0.0; 10];
+        stored[0] = 1.0;
+        let target = ProbeTarget::Record {
+            embedding: Some(stored),
+            text: "cargo".into(),
+        };
+        let result = probe(&embedder, "rust", target).await.unwrap();
+        assert_eq!(result["b"]["source"], json!("stored"));
+        assert!((result["similarity"].as_f64().unwrap() - 1.0).abs() < 1e-6);
+        assert_eq!(*embedder.calls.lock().unwrap(), vec!["rust"]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_reembeds_record_with_other_dimension() {
+        let embedder = MockEmbedder::new();
+        let target = ProbeTarget::Record {
+            embedding: Some(vec![1.0; 4]),
+            text: "cargo".into(),
+        };
+        let result = probe(&embedder, "rust", target).await.unwrap();
+        assert_eq!(result["b"]["source"], json!("reembedded"));
+        assert_eq!(result["b"]["dimensions"], json!(10));
+        assert_eq!(*embedder.calls.lock().unwrap(), vec!["rust", "cargo"]);
+
+        let empty = ProbeTarget::Record {
+            embedding: None,
+            text: " ".into(),
+        };
+        assert!(probe(&embedder, "rust", empty).await.is_err());
+    }
+
+    #[test]
+    fn test_check_size_caps_inputs() {
+        assert!(check_size("text_a", "short").is_ok());
+        let huge = "x".repeat(MAX_CONTENT_SIZE + 1);
+        assert!(check_size("text_a", &huge).is_err());
+    }
+}
@@ -0,0 +1,508 @@
+//! Entity timeline for `search entity_timeline`
+//!
+//! Given an entity id or name, returns what happened to it in time order: its creation,
+//! edges added to or from it, observations citing it (journal `data.thread_id` or
+//! `data.source`), and thoughts mentioning it. Mentions come from `injected_memories` and
+//! from a similarity scan of thoughts against the entity embedding, capped at
+//! `max_mentions` rows above `mention_threshold` (defaults from `[kg]`).
+//!
+//! Pages run forward in time: `cursor` is the `ts` of the last event on the previous page
+//! and the next page starts strictly after it. Everything after the entity lookup is one
+//! batched query, each statement bounded by the page size.
+
+use crate::error::{Result, SurrealMindError};
+use crate::maintenance::privacy::privacy_filter;
+use crate::server::SurrealMindServer;
+use chrono::{DateTime, FixedOffset};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use surrealdb::types::{RecordId, Value as DbValue};
+
+/// Default and maximum number of events on a timeline page.
+pub const DEFAULT_TIMELINE_MAX_EVENTS: usize = 50;
+pub const MAX_TIMELINE_EVENTS: usize = 500;
+
+/// Characters of content kept in each event summary.
+const SUMMARY_CHARS: usize = 160;
+
+const ENTITY_SQL: &str = "SELECT meta::id(id) AS id, name, data.entity_type AS entity_type, \
+    type::string(created_at) AS ts, embedding, embedding_model FROM kg_entities \
+    WHERE meta::id(id) = $val OR name = $val LIMIT 1";
+
+#[derive(Debug, Deserialize)]
+struct EntityRow {
+    id: String,
+    name: String,
+    #[serde(default)]
+    entity_type: Option<String>,
+    #[serde(default)]
+    ts: Option<String>,
+    #[serde(default)]
+    embedding: Option<Vec<f32>>,
+    #[serde(default)]
+    embedding_model: Option<String>,
+}
+
+/// Timeline page options; `None` falls back to the `[kg]` defaults.
+#[derive(Debug, Clone, Default)]
+pub struct TimelineOptions {
+    pub max_events: Option<usize>,
+    pub max_mentions: Option<usize>,
+    pub mention_threshold: Option<f32>,
+    pub cursor: Option<String>,
+    pub include_private: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TimelineEvent {
+    at: DateTime<FixedOffset>,
+    kind: &'static str,
+    ref_id: String,
+    summary: String,
+    extra: Value,
+}
+
+impl TimelineEvent {
+    fn json(&self, ts: &str) -> Value {
+        let mut v = json!({
+            "ts": ts,
+            "kind": self.kind,
+            "ref_id": self.ref_id,
+            "summary": self.summary,
+        });
+        if let (Some(out), Some(extra)) = (v.as_object_mut(), self.extra.as_object()) {
+            out.extend(extra.clone());
+        }
+        v
+    }
+}
+
+fn parse_ts(ts: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(ts).ok()
+}
+
+fn truncate(text: &str) -> String {
+    let mut out: String = text.chars().take(SUMMARY_CHARS).collect();
+    if text.chars().count() > SUMMARY_CHARS {
+        out.push('…');
+    }
+    out
+}
+
+fn str_field<'a>(row: &'a Value, key: &str) -> &'a str {
+    row.get(key).and_then(Value::as_str).unwrap_or("")
+}
+
+/// Build events from the batched rows; mentions found both ways are merged with `via`
+/// listing each source.
+fn collect_events(
+    entity: &EntityRow,
+    include_created: bool,
+    edges: &[Value],
+    observations: &[Value],
+    injected: &[Value],
+    similar: &[Value],
+) -> Vec<TimelineEvent> {
+    let mut events = Vec::new();
+    if include_created && let Some(at) = entity.ts.as_deref().and_then(parse_ts) {
+        let summary = match entity.entity_type.as_deref() {
+            Some(t) if !t.is_empty() => format!("Created {} '{}'", t, entity.name),
+            _ => format!("Created '{}'", entity.name),
+        };
+        events.push(TimelineEvent {
+            at,
+            kind: "created",
+            ref_id: format!("kg_entities:{}", entity.id),
+            summary,
+            extra: json!({}),
+        });
+    }
+    for row in edges {
+        let Some(at) = parse_ts(str_field(row, "ts")) else {
+            continue;
+        };
+        let outgoing = row.get("outgoing").and_then(Value::as_bool).unwrap_or(true);
+        let other = match str_field(row, "other") {
+            "" => "?",
+            name => name,
+        };
+        let rel_type = str_field(row, "rel_type");
+        let summary = if outgoing {
+            format!("{} -[{}]-> {}", entity.name, rel_type, other)
+        } else {
+            format!("{} -[{}]-> {}", other, rel_type, entity.name)
+        };
+        events.push(TimelineEvent {
+            at,
+            kind: "edge_added",
+            ref_id: format!("kg_edges:{}", str_field(row, "id")),
+            summary,
+            extra: json!({
+                "rel_type": rel_type,
+                "direction": if outgoing { "out" } else { "in" },
+            }),
+        });
+    }
+    for row in observations {
+        let Some(at) = parse_ts(str_field(row, "ts")) else {
+            continue;
+        };
+        let content = str_field(row, "content");
+        let summary = if content.is_empty() {
+            truncate(str_field(row, "name"))
+        } else {
+            truncate(content)
+        };
+        events.push(TimelineEvent {
+            at,
+            kind: "observation",
+            ref_id: format!("kg_observations:{}", str_field(row, "id")),
+            summary,
+            extra: json!({}),
+        });
+    }
+
+    let mut mentions: BTreeMap<String, TimelineEvent> = BTreeMap::new();
+    for (via, rows) in [("injected", injected), ("similarity", similar)] {
+        for row in rows {
+            let Some(at) = parse_ts(str_field(row, "ts")) else {
+                continue;
+            };
+            let id = str_field(row, "id").to_string();
+            let event = mentions.entry(id.clone()).or_insert_with(|| TimelineEvent {
+                at,
+                kind: "mention",
+                ref_id: format!("thoughts:{}", id),
+                summary: truncate(str_field(row, "content")),
+                extra: json!({"via": []}),
+            });
+            if let Some(list) = event.extra["via"].as_array_mut() {
+                list.push(json!(via));
+            }
+            if let Some(sim) = row.get("similarity").and_then(Value::as_f64) {
+                event.extra["similarity"] = json!(sim);
+            }
+        }
+    }
+    events.extend(mentions.into_values());
+    events
+}
+
+/// Sort events by time (ties by kind, then ref_id) and cut the page at `max_events`,
+/// returning the events and whether more remain.
+fn paginate(mut events: Vec<TimelineEvent>, max_events: usize) -> (Vec<TimelineEvent>, bool) {
+    events.sort_by(|a, b| {
+        a.at.cmp(&b.at)
+            .then_with(|| a.kind.cmp(b.kind))
+            .then_with(|| a.ref_id.cmp(&b.ref_id))
+    });
+    let more = events.len() > max_events;
+    events.truncate(max_events);
+    (events, more)
+}
+
+impl SurrealMindServer {
+    /// Chronological events for the entity with id or name `entity`.
+    pub(crate) async fn entity_timeline(
+        &self,
+        entity: &str,
+        opts: TimelineOptions,
+    ) -> Result<Value> {
+        let kg = &self.config.kg;
+        let max_events = opts
+            .max_events
+            .unwrap_or(DEFAULT_TIMELINE_MAX_EVENTS)
+            .clamp(1, MAX_TIMELINE_EVENTS);
+        let max_mentions = opts
+            .max_mentions
+            .unwrap_or(kg.timeline_max_mentions)
+            .min(MAX_TIMELINE_EVENTS);
+        let threshold = opts
+            .mention_threshold
+            .unwrap_or(kg.timeline_mention_threshold)
+            .clamp(0.0, 1.0);
+        let after = match opts.cursor.as_deref() {
+            Some(c) => Some(parse_ts(c).ok_or_else(|| SurrealMindError::Validation {
+                message: format!("cursor must be an RFC 3339 timestamp, got '{}'", c),
+            })?),
+            None => None,
+        };
+
+        let key = entity.strip_prefix("kg_entities:").unwrap_or(entity);
+        let key = key
+            .strip_prefix('⟨')
+            .and_then(|k| k.strip_suffix('⟩'))
+            .unwrap_or(key);
+        let rows: Vec<Value> = self
+            .db
+            .query(ENTITY_SQL)
+            .bind(("val", key.to_string()))
+            .await?
+            .take(0)?;
+        let Some(row) = rows.into_iter().next() else {
+            return Err(SurrealMindError::not_found("entity", entity));
+        };
+        let entity: EntityRow = serde_json::from_value(row)?;
+
+        // Each statement returns at most a page plus one, so the merged page is exact
+        let after_clause = if after.is_some() {
+            " AND created_at > <datetime>$after"
+        } else {
+            ""
+        };
+        let privacy = privacy_filter(opts.include_private);
+        let scan = match entity.embedding.as_ref() {
+            Some(emb) if !emb.is_empty() && max_mentions > 0 => Some(emb.clone()),
+            _ => None,
+        };
+        let mut sql = format!(
+            "SELECT meta::id(id) AS id, rel_type, created_at, type::string(created_at) AS ts, \
+                 source = $rec AS outgoing, \
+                 (IF source = $rec THEN target.name ELSE source.name END) AS other \
+             FROM kg_edges WHERE (source = $rec OR target = $rec){after_clause} \
+             ORDER BY created_at ASC LIMIT $lim;\n\
+             SELECT meta::id(id) AS id, name, data.content AS content, created_at, \
+                 type::string(created_at) AS ts FROM kg_observations \
+             WHERE (type::string(data.thread_id) = $rec_str OR data.source IN [$name, $id, $rec_str]){after_clause} \
+             ORDER BY created_at ASC LIMIT $lim;\n\
+             SELECT meta::id(id) AS id, content, created_at, type::string(created_at) AS ts FROM thoughts \
+             WHERE ($id IN injected_memories OR $rec_str IN injected_memories) \
+                 AND deleted_at = NONE AND {privacy}{after_clause} \
+             ORDER BY created_at ASC LIMIT $lim;\n"
+        );
+        if scan.is_some() {
+            let model_clause = if entity.embedding_model.is_some() {
+                " AND embedding_model = $model"
+            } else {
+                ""
+            };
+            sql.push_str(&format!(
+                "SELECT meta::id(id) AS id, content, type::string(created_at) AS ts, \
+                     vector::similarity::cosine(embedding, $emb) AS similarity FROM thoughts \
+                 WHERE embedding_dim = $dim{model_clause} AND deleted_at = NONE AND {privacy}{after_clause} \
+                     AND vector::similarity::cosine(embedding, $emb) >= $thresh \
+                 ORDER BY similarity DESC LIMIT $max_mentions;"
+            ));
+        }
+        let dim = scan.as_ref().map_or(0, Vec::len);
+        let mut resp = self
+            .db
+            .query(sql)
+            .bind((
+                "rec",
+                DbValue::RecordId(RecordId::new("kg_entities", entity.id.as_str())),
+            ))
+            .bind(("rec_str", format!("kg_entities:{}", entity.id)))
+            .bind(("id", entity.id.clone()))
+            .bind(("name", entity.name.clone()))
+            .bind(("after", after.map(|a| a.to_rfc3339())))
+            .bind(("lim", (max_events + 1) as i64))
+            .bind(("emb", scan.unwrap_or_default()))
+            .bind(("dim", dim as i64))
+            .bind(("model", entity.embedding_model.clone()))
+            .bind(("thresh", threshold as f64))
+            .bind(("max_mentions", max_mentions as i64))
+            .await?;
+        let edges: Vec<Value> = resp.take(0)?;
+        let observations: Vec<Value> = resp.take(1)?;
+        let injected: Vec<Value> = resp.take(2)?;
+        let similar: Vec<Value> = if dim > 0 { resp.take(3)? } else { Vec::new() };
+
+        let include_created = match (after, entity.ts.as_deref().and_then(parse_ts)) {
+            (Some(after), Some(created)) => created > after,
+            _ => true,
+        };
+        let similarity_mentions = similar.len();
+        let events = collect_events(
+            &entity,
+            include_created,
+            &edges,
+            &observations,
+            &injected,
+            &similar,
+        );
+        let (events, more) = paginate(events, max_events);
+        let events: Vec<Value> = events
+            .iter()
+            .map(|e| e.json(&e.at.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)))
+            .collect();
+        let next_cursor = if more {
+            events.last().map(|e| e["ts"].clone())
+        } else {
+            None
+        };
+
+        Ok(json!({
+            "entity": {
+                "id": entity.id,
+                "name": entity.name,
+                "entity_type": entity.entity_type,
+            },
+            "count": events.len(),
+            "events": events,
+            "next_cursor": next_cursor,
+            "mention_scan": {
+                "ran": dim > 0,
+                "threshold": threshold,
+                "max_mentions": max_mentions,
+                "matched": similarity_mentions,
+            },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_EMBED_DIM, mem_server};
+
+    fn ids(timeline: &Value) -> Vec<(String, String)> {
+        timeline["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| {
+                (
+                    e["kind"].as_str().unwrap().to_string(),
+                    e["ref_id"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_entity_timeline_orders_events_and_pages() {
+        let server = mem_server().await;
+        let mut emb = vec![0.0f32; TEST_EMBED_DIM];
+        emb[0] = 1.0;
+        let mut other = vec![0.0f32; TEST_EMBED_DIM];
+        other[1] = 1.0;
+        server
+            .db
+            .query(
+                "CREATE kg_entities:sm SET name = 'surreal-mind', data = {entity_type: 'project'}, \
+                     created_at = d'2026-01-01T00:00:00Z', embedding = $emb, embedding_model = 'hash';
+                 CREATE kg_entities:rust SET name = 'Rust', data = {entity_type: 'tool'}, \
+                     created_at = d'2025-06-01T00:00:00Z';
+                 CREATE kg_edges:e1 SET source = kg_entities:sm, target = kg_entities:rust, \
+                     rel_type = 'written_in', created_at = d'2026-02-01T00:00:00Z';
+                 CREATE kg_edges:e2 SET source = kg_entities:rust, target = kg_entities:sm, \
+                     rel_type = 'powers', created_at = d'2026-05-01T00:00:00Z';
+                 CREATE kg_observations:o1 SET name = 'Journal entry', data = {thread_id: 'kg_entities:sm', content: 'Picked SurrealDB 3'}, \
+                     created_at = d'2026-03-01T00:00:00Z';
+                 CREATE kg_observations:o2 SET name = 'Unrelated', data = {source: 'Rust'}, \
+                     created_at = d'2026-03-02T00:00:00Z';
+                 CREATE thoughts:t1 SET injection_scale = 0, significance = 0.5, access_count = 0, content = 'Injected mention', injected_memories = ['sm'], \
+                     created_at = d'2026-04-01T00:00:00Z', embedding = $other, embedding_dim = $dim, embedding_model = 'hash';
+                 CREATE thoughts:t2 SET injection_scale = 0, significance = 0.5, access_count = 0, content = 'Similar mention', injected_memories = [], \
+                     created_at = d'2026-02-15T00:00:00Z', embedding = $emb, embedding_dim = $dim, embedding_model = 'hash';
+                 CREATE thoughts:t3 SET injection_scale = 0, significance = 0.5, access_count = 0, content = 'Private mention', injected_memories = ['kg_entities:sm'], is_private = true, \
+                     created_at = d'2026-04-02T00:00:00Z';",
+            )
+            .bind(("emb", emb))
+            .bind(("other", other))
+            .bind(("dim", TEST_EMBED_DIM as i64))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let full = server
+            .entity_timeline("surreal-mind", TimelineOptions::default())
+            .await
+            .unwrap();
+        let expected: Vec<(String, String)> = [
+            ("created", "kg_entities:sm"),
+            ("edge_added", "kg_edges:e1"),
+            ("mention", "thoughts:t2"),
+            ("observation", "kg_observations:o1"),
+            ("mention", "thoughts:t1"),
+            ("edge_added", "kg_edges:e2"),
+        ]
+        .iter()
+        .map(|(k, r)| (k.to_string(), r.to_string()))
+        .collect();
+        assert_eq!(ids(&full), expected);
+        assert_eq!(
+            full["events"][1]["summary"],
+            "surreal-mind -[written_in]-> Rust"
+        );
+        assert_eq!(full["events"][5]["direction"], "in");
+        assert_eq!(full["events"][2]["via"], json!(["similarity"]));
+        assert_eq!(full["events"][4]["via"], json!(["injected"]));
+        assert_eq!(full["next_cursor"], Value::Null);
+
+        // Two pages of three, joined by the cursor
+        let first = server
+            .entity_timeline(
+                "kg_entities:sm",
+                TimelineOptions {
+                    max_events: Some(3),
+                    ..TimelineOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+        let cursor = first["next_cursor"].as_str().unwrap().to_string();
+        assert_eq!(cursor, "2026-02-15T00:00:00Z");
+        let second = server
+            .entity_timeline(
+                "sm",
+                TimelineOptions {
+                    max_events: Some(3),
+                    cursor: Some(cursor),
+                    ..TimelineOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+        let paged: Vec<_> = ids(&first).into_iter().chain(ids(&second)).collect();
+        assert_eq!(paged, expected);
+        assert_eq!(second["next_cursor"], Value::Null);
+
+        // Private thoughts join with include_private; the scan can be switched off
+        let private = server
+            .entity_timeline(
+                "sm",
+                TimelineOptions {
+                    include_private: true,
+                    max_mentions: Some(0),
+                    ..TimelineOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+        let kinds = ids(&private);
+        assert!(kinds.contains(&("mention".into(), "thoughts:t3".into())));
+        assert!(!kinds.contains(&("mention".into(), "thoughts:t2".into())));
+        assert_eq!(private["mention_scan"]["ran"], false);
+    }
+
+    #[tokio::test]
+    async fn test_entity_timeline_rejects_unknown_entity_and_bad_cursor() {
+        let server = mem_server().await;
+        assert!(
+            server
+                .entity_timeline("nobody", TimelineOptions::default())
+                .await
+                .is_err()
+        );
+        server
+            .db
+            .query("CREATE kg_entities:x SET name = 'x', created_at = time::now()")
+            .await
+            .unwrap();
+        let err = server
+            .entity_timeline(
+                "x",
+                TimelineOptions {
+                    cursor: Some("yesterday".into()),
+                    ..TimelineOptions::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("cursor"), "{err}");
+    }
+}
@@ -0,0 +1,567 @@
+//! export tool: page kg_entities, kg_observations and kg_edges out to a JSONL or GraphML file
+
+use crate::error::{Result, SurrealMindError};
+use crate::server::SurrealMindServer;
+use crate::server::connection::DbConnection;
+use rmcp::model::{CallToolRequestParams, CallToolResult};
+use serde_json::{Map, Value, json};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+pub const MAX_PAGE_SIZE: usize = 5000;
+
+/// (kind, table) pairs in file order; nodes are written before the edges that reference them.
+pub const EXPORT_TABLES: [(&str, &str); 3] = [
+    ("entity", "kg_entities"),
+    ("observation", "kg_observations"),
+    ("relationship", "kg_edges"),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Jsonl,
+    Graphml,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ExportParams {
+    pub path: String,
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub include_embeddings: bool,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ExportCounts {
+    pub entities: usize,
+    pub observations: usize,
+    pub relationships: usize,
+}
+
+impl ExportCounts {
+    fn bump(&mut self, kind: &str) {
+        match kind {
+            "entity" => self.entities += 1,
+            "observation" => self.observations += 1,
+            _ => self.relationships += 1,
+        }
+    }
+}
+
+/// Paged SELECT for one table; binds `$limit` and `$start`.
+///
+/// Ids (and edge endpoints) are selected through `meta::id`/`meta::tb` into `export_*` helper
+/// columns so the output never depends on how record ids are rendered. Legacy string
+/// endpoints are left as they are.
+pub fn page_sql(table: &str, include_embeddings: bool) -> String {
+    let endpoints = if table == "kg_edges" {
+        ", (IF type::is_record(source) THEN meta::tb(source) END) AS export_source_tb, \
+         (IF type::is_record(source) THEN meta::id(source) END) AS export_source_id, \
+         (IF type::is_record(target) THEN meta::tb(target) END) AS export_target_tb, \
+         (IF type::is_record(target) THEN meta::id(target) END) AS export_target_id"
+    } else {
+        ""
+    };
+    let omit = if include_embeddings {
+        ""
+    } else {
+        " OMIT embedding"
+    };
+    format!(
+        "SELECT *, meta::id(id) AS export_id{}{} FROM {} ORDER BY id LIMIT $limit START $start",
+        endpoints, omit, table
+    )
+}
+
+/// Turn a row from [`page_sql`] into one export record:
+/// `{"kind", "id", "record"}` with edge endpoints as `table:id` strings.
+pub fn export_record(kind: &str, mut row: Map<String, Value>) -> Value {
+    let id = row
+        .remove("export_id")
+        .map(|v| {
+            v.as_str()
+                .map(String::from)
+                .unwrap_or_else(|| v.to_string())
+        })
+        .unwrap_or_default();
+    row.remove("id");
+    for end in ["source", "target"] {
+        let tb = row.remove(&format!("export_{}_tb", end));
+        let key = row.remove(&format!("export_{}_id", end));
+        if let (Some(Value::String(tb)), Some(Value::String(key))) = (tb, key) {
+            row.insert(end.to_string(), Value::String(format!("{}:{}", tb, key)));
+        }
+    }
+    json!({"kind": kind, "id": id, "record": row})
+}
+
+/// Absolute target path whose parent directory already exists.
+pub fn validate_export_path(path: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(SurrealMindError::Validation {
+            message: format!("path must be absolute: {}", path.display()),
+        });
+    }
+    if path.file_name().is_none() || path.is_dir() {
+        return Err(SurrealMindError::Validation {
+            message: format!("path must name a file: {}", path.display()),
+        });
+    }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok(path),
+        _ => Err(SurrealMindError::Validation {
+            message: format!("parent directory does not exist: {}", path.display()),
+        }),
+    }
+}
+
+/// Sibling temp file (`<name>.tmp`) that is renamed over the target once complete.
+pub fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Escape text for XML attributes and character data, dropping characters XML 1.0 forbids.
+pub fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+const GRAPHML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">
+  <key id="kind" for="node" attr.name="kind" attr.type="string"/>
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <key id="entity_type" for="node" attr.name="entity_type" attr.type="string"/>
+  <key id="rel_type" for="edge" attr.name="rel_type" attr.type="string"/>
+  <key id="created_at" for="all" attr.name="created_at" attr.type="string"/>
+  <key id="data" for="all" attr.name="data" attr.type="string"/>
+  <graph id="kg" edgedefault="directed">
+"#;
+
+const GRAPHML_FOOTER: &str = "  </graph>\n</graphml>\n";
+
+/// Streams export records to `out` in the chosen format.
+pub struct ExportWriter<W: Write> {
+    out: W,
+    format: ExportFormat,
+}
+
+impl<W: Write> ExportWriter<W> {
+    pub fn begin(mut out: W, format: ExportFormat) -> std::io::Result<Self> {
+        if format == ExportFormat::Graphml {
+            out.write_all(GRAPHML_HEADER.as_bytes())?;
+        }
+        Ok(Self { out, format })
+    }
+
+    pub fn write(&mut self, record: &Value) -> std::io::Result<()> {
+        match self.format {
+            ExportFormat::Jsonl => {
+                serde_json::to_writer(&mut self.out, record)?;
+                self.out.write_all(b"\n")
+            }
+            ExportFormat::Graphml => self.write_graphml(record),
+        }
+    }
+
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if self.format == ExportFormat::Graphml {
+            self.out.write_all(GRAPHML_FOOTER.as_bytes())?;
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn write_graphml(&mut self, record: &Value) -> std::io::Result<()> {
+        let kind = record["kind"].as_str().unwrap_or("entity");
+        let id = record["id"].as_str().unwrap_or_default();
+        let body = &record["record"];
+        let out = &mut self.out;
+        if kind == "relationship" {
+            let (Some(source), Some(target)) = (body["source"].as_str(), body["target"].as_str())
+            else {
+                return Ok(());
+            };
+            writeln!(
+                out,
+                r#"    <edge id="kg_edges:{}" source="{}" target="{}">"#,
+                xml_escape(id),
+                xml_escape(source),
+                xml_escape(target)
+            )?;
+            write_data(out, "rel_type", &body["rel_type"])?;
+            write_data(out, "created_at", &body["created_at"])?;
+            write_data(out, "data", &body["data"])?;
+            writeln!(out, "    </edge>")
+        } else {
+            let table = if kind == "observation" {
+                "kg_observations"
+            } else {
+                "kg_entities"
+            };
+            writeln!(out, r#"    <node id="{}:{}">"#, table, xml_escape(id))?;
+            write_data(out, "kind", &Value::String(kind.to_string()))?;
+            write_data(out, "label", &body["name"])?;
+            let entity_type = match &body["entity_type"] {
+                Value::Null => &body["data"]["entity_type"],
+                v => v,
+            };
+            write_data(out, "entity_type", entity_type)?;
+            write_data(out, "created_at", &body["created_at"])?;
+            write_data(out, "data", &body["data"])?;
+            writeln!(out, "    </node>")
+        }
+    }
+}
+
+/// One `<data>` element; null values are omitted and non-strings are written as JSON.
+fn write_data<W: Write>(out: &mut W, key: &str, value: &Value) -> std::io::Result<()> {
+    let text = match value {
+        Value::Null => return Ok(()),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    };
+    writeln!(
+        out,
+        r#"      <data key="{}">{}</data>"#,
+        key,
+        xml_escape(&text)
+    )
+}
+
+fn io_error(e: std::io::Error) -> SurrealMindError {
+    SurrealMindError::Internal {
+        message: format!("Failed to write export: {}", e),
+    }
+}
+
+/// What [`export_graph`] wrote.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub format: ExportFormat,
+    /// Whether embeddings were written; GraphML never carries them
+    pub include_embeddings: bool,
+    pub counts: ExportCounts,
+    pub bytes: u64,
+}
+
+/// Export the knowledge graph to `params.path`, writing a temp file first and renaming it
+/// into place so a failed export never leaves a partial file behind.
+pub async fn export_graph(db: &DbConnection, params: &ExportParams) -> Result<ExportSummary> {
+    let path = validate_export_path(&params.path)?;
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let tmp = temp_path(&path);
+
+    let counts = match export_to_file(
+        db,
+        &tmp,
+        params.format,
+        params.include_embeddings,
+        page_size,
+    )
+    .await
+    {
+        Ok(counts) => counts,
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            return Err(e);
+        }
+    };
+    if let Err(e) = fs::rename(&tmp, &path) {
+        let _ = fs::remove_file(&tmp);
+        return Err(io_error(e));
+    }
+    let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    Ok(ExportSummary {
+        path: path.display().to_string(),
+        format: params.format,
+        include_embeddings: params.include_embeddings && params.format == ExportFormat::Jsonl,
+        counts,
+        bytes,
+    })
+}
+
+/// Page every export table into `tmp`. GraphML never carries embeddings.
+async fn export_to_file(
+    db: &DbConnection,
+    tmp: &Path,
+    format: ExportFormat,
+    include_embeddings: bool,
+    page_size: usize,
+) -> Result<ExportCounts> {
+    let include_embeddings = include_embeddings && format == ExportFormat::Jsonl;
+    let file = File::create(tmp).map_err(io_error)?;
+    let mut writer = ExportWriter::begin(BufWriter::new(file), format).map_err(io_error)?;
+    let mut counts = ExportCounts::default();
+
+    for (kind, table) in EXPORT_TABLES {
+        let sql = page_sql(table, include_embeddings);
+        let mut start = 0usize;
+        loop {
+            let rows: Vec<Value> = db
+                .query(sql.as_str())
+                .bind(("limit", page_size as i64))
+                .bind(("start", start as i64))
+                .await?
+                .take(0)?;
+            let fetched = rows.len();
+            for row in rows {
+                if let Value::Object(row) = row {
+                    writer.write(&export_record(kind, row)).map_err(io_error)?;
+                    counts.bump(kind);
+                }
+            }
+            if fetched < page_size {
+                break;
+            }
+            start += fetched;
+        }
+    }
+
+    writer.finish().map_err(io_error)?;
+    Ok(counts)
+}
+
+impl SurrealMindServer {
+    /// Handle the export tool call
+    pub async fn handle_export(&self, request: CallToolRequestParams) -> Result<CallToolResult> {
+        let args = request.arguments.ok_or_else(|| SurrealMindError::Mcp {
+            message: "Missing parameters".into(),
+        })?;
+        let params: ExportParams = serde_json::from_value(Value::Object(args)).map_err(|e| {
+            SurrealMindError::InvalidParams {
+                message: format!("Invalid parameters: {}", e),
+                field: None,
+            }
+        })?;
+
+        let summary = export_graph(&self.db, &params).await?;
+        Ok(CallToolResult::structured(serde_json::to_value(summary)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(v: Value) -> Map<String, Value> {
+        v.as_object().unwrap().clone()
+    }
+
+    fn mini_graph() -> Vec<Value> {
+        vec![
+            export_record(
+                "entity",
+                row(
+                    json!({"id": "kg_entities:a", "export_id": "a", "name": "Ada <Lovelace>", "entity_type": "person", "created_at": "2026-01-01T00:00:00Z", "data": {"note": "first & foremost"}, "embedding": [0.1, 0.2]}),
+                ),
+            ),
+            export_record(
+                "entity",
+                row(
+                    json!({"id": "kg_entities:b", "export_id": "b", "name": "Engine", "data": {"entity_type": "machine"}}),
+                ),
+            ),
+            export_record(
+                "observation",
+                row(json!({"export_id": "o", "name": "Wrote \"notes\"", "data": {}})),
+            ),
+            export_record(
+                "relationship",
+                row(
+                    json!({"id": "kg_edges:e", "export_id": "e", "source": "kg_entities:a", "target": "kg_entities:b",
+                    "export_source_tb": "kg_entities", "export_source_id": "a",
+                    "export_target_tb": "kg_entities", "export_target_id": "b", "rel_type": "designed"}),
+                ),
+            ),
+        ]
+    }
+
+    fn render(format: ExportFormat) -> String {
+        let mut writer = ExportWriter::begin(Vec::new(), format).unwrap();
+        for record in mini_graph() {
+            writer.write(&record).unwrap();
+        }
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    /// Minimal well-formedness check: balanced tags and only known entities.
+    /// Returns (element name, attributes) for every start tag.
+    fn parse_xml(doc: &str) -> Vec<(String, Vec<(String, String)>)> {
+        let mut stack: Vec<String> = Vec::new();
+        let mut elements = Vec::new();
+        let mut rest = doc.trim_start();
+        if let Some(decl) = rest.strip_prefix("<?xml") {
+            rest = &decl[decl.find("?>").expect("unterminated declaration") + 2..];
+        }
+        while let Some(open) = rest.find('<') {
+            check_text(&rest[..open]);
+            let close = rest[open..].find('>').expect("unterminated tag") + open;
+            let tag = &rest[open + 1..close];
+            rest = &rest[close + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(
+                    stack.pop().as_deref(),
+                    Some(name.trim()),
+                    "mismatched close"
+                );
+                if stack.is_empty() {
+                    assert!(rest.trim().is_empty(), "content after root element");
+                }
+                continue;
+            }
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, mut attrs_src) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            let mut attrs = Vec::new();
+            while let Some(eq) = attrs_src.find('=') {
+                let key = attrs_src[..eq].trim().to_string();
+                let after = attrs_src[eq + 1..].trim_start();
+                assert!(after.starts_with('"'), "unquoted attribute {}", key);
+                let end = after[1..].find('"').expect("unterminated attribute") + 1;
+                let value = &after[1..end];
+                assert!(!value.contains('<'), "raw '<' in attribute");
+                check_text(value);
+                attrs.push((key, value.to_string()));
+                attrs_src = &after[end + 1..];
+            }
+            assert!(attrs_src.trim().is_empty(), "trailing junk in tag {}", name);
+            elements.push((name.to_string(), attrs));
+            if !self_closing {
+                stack.push(name.to_string());
+            }
+        }
+        assert!(stack.is_empty(), "unclosed elements: {:?}", stack);
+        elements
+    }
+
+    fn check_text(text: &str) {
+        let mut parts = text.split('&');
+        parts.next();
+        for part in parts {
+            let entity = &part[..part.find(';').expect("bare '&'")];
+            assert!(
+                ["amp", "lt", "gt", "quot", "apos"].contains(&entity),
+                "unknown entity {}",
+                entity
+            );
+        }
+    }
+
+    #[test]
+    fn test_page_sql_omits_embeddings_and_flattens_edge_endpoints() {
+        let sql = page_sql("kg_entities", false);
+        assert!(sql.contains("OMIT embedding"));
+        assert!(sql.ends_with("ORDER BY id LIMIT $limit START $start"));
+        assert!(!page_sql("kg_entities", true).contains("OMIT"));
+        assert!(page_sql("kg_edges", false).contains("meta::id(target) END) AS export_target_id"));
+    }
+
+    #[test]
+    fn test_export_record_normalizes_ids() {
+        let graph = mini_graph();
+        assert_eq!(graph[0]["id"], json!("a"));
+        assert!(graph[0]["record"].get("id").is_none());
+        assert_eq!(graph[3]["record"]["source"], json!("kg_entities:a"));
+        assert!(graph[3]["record"].get("export_source_tb").is_none());
+    }
+
+    #[test]
+    fn test_jsonl_writes_one_record_per_line() {
+        let out = render(ExportFormat::Jsonl);
+        let lines: Vec<Value> = out
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        let kinds: Vec<_> = lines.iter().map(|l| l["kind"].clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                json!("entity"),
+                json!("entity"),
+                json!("observation"),
+                json!("relationship")
+            ]
+        );
+        assert_eq!(lines[1]["record"]["name"], json!("Engine"));
+    }
+
+    #[test]
+    fn test_graphml_parses_with_nodes_and_edges() {
+        let out = render(ExportFormat::Graphml);
+        let elements = parse_xml(&out);
+        assert_eq!(elements[0].0, "graphml");
+        let nodes: Vec<_> = elements
+            .iter()
+            .filter(|(n, _)| n == "node")
+            .map(|(_, a)| a[0].1.clone())
+            .collect();
+        assert_eq!(
+            nodes,
+            vec!["kg_entities:a", "kg_entities:b", "kg_observations:o"]
+        );
+        let edges: Vec<_> = elements.iter().filter(|(n, _)| n == "edge").collect();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(
+            edges[0].1,
+            vec![
+                ("id".to_string(), "kg_edges:e".to_string()),
+                ("source".to_string(), "kg_entities:a".to_string()),
+                ("target".to_string(), "kg_entities:b".to_string()),
+            ]
+        );
+        assert!(out.contains(r#"<data key="label">Ada &lt;Lovelace&gt;</data>"#));
+        assert!(out.contains(r#"<data key="entity_type">machine</data>"#));
+        assert!(out.contains(r#"<data key="rel_type">designed</data>"#));
+        assert!(!out.contains("0.1"), "embeddings never reach GraphML");
+    }
+
+    #[test]
+    fn test_xml_escape_drops_forbidden_control_chars() {
+        assert_eq!(xml_escape("a\u{1}b\tc'"), "ab\tc&apos;");
+    }
+
+    #[test]
+    fn test_validate_export_path_requires_absolute_existing_parent() {
+        assert!(validate_export_path("relative/out.jsonl").is_err());
+        assert!(validate_export_path("/definitely/not/here/out.jsonl").is_err());
+        let dir = tempfile::tempdir().unwrap();
+        assert!(validate_export_path(dir.path().to_str().unwrap()).is_err());
+        let target = dir.path().join("kg.graphml");
+        assert_eq!(
+            validate_export_path(target.to_str().unwrap()).unwrap(),
+            target
+        );
+        assert_eq!(temp_path(&target), dir.path().join("kg.graphml.tmp"));
+    }
+}
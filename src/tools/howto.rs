@@ -1,12 +1,24 @@
-//! detailed_help tool handler to provide structured help for tools
+//! howto tool handler and the help registry behind it
+//!
+//! Each tool has one [`ToolHelp`] entry holding what its input schema cannot say: a
+//! description, a note per parameter, the shape of its result, and example calls.
+//! [`ToolHelp::render`] joins the entry with the live input schema from
+//! [`tool_definitions`](crate::server::router::tool_definitions), so parameter names, types,
+//! and required flags always match what `list_tools` advertises. The tests fail when a listed
+//! tool has no entry, when notes and schema properties disagree, or when an example does not
+//! validate against the schema.
 
 use crate::error::{Result, SurrealMindError};
 use crate::server::SurrealMindServer;
-use rmcp::model::{CallToolRequestParams, CallToolResult};
-use serde_json::json;
+use rmcp::model::{CallToolRequestParams, CallToolResult, Tool};
+use serde_json::{Map, Value, json};
+
+/// Notes for the overrides of `server::tenants::TENANT_TOOLS`
+const NAMESPACE_NOTE: &str = "string — run against this namespace instead of the configured one (database defaults to the configured one); the pair must be listed in [multi_tenant] allowed";
+const DATABASE_NOTE: &str = "string — run against this database instead of the configured one (namespace defaults to the configured one); the pair must be listed in [multi_tenant] allowed";
 
 impl SurrealMindServer {
-    /// Handle the detailed_help tool call
+    /// Handle the howto tool call
     pub async fn handle_howto(&self, request: CallToolRequestParams) -> Result<CallToolResult> {
         let args = request.arguments.unwrap_or_default();
 
@@ -16,351 +28,941 @@ impl SurrealMindServer {
             .get("format")
             .and_then(|v| v.as_str())
             .unwrap_or("full");
+        let section = match args.get("section").and_then(|v| v.as_str()) {
+            None => HelpSection::All,
+            Some(s) => HelpSection::parse(s).ok_or_else(|| SurrealMindError::InvalidParams {
+                message: format!(
+                    "section must be 'params', 'examples', or 'all', got '{}'",
+                    s
+                ),
+                field: None,
+            })?,
+        };
 
-        let tool = match maybe_tool {
-            None => {
-                // Canonical tools roster (wrapped in object for rmcp 0.11.0 schema validation)
-                let tools = vec![
-                    json!({"name": "think", "one_liner": "Unified thinking tool with automatic mode routing via triggers/heurs", "key_params": ["content", "hint", "injection_scale", "tags", "significance"]}),
-                    json!({"name": "remember", "one_liner": "Create entities/relationships/observations in the KG", "key_params": ["kind", "data", "confidence", "source_thought_id"]}),
-                    json!({"name": "search", "one_liner": "Unified LM search: memories (default) + optional thoughts", "key_params": ["query", "target", "include_thoughts", "top_k_memories", "top_k_thoughts"]}),
-                    json!({"name": "maintain", "one_liner": "Archival, export, re-embed checks and housekeeping", "key_params": ["subcommand", "limit", "dry_run", "output_dir"]}),
-                    json!({"name": "call_gem", "one_liner": "Delegate a prompt to the Gemini CLI agent", "key_params": ["prompt", "model", "cwd", "mode"]}),
-                    json!({"name": "call_cc", "one_liner": "Delegate a prompt to the Claude Code CLI agent", "key_params": ["prompt", "model", "cwd", "mode"]}),
-                    json!({"name": "call_vibe", "one_liner": "Delegate a prompt to the Vibe CLI agent", "key_params": ["prompt", "cwd", "agent", "mode"]}),
-                    json!({"name": "call_status", "one_liner": "Get status of an async agent job", "key_params": ["job_id"]}),
-                    json!({"name": "call_jobs", "one_liner": "List async agent jobs", "key_params": ["limit", "status_filter", "tool_name"]}),
-                    json!({"name": "call_cancel", "one_liner": "Cancel a running or queued job", "key_params": ["job_id"]}),
-                    json!({"name": "howto", "one_liner": "Get help for a specific tool or list all tools", "key_params": ["tool", "format"]}),
-                    json!({"name": "wander", "one_liner": "Explore the knowledge graph for curiosity-driven discovery", "key_params": ["mode", "current_thought_id", "visited_ids", "recency_bias", "for"]}),
-                    json!({"name": "rethink", "one_liner": "Revise or mark knowledge graph items for correction", "key_params": ["target_id", "mode", "mark_type", "marked_for"]}),
-                    json!({"name": "journal", "one_liner": "Research thread management — create threads, add entries, view dashboard", "key_params": ["mode", "thread", "content", "observation_type", "author"]}),
-                    json!({"name": "corrections", "one_liner": "List recent correction events to inspect the learning journey", "key_params": ["target_id", "limit"]}),
-                ];
-                return Ok(CallToolResult::structured(json!({ "tools": tools })));
-            }
-            Some(t) => t,
+        let Some(tool) = maybe_tool else {
+            // Wrapped in an object for rmcp 0.11.0 schema validation
+            let tools: Vec<_> = registry()
+                .iter()
+                .map(|h| json!({"name": h.name, "one_liner": h.one_liner, "key_params": h.key_params}))
+                .collect();
+            return Ok(CallToolResult::structured(json!({ "tools": tools })));
         };
 
-        let help = match tool {
-            "think" => json!({
-                "name": "think",
-                "description": "Unified thinking tool that routes to appropriate mode. Persists thoughts with optional memory injection. (Framework enhancement currently disabled).",
-                "arguments": {
-                    "content": "string (required) — the thought text",
-                    "hint": "string — optional explicit mode ('debug', 'build', 'plan', 'stuck', 'question', 'conclude')",
-                    "injection_scale": "integer|string (0-3) — memory injection level (overrides mode default)",
-                    "tags": "string[] — optional tags",
-                    "significance": "number|string (0.0-1.0) — importance (overrides mode default)",
-                    "verbose_analysis": "boolean — (unused) previously for verbose framework output",
-                    "session_id": "string — optional session identifier",
-                    "chain_id": "string — optional chain identifier",
-                    "previous_thought_id": "string — optional reference to previous thought",
-                    "revises_thought": "string — optional reference to thought being revised",
-                    "branch_from": "string — optional reference to thought being branched from",
-                    "confidence": "number (0.0-1.0) — optional confidence level",
-                    "hypothesis": "string — optional hypothesis to verify against KG evidence",
-                    "needs_verification": "boolean — set true to run hypothesis verification (only when hypothesis provided)",
-                    "verify_top_k": "integer (1-500) — candidate pool size for KG search (default 100)",
-                    "min_similarity": "number (0.0-1.0) — minimum similarity threshold (default 0.70)",
-                    "evidence_limit": "integer (1-25) — max evidence items per bucket (default 10)",
-                    "contradiction_patterns": "string[] — optional custom patterns for contradiction detection"
-                },
-                "returns": {
-                    "thought_id": "string — the ID of the created thought",
-                    "memories_injected": "integer — count of memories injected",
-                    "embedding_dim": "integer — dimension of the generated embedding",
-                    "embedding_model": "string — model used for embedding",
-                    "continuity": {
-                        "session_id": "string? — resolved session identifier",
-                        "chain_id": "string? — resolved chain identifier",
-                        "previous_thought_id": "string? — resolved previous thought reference",
-                        "revises_thought": "string? — resolved thought being revised",
-                        "branch_from": "string? — resolved branch reference",
-                        "confidence": "number? — clamped confidence value",
-                        "links_resolved": "object? — details on how links were resolved"
-                    },
-                    "verification": "object? — hypothesis verification result"
-                },
-                 "routing": {
-                     "triggers": {
-                         "debug": "debug time",
-                         "build": "building time",
-                         "plan": "plan/planning time",
-                         "stuck": "i'm stuck / stuck",
-                         "question": "question time",
-                         "conclude": "wrap up / conclude"
-                     },
-                     "heuristics": {
-                         "debug": ["error", "bug", "stack trace", "failed", "exception", "panic"],
-                         "build": ["implement", "create", "add function", "build", "scaffold", "wire"],
-                         "plan": ["architecture", "design", "approach", "how should", "strategy", "trade-off"],
-                         "stuck": ["stuck", "unsure", "confused", "not sure", "blocked"]
-                     }
-                 }
-            }),
-            "search" => json!({
-                "name": "search",
-                "description": "Unified search in LegacyMind: searches memories by default and, when include_thoughts=true, also searches thoughts. Supports continuity field filters for thoughts and forensic mode for provenance tracking.",
-                "arguments": {
-                    "query": "object — {name?, text?, id?} query parameters",
-                    "target": "'entity'|'relationship'|'observation'|'mixed' (default 'mixed')",
-                    "include_thoughts": "boolean (default false) — also search thoughts",
-                    "thoughts_content": "string — optional explicit query text for thoughts",
-                    "top_k_memories": "integer (1-50; default 10)",
-                    "top_k_thoughts": "integer (1-50; default 5)",
-                    "sim_thresh": "number (0.0-1.0) — similarity floor for thoughts",
-                    "session_id": "string? — filter thoughts by session_id",
-                    "chain_id": "string? — filter thoughts by chain_id",
-                    "previous_thought_id": "string? — filter thoughts by previous_thought_id (record or string)",
-                    "revises_thought": "string? — filter thoughts by revises_thought (record or string)",
-                    "branch_from": "string? — filter thoughts by branch_from (record or string)",
-                    "origin": "string? — filter thoughts by origin",
-                    "confidence_gte": "number? (0.0-1.0) — filter thoughts with confidence >= value",
-                    "confidence_lte": "number? (0.0-1.0) — filter thoughts with confidence <= value",
-                    "date_from": "string? (YYYY-MM-DD) — filter thoughts created_at >= date",
-                    "date_to": "string? (YYYY-MM-DD) — filter thoughts created_at <= date",
-                    "order": "string? ('created_at_asc'|'created_at_desc') — order thoughts by created_at",
-                    "forensic": "boolean — include correction chain and derivatives in results"
-                },
-                "returns": {"memories": {"items": "array"}, "thoughts": {"total": "number", "results": "array"}},
-                "examples": [
-                    {"description": "Search entities with forensic provenance", "call": {"query": {"name": "REMini"}, "target": "entity", "forensic": true}},
-                    {"description": "Search thoughts in a specific session", "call": {"include_thoughts": true, "session_id": "session_123"}}
-                ]
-            }),
-            "wander" => json!({
-                "name": "wander",
-                "description": "Interactively explore the knowledge graph via traversals. Can wander randomly, semantically, or via metadata and attention marks.",
-                "arguments": {
-                    "mode": "string (required) — 'random', 'semantic', 'meta', 'marks'",
-                    "current_thought_id": "string — optional starting thought ID",
-                    "visited_ids": "array — IDs to avoid preventing loops",
-                    "recency_bias": "boolean (default false) — prioritize recent memories",
-                    "for": "string — filter marks assigned to a specific member ('cc', 'codex', 'sam', 'gemini', 'dt', 'gem')"
-                },
-                "returns": {
-                    "current_node": "object — the node reached in the step",
-                    "mode_used": "string — the mode used for the step",
-                    "affordances": "array — suggested next modes",
-                    "guidance": "string — actionable architectural guidance",
-                    "queue_depth": "integer? — remaining items in queue (marks mode only)"
-                },
-                "examples": [
-                    {"description": "Surface marks for CC", "call": {"mode": "marks", "for": "cc"}},
-                    {"description": "Wander semantically from a specific thought", "call": {"mode": "semantic", "current_thought_id": "thoughts:abc"}}
-                ]
-            }),
-            "rethink" => json!({
-                "name": "rethink",
-                "description": "Revise or mark knowledge graph items for correction. Supports provenance-tracked corrections and attention routing.",
-                "arguments": {
-                    "target_id": "string (required) — ID of the record (thoughts:xxx, entity:xxx, observation:xxx)",
-                    "mode": "string (required) — 'mark' (flag for review) or 'correct' (apply fix)",
-                    "mark_type": "string — 'correction', 'research', 'enrich', 'expand' (mark mode)",
-                    "marked_for": "string — 'cc', 'codex', 'sam', 'gemini', 'dt', 'gem' (mark mode)",
-                    "note": "string — contextual explanation for the mark (mark mode)",
-                    "reasoning": "string — why the record is being corrected (correct mode)",
-                    "sources": "string[] — verification sources (correct mode)",
-                    "cascade": "boolean (default false) — flag derivatives for review (correct mode)"
-                },
-                "returns": {
-                    "success": "boolean",
-                    "marked": "object? — details of the created mark",
-                    "correction": "object? — details of the applied correction event",
-                    "derivatives_flagged": "integer? — count of cascaded marks"
+        let help = lookup(tool);
+        let definition = crate::server::router::tool_definitions()
+            .into_iter()
+            .find(|t| t.name == tool);
+        let (Some(help), Some(definition)) = (help, definition) else {
+            return Err(SurrealMindError::Validation {
+                message: format!("Unknown tool: {}", tool),
+            });
+        };
+
+        let output = if format == "compact" {
+            // Provide a concise one-paragraph summary
+            json!({
+                "tool": tool,
+                "summary": help.description,
+                "arguments": help.params
+            })
+        } else {
+            help.render(&definition, section)
+        };
+
+        Ok(CallToolResult::structured(output))
+    }
+}
+
+/// Which part of a tool's help `howto` returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpSection {
+    Params,
+    Examples,
+    All,
+}
+
+impl HelpSection {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "params" => Some(Self::Params),
+            "examples" => Some(Self::Examples),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
+pub struct ToolHelp {
+    pub name: &'static str,
+    /// Roster line shown when `howto` is called without `tool`
+    pub one_liner: &'static str,
+    pub key_params: &'static [&'static str],
+    pub description: &'static str,
+    /// Note per input schema property; `parent[].field` keys describe array item fields
+    pub params: Value,
+    pub returns: Value,
+    /// `[{description, call}]`, where `call` is a complete argument object
+    pub examples: Value,
+    /// Further top-level sections returned as-is
+    pub extra: Option<Value>,
+}
+
+impl ToolHelp {
+    /// One object per input schema property: its schema, whether it is required, and the note
+    /// kept here, plus the notes for its item fields.
+    pub fn params_with_schema(&self, schema: &Map<String, Value>) -> Value {
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let notes = self.params.as_object().cloned().unwrap_or_default();
+        let mut params = Map::new();
+        for (name, property) in schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let mut param = json!({
+                "schema": property,
+                "required": required.contains(&name.as_str()),
+                "note": notes.get(name).cloned().unwrap_or(Value::Null),
+            });
+            let prefix = format!("{}[].", name);
+            let fields: Map<String, Value> = notes
+                .iter()
+                .filter_map(|(key, note)| {
+                    key.strip_prefix(&prefix)
+                        .map(|field| (field.to_string(), note.clone()))
+                })
+                .collect();
+            if !fields.is_empty() {
+                param["fields"] = Value::Object(fields);
+            }
+            params.insert(name.clone(), param);
+        }
+        Value::Object(params)
+    }
+
+    /// The help object for `section`, with parameters taken from `tool`'s input schema.
+    pub fn render(&self, tool: &Tool, section: HelpSection) -> Value {
+        let mut help = json!({"name": self.name});
+        if section != HelpSection::Examples {
+            help["params"] = self.params_with_schema(&tool.input_schema);
+        }
+        if section != HelpSection::Params {
+            help["examples"] = self.examples.clone();
+        }
+        if section == HelpSection::All {
+            help["description"] = json!(self.description);
+            help["returns"] = self.returns.clone();
+            if let Some(Value::Object(extra)) = &self.extra {
+                for (key, value) in extra {
+                    help[key.as_str()] = value.clone();
                 }
+            }
+        }
+        help
+    }
+}
+
+/// Names of every tool with a help entry, in roster order
+pub fn tool_names() -> Vec<&'static str> {
+    registry().iter().map(|h| h.name).collect()
+}
+
+pub fn lookup(tool: &str) -> Option<ToolHelp> {
+    registry().into_iter().find(|h| h.name == tool)
+}
+
+pub fn registry() -> Vec<ToolHelp> {
+    vec![
+        ToolHelp {
+            name: "think",
+            one_liner: "Unified thinking tool with automatic mode routing via triggers/heurs",
+            key_params: &["content", "hint", "injection_scale", "tags", "significance"],
+            description: "Unified thinking tool that routes to appropriate mode. Persists thoughts with optional memory injection and mode-weighted cognitive framework analysis (disable with SURR_THINK_ENHANCE=0).",
+            params: json!({
+                "content": "string (required) — the thought text",
+                "hint": "string — optional explicit mode ('debug', 'build', 'plan', 'stuck', 'question', 'conclude')",
+                "injection_scale": "integer|string (0-3) — memory injection level (overrides mode default)",
+                "tags": "string[] — optional tags",
+                "significance": "number|string (0.0-1.0) — importance (overrides mode default)",
+                "verbose_analysis": "boolean — include framework insights/questions/next_steps in the result",
+                "session_id": "string — optional session identifier; when omitted, the thought joins the session of the latest thought with the same origin if that is at most SURR_THINK_SESSION_GAP_MINUTES old (default 45; 0 turns inference off), otherwise a new session id is generated",
+                "chain_id": "string — optional chain identifier",
+                "previous_thought_id": "string — optional reference to previous thought; with SURR_THINK_AUTO_LINK_PREVIOUS=true and an inferred session, defaults to that session's latest thought",
+                "revises_thought": "string — optional reference to thought being revised",
+                "branch_from": "string — optional reference to thought being branched from",
+                "confidence": "number (0.0-1.0) — optional confidence level",
+                "hypothesis": "string — optional hypothesis to verify against KG evidence",
+                "needs_verification": "boolean — set true to run hypothesis verification (only when hypothesis provided)",
+                "verify_top_k": "integer (1-500) — candidate pool size for KG search (default 100)",
+                "min_similarity": "number (0.0-1.0) — minimum similarity threshold (default 0.70)",
+                "evidence_limit": "integer (1-25) — max evidence items per bucket (default 10)",
+                "contradiction_patterns": "string[] — extra phrases (whole-word) that mark evidence as contradicting, on top of built-ins like 'does not'/'fails to'",
+                "scoring": "string — 'simple' (default): supporting / (supporting + contradicting); 'weighted': each item votes by how far its similarity is above min_similarity, with one neutral prior vote, so thin or weak evidence stays near 0.5",
+                "idempotency_key": "string — optional retry key; a repeated call with the same key returns the original thought_id with deduplicated=true instead of creating a new one",
+                "suggest_tags": "boolean — return suggested_tags from the KG (default on; SURR_THINK_SUGGEST_TAGS=0 turns the default off)",
+                "is_private": "boolean (default false) — hide the thought from search, chain summaries, wander, and duplicate detection unless a caller passes include_private; maintain set_privacy changes it later",
+                "pinned": "boolean (default false) — inject this thought into later thinks regardless of similarity, ahead of KG matches (up to retrieval.max_pinned, default 3). Untagged pins apply everywhere; tagged pins only to thoughts sharing a tag. maintain pin_thoughts/unpin_thoughts change it later",
+                "response_detail": "string — 'full' (default; SURR_THINK_RESPONSE_DETAIL changes it), 'compact', or 'ids_only'",
+                "references": "array — up to 20 {kind: 'file'|'url'|'commit', value, note?}; file paths must be absolute, URLs must parse, commits are 7-40 hex digits",
+                "namespace": NAMESPACE_NOTE,
+                "database": DATABASE_NOTE
             }),
-            "corrections" => json!({
-                "name": "corrections",
-                "description": "List recent correction events to inspect the learning journey of the KG.",
-                "arguments": {
-                    "target_id": "string — optional filter for a specific target ID",
-                    "limit": "integer (default 10) — max events to return"
+            returns: json!({
+                "thought_id": "string — the ID of the created thought",
+                "deduplicated": "boolean? — true when idempotency_key matched an existing thought (no injection or verification rerun)",
+                "possible_duplicates": "array? — [{id, score, preview}] existing thoughts scoring at or above SURR_DUPLICATE_THRESHOLD (default 0.95); the best match is stored as duplicate_of_hint. Omitted when none",
+                "suggested_tags": "string[]? — names of up to 5 similar KG entities scoring at or above SURR_THINK_SUGGEST_TAGS_THRESHOLD (default 0.6) that are not already tags. Not stored. Omitted when none",
+                "memories_injected": "integer — count of memories injected, pinned thoughts included",
+                "pinned_memories": "string[] — injected pinned thoughts as 'thoughts:<id>'; also stored on the thought as pinned_memories",
+                "redactions": "integer — secrets replaced with «redacted:<kind>» before storing ([security.redaction])",
+                "embedding_dim": "integer — dimension of the generated embedding",
+                "embedding_model": "string — model used for embedding",
+                "continuity": {
+                    "session_id": "string? — resolved session identifier",
+                    "chain_id": "string? — resolved chain identifier",
+                    "previous_thought_id": "string? — resolved previous thought reference",
+                    "revises_thought": "string? — resolved thought being revised",
+                    "branch_from": "string? — resolved branch reference",
+                    "confidence": "number? — clamped confidence value",
+                    "links_resolved": "object? — details on how links were resolved"
                 },
-                "returns": {
-                    "success": "boolean",
-                    "count": "integer",
-                    "events": "array of correction_event objects"
-                }
+                "links.session": "object? — set when session_id was inferred: {source: 'inherited'|'new', session_id, gap_minutes?, after_thought?, auto_linked_previous?}",
+                "verification": "object? — hypothesis verification result: {hypothesis, hypothesis_hash, supporting, contradicting, confidence_score, calibration: {scoring, min_similarity, supporting_count, contradicting_count, supporting_mean_similarity?, supporting_max_similarity?, contradicting_mean_similarity?, contradicting_max_similarity?}, suggested_revision, telemetry}. With SURR_PERSIST_VERIFICATION each run is also appended to hypothesis_history (see maintain hypothesis_trend)",
+                "compact": "object (response_detail='compact') — {thought_id, mode_selected, memories_injected, links: 'session_id=…, chain_id=…' or 'none', verification?: {confidence_score, supporting, contradicting}}",
+                "ids_only": "object (response_detail='ids_only') — {thought_id}"
             }),
-            "journal" => json!({
-                "name": "journal",
-                "description": "Research thread management tool. Create and track research threads as KG entities, add journal entries as observations. A looking glass over the existing KG — no new tables.",
-                "arguments": {
-                    "mode": "string (required) — 'write', 'read', 'threads', 'status'",
-                    "thread": "string — thread name or kg_entities ID (required for write/read/status). Use ID when names are duplicated.",
-                    "content": "string — journal entry content (required for write)",
-                    "observation_type": "string — 'question', 'hypothesis', 'evidence', 'reflection', 'dead_end', 'follow_up' (required for write)",
-                    "author": "string — 'cc', 'codex', 'gem', 'vibe', 'dt' (default 'cc')",
-                    "tags": "string[] — optional tags (write mode)",
-                    "confidence": "number (0.0-1.0) — optional confidence (write mode)",
-                    "thread_status": "string — 'open', 'pursuing', 'resolved', 'abandoned' (required for status mode)",
-                    "author_filter": "string — filter by author (read/threads mode)",
-                    "type_filter": "string — filter by observation_type (read mode)",
-                    "status_filter": "string — filter by thread status (threads mode)",
-                    "limit": "integer (1-100, default 20) — max entries (read mode)"
-                },
-                "returns": {
-                    "write": {"success": true, "thread": "object", "entry": "object"},
-                    "read": {"thread": "object", "entries": "array", "count": "integer"},
-                    "threads": {"threads": "array (enriched with entry_count, last_activity)", "total": "integer"},
-                    "status": {"success": true, "thread": "object with previous/new status"}
-                },
-                "examples": [
-                    {"description": "Start a new research thread", "call": {"mode": "write", "thread": "Embedding dimension drift", "content": "Why do we see dimension mismatches after provider switches?", "observation_type": "question"}},
-                    {"description": "Add evidence to a thread", "call": {"mode": "write", "thread": "Embedding dimension drift", "content": "Found 3 entities with 768-dim embeddings from old BGE provider", "observation_type": "evidence", "confidence": 0.9}},
-                    {"description": "Read all entries for a thread", "call": {"mode": "read", "thread": "Embedding dimension drift"}},
-                    {"description": "Read by thread ID when names are duplicated", "call": {"mode": "read", "thread": "kg_entities:abc123"}},
-                    {"description": "View all open threads", "call": {"mode": "threads", "status_filter": "open"}},
-                    {"description": "Mark a thread as resolved", "call": {"mode": "status", "thread": "Embedding dimension drift", "thread_status": "resolved"}}
-                ]
-            }),
-            "remember" => json!({
-                "name": "remember",
-                "description": "Create personal memory entities or relationships; returns created id.",
-                "arguments": {
-                    "kind": "string — 'entity'|'relationship'|'observation'",
-                    "data": "object — entity: {name, entity_type?, properties?} | relationship: {source, target, rel_type, properties?} | observation: {source, observation_type, properties?}",
-                    "confidence": "number — optional confidence",
-                    "upsert": "boolean (default true) — whether to find existing matching record or always create new"
-                },
-                "returns": {"created": true, "id": "string", "kind": "string"}
-            }),
-            "maintain" => json!({
-                "name": "maintain",
-                "description": "Maintenance operations including archival, cleanup, embedding refresh, rethink queue processing, and health checks (thoughts/entities/observations/edges).",
-                "arguments": {
-                    "subcommand": "string (required) — 'list_removal_candidates'|'export_removals'|'finalize_removal'|'health_check_embeddings'|'health_check_indexes'|'reembed'|'reembed_kg'|'embed_pending'|'ensure_continuity_fields'|'echo_config'|'corrections'|'rethink'|'consolidate'|'populate'|'embed'|'wander'|'health'|'report'|'tasks'",
-                    "dry_run": "boolean (default: false) — simulate operation without changes",
-                    "limit": "integer|string (default: 100) — max items to process",
-                    "format": "string (default: 'json') — export format",
-                    "output_dir": "string (default: './archive') — export directory",
-                    "tasks": "string — comma separated list for subcommand 'tasks' (default populate,embed,rethink,consolidate,wander,health,report,corrections)",
-                    "target_id": "string — optional filter for 'corrections' subcommand",
-                    "rethink_types": "string — comma-separated mark types for 'rethink' subcommand (e.g., correction,research)"
-                },
-                "returns": {
-                    "health_check_embeddings": "object — detailed breakdown per table (total, ok, missing, mismatched) with sample IDs",
-                    "corrections": "object — {success, count, events[]} result from corrections bridge",
-                    "rethink/consolidate/populate/embed/wander/health": "object — {task, success, stdout, stderr}",
-                    "tasks": "object — {results: [...]} aggregated per task",
-                    "report": "object — contents of logs/remini_report.json",
-                    "embed_pending": "object — {message, processed, succeeded, failed, remaining, dry_run} — retry embedding for thoughts with pending/failed status",
-                    "other_subcommands": "object — counts, paths, or messages depending on operation"
+            examples: json!([
+                {"description": "Record a debugging observation with a little memory injection", "call": {"content": "The reconnect loop never resets its backoff after a successful query", "hint": "debug", "injection_scale": 1, "tags": ["surrealdb"]}},
+                {"description": "Verify a hypothesis against KG evidence", "call": {"content": "Checking whether HNSW indexes need a rebuild after a dimension change", "hypothesis": "HNSW indexes must be rebuilt when the embedding dimension changes", "needs_verification": true, "response_detail": "compact"}}
+            ]),
+            extra: Some(json!({
+                "routing": {
+                    "triggers": {
+                        "debug": "debug time",
+                        "build": "building time",
+                        "plan": "plan/planning time",
+                        "stuck": "i'm stuck / stuck",
+                        "question": "question time",
+                        "conclude": "wrap up / conclude"
+                    },
+                    "heuristics": {
+                        "debug": ["error", "bug", "stack trace", "failed", "exception", "panic"],
+                        "build": ["implement", "create", "add function", "build", "scaffold", "wire"],
+                        "plan": ["architecture", "design", "approach", "how should", "strategy", "trade-off"],
+                        "stuck": ["stuck", "unsure", "confused", "not sure", "blocked"]
+                    }
                 }
+            })),
+        },
+        ToolHelp {
+            name: "think_batch",
+            one_liner: "Store several related thoughts atomically, linked by local_ref",
+            key_params: &["thoughts"],
+            description: "Store up to 20 related thoughts in one transaction: all are stored or none. Embedding, framework enhancement, and memory injection run after the commit. Hypothesis verification and response_detail are not available here.",
+            params: json!({
+                "thoughts": "array (required) — items with the think fields content, hint, injection_scale, tags, significance, session_id, chain_id, previous_thought_id, revises_thought, branch_from, confidence, idempotency_key, references, is_private, pinned, plus local_ref",
+                "thoughts[].local_ref": "string — name for this item; a later item's previous_thought_id, revises_thought, or branch_from equal to it is replaced with this item's generated id (status 'batch' in links_resolved). Only earlier items can be referenced",
+                "thoughts[].idempotency_key": "string — stored like think's; a key that already exists fails and rolls back the whole batch",
+                "namespace": NAMESPACE_NOTE,
+                "database": DATABASE_NOTE
             }),
-            "call_gem" => json!({
-                "name": "call_gem",
-                "description": "Delegate a prompt to the Gemini CLI agent. Supports session resume and observe mode.",
-                "arguments": {
-                    "prompt": "string (required) — the prompt text",
-                    "model": "string — override model (env: GEMINI_MODEL/GEMINI_MODELS)",
-                    "cwd": "string (required) — working directory for the agent",
-                    "resume_session_id": "string — resume a specific Gemini session",
-                    "continue_latest": "boolean (default false) — resume last Gemini session",
-                    "timeout_ms": "integer (default 60000) — outer timeout",
-                    "tool_timeout_ms": "integer (default 300000) — per-tool timeout",
-                    "expose_stream": "boolean — include stream events in response",
-                    "mode": "string — 'execute' (default) or 'observe' (read-only analysis)",
-                    "max_response_chars": "integer (default 100000) — max chars for response (0 = no limit)"
-                },
-                "returns": {"status": "completed", "session_id": "string", "response": "string"}
-            }),
-            "call_cc" => json!({
-                "name": "call_cc",
-                "description": "Delegate a prompt to the Claude Code CLI agent. Supports session resume and observe mode.",
-                "arguments": {
-                    "prompt": "string (required) — the prompt text",
-                    "model": "string — override model (env: ANTHROPIC_MODEL/ANTHROPIC_MODELS)",
-                    "cwd": "string (required) — working directory for the agent",
-                    "resume_session_id": "string — resume a specific Claude session",
-                    "continue_latest": "boolean (default false) — resume last Claude session",
-                    "timeout_ms": "integer (default 60000) — outer timeout",
-                    "tool_timeout_ms": "integer (default 300000) — per-tool timeout",
-                    "expose_stream": "boolean — include stream events in metadata",
-                    "mode": "string — 'execute' (default) or 'observe' (read-only analysis)",
-                    "max_response_chars": "integer (default 100000) — max chars for response (0 = no limit)"
-                },
-                "returns": {"status": "completed", "session_id": "string", "response": "string"}
+            returns: json!({
+                "thought_ids": "string[] — generated ids in item order",
+                "local_refs": "object — local_ref → thought_id",
+                "thoughts": "array — per item {thought_id, local_ref, mode_selected, embedding_status, memories_injected, framework_enhanced, redactions, links}"
             }),
-            "call_status" => json!({
-                "name": "call_status",
-                "description": "Get status of an async agent job",
-                "arguments": {
-                    "job_id": "string (required)"
-                },
-                "returns": {
-                    "job_id": "string",
-                    "status": "queued|running|completed|failed|cancelled",
-                    "created_at": "string",
-                    "started_at": "string?",
-                    "completed_at": "string?",
-                    "duration_ms": "integer?",
-                    "error": "string?",
-                    "session_id": "string?",
-                    "exchange_id": "string?",
-                    "metadata": "object?"
-                }
+            examples: json!([
+                {"description": "Store a plan and the step that follows it", "call": {"thoughts": [{"content": "Plan: move snapshots to gzip JSONL", "local_ref": "plan"}, {"content": "Step 1: page each table by START", "previous_thought_id": "plan"}]}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "system_status",
+            one_liner: "Check DB connectivity, embedder, cache stats, and table counts",
+            key_params: &["deep"],
+            description: "Report whether the server's dependencies are healthy without running a real tool. Each check is bounded by a 5s timeout.",
+            params: json!({
+                "deep": "boolean (default false) — also embed a short probe string to verify the embedding provider end to end"
             }),
-            "call_jobs" => json!({
-                "name": "call_jobs",
-                "description": "List async agent jobs with optional filtering",
-                "arguments": {
-                    "limit": "integer (default 20)",
-                    "status_filter": "string — optional status to filter by",
-                    "tool_name": "string — optional tool name to filter by"
-                },
-                "returns": {
-                    "jobs": "array of job summaries",
-                    "total": "integer"
-                }
+            returns: json!({
+                "ok": "boolean — database reachable and (when deep) the embed probe succeeded",
+                "database": "{ok, latency_ms, error, connection} — SurrealDB ping plus reconnect bookkeeping",
+                "embedder": "{provider, model, dimensions, rate_limit, deep_check} — rate_limit is {requests_per_minute, tokens_per_minute, available_requests, available_tokens, queued_waiters, paused_ms, throttled_responses} (null without a limiter); deep_check is null unless deep=true, else {ok, latency_ms, dimensions, error}",
+                "cache": "{size, capacity, hits, misses, hit_rate} — thoughts LRU cache",
+                "metrics": "string|null — Prometheus text exposition of tool, embedder, DB error and cache metrics when SURR_METRICS_ENABLED is set",
+                "tables": "object — row counts for thoughts, kg_entities, kg_edges, kg_observations, kg_entity_candidates, kg_edge_candidates (null when unavailable)"
             }),
-            "call_cancel" => json!({
-                "name": "call_cancel",
-                "description": "Cancel a running or queued async agent job",
-                "arguments": {
-                    "job_id": "string (required)"
-                },
-                "returns": {
-                    "job_id": "string",
-                    "previous_status": "string",
-                    "new_status": "string",
-                    "message": "string"
-                }
+            examples: json!([
+                {"description": "Quick connectivity and cache check", "call": {}},
+                {"description": "Also round-trip an embedding", "call": {"deep": true}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "embedding_probe",
+            one_liner: "Cosine similarity of two texts, or a text and a stored record",
+            key_params: &["text_a", "text_b", "table", "id"],
+            description: "Debug retrieval ranking: embed text_a with the server's embedder and compare it with text_b or a stored record. Inputs are capped at 100KB like think.",
+            params: json!({
+                "text_a": "string (required) — text to embed",
+                "text_b": "string — second text (use this or table + id)",
+                "table": "string — 'thoughts'|'kg_entities'|'kg_observations'",
+                "id": "string — record id, bare or table:id"
             }),
-            "call_vibe" => json!({
-                "name": "call_vibe",
-                "description": "Delegate a prompt to the Vibe CLI agent. Supports agent profiles, session continuation, and observe mode.",
-                "arguments": {
-                    "prompt": "string (required) — the prompt text",
-                    "cwd": "string (required) — working directory for the agent",
-                    "agent": "string — agent profile name from ~/.vibe/agents/*.toml",
-                    "mode": "string — 'execute' (default) or 'observe' (read-only analysis)",
-                    "continue_latest": "boolean (default false) — continue from last Vibe session",
-                    "timeout_ms": "integer (default 60000) — execution timeout",
-                    "max_response_chars": "integer (default 100000) — max chars for response (0 = no limit)"
-                },
-                "returns": {"status": "completed", "response": "string"}
+            returns: json!({
+                "similarity": "number — cosine similarity",
+                "dimensions": "integer — embedder dimensions",
+                "a": "{dimensions, head} — head is the first 8 components",
+                "b": "{dimensions, head, source} — source is 'embedded' (text_b), 'stored' (record embedding reused), or 'reembedded' (stored dimension differed)"
+            }),
+            examples: json!([
+                {"description": "Compare two phrasings", "call": {"text_a": "vector index", "text_b": "HNSW index"}},
+                {"description": "Compare a query with a stored thought", "call": {"text_a": "reconnect backoff", "table": "thoughts", "id": "thoughts:abc123"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "search",
+            one_liner: "Unified LM search: memories (default) + optional thoughts",
+            key_params: &[
+                "query",
+                "target",
+                "include_thoughts",
+                "top_k_memories",
+                "top_k_thoughts",
+                "chain_summary",
+            ],
+            description: "Unified search in LegacyMind: searches memories by default and, when include_thoughts=true, also searches thoughts. Supports continuity field filters for thoughts and forensic mode for provenance tracking.",
+            params: json!({
+                "query": "object — {name?, text?, id?} query parameters",
+                "target": "'entity'|'relationship'|'observation'|'mixed' (default 'mixed')",
+                "include_thoughts": "boolean (default false) — also search thoughts",
+                "thoughts_content": "string — optional explicit query text for thoughts",
+                "top_k_memories": "integer (1-50; default 10)",
+                "top_k_thoughts": "integer (1-50; default 5)",
+                "sim_thresh": "number (0.0-1.0) — similarity floor for thoughts",
+                "session_id": "string? — filter thoughts by session_id",
+                "chain_id": "string? — filter thoughts by chain_id",
+                "previous_thought_id": "string? — filter thoughts by previous_thought_id (record or string)",
+                "revises_thought": "string? — filter thoughts by revises_thought (record or string)",
+                "branch_from": "string? — filter thoughts by branch_from (record or string)",
+                "origin": "string? — filter thoughts by origin (e.g. 'human', 'tool')",
+                "lang": "string? — filter thoughts by detected language: ISO 639-1 code (e.g. 'en', 'de', 'ja') or 'und' for short, mixed, or unrecognized content; thoughts written before detection match only after maintain backfill_lang",
+                "min_significance": "number? (0.0-1.0) — filter thoughts with significance >= value",
+                "confidence_gte": "number? (0.0-1.0) — filter thoughts with confidence >= value",
+                "confidence_lte": "number? (0.0-1.0) — filter thoughts with confidence <= value",
+                "date_from": "string? — filter thoughts created_at >= bound: YYYY-MM-DD (start of the UTC day), RFC 3339, 'now', 'today', 'yesterday', or '-7d'/'-12h'/'-2w' before now",
+                "date_to": "string? — filter thoughts created_at <= bound, same forms; day forms include the whole UTC day. date_from after date_to is rejected",
+                "order": "string? ('created_at_asc'|'created_at_desc'|'final_score') — order thoughts by created_at, or by the ranked score below",
+                "recency_half_life_days": "number ≥ 0 — thought score = similarity * 0.5^(age_days / half_life); 0 or unset disables",
+                "significance_weight": "number (default 0) — adds weight * significance to each thought's score",
+                "explain_scores": "boolean (default false) — attach score {sim, recency_factor, significance_bonus, final} to each thought",
+                "snippet_window": "integer (default 240) — characters in each thought hit's highlight window",
+                "snippet_only": "boolean (default false) — return the highlight snippet instead of full content",
+                "embedding_field": "string ('embedding'|'embedding_v2') — vector field to search; the query is embedded with the configured embedder (primary or [embedding_migration] target) whose model is stored in that field, and only rows with that model and dim are compared. Errors when no configured embedder produces a stored model. Unset searches embedding with the active embedder",
+                "namespace": NAMESPACE_NOTE,
+                "database": DATABASE_NOTE,
+                "forensic": "boolean — include correction chain and derivatives in results",
+                "chain_summary": "boolean (default false) — return the thought chain for chain_id or thought_id instead of searching",
+                "thought_id": "string? — with chain_summary: walk previous_thought_id from this thought to find the chain",
+                "max_thoughts": "integer (1-500; default 50) — cap on thoughts in a chain summary",
+                "entity_timeline": "boolean (default false) — return the timeline of entity instead of searching: its creation, edges added to or from it, observations citing it (journal thread_id or data.source), and thoughts mentioning it (injected_memories or embedding similarity), oldest first",
+                "entity": "string — with entity_timeline: entity id (bare or kg_entities:...) or exact name",
+                "max_events": "integer (1-500; default 50) — events per timeline page",
+                "max_mentions": "integer (0-500) — cap on thoughts from the similarity scan against the entity embedding; 0 skips the scan (default [kg] timeline_max_mentions)",
+                "mention_threshold": "number (0.0-1.0) — cosine similarity at which a thought counts as a mention (default [kg] timeline_mention_threshold)",
+                "cursor": "string — RFC 3339 timestamp; with entity_timeline, return events strictly after it (pass the previous page's next_cursor)",
+                "include_names": "boolean (default true) — give relationship hits source_name/target_name from one batched kg_entities lookup, plus resolved (both endpoints are live entity records). Legacy string endpoints pass through as their own name with resolved=false. false skips the lookup",
+                "expand_graph": "boolean (default false) — also return entities linked to entity hits via kg_edges; each carries hop and via [{rel_type, from}]",
+                "graph_depth": "integer (1-3; default 1) — hops to traverse",
+                "graph_boost": "number (0.0-1.0) — neighbor score = graph_boost * hit similarity, halved per extra hop (default SURR_KG_GRAPH_BOOST)",
+                "min_edge_strength": "number — ignore edges with strength below this; edges without strength count as 1.0 (default SURR_KG_MIN_EDGE_STRENGTH)",
+                "as_of": "string — date (YYYY-MM-DD, RFC 3339, now, today, yesterday, -N[h|d|w]); graph expansion and relationship hits skip edges whose valid_from/valid_to window excludes it. Relationship hits carry strength, valid_from, and valid_to",
+                "include_superseded": "boolean (default false) — keep thoughts that a later thought revises; otherwise each is replaced by its latest revision (carrying substituted_for)",
+                "has_reference": "boolean — only thoughts with (true) or without (false) references",
+                "reference_contains": "string — only thoughts with a reference whose value contains this substring, e.g. 'src/tools/thinking.rs'",
+                "code_ref_contains": "string — only thoughts whose content mentions a file path, Rust error code, or URL (extracted into code_refs when the thought is created) containing this substring, ignoring case, e.g. 'inner_voice.rs', 'E0308', or 'thinking.rs:120'. Works without query text or embedding similarity",
+                "include_private": "boolean (default false) — also return thoughts marked is_private, in thought search and chain_summary",
+                "only_pinned": "boolean (default false) — only return pinned thoughts",
+                "tag_expr": "string — tag filter such as '(rust AND surrealdb) OR mcp' (AND, OR, NOT, parentheses; NOT binds tightest, OR loosest; keywords case-insensitive, tags exact; double-quote tags that are keywords or contain spaces). Applies to thought tags and memory data.tags; a malformed expression is an invalid-params error naming the position"
+            }),
+            returns: json!({"memories": {"items": "array — relationships carry source_id, target_id, source_name, target_name, and resolved (names omitted with include_names=false); those found by query text also carry similarity from their edge embeddings. Observations carry provenance {thought_ids, thoughts: [{id, created_at}], unresolved}"}, "thoughts": {"total": "number", "superseded_substituted": "number", "results": "array — {id, content, similarity?, significance, session_id?, chain_id?, origin?, lang?, superseded_by?, substituted_for?, references?, code_refs? [{kind: path|error_code|url, value, line?}], pinned?, score?}"}, "chain_summary": "object (chain_summary mode) — {chain_id, count, truncated, thoughts: [{id, content_preview, mode, significance, confidence, links}], adjacency: {id: [{id, kind: next|branch|revision}]}, roots, cycles, dangling_links}", "entity_timeline": "object (entity_timeline mode) — {entity: {id, name, entity_type}, count, events: [{ts, kind: created|edge_added|observation|mention, ref_id, summary, rel_type?, direction?, via? [injected|similarity], similarity?}], next_cursor, mention_scan: {ran, threshold, max_mentions, matched}}", "query_embedding": "object (when the query was embedded) — {field, provider, model, dim} used for similarity"}),
+            examples: json!([
+                {"description": "Search entities with forensic provenance", "call": {"query": {"name": "REMini"}, "target": "entity", "forensic": true}},
+                {"description": "Search thoughts in a specific session", "call": {"include_thoughts": true, "session_id": "session_123"}},
+                {"description": "Summarize the chain a thought belongs to", "call": {"chain_summary": true, "thought_id": "thoughts:abc123", "max_thoughts": 20}},
+                {"description": "Page through what happened to an entity", "call": {"entity_timeline": true, "entity": "surreal-mind", "max_events": 25}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "wander",
+            one_liner: "Explore the knowledge graph for curiosity-driven discovery",
+            key_params: &[
+                "mode",
+                "current_thought_id",
+                "visited_ids",
+                "recency_bias",
+                "include_private",
+                "for",
+            ],
+            description: "Interactively explore the knowledge graph via traversals. Can wander randomly, semantically, or via metadata and attention marks.",
+            params: json!({
+                "mode": "string (required) — 'random', 'semantic', 'meta', 'marks'",
+                "current_thought_id": "string — optional starting thought ID",
+                "visited_ids": "array — IDs to avoid preventing loops",
+                "recency_bias": "boolean (default false) — prioritize recent memories",
+                "include_private": "boolean (default false) — also wander onto thoughts marked is_private",
+                "for": "string — filter marks assigned to a specific member ('cc', 'codex', 'sam', 'gemini', 'dt', 'gem')"
+            }),
+            returns: json!({
+                "current_node": "object — the node reached in the step",
+                "mode_used": "string — the mode used for the step",
+                "affordances": "array — suggested next modes",
+                "guidance": "string — actionable architectural guidance",
+                "queue_depth": "integer? — remaining items in queue (marks mode only)"
+            }),
+            examples: json!([
+                {"description": "Surface marks for CC", "call": {"mode": "marks", "for": "cc"}},
+                {"description": "Wander semantically from a specific thought", "call": {"mode": "semantic", "current_thought_id": "thoughts:abc"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "rethink",
+            one_liner: "Revise or mark knowledge graph items for correction",
+            key_params: &["target_id", "mode", "mark_type", "marked_for"],
+            description: "Revise or mark knowledge graph items for correction. Supports provenance-tracked corrections and attention routing.",
+            params: json!({
+                "target_id": "string (required) — ID of the record (thoughts:xxx, entity:xxx, observation:xxx)",
+                "mode": "string (required) — 'mark' (flag for review) or 'correct' (apply fix)",
+                "mark_type": "string — 'correction', 'research', 'enrich', 'expand' (mark mode)",
+                "marked_for": "string — 'cc', 'codex', 'sam', 'gemini', 'dt', 'gem' (mark mode)",
+                "note": "string — contextual explanation for the mark (mark mode)",
+                "reasoning": "string — why the record is being corrected (correct mode)",
+                "sources": "string[] — verification sources (correct mode)",
+                "cascade": "boolean (default false) — flag derivatives for review (correct mode)"
+            }),
+            returns: json!({
+                "success": "boolean",
+                "marked": "object? — details of the created mark",
+                "correction": "object? — details of the applied correction event",
+                "derivatives_flagged": "integer? — count of cascaded marks"
+            }),
+            examples: json!([
+                {"description": "Ask CC to research an entity", "call": {"target_id": "kg_entities:abc", "mode": "mark", "mark_type": "research", "marked_for": "cc", "note": "Check whether this project is still active"}},
+                {"description": "Correct an observation and flag what was derived from it", "call": {"target_id": "kg_observations:def", "mode": "correct", "reasoning": "The release date was off by a year", "sources": ["CHANGELOG.md"], "cascade": true}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "corrections",
+            one_liner: "List recent correction events to inspect the learning journey",
+            key_params: &["target_id", "limit"],
+            description: "List recent correction events to inspect the learning journey of the KG.",
+            params: json!({
+                "target_id": "string — optional filter for a specific target ID",
+                "limit": "integer (default 10) — max events to return"
+            }),
+            returns: json!({
+                "success": "boolean",
+                "count": "integer",
+                "events": "array of correction_event objects"
+            }),
+            examples: json!([
+                {"description": "Latest corrections", "call": {"limit": 5}},
+                {"description": "Corrections of one record", "call": {"target_id": "kg_entities:abc"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "journal",
+            one_liner: "Research thread management — create threads, add entries, view dashboard",
+            key_params: &["mode", "thread", "content", "observation_type", "author"],
+            description: "Research thread management tool. Create and track research threads as KG entities, add journal entries as observations. A looking glass over the existing KG — no new tables.",
+            params: json!({
+                "mode": "string (required) — 'write', 'read', 'threads', 'status'",
+                "thread": "string — thread name or kg_entities ID (required for write/read/status). Use ID when names are duplicated.",
+                "content": "string — journal entry content (required for write)",
+                "observation_type": "string — 'question', 'hypothesis', 'evidence', 'reflection', 'dead_end', 'follow_up' (required for write)",
+                "author": "string — 'cc', 'codex', 'gem', 'vibe', 'dt' (default 'cc')",
+                "tags": "string[] — optional tags (write mode)",
+                "confidence": "number (0.0-1.0) — optional confidence (write mode)",
+                "thread_status": "string — 'open', 'pursuing', 'resolved', 'abandoned' (required for status mode)",
+                "author_filter": "string — filter by author (read/threads mode)",
+                "type_filter": "string — filter by observation_type (read mode)",
+                "status_filter": "string — filter by thread status (threads mode)",
+                "limit": "integer (1-100, default 20) — max entries (read mode)"
+            }),
+            returns: json!({
+                "write": {"success": true, "thread": "object", "entry": "object"},
+                "read": {"thread": "object", "entries": "array", "count": "integer"},
+                "threads": {"threads": "array (enriched with entry_count, last_activity)", "total": "integer"},
+                "status": {"success": true, "thread": "object with previous/new status"}
+            }),
+            examples: json!([
+                {"description": "Start a new research thread", "call": {"mode": "write", "thread": "Embedding dimension drift", "content": "Why do we see dimension mismatches after provider switches?", "observation_type": "question"}},
+                {"description": "Add evidence to a thread", "call": {"mode": "write", "thread": "Embedding dimension drift", "content": "Found 3 entities with 768-dim embeddings from old BGE provider", "observation_type": "evidence", "confidence": 0.9}},
+                {"description": "Read all entries for a thread", "call": {"mode": "read", "thread": "Embedding dimension drift"}},
+                {"description": "Read by thread ID when names are duplicated", "call": {"mode": "read", "thread": "kg_entities:abc123"}},
+                {"description": "View all open threads", "call": {"mode": "threads", "status_filter": "open"}},
+                {"description": "Mark a thread as resolved", "call": {"mode": "status", "thread": "Embedding dimension drift", "thread_status": "resolved"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "remember",
+            one_liner: "Create entities/relationships/observations in the KG",
+            key_params: &[
+                "kind",
+                "data",
+                "items",
+                "atomic",
+                "confidence",
+                "source_thought_id",
+            ],
+            description: "Create personal memory entities or relationships; returns created id.",
+            params: json!({
+                "kind": "string — 'entity'|'relationship'|'observation'",
+                "data": "object — entity: {name, entity_type? (checked against the [kg] taxonomy; stored lowercase, original kept as entity_type_raw), properties?} | relationship: {source, target, rel_type, strength? (non-negative, default 1.0), valid_from?, valid_to? (dates; a day valid_to covers the whole day), properties?} — re-creating an existing source/target/rel_type edge updates the attributes given | observation: {source, observation_type, properties?}",
+                "source_thought_id": "string — thought an observation came from. Stored as source_thought_ids. Unknown ids are kept and returned under provenance.unresolved; with [kg] require_observation_provenance, observations without an existing source thought are rejected",
+                "source_thought_ids": "string[] — several source thoughts for an observation; merged with source_thought_id",
+                "confidence": "number — optional confidence",
+                "upsert": "boolean (default true) — whether to find existing matching record or always create new",
+                "items": "array — batch of {kind, data, upsert?}; later relationships may reference names created earlier in the batch",
+                "atomic": "boolean (default false) — batch only: abort at first failure, roll back created items, report failed_index",
+                "namespace": NAMESPACE_NOTE,
+                "database": DATABASE_NOTE
+            }),
+            returns: json!({"created": true, "id": "string", "kind": "string", "strength/valid_from/valid_to": "relationships: the stored attributes; an existing edge reports the ones it changed under updated", "provenance": "object? — observations: {thought_ids, thoughts: [{id, created_at}], unresolved}", "batch": {"items": "[{kind, id, created, error?}]", "count": "number", "errors": "number"}}),
+            examples: json!([
+                {"description": "Create an entity", "call": {"kind": "entity", "data": {"name": "surreal-mind", "entity_type": "project"}}},
+                {"description": "Create two entities and link them in one batch", "call": {"items": [{"kind": "entity", "data": {"name": "SurrealDB", "entity_type": "technology"}}, {"kind": "relationship", "data": {"source": "surreal-mind", "target": "SurrealDB", "rel_type": "uses"}}], "atomic": true}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "forget",
+            one_liner: "Delete KG entities/relationships/observations with an audit trail",
+            key_params: &["kind", "id", "name", "entity_type", "cascade"],
+            description: "Delete a KG record. Entities/observations still referenced by edges are refused (blocking edge ids returned) unless cascade=true. Each deletion is snapshotted into kg_deletions for recovery.",
+            params: json!({
+                "kind": "string — 'entity'|'relationship'|'observation'",
+                "id": "string — record id (bare or 'kg_entities:...'); relationships require id",
+                "name": "string — alternative to id for entities/observations",
+                "entity_type": "string — optional, disambiguates name lookups",
+                "cascade": "boolean (default false) — also delete referencing edges; without it a referenced record fails with a conflict error whose data lists blocking_edges",
+                "reason": "string — optional note stored on the audit record",
+                "namespace": NAMESPACE_NOTE,
+                "database": DATABASE_NOTE
+            }),
+            returns: json!({"kind": "string", "id": "string", "deleted": "boolean", "cascaded_edges": "string[]"}),
+            examples: json!([
+                {"description": "Delete an entity and the edges that reference it", "call": {"kind": "entity", "name": "old-prototype", "entity_type": "project", "cascade": true, "reason": "merged into surreal-mind"}},
+                {"description": "Delete a relationship by id", "call": {"kind": "relationship", "id": "kg_edges:xyz"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "export",
+            one_liner: "Dump the KG to a JSONL or GraphML file",
+            key_params: &["path", "format", "include_embeddings"],
+            description: "Page kg_entities, kg_observations and kg_edges out of SurrealDB into one file. JSONL writes one {kind, id, record} object per line; GraphML writes entities/observations as nodes and relationships as edges (rel_type attribute) for Gephi/Obsidian. The file is written to '<path>.tmp' and renamed into place when complete.",
+            params: json!({
+                "path": "string (required) — absolute output file path; the parent directory must exist",
+                "format": "string (default 'jsonl') — 'jsonl'|'graphml'",
+                "include_embeddings": "boolean (default false) — keep embedding vectors (JSONL only)",
+                "page_size": "integer (default 500, max 5000) — rows fetched per database page"
+            }),
+            returns: json!({"path": "string", "format": "string", "include_embeddings": "boolean", "counts": {"entities": "number", "observations": "number", "relationships": "number"}, "bytes": "number"}),
+            examples: json!([
+                {"description": "Back up the KG as JSONL with embeddings", "call": {"path": "/tmp/kg.jsonl", "include_embeddings": true}},
+                {"description": "GraphML for Gephi", "call": {"path": "/tmp/kg.graphml", "format": "graphml"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "import",
+            one_liner: "Load a JSONL export back into the KG",
+            key_params: &["path", "mode", "batch_size"],
+            description: "Recreate entities, observations, and relationships from a JSONL file written by export. Every line is validated before anything is written; nodes are imported before edges, in batches. Edge endpoints are remapped to renamed ids; edges whose endpoints exist neither in the file nor in the database are listed in unresolved_edges instead of being written. Embeddings that do not match the active dimension are dropped (with their embedding_* metadata) so 'maintain embed_pending' re-embeds them.",
+            params: json!({
+                "path": "string (required) — absolute path to the JSONL file",
+                "mode": "string (default 'skip') — on id collision: 'skip' keeps the existing record, 'overwrite' replaces it, 'rename' creates the record under a new id",
+                "batch_size": "integer (default 100, max 1000) — records written per database round trip"
+            }),
+            returns: json!({"path": "string", "mode": "string", "batch_size": "number", "stats": {"lines": "number", "batches": "number", "entities|observations|relationships": {"created": "number", "overwritten": "number", "renamed": "number", "skipped": "number"}, "embeddings_kept": "number", "reembed_needed": "string[]", "renamed": "object (old table:id → new table:id)", "unresolved_edges": "[{id, source, target, missing}]"}}),
+            examples: json!([
+                {"description": "Restore an export, keeping existing records", "call": {"path": "/tmp/kg.jsonl"}},
+                {"description": "Import next to existing records under fresh ids", "call": {"path": "/tmp/kg.jsonl", "mode": "rename", "batch_size": 200}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "maintain",
+            one_liner: "Archival, export, re-embed checks and housekeeping",
+            key_params: &["subcommand", "limit", "dry_run", "output_dir"],
+            description: "Maintenance operations including archival, cleanup, embedding refresh, rethink queue processing, and health checks (thoughts/entities/observations/edges).",
+            params: json!({
+                "subcommand": "string (required) — operation to run; the accepted names are the schema enum",
+                "dry_run": "boolean (default: false) — simulate operation without changes",
+                "limit": "integer|string (default: 100) — max items to process; for 'backfill_lang', rows per batch (every row missing a lang is processed)",
+                "format": "string (default: 'json') — export format",
+                "output_dir": "string (default: './archive') — export directory",
+                "tasks": "string — comma separated list for subcommand 'tasks' (default populate,embed,rethink,consolidate,wander,health,report,corrections)",
+                "target_id": "string — optional filter for 'corrections' subcommand",
+                "rethink_types": "string — comma-separated mark types for 'rethink' subcommand (e.g., correction,research)",
+                "concurrency": "integer (default: 1) — parallel embedding requests for 'reembed'",
+                "sample": "integer (default: 0) — example ids per problematic group for 'embedding_audit'",
+                "offset": "integer (default: 0) — thoughts to skip before scanning for 'decay_thoughts' (limit caps the scan; omit for all)",
+                "dangling_after_days": "integer — for 'repair_continuity_links': list links still missing on thoughts at least this many days old",
+                "since": "string — RFC 3339 window start (inclusive) for 'audit_summary'; first day (YYYY-MM-DD) for 'embedding_usage_report'",
+                "until": "string — RFC 3339 window end (exclusive) for 'audit_summary'; last day (YYYY-MM-DD, inclusive) for 'embedding_usage_report'",
+                "skip": "string[] — for 'providers_check': providers not to probe ('embedder', 'gemini')",
+                "ids": "string[] — thought ids for 'soft_delete_thoughts'/'restore_thoughts'/'set_privacy'/'pin_thoughts'/'unpin_thoughts'",
+                "private": "boolean (default: true) — for 'set_privacy': the is_private value to set",
+                "prompt_version": "string — for 'populate': extraction prompt version (default: latest)",
+                "reprocess_batch": "string — for 'populate': re-extract the thoughts of this extraction_batch_id and mark the old batch superseded",
+                "apply": "boolean (default: false) — for 'schema_check': run the additive DEFINE statements for whatever is missing, then re-check",
+                "manifest_path": "string — for 'snapshot_restore': manifest.json written by snapshot_create",
+                "confirm": "boolean (default: false) — required for 'snapshot_restore' to write; dry_run only verifies the snapshot",
+                "force": "boolean (default: false) — for 'snapshot_restore': clear tables that already hold rows instead of refusing; for 'update_thought': allow editing a synthesized (origin inner_voice) thought",
+                "thought_id": "string — for 'update_thought': the thought to edit",
+                "mode": "string — for 'update_thought': 'replace_content' or 'append' (added after a blank line)",
+                "content": "string — for 'update_thought': the new content or the text to append; redacted like think content",
+                "reason": "string — for 'update_thought': stored with the revision",
+                "session_id": "string — for 'summarize_session': the session to roll up",
+                "demote_factor": "number 0..1 (exclusive) — for 'summarize_session': multiply summarized thoughts' significance by this the first time a summary covers them (default: [maintenance.summarize] demote_factor, unset = no demotion)",
+                "hypothesis_hash": "string — for 'hypothesis_trend': verification.hypothesis_hash from a think result",
+                "hypothesis": "string — for 'hypothesis_trend': hypothesis text, hashed the same way (case, whitespace, and trailing punctuation ignored) when hypothesis_hash is absent",
+                "older_than_days": "integer — for 'purge_deleted': remove thoughts soft-deleted at least this many days ago (default 30)",
+                "to_v2": "boolean (default: false) — for 'reembed': backfill embedding_v2 from the [embedding_migration] target on thoughts, entities, and observations",
+                "survivor": "string (default: 'oldest') — for 'dedupe_entities': 'oldest' or 'most_edges' (most inbound edges, then oldest)",
+                "similarity_threshold": "number 0..1 — for 'dedupe_entities': also group normalized names with at least this Jaro-Winkler similarity; for 'normalize_entity_types': similarity to the nearest [kg] taxonomy type needed to rewrite (default 0.85)"
+            }),
+            returns: json!({
+                "health_check_embeddings": "object — detailed breakdown per table (total, ok, missing, mismatched) with sample IDs",
+                "embedding_audit": "object — {expected_dim, tables: {thoughts|kg_entities|kg_observations: {total, null_or_empty, dim_disagrees_with_length, wrong_active_dim, groups[{provider, model, dim, len, count, issues, sample_ids?}]}}}",
+                "decay_thoughts": "object — {config, limit, offset, stats: {dry_run, scanned, decayed, unchanged, pruned, skipped_referenced}}",
+                "repair_continuity_links": "object — {limit, dangling_after_days, stats: {dry_run, scanned, repaired, still_missing, backfilled, superseded_filled, dangling?[{thought_id, link, target, age_days}]}}",
+                "normalize_observations": "object — {stats: {dry_run, backfilled, empty}}; copies each observation's search text (data.content, else name - data.description) into a missing content field. Idempotent",
+                "backfill_lang": "object — {stats: {dry_run, batch_size, tables: [{table: thoughts|kg_observations, updated, languages: {code: count}}]}}; stores the detected ISO 639-1 code ('und' for short, mixed, or unrecognized text) on rows written before language detection. Idempotent",
+                "observation_provenance_report": "object — {scanned, missing, dangling, observations: [{id, name, source_thought_ids, reason: missing|dangling}], truncated}; lists up to limit observations with no source thought that still exists",
+                "dedupe_entities": "object — {stats: {dry_run, scanned, groups_found, merged, absorbed, edges_rewired, groups[{entity_type, key, survivor, survivor_name, absorbed[], absorbed_names[], edges_rewired, edges_dropped}]}}; limit caps groups merged. Absorbed entities are snapshotted into kg_deletions",
+                "soft_delete_thoughts/restore_thoughts": "object — {stats: {dry_run, requested, updated[], unchanged[], missing[]}}; soft-deleted thoughts are skipped by search and duplicate detection until restored",
+                "set_privacy": "object — {stats: {dry_run, private, requested, updated[], unchanged[], missing[]}}; private thoughts are skipped by search, chain summaries, and wander unless include_private is passed",
+                "update_thought": "object — {update: {thought_id, mode, dry_run, revision_count, content, redactions, code_refs, embedding_status?, embedding_dim?}}; the previous content is kept in the thought's revisions [{content, edited_at, reason, mode}] and the thought is re-embedded",
+                "summarize_session": "object — {summary: {session_id, dry_run, summary_id?, updated, method: extractive|gemini, provider_error?, content, summarizes[], demoted[], embedding_status?}}; the summary is a thought with origin 'summary' and the session's session_id. Re-runs rewrite it in place. Private, soft-deleted, and summary thoughts are not summarized",
+                "hypothesis_trend": "object — {trend: {hypothesis_hash, hypothesis?, count, series: [{verified_at, confidence_score, scoring, supporting_count, contradicting_count, thought_id, session_id}]}} oldest first; rows are written by think verification with SURR_PERSIST_VERIFICATION",
+                "pin_thoughts": "object — {stats: {dry_run, pinned, requested, updated[], unchanged[], missing[]}}; unpin_thoughts returns the same with pinned=false",
+                "purge_deleted": "object — {stats: {dry_run, older_than_days, purged[]}}; permanently deletes thoughts soft-deleted before the cutoff",
+                "reembed (to_v2)": "object — {stats: {dry_run, provider, model, target_dim, tables[{table, scanned, updated, skipped}]}}",
+                "finalize_migration": "object — {stats: {dry_run, tables[{table, pending, promoted}]}, next_steps}; refuses while embedded rows lack embedding_v2, otherwise promotes v2 fields to primary in batches",
+                "audit_summary": "object — {audit_enabled, since, until, total_calls, tools[{tool, calls, errors, error_rate, p50_ms, p95_ms}]} from the tool_audit table",
+                "providers_check": "object — {ok, providers: [{provider: embedder|gemini, configured, reachable, skipped?, latency_ms?, error_class?, error?, detail?}]}. The embedder is probed with a one-word embed; Gemini with `gemini --version` and a credential lookup (detail {version, auth}), no prompt. error_class is auth, rate_limited, not_found, unreachable, timeout, dimension_mismatch, or error for the embedder, and not_installed, no_credentials, timeout, or cli_error for Gemini; error text has API keys and tokens removed",
+                "embedding_usage_report": "object — {since, until, usd_per_million_tokens, total, by_purpose[], by_day[], by_model[], process} where each entry has {calls, tokens, estimated_cost_usd}; purposes are thought_create, kg_embed, reembed, search_query, other. process holds this server's counters since startup",
+                "rebuild_vector_index": "object — {dry_run, ready, indexes[{table, index, index_dimension, embedder_dimension, status}]}; dry_run only reports state",
+                "corrections": "object — {success, count, events[]} result from corrections bridge",
+                "rethink/consolidate/populate/embed/wander": "object — {task, success, stdout, stderr, exit_code}; a non-zero exit is a tool_execution_failed error carrying this object as data.partial",
+                "health": "object — {task, success, stdout, stderr, db_health: {reachable, reconnect_enabled, reconnects, last_reconnect_at, last_reconnect_attempts, total_attempts, last_error}, embedding_rate_limit: same shape as system_status embedder.rate_limit}",
+                "schema_check": "object — {ok, apply, expected: {tables, fields, indexes}, drift: {missing_tables[], missing_fields[{table, name}], missing_indexes[{table, name}], unexpected_candidate_tables[], statements?[]}, applied[], failed[{statement, error}], remaining?}; compares the live database with the migrated schema. ok is false while anything is missing (after applying, with apply)",
+                "migration_status": "object — {applied[{name, applied_at}], pending[], unknown[], skip_migrations}; pending migrations run at the next startup unless skip_migrations is set. unknown lists records from a newer build",
+                "snapshot_create": "object — {snapshot_dir, manifest_path, manifest: {version, created_at, namespace, database, embedding: {provider, model, dim}, tables[{table, file, count}]}, rows, batches}; writes gzip JSONL per table under [maintenance.snapshot] backup_dir",
+                "snapshot_restore": "object — {manifest_path, version, created_at, tables[{table, expected, cleared, loaded}], rows, batches, dry_run}; checks the manifest version, embedding dim, and file row counts first and refuses non-empty tables unless force",
+                "tasks": "object — {results: [...]} aggregated per task; failed tasks appear as {task, error, class, partial?}",
+                "report": "object — contents of logs/remini_report.json",
+                "embed_edges": "object — {stats: {expected_dim, provider, model, dry_run, edges_updated, edges_skipped, edges_missing, edges_mismatched, edges_dangling}}; embeds up to limit edges (0 = all) as '<source name> <rel_type> <target name>'. Edges with a deleted or unnamed endpoint are counted as dangling and left unembedded",
+                "embed_pending": "object — {message, processed, succeeded, failed, remaining, dry_run} — retry embedding for thoughts with pending/failed status",
+                "other_subcommands": "object — counts, paths, or messages depending on operation"
+            }),
+            examples: json!([
+                {"description": "Preview a duplicate-entity merge", "call": {"subcommand": "dedupe_entities", "dry_run": true, "survivor": "most_edges"}},
+                {"description": "Re-embed up to 500 thoughts, four at a time", "call": {"subcommand": "reembed", "limit": 500, "concurrency": 4}},
+                {"description": "Fix a typo in a thought, keeping the old text as a revision", "call": {"subcommand": "update_thought", "thought_id": "abc123", "mode": "replace_content", "content": "Use gzip JSONL for snapshots", "reason": "typo"}},
+                {"description": "Roll a long session up into one summary thought, halving the covered thoughts' significance", "call": {"subcommand": "summarize_session", "session_id": "2026-10-17-refactor", "demote_factor": 0.5}},
+                {"description": "Embedding spend per purpose for October", "call": {"subcommand": "embedding_usage_report", "since": "2026-10-01", "until": "2026-10-31"}},
+                {"description": "Preview the languages detected on older thoughts and observations", "call": {"subcommand": "backfill_lang", "dry_run": true, "limit": 500}},
+                {"description": "Check the embedder credentials without probing Gemini", "call": {"subcommand": "providers_check", "skip": ["gemini"]}},
+                {"description": "Trend the verification score of a hypothesis across sessions", "call": {"subcommand": "hypothesis_trend", "hypothesis": "HNSW indexes must be rebuilt when the embedding dimension changes"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "call_gem",
+            one_liner: "Delegate a prompt to the Gemini CLI agent",
+            key_params: &["prompt", "model", "cwd", "mode"],
+            description: "Delegate a prompt to the Gemini CLI agent. Supports session resume and observe mode.",
+            params: json!({
+                "prompt": "string (required) — the prompt text",
+                "task_name": "string — accepted for compatibility; currently unused",
+                "model": "string — override model (env: GEMINI_MODEL/GEMINI_MODELS)",
+                "cwd": "string (required) — working directory for the agent",
+                "resume_session_id": "string — resume a specific Gemini session",
+                "continue_latest": "boolean (default false) — resume last Gemini session",
+                "timeout_ms": "integer (default 60000) — outer timeout",
+                "tool_timeout_ms": "integer (default 300000) — per-tool timeout",
+                "expose_stream": "boolean — include stream events in response",
+                "mode": "string — 'execute' (default) or 'observe' (read-only analysis)",
+                "max_response_chars": "integer (default 100000) — max chars for response (0 = no limit)"
+            }),
+            returns: json!({"status": "completed", "session_id": "string", "response": "string"}),
+            examples: json!([
+                {"description": "Read-only review of a workspace", "call": {"prompt": "Summarize the open TODOs in src/", "cwd": "surreal-mind", "mode": "observe"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "call_cc",
+            one_liner: "Delegate a prompt to the Claude Code CLI agent",
+            key_params: &["prompt", "model", "cwd", "mode"],
+            description: "Delegate a prompt to the Claude Code CLI agent. Supports session resume and observe mode.",
+            params: json!({
+                "prompt": "string (required) — the prompt text",
+                "task_name": "string — accepted for compatibility; currently unused",
+                "model": "string — override model (env: ANTHROPIC_MODEL/ANTHROPIC_MODELS)",
+                "cwd": "string (required) — working directory for the agent",
+                "resume_session_id": "string — resume a specific Claude session",
+                "continue_latest": "boolean (default false) — resume last Claude session",
+                "timeout_ms": "integer (default 60000) — outer timeout",
+                "tool_timeout_ms": "integer (default 300000) — per-tool timeout",
+                "expose_stream": "boolean — include stream events in metadata",
+                "mode": "string — 'execute' (default) or 'observe' (read-only analysis)",
+                "max_response_chars": "integer (default 100000) — max chars for response (0 = no limit)"
+            }),
+            returns: json!({"status": "completed", "session_id": "string", "response": "string"}),
+            examples: json!([
+                {"description": "Continue the last session with a follow-up", "call": {"prompt": "Now add a test for the empty case", "cwd": "~/Projects/surreal-mind", "continue_latest": true}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "call_status",
+            one_liner: "Get status of an async agent job",
+            key_params: &["job_id"],
+            description: "Get status of an async agent job",
+            params: json!({
+                "job_id": "string (required)"
+            }),
+            returns: json!({
+                "job_id": "string",
+                "status": "queued|running|completed|failed|cancelled",
+                "created_at": "string",
+                "started_at": "string?",
+                "completed_at": "string?",
+                "duration_ms": "integer?",
+                "error": "string?",
+                "session_id": "string?",
+                "exchange_id": "string?",
+                "metadata": "object?"
+            }),
+            examples: json!([
+                {"description": "Poll a job", "call": {"job_id": "job_123"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "call_jobs",
+            one_liner: "List async agent jobs",
+            key_params: &["limit", "status_filter", "tool_name"],
+            description: "List async agent jobs with optional filtering",
+            params: json!({
+                "limit": "integer (default 20)",
+                "status_filter": "string — optional status to filter by",
+                "tool_name": "string — optional tool name to filter by"
+            }),
+            returns: json!({
+                "jobs": "array of job summaries",
+                "total": "integer"
+            }),
+            examples: json!([
+                {"description": "Running Gemini jobs", "call": {"status_filter": "running", "tool_name": "call_gem", "limit": 10}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "call_cancel",
+            one_liner: "Cancel a running or queued job",
+            key_params: &["job_id"],
+            description: "Cancel a running or queued async agent job",
+            params: json!({
+                "job_id": "string (required)"
+            }),
+            returns: json!({
+                "job_id": "string",
+                "previous_status": "string",
+                "new_status": "string",
+                "message": "string"
+            }),
+            examples: json!([
+                {"description": "Cancel a job", "call": {"job_id": "job_123"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "call_vibe",
+            one_liner: "Delegate a prompt to the Vibe CLI agent",
+            key_params: &["prompt", "cwd", "agent", "mode"],
+            description: "Delegate a prompt to the Vibe CLI agent. Supports agent profiles, session continuation, and observe mode.",
+            params: json!({
+                "prompt": "string (required) — the prompt text",
+                "cwd": "string (required) — working directory for the agent",
+                "agent": "string — agent profile name from ~/.vibe/agents/*.toml",
+                "mode": "string — 'execute' (default) or 'observe' (read-only analysis)",
+                "continue_latest": "boolean (default false) — continue from last Vibe session",
+                "timeout_ms": "integer (default 60000) — execution timeout",
+                "max_response_chars": "integer (default 100000) — max chars for response (0 = no limit)"
+            }),
+            returns: json!({"status": "completed", "response": "string"}),
+            examples: json!([
+                {"description": "Run a task with an agent profile", "call": {"prompt": "Fix the failing clippy lint", "cwd": "surreal-mind", "agent": "reviewer"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "howto",
+            one_liner: "Get help for a specific tool or list all tools",
+            key_params: &["tool", "section", "format"],
+            description: "Without tool, lists every tool with a one-line summary and its key parameters. With tool, returns that tool's parameters (live input schema, required flag, and notes), its result shape, and example calls.",
+            params: json!({
+                "tool": "string — tool to describe; omit for the roster",
+                "section": "string (default 'all') — 'params' (parameters only), 'examples' (example calls only), or 'all' (adds description and returns)",
+                "format": "string (default 'full') — 'compact' returns only {tool, summary, arguments} with the parameter notes"
             }),
-            _ => {
-                return Err(SurrealMindError::Validation {
-                    message: format!("Unknown tool: {}", tool),
-                });
+            returns: json!({
+                "tools": "array (no tool) — [{name, one_liner, key_params}]",
+                "name": "string",
+                "params": "object — per parameter {schema, required, note, fields?}",
+                "examples": "array — [{description, call}]",
+                "description": "string (section 'all')",
+                "returns": "object (section 'all') — result fields of the tool"
+            }),
+            examples: json!([
+                {"description": "List every tool", "call": {}},
+                {"description": "Parameters of search only", "call": {"tool": "search", "section": "params"}}
+            ]),
+            extra: None,
+        },
+        ToolHelp {
+            name: "test_notification",
+            one_liner: "Send a test logging notification to the client",
+            key_params: &["message", "level"],
+            description: "Send an MCP logging notification so a client can check that it receives server notifications.",
+            params: json!({
+                "message": "string (required) — notification text",
+                "level": "string (default 'info') — MCP log level, 'debug' through 'emergency'"
+            }),
+            returns: json!({"status": "string — 'success'", "message": "string — 'Notification sent: <message>'"}),
+            examples: json!([
+                {"description": "Send a warning", "call": {"message": "ping from the client", "level": "warning"}}
+            ]),
+            extra: None,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::validate::validate;
+    use crate::server::router::tests::model_env;
+    use crate::server::router::tool_definitions;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_every_listed_tool_has_help() {
+        model_env();
+        let listed: BTreeSet<String> = tool_definitions()
+            .into_iter()
+            .map(|t| t.name.to_string())
+            .collect();
+        let documented: BTreeSet<String> = tool_names().into_iter().map(String::from).collect();
+        assert_eq!(listed, documented);
+        assert_eq!(documented.len(), registry().len(), "duplicate help entry");
+    }
+
+    #[test]
+    fn test_notes_cover_exactly_the_schema_properties() {
+        model_env();
+        let mut drift = Vec::new();
+        for tool in tool_definitions() {
+            let help = lookup(&tool.name).unwrap();
+            let properties: BTreeSet<&str> = tool
+                .input_schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map(|p| p.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            let notes = help.params.as_object().unwrap();
+            let noted: BTreeSet<&str> = notes
+                .keys()
+                .map(|k| k.split("[].").next().unwrap())
+                .collect();
+            for undocumented in properties.difference(&noted) {
+                drift.push(format!("{}.{} has no note", tool.name, undocumented));
             }
-        };
+            for stale in noted.difference(&properties) {
+                drift.push(format!(
+                    "{}.{} is not in the input schema",
+                    tool.name, stale
+                ));
+            }
+            if !notes.values().all(Value::is_string) {
+                drift.push(format!("{}: notes must be strings", tool.name));
+            }
+        }
+        assert!(drift.is_empty(), "{drift:#?}");
+    }
 
-        let output = if format == "compact" {
-            // Provide a concise one-paragraph summary
-            json!({
-                "tool": tool,
-                "summary": help.get("description").cloned().unwrap_or(json!("")),
-                "arguments": help.get("arguments").cloned().unwrap_or(json!({}))
-            })
-        } else {
-            help
-        };
+    #[test]
+    fn test_examples_validate_against_the_input_schema() {
+        model_env();
+        let mut failures = Vec::new();
+        for tool in tool_definitions() {
+            let help = lookup(&tool.name).unwrap();
+            let examples = help.examples.as_array().unwrap();
+            if examples.is_empty() {
+                failures.push(format!("{}: no examples", tool.name));
+            }
+            let schema = Value::Object((*tool.input_schema).clone());
+            for example in examples {
+                if !example["description"].is_string() {
+                    failures.push(format!("{}: example without description", tool.name));
+                }
+                if let Err(e) = validate(&schema, &example["call"], &tool.name) {
+                    failures.push(e);
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{failures:#?}");
+    }
 
-        Ok(CallToolResult::structured(output))
+    #[test]
+    fn test_sections_select_parts_of_the_help() {
+        model_env();
+        let tool = tool_definitions()
+            .into_iter()
+            .find(|t| t.name == "think_batch")
+            .unwrap();
+        let help = lookup("think_batch").unwrap();
+
+        let params = help.render(&tool, HelpSection::Params);
+        assert!(params.get("examples").is_none());
+        assert_eq!(params["params"]["thoughts"]["required"], true);
+        assert_eq!(params["params"]["thoughts"]["schema"]["maxItems"], 20);
+        assert!(params["params"]["thoughts"]["fields"]["local_ref"].is_string());
+
+        let examples = help.render(&tool, HelpSection::Examples);
+        assert!(examples.get("params").is_none());
+        assert!(examples["examples"].is_array());
+
+        let all = help.render(&tool, HelpSection::All);
+        for key in ["params", "examples", "description", "returns"] {
+            assert!(all.get(key).is_some(), "{key}");
+        }
     }
 }
@@ -0,0 +1,659 @@
+//! import tool: load a JSONL file written by `export` back into the knowledge graph
+
+use crate::error::{Result, SurrealMindError};
+use crate::server::SurrealMindServer;
+use crate::server::connection::DbConnection;
+use crate::tools::export::EXPORT_TABLES;
+use rmcp::model::{CallToolRequestParams, CallToolResult};
+use serde_json::{Map, Value, json};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use surrealdb::types::{Datetime, Object, RecordId, Value as DbValue};
+
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// Top-level fields stored as datetimes; export writes them as RFC 3339 strings.
+pub const DATETIME_FIELDS: [&str; 6] = [
+    "created_at",
+    "updated_at",
+    "extracted_at",
+    "marked_at",
+    "last_refreshed",
+    "embedded_at",
+];
+
+/// Embedding metadata removed alongside a mismatched vector so the record reads as un-embedded.
+const EMBEDDING_FIELDS: [&str; 5] = [
+    "embedding",
+    "embedding_provider",
+    "embedding_model",
+    "embedding_dim",
+    "embedded_at",
+];
+
+/// What to do when a record id from the file already exists in the database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    #[default]
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ImportParams {
+    pub path: String,
+    #[serde(default)]
+    pub mode: ImportMode,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub batch_size: Option<usize>,
+}
+
+/// One parsed export line.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct ImportLine {
+    pub kind: String,
+    pub id: String,
+    #[serde(default)]
+    pub record: Map<String, Value>,
+}
+
+impl ImportLine {
+    fn table(&self) -> &'static str {
+        table_for_kind(&self.kind).unwrap_or("kg_entities")
+    }
+
+    /// `table:id` reference as written in edge endpoints.
+    fn reference(&self) -> String {
+        format!("{}:{}", self.table(), self.id)
+    }
+}
+
+fn table_for_kind(kind: &str) -> Option<&'static str> {
+    EXPORT_TABLES
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, table)| *table)
+}
+
+/// Parse one non-empty line; `line_no` is 1-based for error messages.
+pub fn parse_line(line: &str, line_no: usize) -> Result<ImportLine> {
+    let parsed: ImportLine =
+        serde_json::from_str(line).map_err(|e| SurrealMindError::Validation {
+            message: format!("line {}: invalid export record: {}", line_no, e),
+        })?;
+    if table_for_kind(&parsed.kind).is_none() {
+        return Err(SurrealMindError::Validation {
+            message: format!("line {}: unknown kind '{}'", line_no, parsed.kind),
+        });
+    }
+    if parsed.id.is_empty() {
+        return Err(SurrealMindError::Validation {
+            message: format!("line {}: missing id", line_no),
+        });
+    }
+    Ok(parsed)
+}
+
+/// Decision for a single record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Overwrite,
+    Rename,
+    Skip,
+}
+
+pub fn plan_action(mode: ImportMode, exists: bool) -> Action {
+    match (exists, mode) {
+        (false, _) => Action::Create,
+        (true, ImportMode::Skip) => Action::Skip,
+        (true, ImportMode::Overwrite) => Action::Overwrite,
+        (true, ImportMode::Rename) => Action::Rename,
+    }
+}
+
+/// Outcome of checking a record's embedding against the active dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingCheck {
+    Absent,
+    Kept,
+    Dropped,
+}
+
+/// Keep an embedding only when it is a numeric array of `expected_dim` values. Mismatched
+/// vectors are removed along with their `embedding_*` metadata so `maintain embed_pending`
+/// picks the record up again.
+pub fn check_embedding(record: &mut Map<String, Value>, expected_dim: usize) -> EmbeddingCheck {
+    let Some(embedding) = record.get("embedding") else {
+        return EmbeddingCheck::Absent;
+    };
+    if embedding.is_null() {
+        record.remove("embedding");
+        return EmbeddingCheck::Absent;
+    }
+    let valid = embedding
+        .as_array()
+        .is_some_and(|v| v.len() == expected_dim && v.iter().all(Value::is_number));
+    if valid {
+        record.insert("embedding_dim".to_string(), json!(expected_dim));
+        EmbeddingCheck::Kept
+    } else {
+        for field in EMBEDDING_FIELDS {
+            record.remove(field);
+        }
+        EmbeddingCheck::Dropped
+    }
+}
+
+fn split_reference(reference: &str) -> Option<(&str, &str)> {
+    reference
+        .split_once(':')
+        .filter(|(tb, id)| !tb.is_empty() && !id.is_empty())
+}
+
+/// Convert a file record into database content: datetime fields become datetimes and the
+/// given edge endpoints become record ids.
+pub fn record_content(record: Map<String, Value>, endpoints: &[(&str, &str)]) -> DbValue {
+    let mut obj = Object::new();
+    for (key, value) in record {
+        if key == "id" {
+            continue;
+        }
+        if let Some(dt) = DATETIME_FIELDS
+            .contains(&key.as_str())
+            .then(|| value.as_str().and_then(|s| s.parse::<Datetime>().ok()))
+            .flatten()
+        {
+            obj.insert(key, dt);
+        } else {
+            obj.insert(key, value);
+        }
+    }
+    for (field, reference) in endpoints {
+        if let Some((tb, id)) = split_reference(reference) {
+            obj.insert(*field, RecordId::new(tb, id));
+        }
+    }
+    DbValue::Object(obj)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct KindCounts {
+    pub created: usize,
+    pub overwritten: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+}
+
+impl KindCounts {
+    fn record(&mut self, action: Action) {
+        match action {
+            Action::Create => self.created += 1,
+            Action::Overwrite => self.overwritten += 1,
+            Action::Rename => self.renamed += 1,
+            Action::Skip => self.skipped += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct UnresolvedEdge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ImportStats {
+    pub lines: usize,
+    pub batches: usize,
+    pub entities: KindCounts,
+    pub observations: KindCounts,
+    pub relationships: KindCounts,
+    pub embeddings_kept: usize,
+    /// Records whose embedding did not match the active dimension and need re-embedding
+    pub reembed_needed: Vec<String>,
+    /// Old `table:id` → new `table:id` for records created under a fresh id
+    pub renamed: HashMap<String, String>,
+    pub unresolved_edges: Vec<UnresolvedEdge>,
+}
+
+impl ImportStats {
+    fn counts(&mut self, kind: &str) -> &mut KindCounts {
+        match kind {
+            "entity" => &mut self.entities,
+            "observation" => &mut self.observations,
+            _ => &mut self.relationships,
+        }
+    }
+}
+
+/// Absolute path to an existing file.
+pub fn validate_import_path(path: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(SurrealMindError::Validation {
+            message: format!("path must be absolute: {}", path.display()),
+        });
+    }
+    if !path.is_file() {
+        return Err(SurrealMindError::not_found(
+            "file",
+            path.display().to_string(),
+        ));
+    }
+    Ok(path)
+}
+
+fn io_error(e: std::io::Error) -> SurrealMindError {
+    SurrealMindError::Internal {
+        message: format!("Failed to read import file: {}", e),
+    }
+}
+
+/// Reads parsed lines of an import file in batches, keeping only one kind group.
+pub struct BatchReader {
+    lines: std::iter::Enumerate<std::io::Lines<BufReader<File>>>,
+    batch_size: usize,
+    relationships: bool,
+}
+
+impl BatchReader {
+    /// `relationships` selects edges (`true`) or entities/observations (`false`).
+    pub fn open(path: &Path, batch_size: usize, relationships: bool) -> Result<Self> {
+        let file = File::open(path).map_err(io_error)?;
+        Ok(Self {
+            lines: BufReader::new(file).lines().enumerate(),
+            batch_size,
+            relationships,
+        })
+    }
+
+    /// Next batch, or `None` at end of file. Malformed lines fail with their line number.
+    pub fn next_batch(&mut self) -> Result<Option<Vec<ImportLine>>> {
+        let mut batch = Vec::with_capacity(self.batch_size);
+        for (idx, line) in self.lines.by_ref() {
+            let line = line.map_err(io_error)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed = parse_line(&line, idx + 1)?;
+            if (parsed.kind == "relationship") == self.relationships {
+                batch.push(parsed);
+                if batch.len() == self.batch_size {
+                    break;
+                }
+            }
+        }
+        Ok((!batch.is_empty()).then_some(batch))
+    }
+}
+
+/// What [`import_graph`] did.
+#[derive(Debug, serde::Serialize)]
+pub struct ImportSummary {
+    pub path: String,
+    pub mode: ImportMode,
+    pub batch_size: usize,
+    pub stats: ImportStats,
+}
+
+/// Import an `export` JSONL file. Every line is validated before anything is written; nodes
+/// are then imported before edges, and embeddings whose length is not `expected_dim` are
+/// dropped and listed in `reembed_needed`.
+pub async fn import_graph(
+    db: &DbConnection,
+    params: &ImportParams,
+    expected_dim: usize,
+) -> Result<ImportSummary> {
+    let path = validate_import_path(&params.path)?;
+    let batch_size = params
+        .batch_size
+        .unwrap_or(DEFAULT_BATCH_SIZE)
+        .clamp(1, MAX_BATCH_SIZE);
+
+    // Validate every line before writing anything
+    let mut lines = 0usize;
+    for relationships in [false, true] {
+        let mut reader = BatchReader::open(&path, MAX_BATCH_SIZE, relationships)?;
+        while let Some(batch) = reader.next_batch()? {
+            lines += batch.len();
+        }
+    }
+
+    let mut stats = ImportStats {
+        lines,
+        ..Default::default()
+    };
+    // Every node reference from the file → the id it lives under after import
+    let mut id_map: HashMap<String, String> = HashMap::new();
+
+    // Nodes first (file order is not trusted), then edges
+    for relationships in [false, true] {
+        let mut reader = BatchReader::open(&path, batch_size, relationships)?;
+        while let Some(batch) = reader.next_batch()? {
+            import_batch(
+                db,
+                batch,
+                params.mode,
+                expected_dim,
+                &mut id_map,
+                &mut stats,
+            )
+            .await?;
+            stats.batches += 1;
+        }
+    }
+
+    Ok(ImportSummary {
+        path: path.display().to_string(),
+        mode: params.mode,
+        batch_size,
+        stats,
+    })
+}
+
+impl SurrealMindServer {
+    /// Handle the import tool call
+    pub async fn handle_import(&self, request: CallToolRequestParams) -> Result<CallToolResult> {
+        let args = request.arguments.ok_or_else(|| SurrealMindError::Mcp {
+            message: "Missing parameters".into(),
+        })?;
+        let params: ImportParams = serde_json::from_value(Value::Object(args)).map_err(|e| {
+            SurrealMindError::InvalidParams {
+                message: format!("Invalid parameters: {}", e),
+                field: None,
+            }
+        })?;
+
+        let (_, _, dim) = self.get_embedding_metadata();
+        let summary = import_graph(&self.db, &params, dim as usize).await?;
+        // Overwritten entities may have changed name under the same id
+        if summary.stats.entities.overwritten > 0 {
+            self.entity_index.write().await.clear();
+        }
+        Ok(CallToolResult::structured(serde_json::to_value(summary)?))
+    }
+}
+
+/// Look up which of `refs` (`table:id`) exist; one round trip per call.
+async fn existing_refs(db: &DbConnection, refs: &[String]) -> Result<HashSet<String>> {
+    if refs.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let mut sql = String::new();
+    let mut vars = Object::new();
+    for (i, reference) in refs.iter().enumerate() {
+        let (tb, id) = split_reference(reference).unwrap_or(("kg_entities", reference));
+        sql.push_str(&format!(
+            "SELECT VALUE meta::id(id) FROM type::record($tb{i}, $id{i});"
+        ));
+        vars.insert(format!("tb{i}"), tb.to_string());
+        vars.insert(format!("id{i}"), id.to_string());
+    }
+    let mut response = db.query(sql).bind(DbValue::Object(vars)).await?;
+    let mut found = HashSet::new();
+    for (i, reference) in refs.iter().enumerate() {
+        let rows: Vec<String> = response.take(i)?;
+        if !rows.is_empty() {
+            found.insert(reference.clone());
+        }
+    }
+    Ok(found)
+}
+
+async fn import_batch(
+    db: &DbConnection,
+    batch: Vec<ImportLine>,
+    mode: ImportMode,
+    expected_dim: usize,
+    id_map: &mut HashMap<String, String>,
+    stats: &mut ImportStats,
+) -> Result<()> {
+    // Resolve edge endpoints that were not imported from this file
+    let mut unknown: Vec<String> = batch
+        .iter()
+        .filter(|l| l.kind == "relationship")
+        .flat_map(|l| {
+            ["source", "target"]
+                .map(|f| l.record.get(f).and_then(Value::as_str).unwrap_or_default())
+        })
+        .filter(|r| !id_map.contains_key(*r))
+        .map(String::from)
+        .collect();
+    unknown.sort();
+    unknown.dedup();
+    let in_db = existing_refs(db, &unknown).await?;
+
+    let refs: Vec<String> = batch.iter().map(ImportLine::reference).collect();
+    let existing = existing_refs(db, &refs).await?;
+
+    let mut sql = String::new();
+    let mut vars = Object::new();
+    // (statement index, old reference, table) for renamed records
+    let mut renames: Vec<(usize, String, &'static str)> = Vec::new();
+    let mut statements = 0usize;
+
+    for (i, mut line) in batch.into_iter().enumerate() {
+        let reference = line.reference();
+        let table = line.table();
+
+        let mut endpoints: Vec<(&str, String)> = Vec::new();
+        if line.kind == "relationship" {
+            let mut missing = Vec::new();
+            for field in ["source", "target"] {
+                let old = line
+                    .record
+                    .get(field)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                match id_map.get(&old) {
+                    Some(new) => endpoints.push((field, new.clone())),
+                    None if in_db.contains(&old) => endpoints.push((field, old)),
+                    None => missing.push(old),
+                }
+            }
+            if !missing.is_empty() {
+                stats.unresolved_edges.push(UnresolvedEdge {
+                    id: line.id,
+                    source: line.record["source"].as_str().unwrap_or_default().into(),
+                    target: line.record["target"].as_str().unwrap_or_default().into(),
+                    missing,
+                });
+                continue;
+            }
+        }
+
+        let action = plan_action(mode, existing.contains(&reference));
+        stats.counts(&line.kind).record(action);
+        if action == Action::Skip {
+            id_map.insert(reference.clone(), reference);
+            continue;
+        }
+
+        match check_embedding(&mut line.record, expected_dim) {
+            EmbeddingCheck::Kept => stats.embeddings_kept += 1,
+            EmbeddingCheck::Dropped => stats.reembed_needed.push(reference.clone()),
+            EmbeddingCheck::Absent => {}
+        }
+
+        let endpoints: Vec<(&str, &str)> =
+            endpoints.iter().map(|(f, r)| (*f, r.as_str())).collect();
+        vars.insert(format!("c{i}"), record_content(line.record, &endpoints));
+        vars.insert(format!("tb{i}"), table.to_string());
+        vars.insert(format!("id{i}"), line.id);
+        match action {
+            Action::Create => {
+                sql.push_str(&format!(
+                    "CREATE type::record($tb{i}, $id{i}) CONTENT $c{i} RETURN NONE;"
+                ));
+                id_map.insert(reference.clone(), reference);
+            }
+            Action::Overwrite => {
+                sql.push_str(&format!(
+                    "UPSERT type::record($tb{i}, $id{i}) CONTENT $c{i} RETURN NONE;"
+                ));
+                id_map.insert(reference.clone(), reference);
+            }
+            Action::Rename => {
+                sql.push_str(&format!(
+                    "CREATE type::table($tb{i}) CONTENT $c{i} RETURN VALUE meta::id(id);"
+                ));
+                renames.push((statements, reference, table));
+            }
+            Action::Skip => unreachable!(),
+        }
+        statements += 1;
+    }
+
+    if statements == 0 {
+        return Ok(());
+    }
+    let mut response = db.query(sql).bind(DbValue::Object(vars)).await?.check()?;
+    for (idx, old, table) in renames {
+        let ids: Vec<String> = response.take(idx)?;
+        let new_id = ids
+            .into_iter()
+            .next()
+            .ok_or_else(|| SurrealMindError::Database {
+                message: format!("rename of {} returned no id", old),
+            })?;
+        let new_ref = format!("{}:{}", table, new_id);
+        id_map.insert(old.clone(), new_ref.clone());
+        stats.renamed.insert(old, new_ref);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obj(v: Value) -> Map<String, Value> {
+        v.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_parse_line_accepts_export_records() {
+        let line = parse_line(
+            r#"{"kind":"relationship","id":"e1","record":{"source":"kg_entities:a","target":"kg_entities:b","rel_type":"knows"}}"#,
+            1,
+        )
+        .unwrap();
+        assert_eq!(line.table(), "kg_edges");
+        assert_eq!(line.reference(), "kg_edges:e1");
+        assert_eq!(line.record["rel_type"], json!("knows"));
+    }
+
+    #[test]
+    fn test_parse_line_reports_line_numbers() {
+        let bad_kind = parse_line(r#"{"kind":"thought","id":"x"}"#, 7).unwrap_err();
+        assert!(
+            bad_kind
+                .to_string()
+                .contains("line 7: unknown kind 'thought'")
+        );
+        let bad_json = parse_line("{not json", 3).unwrap_err();
+        assert!(bad_json.to_string().contains("line 3"));
+        assert!(parse_line(r#"{"kind":"entity","id":""}"#, 2).is_err());
+    }
+
+    #[test]
+    fn test_plan_action_by_mode() {
+        for mode in [ImportMode::Skip, ImportMode::Overwrite, ImportMode::Rename] {
+            assert_eq!(plan_action(mode, false), Action::Create);
+        }
+        assert_eq!(plan_action(ImportMode::Skip, true), Action::Skip);
+        assert_eq!(plan_action(ImportMode::Overwrite, true), Action::Overwrite);
+        assert_eq!(plan_action(ImportMode::Rename, true), Action::Rename);
+    }
+
+    #[test]
+    fn test_check_embedding_keeps_matching_and_drops_mismatched() {
+        let mut ok = obj(json!({"embedding": [0.1, 0.2, 0.3], "embedding_model": "m"}));
+        assert_eq!(check_embedding(&mut ok, 3), EmbeddingCheck::Kept);
+        assert_eq!(ok["embedding_dim"], json!(3));
+
+        let mut wrong = obj(json!({
+            "name": "n",
+            "embedding": [0.1, 0.2],
+            "embedding_provider": "openai",
+            "embedding_model": "m",
+            "embedding_dim": 2,
+            "embedded_at": "2026-01-01T00:00:00Z"
+        }));
+        assert_eq!(check_embedding(&mut wrong, 3), EmbeddingCheck::Dropped);
+        assert_eq!(wrong, obj(json!({"name": "n"})));
+
+        let mut none = obj(json!({"name": "n"}));
+        assert_eq!(check_embedding(&mut none, 3), EmbeddingCheck::Absent);
+    }
+
+    #[test]
+    fn test_record_content_converts_datetimes_and_endpoints() {
+        let content = record_content(
+            obj(json!({
+                "id": "kg_edges:e1",
+                "created_at": "2026-01-02T03:04:05Z",
+                "rel_type": "knows",
+                "source": "kg_entities:old",
+                "target": "kg_entities:b",
+                "data": {"created_at": "2026-01-02T03:04:05Z"}
+            })),
+            &[("source", "kg_entities:new"), ("target", "kg_entities:b")],
+        );
+        let DbValue::Object(obj) = content else {
+            panic!("expected object");
+        };
+        assert!(!obj.contains_key("id"));
+        assert!(matches!(obj["created_at"], DbValue::Datetime(_)));
+        assert_eq!(
+            obj["source"],
+            DbValue::RecordId(RecordId::new("kg_entities", "new"))
+        );
+        assert_eq!(obj["rel_type"], DbValue::String("knows".into()));
+        // Only top-level fields are converted
+        assert_eq!(
+            obj["data"].clone().into_json_value(),
+            json!({"created_at": "2026-01-02T03:04:05Z"})
+        );
+    }
+
+    #[test]
+    fn test_batch_reader_filters_and_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kg.jsonl");
+        let mut body = String::new();
+        for i in 0..5 {
+            body.push_str(&format!(r#"{{"kind":"entity","id":"e{i}","record":{{}}}}"#));
+            body.push('\n');
+        }
+        body.push('\n');
+        body.push_str(r#"{"kind":"relationship","id":"r","record":{}}"#);
+        std::fs::write(&path, body).unwrap();
+
+        let mut reader = BatchReader::open(&path, 2, false).unwrap();
+        let mut sizes = Vec::new();
+        while let Some(batch) = reader.next_batch().unwrap() {
+            sizes.push(batch.len());
+        }
+        assert_eq!(sizes, vec![2, 2, 1]);
+        let mut edges = BatchReader::open(&path, 2, true).unwrap();
+        assert_eq!(edges.next_batch().unwrap().unwrap()[0].id, "r");
+        assert!(edges.next_batch().unwrap().is_none());
+
+        assert!(validate_import_path("kg.jsonl").is_err());
+        assert!(validate_import_path(dir.path().to_str().unwrap()).is_err());
+        assert_eq!(validate_import_path(path.to_str().unwrap()).unwrap(), path);
+    }
+}
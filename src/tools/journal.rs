@@ -6,6 +6,7 @@
 
 use crate::error::{Result, SurrealMindError};
 use crate::server::SurrealMindServer;
+use crate::utils::lang::detect_lang;
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde_json::json;
 
@@ -63,6 +64,7 @@ impl SurrealMindServer {
         let params: JournalParams = serde_json::from_value(serde_json::Value::Object(args))
             .map_err(|e| SurrealMindError::InvalidParams {
                 message: format!("Invalid parameters: {}", e),
+                field: None,
             })?;
 
         match params.mode.as_str() {
@@ -218,6 +220,7 @@ impl SurrealMindServer {
 
             // Auto-embed the new thread entity
             if !id.is_empty() {
+                self.index_entity(&id, thread_name, "research_thread").await;
                 let data = json!({"entity_type": "research_thread", "description": thread_name});
                 if let Err(e) = self
                     .ensure_kg_embedding("kg_entities", &id, thread_name, &data)
@@ -249,10 +252,12 @@ impl SurrealMindServer {
                  confidence = $conf, \
                  tags = $tags, \
                  author = $author, \
-                 observation_type = $obs_type \
+                 observation_type = $obs_type, \
+                 lang = $lang \
                  RETURN meta::id(id) as id, type::string(created_at) as created_at",
             )
             .bind(("name", resolved_thread_name.clone()))
+            .bind(("lang", detect_lang(content)))
             .bind(("data", obs_data.clone()))
             .bind(("conf", confidence))
             .bind(("tags", tags.clone()))
@@ -329,9 +334,7 @@ impl SurrealMindServer {
         let thread = self
             .resolve_journal_thread(thread_ref_or_name)
             .await?
-            .ok_or_else(|| SurrealMindError::Validation {
-                message: format!("Thread not found: {}", thread_ref_or_name),
-            })?;
+            .ok_or_else(|| SurrealMindError::not_found("thread", thread_ref_or_name))?;
         let thread_ref = format!("kg_entities:{}", thread.id);
 
         // Fetch observations — no ORDER BY in SQL (surrealdb crate bug: ORDER BY created_at
@@ -479,9 +482,7 @@ impl SurrealMindServer {
         let thread = self
             .resolve_journal_thread(thread_ref_or_name)
             .await?
-            .ok_or_else(|| SurrealMindError::Validation {
-                message: format!("Thread not found: {}", thread_ref_or_name),
-            })?;
+            .ok_or_else(|| SurrealMindError::not_found("thread", thread_ref_or_name))?;
         let thread_id = thread.id;
         let thread_name = thread.name;
         let current_status = thread
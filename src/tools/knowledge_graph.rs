@@ -1,12 +1,189 @@
 //! Knowledge graph tool handlers for creating and searching entities/relationships
 
+use crate::config::KgConfig;
+use crate::embeddings::EmbedPurpose;
 use crate::error::{Result, SurrealMindError};
+use crate::maintenance::observation_provenance::{
+    attach_provenance, observation_source_ids, provenance, resolve_thoughts,
+};
+use crate::maintenance::observations::observation_text;
 use crate::server::SurrealMindServer;
+use crate::utils::dates::parse_date_bound;
+use crate::utils::lang::detect_lang;
+use chrono::{DateTime, Utc};
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde_json::json;
+use std::collections::HashMap;
+
+/// Entity name comparison key: lowercase, with runs of punctuation, `-`, `_`, and whitespace
+/// collapsed to single spaces ("Surreal-Mind" and "surreal_mind" both become "surreal mind").
+pub(crate) fn normalize_entity_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Stored entity_type form: lowercase, spaces to underscores, trimmed.
+pub(crate) fn normalize_entity_type(entity_type: &str) -> String {
+    entity_type
+        .to_lowercase()
+        .replace(' ', "_")
+        .trim()
+        .to_string()
+}
+
+/// Taxonomy entry closest to `entity_type` by Jaro-Winkler over the normalized form, with
+/// its similarity; the earliest entry wins ties.
+pub(crate) fn nearest_entity_type<'a>(
+    entity_type: &str,
+    taxonomy: &'a [String],
+) -> Option<(&'a str, f64)> {
+    let normalized = normalize_entity_type(entity_type);
+    taxonomy
+        .iter()
+        .rev()
+        .map(|t| (t.as_str(), strsim::jaro_winkler(&normalized, t)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Check `raw` against the `[kg]` taxonomy and store its normalized form as
+/// `data.entity_type`, keeping a differing original in `data.entity_type_raw`. With
+/// `enforce_taxonomy`, types outside the taxonomy are rejected with the nearest valid one.
+pub(crate) fn apply_entity_taxonomy(
+    kg: &KgConfig,
+    raw: &str,
+    data: &mut serde_json::Value,
+) -> Result<String> {
+    let normalized = normalize_entity_type(raw);
+    if kg.enforce_taxonomy && !kg.has_entity_type(&normalized) {
+        let hint = nearest_entity_type(raw, &kg.entity_types)
+            .map(|(t, _)| format!("; nearest valid type is '{}'", t))
+            .unwrap_or_default();
+        return Err(SurrealMindError::Validation {
+            message: format!("entity_type '{}' is not in the [kg] taxonomy{}", raw, hint),
+        });
+    }
+    if let Some(obj) = data.as_object_mut() {
+        obj.insert("entity_type".into(), json!(normalized));
+        if raw != normalized {
+            obj.insert("entity_type_raw".into(), json!(raw));
+        }
+    }
+    Ok(normalized)
+}
+
+/// Strength of a relationship created without one.
+pub(crate) const DEFAULT_EDGE_STRENGTH: f32 = 1.0;
+
+/// `strength` and validity window of a relationship, stored as top-level `kg_edges` fields.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct EdgeAttributes {
+    pub strength: Option<f32>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_to: Option<DateTime<Utc>>,
+}
+
+impl EdgeAttributes {
+    /// Move `strength`, `valid_from`, and `valid_to` out of a relationship's `data`. Strength
+    /// must be a non-negative number; the dates take the search date forms, a day `valid_to`
+    /// covering the whole day, and the window must not end before it starts.
+    pub(crate) fn take(data: &mut serde_json::Value, now: DateTime<Utc>) -> Result<Self> {
+        let Some(obj) = data.as_object_mut() else {
+            return Ok(Self::default());
+        };
+        let strength = match obj.remove("strength") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(v) => Some(
+                v.as_f64()
+                    .filter(|s| s.is_finite() && *s >= 0.0)
+                    .ok_or_else(|| {
+                        SurrealMindError::invalid_field(
+                            "strength",
+                            format!("strength must be a non-negative number, got {}", v),
+                        )
+                    })? as f32,
+            ),
+        };
+        let mut date = |key: &str, end_of_day: bool| -> Result<Option<DateTime<Utc>>> {
+            match obj.remove(key) {
+                None | Some(serde_json::Value::Null) => Ok(None),
+                Some(serde_json::Value::String(v)) => {
+                    parse_date_bound(&v, end_of_day, key, now).map(Some)
+                }
+                Some(v) => Err(SurrealMindError::invalid_field(
+                    key,
+                    format!("{} must be a date string, got {}", key, v),
+                )),
+            }
+        };
+        let valid_from = date("valid_from", false)?;
+        let valid_to = date("valid_to", true)?;
+        if let (Some(from), Some(to)) = (valid_from, valid_to)
+            && from > to
+        {
+            return Err(SurrealMindError::invalid_field(
+                "valid_to",
+                "valid_to is before valid_from",
+            ));
+        }
+        Ok(Self {
+            strength,
+            valid_from,
+            valid_to,
+        })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// `SET` assignments for the attributes present, bound by [`Self::bind`].
+    fn assignments(&self) -> String {
+        [
+            self.strength.map(|_| "strength = $strength"),
+            self.valid_from.map(|_| "valid_from = $valid_from"),
+            self.valid_to.map(|_| "valid_to = $valid_to"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+
+    fn bind<'a>(
+        &self,
+        mut query: crate::server::connection::DbQuery<'a>,
+    ) -> crate::server::connection::DbQuery<'a> {
+        if let Some(strength) = self.strength {
+            query = query.bind(("strength", strength));
+        }
+        for (key, at) in [("valid_from", self.valid_from), ("valid_to", self.valid_to)] {
+            if let Some(at) = at {
+                query = query.bind((key, surrealdb::types::Datetime::from(at)));
+            }
+        }
+        query
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let mut out = serde_json::Map::new();
+        if let Some(strength) = self.strength {
+            out.insert("strength".into(), json!(strength));
+        }
+        for (key, at) in [("valid_from", self.valid_from), ("valid_to", self.valid_to)] {
+            if let Some(at) = at {
+                out.insert(key.into(), json!(at.to_rfc3339()));
+            }
+        }
+        serde_json::Value::Object(out)
+    }
+}
 
 impl SurrealMindServer {
-    /// Handle the knowledgegraph_create tool call
+    /// Handle the knowledgegraph_create tool call.
+    /// Accepts a single `{kind, data, upsert}` payload or a batch via `items`.
     pub async fn handle_knowledgegraph_create(
         &self,
         request: CallToolRequestParams,
@@ -15,6 +192,135 @@ impl SurrealMindServer {
             message: "Missing parameters".into(),
         })?;
 
+        if let Some(items) = args.get("items") {
+            let items = items
+                .as_array()
+                .ok_or_else(|| SurrealMindError::Validation {
+                    message: "items must be an array of {kind, data, upsert} objects".into(),
+                })?;
+            let atomic = args
+                .get("atomic")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let default_upsert = args.get("upsert").and_then(|v| v.as_bool());
+            return self
+                .create_kg_batch(items, atomic, default_upsert)
+                .await
+                .map(CallToolResult::structured);
+        }
+
+        let result = self.create_kg_item(&args, &mut HashMap::new()).await?;
+        Ok(CallToolResult::structured(result))
+    }
+
+    /// Create batch items in order. Names created earlier in the batch resolve for later
+    /// relationships. With `atomic`, the first failure rolls back items created by this
+    /// batch and reports the failing index; otherwise failures are reported per item.
+    async fn create_kg_batch(
+        &self,
+        items: &[serde_json::Value],
+        atomic: bool,
+        default_upsert: Option<bool>,
+    ) -> Result<serde_json::Value> {
+        let mut batch_names: HashMap<String, String> = HashMap::new();
+        let mut results: Vec<serde_json::Value> = Vec::with_capacity(items.len());
+        let mut errors = 0usize;
+
+        for (index, item) in items.iter().enumerate() {
+            let outcome = match item.as_object() {
+                Some(obj) => {
+                    let mut obj = obj.clone();
+                    if let Some(upsert) = default_upsert {
+                        obj.entry("upsert").or_insert(json!(upsert));
+                    }
+                    self.create_kg_item(&obj, &mut batch_names).await
+                }
+                None => Err(SurrealMindError::Validation {
+                    message: format!("items[{}] must be an object", index),
+                }),
+            };
+
+            match outcome {
+                Ok(result) => results.push(result),
+                Err(e) if atomic => {
+                    let rolled_back = self.rollback_kg_batch(&results).await;
+                    return Ok(json!({
+                        "items": results,
+                        "aborted": true,
+                        "failed_index": index,
+                        "error": e.to_string(),
+                        "rolled_back": rolled_back
+                    }));
+                }
+                Err(e) => {
+                    errors += 1;
+                    let kind = item
+                        .get("kind")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("entity");
+                    results.push(json!({
+                        "kind": kind,
+                        "id": null,
+                        "created": false,
+                        "error": e.to_string()
+                    }));
+                }
+            }
+        }
+
+        Ok(json!({
+            "items": results,
+            "count": items.len(),
+            "errors": errors
+        }))
+    }
+
+    /// Delete records created (not matched via upsert) earlier in an aborted batch.
+    /// Returns how many were removed.
+    async fn rollback_kg_batch(&self, results: &[serde_json::Value]) -> usize {
+        let mut removed = 0usize;
+        for result in results.iter().rev() {
+            if result.get("created").and_then(|v| v.as_bool()) != Some(true) {
+                continue;
+            }
+            let (Some(kind), Some(id)) = (
+                result.get("kind").and_then(|v| v.as_str()),
+                result.get("id").and_then(|v| v.as_str()),
+            ) else {
+                continue;
+            };
+            let table = match kind {
+                "entity" => "kg_entities",
+                "relationship" => "kg_edges",
+                "observation" => "kg_observations",
+                _ => continue,
+            };
+            match self
+                .db
+                .query("DELETE type::record($tb, $id) RETURN NONE;")
+                .bind(("tb", table))
+                .bind(("id", id.to_string()))
+                .await
+            {
+                Ok(_) => removed += 1,
+                Err(e) => tracing::warn!(
+                    "kg batch rollback: failed to delete {}:{}: {}",
+                    table,
+                    id,
+                    e
+                ),
+            }
+        }
+        removed
+    }
+
+    /// Create (or upsert) a single KG item from a `{kind, data, upsert}` payload.
+    /// `batch_names` maps names created in the current batch to record ids.
+    pub(crate) async fn create_kg_item(
+        &self,
+        args: &serde_json::Map<String, serde_json::Value>,
+        batch_names: &mut HashMap<String, String>,
+    ) -> Result<serde_json::Value> {
         let kind_s: String = args
             .get("kind")
             .and_then(|v| v.as_str())
@@ -37,6 +343,8 @@ impl SurrealMindServer {
         let mut id: String = "".to_string();
         #[allow(unused_assignments)]
         let mut name: String = "".to_string();
+        let mut observation_provenance = None;
+        let mut edge_attributes = None;
 
         match kind_s.as_str() {
             "entity" => {
@@ -50,25 +358,14 @@ impl SurrealMindServer {
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string());
 
-                // Normalize entity_type: lowercase, spaces to underscores, trimmed
-                let entity_type_s = entity_type_s.map(|et| {
-                    et.to_lowercase().replace(' ', "_").trim().to_string()
-                });
+                let entity_type_s = entity_type_s
+                    .map(|raw| apply_entity_taxonomy(&self.config.kg, &raw, &mut data))
+                    .transpose()?;
 
                 // Try upsert: find existing by name only (entity_type varies too much to be reliable for dedup)
-                if upsert {
-                    let sql =
-                        "SELECT meta::id(id) as id FROM kg_entities WHERE name = $name LIMIT 1".to_string();
-                    let q = self.db.query(sql).bind(("name", name_s.clone()));
-                    let found: Vec<serde_json::Value> = q.await?.take(0)?;
-                    if let Some(idv) = found
-                        .first()
-                        .and_then(|v| v.get("id"))
-                        .and_then(|v| v.as_str())
-                    {
-                        let result = json!({"kind": kind_s, "id": idv, "created": false});
-                        return Ok(CallToolResult::structured(result));
-                    }
+                if upsert && let Some(idv) = self.entity_id_by_name(&name_s).await? {
+                    batch_names.insert(name_s, format!("kg_entities:{}", idv));
+                    return Ok(json!({"kind": kind_s, "id": idv, "created": false}));
                 }
 
                 // Create new entity; also store entity_type as top-level convenience if present
@@ -86,6 +383,13 @@ impl SurrealMindServer {
                     .and_then(|v| v.as_str())
                     .unwrap_or("")
                     .to_string();
+                batch_names.insert(name_s.clone(), format!("kg_entities:{}", entity_id));
+                self.index_entity(
+                    &entity_id,
+                    &name_s,
+                    entity_type_s.as_deref().unwrap_or_default(),
+                )
+                .await;
                 id = entity_id;
                 name = name_s;
             }
@@ -109,6 +413,7 @@ impl SurrealMindServer {
                     .and_then(|v| v.as_str())
                     .unwrap_or("related_to")
                     .to_string();
+                let attributes = EdgeAttributes::take(&mut data, Utc::now())?;
 
                 tracing::debug!(
                     "Attempting to create relationship: src: '{}', dst: '{}', kind: '{}'",
@@ -118,8 +423,17 @@ impl SurrealMindServer {
                 );
 
                 // 1. Resolve KG refs to (table, key) pairs
-                let src_resolved = self.resolve_kg_item(&src_s).await?;
-                let dst_resolved = self.resolve_kg_item(&dst_s).await?;
+                // Names created earlier in the same batch resolve directly
+                let batch_ref = |s: &String| {
+                    batch_names
+                        .get(s)
+                        .filter(|_| !s.is_empty())
+                        .cloned()
+                        .unwrap_or_else(|| s.clone())
+                };
+                let (src_ref, dst_ref) = (batch_ref(&src_s), batch_ref(&dst_s));
+                let src_resolved = self.resolve_kg_item(&src_ref).await?;
+                let dst_resolved = self.resolve_kg_item(&dst_ref).await?;
 
                 let ((src_tb, src_id), (dst_tb, dst_id)) = match (src_resolved, dst_resolved) {
                     (Some(s), Some(d)) => (s, d),
@@ -147,38 +461,85 @@ impl SurrealMindServer {
                 if let Some(rel_row) = existing_rel.first()
                     && let Some(rel_id) = rel_row.get("id").and_then(|v| v.as_str())
                 {
-                    let result = json!({"kind": kind_s, "id": rel_id, "created": false});
-                    return Ok(CallToolResult::structured(result));
+                    // Same endpoints and rel_type is the same edge; refresh its attributes
+                    let mut existing = json!({"kind": kind_s, "id": rel_id, "created": false});
+                    if !attributes.is_empty() {
+                        attributes
+                            .bind(
+                                self.db
+                                    .query(format!(
+                                        "UPDATE type::record('kg_edges', $id) SET {} RETURN NONE",
+                                        attributes.assignments()
+                                    ))
+                                    .bind(("id", rel_id.to_string())),
+                            )
+                            .await?
+                            .check()?;
+                        existing["updated"] = attributes.to_json();
+                    }
+                    return Ok(existing);
                 }
 
                 // 3. Create new relationship
-                let created_rel: Vec<serde_json::Value> = self
+                let attributes = EdgeAttributes {
+                    strength: attributes.strength.or(Some(DEFAULT_EDGE_STRENGTH)),
+                    ..attributes
+                };
+                let created_rel: Vec<serde_json::Value> = attributes
+                    .bind(self
                     .db
-                    .query("CREATE kg_edges SET created_at = time::now(), source = type::record($stb, $sid), target = type::record($dtb, $did), rel_type = $rel, data = $data RETURN meta::id(id) as id, rel_type, type::string(created_at) as created_at;")
+                    .query(format!("CREATE kg_edges SET created_at = time::now(), source = type::record($stb, $sid), target = type::record($dtb, $did), rel_type = $rel, data = $data, {} RETURN meta::id(id) as id, rel_type, type::string(created_at) as created_at;", attributes.assignments()))
                     .bind(("stb", src_tb))
                     .bind(("sid", src_id.replace("\"", "")))
                     .bind(("dtb", dst_tb))
                     .bind(("did", dst_id.replace("\"", "")))
                     .bind(("rel", rel_kind_s))
-                    .bind(("data", data.clone()))
+                    .bind(("data", data.clone())))
                     .await?
                     .take(0)?;
+                edge_attributes = Some(attributes.to_json());
                 let rel_id = created_rel
                     .first()
                     .and_then(|v| v.get("id"))
                     .and_then(|v| v.as_str())
                     .unwrap_or("")
                     .to_string();
-                // Relationships don't need embedding
                 id = rel_id;
                 name = "".to_string();
             }
             "observation" => {
-                let source_thought_id_s: String = data
-                    .get("source_thought_id")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
+                let args_value = serde_json::Value::Object(args.clone());
+                let source_ids = observation_source_ids(&[&data, &args_value]);
+                let resolved = resolve_thoughts(&self.db.client(), &source_ids)
+                    .await
+                    .map_err(|e| SurrealMindError::Database {
+                        message: format!("source thought lookup failed: {}", e),
+                    })?;
+                let unresolved: Vec<&String> = source_ids
+                    .iter()
+                    .filter(|id| !resolved.contains_key(*id))
+                    .collect();
+                if self.config.kg.require_observation_provenance {
+                    if source_ids.is_empty() {
+                        return Err(SurrealMindError::Validation {
+                            message: "observation needs source_thought_id or source_thought_ids ([kg] require_observation_provenance)".into(),
+                        });
+                    }
+                    if !unresolved.is_empty() {
+                        return Err(SurrealMindError::Validation {
+                            message: format!(
+                                "source thoughts not found: {:?} ([kg] require_observation_provenance)",
+                                unresolved
+                            ),
+                        });
+                    }
+                } else if source_ids.is_empty() {
+                    tracing::warn!("kg.observation.no_provenance");
+                } else if !unresolved.is_empty() {
+                    // Preserved as given, like unresolved continuity links
+                    tracing::warn!(unresolved = ?unresolved, "kg.observation.unresolved_provenance");
+                }
+                let source_thought_id_s = source_ids.first().cloned().unwrap_or_default();
                 let confidence_f = data
                     .get("confidence")
                     .and_then(|v| v.as_f64())
@@ -202,17 +563,19 @@ impl SurrealMindServer {
                     if let Some(obs_row) = existing_obs.first()
                         && let Some(obs_id) = obs_row.get("id").and_then(|v| v.as_str())
                     {
-                        let result = json!({"kind": kind_s, "id": obs_id, "created": false});
-                        return Ok(CallToolResult::structured(result));
+                        batch_names.insert(name_s, format!("kg_observations:{}", obs_id));
+                        return Ok(json!({"kind": kind_s, "id": obs_id, "created": false}));
                     }
                 }
 
                 let created_raw: Vec<serde_json::Value> = self
                     .db
-                    .query("CREATE kg_observations SET created_at = time::now(), name = $name, data = $data, source_thought_id = $src, confidence = $conf RETURN meta::id(id) as id, name, data, type::string(created_at) as created_at;")
+                    .query("CREATE kg_observations SET created_at = time::now(), name = $name, data = $data, source_thought_id = $src, source_thought_ids = $src_ids, confidence = $conf, lang = $lang RETURN meta::id(id) as id, name, data, type::string(created_at) as created_at;")
+                    .bind(("lang", detect_lang(&observation_text(&json!({"name": name_s, "data": data})))))
                     .bind(("name", name_s.clone()))
                     .bind(("data", data.clone()))
                     .bind(("src", source_thought_id_s))
+                    .bind(("src_ids", (!source_ids.is_empty()).then(|| source_ids.clone())))
                     .bind(("conf", confidence_f))
                     .await?
                     .take(0)?;
@@ -222,8 +585,10 @@ impl SurrealMindServer {
                     .and_then(|v| v.as_str())
                     .unwrap_or("")
                     .to_string();
+                batch_names.insert(name_s.clone(), format!("kg_observations:{}", obs_id));
                 id = obs_id;
                 name = name_s;
+                observation_provenance = Some(provenance(&source_ids, &resolved));
             }
             _ => {
                 return Err(SurrealMindError::Validation {
@@ -244,25 +609,226 @@ impl SurrealMindServer {
                     e
                 );
             }
-        } else if kind_s == "observation"
-            && let Err(e) = self
+        } else if kind_s == "observation" {
+            if let Err(e) = self
                 .ensure_kg_embedding("kg_observations", &id, &name, &data)
                 .await
+            {
+                tracing::warn!(
+                    "kg_embedding: failed to auto-embed created observation {}: {}",
+                    id,
+                    e
+                );
+            }
+        } else if kind_s == "relationship"
+            && let Err(e) = self.ensure_edge_embedding(&id).await
         {
             tracing::warn!(
-                "kg_embedding: failed to auto-embed created observation {}: {}",
+                "kg_embedding: failed to auto-embed created relationship {}: {}",
                 id,
                 e
             );
         }
 
-        let result = json!({
+        let mut created = json!({
             "kind": kind_s,
             "id": id,
             "created": true
         });
+        if let Some(provenance) = observation_provenance {
+            created["provenance"] = provenance;
+        }
+        if let Some(serde_json::Value::Object(attributes)) = edge_attributes {
+            created.as_object_mut().unwrap().extend(attributes);
+        }
+        Ok(created)
+    }
 
-        Ok(CallToolResult::structured(result))
+    /// Handle the forget tool call: delete an entity, relationship, or observation.
+    /// Entities/observations still referenced by edges are refused unless `cascade` is set.
+    /// Every removed record is snapshotted into `kg_deletions` for recovery.
+    pub async fn handle_knowledgegraph_delete(
+        &self,
+        request: CallToolRequestParams,
+    ) -> Result<CallToolResult> {
+        let args = request.arguments.ok_or_else(|| SurrealMindError::Mcp {
+            message: "Missing parameters".into(),
+        })?;
+
+        let kind_s = args
+            .get("kind")
+            .and_then(|v| v.as_str())
+            .unwrap_or("entity")
+            .to_string();
+        let table = match kind_s.as_str() {
+            "entity" => "kg_entities",
+            "relationship" => "kg_edges",
+            "observation" => "kg_observations",
+            _ => {
+                return Err(SurrealMindError::Validation {
+                    message: format!("Unsupported KG kind: {}", kind_s),
+                });
+            }
+        };
+        let cascade = args
+            .get("cascade")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let reason = args
+            .get("reason")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let id = match args.get("id").and_then(|v| v.as_str()) {
+            Some(raw) => raw
+                .strip_prefix(&format!("{}:", table))
+                .unwrap_or(raw)
+                .to_string(),
+            None => self.resolve_delete_target(table, &args).await?,
+        };
+
+        let exists: Vec<serde_json::Value> = self
+            .db
+            .query("SELECT meta::id(id) as id FROM type::record($tb, $id)")
+            .bind(("tb", table))
+            .bind(("id", id.clone()))
+            .await?
+            .take(0)?;
+        if exists.is_empty() {
+            return Err(SurrealMindError::not_found(kind_s, id));
+        }
+
+        // Edges pointing at this record block deletion unless cascading
+        let mut edge_ids: Vec<String> = Vec::new();
+        if table != "kg_edges" {
+            let rows: Vec<serde_json::Value> = self
+                .db
+                .query("SELECT meta::id(id) as id FROM kg_edges WHERE source = type::record($tb, $id) OR target = type::record($tb, $id)")
+                .bind(("tb", table))
+                .bind(("id", id.clone()))
+                .await?
+                .take(0)?;
+            edge_ids = rows
+                .iter()
+                .filter_map(|r| r.get("id").and_then(|v| v.as_str()).map(String::from))
+                .collect();
+            if !edge_ids.is_empty() && !cascade {
+                return Err(SurrealMindError::Conflict {
+                    message:
+                        "Record is still referenced by edges; pass cascade=true to delete them too"
+                            .into(),
+                    data: Some(json!({
+                        "kind": kind_s,
+                        "id": id,
+                        "blocking_edges": edge_ids
+                    })),
+                });
+            }
+        }
+
+        self.delete_kg_record(&kind_s, table, &id, &edge_ids, &reason)
+            .await?;
+
+        Ok(CallToolResult::structured(json!({
+            "kind": kind_s,
+            "id": id,
+            "deleted": true,
+            "cascaded_edges": edge_ids
+        })))
+    }
+
+    /// Resolve a delete target by `name` (plus optional `entity_type`), refusing ambiguous matches.
+    async fn resolve_delete_target(
+        &self,
+        table: &str,
+        args: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<String> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .filter(|_| table != "kg_edges")
+            .ok_or_else(|| SurrealMindError::Validation {
+                message: if table == "kg_edges" {
+                    "Relationships must be deleted by id".into()
+                } else {
+                    "Provide either id or name".into()
+                },
+            })?;
+        let entity_type = args
+            .get("entity_type")
+            .and_then(|v| v.as_str())
+            .map(normalize_entity_type);
+
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query("SELECT meta::id(id) as id FROM type::table($tb) WHERE name = $name AND ($etype = NONE OR entity_type = $etype) LIMIT 10")
+            .bind(("tb", table.to_string()))
+            .bind(("name", name.to_string()))
+            .bind(("etype", entity_type))
+            .await?
+            .take(0)?;
+        let ids: Vec<String> = rows
+            .iter()
+            .filter_map(|r| r.get("id").and_then(|v| v.as_str()).map(String::from))
+            .collect();
+        match ids.as_slice() {
+            [only] => Ok(only.clone()),
+            [] => Err(SurrealMindError::Validation {
+                message: format!("No record named '{}' in {}", name, table),
+            }),
+            _ => Err(SurrealMindError::Validation {
+                message: format!(
+                    "Name '{}' matches {} records in {} ({}); pass id or entity_type",
+                    name,
+                    ids.len(),
+                    table,
+                    ids.join(", ")
+                ),
+            }),
+        }
+    }
+
+    /// Snapshot a record and its cascaded `edges` into `kg_deletions` and delete them all in
+    /// one transaction. Aborts, deleting nothing, if an edge not in `edges` still references
+    /// the record by then.
+    async fn delete_kg_record(
+        &self,
+        kind: &str,
+        table: &str,
+        id: &str,
+        edges: &[String],
+        reason: &Option<String>,
+    ) -> Result<()> {
+        self.db
+            .query(
+                "BEGIN TRANSACTION;
+                 LET $target = type::record($tb, $id);
+                 FOR $edge_id IN $edges {
+                     LET $edge = (SELECT * OMIT embedding FROM type::record('kg_edges', $edge_id))[0];
+                     CREATE kg_deletions SET created_at = time::now(), kind = 'relationship', source_table = 'kg_edges', record_id = $edge_id, name = $edge.name, record = $edge, cascade_of = $parent, reason = $reason;
+                     DELETE type::record('kg_edges', $edge_id) RETURN NONE;
+                 };
+                 IF array::len(SELECT VALUE id FROM kg_edges WHERE source = $target OR target = $target LIMIT 1) > 0 {
+                     THROW 'Record gained new edges during deletion; retry the delete';
+                 };
+                 LET $rec = (SELECT * OMIT embedding FROM $target)[0];
+                 CREATE kg_deletions SET created_at = time::now(), kind = $kind, source_table = $tb, record_id = $id, name = $rec.name, record = $rec, cascade_of = NONE, reason = $reason;
+                 DELETE $target RETURN NONE;
+                 COMMIT TRANSACTION;",
+            )
+            .bind(("kind", kind.to_string()))
+            .bind(("tb", table.to_string()))
+            .bind(("id", id.to_string()))
+            .bind(("edges", edges.to_vec()))
+            .bind(("parent", format!("{}:{}", table, id)))
+            .bind(("reason", reason.clone()))
+            .await?
+            .check()?;
+        if table == "kg_entities" {
+            self.unindex_entities(&[id.to_string()]).await;
+        }
+        tracing::info!(kind = %kind, table = %table, id = %id, "kg.delete.done");
+        Ok(())
     }
 
     /// Handle the knowledgegraph_search tool call
@@ -315,7 +881,7 @@ impl SurrealMindServer {
                 )
             } else {
                 format!(
-                    "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_entities WHERE name ~ $name LIMIT {}",
+                    "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_entities WHERE string::contains(string::lowercase(name), string::lowercase($name)) LIMIT {}",
                     top_k
                 )
             };
@@ -330,8 +896,8 @@ impl SurrealMindServer {
             // Project to string IDs safely; handle legacy rows with non-record source/target
             let sql = format!(
                 "SELECT meta::id(id) as id,
-                        (IF meta::tb(source) IS NOT NONE THEN meta::id(source) ELSE string::concat(source) END) as source_id,
-                        (IF meta::tb(target) IS NOT NONE THEN meta::id(target) ELSE string::concat(target) END) as target_id,
+                        (IF type::is_record(source) THEN meta::id(source) ELSE string::concat(source) END) as source_id,
+                        (IF type::is_record(target) THEN meta::id(target) ELSE string::concat(target) END) as target_id,
                         rel_type, data, type::string(created_at) as created_at
                  FROM kg_edges LIMIT {}",
                 top_k
@@ -342,12 +908,12 @@ impl SurrealMindServer {
         if target_s == "observation" || target_s == "mixed" {
             let sql = if name_like_s.is_empty() {
                 format!(
-                    "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_observations LIMIT {}",
+                    "SELECT meta::id(id) as id, name, data, source_thought_ids, source_thought_id, type::string(created_at) as created_at FROM kg_observations LIMIT {}",
                     top_k
                 )
             } else {
                 format!(
-                    "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_observations WHERE name ~ $name LIMIT {}",
+                    "SELECT meta::id(id) as id, name, data, source_thought_ids, source_thought_id, type::string(created_at) as created_at FROM kg_observations WHERE string::contains(string::lowercase(name), string::lowercase($name)) LIMIT {}",
                     top_k
                 )
             };
@@ -355,7 +921,12 @@ impl SurrealMindServer {
             if !name_like_s.is_empty() {
                 q = q.bind(("name", name_like_s.clone()));
             }
-            let rows: Vec<serde_json::Value> = q.await?.take(0)?;
+            let mut rows: Vec<serde_json::Value> = q.await?.take(0)?;
+            attach_provenance(&self.db.client(), &mut rows)
+                .await
+                .map_err(|e| SurrealMindError::Database {
+                    message: format!("observation provenance failed: {}", e),
+                })?;
             items.extend(rows);
         }
 
@@ -375,21 +946,15 @@ impl SurrealMindServer {
     ) -> Result<()> {
         let provider = self.config.system.embedding_provider.clone();
         let model = self.config.system.embedding_model.clone();
-        let dim = self.config.system.embedding_dimensions;
+        let dim = self.embedder.dimensions();
 
-        let mut text = name.to_string();
-        if table == "kg_entities" {
-            if let Some(entity_type) = data.get("entity_type").and_then(|v| v.as_str()) {
-                text.push_str(&format!(" ({})", entity_type));
-            }
-        } else if table == "kg_observations"
-            && let Some(description) = data.get("description").and_then(|v| v.as_str())
-        {
-            text.push_str(&format!(" - {}", description));
-        }
+        let text = crate::maintenance::embedding_migration::kg_embedding_text(table, name, data);
 
         // Generate embedding
-        let embedding = self.embedder.embed(&text).await?;
+        let embedding = self
+            .embedder
+            .embed_with_purpose(&text, EmbedPurpose::KgEmbed)
+            .await?;
 
         // Update record with embedding metadata
         self.db
@@ -403,6 +968,35 @@ impl SurrealMindServer {
             .bind(("model", model))
             .bind(("dim", dim))
             .await?;
+        self.write_embedding_v2(table, id, &text).await;
+        Ok(())
+    }
+
+    /// Helper: embed an edge as "<source name> <rel_type> <target name>". Dangling edges
+    /// are left unembedded.
+    pub(crate) async fn ensure_edge_embedding(&self, id: &str) -> Result<()> {
+        use crate::maintenance::reembed::{EDGE_TEXT_FIELDS, EdgeTextRow, embed_edge};
+
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(format!(
+                "SELECT {EDGE_TEXT_FIELDS} FROM type::record('kg_edges', $id)"
+            ))
+            .bind(("id", id.to_string()))
+            .await?
+            .take(0)?;
+        let Some(row) = rows.into_iter().next() else {
+            return Ok(());
+        };
+        let row: EdgeTextRow = serde_json::from_value(row)?;
+        embed_edge(
+            &self.db.client(),
+            self.embedder.as_ref(),
+            &self.config.system.embedding_provider,
+            &self.config.system.embedding_model,
+            &row,
+        )
+        .await?;
         Ok(())
     }
 
@@ -425,6 +1019,9 @@ impl SurrealMindServer {
         }
 
         // 2. Try to find by bare ID or Name in kg_entities
+        if let Some(id) = self.entity_index.read().await.get(entity) {
+            return Ok(Some(("kg_entities".to_string(), id.id.clone())));
+        }
         let entities: Vec<serde_json::Value> = self
             .db
             .query("SELECT string::concat(id) as id_str FROM kg_entities WHERE meta::id(id) = $val OR name = $val LIMIT 1")
@@ -471,3 +1068,313 @@ impl SurrealMindServer {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{call, mem_server};
+
+    #[test]
+    fn test_apply_entity_taxonomy_normalizes_and_keeps_raw() {
+        let kg = KgConfig::default();
+        let mut data = json!({"name": "Ada"});
+        let stored = apply_entity_taxonomy(&kg, "Research Thread", &mut data).unwrap();
+        assert_eq!(stored, "research_thread");
+        assert_eq!(
+            data,
+            json!({"name": "Ada", "entity_type": "research_thread", "entity_type_raw": "Research Thread"})
+        );
+
+        // Off-taxonomy types pass through normalized while enforcement is off
+        let mut data = json!({});
+        assert_eq!(
+            apply_entity_taxonomy(&kg, "galaxy", &mut data).unwrap(),
+            "galaxy"
+        );
+        assert_eq!(data, json!({"entity_type": "galaxy"}));
+    }
+
+    #[test]
+    fn test_apply_entity_taxonomy_rejects_with_nearest_type() {
+        let kg = KgConfig {
+            enforce_taxonomy: true,
+            ..KgConfig::default()
+        };
+        let err = apply_entity_taxonomy(&kg, "Persons", &mut json!({}))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("'Persons'"), "{err}");
+        assert!(err.contains("nearest valid type is 'person'"), "{err}");
+        assert!(apply_entity_taxonomy(&kg, "PERSON", &mut json!({})).is_ok());
+        assert_eq!(
+            nearest_entity_type("projekt", &kg.entity_types).map(|(t, _)| t),
+            Some("project")
+        );
+    }
+
+    async fn remember(server: &SurrealMindServer, args: serde_json::Value) -> serde_json::Value {
+        server
+            .handle_knowledgegraph_create(call("remember", args))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_remember_search_and_forget_round_trip() {
+        let server = mem_server().await;
+        let ada = remember(
+            &server,
+            json!({"kind": "entity", "data": {"name": "Ada", "entity_type": "person"}}),
+        )
+        .await["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        remember(
+            &server,
+            json!({"kind": "entity", "data": {"name": "Engine", "entity_type": "project"}}),
+        )
+        .await;
+        // Relationship endpoints resolve by name
+        let edge = remember(
+            &server,
+            json!({"kind": "relationship", "data": {"source": "Ada", "target": "Engine", "rel_type": "works_on"}}),
+        )
+        .await["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let found = server
+            .handle_knowledgegraph_search(call(
+                "search",
+                json!({"target": "entity", "query": {"name": "Ada"}}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        let items = found["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], json!(ada));
+
+        let forget =
+            |args: serde_json::Value| server.handle_knowledgegraph_delete(call("forget", args));
+        let refused = rmcp::ErrorData::from(
+            forget(json!({"kind": "entity", "id": ada}))
+                .await
+                .unwrap_err(),
+        );
+        assert_eq!(refused.code, rmcp::model::ErrorCode::INVALID_REQUEST);
+        let refused = refused.data.unwrap();
+        assert_eq!(refused["class"], "conflict");
+        assert_eq!(refused["blocking_edges"], json!([edge]));
+
+        let deleted = forget(json!({"kind": "entity", "id": ada, "cascade": true}))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        assert_eq!(deleted["deleted"], json!(true));
+        assert_eq!(deleted["cascaded_edges"], json!([edge]));
+
+        let mut left = server
+            .db
+            .query("SELECT name FROM kg_entities; SELECT id FROM kg_edges")
+            .await
+            .unwrap();
+        let entities: Vec<serde_json::Value> = left.take(0).unwrap();
+        let edges: Vec<serde_json::Value> = left.take(1).unwrap();
+        assert_eq!(entities, vec![json!({"name": "Engine"})]);
+        assert!(edges.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_observations_by_name_ignores_case() {
+        let server = mem_server().await;
+        server
+            .db
+            .query(
+                "CREATE kg_observations:o1 SET name = 'Ada prefers tea', data = {}, created_at = time::now();
+                 CREATE kg_observations:o2 SET name = 'Bob prefers coffee', data = {}, created_at = time::now();",
+            )
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let found = server
+            .handle_knowledgegraph_search(call(
+                "search",
+                json!({"target": "observation", "query": {"name": "ADA"}}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        let items = found["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], json!("o1"));
+    }
+
+    #[tokio::test]
+    async fn test_relationship_attributes_are_stored_and_refreshed_on_recreate() {
+        let server = mem_server().await;
+        for name in ["Ada", "Acme"] {
+            remember(&server, json!({"kind": "entity", "data": {"name": name}})).await;
+        }
+        let worked_at = |extra: serde_json::Value| {
+            let mut data = json!({"source": "Ada", "target": "Acme", "rel_type": "worked_at"});
+            data.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            json!({"kind": "relationship", "data": data})
+        };
+
+        let created = remember(
+            &server,
+            worked_at(json!({"valid_from": "2019-01-01", "valid_to": "2022-06-30", "note": "x"})),
+        )
+        .await;
+        assert_eq!(created["created"], true);
+        assert_eq!(created["strength"], json!(DEFAULT_EDGE_STRENGTH));
+        assert_eq!(created["valid_to"], "2022-06-30T23:59:59.999999999+00:00");
+
+        // Same endpoints and rel_type: no new edge, only the supplied attribute changes
+        let again = remember(&server, worked_at(json!({"strength": 0.25}))).await;
+        assert_eq!(again["created"], false);
+        assert_eq!(again["id"], created["id"]);
+        assert_eq!(again["updated"], json!({"strength": 0.25}));
+
+        let edges: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT strength, type::string(valid_from) AS valid_from, data FROM kg_edges")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0]["strength"], json!(0.25));
+        assert!(
+            edges[0]["valid_from"]
+                .as_str()
+                .unwrap()
+                .starts_with("2019-01-01T00:00:00"),
+            "{}",
+            edges[0]
+        );
+        assert!(edges[0]["data"].get("valid_from").is_none());
+        assert_eq!(edges[0]["data"]["note"], "x");
+
+        for (bad, field) in [
+            (json!({"strength": -1}), "strength"),
+            (
+                json!({"valid_from": "2023-01-01", "valid_to": "2022-01-01"}),
+                "valid_to",
+            ),
+            (json!({"valid_from": "last spring"}), "valid_from"),
+        ] {
+            let err = server
+                .handle_knowledgegraph_create(call("remember", worked_at(bad)))
+                .await
+                .unwrap_err();
+            let data = rmcp::ErrorData::from(err).data.unwrap();
+            assert_eq!(data["class"], "invalid_params");
+            assert_eq!(data["field"], field);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remember_rejects_unknown_kind() {
+        let server = mem_server().await;
+        let err = server
+            .handle_knowledgegraph_create(call(
+                "remember",
+                json!({"kind": "galaxy", "data": {"name": "M31"}}),
+            ))
+            .await;
+        assert!(err.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cascade_delete_snapshots_and_removes_all_or_nothing() {
+        let server = mem_server().await;
+        for name in ["Ada", "Engine", "Lab"] {
+            remember(
+                &server,
+                json!({"kind": "entity", "data": {"name": name, "entity_type": "project"}}),
+            )
+            .await;
+        }
+        let edge = |target: &str| json!({"kind": "relationship", "data": {"source": "Ada", "target": target, "rel_type": "works_on"}});
+        let first = remember(&server, edge("Engine")).await["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let ada = server
+            .resolve_delete_target("kg_entities", json!({"name": "Ada"}).as_object().unwrap())
+            .await
+            .unwrap();
+
+        // An edge the cascade did not collect aborts the whole transaction
+        remember(&server, edge("Lab")).await;
+        assert!(
+            server
+                .delete_kg_record(
+                    "entity",
+                    "kg_entities",
+                    &ada,
+                    std::slice::from_ref(&first),
+                    &None
+                )
+                .await
+                .is_err()
+        );
+        let mut left = server
+            .db
+            .query("SELECT VALUE name FROM kg_entities WHERE name = 'Ada'; SELECT VALUE id FROM kg_edges; SELECT VALUE id FROM kg_deletions")
+            .await
+            .unwrap();
+        assert_eq!(left.take::<Vec<String>>(0).unwrap(), ["Ada"]);
+        assert_eq!(left.take::<Vec<serde_json::Value>>(1).unwrap().len(), 2);
+        assert!(left.take::<Vec<serde_json::Value>>(2).unwrap().is_empty());
+
+        let deleted = server
+            .handle_knowledgegraph_delete(call(
+                "forget",
+                json!({"kind": "entity", "id": ada, "cascade": true, "reason": "cleanup"}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        assert_eq!(deleted["cascaded_edges"].as_array().unwrap().len(), 2);
+
+        let snapshots: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT kind, cascade_of, reason FROM kg_deletions ORDER BY kind")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        let parent = format!("kg_entities:{ada}");
+        assert_eq!(
+            snapshots,
+            [
+                json!({"kind": "entity", "cascade_of": null, "reason": "cleanup"}),
+                json!({"kind": "relationship", "cascade_of": parent, "reason": "cleanup"}),
+                json!({"kind": "relationship", "cascade_of": parent, "reason": "cleanup"}),
+            ]
+        );
+        let edges: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT id FROM kg_edges")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert!(edges.is_empty());
+    }
+}
@@ -2,11 +2,10 @@
 
 use crate::error::{Result, SurrealMindError};
 use crate::server::SurrealMindServer;
+use crate::server::connection::DbConnection;
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde::Deserialize;
 use serde_json::{Value, json};
-use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client as WsClient;
 
 /// Parameters for the list_agent_jobs tool
 #[derive(Debug, Deserialize)]
@@ -38,6 +37,7 @@ impl SurrealMindServer {
             serde_json::from_value(Value::Object(args)).map_err(|e| {
                 SurrealMindError::InvalidParams {
                     message: format!("Invalid parameters: {}", e),
+                    field: None,
                 }
             })?;
 
@@ -64,7 +64,7 @@ impl SurrealMindServer {
 }
 
 async fn fetch_jobs(
-    db: &Surreal<WsClient>,
+    db: &DbConnection,
     limit: u32,
     status_filter: Option<&str>,
     tool_name_filter: Option<&str>,
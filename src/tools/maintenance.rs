@@ -1,8 +1,10 @@
 //! maintenance_ops tool handler for archival and cleanup operations
 
+use crate::embeddings::EmbedPurpose;
 use crate::error::{Result, SurrealMindError};
 use crate::indexes::{IndexHealth, TableInfo, get_expected_indexes};
 use crate::server::SurrealMindServer;
+use crate::server::progress::ProgressReporter;
 // corrections tool handler is in scope via SurrealMindServer impl; no direct import needed
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde_json::json;
@@ -31,6 +33,105 @@ pub struct MaintenanceParams {
     pub target_id: Option<String>,
     #[serde(default)]
     pub rethink_types: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub concurrency: Option<usize>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub sample: Option<usize>,
+    /// Thoughts to skip before scanning (decay_thoughts subcommand)
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub offset: Option<usize>,
+    /// List links still missing after this many days (repair_continuity_links subcommand)
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_u64_forgiving"
+    )]
+    pub dangling_after_days: Option<u64>,
+    /// Start of the `audit_summary` window (RFC 3339, inclusive), or first day of the
+    /// `embedding_usage_report` range (YYYY-MM-DD, inclusive)
+    #[serde(default)]
+    pub since: Option<String>,
+    /// End of the `audit_summary` window (RFC 3339, exclusive), or last day of the
+    /// `embedding_usage_report` range (YYYY-MM-DD, inclusive)
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Thought ids for `soft_delete_thoughts` / `restore_thoughts` / `set_privacy` /
+    /// `pin_thoughts` / `unpin_thoughts`
+    #[serde(default, deserialize_with = "crate::deserializers::de_option_tags")]
+    pub ids: Option<Vec<String>>,
+    /// Purge thoughts soft-deleted at least this many days ago (purge_deleted subcommand)
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_u64_forgiving"
+    )]
+    pub older_than_days: Option<u64>,
+    /// Backfill `embedding_v2` from the `[embedding_migration]` target (reembed subcommand)
+    #[serde(default)]
+    pub to_v2: Option<bool>,
+    /// Survivor rule for `dedupe_entities`: "oldest" (default) or "most_edges"
+    #[serde(default)]
+    pub survivor: Option<String>,
+    /// Jaro-Winkler similarity that also groups near-identical names (dedupe_entities)
+    #[serde(default)]
+    pub similarity_threshold: Option<f64>,
+    /// Flag value for `set_privacy` (default true)
+    #[serde(default)]
+    pub private: Option<bool>,
+    /// Extraction prompt version for `populate` (default: latest)
+    #[serde(default)]
+    pub prompt_version: Option<String>,
+    /// Extraction batch id to re-run for `populate`
+    #[serde(default)]
+    pub reprocess_batch: Option<String>,
+    /// Run the missing additive definitions (schema_check subcommand)
+    #[serde(default)]
+    pub apply: Option<bool>,
+    /// `manifest.json` written by `snapshot_create` (snapshot_restore subcommand)
+    #[serde(default)]
+    pub manifest_path: Option<String>,
+    /// Required for `snapshot_restore` to write anything
+    #[serde(default)]
+    pub confirm: Option<bool>,
+    /// Let `snapshot_restore` clear tables that already hold rows, or `update_thought` edit a
+    /// synthesized thought
+    #[serde(default)]
+    pub force: Option<bool>,
+    /// Thought to edit (update_thought subcommand)
+    #[serde(default)]
+    pub thought_id: Option<String>,
+    /// "replace_content" or "append" (update_thought subcommand)
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// New content or the text to append (update_thought subcommand)
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Stored with the revision (update_thought subcommand)
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// blake3 key of a verified hypothesis (hypothesis_trend subcommand)
+    #[serde(default)]
+    pub hypothesis_hash: Option<String>,
+    /// Hypothesis text, hashed when `hypothesis_hash` is absent (hypothesis_trend subcommand)
+    #[serde(default)]
+    pub hypothesis: Option<String>,
+    /// Providers not to probe (providers_check subcommand)
+    #[serde(default, deserialize_with = "crate::deserializers::de_option_tags")]
+    pub skip: Option<Vec<String>>,
+    /// Session to summarize (summarize_session subcommand)
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Lower summarized thoughts' significance by this factor, overriding
+    /// `[maintenance.summarize]` (summarize_session subcommand)
+    #[serde(default)]
+    pub demote_factor: Option<f32>,
 }
 
 impl SurrealMindServer {
@@ -107,10 +208,40 @@ impl SurrealMindServer {
 
         Ok(CallToolResult::structured(report))
     }
+    /// Drop and redefine HNSW vector indexes at the active embedding dimension
+    async fn handle_rebuild_vector_index(&self, dry_run: bool) -> Result<CallToolResult> {
+        if !self.config.system.use_vector_index {
+            return Err(SurrealMindError::FeatureDisabled {
+                message: "Vector index is disabled; set system.use_vector_index = true".into(),
+            });
+        }
+        let indexes = if dry_run {
+            self.refresh_vector_index_state().await?
+        } else {
+            self.rebuild_vector_indexes().await?
+        };
+        Ok(CallToolResult::structured(json!({
+            "dry_run": dry_run,
+            "ready": self.vector_index_ready(),
+            "indexes": indexes
+        })))
+    }
+
     /// Handle the maintenance_ops tool call
     pub async fn handle_maintenance_ops(
         &self,
         request: CallToolRequestParams,
+    ) -> Result<CallToolResult> {
+        self.handle_maintenance_ops_with_progress(request, &ProgressReporter::disabled())
+            .await
+    }
+
+    /// Handle the maintenance_ops tool call, reporting progress of `reembed`,
+    /// `embedding_audit`, and `embed_pending` to `progress`
+    pub async fn handle_maintenance_ops_with_progress(
+        &self,
+        request: CallToolRequestParams,
+        progress: &ProgressReporter,
     ) -> Result<CallToolResult> {
         let args = request.arguments.ok_or_else(|| SurrealMindError::Mcp {
             message: "Missing parameters".into(),
@@ -118,6 +249,7 @@ impl SurrealMindServer {
         let params: MaintenanceParams = serde_json::from_value(serde_json::Value::Object(args))
             .map_err(|e| SurrealMindError::InvalidParams {
                 message: format!("Invalid parameters: {}", e),
+                field: None,
             })?;
 
         let dry_run = params.dry_run.unwrap_or(false);
@@ -143,9 +275,122 @@ impl SurrealMindServer {
             "finalize_removal" => self.handle_finalize_removal(limit, dry_run).await,
             "health_check_embeddings" => self.handle_health_check_embeddings(dry_run).await,
             "health_check_indexes" => self.handle_health_check_indexes(dry_run).await,
-            "reembed" => self.handle_reembed(limit, dry_run).await,
+            "embedding_audit" => {
+                self.handle_embedding_audit(params.sample.unwrap_or(0), progress)
+                    .await
+            }
+            "rebuild_vector_index" => self.handle_rebuild_vector_index(dry_run).await,
+            "decay_thoughts" => {
+                self.handle_decay_thoughts(
+                    params.limit.map(|l| l as usize),
+                    params.offset.unwrap_or(0),
+                    dry_run,
+                )
+                .await
+            }
+            "repair_continuity_links" => {
+                self.handle_repair_continuity_links(
+                    limit,
+                    params
+                        .dangling_after_days
+                        .map(|d| u32::try_from(d).unwrap_or(u32::MAX)),
+                    dry_run,
+                )
+                .await
+            }
+            "normalize_observations" => self.handle_normalize_observations(dry_run).await,
+            "backfill_lang" => self.handle_backfill_lang(limit, dry_run).await,
+            "observation_provenance_report" => {
+                self.handle_observation_provenance_report(limit).await
+            }
+            "normalize_entity_types" => {
+                self.handle_normalize_entity_types(params.similarity_threshold, dry_run)
+                    .await
+            }
+            "dedupe_entities" => {
+                self.handle_dedupe_entities(
+                    limit,
+                    params.survivor.as_deref(),
+                    params.similarity_threshold,
+                    dry_run,
+                )
+                .await
+            }
+            "soft_delete_thoughts" => {
+                self.handle_set_thoughts_deleted(params.ids.unwrap_or_default(), true, dry_run)
+                    .await
+            }
+            "restore_thoughts" => {
+                self.handle_set_thoughts_deleted(params.ids.unwrap_or_default(), false, dry_run)
+                    .await
+            }
+            "update_thought" => {
+                self.handle_update_thought(
+                    params.thought_id.unwrap_or_default(),
+                    params.mode.as_deref().unwrap_or_default(),
+                    params.content.unwrap_or_default(),
+                    params.reason,
+                    params.force.unwrap_or(false),
+                    dry_run,
+                )
+                .await
+            }
+            "hypothesis_trend" => {
+                self.handle_hypothesis_trend(params.hypothesis_hash, params.hypothesis)
+                    .await
+            }
+            "set_privacy" => {
+                self.handle_set_privacy(
+                    params.ids.unwrap_or_default(),
+                    params.private.unwrap_or(true),
+                    dry_run,
+                )
+                .await
+            }
+            "pin_thoughts" => {
+                self.handle_set_pinned(params.ids.unwrap_or_default(), true, dry_run)
+                    .await
+            }
+            "unpin_thoughts" => {
+                self.handle_set_pinned(params.ids.unwrap_or_default(), false, dry_run)
+                    .await
+            }
+            "purge_deleted" => {
+                self.handle_purge_deleted(params.older_than_days.unwrap_or(30), dry_run)
+                    .await
+            }
+            "audit_summary" => {
+                self.handle_audit_summary(params.since.as_deref(), params.until.as_deref())
+                    .await
+            }
+            "embedding_usage_report" => {
+                self.handle_embedding_usage_report(params.since.as_deref(), params.until.as_deref())
+                    .await
+            }
+            "summarize_session" => {
+                self.handle_summarize_session(
+                    params.session_id.unwrap_or_default(),
+                    params.demote_factor,
+                    dry_run,
+                )
+                .await
+            }
+            "providers_check" => {
+                self.handle_providers_check(params.skip.as_deref().unwrap_or_default())
+                    .await
+            }
+            "reembed" if params.to_v2.unwrap_or(false) => {
+                self.handle_reembed_v2(params.limit.map(|l| l as usize), dry_run, progress)
+                    .await
+            }
+            "reembed" => {
+                self.handle_reembed(limit, dry_run, params.concurrency.unwrap_or(1), progress)
+                    .await
+            }
+            "finalize_migration" => self.handle_finalize_migration(dry_run).await,
             "reembed_kg" => self.handle_reembed_kg(limit, dry_run).await,
-            "embed_pending" => self.handle_embed_pending(limit, dry_run).await,
+            "embed_edges" => self.handle_embed_edges(limit, dry_run).await,
+            "embed_pending" => self.handle_embed_pending(limit, dry_run, progress).await,
             "ensure_continuity_fields" => self.handle_ensure_continuity_fields(dry_run).await,
             "echo_config" => self.handle_echo_config().await,
             "corrections" => {
@@ -167,7 +412,14 @@ impl SurrealMindServer {
                     .await
             }
             "populate" => {
-                self.handle_spawn_binary("kg_populate", dry_run, &Vec::new())
+                let mut envs: Vec<(String, String)> = vec![];
+                if let Some(v) = params.prompt_version.clone() {
+                    envs.push(("KG_POPULATE_PROMPT_VERSION".into(), v));
+                }
+                if let Some(batch) = params.reprocess_batch.clone() {
+                    envs.push(("KG_POPULATE_REPROCESS_BATCH".into(), batch));
+                }
+                self.handle_spawn_binary("kg_populate", dry_run, &envs)
                     .await
             }
             "embed" => {
@@ -178,10 +430,23 @@ impl SurrealMindServer {
                 self.handle_spawn_binary("kg_wander", dry_run, &Vec::new())
                     .await
             }
-            "health" => {
-                self.handle_spawn_script("scripts/sm_health.sh", dry_run)
+            "health" => self.handle_health(dry_run).await,
+            "schema_check" => {
+                self.handle_schema_check(params.apply.unwrap_or(false))
                     .await
             }
+            "migration_status" => self.handle_migration_status().await,
+            "snapshot_create" => self.handle_snapshot_create(progress).await,
+            "snapshot_restore" => {
+                self.handle_snapshot_restore(
+                    params.manifest_path.as_deref(),
+                    params.confirm.unwrap_or(false),
+                    params.force.unwrap_or(false),
+                    dry_run,
+                    progress,
+                )
+                .await
+            }
             "report" => self.handle_report().await,
             "tasks" => self.handle_tasks(params.tasks.clone(), dry_run).await,
             _ => Err(SurrealMindError::Validation {
@@ -241,10 +506,7 @@ impl SurrealMindServer {
                     let envs: Vec<(String, String)> = Vec::new();
                     self.handle_spawn_binary("kg_wander", dry_run, &envs).await
                 }
-                "health" => {
-                    self.handle_spawn_script("scripts/sm_health.sh", dry_run)
-                        .await
-                }
+                "health" => self.handle_health(dry_run).await,
                 "report" => self.handle_report().await,
                 other => Err(SurrealMindError::Validation {
                     message: format!("Unknown task in list: {}", other),
@@ -258,7 +520,18 @@ impl SurrealMindServer {
                         .unwrap_or_else(|| json!(r.content));
                     results.push(payload);
                 }
-                Err(e) => results.push(json!({ "error": e.to_string(), "task": t })),
+                Err(e) => {
+                    let mut entry =
+                        json!({ "error": e.to_string(), "class": e.class(), "task": t });
+                    if let SurrealMindError::ToolExecutionFailed {
+                        partial: Some(partial),
+                        ..
+                    } = e
+                    {
+                        entry["partial"] = partial;
+                    }
+                    results.push(entry);
+                }
             }
         }
         Ok(CallToolResult::structured(json!({ "results": results })))
@@ -300,16 +573,7 @@ impl SurrealMindServer {
         let output = cmd.output().map_err(|e| SurrealMindError::Internal {
             message: format!("failed to run {}: {}", bin, e),
         })?;
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let success = output.status.success();
-        let report = json!({
-            "task": bin,
-            "success": success,
-            "stdout": stdout,
-            "stderr": stderr
-        });
-        Ok(CallToolResult::structured(report))
+        binary_report(bin, output).map(CallToolResult::structured)
     }
 
     async fn handle_spawn_script(&self, script: &str, dry_run: bool) -> Result<CallToolResult> {
@@ -334,6 +598,119 @@ impl SurrealMindServer {
         Ok(CallToolResult::structured(report))
     }
 
+    /// Run the health script and attach DB connection/reconnect state as `db_health` and
+    /// embedder rate limiter state as `embedding_rate_limit`.
+    async fn handle_health(&self, dry_run: bool) -> Result<CallToolResult> {
+        let mut report = self
+            .handle_spawn_script("scripts/sm_health.sh", dry_run)
+            .await?
+            .structured_content
+            .unwrap_or_else(|| json!({}));
+        let mut db_health = serde_json::to_value(self.db.health_snapshot())?;
+        db_health["reachable"] = json!(self.db.health().await.is_ok());
+        report["db_health"] = db_health;
+        report["embedding_rate_limit"] = json!(self.embedder.rate_limit());
+        Ok(CallToolResult::structured(report))
+    }
+
+    /// Write every snapshot table under `[maintenance.snapshot] backup_dir`
+    async fn handle_snapshot_create(&self, progress: &ProgressReporter) -> Result<CallToolResult> {
+        use crate::maintenance::snapshot::{SnapshotEmbedding, create_snapshot};
+
+        let settings = &self.config.maintenance.snapshot;
+        let embedding = SnapshotEmbedding {
+            provider: self.config.system.embedding_provider.clone(),
+            model: self.config.system.embedding_model.clone(),
+            dim: self.embedder.dimensions(),
+        };
+        let created = create_snapshot(
+            &self.db.client(),
+            Path::new(&settings.backup_dir),
+            &self.config.system.database_ns,
+            &self.config.system.database_db,
+            embedding,
+            settings.batch_size,
+            progress,
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("snapshot_create failed: {}", e),
+        })?;
+
+        Ok(CallToolResult::structured(json!(created)))
+    }
+
+    /// Reload a snapshot from its manifest; writes only with `confirm` (or checks with `dry_run`)
+    async fn handle_snapshot_restore(
+        &self,
+        manifest_path: Option<&str>,
+        confirm: bool,
+        force: bool,
+        dry_run: bool,
+        progress: &ProgressReporter,
+    ) -> Result<CallToolResult> {
+        use crate::maintenance::snapshot::{RestoreOptions, restore_snapshot};
+
+        let manifest_path = manifest_path
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| SurrealMindError::Validation {
+                message: "snapshot_restore requires 'manifest_path'".into(),
+            })?;
+        if !confirm && !dry_run {
+            return Err(SurrealMindError::Validation {
+                message: "snapshot_restore replaces stored memory; pass confirm: true (or dry_run: true to only verify)".into(),
+            });
+        }
+        let options = RestoreOptions {
+            expected_dim: self.embedder.dimensions(),
+            force,
+            batch_size: self.config.maintenance.snapshot.batch_size,
+            dry_run,
+        };
+        let restored = restore_snapshot(
+            &self.db.client(),
+            Path::new(manifest_path),
+            &options,
+            progress,
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("snapshot_restore failed: {}", e),
+        })?;
+        if !dry_run {
+            self.thoughts.write().await.clear();
+            self.entity_index.write().await.clear();
+        }
+
+        Ok(CallToolResult::structured(json!(restored)))
+    }
+
+    /// Compare the live schema with the migrations; with `apply`, add what is missing
+    async fn handle_schema_check(&self, apply: bool) -> Result<CallToolResult> {
+        let report = crate::maintenance::schema_check::check_schema(&self.db.client(), apply)
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("schema_check failed: {}", e),
+            })?;
+        Ok(CallToolResult::structured(serde_json::to_value(report)?))
+    }
+
+    /// Applied and pending schema migrations
+    async fn handle_migration_status(&self) -> Result<CallToolResult> {
+        let status = crate::server::schema::migration_status(
+            &self.db.client(),
+            crate::server::schema::MIGRATIONS,
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("migration_status failed: {}", e),
+        })?;
+        let mut out = serde_json::to_value(status)?;
+        out["skip_migrations"] = json!(self.config.runtime.skip_migrations);
+        Ok(CallToolResult::structured(out))
+    }
+
     async fn handle_report(&self) -> Result<CallToolResult> {
         let path = format!("{}/logs/remini_report.json", env!("CARGO_MANIFEST_DIR"));
         let contents = fs::read_to_string(&path).unwrap_or_else(|_| "{}".to_string());
@@ -482,6 +859,517 @@ impl SurrealMindServer {
         Ok(CallToolResult::structured(result))
     }
 
+    /// Embedding audit: per-table record counts grouped by provider/model/dim/actual length,
+    /// aggregated in SurrealQL, with optional sample ids for problematic groups
+    async fn handle_embedding_audit(
+        &self,
+        sample: usize,
+        progress: &ProgressReporter,
+    ) -> Result<CallToolResult> {
+        let expected = self.embedder.dimensions() as i64;
+        let audit = crate::maintenance::embedding_audit::run_embedding_audit(
+            &self.db, expected, sample, progress,
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("embedding_audit failed: {}", e),
+        })?;
+        Ok(CallToolResult::structured(serde_json::to_value(audit)?))
+    }
+
+    /// Decay thought significance per `[maintenance.decay]`, pruning unreferenced thoughts
+    /// below the prune threshold
+    async fn handle_decay_thoughts(
+        &self,
+        limit: Option<usize>,
+        offset: usize,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        use crate::maintenance::decay::{DecayOptions, decay_thoughts};
+
+        let cfg = &self.config.maintenance.decay;
+        let opts = DecayOptions {
+            limit,
+            offset,
+            dry_run,
+        };
+        let stats = decay_thoughts(&self.db.client(), cfg, opts, chrono::Utc::now())
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("decay_thoughts failed: {}", e),
+            })?;
+
+        Ok(CallToolResult::structured(json!({
+            "config": cfg,
+            "limit": limit,
+            "offset": offset,
+            "stats": stats
+        })))
+    }
+
+    /// Re-check continuity links stored as strings and upgrade those whose target now exists
+    async fn handle_repair_continuity_links(
+        &self,
+        limit: usize,
+        dangling_after_days: Option<u32>,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        use crate::maintenance::continuity::{RepairOptions, repair_continuity_links};
+
+        let opts = RepairOptions {
+            limit,
+            dangling_after_days,
+            dry_run,
+        };
+        let stats = repair_continuity_links(&self.db.client(), opts, chrono::Utc::now())
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("repair_continuity_links failed: {}", e),
+            })?;
+
+        Ok(CallToolResult::structured(json!({
+            "limit": limit,
+            "dangling_after_days": dangling_after_days,
+            "stats": stats
+        })))
+    }
+
+    /// Report entity types outside the `[kg]` taxonomy and rewrite the fixable ones
+    async fn handle_normalize_entity_types(
+        &self,
+        similarity_threshold: Option<f64>,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        use crate::maintenance::entity_types::{DEFAULT_FIX_THRESHOLD, normalize_entity_types};
+
+        let threshold = similarity_threshold.unwrap_or(DEFAULT_FIX_THRESHOLD);
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(SurrealMindError::InvalidParams {
+                message: format!(
+                    "similarity_threshold must be within 0..=1, got {}",
+                    threshold
+                ),
+                field: None,
+            });
+        }
+        let stats = normalize_entity_types(&self.db.client(), &self.config.kg, threshold, dry_run)
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("normalize_entity_types failed: {}", e),
+            })?;
+        Ok(CallToolResult::structured(json!({"stats": stats})))
+    }
+
+    /// Merge kg_entities that share a normalized name and entity_type
+    async fn handle_dedupe_entities(
+        &self,
+        limit: usize,
+        survivor: Option<&str>,
+        similarity_threshold: Option<f64>,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        use crate::maintenance::{DedupeOptions, SurvivorRule, dedupe_entities};
+
+        let survivor = match survivor {
+            None => SurvivorRule::Oldest,
+            Some(s) => SurvivorRule::parse(s).ok_or_else(|| SurrealMindError::InvalidParams {
+                message: format!("survivor must be 'oldest' or 'most_edges', got '{}'", s),
+                field: None,
+            })?,
+        };
+        if let Some(t) = similarity_threshold
+            && !(0.0..=1.0).contains(&t)
+        {
+            return Err(SurrealMindError::InvalidParams {
+                message: format!("similarity_threshold must be within 0..=1, got {}", t),
+                field: None,
+            });
+        }
+        let opts = DedupeOptions {
+            survivor,
+            fuzzy_threshold: similarity_threshold,
+            limit,
+            dry_run,
+        };
+        let stats = dedupe_entities(&self.db.client(), opts)
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("dedupe_entities failed: {}", e),
+            })?;
+        if !dry_run {
+            for group in &stats.groups {
+                self.unindex_entities(&group.absorbed).await;
+            }
+        }
+        Ok(CallToolResult::structured(json!({"stats": stats})))
+    }
+
+    /// Backfill `content` on observations that only carry `data.content` or `name` plus
+    /// `data.description`
+    async fn handle_normalize_observations(&self, dry_run: bool) -> Result<CallToolResult> {
+        let stats = crate::maintenance::normalize_observations(&self.db.client(), dry_run)
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("normalize_observations failed: {}", e),
+            })?;
+
+        Ok(CallToolResult::structured(json!({ "stats": stats })))
+    }
+
+    /// Detect and store `lang` on thoughts and observations written before detection
+    async fn handle_backfill_lang(
+        &self,
+        batch_size: usize,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        let stats = crate::maintenance::backfill_lang(&self.db.client(), batch_size, dry_run)
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("backfill_lang failed: {}", e),
+            })?;
+
+        Ok(CallToolResult::structured(json!({ "stats": stats })))
+    }
+
+    /// List observations whose source thoughts are missing or no longer exist
+    async fn handle_observation_provenance_report(&self, limit: usize) -> Result<CallToolResult> {
+        let report = crate::maintenance::observation_provenance_report(&self.db.client(), limit)
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("observation_provenance_report failed: {}", e),
+            })?;
+
+        Ok(CallToolResult::structured(json!(report)))
+    }
+
+    /// Soft-delete (`deleted = true`) or restore thoughts by id, evicting them from the cache
+    async fn handle_set_thoughts_deleted(
+        &self,
+        ids: Vec<String>,
+        deleted: bool,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        use crate::maintenance::soft_delete::{restore_thoughts, soft_delete_thoughts};
+
+        let action = if deleted {
+            "soft_delete_thoughts"
+        } else {
+            "restore_thoughts"
+        };
+        if ids.iter().all(|id| id.trim().is_empty()) {
+            return Err(SurrealMindError::Validation {
+                message: format!("{} requires a non-empty 'ids' list", action),
+            });
+        }
+        let db = self.db.client();
+        let stats = if deleted {
+            soft_delete_thoughts(&db, &ids, dry_run).await
+        } else {
+            restore_thoughts(&db, &ids, dry_run).await
+        }
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("{} failed: {}", action, e),
+        })?;
+        if !dry_run {
+            self.evict_cached_thoughts(&stats.updated).await;
+        }
+
+        Ok(CallToolResult::structured(json!({ "stats": stats })))
+    }
+
+    /// Mark thoughts private (hidden from retrieval by default) or public again
+    async fn handle_set_privacy(
+        &self,
+        ids: Vec<String>,
+        private: bool,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        if ids.iter().all(|id| id.trim().is_empty()) {
+            return Err(SurrealMindError::Validation {
+                message: "set_privacy requires a non-empty 'ids' list".into(),
+            });
+        }
+        let stats = crate::maintenance::privacy::set_thought_privacy(
+            &self.db.client(),
+            &ids,
+            private,
+            dry_run,
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("set_privacy failed: {}", e),
+        })?;
+        if !dry_run {
+            self.evict_cached_thoughts(&stats.updated).await;
+        }
+
+        Ok(CallToolResult::structured(json!({ "stats": stats })))
+    }
+
+    /// Replace or append to one thought's content, keeping the old content as a revision
+    async fn handle_update_thought(
+        &self,
+        thought_id: String,
+        mode: &str,
+        content: String,
+        reason: Option<String>,
+        force: bool,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        if thought_id.trim().is_empty() {
+            return Err(SurrealMindError::Validation {
+                message: "update_thought requires 'thought_id'".into(),
+            });
+        }
+        let mode = crate::tools::thinking::update::EditMode::parse(mode)?;
+        let edit = self
+            .update_thought(&thought_id, mode, &content, reason, force, dry_run)
+            .await?;
+        Ok(CallToolResult::structured(json!({ "update": edit })))
+    }
+
+    /// Create or rewrite a session's summary thought
+    async fn handle_summarize_session(
+        &self,
+        session_id: String,
+        demote_factor: Option<f32>,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        let gemini = (self.config.maintenance.summarize.provider == "gemini")
+            .then(crate::clients::GeminiClient::new);
+        let agent = gemini
+            .as_ref()
+            .map(|g| g as &dyn crate::clients::CognitiveAgent);
+        let summary = self
+            .summarize_session(&session_id, agent, demote_factor, dry_run)
+            .await?;
+        Ok(CallToolResult::structured(json!({ "summary": summary })))
+    }
+
+    /// Score series of one hypothesis from `hypothesis_history`
+    async fn handle_hypothesis_trend(
+        &self,
+        hash: Option<String>,
+        hypothesis: Option<String>,
+    ) -> Result<CallToolResult> {
+        let hash = match (hash, hypothesis) {
+            (Some(hash), _) => hash,
+            (None, Some(text)) if !text.trim().is_empty() => {
+                crate::tools::thinking::verification::hypothesis_hash(&text)
+            }
+            _ => {
+                return Err(SurrealMindError::Validation {
+                    message: "hypothesis_trend requires 'hypothesis_hash' or 'hypothesis'".into(),
+                });
+            }
+        };
+        let trend = self.hypothesis_trend(&hash).await?;
+        Ok(CallToolResult::structured(json!({ "trend": trend })))
+    }
+
+    /// Set or clear `pinned` on thoughts (pin_thoughts / unpin_thoughts)
+    async fn handle_set_pinned(
+        &self,
+        ids: Vec<String>,
+        pinned: bool,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        let op = if pinned {
+            "pin_thoughts"
+        } else {
+            "unpin_thoughts"
+        };
+        if ids.iter().all(|id| id.trim().is_empty()) {
+            return Err(SurrealMindError::Validation {
+                message: format!("{} requires a non-empty 'ids' list", op),
+            });
+        }
+        let stats = crate::maintenance::pinning::set_thought_pinned(
+            &self.db.client(),
+            &ids,
+            pinned,
+            dry_run,
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("{} failed: {}", op, e),
+        })?;
+        if !dry_run {
+            self.evict_cached_thoughts(&stats.updated).await;
+        }
+
+        Ok(CallToolResult::structured(json!({ "stats": stats })))
+    }
+
+    /// Permanently remove thoughts soft-deleted at least `older_than_days` ago
+    async fn handle_purge_deleted(
+        &self,
+        older_than_days: u64,
+        dry_run: bool,
+    ) -> Result<CallToolResult> {
+        let stats = crate::maintenance::soft_delete::purge_deleted_thoughts(
+            &self.db.client(),
+            older_than_days,
+            dry_run,
+            chrono::Utc::now(),
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("purge_deleted failed: {}", e),
+        })?;
+        if !dry_run {
+            self.evict_cached_thoughts(&stats.purged).await;
+        }
+
+        Ok(CallToolResult::structured(json!({ "stats": stats })))
+    }
+
+    /// Per-tool call counts, p50/p95 latency, and error rates from `tool_audit`
+    async fn handle_audit_summary(
+        &self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<CallToolResult> {
+        use crate::server::audit::{AuditRow, SUMMARY_SQL, summarize};
+
+        let parse =
+            |field: &str, value: Option<&str>| -> Result<Option<surrealdb::types::Datetime>> {
+                value
+                    .map(|v| {
+                        v.parse().map_err(|_| {
+                            SurrealMindError::invalid_field(
+                                field,
+                                format!("{} must be an RFC 3339 datetime, got '{}'", field, v),
+                            )
+                        })
+                    })
+                    .transpose()
+            };
+        let since_dt = parse("since", since)?;
+        let until_dt = parse("until", until)?;
+
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(SUMMARY_SQL)
+            .bind(("since", since_dt))
+            .bind(("until", until_dt))
+            .await?
+            .check()?
+            .take(0)?;
+        let rows: Vec<AuditRow> = rows
+            .into_iter()
+            .filter_map(|row| serde_json::from_value(row).ok())
+            .collect();
+        let total_calls = rows.len();
+        let tools = summarize(rows);
+
+        Ok(CallToolResult::structured(json!({
+            "audit_enabled": self.config.runtime.audit_enabled,
+            "since": since,
+            "until": until,
+            "total_calls": total_calls,
+            "tools": tools
+        })))
+    }
+
+    /// Embedding calls, tokens, and estimated cost per purpose, day, and model from the
+    /// `embedding_usage` rollups
+    async fn handle_embedding_usage_report(
+        &self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<CallToolResult> {
+        use crate::embeddings::usage::{REPORT_SQL, UsageRow, summarize};
+
+        let parse = |field: &str, value: Option<&str>| -> Result<Option<String>> {
+            value
+                .map(|v| {
+                    chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d")
+                        .map(|d| d.format("%Y-%m-%d").to_string())
+                        .map_err(|_| {
+                            SurrealMindError::invalid_field(
+                                field,
+                                format!("{} must be a YYYY-MM-DD date, got '{}'", field, v),
+                            )
+                        })
+                })
+                .transpose()
+        };
+        let since_day = parse("since", since)?;
+        let until_day = parse("until", until)?;
+
+        // Include this process's usage that has not been flushed yet
+        self.flush_embedding_usage().await;
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(REPORT_SQL)
+            .bind(("since", since_day.clone()))
+            .bind(("until", until_day.clone()))
+            .await?
+            .check()?
+            .take(0)?;
+        let rows: Vec<UsageRow> = rows
+            .into_iter()
+            .filter_map(|row| serde_json::from_value(row).ok())
+            .collect();
+        let cost = &self.config.embedding.cost;
+        let mut report = summarize(&rows, cost);
+        report["since"] = json!(since_day);
+        report["until"] = json!(until_day);
+        report["usd_per_million_tokens"] = json!({
+            "openai": cost.openai_usd_per_million_tokens,
+            "openai_compat": cost.compat_usd_per_million_tokens,
+        });
+        report["process"] = json!(
+            crate::metrics::global()
+                .embed_usage()
+                .into_iter()
+                .map(|(purpose, (calls, tokens))| (
+                    purpose,
+                    json!({"calls": calls, "tokens": tokens})
+                ))
+                .collect::<serde_json::Map<_, _>>()
+        );
+        Ok(CallToolResult::structured(report))
+    }
+
+    /// Cheapest liveness probe per provider (embedder, Gemini CLI), never echoing secrets.
+    async fn handle_providers_check(&self, skip: &[String]) -> Result<CallToolResult> {
+        use crate::maintenance::providers::{PROVIDERS, SecretScrubber, providers_check};
+
+        if let Some(unknown) = skip.iter().find(|s| !PROVIDERS.contains(&s.as_str())) {
+            return Err(SurrealMindError::InvalidParams {
+                message: format!(
+                    "skip: unknown provider '{}' (expected one of {:?})",
+                    unknown, PROVIDERS
+                ),
+                field: None,
+            });
+        }
+        let runtime = &self.config.runtime;
+        let secrets = [
+            runtime.openai_api_key.clone(),
+            runtime.embed_api_key.clone(),
+            runtime.nomic_api_key.clone(),
+            std::env::var("GEMINI_API_KEY").ok(),
+            std::env::var("GOOGLE_API_KEY").ok(),
+        ];
+        let scrubber = SecretScrubber::new(secrets.into_iter().flatten());
+        let gemini = crate::clients::gemini::GeminiClient::with_timeout_ms(
+            "auto",
+            crate::maintenance::providers::PROBE_TIMEOUT.as_millis() as u64,
+        );
+        let report = providers_check(
+            self.embedder.as_ref(),
+            &self.config.system.embedding_provider,
+            &gemini,
+            skip,
+            &scrubber,
+        )
+        .await;
+        Ok(CallToolResult::structured(report))
+    }
+
     async fn handle_health_check_embeddings(&self, _dry_run: bool) -> Result<CallToolResult> {
         // Determine expected embedding dimension from active embedder
         let expected = self.embedder.dimensions() as i64;
@@ -775,13 +1663,28 @@ impl SurrealMindServer {
         Ok(CallToolResult::structured(summary))
     }
 
-    async fn handle_reembed(&self, limit: usize, dry_run: bool) -> Result<CallToolResult> {
+    async fn handle_reembed(
+        &self,
+        limit: usize,
+        dry_run: bool,
+        concurrency: usize,
+        progress: &ProgressReporter,
+    ) -> Result<CallToolResult> {
         // Call the reembed function from lib.rs
         let batch_size = 100; // Default batch size
-        let stats = crate::run_reembed(batch_size, Some(limit), false, dry_run).await?;
+        let stats = crate::maintenance::run_reembed_with_progress(
+            batch_size,
+            Some(limit),
+            false,
+            dry_run,
+            concurrency,
+            progress,
+        )
+        .await?;
         let result = json!({
             "expected_dim": stats.expected_dim,
             "batch_size": stats.batch_size,
+            "concurrency": stats.concurrency,
             "processed": stats.processed,
             "updated": stats.updated,
             "skipped": stats.skipped,
@@ -792,6 +1695,67 @@ impl SurrealMindServer {
         Ok(CallToolResult::structured(result))
     }
 
+    /// Backfill `embedding_v2` from the migration target on rows that lack it
+    async fn handle_reembed_v2(
+        &self,
+        limit: Option<usize>,
+        dry_run: bool,
+        progress: &ProgressReporter,
+    ) -> Result<CallToolResult> {
+        let target = self.embedding_target.as_ref().ok_or_else(|| {
+            SurrealMindError::FeatureDisabled {
+                message: "No embedding migration in progress; set [embedding_migration] target_provider and target_model".into(),
+            }
+        })?;
+        let stats = crate::maintenance::backfill_embedding_v2(
+            &self.db.client(),
+            target,
+            100,
+            limit,
+            dry_run,
+            progress,
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("reembed to_v2 failed: {}", e),
+        })?;
+        Ok(CallToolResult::structured(json!({"stats": stats})))
+    }
+
+    /// Promote `embedding_v2` to the primary embedding once every embedded row has one
+    async fn handle_finalize_migration(&self, dry_run: bool) -> Result<CallToolResult> {
+        let stats = crate::maintenance::finalize_migration(&self.db.client(), 100, dry_run)
+            .await
+            .map_err(|e| SurrealMindError::Internal {
+                message: format!("finalize_migration failed: {}", e),
+            })?;
+        if !dry_run {
+            self.thoughts.write().await.clear();
+        }
+        let next_steps = "Set [system] embedding_provider/embedding_model/embedding_dimensions to the migration target, remove [embedding_migration], and restart; with use_vector_index, then run maintain rebuild_vector_index.";
+        Ok(CallToolResult::structured(
+            json!({"stats": stats, "next_steps": next_steps}),
+        ))
+    }
+
+    /// Embed kg_edges as "<source name> <rel_type> <target name>" for relationship search
+    async fn handle_embed_edges(&self, limit: usize, dry_run: bool) -> Result<CallToolResult> {
+        let limit_opt = if limit == 0 { None } else { Some(limit) };
+        let stats = crate::maintenance::embed_edges(
+            &self.db.client(),
+            self.embedder.as_ref(),
+            &self.config.system.embedding_provider,
+            &self.config.system.embedding_model,
+            limit_opt,
+            dry_run,
+        )
+        .await
+        .map_err(|e| SurrealMindError::Internal {
+            message: format!("embed_edges failed: {}", e),
+        })?;
+        Ok(CallToolResult::structured(json!({"stats": stats})))
+    }
+
     async fn handle_reembed_kg(&self, limit: usize, dry_run: bool) -> Result<CallToolResult> {
         // Call the library function directly
         let limit_opt = if limit == 0 { None } else { Some(limit) };
@@ -824,7 +1788,12 @@ impl SurrealMindServer {
     }
 
     /// Handle embed_pending: retry embedding for thoughts with pending/failed status
-    async fn handle_embed_pending(&self, limit: usize, dry_run: bool) -> Result<CallToolResult> {
+    async fn handle_embed_pending(
+        &self,
+        limit: usize,
+        dry_run: bool,
+        progress: &ProgressReporter,
+    ) -> Result<CallToolResult> {
         let limit_val = if limit == 0 { 100 } else { limit };
 
         // Query thoughts with pending or failed embedding status
@@ -858,7 +1827,14 @@ impl SurrealMindServer {
         let mut succeeded = 0;
         let mut failed = 0;
 
-        for row in &rows {
+        for (i, row) in rows.iter().enumerate() {
+            progress
+                .report(
+                    i,
+                    Some(rows.len()),
+                    format!("embed_pending: {} succeeded, {} failed", succeeded, failed),
+                )
+                .await;
             let id = row
                 .get("id")
                 .and_then(|v| v.as_str())
@@ -883,7 +1859,11 @@ impl SurrealMindServer {
             }
 
             // Attempt embedding
-            match self.embedder.embed(&content).await {
+            match self
+                .embedder
+                .embed_with_purpose(&content, EmbedPurpose::Reembed)
+                .await
+            {
                 Ok(embedding) if !embedding.is_empty() => {
                     // Update thought with embedding
                     let update_query = r#"
@@ -919,6 +1899,14 @@ impl SurrealMindServer {
             }
         }
 
+        progress
+            .report(
+                rows.len(),
+                Some(rows.len()),
+                format!("embed_pending: {} succeeded, {} failed", succeeded, failed),
+            )
+            .await;
+
         // Count remaining pending
         let count_query = r#"
             SELECT count() AS cnt
@@ -943,3 +1931,46 @@ impl SurrealMindServer {
         })))
     }
 }
+
+/// Report a finished maintenance binary; a non-zero exit fails the call with the captured
+/// output as `partial`.
+fn binary_report(bin: &str, output: std::process::Output) -> Result<serde_json::Value> {
+    let report = json!({
+        "task": bin,
+        "success": output.status.success(),
+        "stdout": String::from_utf8_lossy(&output.stdout),
+        "stderr": String::from_utf8_lossy(&output.stderr),
+        "exit_code": output.status.code()
+    });
+    if output.status.success() {
+        return Ok(report);
+    }
+    Err(SurrealMindError::ToolExecutionFailed {
+        tool: bin.to_string(),
+        error: format!("exited with {}", output.status),
+        partial: Some(report),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_report_fails_on_non_zero_exit() {
+        let ok = Command::new("true").output().unwrap();
+        assert_eq!(binary_report("kg_embed", ok).unwrap()["success"], true);
+
+        let failed = Command::new("sh")
+            .args(["-c", "echo half done; exit 3"])
+            .output()
+            .unwrap();
+        let err = rmcp::ErrorData::from(binary_report("kg_embed", failed).unwrap_err());
+        assert_eq!(err.code, rmcp::model::ErrorCode::INTERNAL_ERROR);
+        let data = err.data.unwrap();
+        assert_eq!(data["class"], "tool_execution_failed");
+        assert_eq!(data["tool"], "kg_embed");
+        assert_eq!(data["partial"]["exit_code"], 3);
+        assert_eq!(data["partial"]["stdout"], "half done\n");
+    }
+}
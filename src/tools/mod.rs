@@ -6,12 +6,17 @@ pub mod call_gem;
 pub mod call_vibe;
 pub mod cancel_agent_job;
 pub mod corrections;
+pub mod embedding_probe;
+pub mod entity_timeline;
+pub mod export;
 pub mod howto;
+pub mod import;
 pub mod journal;
 pub mod knowledge_graph;
 pub mod list_agent_jobs;
 pub mod maintenance;
 pub mod rethink;
+pub mod system_status;
 pub mod test_notification;
 pub mod thinking;
 pub mod unified_search;
@@ -27,6 +27,7 @@ impl SurrealMindServer {
         let params: RethinkParams = serde_json::from_value(serde_json::Value::Object(args))
             .map_err(|e| SurrealMindError::InvalidParams {
                 message: format!("Invalid parameters: {}", e),
+                field: None,
             })?;
 
         let mode = params.mode.as_str();
@@ -151,9 +152,7 @@ impl SurrealMindServer {
             .take(0)?;
 
         if count.unwrap_or(0) == 0 {
-            return Err(SurrealMindError::Validation {
-                message: format!("Record not found: {}", params.target_id),
-            });
+            return Err(SurrealMindError::not_found(table_name, &params.target_id));
         }
 
         if mode == "mark" {
@@ -200,13 +199,10 @@ impl SurrealMindServer {
             .await?
             .take(0)?;
 
-        let previous_state =
-            previous
-                .first()
-                .cloned()
-                .ok_or_else(|| SurrealMindError::Validation {
-                    message: format!("Record not found: {}", params.target_id),
-                })?;
+        let previous_state = previous
+            .first()
+            .cloned()
+            .ok_or_else(|| SurrealMindError::not_found(table_name, &params.target_id))?;
 
         // For now, new_state is identical (no field edits supplied); this establishes provenance and clears the mark.
         let new_state = previous_state.clone();
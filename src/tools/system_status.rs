@@ -0,0 +1,169 @@
+//! system_status tool: dependency health without running a real tool
+//!
+//! Pings SurrealDB, reports the embedder configuration and rate limiter state (with an
+//! optional embed round-trip when `deep` is set), the `thoughts` cache counters, and row
+//! counts for the core tables.
+//! Every DB and embedder call is bounded by `STATUS_TIMEOUT` so the tool cannot hang a session.
+
+use crate::error::{Result, SurrealMindError};
+use crate::server::SurrealMindServer;
+use rmcp::model::{CallToolRequestParams, CallToolResult};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+/// Tables whose row counts are reported.
+pub const STATUS_TABLES: [&str; 6] = [
+    "thoughts",
+    "kg_entities",
+    "kg_edges",
+    "kg_observations",
+    "kg_entity_candidates",
+    "kg_edge_candidates",
+];
+
+/// Upper bound for each check (ping, counts, embed round-trip).
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SystemStatusParams {
+    /// Also embed a short probe string to verify the provider end to end
+    #[serde(default)]
+    pub deep: bool,
+}
+
+/// Run `fut`, mapping a timeout to an error string.
+async fn bounded<T, E: std::fmt::Display>(
+    fut: impl std::future::Future<Output = std::result::Result<T, E>>,
+) -> std::result::Result<T, String> {
+    match tokio::time::timeout(STATUS_TIMEOUT, fut).await {
+        Ok(Ok(v)) => Ok(v),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("timed out after {}ms", STATUS_TIMEOUT.as_millis())),
+    }
+}
+
+impl SurrealMindServer {
+    pub async fn handle_system_status(
+        &self,
+        request: CallToolRequestParams,
+    ) -> Result<CallToolResult> {
+        let params: SystemStatusParams = match request.arguments {
+            Some(args) => serde_json::from_value(Value::Object(args)).map_err(|e| {
+                SurrealMindError::InvalidParams {
+                    message: format!("Invalid parameters: {}", e),
+                    field: None,
+                }
+            })?,
+            None => SystemStatusParams::default(),
+        };
+
+        let started = Instant::now();
+        let ping = bounded(async { self.db.query("RETURN 1").await?.check() }).await;
+        let database = json!({
+            "ok": ping.is_ok(),
+            "latency_ms": started.elapsed().as_millis() as u64,
+            "error": ping.as_ref().err(),
+            "connection": self.db.health_snapshot(),
+        });
+
+        let tables = if ping.is_ok() {
+            self.table_counts().await
+        } else {
+            STATUS_TABLES
+                .iter()
+                .map(|t| (t.to_string(), Value::Null))
+                .collect()
+        };
+
+        let (provider, model, dimensions) = self.get_embedding_metadata();
+        let deep_check = if params.deep {
+            let started = Instant::now();
+            let probe = bounded(self.embedder.embed("system status probe")).await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+            Some(match probe {
+                Ok(v) => json!({
+                    "ok": v.len() as i64 == dimensions,
+                    "latency_ms": latency_ms,
+                    "dimensions": v.len(),
+                    "error": Value::Null,
+                }),
+                Err(e) => json!({
+                    "ok": false,
+                    "latency_ms": latency_ms,
+                    "dimensions": Value::Null,
+                    "error": e,
+                }),
+            })
+        } else {
+            None
+        };
+
+        let cache = {
+            let lru = self.thoughts.read().await;
+            json!({
+                "size": lru.len(),
+                "capacity": lru.cap().get(),
+                "hits": self.cache_stats.hits.load(Ordering::Relaxed),
+                "misses": self.cache_stats.misses.load(Ordering::Relaxed),
+                "hit_rate": self.cache_stats.hit_rate(),
+            })
+        };
+
+        Ok(CallToolResult::structured(json!({
+            "ok": ping.is_ok() && deep_check.as_ref().is_none_or(|d| d["ok"] == json!(true)),
+            "database": database,
+            "embedder": {
+                "provider": provider,
+                "model": model,
+                "dimensions": dimensions,
+                "rate_limit": self.embedder.rate_limit(),
+                "deep_check": deep_check,
+            },
+            "cache": cache,
+            "tables": tables,
+            "metrics": self
+                .config
+                .runtime
+                .metrics_enabled
+                .then(|| crate::metrics::global().render_prometheus()),
+        })))
+    }
+
+    /// Row count per status table; null for a table whose count fails, or for all of them
+    /// when the query times out.
+    async fn table_counts(&self) -> serde_json::Map<String, Value> {
+        let sql: String = STATUS_TABLES
+            .iter()
+            .map(|t| format!("SELECT count() AS n FROM {t} GROUP ALL;"))
+            .collect();
+        let counted = bounded(async {
+            let mut resp = self.db.query(sql).await?;
+            let mut counts = Vec::with_capacity(STATUS_TABLES.len());
+            for i in 0..STATUS_TABLES.len() {
+                // GROUP ALL over an empty table yields no rows
+                counts.push(match resp.take::<Vec<Value>>(i) {
+                    Ok(rows) => json!(rows.first().and_then(|r| r["n"].as_i64()).unwrap_or(0)),
+                    Err(_) => Value::Null,
+                });
+            }
+            Ok::<_, surrealdb::Error>(counts)
+        })
+        .await;
+        match counted {
+            Ok(counts) => STATUS_TABLES
+                .iter()
+                .zip(counts)
+                .map(|(t, n)| (t.to_string(), n))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("system_status: table counts failed: {}", e);
+                STATUS_TABLES
+                    .iter()
+                    .map(|t| (t.to_string(), Value::Null))
+                    .collect()
+            }
+        }
+    }
+}
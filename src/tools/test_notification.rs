@@ -28,6 +28,7 @@ impl SurrealMindServer {
             serde_json::from_value(serde_json::Value::Object(args)).map_err(|e| {
                 SurrealMindError::InvalidParams {
                     message: format!("Invalid parameters: {}", e),
+                    field: None,
                 }
             })?;
 
@@ -7,29 +7,61 @@
 //! - `continuity`: Continuity link resolution and validation
 //! - `mode_router`: Mode selection with routing metadata
 //! - `verification`: Hypothesis verification against knowledge graph
+//! - `duplicates`: Near-duplicate detection for newly created thoughts
+//! - `tag_suggestions`: Write-time tag suggestions from similar KG entities
+//! - `batch`: Several related thoughts created in one transaction
 
+pub mod batch;
+pub mod chain;
 pub mod continuity;
+pub mod duplicates;
 pub mod mode_detection;
 pub mod mode_router;
 pub mod runners;
+pub mod session;
+pub mod summarize;
+pub mod tag_suggestions;
 pub mod types;
+pub mod update;
 pub mod verification;
 
 // Re-export types for external use
 pub use types::{
-    CONTRADICTION_PATTERNS, ContinuityResult, EvidenceItem, LegacymindThinkParams,
-    MAX_CONTENT_SIZE, ThinkMode, VerificationResult, process_continuity_query_result,
+    CONTRADICTION_PHRASES, ContinuityResult, EvidenceItem, LegacymindThinkParams,
+    MAX_BATCH_THOUGHTS, MAX_CONTENT_SIZE, MAX_REFERENCES, NEGATION_CUES, Reference, ReferenceKind,
+    ResponseDetail, ThinkBatchItem, ThinkBatchParams, ThinkMode, VerificationResult,
+    process_continuity_query_result, validate_references,
 };
 
 // Re-export mode detection for internal use
 use mode_detection::detect_mode;
 
+use crate::embeddings::EmbedPurpose;
 use crate::error::{Result, SurrealMindError};
 use crate::server::SurrealMindServer;
+use crate::server::connection::DbQuery;
+use crate::utils::code_refs::extract_code_refs;
+use crate::utils::lang::detect_lang;
 use anyhow::Context;
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde_json::json;
 
+/// A thought written (or found by idempotency key) by [`ThoughtBuilder::execute`]
+#[derive(Debug, Clone)]
+pub struct BuiltThought {
+    pub id: String,
+    /// Empty unless `embedding_status` is "complete"
+    pub embedding: Vec<f32>,
+    pub continuity: ContinuityResult,
+    /// "complete", "pending", or "failed", or "deduplicated" when the idempotency key
+    /// matched an existing thought (nothing is created or embedded)
+    pub embedding_status: String,
+    /// Content as stored, after redaction
+    pub content: String,
+    /// Secrets replaced per `[security.redaction]`
+    pub redactions: usize,
+}
+
 /// Builder for creating thoughts with consistent logic
 pub struct ThoughtBuilder<'a> {
     server: &'a SurrealMindServer,
@@ -45,6 +77,11 @@ pub struct ThoughtBuilder<'a> {
     previous_thought_id: Option<String>,
     revises_thought: Option<String>,
     branch_from: Option<String>,
+    idempotency_key: Option<String>,
+    references: Vec<Reference>,
+    is_private: bool,
+    pinned: bool,
+    infer_session: bool,
 }
 
 impl<'a> ThoughtBuilder<'a> {
@@ -62,6 +99,11 @@ impl<'a> ThoughtBuilder<'a> {
             previous_thought_id: None,
             revises_thought: None,
             branch_from: None,
+            idempotency_key: None,
+            references: Vec::new(),
+            is_private: false,
+            pinned: false,
+            infer_session: false,
         }
     }
 
@@ -101,21 +143,81 @@ impl<'a> ThoughtBuilder<'a> {
         self
     }
 
-    /// Execute the build process: save first (graceful degradation), then embed
-    /// Returns (thought_id, embedding, continuity, embedding_status)
-    /// embedding_status is "complete", "pending", or "failed"
-    pub async fn execute(self) -> Result<(String, Vec<f32>, ContinuityResult, String)> {
+    /// Store `key` on the thought; a later build with the same key returns the existing
+    /// thought instead of creating a new one. Blank keys are ignored.
+    pub fn idempotency_key(mut self, key: Option<String>) -> Self {
+        self.idempotency_key = key.filter(|k| !k.trim().is_empty());
+        self
+    }
+
+    pub fn references(mut self, references: Vec<Reference>) -> Self {
+        self.references = references;
+        self
+    }
+
+    pub fn private(mut self, is_private: bool) -> Self {
+        self.is_private = is_private;
+        self
+    }
+
+    /// Inject the thought into every matching `think`, regardless of similarity
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// Fill in a missing `session_id` (and, if configured, `previous_thought_id`) from recent
+    /// thoughts; see [`session`]
+    pub fn infer_session(mut self, infer: bool) -> Self {
+        self.infer_session = infer;
+        self
+    }
+
+    /// Execute the build process: redact secrets, save first (graceful degradation), then
+    /// embed. See [`BuiltThought`] for the embedding status values.
+    pub async fn execute(self) -> Result<BuiltThought> {
+        if let Some(key) = &self.idempotency_key
+            && let Some(existing) = self.server.find_thought_by_idempotency_key(key).await?
+        {
+            tracing::info!(thought_id = %existing.id, "think.execute.deduplicated");
+            return Ok(existing);
+        }
+
         let thought_id = uuid::Uuid::new_v4().to_string();
-        let (provider, model, dim) = self.server.get_embedding_metadata();
         tracing::info!(thought_id = %thought_id, "think.execute.start");
 
+        // Secrets never reach the embedder or the database
+        let (content, redactions) = self.server.redactor.redact(&self.content);
+        if redactions > 0 {
+            tracing::warn!(thought_id = %thought_id, redactions, "think.execute.redacted");
+        }
+
+        let mut session_id = self.session_id;
+        let mut previous_thought_id = self.previous_thought_id;
+        let session_inference = if self.infer_session
+            && session_id.is_none()
+            && self.server.config.runtime.think_session_gap_minutes > 0
+        {
+            let inference = self
+                .server
+                .infer_session(&self.origin, previous_thought_id.is_none())
+                .await?;
+            session_id = Some(inference.session_id.clone());
+            if previous_thought_id.is_none() {
+                previous_thought_id = inference.auto_linked_previous.clone();
+            }
+            Some(inference)
+        } else {
+            None
+        };
+
         // Resolve continuity links first (doesn't depend on embedding)
         let continuity_start = std::time::Instant::now();
         let mut resolved_continuity = self
             .server
             .resolve_continuity_links(
                 &thought_id,
-                self.previous_thought_id,
+                previous_thought_id,
                 self.revises_thought,
                 self.branch_from,
             )
@@ -125,98 +227,241 @@ impl<'a> ThoughtBuilder<'a> {
             elapsed_ms = continuity_start.elapsed().as_millis(),
             "think.execute.continuity.done"
         );
-        resolved_continuity.session_id = self.session_id;
+        resolved_continuity.session_id = session_id;
+        resolved_continuity.session_inference = session_inference;
         resolved_continuity.chain_id = self.chain_id;
         resolved_continuity.confidence = self.confidence;
 
         // SAVE FIRST with pending status - thought is never lost
         let create_start = std::time::Instant::now();
         tracing::info!(thought_id = %thought_id, "think.execute.create.start");
-        let mut create_resp = self
-            .server
-            .db
-            .query(
-                "CREATE type::record('thoughts', $id) CONTENT {
-            content: $content,
+        let row = NewThought {
+            id: thought_id.clone(),
+            content: content.clone(),
+            origin: self.origin,
+            injection_scale: self.injection_scale,
+            significance: self.significance,
+            tags: self.tags,
+            continuity: resolved_continuity.clone(),
+            idempotency_key: self.idempotency_key.clone(),
+            references: self.references,
+            is_private: self.is_private,
+            pinned: self.pinned,
+        };
+        let query = self.server.db.query(format!(
+            "{} RETURN meta::id(id) as id;",
+            NewThought::create_sql("")
+        ));
+        let mut create_resp = row.bind(query, "", self.server)?.await?;
+        tracing::info!(
+            thought_id = %thought_id,
+            elapsed_ms = create_start.elapsed().as_millis(),
+            "think.execute.create.done"
+        );
+
+        let created: Vec<serde_json::Value> = match create_resp.take(0) {
+            Ok(rows) => rows,
+            Err(e) => {
+                // A concurrent call with the same key won the unique index
+                if let Some(key) = &self.idempotency_key
+                    && let Some(existing) = self.server.find_thought_by_idempotency_key(key).await?
+                {
+                    tracing::info!(thought_id = %existing.id, "think.execute.deduplicated_on_conflict");
+                    return Ok(existing);
+                }
+                return Err(e.into());
+            }
+        };
+        if created.is_empty() {
+            return Err(SurrealMindError::Internal {
+                message: "Thought create returned empty result".to_string(),
+            });
+        }
+
+        self.server
+            .mark_superseded_if_resolved(&thought_id, &resolved_continuity)
+            .await;
+        let (embedding, embedding_status) =
+            self.server.embed_new_thought(&thought_id, &content).await?;
+        Ok(BuiltThought {
+            id: thought_id,
+            embedding,
+            continuity: resolved_continuity,
+            embedding_status: embedding_status.to_string(),
+            content,
+            redactions,
+        })
+    }
+}
+
+/// Column values of a thought about to be created
+pub(crate) struct NewThought {
+    pub id: String,
+    /// Already redacted
+    pub content: String,
+    pub origin: String,
+    pub injection_scale: i64,
+    pub significance: f64,
+    pub tags: Vec<String>,
+    pub continuity: ContinuityResult,
+    pub idempotency_key: Option<String>,
+    pub references: Vec<Reference>,
+    pub is_private: bool,
+    pub pinned: bool,
+}
+
+impl NewThought {
+    /// CREATE statement for one thought, with `embedding_status: 'pending'`. Every parameter
+    /// ends in `n`, so several thoughts can be created in one query.
+    pub(crate) fn create_sql(n: &str) -> String {
+        format!(
+            "CREATE type::record('thoughts', $id{n}) CONTENT {{
+            content: $content{n},
             created_at: time::now(),
             embedding: NONE,
             injected_memories: [],
             enriched_content: NONE,
-            injection_scale: $injection_scale,
-            significance: $significance,
+            injection_scale: $injection_scale{n},
+            significance: $significance{n},
             access_count: 0,
             last_accessed: NONE,
             submode: NONE,
             framework_enhanced: NONE,
             framework_analysis: NONE,
-            origin: $origin,
-            tags: $tags,
-            is_private: false,
+            origin: $origin{n},
+            tags: $tags{n},
+            is_private: $is_private{n},
+            pinned: $pinned{n},
             embedding_provider: $provider,
             embedding_model: $model,
             embedding_dim: $dim,
             embedded_at: NONE,
             embedding_status: 'pending',
-            session_id: $session_id,
-            chain_id: $chain_id,
-            previous_thought_id: $previous_thought_id,
-            revises_thought: $revises_thought,
-            branch_from: $branch_from,
-            confidence: $confidence
-        } RETURN meta::id(id) as id;",
-            )
-            .bind(("id", thought_id.clone()))
-            .bind(("content", self.content.clone()))
-            .bind(("injection_scale", self.injection_scale))
-            .bind(("significance", self.significance))
-            .bind(("origin", self.origin))
-            .bind(("tags", self.tags))
+            session_id: $session_id{n},
+            chain_id: $chain_id{n},
+            previous_thought_id: $previous_thought_id{n},
+            revises_thought: $revises_thought{n},
+            branch_from: $branch_from{n},
+            link_status: $link_status{n},
+            confidence: $confidence{n},
+            idempotency_key: $idempotency_key{n},
+            references: $references{n},
+            code_refs: $code_refs{n},
+            lang: $lang{n}
+        }}"
+        )
+    }
+
+    /// Bind this thought's parameters for [`NewThought::create_sql`]`(n)`, plus the shared
+    /// embedding metadata.
+    pub(crate) fn bind<'a>(
+        self,
+        query: DbQuery<'a>,
+        n: &str,
+        server: &SurrealMindServer,
+    ) -> Result<DbQuery<'a>> {
+        let (provider, model, dim) = server.get_embedding_metadata();
+        let key = |name: &str| format!("{}{}", name, n);
+        let continuity = self.continuity;
+        Ok(query
+            .bind((key("id"), self.id))
+            .bind((key("injection_scale"), self.injection_scale))
+            .bind((key("significance"), self.significance))
+            .bind((key("origin"), self.origin))
+            .bind((key("tags"), self.tags))
             .bind(("provider", provider))
             .bind(("model", model))
             .bind(("dim", dim))
-            .bind(("session_id", resolved_continuity.session_id.clone()))
-            .bind(("chain_id", resolved_continuity.chain_id.clone()))
+            .bind((key("session_id"), continuity.session_id))
+            .bind((key("chain_id"), continuity.chain_id))
+            .bind((key("previous_thought_id"), continuity.previous_thought_id))
+            .bind((key("revises_thought"), continuity.revises_thought))
+            .bind((key("branch_from"), continuity.branch_from))
+            .bind((key("link_status"), continuity.links_resolved))
+            .bind((key("confidence"), continuity.confidence))
+            .bind((key("idempotency_key"), self.idempotency_key))
+            .bind((key("is_private"), self.is_private))
+            .bind((key("pinned"), self.pinned))
+            .bind((key("code_refs"), {
+                let refs = extract_code_refs(&self.content);
+                (!refs.is_empty())
+                    .then(|| serde_json::to_value(refs))
+                    .transpose()?
+            }))
+            .bind((key("lang"), detect_lang(&self.content)))
+            .bind((key("content"), self.content))
             .bind((
-                "previous_thought_id",
-                resolved_continuity.previous_thought_id.clone(),
-            ))
-            .bind((
-                "revises_thought",
-                resolved_continuity.revises_thought.clone(),
-            ))
-            .bind(("branch_from", resolved_continuity.branch_from.clone()))
-            .bind(("confidence", resolved_continuity.confidence))
-            .await?;
-        tracing::info!(
-            thought_id = %thought_id,
-            elapsed_ms = create_start.elapsed().as_millis(),
-            "think.execute.create.done"
-        );
+                key("references"),
+                (!self.references.is_empty())
+                    .then(|| serde_json::to_value(&self.references))
+                    .transpose()?,
+            )))
+    }
+}
 
-        let created: Vec<serde_json::Value> = create_resp.take(0)?;
-        if created.is_empty() {
-            return Err(SurrealMindError::Internal {
-                message: "Thought create returned empty result".to_string(),
-            });
+/// Size cap on content and structural checks on references, shared by every thought writer.
+pub fn validate_thought_input(content: &str, references: &[Reference]) -> Result<()> {
+    if content.len() > MAX_CONTENT_SIZE {
+        return Err(SurrealMindError::Validation {
+            message: format!(
+                "Content exceeds maximum size of {}KB",
+                MAX_CONTENT_SIZE / 1024
+            ),
+        });
+    }
+    validate_references(references).map_err(|message| SurrealMindError::Validation { message })
+}
+
+impl SurrealMindServer {
+    /// Point a revised thought at its replacement. Only existing thoughts get a back-pointer;
+    /// unresolved string links are left alone.
+    pub(crate) async fn mark_superseded_if_resolved(
+        &self,
+        thought_id: &str,
+        continuity: &ContinuityResult,
+    ) {
+        if let Some(revised) = &continuity.revises_thought
+            && matches!(
+                continuity
+                    .links_resolved
+                    .get("revises_thought")
+                    .and_then(|v| v.as_str()),
+                Some("record" | "batch")
+            )
+        {
+            self.mark_superseded(revised, thought_id).await;
         }
+    }
 
-        // NOW attempt embedding - failure won't lose the thought
+    /// Embed a just-created thought and record the outcome. Returns the embedding (empty
+    /// unless complete) and the status: "complete", "failed" (empty vector), or "pending"
+    /// (embedder error; `maintain embed_pending` retries it).
+    pub(crate) async fn embed_new_thought(
+        &self,
+        thought_id: &str,
+        content: &str,
+    ) -> Result<(Vec<f32>, &'static str)> {
+        // Embedding failure won't lose the thought
         let embed_start = std::time::Instant::now();
         tracing::info!(thought_id = %thought_id, "think.execute.embed.start");
-        let embed_result = self.server.embedder.embed(&self.content).await;
+        let embed_result = self
+            .embedder
+            .embed_with_purpose(content, EmbedPurpose::ThoughtCreate)
+            .await;
         tracing::info!(
             thought_id = %thought_id,
             elapsed_ms = embed_start.elapsed().as_millis(),
             "think.execute.embed.done"
         );
+        self.write_embedding_v2("thoughts", thought_id, content)
+            .await;
 
         match embed_result {
             Ok(embedding) if !embedding.is_empty() => {
                 // Success - update with embedding and mark complete
                 let update_start = std::time::Instant::now();
                 tracing::info!(thought_id = %thought_id, "think.execute.update_embedding.start");
-                self.server
-                    .db
+                self.db
                     .query(
                         "UPDATE type::record('thoughts', $id) SET
                         embedding = $embedding,
@@ -224,7 +469,7 @@ impl<'a> ThoughtBuilder<'a> {
                         embedding_status = 'complete'
                         RETURN NONE;",
                     )
-                    .bind(("id", thought_id.clone()))
+                    .bind(("id", thought_id.to_string()))
                     .bind(("embedding", embedding.clone()))
                     .await?;
                 tracing::info!(
@@ -233,13 +478,7 @@ impl<'a> ThoughtBuilder<'a> {
                     embedding_dim = embedding.len(),
                     "think.execute.update_embedding.done"
                 );
-
-                Ok((
-                    thought_id,
-                    embedding,
-                    resolved_continuity,
-                    "complete".to_string(),
-                ))
+                Ok((embedding, "complete"))
             }
             Ok(_) => {
                 // Empty embedding - mark as failed
@@ -248,27 +487,20 @@ impl<'a> ThoughtBuilder<'a> {
                     "Embedding returned empty vector, thought saved with pending status"
                 );
                 let fail_start = std::time::Instant::now();
-                self.server
-                    .db
+                self.db
                     .query(
                         "UPDATE type::record('thoughts', $id) SET
                         embedding_status = 'failed'
                         RETURN NONE;",
                     )
-                    .bind(("id", thought_id.clone()))
+                    .bind(("id", thought_id.to_string()))
                     .await?;
                 tracing::info!(
                     thought_id = %thought_id,
                     elapsed_ms = fail_start.elapsed().as_millis(),
                     "think.execute.mark_failed.done"
                 );
-
-                Ok((
-                    thought_id,
-                    vec![],
-                    resolved_continuity,
-                    "failed".to_string(),
-                ))
+                Ok((Vec::new(), "failed"))
             }
             Err(e) => {
                 // Embedding failed - log warning but don't fail the operation
@@ -277,19 +509,53 @@ impl<'a> ThoughtBuilder<'a> {
                     error = %e,
                     "Embedding failed, thought saved with pending status for later retry"
                 );
-
-                Ok((
-                    thought_id,
-                    vec![],
-                    resolved_continuity,
-                    "pending".to_string(),
-                ))
+                Ok((Vec::new(), "pending"))
             }
         }
     }
-}
 
-impl SurrealMindServer {
+    /// Find the thought created with `key`, with status "deduplicated" and the stored
+    /// content and continuity links.
+    async fn find_thought_by_idempotency_key(&self, key: &str) -> Result<Option<BuiltThought>> {
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(
+                "SELECT meta::id(id) AS id, content, session_id, chain_id, confidence, \
+                 (IF type::is_record(previous_thought_id) THEN 'thoughts:' + meta::id(previous_thought_id) ELSE previous_thought_id END) AS previous_thought_id, \
+                 (IF type::is_record(revises_thought) THEN 'thoughts:' + meta::id(revises_thought) ELSE revises_thought END) AS revises_thought, \
+                 (IF type::is_record(branch_from) THEN 'thoughts:' + meta::id(branch_from) ELSE branch_from END) AS branch_from \
+                 FROM thoughts WHERE idempotency_key = $key LIMIT 1",
+            )
+            .bind(("key", key.to_string()))
+            .await?
+            .take(0)?;
+        let Some(row) = rows.into_iter().next() else {
+            return Ok(None);
+        };
+        let text = |field: &str| row.get(field).and_then(|v| v.as_str()).map(String::from);
+        let continuity = ContinuityResult {
+            session_id: text("session_id"),
+            chain_id: text("chain_id"),
+            previous_thought_id: text("previous_thought_id"),
+            revises_thought: text("revises_thought"),
+            branch_from: text("branch_from"),
+            confidence: row
+                .get("confidence")
+                .and_then(|v| v.as_f64())
+                .map(|c| c as f32),
+            links_resolved: json!({"deduplicated": true}),
+            session_inference: None,
+        };
+        Ok(text("id").map(|id| BuiltThought {
+            id,
+            embedding: Vec::new(),
+            continuity,
+            embedding_status: "deduplicated".to_string(),
+            content: text("content").unwrap_or_default(),
+            redactions: 0,
+        }))
+    }
+
     /// Handle legacymind_think tool
     pub async fn handle_legacymind_think(
         &self,
@@ -302,6 +568,7 @@ impl SurrealMindServer {
         let params: LegacymindThinkParams = serde_json::from_value(serde_json::Value::Object(args))
             .map_err(|e| SurrealMindError::InvalidParams {
                 message: format!("Invalid parameters: {}", e),
+                field: None,
             })?;
         tracing::info!(
             content_len = params.content.len(),
@@ -309,41 +576,11 @@ impl SurrealMindServer {
             "think.handle.params_parsed"
         );
 
-        if params.content.len() > MAX_CONTENT_SIZE {
-            return Err(SurrealMindError::Validation {
-                message: format!(
-                    "Content exceeds maximum size of {}KB",
-                    MAX_CONTENT_SIZE / 1024
-                ),
-            });
-        }
+        let references = params.references.clone().unwrap_or_default();
+        validate_thought_input(&params.content, &references)?;
 
         let content_lower = params.content.to_lowercase();
-        let mode = if let Some(hint) = &params.hint {
-            match hint.as_str() {
-                "debug" => ThinkMode::Debug,
-                "build" => ThinkMode::Build,
-                "plan" => ThinkMode::Plan,
-                "stuck" => ThinkMode::Stuck,
-                "question" => ThinkMode::Question,
-                "conclude" => ThinkMode::Conclude,
-                _ => detect_mode(&params.content),
-            }
-        } else if content_lower.contains("debug time") {
-            ThinkMode::Debug
-        } else if content_lower.contains("building time") {
-            ThinkMode::Build
-        } else if content_lower.contains("plan time") || content_lower.contains("planning time") {
-            ThinkMode::Plan
-        } else if content_lower.contains("i'm stuck") || content_lower.contains("stuck") {
-            ThinkMode::Stuck
-        } else if content_lower.contains("question time") {
-            ThinkMode::Question
-        } else if content_lower.contains("wrap up") || content_lower.contains("conclude") {
-            ThinkMode::Conclude
-        } else {
-            detect_mode(&params.content)
-        };
+        let mode = select_mode(params.hint.as_deref(), &params.content);
 
         let (mode_selected, reason, trigger_matched, heuristics) = match mode {
             ThinkMode::Debug => {
@@ -591,6 +828,9 @@ impl SurrealMindServer {
             };
 
         let is_conclude = matches!(mode, ThinkMode::Conclude);
+        let suggest_tags = params
+            .suggest_tags
+            .unwrap_or(self.config.runtime.think_suggest_tags);
         tracing::info!(
             mode = %mode_selected,
             reason = %reason,
@@ -612,6 +852,11 @@ impl SurrealMindServer {
                     params.revises_thought.clone(),
                     params.branch_from.clone(),
                     params.confidence,
+                    params.idempotency_key.clone(),
+                    suggest_tags,
+                    references,
+                    params.is_private.unwrap_or(false),
+                    params.pinned.unwrap_or(false),
                 )
                 .await?
             }
@@ -636,14 +881,23 @@ impl SurrealMindServer {
                     params.revises_thought.clone(),
                     params.branch_from.clone(),
                     params.confidence,
+                    params.idempotency_key.clone(),
+                    suggest_tags,
+                    references,
+                    params.is_private.unwrap_or(false),
+                    params.pinned.unwrap_or(false),
                 )
                 .await?
             }
         };
 
-        // Run hypothesis verification if requested
-        let verification_result = if let (Some(hypothesis), Some(true)) =
-            (&params.hypothesis, params.needs_verification)
+        // Run hypothesis verification if requested (a deduplicated retry already ran it)
+        let deduplicated = delegated_result
+            .get("deduplicated")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let verification_result = if let (Some(hypothesis), Some(true), false) =
+            (&params.hypothesis, params.needs_verification, deduplicated)
         {
             if !hypothesis.is_empty() {
                 let top_k = params
@@ -662,6 +916,7 @@ impl SurrealMindServer {
                     min_similarity,
                     evidence_limit,
                     contradiction_patterns,
+                    params.scoring.unwrap_or_default(),
                 )
                 .await?
             } else {
@@ -681,12 +936,22 @@ impl SurrealMindServer {
             let _ = self
                 .db
                 .query("UPDATE type::record('thoughts', $id) SET verification = $verif")
-                .bind(("id", thought_id))
+                .bind(("id", thought_id.clone()))
                 .bind((
                     "verif",
                     serde_json::to_value(verification).unwrap_or(serde_json::Value::Null),
                 ))
                 .await;
+            if let Err(e) = self
+                .record_hypothesis_history(
+                    verification,
+                    Some(&thought_id),
+                    continuity_result.session_id.as_deref(),
+                )
+                .await
+            {
+                tracing::warn!("hypothesis history write failed: {}", e);
+            }
         }
 
         let telemetry = json!({
@@ -702,7 +967,7 @@ impl SurrealMindServer {
             "links_telemetry": continuity_result.links_resolved
         });
 
-        let result = json!({
+        let mut result = json!({
             "mode_selected": mode_selected,
             "reason": reason,
             "delegated_result": delegated_result,
@@ -716,6 +981,9 @@ impl SurrealMindServer {
             },
             "telemetry": telemetry
         });
+        if let Some(inference) = &continuity_result.session_inference {
+            result["links"]["session"] = serde_json::to_value(inference)?;
+        }
 
         // Include verification result in the response if present
         let mut final_result = result;
@@ -731,13 +999,103 @@ impl SurrealMindServer {
             final_result = serde_json::Value::Object(map.clone());
         }
 
-        Ok(CallToolResult::structured(final_result))
+        let detail = params
+            .response_detail
+            .unwrap_or(self.config.runtime.think_response_detail);
+        Ok(CallToolResult::structured(reduce_think_result(
+            final_result,
+            detail,
+        )))
+    }
+}
+
+/// Mode for a think: an explicit `hint` wins, then trigger phrases, then keyword heuristics.
+pub(crate) fn select_mode(hint: Option<&str>, content: &str) -> ThinkMode {
+    let content_lower = content.to_lowercase();
+    if let Some(hint) = hint {
+        match hint {
+            "debug" => ThinkMode::Debug,
+            "build" => ThinkMode::Build,
+            "plan" => ThinkMode::Plan,
+            "stuck" => ThinkMode::Stuck,
+            "question" => ThinkMode::Question,
+            "conclude" => ThinkMode::Conclude,
+            _ => detect_mode(content),
+        }
+    } else if content_lower.contains("debug time") {
+        ThinkMode::Debug
+    } else if content_lower.contains("building time") {
+        ThinkMode::Build
+    } else if content_lower.contains("plan time") || content_lower.contains("planning time") {
+        ThinkMode::Plan
+    } else if content_lower.contains("i'm stuck") || content_lower.contains("stuck") {
+        ThinkMode::Stuck
+    } else if content_lower.contains("question time") {
+        ThinkMode::Question
+    } else if content_lower.contains("wrap up") || content_lower.contains("conclude") {
+        ThinkMode::Conclude
+    } else {
+        detect_mode(content)
+    }
+}
+
+/// One-line summary of a think result's `links`, e.g. "session_id=s1, chain_id=c1".
+fn links_summary(links: &serde_json::Value) -> String {
+    let parts: Vec<String> = [
+        "session_id",
+        "chain_id",
+        "previous_thought_id",
+        "revises_thought",
+        "branch_from",
+    ]
+    .iter()
+    .filter_map(|key| {
+        links
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|v| format!("{}={}", key, v))
+    })
+    .collect();
+    if parts.is_empty() {
+        "none".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Cut a full think result down to `detail`.
+fn reduce_think_result(full: serde_json::Value, detail: ResponseDetail) -> serde_json::Value {
+    let thought_id = full["delegated_result"]["thought_id"].clone();
+    match detail {
+        ResponseDetail::Full => full,
+        ResponseDetail::IdsOnly => json!({"thought_id": thought_id}),
+        ResponseDetail::Compact => {
+            let mut compact = json!({
+                "thought_id": thought_id,
+                "mode_selected": full["mode_selected"],
+                "memories_injected": full["delegated_result"]["memories_injected"]
+                    .as_u64()
+                    .unwrap_or(0),
+                "links": links_summary(&full["links"]),
+            });
+            if let Some(verification) = full.get("verification") {
+                let count = |key: &str| verification[key].as_array().map_or(0, |a| a.len());
+                compact["verification"] = json!({
+                    "confidence_score": verification["confidence_score"],
+                    "supporting": count("supporting"),
+                    "contradicting": count("contradicting"),
+                });
+            }
+            compact
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{TEST_EMBED_DIM, call, mem_server, mem_server_with, test_config};
+    use crate::tools::thinking::update::EditMode;
 
     #[test]
     fn test_process_continuity_query_result() {
@@ -774,4 +1132,360 @@ mod tests {
         assert_eq!(id, Some("thoughts:not-found".to_string()));
         assert_eq!(resolution_type, "string");
     }
+
+    fn full_result(verification: bool) -> serde_json::Value {
+        let mut full = json!({
+            "mode_selected": "debug",
+            "reason": "hint specified",
+            "delegated_result": {"thought_id": "t1", "memories_injected": 3, "framework_enhanced": false},
+            "links": {"session_id": "s1", "chain_id": "c1", "previous_thought_id": null, "confidence": 0.8},
+            "telemetry": {"trigger_matched": null, "heuristics": null, "links_telemetry": {}}
+        });
+        if verification {
+            full["verification"] = json!({
+                "hypothesis": "h",
+                "supporting": [{"id": "a"}, {"id": "b"}],
+                "contradicting": [{"id": "c"}],
+                "confidence_score": 0.67,
+                "suggested_revision": null,
+                "telemetry": {}
+            });
+        }
+        full
+    }
+
+    fn keys(value: &serde_json::Value) -> Vec<&str> {
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn test_reduce_think_result_key_sets() {
+        let full = reduce_think_result(full_result(true), ResponseDetail::Full);
+        assert_eq!(full, full_result(true));
+
+        let compact = reduce_think_result(full_result(false), ResponseDetail::Compact);
+        assert_eq!(
+            keys(&compact),
+            ["links", "memories_injected", "mode_selected", "thought_id"]
+        );
+        assert_eq!(compact["links"], json!("session_id=s1, chain_id=c1"));
+        assert_eq!(compact["memories_injected"], json!(3));
+
+        let verified = reduce_think_result(full_result(true), ResponseDetail::Compact);
+        assert_eq!(
+            keys(&verified),
+            [
+                "links",
+                "memories_injected",
+                "mode_selected",
+                "thought_id",
+                "verification"
+            ]
+        );
+        assert_eq!(
+            verified["verification"],
+            json!({"confidence_score": 0.67, "supporting": 2, "contradicting": 1})
+        );
+
+        let ids = reduce_think_result(full_result(true), ResponseDetail::IdsOnly);
+        assert_eq!(ids, json!({"thought_id": "t1"}));
+    }
+
+    #[test]
+    fn test_links_summary_without_links() {
+        assert_eq!(links_summary(&json!({"session_id": null})), "none");
+        assert_eq!(
+            ResponseDetail::parse("ids_only"),
+            Some(ResponseDetail::IdsOnly)
+        );
+        assert_eq!(ResponseDetail::parse("terse"), None);
+    }
+
+    fn reference(kind: ReferenceKind, value: &str) -> Reference {
+        Reference {
+            kind,
+            value: value.to_string(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_references() {
+        let valid = vec![
+            reference(ReferenceKind::File, "/root/crate/src/tools/thinking.rs"),
+            reference(
+                ReferenceKind::Url,
+                "https://github.com/8agana/surreal-mind/pull/12",
+            ),
+            reference(ReferenceKind::Commit, "11818c1"),
+        ];
+        assert!(validate_references(&valid).is_ok());
+
+        for bad in [
+            reference(ReferenceKind::File, "src/tools/thinking.rs"),
+            reference(ReferenceKind::Url, "not a url"),
+            reference(ReferenceKind::Commit, "main"),
+            reference(ReferenceKind::Commit, "abc"),
+            reference(ReferenceKind::Url, "  "),
+        ] {
+            assert!(
+                validate_references(std::slice::from_ref(&bad)).is_err(),
+                "{bad:?}"
+            );
+        }
+
+        let too_many = vec![reference(ReferenceKind::Commit, "abcdef1"); MAX_REFERENCES + 1];
+        let err = validate_references(&too_many).unwrap_err();
+        assert!(err.contains("At most 20"), "{err}");
+        assert!(validate_references(&too_many[..MAX_REFERENCES]).is_ok());
+    }
+
+    #[test]
+    fn test_reference_kind_deserializes_lowercase() {
+        let parsed: Reference =
+            serde_json::from_value(json!({"kind": "url", "value": "https://example.com"})).unwrap();
+        assert_eq!(parsed.kind, ReferenceKind::Url);
+        assert!(serde_json::from_value::<Reference>(json!({"kind": "pr", "value": "1"})).is_err());
+    }
+
+    async fn think(server: &SurrealMindServer, args: serde_json::Value) -> serde_json::Value {
+        server
+            .handle_legacymind_think(call("think", args))
+            .await
+            .expect("think should succeed")
+            .structured_content
+            .expect("structured result")
+    }
+
+    #[tokio::test]
+    async fn test_think_stores_embedding_and_code_refs() {
+        let server = mem_server().await;
+        let out = think(
+            &server,
+            json!({"content": "rustc says E0308 at src/tools/thinking.rs:42", "tags": ["debug"]}),
+        )
+        .await;
+        let id = out["delegated_result"]["thought_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let rows: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT embedding, embedding_dim, embedding_status, code_refs, tags FROM type::record('thoughts', $id)")
+            .bind(("id", id))
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        let row = &rows[0];
+        assert_eq!(row["embedding"].as_array().unwrap().len(), TEST_EMBED_DIM);
+        assert_eq!(row["embedding_dim"], json!(TEST_EMBED_DIM));
+        assert_eq!(row["embedding_status"], "complete");
+        assert_eq!(row["tags"], json!(["debug"]));
+        assert_eq!(
+            row["code_refs"],
+            json!([
+                {"kind": "error_code", "value": "E0308"},
+                {"kind": "path", "value": "src/tools/thinking.rs", "line": 42}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_think_detects_lang_and_search_filters_on_it() {
+        let server = mem_server().await;
+        let english = "The index was rebuilt and the migration is done for this table.";
+        let german = "Der Index ist neu gebaut und die Migration ist fertig.";
+        for content in [english, german] {
+            think(&server, json!({"content": content})).await;
+        }
+
+        let search = |lang: &'static str| {
+            let server = &server;
+            async move {
+                server
+                    .handle_unified_search(call(
+                        "search",
+                        json!({"target": "thoughts", "include_thoughts": true, "lang": lang}),
+                    ))
+                    .await
+                    .unwrap()
+                    .structured_content
+                    .unwrap()["thoughts"]["results"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|t| (t["content"].clone(), t["lang"].clone()))
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(search("de").await, vec![(json!(german), json!("de"))]);
+        assert_eq!(search("EN").await, vec![(json!(english), json!("en"))]);
+        assert!(search("ja").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_think_idempotency_key_deduplicates_retries() {
+        let server = mem_server().await;
+        let first = think(
+            &server,
+            json!({"content": "Anchor thought", "injection_scale": 0}),
+        )
+        .await;
+        let anchor = first["delegated_result"]["thought_id"].as_str().unwrap();
+        let args = json!({
+            "content": "Idempotent retry probe",
+            "hint": "plan",
+            "idempotency_key": "k1",
+            "previous_thought_id": format!("thoughts:{anchor}")
+        });
+
+        let created = think(&server, args.clone()).await;
+        let id = created["delegated_result"]["thought_id"].clone();
+        assert!(created["delegated_result"].get("deduplicated").is_none());
+        assert!(
+            created["delegated_result"]
+                .get("memories_injected")
+                .is_some()
+        );
+
+        // The retry returns the original id and skips enhancement and injection
+        let retried = think(&server, args).await;
+        assert_eq!(
+            retried["delegated_result"],
+            json!({"thought_id": id, "deduplicated": true})
+        );
+        let found = server
+            .find_thought_by_idempotency_key("k1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(json!(found.id), id);
+        assert_eq!(found.embedding_status, "deduplicated");
+        assert_eq!(
+            found.continuity.previous_thought_id,
+            Some(format!("thoughts:{anchor}"))
+        );
+        assert!(
+            server
+                .find_thought_by_idempotency_key("k2")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // Simultaneous first calls: the unique index lets one CREATE through and the
+        // others fall back to the stored thought
+        let race = json!({"content": "Idempotent race probe", "idempotency_key": "k2", "injection_scale": 0});
+        let calls = (0..4).map(|_| think(&server, race.clone()));
+        let outs = futures_util::future::join_all(calls).await;
+        for out in &outs {
+            assert_eq!(
+                out["delegated_result"]["thought_id"],
+                outs[0]["delegated_result"]["thought_id"]
+            );
+        }
+
+        let counts: Vec<serde_json::Value> = server
+            .db
+            .query(
+                "SELECT idempotency_key, count() AS n FROM thoughts \
+                 WHERE idempotency_key != NONE GROUP BY idempotency_key",
+            )
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(
+            counts,
+            vec![
+                json!({"idempotency_key": "k1", "n": 1}),
+                json!({"idempotency_key": "k2", "n": 1})
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_think_inherits_session_and_auto_links() {
+        let mut config = test_config();
+        config.runtime.think_auto_link_previous = true;
+        let server = mem_server_with(config).await;
+
+        let first = think(&server, json!({"content": "first step of the plan"})).await;
+        assert_eq!(first["links"]["session"]["source"], "new");
+        let first_id = first["delegated_result"]["thought_id"].as_str().unwrap();
+
+        let second = think(&server, json!({"content": "second step of the plan"})).await;
+        assert_eq!(second["links"]["session"]["source"], "inherited");
+        assert_eq!(second["links"]["session_id"], first["links"]["session_id"]);
+        assert_eq!(second["links"]["session"]["auto_linked_previous"], first_id);
+    }
+
+    #[tokio::test]
+    async fn test_revising_a_thought_marks_it_superseded() {
+        let server = mem_server().await;
+        let old = think(&server, json!({"content": "Snapshots are zipped"})).await;
+        let old_id = old["delegated_result"]["thought_id"].as_str().unwrap();
+        let new = think(
+            &server,
+            json!({"content": "Snapshots are gzipped", "revises_thought": format!("thoughts:{old_id}")}),
+        )
+        .await;
+        let new_id = new["delegated_result"]["thought_id"].as_str().unwrap();
+
+        let rows: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT superseded_by FROM type::record('thoughts', $id)")
+            .bind(("id", old_id.to_string()))
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(rows[0]["superseded_by"], json!(new_id));
+    }
+
+    #[tokio::test]
+    async fn test_update_thought_records_revision() {
+        let server = mem_server().await;
+        let out = think(&server, json!({"content": "Snapshots use gizp pages"})).await;
+        let id = out["delegated_result"]["thought_id"].as_str().unwrap();
+
+        let edit = server
+            .update_thought(
+                id,
+                EditMode::ReplaceContent,
+                "Snapshots use gzip pages",
+                Some("typo".into()),
+                false,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(edit.revision_count, 1);
+        assert_eq!(edit.embedding_status.as_deref(), Some("complete"));
+        assert_eq!(edit.embedding_dim, Some(TEST_EMBED_DIM));
+
+        let rows: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT content, revisions FROM type::record('thoughts', $id)")
+            .bind(("id", id.to_string()))
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(rows[0]["content"], "Snapshots use gzip pages");
+        assert_eq!(
+            rows[0]["revisions"][0]["content"],
+            "Snapshots use gizp pages"
+        );
+        assert_eq!(rows[0]["revisions"][0]["reason"], "typo");
+    }
 }
@@ -0,0 +1,501 @@
+//! think_batch: several related thoughts created atomically
+//!
+//! Items may name themselves with `local_ref`; a later item's `previous_thought_id`,
+//! `revises_thought`, or `branch_from` equal to an earlier `local_ref` is rewritten to that
+//! item's generated id before anything is written. All creates run in one SurrealDB
+//! transaction, so a failing item rolls back the whole batch. Embedding, framework
+//! enhancement, and memory injection run per thought only after the commit.
+
+use super::runners::technical_defaults;
+use super::types::{
+    ContinuityResult, MAX_BATCH_THOUGHTS, ThinkBatchItem, ThinkBatchParams, ThinkMode,
+};
+use super::{NewThought, select_mode, validate_thought_input};
+use crate::error::{Result, SurrealMindError};
+use crate::server::SurrealMindServer;
+use rmcp::model::{CallToolRequestParams, CallToolResult};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Continuity link fields, in the order they are resolved and deduplicated
+const LINK_FIELDS: [&str; 3] = ["previous_thought_id", "revises_thought", "branch_from"];
+
+/// A continuity link after local refs are resolved
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlannedLink {
+    /// An id outside the batch, checked against the database as for a single think
+    External(String),
+    /// An earlier item in the batch, as "thoughts:<id>"
+    Batch(String),
+}
+
+/// Links for one batch item, in [`LINK_FIELDS`] order
+pub type PlannedLinks = [Option<PlannedLink>; 3];
+
+/// Resolve `local_ref`s: item `i` gets `ids[i]`, and each link naming an earlier item's
+/// `local_ref` becomes [`PlannedLink::Batch`]. Local refs shadow external ids of the same
+/// name. Returns the links per item and the `local_ref` → id map, or an error for a blank or
+/// repeated `local_ref` or a link to an item later in the batch.
+pub fn plan_batch(
+    items: &[ThinkBatchItem],
+    ids: &[String],
+) -> std::result::Result<(Vec<PlannedLinks>, BTreeMap<String, String>), String> {
+    let mut defined: BTreeMap<String, usize> = BTreeMap::new();
+    for (i, item) in items.iter().enumerate() {
+        if let Some(local) = &item.local_ref {
+            if local.trim().is_empty() {
+                return Err(format!("thoughts[{}].local_ref is empty", i));
+            }
+            if let Some(first) = defined.insert(local.clone(), i) {
+                return Err(format!(
+                    "thoughts[{}].local_ref '{}' is already used by thoughts[{}]",
+                    i, local, first
+                ));
+            }
+        }
+    }
+
+    let mut plans = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        let links = [
+            &item.previous_thought_id,
+            &item.revises_thought,
+            &item.branch_from,
+        ];
+        let mut planned: PlannedLinks = [None, None, None];
+        for (slot, (field, link)) in planned.iter_mut().zip(LINK_FIELDS.iter().zip(links)) {
+            let Some(link) = link else { continue };
+            *slot = Some(match defined.get(link) {
+                Some(&target) if target < i => {
+                    PlannedLink::Batch(format!("thoughts:{}", ids[target]))
+                }
+                Some(&target) => {
+                    return Err(format!(
+                        "thoughts[{}].{} refers to local_ref '{}' of thoughts[{}]; only earlier thoughts can be referenced",
+                        i, field, link, target
+                    ));
+                }
+                None => PlannedLink::External(link.clone()),
+            });
+        }
+        plans.push(planned);
+    }
+
+    let local_refs = defined
+        .into_iter()
+        .map(|(local, i)| (local, ids[i].clone()))
+        .collect();
+    Ok((plans, local_refs))
+}
+
+/// One `CREATE` per thought, suffixed `_0`, `_1`, …, inside a single transaction.
+fn batch_create_sql(count: usize) -> String {
+    let mut sql = String::from("BEGIN TRANSACTION;\n");
+    for i in 0..count {
+        sql.push_str(&NewThought::create_sql(&format!("_{}", i)));
+        sql.push_str(" RETURN NONE;\n");
+    }
+    sql.push_str("COMMIT TRANSACTION;");
+    sql
+}
+
+/// A created thought waiting for its post-commit steps
+struct Pending {
+    id: String,
+    local_ref: Option<String>,
+    mode: &'static str,
+    injection_scale: i64,
+    tags: Vec<String>,
+    content: String,
+    redactions: usize,
+    continuity: ContinuityResult,
+}
+
+impl SurrealMindServer {
+    /// Handle the think_batch tool
+    pub async fn handle_think_batch(
+        &self,
+        request: CallToolRequestParams,
+    ) -> Result<CallToolResult> {
+        let args = request.arguments.ok_or_else(|| SurrealMindError::Mcp {
+            message: "Missing parameters".into(),
+        })?;
+        let params: ThinkBatchParams = serde_json::from_value(serde_json::Value::Object(args))
+            .map_err(|e| SurrealMindError::InvalidParams {
+                message: format!("Invalid parameters: {}", e),
+                field: None,
+            })?;
+        let items = params.thoughts;
+        if items.is_empty() || items.len() > MAX_BATCH_THOUGHTS {
+            return Err(SurrealMindError::Validation {
+                message: format!(
+                    "thoughts must hold 1 to {} items, got {}",
+                    MAX_BATCH_THOUGHTS,
+                    items.len()
+                ),
+            });
+        }
+        for (i, item) in items.iter().enumerate() {
+            validate_thought_input(
+                &item.content,
+                item.references.as_deref().unwrap_or_default(),
+            )
+            .map_err(|e| match e {
+                SurrealMindError::Validation { message } => SurrealMindError::Validation {
+                    message: format!("thoughts[{}]: {}", i, message),
+                },
+                other => other,
+            })?;
+        }
+        let ids: Vec<String> = items
+            .iter()
+            .map(|_| uuid::Uuid::new_v4().to_string())
+            .collect();
+        let (plans, local_refs) =
+            plan_batch(&items, &ids).map_err(|message| SurrealMindError::Validation { message })?;
+        tracing::info!(count = items.len(), "think.batch.start");
+
+        let mut rows = Vec::with_capacity(items.len());
+        let mut pending = Vec::with_capacity(items.len());
+        for ((item, id), plan) in items.into_iter().zip(ids).zip(plans) {
+            let mode = select_mode(item.hint.as_deref(), &item.content);
+            let (mode, origin, default_scale, default_significance) = match mode {
+                ThinkMode::Question => ("question", "human", 1, 0.5),
+                ThinkMode::Conclude => ("conclude", "human", 1, 0.5),
+                ThinkMode::Debug | ThinkMode::Build | ThinkMode::Plan | ThinkMode::Stuck => {
+                    let mode = match mode {
+                        ThinkMode::Debug => "debug",
+                        ThinkMode::Build => "build",
+                        ThinkMode::Plan => "plan",
+                        _ => "stuck",
+                    };
+                    let (scale, significance) = technical_defaults(mode);
+                    (mode, "tool", scale, significance)
+                }
+            };
+            let injection_scale = item.injection_scale.unwrap_or(default_scale) as i64;
+            let tags = item.tags.unwrap_or_default();
+
+            // Secrets never reach the embedder or the database
+            let (content, redactions) = self.redactor.redact(&item.content);
+            if redactions > 0 {
+                tracing::warn!(thought_id = %id, redactions, "think.batch.redacted");
+            }
+
+            let external = |n: usize| match &plan[n] {
+                Some(PlannedLink::External(link)) => Some(link.clone()),
+                _ => None,
+            };
+            let mut continuity = self
+                .resolve_continuity_links(&id, external(0), external(1), external(2))
+                .await?;
+            apply_batch_links(&mut continuity, &plan);
+            continuity.session_id = item.session_id;
+            continuity.chain_id = item.chain_id;
+            continuity.confidence = item.confidence.map(|c| c.clamp(0.0, 1.0));
+
+            rows.push(NewThought {
+                id: id.clone(),
+                content: content.clone(),
+                origin: origin.to_string(),
+                injection_scale,
+                significance: item.significance.unwrap_or(default_significance) as f64,
+                tags: tags.clone(),
+                continuity: continuity.clone(),
+                idempotency_key: item.idempotency_key.filter(|k| !k.trim().is_empty()),
+                references: item.references.unwrap_or_default(),
+                is_private: item.is_private.unwrap_or(false),
+                pinned: item.pinned.unwrap_or(false),
+            });
+            pending.push(Pending {
+                id,
+                local_ref: item.local_ref,
+                mode,
+                injection_scale,
+                tags,
+                content,
+                redactions,
+                continuity,
+            });
+        }
+
+        let mut query = self.db.query(batch_create_sql(rows.len()));
+        for (i, row) in rows.into_iter().enumerate() {
+            query = row.bind(query, &format!("_{}", i), self)?;
+        }
+        query
+            .await
+            .and_then(|r| r.check())
+            .map_err(|e| SurrealMindError::Database {
+                message: format!("think batch rolled back, nothing was stored: {}", e),
+            })?;
+        tracing::info!(count = pending.len(), "think.batch.committed");
+
+        // The rows are committed; later failures degrade a thought instead of failing the call
+        let mut thoughts = Vec::with_capacity(pending.len());
+        for p in &pending {
+            self.mark_superseded_if_resolved(&p.id, &p.continuity).await;
+            let (embedding, embedding_status) =
+                match self.embed_new_thought(&p.id, &p.content).await {
+                    Ok(embedded) => embedded,
+                    Err(e) => {
+                        tracing::warn!(thought_id = %p.id, error = %e, "think.batch.embed_failed");
+                        (Vec::new(), "pending")
+                    }
+                };
+            let framework_enhanced = match self.enhance_thought(&p.id, &p.content, p.mode).await {
+                Ok(analysis) => analysis.is_some(),
+                Err(e) => {
+                    tracing::warn!(thought_id = %p.id, error = %e, "think.batch.enhance_failed");
+                    false
+                }
+            };
+            let injection = if !embedding.is_empty() {
+                self.inject_memories(
+                    &p.id,
+                    &embedding,
+                    p.injection_scale,
+                    None,
+                    &p.tags,
+                    Some("think_batch"),
+                )
+                .await
+                .unwrap_or_default()
+            } else {
+                Default::default()
+            };
+            thoughts.push(json!({
+                "thought_id": p.id,
+                "local_ref": p.local_ref,
+                "mode_selected": p.mode,
+                "embedding_status": embedding_status,
+                "memories_injected": injection.count,
+                "framework_enhanced": framework_enhanced,
+                "redactions": p.redactions,
+                "links": {
+                    "previous_thought_id": p.continuity.previous_thought_id,
+                    "revises_thought": p.continuity.revises_thought,
+                    "branch_from": p.continuity.branch_from,
+                    "links_resolved": p.continuity.links_resolved
+                }
+            }));
+        }
+        tracing::info!(count = thoughts.len(), "think.batch.done");
+
+        Ok(CallToolResult::structured(json!({
+            "thought_ids": pending.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(),
+            "local_refs": local_refs,
+            "thoughts": thoughts
+        })))
+    }
+}
+
+/// Fill in links to earlier batch items with status "batch", dropping one that repeats an
+/// earlier field's target as [`SurrealMindServer::resolve_continuity_links`] does.
+fn apply_batch_links(continuity: &mut ContinuityResult, plan: &PlannedLinks) {
+    let mut statuses = continuity
+        .links_resolved
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+    let mut seen: Vec<String> = [
+        &continuity.previous_thought_id,
+        &continuity.revises_thought,
+        &continuity.branch_from,
+    ]
+    .into_iter()
+    .flatten()
+    .cloned()
+    .collect();
+    for (field, link) in LINK_FIELDS.iter().zip(plan) {
+        let Some(PlannedLink::Batch(target)) = link else {
+            continue;
+        };
+        let status = if seen.contains(target) {
+            "dropped_duplicate"
+        } else {
+            seen.push(target.clone());
+            let slot = match *field {
+                "previous_thought_id" => &mut continuity.previous_thought_id,
+                "revises_thought" => &mut continuity.revises_thought,
+                _ => &mut continuity.branch_from,
+            };
+            *slot = Some(target.clone());
+            "batch"
+        };
+        statuses.insert(field.to_string(), json!(status));
+    }
+    continuity.links_resolved = serde_json::Value::Object(statuses);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{call, mem_server};
+
+    fn item(value: serde_json::Value) -> ThinkBatchItem {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn ids(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("id{}", i)).collect()
+    }
+
+    #[test]
+    fn test_plan_batch_resolves_earlier_local_refs() {
+        let items = vec![
+            item(json!({"content": "conclusion", "local_ref": "c"})),
+            item(json!({"content": "support", "local_ref": "s1", "previous_thought_id": "c"})),
+            item(json!({"content": "more", "branch_from": "s1", "revises_thought": "old-thought"})),
+        ];
+        let (plans, local_refs) = plan_batch(&items, &ids(3)).unwrap();
+        assert_eq!(plans[0], [None, None, None]);
+        assert_eq!(plans[1][0], Some(PlannedLink::Batch("thoughts:id0".into())));
+        assert_eq!(
+            plans[2],
+            [
+                None,
+                Some(PlannedLink::External("old-thought".into())),
+                Some(PlannedLink::Batch("thoughts:id1".into()))
+            ]
+        );
+        assert_eq!(
+            local_refs,
+            BTreeMap::from([("c".into(), "id0".into()), ("s1".into(), "id1".into())])
+        );
+    }
+
+    #[test]
+    fn test_plan_batch_rejects_bad_local_refs() {
+        let forward = vec![
+            item(json!({"content": "a", "previous_thought_id": "later"})),
+            item(json!({"content": "b", "local_ref": "later"})),
+        ];
+        let err = plan_batch(&forward, &ids(2)).unwrap_err();
+        assert!(err.contains("thoughts[0].previous_thought_id"), "{err}");
+
+        let repeated = vec![
+            item(json!({"content": "a", "local_ref": "x"})),
+            item(json!({"content": "b", "local_ref": "x"})),
+        ];
+        let err = plan_batch(&repeated, &ids(2)).unwrap_err();
+        assert!(err.contains("already used by thoughts[0]"), "{err}");
+
+        let blank = vec![item(json!({"content": "a", "local_ref": " "}))];
+        assert!(plan_batch(&blank, &ids(1)).is_err());
+    }
+
+    #[test]
+    fn test_apply_batch_links_marks_and_dedupes() {
+        let mut continuity = ContinuityResult {
+            session_id: None,
+            chain_id: None,
+            previous_thought_id: Some("thoughts:ext".into()),
+            revises_thought: None,
+            branch_from: None,
+            confidence: None,
+            links_resolved: json!({"previous_thought_id": "record"}),
+            session_inference: None,
+        };
+        let plan: PlannedLinks = [
+            Some(PlannedLink::External("ext".into())),
+            Some(PlannedLink::Batch("thoughts:id0".into())),
+            Some(PlannedLink::Batch("thoughts:id0".into())),
+        ];
+        apply_batch_links(&mut continuity, &plan);
+        assert_eq!(continuity.revises_thought.as_deref(), Some("thoughts:id0"));
+        assert_eq!(continuity.branch_from, None);
+        assert_eq!(
+            continuity.links_resolved,
+            json!({"previous_thought_id": "record", "revises_thought": "batch", "branch_from": "dropped_duplicate"})
+        );
+
+        let sql = batch_create_sql(2);
+        assert!(sql.starts_with("BEGIN TRANSACTION;") && sql.ends_with("COMMIT TRANSACTION;"));
+        assert!(sql.contains("$content_0,") && sql.contains("$content_1,"));
+    }
+
+    #[tokio::test]
+    async fn test_think_batch_resolves_local_refs() {
+        let server = mem_server().await;
+        let out = server
+            .handle_think_batch(call("think_batch", json!({"thoughts": [
+                {"content": "Conclusion: the cache fix holds", "hint": "conclude", "local_ref": "c", "session_id": "s1", "injection_scale": 0},
+                {"content": "Support: p95 dropped after the fix", "local_ref": "s1", "previous_thought_id": "c", "session_id": "s1", "injection_scale": 0},
+                {"content": "Support: no regressions in soak test", "previous_thought_id": "s1", "branch_from": "c", "session_id": "s1", "injection_scale": 0}
+            ]})))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        let ids: Vec<String> = out["thought_ids"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(ids.len(), 3);
+        assert_eq!(out["local_refs"], json!({"c": ids[0], "s1": ids[1]}));
+        assert_eq!(out["thoughts"][0]["mode_selected"], json!("conclude"));
+
+        let rows: Vec<serde_json::Value> = server
+            .db
+            .query(
+                "SELECT meta::id(id) AS id, type::string(previous_thought_id) AS previous, \
+                 type::string(branch_from) AS branch FROM thoughts WHERE session_id = 's1'",
+            )
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+        let row = |id: &str| rows.iter().find(|r| r["id"] == json!(id)).unwrap();
+        assert_eq!(
+            row(&ids[1])["previous"],
+            json!(format!("thoughts:{}", ids[0]))
+        );
+        assert_eq!(
+            row(&ids[2])["previous"],
+            json!(format!("thoughts:{}", ids[1]))
+        );
+        assert_eq!(
+            row(&ids[2])["branch"],
+            json!(format!("thoughts:{}", ids[0]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_think_batch_failure_rolls_back_every_item() {
+        let server = mem_server().await;
+        server
+            .handle_legacymind_think(call(
+                "think",
+                json!({"content": "Earlier thought holding the key", "idempotency_key": "k1", "injection_scale": 0}),
+            ))
+            .await
+            .unwrap();
+
+        // The third item's key is already taken, so its CREATE fails inside the transaction
+        let err = server
+            .handle_think_batch(call(
+                "think_batch",
+                json!({"thoughts": [
+                    {"content": "first", "local_ref": "a", "session_id": "s1"},
+                    {"content": "second", "previous_thought_id": "a", "session_id": "s1"},
+                    {"content": "third", "idempotency_key": "k1", "session_id": "s1"}
+                ]}),
+            ))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("rolled back"), "{err}");
+
+        let counts: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT count() AS n FROM thoughts GROUP ALL")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(counts[0]["n"], json!(1));
+    }
+}
@@ -0,0 +1,415 @@
+//! Chain summary retrieval for thought chains
+//!
+//! Given a `chain_id`, or a `thought_id` from which the chain is discovered by walking
+//! `previous_thought_id`, returns the ordered thoughts with their resolved continuity links
+//! and a compact adjacency list (`next`, `branch`, `revision`) so clients can render a tree.
+//!
+//! Unresolved links are preserved as strings at write time, so a chain can contain cycles;
+//! both the walk and the summary detect them instead of looping.
+
+use crate::error::Result;
+use crate::maintenance::privacy::privacy_filter;
+use crate::server::SurrealMindServer;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Default and maximum number of thoughts returned in a chain summary.
+pub const DEFAULT_CHAIN_MAX_THOUGHTS: usize = 50;
+pub const MAX_CHAIN_THOUGHTS: usize = 500;
+
+/// Characters of content kept in each thought preview.
+const PREVIEW_CHARS: usize = 200;
+
+/// Fields selected for chain nodes; links are flattened to bare ids or strings.
+const CHAIN_FIELDS: &str = "meta::id(id) AS id, content, submode, significance, confidence, \
+    session_id, chain_id, type::string(created_at) AS created_at, \
+    (IF type::is_record(previous_thought_id) THEN meta::id(previous_thought_id) ELSE previous_thought_id END) AS previous_thought_id, \
+    (IF type::is_record(revises_thought) THEN meta::id(revises_thought) ELSE revises_thought END) AS revises_thought, \
+    (IF type::is_record(branch_from) THEN meta::id(branch_from) ELSE branch_from END) AS branch_from";
+
+/// A thought as loaded for a chain summary.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct ChainNode {
+    pub id: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub submode: Option<String>,
+    #[serde(default)]
+    pub significance: Option<f32>,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub chain_id: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub previous_thought_id: Option<String>,
+    #[serde(default)]
+    pub revises_thought: Option<String>,
+    #[serde(default)]
+    pub branch_from: Option<String>,
+}
+
+impl ChainNode {
+    /// Continuity links as (adjacency kind, field name, normalized target id).
+    fn links(&self) -> Vec<(&'static str, &'static str, String)> {
+        [
+            ("next", "previous_thought_id", &self.previous_thought_id),
+            ("branch", "branch_from", &self.branch_from),
+            ("revision", "revises_thought", &self.revises_thought),
+        ]
+        .into_iter()
+        .filter_map(|(kind, field, link)| {
+            link.as_deref()
+                .map(normalize_thought_id)
+                .filter(|id| !id.is_empty())
+                .map(|id| (kind, field, id))
+        })
+        .collect()
+    }
+}
+
+/// Strip the `thoughts:` prefix and SurrealDB id escaping from a link value.
+pub(crate) fn normalize_thought_id(raw: &str) -> String {
+    let id = raw.trim();
+    let id = id.strip_prefix("thoughts:").unwrap_or(id);
+    id.trim_start_matches(['⟨', '`'])
+        .trim_end_matches(['⟩', '`'])
+        .to_string()
+}
+
+fn preview(content: &str) -> String {
+    if content.chars().count() <= PREVIEW_CHARS {
+        return content.to_string();
+    }
+    let mut out: String = content.chars().take(PREVIEW_CHARS).collect();
+    out.push('…');
+    out
+}
+
+/// Cycles among `ids` following the adjacency (parent -> child) edges, each reported once
+/// as the ids along the cycle. Iterative DFS, so deep chains can't overflow the stack.
+pub(crate) fn find_cycles(
+    ids: &[String],
+    adjacency: &BTreeMap<String, Vec<Value>>,
+) -> Vec<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Color {
+        White,
+        Gray,
+        Black,
+    }
+    let mut color: HashMap<String, Color> =
+        ids.iter().map(|id| (id.clone(), Color::White)).collect();
+    let children = |id: &str| -> Vec<String> {
+        adjacency
+            .get(id)
+            .map(|edges| {
+                edges
+                    .iter()
+                    .filter_map(|e| e.get("id").and_then(|v| v.as_str()).map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut cycles = Vec::new();
+    for start in ids {
+        if color.get(start) != Some(&Color::White) {
+            continue;
+        }
+        // Stack of (node, its children, next child index); `path` mirrors the gray nodes.
+        let mut stack = vec![(start.clone(), children(start), 0usize)];
+        let mut path = vec![start.clone()];
+        color.insert(start.clone(), Color::Gray);
+        while let Some((node, kids, idx)) = stack.last_mut() {
+            let Some(child) = kids.get(*idx).cloned() else {
+                color.insert(node.clone(), Color::Black);
+                stack.pop();
+                path.pop();
+                continue;
+            };
+            *idx += 1;
+            match color.get(&child).copied() {
+                Some(Color::White) => {
+                    color.insert(child.clone(), Color::Gray);
+                    let grandkids = children(&child);
+                    path.push(child.clone());
+                    stack.push((child, grandkids, 0));
+                }
+                Some(Color::Gray) => {
+                    if let Some(pos) = path.iter().position(|p| *p == child) {
+                        cycles.push(path[pos..].to_vec());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    cycles
+}
+
+/// Build the chain summary from thoughts already in display order (capped by the caller).
+pub(crate) fn summarize_chain(
+    chain_id: Option<&str>,
+    nodes: &[ChainNode],
+    truncated: bool,
+) -> Value {
+    let ids: Vec<String> = nodes.iter().map(|n| n.id.clone()).collect();
+    let in_chain: HashSet<&str> = ids.iter().map(String::as_str).collect();
+
+    let mut adjacency: BTreeMap<String, Vec<Value>> =
+        ids.iter().map(|id| (id.clone(), Vec::new())).collect();
+    let mut has_parent: HashSet<&str> = HashSet::new();
+    let mut dangling = Vec::new();
+    let mut thoughts = Vec::with_capacity(nodes.len());
+
+    for node in nodes {
+        let mut links = serde_json::Map::new();
+        for (kind, field, target) in node.links() {
+            let resolved = in_chain.contains(target.as_str());
+            links.insert(
+                field.to_string(),
+                json!({"id": target, "resolved": resolved}),
+            );
+            if resolved {
+                has_parent.insert(node.id.as_str());
+                if let Some(edges) = adjacency.get_mut(&target) {
+                    edges.push(json!({"id": node.id, "kind": kind}));
+                }
+            } else {
+                dangling.push(json!({"from": node.id, "field": field, "target": target}));
+            }
+        }
+        thoughts.push(json!({
+            "id": node.id,
+            "content_preview": preview(&node.content),
+            "mode": node.submode,
+            "significance": node.significance,
+            "confidence": node.confidence,
+            "session_id": node.session_id,
+            "chain_id": node.chain_id,
+            "created_at": node.created_at,
+            "links": links,
+        }));
+    }
+
+    let roots: Vec<&str> = ids
+        .iter()
+        .map(String::as_str)
+        .filter(|id| !has_parent.contains(id))
+        .collect();
+    let cycles = find_cycles(&ids, &adjacency);
+
+    json!({
+        "chain_id": chain_id,
+        "count": thoughts.len(),
+        "truncated": truncated,
+        "thoughts": thoughts,
+        "adjacency": adjacency,
+        "roots": roots,
+        "cycles": cycles,
+        "dangling_links": dangling,
+    })
+}
+
+fn parse_nodes(rows: Vec<Value>) -> Vec<ChainNode> {
+    rows.into_iter()
+        .filter_map(|row| serde_json::from_value(row).ok())
+        .collect()
+}
+
+impl SurrealMindServer {
+    async fn fetch_chain_node(&self, id: &str, include_private: bool) -> Result<Option<ChainNode>> {
+        let mut resp = self
+            .db
+            .query(format!(
+                "SELECT {} FROM type::record('thoughts', $id) WHERE deleted_at = NONE AND {}",
+                CHAIN_FIELDS,
+                privacy_filter(include_private)
+            ))
+            .bind(("id", id.to_string()))
+            .await?;
+        let rows: Vec<Value> = resp.take(0)?;
+        Ok(parse_nodes(rows).into_iter().next())
+    }
+
+    async fn fetch_chain_by_id(
+        &self,
+        chain_id: &str,
+        limit: usize,
+        include_private: bool,
+    ) -> Result<Vec<ChainNode>> {
+        let mut resp = self
+            .db
+            .query(format!(
+                "SELECT {} FROM thoughts WHERE chain_id = $cid AND deleted_at = NONE AND {} ORDER BY created_at ASC LIMIT $limit",
+                CHAIN_FIELDS,
+                privacy_filter(include_private)
+            ))
+            .bind(("cid", chain_id.to_string()))
+            .bind(("limit", limit as i64))
+            .await?;
+        let rows: Vec<Value> = resp.take(0)?;
+        Ok(parse_nodes(rows))
+    }
+
+    /// Summarize a thought chain by `chain_id`, or discover it from `thought_id` by walking
+    /// `previous_thought_id` back to the root (switching to the full chain if a `chain_id`
+    /// is found on the way). Private thoughts are left out unless `include_private`.
+    pub(crate) async fn chain_summary(
+        &self,
+        chain_id: Option<&str>,
+        thought_id: Option<&str>,
+        max_thoughts: usize,
+        include_private: bool,
+    ) -> Result<Value> {
+        let max_thoughts = max_thoughts.clamp(1, MAX_CHAIN_THOUGHTS);
+        let mut chain_id = chain_id.map(str::to_string);
+        let mut walked: Vec<ChainNode> = Vec::new();
+        let mut walk_cycle = None;
+
+        if chain_id.is_none()
+            && let Some(start) = thought_id
+        {
+            let mut seen = HashSet::new();
+            let mut next = Some(normalize_thought_id(start));
+            while let Some(id) = next.take() {
+                if !seen.insert(id.clone()) {
+                    walk_cycle = Some(id);
+                    break;
+                }
+                if walked.len() >= max_thoughts {
+                    break;
+                }
+                let Some(node) = self.fetch_chain_node(&id, include_private).await? else {
+                    break;
+                };
+                next = node
+                    .previous_thought_id
+                    .as_deref()
+                    .map(normalize_thought_id);
+                if node.chain_id.is_some() {
+                    chain_id = node.chain_id.clone();
+                    walked.push(node);
+                    break;
+                }
+                walked.push(node);
+            }
+            walked.reverse();
+        }
+
+        let (mut nodes, truncated) = match chain_id.as_deref() {
+            Some(cid) => {
+                let mut nodes = self
+                    .fetch_chain_by_id(cid, max_thoughts + 1, include_private)
+                    .await?;
+                let truncated = nodes.len() > max_thoughts;
+                nodes.truncate(max_thoughts);
+                (nodes, truncated)
+            }
+            None => (Vec::new(), false),
+        };
+        // Ancestors without the chain_id (walked before reaching it) lead the chain.
+        let known: HashSet<String> = nodes.iter().map(|n| n.id.clone()).collect();
+        let ancestors: Vec<ChainNode> = walked
+            .into_iter()
+            .filter(|n| !known.contains(&n.id))
+            .collect();
+        let truncated = truncated || ancestors.len() + nodes.len() > max_thoughts;
+        nodes.splice(0..0, ancestors);
+        nodes.truncate(max_thoughts);
+
+        let mut summary = summarize_chain(chain_id.as_deref(), &nodes, truncated);
+        if let Some(id) = walk_cycle {
+            summary["walk_cycle_at"] = json!(id);
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(
+        id: &str,
+        prev: Option<&str>,
+        branch: Option<&str>,
+        revises: Option<&str>,
+    ) -> ChainNode {
+        ChainNode {
+            id: id.to_string(),
+            content: format!("thought {}", id),
+            previous_thought_id: prev.map(String::from),
+            branch_from: branch.map(String::from),
+            revises_thought: revises.map(String::from),
+            ..ChainNode::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_thought_id() {
+        assert_eq!(normalize_thought_id("thoughts:abc"), "abc");
+        assert_eq!(normalize_thought_id("thoughts:⟨a-b-c⟩"), "a-b-c");
+        assert_eq!(normalize_thought_id("abc"), "abc");
+    }
+
+    #[test]
+    fn test_summarize_chain_with_branch_and_revision() {
+        let nodes = vec![
+            node("a", None, None, None),
+            node("b", Some("thoughts:a"), None, None),
+            node("c", None, Some("a"), None),
+            node("d", Some("b"), None, Some("b")),
+            node("e", Some("missing"), None, None),
+        ];
+        let summary = summarize_chain(Some("chain-1"), &nodes, false);
+
+        assert_eq!(summary["count"], json!(5));
+        assert_eq!(
+            summary["adjacency"]["a"],
+            json!([{"id": "b", "kind": "next"}, {"id": "c", "kind": "branch"}])
+        );
+        assert_eq!(
+            summary["adjacency"]["b"],
+            json!([{"id": "d", "kind": "next"}, {"id": "d", "kind": "revision"}])
+        );
+        assert_eq!(summary["roots"], json!(["a", "e"]));
+        assert_eq!(summary["cycles"], json!([]));
+        assert_eq!(
+            summary["dangling_links"],
+            json!([{"from": "e", "field": "previous_thought_id", "target": "missing"}])
+        );
+        assert_eq!(
+            summary["thoughts"][3]["links"]["revises_thought"],
+            json!({"id": "b", "resolved": true})
+        );
+    }
+
+    #[test]
+    fn test_summarize_chain_detects_cycle() {
+        let nodes = vec![
+            node("a", Some("c"), None, None),
+            node("b", Some("a"), None, None),
+            node("c", Some("b"), None, None),
+        ];
+        let summary = summarize_chain(None, &nodes, false);
+        let cycles = summary["cycles"].as_array().unwrap();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].as_array().unwrap().len(), 3);
+        assert_eq!(summary["roots"], json!([]));
+    }
+
+    #[test]
+    fn test_preview_truncates_on_char_boundary() {
+        let long = "é".repeat(PREVIEW_CHARS + 10);
+        let p = preview(&long);
+        assert_eq!(p.chars().count(), PREVIEW_CHARS + 1);
+        assert!(p.ends_with('…'));
+    }
+}
@@ -49,25 +49,18 @@ impl SurrealMindServer {
             branch_from: None,
             confidence: None,
             links_resolved: serde_json::Value::Object(serde_json::Map::new()),
+            session_inference: None,
         };
 
         // Helper function to resolve and validate a thought reference
         let resolve_thought = |id: String| async move {
-            // Determine the full ID format for querying
-            let full_id = if id.starts_with("thoughts:") {
-                id.clone()
-            } else {
-                format!("thoughts:{}", id)
-            };
+            // Query by the bare key; type::record() does not parse hyphenated uuid ids
+            let bare_id = id.strip_prefix("thoughts:").unwrap_or(&id).to_string();
+            let full_id = format!("thoughts:{}", bare_id);
 
             // Query the database to check if the record exists
-            let check_query = "SELECT id FROM type::record($id) LIMIT 1";
-            let query_result = match self
-                .db
-                .query(check_query)
-                .bind(("id", full_id.clone()))
-                .await
-            {
+            let check_query = "SELECT id FROM type::record('thoughts', $id) LIMIT 1";
+            let query_result = match self.db.query(check_query).bind(("id", bare_id)).await {
                 Ok(mut response) => response
                     .take::<Vec<serde_json::Value>>(0)
                     .unwrap_or_default(),
@@ -176,4 +169,33 @@ impl SurrealMindServer {
         resolved.links_resolved = serde_json::Value::Object(links_resolved);
         Ok(resolved)
     }
+
+    /// Record `new_thought_id` as the successor of the thought it revises.
+    ///
+    /// `revised` is the normalized "thoughts:id" form from [`Self::resolve_continuity_links`].
+    /// Search skips thoughts with `superseded_by` set unless `include_superseded` is passed.
+    /// Failures are logged; the new thought is kept either way.
+    pub(crate) async fn mark_superseded(&self, revised: &str, new_thought_id: &str) {
+        let result = self
+            .db
+            .query("UPDATE type::record('thoughts', $id) SET superseded_by = $new RETURN NONE")
+            .bind((
+                "id",
+                revised
+                    .strip_prefix("thoughts:")
+                    .unwrap_or(revised)
+                    .to_string(),
+            ))
+            .bind(("new", new_thought_id.to_string()))
+            .await
+            .and_then(|r| r.check());
+        if let Err(e) = result {
+            tracing::warn!(
+                revised = %revised,
+                thought_id = %new_thought_id,
+                error = %e,
+                "think.continuity.mark_superseded_failed"
+            );
+        }
+    }
 }
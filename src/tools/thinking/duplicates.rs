@@ -0,0 +1,171 @@
+//! Near-duplicate detection for newly created thoughts.
+//!
+//! After a thought is embedded, its nearest existing thoughts are scored and any at or above
+//! `runtime.duplicate_threshold` are returned as `possible_duplicates`; the best match is
+//! stored on the new thought as `duplicate_of_hint`. Private thoughts are never candidates.
+//! Detection is advisory: failures are logged and the thought is kept either way.
+
+use crate::server::SurrealMindServer;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Characters of content shown per duplicate.
+pub const DUPLICATE_PREVIEW_CHARS: usize = 120;
+
+/// Most duplicates reported per thought.
+pub const MAX_DUPLICATES: usize = 3;
+
+/// An existing thought that closely matches a new one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PossibleDuplicate {
+    pub id: String,
+    pub score: f32,
+    pub preview: String,
+}
+
+/// Keep rows (`{id, content, similarity}`) scoring at least `threshold`, best first,
+/// excluding `self_id`.
+pub fn select_duplicates(
+    rows: Vec<Value>,
+    self_id: &str,
+    threshold: f32,
+) -> Vec<PossibleDuplicate> {
+    let mut found: Vec<PossibleDuplicate> = rows
+        .into_iter()
+        .filter_map(|r| {
+            let id = r.get("id")?.as_str()?;
+            let score = r.get("similarity")?.as_f64()? as f32;
+            if id == self_id || score < threshold {
+                return None;
+            }
+            let content = r.get("content").and_then(|c| c.as_str()).unwrap_or("");
+            Some(PossibleDuplicate {
+                id: id.to_string(),
+                score,
+                preview: content.chars().take(DUPLICATE_PREVIEW_CHARS).collect(),
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| b.score.total_cmp(&a.score));
+    found.truncate(MAX_DUPLICATES);
+    found
+}
+
+impl SurrealMindServer {
+    /// Find thoughts nearly identical to `thought_id` and record the best as its
+    /// `duplicate_of_hint`. Returns an empty list when disabled, when the embedding does not
+    /// match the active embedder's dimension, or on any query failure.
+    pub(crate) async fn flag_possible_duplicates(
+        &self,
+        thought_id: &str,
+        embedding: &[f32],
+    ) -> Vec<PossibleDuplicate> {
+        let runtime = &self.config.runtime;
+        if !runtime.duplicate_detect
+            || embedding.is_empty()
+            || embedding.len() != self.embedder.dimensions()
+        {
+            return Vec::new();
+        }
+
+        let started = std::time::Instant::now();
+        let mut use_knn = self.vector_index_ready();
+        let rows: Vec<Value> = loop {
+            // KNN includes the new thought itself, so ask for one extra neighbour
+            let source = if use_knn {
+                format!(
+                    "thoughts WHERE embedding_dim = $dim AND deleted_at = NONE \
+                     AND is_private != true AND {}",
+                    self.embedding_filter(MAX_DUPLICATES + 1, true)
+                )
+            } else {
+                "(SELECT id, content, embedding, created_at FROM thoughts \
+                  WHERE embedding_dim = $dim AND embedding IS NOT NONE AND deleted_at = NONE \
+                  AND is_private != true AND meta::id(id) != $self \
+                  ORDER BY created_at DESC LIMIT $scan)"
+                    .to_string()
+            };
+            let sql = format!(
+                "SELECT meta::id(id) AS id, content, \
+                        vector::similarity::cosine(embedding, $q) AS similarity \
+                 FROM {source} ORDER BY similarity DESC LIMIT $top"
+            );
+            let result = self
+                .db
+                .query(sql)
+                .bind(("dim", embedding.len() as i64))
+                .bind(("q", embedding.to_vec()))
+                .bind(("self", thought_id.to_string()))
+                .bind(("scan", runtime.duplicate_scan_limit as i64))
+                .bind(("top", (MAX_DUPLICATES + 1) as i64))
+                .await
+                .and_then(|r| r.check());
+            match result {
+                Ok(mut r) => break r.take(0).unwrap_or_default(),
+                Err(e) if use_knn => {
+                    self.disable_vector_index(&e);
+                    use_knn = false;
+                }
+                Err(e) => {
+                    tracing::warn!(thought_id = %thought_id, error = %e, "think.duplicates.query_failed");
+                    return Vec::new();
+                }
+            }
+        };
+
+        let duplicates = select_duplicates(rows, thought_id, runtime.duplicate_threshold);
+        if let Some(best) = duplicates.first()
+            && let Err(e) = self
+                .db
+                .query("UPDATE type::record('thoughts', $id) SET duplicate_of_hint = $best RETURN NONE")
+                .bind(("id", thought_id.to_string()))
+                .bind(("best", best.id.clone()))
+                .await
+                .and_then(|r| r.check())
+        {
+            tracing::warn!(thought_id = %thought_id, error = %e, "think.duplicates.hint_failed");
+        }
+        tracing::info!(
+            thought_id = %thought_id,
+            elapsed_ms = started.elapsed().as_millis(),
+            duplicates = duplicates.len(),
+            "think.duplicates.done"
+        );
+        duplicates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_select_duplicates_filters_sorts_and_truncates() {
+        let long = "x".repeat(300);
+        let rows = vec![
+            json!({"id": "new", "content": "same", "similarity": 1.0}),
+            json!({"id": "a", "content": "close", "similarity": 0.96}),
+            json!({"id": "b", "content": long, "similarity": 0.999}),
+            json!({"id": "c", "content": "related", "similarity": 0.80}),
+            json!({"id": "d", "similarity": 0.97}),
+            json!({"id": "e", "content": "also", "similarity": 0.95}),
+        ];
+        let dups = select_duplicates(rows, "new", 0.95);
+        let ids: Vec<&str> = dups.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "d", "a"]);
+        assert_eq!(dups[0].preview.chars().count(), DUPLICATE_PREVIEW_CHARS);
+        assert_eq!(dups[1].preview, "");
+    }
+
+    #[test]
+    fn test_identical_embeddings_are_flagged() {
+        let v = vec![0.3_f32, -0.1, 0.7, 0.2];
+        let sim = crate::utils::cosine_similarity(&v, &v);
+        let rows = vec![json!({"id": "old", "content": "restated idea", "similarity": sim})];
+        let dups = select_duplicates(rows, "new", 0.95);
+        assert_eq!(dups.len(), 1);
+        assert_eq!(dups[0].id, "old");
+        assert_eq!(dups[0].preview, "restated idea");
+    }
+}
@@ -7,16 +7,24 @@
 //! Both runners use `ThoughtBuilder` for consistent thought creation and
 //! `CognitiveEngine` for optional framework analysis.
 
-use super::ThoughtBuilder;
-use super::types::ContinuityResult;
-use crate::cognitive::{
-    CognitiveEngine,
-    profile::{Submode, profile_for},
-};
+use super::types::{ContinuityResult, Reference};
+use super::{BuiltThought, ThoughtBuilder};
+use crate::cognitive::{CognitiveEngine, types::FrameworkOutput};
 use crate::error::Result;
 use crate::server::SurrealMindServer;
 use serde_json::json;
 
+/// Default (injection_scale, significance) for a technical mode; see [`SurrealMindServer::run_technical`]
+pub(crate) fn technical_defaults(mode: &str) -> (u8, f32) {
+    match mode {
+        "debug" => (3, 0.8),
+        "build" => (2, 0.6),
+        "plan" => (3, 0.7),
+        "stuck" => (3, 0.9),
+        _ => (2, 0.6), // fallback
+    }
+}
+
 impl SurrealMindServer {
     /// Run conversational think (with framework enhancement, origin='human')
     ///
@@ -31,6 +39,11 @@ impl SurrealMindServer {
     /// * `verbose_analysis` - Whether to include detailed framework analysis
     /// * `is_conclude` - Whether this is a conclusion thought
     /// * `session_id`, `chain_id`, etc. - Continuity parameters
+    /// * `idempotency_key` - Optional retry key; a repeat returns the original thought
+    /// * `suggest_tags` - Whether to return `suggested_tags` from similar KG entities
+    /// * `references` - Validated files, URLs, or commits stored on the thought
+    /// * `is_private` - Hide the thought from retrieval unless `include_private` is passed
+    /// * `pinned` - Inject the thought into later matching thinks regardless of similarity
     #[allow(clippy::too_many_arguments)]
     pub async fn run_convo(
         &self,
@@ -46,6 +59,11 @@ impl SurrealMindServer {
         revises_thought: Option<String>,
         branch_from: Option<String>,
         confidence: Option<f32>,
+        idempotency_key: Option<String>,
+        suggest_tags: bool,
+        references: Vec<Reference>,
+        is_private: bool,
+        pinned: bool,
     ) -> Result<(serde_json::Value, ContinuityResult)> {
         let runner_start = std::time::Instant::now();
         let injection_scale_val = injection_scale.unwrap_or(1) as i64;
@@ -57,76 +75,63 @@ impl SurrealMindServer {
         );
 
         // Use ThoughtBuilder to create the thought (graceful degradation)
-        let (thought_id, embedding, resolved_continuity, embedding_status) =
-            ThoughtBuilder::new(self, content, "human")
-                .scale(Some(injection_scale_val as u8))
-                .tags(Some(tags.clone()))
-                .significance(significance)
-                .confidence(confidence)
-                .continuity(
-                    session_id,
-                    chain_id,
-                    previous_thought_id,
-                    revises_thought,
-                    branch_from,
-                )
-                .execute()
-                .await?;
-
-        // Framework enhancement
-        let enhance_enabled = std::env::var("SURR_THINK_ENHANCE").unwrap_or("1".to_string()) == "1";
-
-        let verbose_analysis = verbose_analysis.unwrap_or(false);
-        let mut framework_enhanced = false;
-        let mut framework_analysis: Option<serde_json::Value> = None;
-
-        if enhance_enabled || verbose_analysis {
-            let submode = if is_conclude {
-                Submode::Sarcastic
-            } else {
-                Submode::Philosophical
-            };
-            let profile = profile_for(submode);
-            let engine = CognitiveEngine::new();
-
-            let analysis = engine.blend(content, &profile.weights);
+        let BuiltThought {
+            id: thought_id,
+            embedding,
+            continuity: resolved_continuity,
+            embedding_status,
+            content,
+            redactions,
+        } = ThoughtBuilder::new(self, content, "human")
+            .scale(Some(injection_scale_val as u8))
+            .tags(Some(tags.clone()))
+            .significance(significance)
+            .confidence(confidence)
+            .continuity(
+                session_id,
+                chain_id,
+                previous_thought_id,
+                revises_thought,
+                branch_from,
+            )
+            .idempotency_key(idempotency_key)
+            .references(references)
+            .private(is_private)
+            .pinned(pinned)
+            .infer_session(true)
+            .execute()
+            .await?;
 
-            framework_enhanced = true;
-            match serde_json::to_value(&analysis) {
-                Ok(val) => framework_analysis = Some(val),
-                Err(e) => tracing::error!("Failed to serialize framework analysis: {}", e),
-            }
+        // Retry of an earlier call: return the original thought without re-running
+        // enhancement or memory injection
+        if embedding_status == "deduplicated" {
+            return Ok((
+                json!({"thought_id": thought_id, "deduplicated": true}),
+                resolved_continuity,
+            ));
         }
 
-        if framework_enhanced || framework_analysis.is_some() {
-            let query = "UPDATE type::record('thoughts', $id) SET framework_enhanced = $enhanced, framework_analysis = $analysis RETURN NONE;";
-            let framework_start = std::time::Instant::now();
-            self.db
-                .query(query)
-                .bind(("id", thought_id.clone()))
-                .bind(("enhanced", framework_enhanced))
-                .bind((
-                    "analysis",
-                    framework_analysis
-                        .clone()
-                        .unwrap_or(serde_json::Value::Null),
-                ))
-                .await?;
-            tracing::info!(
-                thought_id = %thought_id,
-                elapsed_ms = framework_start.elapsed().as_millis(),
-                "think.run_convo.framework_update.done"
-            );
-        }
+        let possible_duplicates = self.flag_possible_duplicates(&thought_id, &embedding).await;
+        let suggested_tags = if suggest_tags {
+            self.suggest_tags(&embedding, &tags).await
+        } else {
+            Vec::new()
+        };
+
+        // Framework enhancement
+        let mode = if is_conclude { "conclude" } else { "question" };
+        let framework_analysis = self.enhance_thought(&thought_id, &content, mode).await?;
+        let framework_enhanced = framework_analysis.is_some();
 
         // Memory injection - only if we have an embedding
-        let (mem_count, enriched) = if !embedding.is_empty() {
+        let injection = if !embedding.is_empty() {
             let inject_start = std::time::Instant::now();
             self.inject_memories(
                 &thought_id,
                 &embedding,
                 injection_scale_val,
                 None,
+                &tags,
                 Some("think_convo"),
             )
             .await
@@ -134,24 +139,43 @@ impl SurrealMindServer {
                 tracing::info!(
                     thought_id = %thought_id,
                     elapsed_ms = inject_start.elapsed().as_millis(),
-                    memories_injected = v.0,
+                    memories_injected = v.count,
                     "think.run_convo.inject.done"
                 );
             })
-            .unwrap_or((0, None))
+            .unwrap_or_default()
         } else {
-            (0, None)
+            Default::default()
         };
 
         let mut original_result = json!({
             "thought_id": thought_id.clone(),
             "embedding_model": self.get_embedding_metadata().1,
             "embedding_dim": self.embedder.dimensions(),
-            "memories_injected": mem_count,
-            "enriched_content": enriched,
-            "framework_enhanced": framework_enhanced
+            "memories_injected": injection.count,
+            "pinned_memories": injection.pinned,
+            "enriched_content": injection.enriched,
+            "framework_enhanced": framework_enhanced,
+            "redactions": redactions
         });
 
+        if verbose_analysis.unwrap_or(false)
+            && let Some(analysis) = &framework_analysis
+        {
+            original_result["framework_analysis"] = json!({
+                "insights": analysis.insights,
+                "questions": analysis.questions,
+                "next_steps": analysis.next_steps
+            });
+        }
+
+        if !possible_duplicates.is_empty() {
+            original_result["possible_duplicates"] = json!(possible_duplicates);
+        }
+        if !suggested_tags.is_empty() {
+            original_result["suggested_tags"] = json!(suggested_tags);
+        }
+
         // Add embedding status warning if not complete
         if embedding_status != "complete" {
             original_result["embedding_status"] = json!(embedding_status);
@@ -168,7 +192,7 @@ impl SurrealMindServer {
         Ok((original_result, resolved_continuity))
     }
 
-    /// Run technical think (no framework by default, origin='tool', mode-specific defaults)
+    /// Run technical think (origin='tool', mode-specific defaults)
     ///
     /// Creates a thought with origin='tool' and mode-specific defaults for
     /// injection scale and significance. Framework analysis is applied when enabled.
@@ -193,15 +217,14 @@ impl SurrealMindServer {
         revises_thought: Option<String>,
         branch_from: Option<String>,
         confidence: Option<f32>,
+        idempotency_key: Option<String>,
+        suggest_tags: bool,
+        references: Vec<Reference>,
+        is_private: bool,
+        pinned: bool,
     ) -> Result<(serde_json::Value, ContinuityResult)> {
         let runner_start = std::time::Instant::now();
-        let (default_injection_scale, default_significance) = match mode {
-            "debug" => (3u8, 0.8_f32),
-            "build" => (2u8, 0.6_f32),
-            "plan" => (3u8, 0.7_f32),
-            "stuck" => (3u8, 0.9_f32),
-            _ => (2u8, 0.6_f32), // fallback
-        };
+        let (default_injection_scale, default_significance) = technical_defaults(mode);
         let injection_scale_val = injection_scale.unwrap_or(default_injection_scale) as i64;
         let tags = tags.unwrap_or_default();
         tracing::info!(
@@ -212,72 +235,63 @@ impl SurrealMindServer {
         );
 
         // Use ThoughtBuilder (graceful degradation)
-        let (thought_id, embedding, resolved_continuity, embedding_status) =
-            ThoughtBuilder::new(self, content, "tool")
-                .scale(Some(injection_scale_val as u8))
-                .tags(Some(tags.clone()))
-                .significance(significance.or(Some(default_significance)))
-                .confidence(confidence)
-                .continuity(
-                    session_id,
-                    chain_id,
-                    previous_thought_id,
-                    revises_thought,
-                    branch_from,
-                )
-                .execute()
-                .await?;
-
-        // Framework enhancement
-        let enhance_enabled = std::env::var("SURR_THINK_ENHANCE").unwrap_or("1".to_string()) == "1";
-        let verbose = verbose_analysis.unwrap_or(false);
-
-        let mut framework_enhanced = false;
-        let mut framework_analysis: Option<serde_json::Value> = None;
-
-        if enhance_enabled || verbose {
-            let submode = Submode::from_str(mode);
-            let profile = profile_for(submode);
-            let engine = CognitiveEngine::new();
-            let analysis = engine.blend(content, &profile.weights);
+        let BuiltThought {
+            id: thought_id,
+            embedding,
+            continuity: resolved_continuity,
+            embedding_status,
+            content,
+            redactions,
+        } = ThoughtBuilder::new(self, content, "tool")
+            .scale(Some(injection_scale_val as u8))
+            .tags(Some(tags.clone()))
+            .significance(significance.or(Some(default_significance)))
+            .confidence(confidence)
+            .continuity(
+                session_id,
+                chain_id,
+                previous_thought_id,
+                revises_thought,
+                branch_from,
+            )
+            .idempotency_key(idempotency_key)
+            .references(references)
+            .private(is_private)
+            .pinned(pinned)
+            .infer_session(true)
+            .execute()
+            .await?;
 
-            framework_enhanced = true;
-            match serde_json::to_value(&analysis) {
-                Ok(val) => framework_analysis = Some(val),
-                Err(e) => tracing::error!("Failed to serialize framework analysis: {}", e),
-            }
+        // Retry of an earlier call: return the original thought without re-running
+        // enhancement or memory injection
+        if embedding_status == "deduplicated" {
+            return Ok((
+                json!({"thought_id": thought_id, "deduplicated": true}),
+                resolved_continuity,
+            ));
         }
 
-        if framework_enhanced || framework_analysis.is_some() {
-            let query = "UPDATE type::record('thoughts', $id) SET framework_enhanced = $enhanced, framework_analysis = $analysis RETURN NONE;";
-            let framework_start = std::time::Instant::now();
-            self.db
-                .query(query)
-                .bind(("id", thought_id.clone()))
-                .bind(("enhanced", framework_enhanced))
-                .bind((
-                    "analysis",
-                    framework_analysis
-                        .clone()
-                        .unwrap_or(serde_json::Value::Null),
-                ))
-                .await?;
-            tracing::info!(
-                thought_id = %thought_id,
-                elapsed_ms = framework_start.elapsed().as_millis(),
-                "think.run_technical.framework_update.done"
-            );
-        }
+        let possible_duplicates = self.flag_possible_duplicates(&thought_id, &embedding).await;
+        let suggested_tags = if suggest_tags {
+            self.suggest_tags(&embedding, &tags).await
+        } else {
+            Vec::new()
+        };
+
+        // Framework enhancement
+        let framework_analysis = self.enhance_thought(&thought_id, &content, mode).await?;
+        let framework_enhanced = framework_analysis.is_some();
 
         // Memory injection - only if we have an embedding
         let tool_name = format!("think_{}", mode);
-        let (mem_count, enriched) = if !embedding.is_empty() {
+        let injection = if !embedding.is_empty() {
             let inject_start = std::time::Instant::now();
             self.inject_memories(
                 &thought_id,
                 &embedding,
                 injection_scale_val,
                 None,
+                &tags,
                 Some(&tool_name),
             )
             .await
@@ -285,24 +299,43 @@ impl SurrealMindServer {
                 tracing::info!(
                     thought_id = %thought_id,
                     elapsed_ms = inject_start.elapsed().as_millis(),
-                    memories_injected = v.0,
+                    memories_injected = v.count,
                     "think.run_technical.inject.done"
                 );
             })
-            .unwrap_or((0, None))
+            .unwrap_or_default()
         } else {
-            (0, None)
+            Default::default()
         };
 
         let mut original_result = json!({
             "thought_id": thought_id,
             "embedding_model": self.get_embedding_metadata().1,
             "embedding_dim": self.embedder.dimensions(),
-            "memories_injected": mem_count,
-            "enriched_content": enriched,
-            "framework_enhanced": framework_enhanced
+            "memories_injected": injection.count,
+            "pinned_memories": injection.pinned,
+            "enriched_content": injection.enriched,
+            "framework_enhanced": framework_enhanced,
+            "redactions": redactions
         });
 
+        if verbose_analysis.unwrap_or(false)
+            && let Some(analysis) = &framework_analysis
+        {
+            original_result["framework_analysis"] = json!({
+                "insights": analysis.insights,
+                "questions": analysis.questions,
+                "next_steps": analysis.next_steps
+            });
+        }
+
+        if !possible_duplicates.is_empty() {
+            original_result["possible_duplicates"] = json!(possible_duplicates);
+        }
+        if !suggested_tags.is_empty() {
+            original_result["suggested_tags"] = json!(suggested_tags);
+        }
+
         // Add embedding status warning if not complete
         if embedding_status != "complete" {
             original_result["embedding_status"] = json!(embedding_status);
@@ -319,4 +352,70 @@ impl SurrealMindServer {
 
         Ok((original_result, resolved_continuity))
     }
+
+    /// Run cognitive framework analysis for a thought and persist it
+    ///
+    /// Blends framework outputs using the weights for `mode` on the blocking pool,
+    /// bounded by `think_enhance_timeout_ms`. The result is stored in
+    /// `framework_analysis` with `framework_enhanced = true`.
+    ///
+    /// Returns `None` when `SURR_THINK_ENHANCE` is off, or the blend times out or panics.
+    pub(crate) async fn enhance_thought(
+        &self,
+        thought_id: &str,
+        content: &str,
+        mode: &str,
+    ) -> Result<Option<FrameworkOutput>> {
+        if !self.config.runtime.think_enhance {
+            return Ok(None);
+        }
+
+        let framework_start = std::time::Instant::now();
+        let profiles = self.config.cognitive.profiles.clone();
+        let blend_mode = mode.to_string();
+        let input = content.to_string();
+        let timeout =
+            std::time::Duration::from_millis(self.config.runtime.think_enhance_timeout_ms);
+        let blend = tokio::task::spawn_blocking(move || {
+            CognitiveEngine::new().blend_for_mode(&blend_mode, &input, &profiles)
+        });
+        let analysis = match tokio::time::timeout(timeout, blend).await {
+            Ok(Ok(analysis)) => analysis,
+            Ok(Err(e)) => {
+                tracing::warn!(thought_id = %thought_id, error = %e, "think.enhance.blend_failed");
+                return Ok(None);
+            }
+            Err(_) => {
+                tracing::warn!(
+                    thought_id = %thought_id,
+                    timeout_ms = timeout.as_millis(),
+                    "think.enhance.timeout"
+                );
+                return Ok(None);
+            }
+        };
+
+        let analysis_json = match serde_json::to_value(&analysis) {
+            Ok(val) => val,
+            Err(e) => {
+                tracing::error!("Failed to serialize framework analysis: {}", e);
+                return Ok(None);
+            }
+        };
+
+        self.db
+            .query("UPDATE type::record('thoughts', $id) SET framework_enhanced = true, framework_analysis = $analysis, submode = $mode RETURN NONE;")
+            .bind(("id", thought_id.to_string()))
+            .bind(("analysis", analysis_json))
+            .bind(("mode", mode.to_string()))
+            .await?;
+        tracing::info!(
+            thought_id = %thought_id,
+            mode = %mode,
+            elapsed_ms = framework_start.elapsed().as_millis(),
+            "think.enhance.done"
+        );
+
+        Ok(Some(analysis))
+    }
 }
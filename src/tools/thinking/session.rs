@@ -0,0 +1,205 @@
+//! Session inference for `think` calls without a `session_id`.
+//!
+//! The latest thought with the same origin decides: if it has a session and is at most
+//! `runtime.think_session_gap_minutes` old, the new thought joins that session, otherwise a
+//! new session id is minted. With `runtime.think_auto_link_previous`, a thought that joins a
+//! session and has no `previous_thought_id` is linked to the session's latest thought.
+//! Explicit `session_id` and `previous_thought_id` always win. The outcome is reported as
+//! `links.session` in the think result.
+
+use crate::error::Result;
+use crate::server::SurrealMindServer;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+const LATEST_BY_ORIGIN_SQL: &str = "SELECT meta::id(id) AS id, session_id, \
+    type::string(created_at) AS created_at FROM thoughts \
+    WHERE origin = $origin AND deleted_at = NONE ORDER BY created_at DESC LIMIT 1";
+
+const LATEST_IN_SESSION_SQL: &str = "SELECT meta::id(id) AS id, \
+    type::string(created_at) AS created_at FROM thoughts \
+    WHERE session_id = $session_id AND deleted_at = NONE ORDER BY created_at DESC LIMIT 1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionSource {
+    Inherited,
+    New,
+}
+
+/// How an omitted `session_id` was filled in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionInference {
+    pub source: SessionSource,
+    pub session_id: String,
+    /// Minutes since the latest thought with the same origin; absent when there was none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_minutes: Option<f64>,
+    /// That thought's id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_thought: Option<String>,
+    /// `previous_thought_id` filled in by `think_auto_link_previous`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_linked_previous: Option<String>,
+}
+
+/// The latest thought with the new thought's origin.
+#[derive(Debug, Clone)]
+pub struct LatestThought {
+    pub id: String,
+    pub session_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Join `latest`'s session when it is at most `gap_minutes` before `now`, else mint one.
+pub fn decide_session(
+    latest: Option<&LatestThought>,
+    now: DateTime<Utc>,
+    gap_minutes: u64,
+) -> SessionInference {
+    let gap = latest.map(|t| (now - t.created_at).num_seconds().max(0) as f64 / 60.0);
+    let inherited = latest
+        .zip(gap)
+        .filter(|(_, gap)| *gap <= gap_minutes as f64)
+        .and_then(|(t, _)| t.session_id.clone());
+    let (source, session_id) = match inherited {
+        Some(session) => (SessionSource::Inherited, session),
+        None => (SessionSource::New, uuid::Uuid::new_v4().to_string()),
+    };
+    SessionInference {
+        source,
+        session_id,
+        gap_minutes: gap.map(|g| (g * 10.0).round() / 10.0),
+        after_thought: latest.map(|t| t.id.clone()),
+        auto_linked_previous: None,
+    }
+}
+
+fn parse_row(row: &serde_json::Value) -> Option<LatestThought> {
+    Some(LatestThought {
+        id: row.get("id")?.as_str()?.to_string(),
+        session_id: row
+            .get("session_id")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+        created_at: DateTime::parse_from_rfc3339(row.get("created_at")?.as_str()?)
+            .ok()?
+            .with_timezone(&Utc),
+    })
+}
+
+impl SurrealMindServer {
+    /// Pick the session for a new thought from `origin` that has none; `link_previous` asks
+    /// for a `previous_thought_id` as well (only used when a session is inherited).
+    pub(crate) async fn infer_session(
+        &self,
+        origin: &str,
+        link_previous: bool,
+    ) -> Result<SessionInference> {
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(LATEST_BY_ORIGIN_SQL)
+            .bind(("origin", origin.to_string()))
+            .await?
+            .take(0)?;
+        let latest = rows.first().and_then(parse_row);
+        let runtime = &self.config.runtime;
+        let mut inference = decide_session(
+            latest.as_ref(),
+            Utc::now(),
+            runtime.think_session_gap_minutes,
+        );
+
+        if link_previous
+            && runtime.think_auto_link_previous
+            && inference.source == SessionSource::Inherited
+        {
+            let rows: Vec<serde_json::Value> = self
+                .db
+                .query(LATEST_IN_SESSION_SQL)
+                .bind(("session_id", inference.session_id.clone()))
+                .await?
+                .take(0)?;
+            inference.auto_linked_previous = rows
+                .first()
+                .and_then(|r| r.get("id"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+        }
+        Ok(inference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn latest(minutes_ago: i64, session: Option<&str>, now: DateTime<Utc>) -> LatestThought {
+        LatestThought {
+            id: "t1".into(),
+            session_id: session.map(str::to_string),
+            created_at: now - Duration::minutes(minutes_ago),
+        }
+    }
+
+    #[test]
+    fn test_gap_within_window_inherits_the_session() {
+        let now = Utc::now();
+        let decided = decide_session(Some(&latest(30, Some("s1"), now)), now, 45);
+        assert_eq!(decided.source, SessionSource::Inherited);
+        assert_eq!(decided.session_id, "s1");
+        assert_eq!(decided.gap_minutes, Some(30.0));
+        assert_eq!(decided.after_thought.as_deref(), Some("t1"));
+
+        // The window is inclusive
+        let edge = decide_session(Some(&latest(45, Some("s1"), now)), now, 45);
+        assert_eq!(edge.source, SessionSource::Inherited);
+    }
+
+    #[test]
+    fn test_long_gap_or_no_history_starts_a_new_session() {
+        let now = Utc::now();
+        let stale = decide_session(Some(&latest(46, Some("s1"), now)), now, 45);
+        assert_eq!(stale.source, SessionSource::New);
+        assert_ne!(stale.session_id, "s1");
+        assert_eq!(stale.gap_minutes, Some(46.0));
+
+        // A recent thought without a session has nothing to inherit
+        let sessionless = decide_session(Some(&latest(1, None, now)), now, 45);
+        assert_eq!(sessionless.source, SessionSource::New);
+
+        let first = decide_session(None, now, 45);
+        assert_eq!(first.source, SessionSource::New);
+        assert!(first.gap_minutes.is_none() && first.after_thought.is_none());
+        assert!(uuid::Uuid::parse_str(&first.session_id).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_infer_session_reads_origin_and_session_history() {
+        let mut config = crate::test_support::test_config();
+        config.runtime.think_auto_link_previous = true;
+        let server = crate::test_support::mem_server_with(config).await;
+        let thought = |id: &str, origin: &str, minutes_ago: i64| {
+            format!(
+                "CREATE thoughts:{id} SET content = '{id}', origin = '{origin}', \
+                 session_id = 's1', created_at = time::now() - {minutes_ago}m, \
+                 injection_scale = 0, significance = 0.5, access_count = 0, \
+                 injected_memories = [];"
+            )
+        };
+        let sql = [thought("a", "human", 10), thought("b", "tool", 5)].concat();
+        server.db.query(sql).await.unwrap().check().unwrap();
+
+        let inferred = server.infer_session("human", true).await.unwrap();
+        assert_eq!(inferred.source, SessionSource::Inherited);
+        assert_eq!(inferred.session_id, "s1");
+        assert_eq!(inferred.after_thought.as_deref(), Some("a"));
+        // The session's latest thought, whatever its origin
+        assert_eq!(inferred.auto_linked_previous.as_deref(), Some("b"));
+
+        let unlinked = server.infer_session("human", false).await.unwrap();
+        assert!(unlinked.auto_linked_previous.is_none());
+    }
+}
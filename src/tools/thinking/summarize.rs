@@ -0,0 +1,458 @@
+//! Session rollups for `maintain summarize_session`.
+//!
+//! A session's visible thoughts (not private, not soft-deleted, not themselves summaries) are
+//! read oldest first, up to `[maintenance.summarize] max_thoughts`, and condensed into one
+//! thought with origin `summary`, the session's `session_id`, `synthesis_type
+//! 'session_summary'`, the configured significance, and `summarizes` listing the covered
+//! thought ids. With `provider = "gemini"` the summary is written by the Gemini CLI; without
+//! it, or when the call fails, [`extractive_summary`] keeps the first sentence of the most
+//! significant thoughts in session order.
+//!
+//! There is one summary per session: a re-run finds it by origin and `session_id` and
+//! rewrites it in place (re-embedded) instead of creating another. Covered thoughts get
+//! `summarized_by`; with a `demote_factor` their significance is multiplied by it the first
+//! time a summary covers them, so re-runs do not compound.
+
+use crate::clients::CognitiveAgent;
+use crate::clients::gemini::{RetryPolicy, call_with_retry};
+use crate::config::SummarizeConfig;
+use crate::error::{Result, SurrealMindError};
+use crate::maintenance::soft_delete::records;
+use crate::server::SurrealMindServer;
+use crate::tools::thinking::ThoughtBuilder;
+use crate::utils::code_refs::extract_code_refs;
+use crate::utils::lang::detect_lang;
+use serde::Serialize;
+
+/// Origin of summary thoughts.
+pub const SUMMARY_ORIGIN: &str = "summary";
+
+/// Characters kept from each thought's first sentence.
+const POINT_CHARS: usize = 200;
+
+const SESSION_SQL: &str = "SELECT meta::id(id) AS id, content, significance, created_at \
+    FROM thoughts WHERE session_id = $session_id AND origin != 'summary' \
+    AND deleted_at = NONE AND is_private != true ORDER BY created_at ASC LIMIT $limit";
+
+const EXISTING_SQL: &str = "SELECT VALUE meta::id(id) FROM thoughts \
+    WHERE origin = 'summary' AND session_id = $session_id AND deleted_at = NONE LIMIT 1";
+
+const REWRITE_SQL: &str = "UPDATE type::record('thoughts', $id) SET
+    content = $content,
+    significance = $significance,
+    code_refs = $code_refs,
+    lang = $lang,
+    embedding = NONE,
+    embedded_at = NONE,
+    embedding_status = 'pending',
+    embedding_provider = $provider,
+    embedding_model = $model,
+    embedding_dim = $dim
+    RETURN NONE";
+
+const PROVENANCE_SQL: &str = "UPDATE type::record('thoughts', $id) \
+    SET summarizes = $ids, synthesis_type = 'session_summary' RETURN NONE";
+
+const COVER_SQL: &str = "UPDATE thoughts SET summarized_by = $summary \
+    WHERE id IN $recs AND summarized_by = NONE RETURN VALUE meta::id(id)";
+
+const DEMOTE_SQL: &str = "UPDATE thoughts SET significance = significance * $factor, \
+    summarized_by = $summary WHERE id IN $recs AND summarized_by = NONE \
+    RETURN VALUE meta::id(id)";
+
+/// A thought as read for summarizing.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct SessionThought {
+    pub id: String,
+    pub content: String,
+    #[serde(default)]
+    pub significance: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub dry_run: bool,
+    /// Summary thought id; absent on a dry run that would create one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary_id: Option<String>,
+    /// True when an existing summary was rewritten (or would be)
+    pub updated: bool,
+    /// "extractive" or "gemini"
+    pub method: &'static str,
+    /// Why a configured provider was not used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_error: Option<String>,
+    pub content: String,
+    pub summarizes: Vec<String>,
+    /// Thoughts whose significance was lowered by this run
+    pub demoted: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_status: Option<String>,
+}
+
+/// First sentence (or line) of `content`, trimmed and capped at [`POINT_CHARS`].
+fn first_sentence(content: &str) -> String {
+    let line = content.trim().lines().next().unwrap_or_default();
+    let mut end = line.len();
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if matches!(c, '.' | '!' | '?') && chars.peek().is_none_or(|(_, n)| n.is_whitespace()) {
+            end = i + c.len_utf8();
+            break;
+        }
+    }
+    let sentence = line[..end].trim();
+    let mut out: String = sentence.chars().take(POINT_CHARS).collect();
+    if sentence.chars().count() > POINT_CHARS {
+        out.push('…');
+    }
+    out
+}
+
+/// Deterministic summary: the first sentence of the `max_points` most significant thoughts
+/// (earlier ones win ties), listed in session order without repeats.
+pub fn extractive_summary(
+    session_id: &str,
+    thoughts: &[SessionThought],
+    max_points: usize,
+) -> String {
+    let mut ranked: Vec<usize> = (0..thoughts.len()).collect();
+    ranked.sort_by(|a, b| {
+        thoughts[*b]
+            .significance
+            .total_cmp(&thoughts[*a].significance)
+            .then(a.cmp(b))
+    });
+    let mut picked: Vec<usize> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for i in ranked {
+        if picked.len() >= max_points {
+            break;
+        }
+        let point = first_sentence(&thoughts[i].content);
+        if !point.is_empty() && seen.insert(point) {
+            picked.push(i);
+        }
+    }
+    picked.sort_unstable();
+    let mut out = format!(
+        "Summary of session {} ({} thoughts):",
+        session_id,
+        thoughts.len()
+    );
+    for i in picked {
+        out.push_str("\n- ");
+        out.push_str(&first_sentence(&thoughts[i].content));
+    }
+    out
+}
+
+fn summary_prompt(session_id: &str, thoughts: &[SessionThought]) -> String {
+    let mut prompt = format!(
+        "Summarize the following thoughts from working session {session_id} in one short \
+         paragraph for future retrieval. Keep decisions, findings, and open questions; drop \
+         chatter. Reply with the summary text only.\n"
+    );
+    for (i, t) in thoughts.iter().enumerate() {
+        prompt.push_str(&format!("\n[{}] {}", i + 1, t.content.trim()));
+    }
+    prompt
+}
+
+impl SurrealMindServer {
+    /// Create or rewrite the summary thought of `session_id`; see the module docs. `agent`
+    /// writes the summary when given; `demote_factor` overrides the configured one.
+    pub async fn summarize_session(
+        &self,
+        session_id: &str,
+        agent: Option<&dyn CognitiveAgent>,
+        demote_factor: Option<f32>,
+        dry_run: bool,
+    ) -> Result<SessionSummary> {
+        let cfg: &SummarizeConfig = &self.config.maintenance.summarize;
+        let session_id = session_id.trim();
+        if session_id.is_empty() {
+            return Err(SurrealMindError::Validation {
+                message: "summarize_session requires 'session_id'".into(),
+            });
+        }
+        let demote_factor = demote_factor.or(cfg.demote_factor);
+        if let Some(f) = demote_factor
+            && !(f > 0.0 && f < 1.0)
+        {
+            return Err(SurrealMindError::Validation {
+                message: format!("demote_factor must be in (0, 1), got {}", f),
+            });
+        }
+
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(SESSION_SQL)
+            .bind(("session_id", session_id.to_string()))
+            .bind(("limit", cfg.max_thoughts as i64))
+            .await?
+            .check()?
+            .take(0)?;
+        let thoughts: Vec<SessionThought> = rows
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<std::result::Result<_, _>>()?;
+        if thoughts.is_empty() {
+            return Err(SurrealMindError::not_found("session", session_id));
+        }
+        let existing: Vec<String> = self
+            .db
+            .query(EXISTING_SQL)
+            .bind(("session_id", session_id.to_string()))
+            .await?
+            .take(0)?;
+        let existing = existing.into_iter().next();
+
+        let mut method = "extractive";
+        let mut provider_error = None;
+        let mut text = None;
+        if let Some(agent) = agent {
+            let prompt = summary_prompt(session_id, &thoughts);
+            match call_with_retry(agent, &prompt, None, &RetryPolicy::from_env()).await {
+                (Ok(resp), _) if !resp.response.trim().is_empty() => {
+                    method = "gemini";
+                    text = Some(resp.response.trim().to_string());
+                }
+                (Ok(_), _) => provider_error = Some("empty response".to_string()),
+                (Err(e), _) => provider_error = Some(e.to_string()),
+            }
+            if let Some(e) = &provider_error {
+                tracing::warn!(session_id, error = %e, "summarize_session.provider_fallback");
+            }
+        }
+        let text =
+            text.unwrap_or_else(|| extractive_summary(session_id, &thoughts, cfg.max_points));
+        let (content, _) = self.redactor.redact(&text);
+        let summarizes: Vec<String> = thoughts.iter().map(|t| t.id.clone()).collect();
+
+        let mut summary = SessionSummary {
+            session_id: session_id.to_string(),
+            dry_run,
+            summary_id: existing.clone(),
+            updated: existing.is_some(),
+            method,
+            provider_error,
+            content: content.clone(),
+            summarizes: summarizes.clone(),
+            demoted: Vec::new(),
+            embedding_status: None,
+        };
+        if dry_run {
+            return Ok(summary);
+        }
+
+        let summary_id = match existing {
+            Some(id) => {
+                let (provider, model, dim) = self.get_embedding_metadata();
+                let code_refs = extract_code_refs(&content);
+                self.db
+                    .query(REWRITE_SQL)
+                    .bind(("id", id.clone()))
+                    .bind(("content", content.clone()))
+                    .bind(("significance", cfg.significance as f64))
+                    .bind((
+                        "code_refs",
+                        (!code_refs.is_empty())
+                            .then(|| serde_json::to_value(&code_refs))
+                            .transpose()?,
+                    ))
+                    .bind(("lang", detect_lang(&content)))
+                    .bind(("provider", provider))
+                    .bind(("model", model))
+                    .bind(("dim", dim))
+                    .await?
+                    .check()?;
+                self.evict_cached_thoughts(std::slice::from_ref(&id)).await;
+                let (_, status) = self.embed_new_thought(&id, &content).await?;
+                summary.embedding_status = Some(status.to_string());
+                id
+            }
+            None => {
+                let built = ThoughtBuilder::new(self, &content, SUMMARY_ORIGIN)
+                    .significance(Some(cfg.significance))
+                    .continuity(Some(session_id.to_string()), None, None, None, None)
+                    .execute()
+                    .await?;
+                summary.embedding_status = Some(built.embedding_status);
+                built.id
+            }
+        };
+        self.db
+            .query(PROVENANCE_SQL)
+            .bind(("id", summary_id.clone()))
+            .bind(("ids", summarizes.clone()))
+            .await?
+            .check()?;
+
+        let cover = match demote_factor {
+            Some(factor) => self.db.query(DEMOTE_SQL).bind(("factor", factor as f64)),
+            None => self.db.query(COVER_SQL),
+        };
+        let covered: Vec<String> = cover
+            .bind(("summary", summary_id.clone()))
+            .bind(("recs", records(&summarizes)))
+            .await?
+            .check()?
+            .take(0)?;
+        if demote_factor.is_some() {
+            self.evict_cached_thoughts(&covered).await;
+            summary.demoted = covered;
+        }
+        summary.summary_id = Some(summary_id);
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_extractive_summary_keeps_significant_first_sentences_in_order() {
+        let t = |id: &str, content: &str, significance: f64| SessionThought {
+            id: id.into(),
+            content: content.into(),
+            significance,
+        };
+        let thoughts = [
+            t("a", "Looked at the logs. Nothing there.", 0.3),
+            t("b", "Decided to gzip snapshots! Saves space.", 0.9),
+            t("c", "ok", 0.1),
+            t("d", "Open question: should restore verify hashes?", 0.7),
+            t("e", "Decided to gzip snapshots! Again.", 0.9),
+        ];
+        assert_eq!(
+            extractive_summary("s1", &thoughts, 3),
+            "Summary of session s1 (5 thoughts):\n\
+             - Looked at the logs.\n\
+             - Decided to gzip snapshots!\n\
+             - Open question: should restore verify hashes?"
+        );
+        assert_eq!(first_sentence("v1.2 shipped today"), "v1.2 shipped today");
+    }
+
+    #[tokio::test]
+    async fn test_summarize_session_is_idempotent_and_demotes_once() {
+        let server = crate::test_support::mem_server().await;
+        let thought =
+            |id: &str, content: &str, minutes_ago: i64, significance: f64, extra: &str| {
+                format!(
+                    "CREATE thoughts:{id} SET content = '{content}', session_id = 's1', \
+                 created_at = time::now() - {minutes_ago}m, injection_scale = 0, \
+                 significance = {significance}, access_count = 0, injected_memories = []{extra};"
+                )
+            };
+        let sql = [
+            thought(
+                "t1",
+                "Chose JSONL for export. It streams well.",
+                30,
+                0.8,
+                "",
+            ),
+            thought("t2", "Import must remap edge endpoints.", 20, 0.6, ""),
+            thought("hidden", "Private aside.", 15, 0.9, ", is_private = true"),
+            thought(
+                "gone",
+                "Deleted aside.",
+                10,
+                0.9,
+                ", deleted_at = time::now()",
+            ),
+        ]
+        .concat();
+        server.db.query(sql).await.unwrap().check().unwrap();
+
+        let dry = server
+            .summarize_session("s1", None, Some(0.5), true)
+            .await
+            .unwrap();
+        assert!(dry.summary_id.is_none() && !dry.updated && dry.demoted.is_empty());
+
+        let first = server
+            .summarize_session("s1", None, Some(0.5), false)
+            .await
+            .unwrap();
+        assert_eq!(first.method, "extractive");
+        assert_eq!(first.summarizes, vec!["t1", "t2"]);
+        assert_eq!(first.demoted.len(), 2);
+        assert!(first.content.contains("- Chose JSONL for export."));
+        assert!(!first.content.contains("aside"));
+        let summary_id = first.summary_id.clone().unwrap();
+
+        let row = |id: String| {
+            let db = server.db.clone();
+            async move {
+                let rows: Vec<Value> = db
+                    .query(
+                        "SELECT origin, session_id, significance, summarizes, summarized_by, \
+                         synthesis_type FROM type::record('thoughts', $id)",
+                    )
+                    .bind(("id", id))
+                    .await
+                    .unwrap()
+                    .take(0)
+                    .unwrap();
+                rows.into_iter().next().unwrap()
+            }
+        };
+        let stored = row(summary_id.clone()).await;
+        assert_eq!(stored["origin"], "summary");
+        assert_eq!(stored["session_id"], "s1");
+        assert_eq!(stored["synthesis_type"], "session_summary");
+        assert_eq!(stored["summarizes"], serde_json::json!(["t1", "t2"]));
+        assert!((stored["significance"].as_f64().unwrap() - 0.8).abs() < 1e-6);
+        let t1 = row("t1".into()).await;
+        assert!((t1["significance"].as_f64().unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(t1["summarized_by"], summary_id.as_str());
+        assert!(row("hidden".into()).await["summarized_by"].is_null());
+
+        // A new thought joins the session; the re-run rewrites the same summary and only
+        // demotes the newcomer
+        server
+            .db
+            .query(thought(
+                "t3",
+                "Verified round trip on 10k rows.",
+                5,
+                0.7,
+                "",
+            ))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        let second = server
+            .summarize_session("s1", None, Some(0.5), false)
+            .await
+            .unwrap();
+        assert!(second.updated);
+        assert_eq!(second.summary_id.as_deref(), Some(summary_id.as_str()));
+        assert_eq!(second.summarizes, vec!["t1", "t2", "t3"]);
+        assert_eq!(second.demoted, vec!["t3"]);
+        assert!(second.content.contains("(3 thoughts)"));
+        let t1 = row("t1".into()).await;
+        assert!((t1["significance"].as_f64().unwrap() - 0.4).abs() < 1e-9);
+        let summaries: Vec<String> = server
+            .db
+            .query("SELECT VALUE meta::id(id) FROM thoughts WHERE origin = 'summary'")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(summaries, vec![summary_id]);
+
+        let missing = server.summarize_session("nope", None, None, false).await;
+        assert!(matches!(
+            missing,
+            Err(SurrealMindError::NotFound { ref resource, .. }) if resource == "session"
+        ));
+    }
+}
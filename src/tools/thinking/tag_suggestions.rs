@@ -0,0 +1,116 @@
+//! Write-time tag suggestions from the knowledge graph.
+//!
+//! After a thought is embedded, the most similar `kg_entities` are looked up in a single
+//! query and the names of those scoring at least `runtime.think_suggest_tags_threshold` are
+//! returned as `suggested_tags`, minus tags the thought already has. Suggestions are advisory
+//! and never stored; failures are logged and yield no suggestions.
+
+use crate::server::SurrealMindServer;
+use serde_json::Value;
+
+/// Most similar entities considered per thought.
+pub const MAX_TAG_SUGGESTIONS: usize = 5;
+
+/// Names from rows (`{name, similarity}`) scoring at least `threshold`, best first, skipping
+/// names already in `tags` (case-insensitive) and repeats.
+pub fn select_tag_suggestions(rows: Vec<Value>, tags: &[String], threshold: f32) -> Vec<String> {
+    let mut scored: Vec<(String, f32)> = rows
+        .into_iter()
+        .filter_map(|r| {
+            let name = r.get("name")?.as_str()?.trim();
+            let score = r.get("similarity")?.as_f64()? as f32;
+            (!name.is_empty() && score >= threshold).then(|| (name.to_string(), score))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut seen: Vec<String> = tags.iter().map(|t| t.trim().to_lowercase()).collect();
+    let mut suggestions = Vec::new();
+    for (name, _) in scored {
+        let key = name.to_lowercase();
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+        suggestions.push(name);
+        if suggestions.len() == MAX_TAG_SUGGESTIONS {
+            break;
+        }
+    }
+    suggestions
+}
+
+impl SurrealMindServer {
+    /// Names of the KG entities closest to a thought's `embedding` that are not already in
+    /// `tags`. Returns an empty list when the embedding does not match the active embedder's
+    /// dimension or the query fails; entities stored at another dimension never match.
+    pub(crate) async fn suggest_tags(&self, embedding: &[f32], tags: &[String]) -> Vec<String> {
+        if embedding.is_empty() || embedding.len() != self.embedder.dimensions() {
+            return Vec::new();
+        }
+
+        // One query only: a KNN failure disables the index for later calls instead of
+        // retrying with a scan here
+        let use_knn = self.vector_index_ready();
+        let sql = format!(
+            "SELECT name, vector::similarity::cosine(embedding, $q) AS similarity \
+             FROM kg_entities WHERE embedding_dim = $dim AND {} \
+             ORDER BY similarity DESC LIMIT $top",
+            self.embedding_filter(MAX_TAG_SUGGESTIONS, use_knn)
+        );
+        let result = self
+            .db
+            .query(sql)
+            .bind(("dim", embedding.len() as i64))
+            .bind(("q", embedding.to_vec()))
+            .bind(("top", MAX_TAG_SUGGESTIONS as i64))
+            .await
+            .and_then(|r| r.check());
+        let rows: Vec<Value> = match result {
+            Ok(mut r) => r.take(0).unwrap_or_default(),
+            Err(e) => {
+                if use_knn {
+                    self.disable_vector_index(&e);
+                }
+                tracing::warn!(error = %e, "think.suggest_tags.query_failed");
+                return Vec::new();
+            }
+        };
+        select_tag_suggestions(rows, tags, self.config.runtime.think_suggest_tags_threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_select_tag_suggestions_thresholds_and_excludes_existing_tags() {
+        let rows = vec![
+            json!({"name": "photography workflow", "similarity": 0.71}),
+            json!({"name": "SurrealDB", "similarity": 0.93}),
+            json!({"name": "Rust", "similarity": 0.88}),
+            json!({"name": "surrealdb", "similarity": 0.80}),
+            json!({"name": "Gardening", "similarity": 0.20}),
+            json!({"name": "   ", "similarity": 0.99}),
+            json!({"similarity": 0.97}),
+        ];
+        let tags = vec!["rust".to_string()];
+        assert_eq!(
+            select_tag_suggestions(rows, &tags, 0.6),
+            vec!["SurrealDB", "photography workflow"]
+        );
+    }
+
+    #[test]
+    fn test_select_tag_suggestions_caps_results() {
+        let rows = (0..8)
+            .map(|i| json!({"name": format!("entity-{i}"), "similarity": 0.9}))
+            .collect();
+        assert_eq!(
+            select_tag_suggestions(rows, &[], 0.5).len(),
+            MAX_TAG_SUGGESTIONS
+        );
+    }
+}
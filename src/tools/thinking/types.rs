@@ -8,20 +8,60 @@ use serde::{Deserialize, Serialize};
 /// Maximum content size in bytes (100KB)
 pub const MAX_CONTENT_SIZE: usize = 100 * 1024;
 
-/// Default contradiction patterns for hypothesis verification (case-insensitive)
-pub const CONTRADICTION_PATTERNS: &[&str] = &[
+/// Maximum number of references attached to one thought
+pub const MAX_REFERENCES: usize = 20;
+
+/// Maximum number of thoughts in one `think_batch` call
+pub const MAX_BATCH_THOUGHTS: usize = 20;
+
+/// Explicit contradiction phrases for hypothesis verification (matched as whole tokens)
+pub const CONTRADICTION_PHRASES: &[&str] = &[
+    "is not",
+    "isn't",
+    "are not",
+    "aren't",
+    "does not",
+    "doesn't",
+    "do not",
+    "don't",
+    "cannot",
+    "can't",
+    "fails to",
+    "failed to",
+];
+
+/// Negation cues that count as a contradiction only near a hypothesis term
+pub const NEGATION_CUES: &[&str] = &[
     "not",
     "no",
-    "cannot",
+    "never",
+    "none",
+    "neither",
+    "nor",
+    "without",
     "false",
     "incorrect",
     "fails",
     "broken",
-    "doesn't",
-    "isn't",
     "won't",
+    "cannot",
+    "can't",
+    "isn't",
+    "doesn't",
 ];
 
+/// Idioms that contain a negation cue but do not negate anything
+pub const NEGATION_IDIOMS: &[&str] = &[
+    "cannot wait",
+    "can't wait",
+    "no doubt",
+    "not only",
+    "no matter",
+];
+
+/// Default token window between a negation cue and a hypothesis term
+pub const DEFAULT_NEGATION_WINDOW: usize = 4;
+
 /// Modes for legacymind_think routing
 #[derive(Debug, Clone, PartialEq)]
 pub enum ThinkMode {
@@ -43,17 +83,122 @@ pub struct EvidenceItem {
     pub provenance: Option<serde_json::Value>,
 }
 
+/// How hypothesis verification turns evidence into `confidence_score`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationScoring {
+    /// supporting / (supporting + contradicting), 0.5 without evidence
+    #[default]
+    Simple,
+    /// Similarity-weighted vote; see `verification::weighted_confidence`
+    Weighted,
+}
+
+/// Inputs behind `confidence_score`, over the evidence kept after `evidence_limit`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationCalibration {
+    pub scoring: VerificationScoring,
+    /// Similarity floor evidence had to reach (`min_similarity`)
+    pub min_similarity: f32,
+    pub supporting_count: usize,
+    pub contradicting_count: usize,
+    /// Absent for an empty bucket
+    pub supporting_mean_similarity: Option<f32>,
+    pub supporting_max_similarity: Option<f32>,
+    pub contradicting_mean_similarity: Option<f32>,
+    pub contradicting_max_similarity: Option<f32>,
+}
+
 /// Verification result for hypothesis verification
 #[derive(Debug, Clone, Serialize)]
 pub struct VerificationResult {
     pub hypothesis: String,
+    /// blake3 of the normalized hypothesis; keys `hypothesis_history`
+    pub hypothesis_hash: String,
     pub supporting: Vec<EvidenceItem>,
     pub contradicting: Vec<EvidenceItem>,
     pub confidence_score: f32,
+    pub calibration: VerificationCalibration,
     pub suggested_revision: Option<String>,
     pub telemetry: serde_json::Value,
 }
 
+/// How much of the think result is returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseDetail {
+    /// Mode reasoning, delegated result, links, telemetry, and verification
+    #[default]
+    Full,
+    /// thought_id, mode_selected, memories_injected, a one-line links summary, and
+    /// verification counts when verification ran
+    Compact,
+    /// Only thought_id
+    IdsOnly,
+}
+
+impl ResponseDetail {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "full" => Some(Self::Full),
+            "compact" => Some(Self::Compact),
+            "ids_only" => Some(Self::IdsOnly),
+            _ => None,
+        }
+    }
+}
+
+/// What a thought reference points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceKind {
+    File,
+    Url,
+    Commit,
+}
+
+/// A file path, URL, or commit stored structurally on a thought
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reference {
+    pub kind: ReferenceKind,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Check a thought's references: at most [`MAX_REFERENCES`], files absolute, URLs parseable,
+/// commits a 7-40 digit hex SHA. Returns the first problem found.
+pub fn validate_references(references: &[Reference]) -> std::result::Result<(), String> {
+    if references.len() > MAX_REFERENCES {
+        return Err(format!(
+            "At most {} references per thought, got {}",
+            MAX_REFERENCES,
+            references.len()
+        ));
+    }
+    for (i, reference) in references.iter().enumerate() {
+        let value = reference.value.as_str();
+        let problem = match reference.kind {
+            _ if value.trim().is_empty() => Some("value is empty"),
+            ReferenceKind::File if !std::path::Path::new(value).is_absolute() => {
+                Some("file path must be absolute")
+            }
+            ReferenceKind::Url if reqwest::Url::parse(value).is_err() => Some("URL does not parse"),
+            ReferenceKind::Commit
+                if !(7..=40).contains(&value.len())
+                    || !value.chars().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                Some("commit must be a 7-40 digit hex SHA")
+            }
+            _ => None,
+        };
+        if let Some(problem) = problem {
+            return Err(format!("references[{}]: {} ({:?})", i, problem, value));
+        }
+    }
+    Ok(())
+}
+
 /// Parameters for legacymind_think
 #[derive(Debug, Deserialize)]
 pub struct LegacymindThinkParams {
@@ -101,10 +246,85 @@ pub struct LegacymindThinkParams {
     pub evidence_limit: Option<usize>,
     #[serde(default)]
     pub contradiction_patterns: Option<Vec<String>>,
+    /// Verification scoring; defaults to `simple`
+    #[serde(default)]
+    pub scoring: Option<VerificationScoring>,
+    /// Client retry key: a repeated call with the same key returns the original thought
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Return `suggested_tags` from similar KG entities; defaults to `runtime.think_suggest_tags`
+    #[serde(default)]
+    pub suggest_tags: Option<bool>,
+    /// Files, URLs, or commits the thought is about; see [`validate_references`]
+    #[serde(default)]
+    pub references: Option<Vec<Reference>>,
+    /// Hide the thought from retrieval unless a caller passes `include_private`
+    #[serde(default)]
+    pub is_private: Option<bool>,
+    /// Inject the thought into later matching thinks regardless of similarity
+    #[serde(default)]
+    pub pinned: Option<bool>,
+    /// Result size; defaults to `runtime.think_response_detail`
+    #[serde(default)]
+    pub response_detail: Option<ResponseDetail>,
+}
+
+/// One thought in a `think_batch` call: the single-thought fields, minus verification and
+/// result shaping, plus an optional `local_ref`
+#[derive(Debug, Deserialize)]
+pub struct ThinkBatchItem {
+    pub content: String,
+    /// Name later items use in `previous_thought_id`, `revises_thought`, or `branch_from`
+    #[serde(default)]
+    pub local_ref: Option<String>,
+    #[serde(default)]
+    pub hint: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_u8_forgiving"
+    )]
+    pub injection_scale: Option<u8>,
+    #[serde(default, deserialize_with = "crate::deserializers::de_option_tags")]
+    pub tags: Option<Vec<String>>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_f32_forgiving"
+    )]
+    pub significance: Option<f32>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub chain_id: Option<String>,
+    #[serde(default)]
+    pub previous_thought_id: Option<String>,
+    #[serde(default)]
+    pub revises_thought: Option<String>,
+    #[serde(default)]
+    pub branch_from: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_f32_forgiving"
+    )]
+    pub confidence: Option<f32>,
+    /// Stored like a single think's key; one that already exists fails the whole batch
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    #[serde(default)]
+    pub references: Option<Vec<Reference>>,
+    #[serde(default)]
+    pub is_private: Option<bool>,
+    #[serde(default)]
+    pub pinned: Option<bool>,
+}
+
+/// Parameters for think_batch
+#[derive(Debug, Deserialize)]
+pub struct ThinkBatchParams {
+    pub thoughts: Vec<ThinkBatchItem>,
 }
 
 /// Result struct for continuity links resolution
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ContinuityResult {
     pub session_id: Option<String>,
     pub chain_id: Option<String>,
@@ -113,6 +333,9 @@ pub struct ContinuityResult {
     pub branch_from: Option<String>,
     pub confidence: Option<f32>,
     pub links_resolved: serde_json::Value,
+    /// Set when `session_id` was inferred rather than passed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_inference: Option<super::session::SessionInference>,
 }
 
 /// Process a database query result for continuity link resolution
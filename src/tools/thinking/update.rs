@@ -0,0 +1,266 @@
+//! In-place thought edits for `maintain update_thought`.
+//!
+//! `replace_content` swaps the content, `append` adds text after a blank line. The content
+//! before the edit is pushed onto the thought's `revisions` (`{content, edited_at, reason,
+//! mode}`) and `revision_count` goes up by one. The new content is redacted, its `code_refs`
+//! and `lang` re-derived, and it is re-embedded with the current embedding metadata; a
+//! failed embed leaves `embedding_status` pending or failed for `embed_pending`. Synthesized
+//! thoughts (origin `inner_voice`) and soft-deleted ones are refused, the former unless
+//! `force`.
+
+use crate::error::{Result, SurrealMindError};
+use crate::server::SurrealMindServer;
+use crate::tools::thinking::validate_thought_input;
+use crate::utils::code_refs::extract_code_refs;
+use crate::utils::lang::detect_lang;
+use serde::Serialize;
+
+/// Origins whose content was generated rather than written, and is only edited with `force`.
+pub const PROTECTED_ORIGINS: &[&str] = &["inner_voice"];
+
+const LOAD_SQL: &str = "SELECT meta::id(id) AS id, content, origin, revision_count, \
+    deleted_at != NONE AS deleted FROM type::record('thoughts', $id)";
+
+// SET clauses apply in order, so the revision records the content before the edit
+const UPDATE_SQL: &str = "UPDATE type::record('thoughts', $id) SET
+    revisions = array::append(revisions ?? [], {
+        content: content, edited_at: time::now(), reason: $reason, mode: $mode
+    }),
+    revision_count = (revision_count ?? 0) + 1,
+    content = $content,
+    code_refs = $code_refs,
+    lang = $lang,
+    embedding = NONE,
+    embedded_at = NONE,
+    embedding_status = 'pending',
+    embedding_provider = $provider,
+    embedding_model = $model,
+    embedding_dim = $dim
+    RETURN VALUE revision_count";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditMode {
+    ReplaceContent,
+    Append,
+}
+
+impl EditMode {
+    pub fn parse(mode: &str) -> Result<Self> {
+        match mode {
+            "replace_content" => Ok(Self::ReplaceContent),
+            "append" => Ok(Self::Append),
+            other => Err(SurrealMindError::InvalidParams {
+                message: format!(
+                    "update_thought mode must be 'replace_content' or 'append', got '{}'",
+                    other
+                ),
+                field: None,
+            }),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::ReplaceContent => "replace_content",
+            Self::Append => "append",
+        }
+    }
+}
+
+/// Content after applying `mode` with `text` to `current`.
+pub fn edited_content(current: &str, mode: EditMode, text: &str) -> String {
+    match mode {
+        EditMode::ReplaceContent => text.to_string(),
+        EditMode::Append if current.trim().is_empty() => text.to_string(),
+        EditMode::Append => format!("{}\n\n{}", current.trim_end(), text),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThoughtEdit {
+    pub thought_id: String,
+    pub mode: EditMode,
+    pub dry_run: bool,
+    /// Revisions stored after this edit
+    pub revision_count: i64,
+    pub content: String,
+    pub redactions: usize,
+    pub code_refs: usize,
+    /// Absent on a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_dim: Option<usize>,
+}
+
+impl SurrealMindServer {
+    /// Edit thought `id` in place; see the module docs.
+    pub async fn update_thought(
+        &self,
+        id: &str,
+        mode: EditMode,
+        text: &str,
+        reason: Option<String>,
+        force: bool,
+        dry_run: bool,
+    ) -> Result<ThoughtEdit> {
+        let id = id
+            .strip_prefix("thoughts:")
+            .unwrap_or(id)
+            .trim()
+            .to_string();
+        if text.trim().is_empty() {
+            return Err(SurrealMindError::Validation {
+                message: "update_thought requires non-empty 'content'".into(),
+            });
+        }
+        let rows: Vec<serde_json::Value> = self
+            .db
+            .query(LOAD_SQL)
+            .bind(("id", id.clone()))
+            .await?
+            .take(0)?;
+        let Some(row) = rows.into_iter().next() else {
+            return Err(SurrealMindError::not_found("thought", id));
+        };
+        if row["deleted"].as_bool().unwrap_or(false) {
+            return Err(SurrealMindError::Conflict {
+                message: format!("thought '{}' is soft-deleted; restore it first", id),
+                data: Some(serde_json::json!({ "id": id, "deleted": true })),
+            });
+        }
+        let origin = row["origin"].as_str().unwrap_or_default();
+        if PROTECTED_ORIGINS.contains(&origin) && !force {
+            return Err(SurrealMindError::Validation {
+                message: format!(
+                    "thought '{}' has origin '{}' (synthesized); pass force: true to edit it",
+                    id, origin
+                ),
+            });
+        }
+
+        let current = row["content"].as_str().unwrap_or_default();
+        let (content, redactions) = self.redactor.redact(&edited_content(current, mode, text));
+        validate_thought_input(&content, &[])?;
+        if content == current {
+            return Err(SurrealMindError::Validation {
+                message: format!("update_thought would leave thought '{}' unchanged", id),
+            });
+        }
+        let code_refs = extract_code_refs(&content);
+        let mut edit = ThoughtEdit {
+            thought_id: id.clone(),
+            mode,
+            dry_run,
+            revision_count: row["revision_count"].as_i64().unwrap_or(0) + 1,
+            content: content.clone(),
+            redactions,
+            code_refs: code_refs.len(),
+            embedding_status: None,
+            embedding_dim: None,
+        };
+        if dry_run {
+            return Ok(edit);
+        }
+
+        let (provider, model, dim) = self.get_embedding_metadata();
+        let updated: Vec<i64> = self
+            .db
+            .query(UPDATE_SQL)
+            .bind(("id", id.clone()))
+            .bind(("content", content.clone()))
+            .bind(("reason", reason))
+            .bind(("mode", mode.as_str()))
+            .bind((
+                "code_refs",
+                (!code_refs.is_empty())
+                    .then(|| serde_json::to_value(&code_refs))
+                    .transpose()?,
+            ))
+            .bind(("lang", detect_lang(&content)))
+            .bind(("provider", provider))
+            .bind(("model", model))
+            .bind(("dim", dim))
+            .await?
+            .check()?
+            .take(0)?;
+        if let Some(count) = updated.first() {
+            edit.revision_count = *count;
+        }
+        self.evict_cached_thoughts(std::slice::from_ref(&id)).await;
+
+        let (embedding, status) = self.embed_new_thought(&id, &content).await?;
+        edit.embedding_status = Some(status.to_string());
+        edit.embedding_dim = Some(embedding.len());
+        Ok(edit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edited_content_per_mode() {
+        assert_eq!(
+            edited_content("teh plan", EditMode::ReplaceContent, "the plan"),
+            "the plan"
+        );
+        assert_eq!(
+            edited_content(
+                "Plan: gzip snapshots\n",
+                EditMode::Append,
+                "Addendum: zstd is faster"
+            ),
+            "Plan: gzip snapshots\n\nAddendum: zstd is faster"
+        );
+        assert_eq!(edited_content("  ", EditMode::Append, "only"), "only");
+    }
+
+    #[test]
+    fn test_mode_parsing() {
+        assert_eq!(
+            EditMode::parse("replace_content").unwrap(),
+            EditMode::ReplaceContent
+        );
+        assert_eq!(EditMode::parse("append").unwrap(), EditMode::Append);
+        assert!(matches!(
+            EditMode::parse("prepend"),
+            Err(SurrealMindError::InvalidParams { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_deleted_thoughts_are_not_found_and_conflict() {
+        let server = crate::test_support::mem_server().await;
+        server
+            .db
+            .query(
+                "CREATE thoughts:gone SET content = 'old', created_at = time::now(), \
+                 injection_scale = 0, significance = 0.5, access_count = 0, \
+                 injected_memories = [], deleted_at = time::now();",
+            )
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        let edit = |id: &'static str| {
+            server.update_thought(id, EditMode::Append, "more", None, false, true)
+        };
+
+        let missing = rmcp::ErrorData::from(edit("nope").await.unwrap_err());
+        assert_eq!(missing.code, rmcp::model::ErrorCode::RESOURCE_NOT_FOUND);
+        let data = missing.data.unwrap();
+        assert_eq!(
+            (data["resource"].as_str(), data["id"].as_str()),
+            (Some("thought"), Some("nope"))
+        );
+
+        let deleted = rmcp::ErrorData::from(edit("thoughts:gone").await.unwrap_err());
+        assert_eq!(deleted.code, rmcp::model::ErrorCode::INVALID_REQUEST);
+        let data = deleted.data.unwrap();
+        assert_eq!(data["class"], "conflict");
+        assert_eq!(data["deleted"], true);
+    }
+}
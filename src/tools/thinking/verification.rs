@@ -2,12 +2,35 @@
 //!
 //! This module provides functionality for verifying hypotheses by finding
 //! supporting and contradicting evidence in the knowledge graph.
+//!
+//! With `persist_verification` on, every verification of a hypothesis is also appended to
+//! `hypothesis_history` under [`hypothesis_hash`], so `maintain hypothesis_trend` can return
+//! the score series for the same hypothesis across sessions.
 
-use super::types::{CONTRADICTION_PATTERNS, EvidenceItem, VerificationResult};
-use crate::error::Result;
+use super::types::{
+    CONTRADICTION_PHRASES, EvidenceItem, NEGATION_CUES, NEGATION_IDIOMS, VerificationCalibration,
+    VerificationResult, VerificationScoring,
+};
+use crate::embeddings::EmbedPurpose;
+use crate::error::{Result, SurrealMindError};
 use crate::server::SurrealMindServer;
+use serde::Serialize;
 use serde_json::json;
 
+/// Virtual votes added to the weighted score, split evenly between the two sides
+const WEIGHTED_PRIOR: f32 = 1.0;
+
+const HISTORY_INSERT_SQL: &str = "CREATE hypothesis_history CONTENT {
+    hypothesis_hash: $hash, hypothesis: $hypothesis, confidence_score: $score,
+    scoring: $scoring, calibration: $calibration, thought_id: $thought_id,
+    session_id: $session_id, created_at: time::now()
+}";
+
+const HISTORY_SERIES_SQL: &str = "SELECT type::string(created_at) AS verified_at, \
+    confidence_score, scoring, calibration.supporting_count AS supporting_count, \
+    calibration.contradicting_count AS contradicting_count, thought_id, session_id, hypothesis, \
+    created_at FROM hypothesis_history WHERE hypothesis_hash = $hash ORDER BY created_at ASC";
+
 impl SurrealMindServer {
     /// Build text from KG entity or observation for embedding
     ///
@@ -36,7 +59,8 @@ impl SurrealMindServer {
     /// * `top_k` - Maximum candidates to retrieve from each KG table
     /// * `min_similarity` - Minimum cosine similarity threshold (0.0-1.0)
     /// * `evidence_limit` - Maximum items per category (supporting/contradicting)
-    /// * `contradiction_patterns` - Optional custom patterns indicating contradiction
+    /// * `contradiction_patterns` - Optional extra phrases indicating contradiction
+    /// * `scoring` - How evidence becomes `confidence_score`
     ///
     /// # Returns
     /// `VerificationResult` containing supporting/contradicting evidence and confidence score
@@ -47,6 +71,7 @@ impl SurrealMindServer {
         min_similarity: f32,
         evidence_limit: usize,
         contradiction_patterns: Option<&[String]>,
+        scoring: VerificationScoring,
     ) -> Result<Option<VerificationResult>> {
         let start = std::time::Instant::now();
 
@@ -69,27 +94,24 @@ impl SurrealMindServer {
             );
         }
 
-        let embedding = self.embedder.embed(hypothesis).await?;
+        let embedding = self
+            .embedder
+            .embed_with_purpose(hypothesis, EmbedPurpose::SearchQuery)
+            .await?;
         let q_dim = embedding.len() as i64;
 
-        let patterns = contradiction_patterns.unwrap_or(&[]).to_vec();
-        let default_patterns: Vec<String> = CONTRADICTION_PATTERNS
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let all_patterns = if patterns.is_empty() {
-            &default_patterns
-        } else {
-            &patterns
-        };
+        // Custom patterns add phrases on top of the built-in contradiction phrases
+        let extra_phrases = contradiction_patterns.unwrap_or(&[]);
+        let negation_window = self.config.runtime.verify_negation_window;
+        let hypothesis_terms = content_terms(hypothesis);
+        let mut contradiction_rules = Vec::new();
 
         // Query KG entities and observations
+        let order = &self.config.runtime.verify_candidate_order;
         let query_sql = format!(
-            "SELECT meta::id(id) as id, name, data, embedding FROM kg_entities \
-             WHERE embedding_dim = $dim AND embedding IS NOT NULL LIMIT {}; \
-             SELECT meta::id(id) as id, name, data, embedding FROM kg_observations \
-             WHERE embedding_dim = $dim AND embedding IS NOT NULL LIMIT {};",
-            top_k as i64, top_k as i64
+            "{}; {};",
+            candidate_sql("kg_entities", order, top_k),
+            candidate_sql("kg_observations", order, top_k)
         );
 
         if std::env::var("RUST_LOG")
@@ -115,6 +137,7 @@ impl SurrealMindServer {
         rows.append(&mut rows2);
 
         let total_candidates = rows.len();
+        let candidate_created_range = created_range(&rows);
 
         if std::env::var("RUST_LOG")
             .unwrap_or_default()
@@ -157,7 +180,10 @@ impl SurrealMindServer {
                     }
                 }
                 if emb_opt.is_none() {
-                    let new_emb = self.embedder.embed(&text).await?;
+                    let new_emb = self
+                        .embedder
+                        .embed_with_purpose(&text, EmbedPurpose::Reembed)
+                        .await?;
                     if new_emb.len() == embedding.len() {
                         emb_opt = Some(new_emb.clone());
                         // Persist (similar to inject_memories)
@@ -179,11 +205,13 @@ impl SurrealMindServer {
                             similarity: sim,
                             provenance: data.cloned(),
                         };
-                        let lower_text = text.to_lowercase();
-                        let is_contradiction = all_patterns
-                            .iter()
-                            .any(|pat| lower_text.contains(&pat.to_lowercase()));
-                        if is_contradiction {
+                        if let Some(rule) = detect_contradiction(
+                            &text,
+                            &hypothesis_terms,
+                            extra_phrases,
+                            negation_window,
+                        ) {
+                            contradiction_rules.push(json!({"id": id, "rule": rule}));
                             contradicting.push(item);
                             matched_contradict += 1;
                         } else {
@@ -220,11 +248,29 @@ impl SurrealMindServer {
         supporting.truncate(evidence_limit);
         contradicting.truncate(evidence_limit);
 
-        let total = supporting.len() + contradicting.len();
-        let confidence_score = if total > 0 {
-            supporting.len() as f32 / total as f32
-        } else {
-            0.5
+        let support_sims: Vec<f32> = supporting.iter().map(|e| e.similarity).collect();
+        let contradict_sims: Vec<f32> = contradicting.iter().map(|e| e.similarity).collect();
+        let confidence_score = match scoring {
+            VerificationScoring::Simple => {
+                simple_confidence(support_sims.len(), contradict_sims.len())
+            }
+            VerificationScoring::Weighted => {
+                weighted_confidence(&support_sims, &contradict_sims, min_similarity)
+            }
+        };
+        let (supporting_mean_similarity, supporting_max_similarity) =
+            similarity_stats(&support_sims);
+        let (contradicting_mean_similarity, contradicting_max_similarity) =
+            similarity_stats(&contradict_sims);
+        let calibration = VerificationCalibration {
+            scoring,
+            min_similarity,
+            supporting_count: support_sims.len(),
+            contradicting_count: contradict_sims.len(),
+            supporting_mean_similarity,
+            supporting_max_similarity,
+            contradicting_mean_similarity,
+            contradicting_max_similarity,
         };
 
         let suggested_revision = if confidence_score < 0.4 {
@@ -247,15 +293,21 @@ impl SurrealMindServer {
             "matched_support": matched_support,
             "matched_contradict": matched_contradict,
             "total_candidates": total_candidates,
+            "candidate_order": order,
+            "candidate_created_range": candidate_created_range,
             "candidates_with_embedding": candidates_with_embedding,
-            "candidates_after_similarity": candidates_after_similarity
+            "candidates_after_similarity": candidates_after_similarity,
+            "negation_window": negation_window,
+            "contradiction_rules": contradiction_rules
         });
 
         let result = VerificationResult {
             hypothesis: hypothesis.to_string(),
+            hypothesis_hash: hypothesis_hash(hypothesis),
             supporting,
             contradicting,
             confidence_score,
+            calibration,
             suggested_revision,
             telemetry,
         };
@@ -263,3 +315,436 @@ impl SurrealMindServer {
         Ok(Some(result))
     }
 }
+
+/// Verifications of one hypothesis, oldest first
+#[derive(Debug, Clone, Serialize)]
+pub struct HypothesisTrend {
+    pub hypothesis_hash: String,
+    /// Text of the latest verification; absent when there is none
+    pub hypothesis: Option<String>,
+    pub count: usize,
+    /// `{verified_at, confidence_score, scoring, supporting_count, contradicting_count,
+    /// thought_id, session_id}` per verification
+    pub series: Vec<serde_json::Value>,
+}
+
+impl SurrealMindServer {
+    /// Append `verification` to `hypothesis_history`.
+    pub(crate) async fn record_hypothesis_history(
+        &self,
+        verification: &VerificationResult,
+        thought_id: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        self.db
+            .query(HISTORY_INSERT_SQL)
+            .bind(("hash", verification.hypothesis_hash.clone()))
+            .bind(("hypothesis", verification.hypothesis.clone()))
+            .bind(("score", verification.confidence_score as f64))
+            .bind((
+                "scoring",
+                serde_json::to_value(verification.calibration.scoring)?,
+            ))
+            .bind((
+                "calibration",
+                serde_json::to_value(&verification.calibration)?,
+            ))
+            .bind(("thought_id", thought_id.map(str::to_string)))
+            .bind(("session_id", session_id.map(str::to_string)))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    /// Score series for the hypothesis with `hash` (see [`hypothesis_hash`]).
+    pub async fn hypothesis_trend(&self, hash: &str) -> Result<HypothesisTrend> {
+        let hash = hash.trim().to_lowercase();
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(SurrealMindError::InvalidParams {
+                message: format!(
+                    "hypothesis_hash must be 64 hex characters (blake3), got '{}'",
+                    hash
+                ),
+                field: None,
+            });
+        }
+        let mut series: Vec<serde_json::Value> = self
+            .db
+            .query(HISTORY_SERIES_SQL)
+            .bind(("hash", hash.clone()))
+            .await?
+            .take(0)?;
+        // created_at is only selected for the ORDER BY; the text is reported once
+        let mut hypothesis = None;
+        for row in series.iter_mut().filter_map(|r| r.as_object_mut()) {
+            row.remove("created_at");
+            if let Some(text) = row
+                .remove("hypothesis")
+                .and_then(|v| v.as_str().map(str::to_string))
+            {
+                hypothesis = Some(text);
+            }
+        }
+        Ok(HypothesisTrend {
+            hypothesis_hash: hash,
+            hypothesis,
+            count: series.len(),
+            series,
+        })
+    }
+}
+
+/// blake3 hex digest of `hypothesis` lowercased, with runs of whitespace collapsed and
+/// trailing sentence punctuation dropped, so trivially different phrasings share a history.
+pub fn hypothesis_hash(hypothesis: &str) -> String {
+    let normalized = hypothesis
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let normalized = normalized.trim_end_matches(['.', '!', '?']).trim_end();
+    blake3::hash(normalized.as_bytes()).to_hex().to_string()
+}
+
+/// supporting / (supporting + contradicting), or 0.5 without evidence.
+pub(crate) fn simple_confidence(supporting: usize, contradicting: usize) -> f32 {
+    match supporting + contradicting {
+        0 => 0.5,
+        total => supporting as f32 / total as f32,
+    }
+}
+
+/// Similarity-weighted confidence. Each evidence item votes with weight
+/// `(sim - floor) / (1 - floor)` clamped to 0..=1, so an item just over the `min_similarity`
+/// floor counts for almost nothing and an exact match counts fully. [`WEIGHTED_PRIOR`]
+/// virtual votes, half on each side, keep thin evidence near 0.5:
+/// `(Σw_support + prior/2) / (Σw_support + Σw_contradict + prior)`. No evidence gives 0.5.
+pub(crate) fn weighted_confidence(supporting: &[f32], contradicting: &[f32], floor: f32) -> f32 {
+    let span = (1.0 - floor).max(f32::EPSILON);
+    let votes = |sims: &[f32]| -> f32 {
+        sims.iter()
+            .map(|s| ((s - floor) / span).clamp(0.0, 1.0))
+            .sum()
+    };
+    let (support, contradict) = (votes(supporting), votes(contradicting));
+    (support + WEIGHTED_PRIOR / 2.0) / (support + contradict + WEIGHTED_PRIOR)
+}
+
+/// (mean, max) of `sims`, or (None, None) when empty.
+fn similarity_stats(sims: &[f32]) -> (Option<f32>, Option<f32>) {
+    if sims.is_empty() {
+        return (None, None);
+    }
+    let mean = sims.iter().sum::<f32>() / sims.len() as f32;
+    let max = sims.iter().copied().fold(f32::MIN, f32::max);
+    (Some(mean), Some(max))
+}
+
+/// Candidate fetch for one KG table: rows with a non-empty embedding of the query's
+/// dimension, in a fixed order so `limit` keeps the same rows between runs. `order` is
+/// `runtime.verify_candidate_order`; `confidence` ranks by extraction_confidence and anything
+/// else by recency.
+pub(crate) fn candidate_sql(table: &str, order: &str, limit: usize) -> String {
+    let order_by = match order {
+        "confidence" => "extraction_confidence DESC, created_at DESC, id ASC",
+        _ => "created_at DESC, id ASC",
+    };
+    format!(
+        "SELECT meta::id(id) as id, name, data, embedding, created_at, extraction_confidence \
+         FROM {table} WHERE embedding_dim = $dim \
+         AND (IF type::is_array(embedding) THEN array::len(embedding) ELSE 0 END) > 0 \
+         ORDER BY {order_by} LIMIT {limit}"
+    )
+}
+
+/// `{oldest, newest}` created_at among the fetched candidates, or null when none have one.
+fn created_range(rows: &[serde_json::Value]) -> serde_json::Value {
+    let mut dates = rows.iter().filter_map(|r| {
+        r.get("created_at")
+            .and_then(|v| v.as_str())
+            .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+    });
+    let Some(first) = dates.next() else {
+        return serde_json::Value::Null;
+    };
+    let (oldest, newest) = dates.fold((first, first), |(lo, hi), d| (lo.min(d), hi.max(d)));
+    json!({"oldest": oldest.to_rfc3339(), "newest": newest.to_rfc3339()})
+}
+
+/// Lowercase tokens; apostrophes and hyphens stay inside words ("doesn't", "no-op").
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '-'))
+        .map(|t| t.trim_matches(|c| c == '\'' || c == '-'))
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Content-bearing hypothesis terms (stopwords, negation cues and short words excluded).
+fn content_terms(text: &str) -> std::collections::HashSet<String> {
+    const STOPWORDS: &[&str] = &[
+        "the", "and", "for", "are", "was", "were", "with", "this", "that", "from", "has", "have",
+        "its", "into", "than", "then",
+    ];
+    tokenize(text)
+        .into_iter()
+        .filter(|t| {
+            t.len() >= 3 && !STOPWORDS.contains(&t.as_str()) && !NEGATION_CUES.contains(&t.as_str())
+        })
+        .collect()
+}
+
+/// Start indices where `phrase` occurs as a whole-token sequence in `tokens`.
+fn phrase_positions(tokens: &[String], phrase: &str) -> Vec<usize> {
+    let needle = tokenize(phrase);
+    if needle.is_empty() || needle.len() > tokens.len() {
+        return Vec::new();
+    }
+    (0..=tokens.len() - needle.len())
+        .filter(|&i| tokens[i..i + needle.len()] == needle[..])
+        .collect()
+}
+
+/// Decide whether evidence text contradicts the hypothesis; returns the rule that fired.
+///
+/// Rules, in order: an explicit contradiction phrase, a caller-supplied phrase, or a
+/// negation cue within `window` tokens of a hypothesis term. Tokens that belong to a
+/// known idiom ("cannot wait", "no doubt") are ignored.
+pub(crate) fn detect_contradiction(
+    text: &str,
+    hypothesis_terms: &std::collections::HashSet<String>,
+    extra_phrases: &[String],
+    window: usize,
+) -> Option<String> {
+    let mut tokens = tokenize(text);
+    for idiom in NEGATION_IDIOMS {
+        let len = tokenize(idiom).len();
+        for start in phrase_positions(&tokens, idiom) {
+            for t in &mut tokens[start..start + len] {
+                t.clear();
+            }
+        }
+    }
+
+    if let Some(phrase) = CONTRADICTION_PHRASES
+        .iter()
+        .find(|p| !phrase_positions(&tokens, p).is_empty())
+    {
+        return Some(format!("phrase:{}", phrase));
+    }
+    if let Some(phrase) = extra_phrases
+        .iter()
+        .find(|p| !phrase_positions(&tokens, p).is_empty())
+    {
+        return Some(format!("custom:{}", phrase));
+    }
+
+    for (i, cue) in tokens.iter().enumerate() {
+        if !NEGATION_CUES.contains(&cue.as_str()) {
+            continue;
+        }
+        let lo = i.saturating_sub(window);
+        let hi = (i + window).min(tokens.len() - 1);
+        if let Some(term) = tokens[lo..=hi]
+            .iter()
+            .find(|t| hypothesis_terms.contains(t.as_str()))
+        {
+            return Some(format!("negation_scope:{}~{}", cue, term));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_sql_is_ordered_and_skips_empty_embeddings() {
+        let recent = candidate_sql("kg_entities", "recent", 25);
+        assert!(recent.contains("FROM kg_entities"));
+        assert!(recent.contains("array::len(embedding) ELSE 0 END) > 0"));
+        assert!(recent.ends_with("ORDER BY created_at DESC, id ASC LIMIT 25"));
+
+        let by_confidence = candidate_sql("kg_observations", "confidence", 5);
+        assert!(by_confidence.contains("ORDER BY extraction_confidence DESC, created_at DESC"));
+        // Unknown orders fall back to recency
+        assert_eq!(candidate_sql("kg_entities", "significance", 25), recent);
+    }
+
+    #[test]
+    fn test_created_range_spans_candidates() {
+        let rows = vec![
+            json!({"created_at": "2026-03-02T10:00:00Z"}),
+            json!({"created_at": "2026-01-15T08:30:00+00:00"}),
+            json!({"name": "no timestamp"}),
+            json!({"created_at": "2026-02-01T00:00:00Z"}),
+        ];
+        assert_eq!(
+            created_range(&rows),
+            json!({"oldest": "2026-01-15T08:30:00+00:00", "newest": "2026-03-02T10:00:00+00:00"})
+        );
+        assert!(created_range(&[json!({"name": "x"})]).is_null());
+    }
+
+    fn detect(text: &str, hypothesis: &str) -> Option<String> {
+        detect_contradiction(
+            text,
+            &content_terms(hypothesis),
+            &[],
+            crate::tools::thinking::types::DEFAULT_NEGATION_WINDOW,
+        )
+    }
+
+    #[test]
+    fn test_negation_substrings_are_not_contradictions() {
+        let hyp = "The parser handles empty input";
+        assert_eq!(detect("Notable improvements in the parser", hyp), None);
+        assert_eq!(detect("Parser cleanup was a no-op", hyp), None);
+        assert_eq!(detect("We cannot wait to ship the parser", hyp), None);
+        assert_eq!(detect("Nothing about tokenizers here", hyp), None);
+    }
+
+    #[test]
+    fn test_explicit_phrases_contradict() {
+        let hyp = "The parser handles empty input";
+        assert_eq!(
+            detect("The parser does not handle empty input", hyp).as_deref(),
+            Some("phrase:does not")
+        );
+        assert_eq!(
+            detect("Parser fails to handle empty files", hyp).as_deref(),
+            Some("phrase:fails to")
+        );
+    }
+
+    #[test]
+    fn test_negation_scope_requires_nearby_hypothesis_term() {
+        let hyp = "The parser handles empty input";
+        assert_eq!(
+            detect("Empty input never reaches the parser", hyp).as_deref(),
+            Some("negation_scope:never~empty")
+        );
+        // Cue too far from any hypothesis term
+        assert_eq!(
+            detect(
+                "No regressions were seen in the overnight batch jobs for billing or the parser",
+                hyp
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_custom_patterns_match_as_phrases() {
+        let terms = content_terms("The cache is warm");
+        let extra = vec!["went cold".to_string()];
+        assert_eq!(
+            detect_contradiction("The cache went cold overnight", &terms, &extra, 4).as_deref(),
+            Some("custom:went cold")
+        );
+        assert_eq!(
+            detect_contradiction("The cache wentcold overnight", &terms, &extra, 4),
+            None
+        );
+    }
+
+    #[test]
+    fn test_weighted_confidence_math() {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-5;
+        // No evidence, or evidence exactly at the floor, is neutral
+        assert_eq!(weighted_confidence(&[], &[], 0.7), 0.5);
+        assert!(close(weighted_confidence(&[0.7], &[0.7], 0.7), 0.5));
+        // Floor 0.5: 0.9 weighs 0.8, 0.6 weighs 0.2 -> (0.8 + 0.5) / (0.8 + 0.2 + 1)
+        assert!(close(weighted_confidence(&[0.9], &[0.6], 0.5), 0.65));
+        // Strong support outweighs several weak contradictions, unlike the simple share
+        let support = [0.95, 0.95];
+        let contradict = [0.72, 0.72, 0.72];
+        assert!(weighted_confidence(&support, &contradict, 0.7) > 0.7);
+        assert!(close(simple_confidence(2, 3), 0.4));
+        // Thin evidence stays near the middle; weights are capped at 1
+        assert!(close(weighted_confidence(&[1.0], &[], 0.7), 0.75));
+        assert!(close(weighted_confidence(&[1.2], &[], 0.7), 0.75));
+        // A floor of 1 leaves nothing above it rather than dividing by zero
+        assert_eq!(weighted_confidence(&[1.0], &[], 1.0), 0.5);
+        assert_eq!(simple_confidence(0, 0), 0.5);
+    }
+
+    #[test]
+    fn test_similarity_stats_and_hash_normalization() {
+        assert_eq!(similarity_stats(&[]), (None, None));
+        let (mean, max) = similarity_stats(&[0.8, 0.6, 0.7]);
+        assert!((mean.unwrap() - 0.7).abs() < 1e-5);
+        assert_eq!(max, Some(0.8));
+
+        let hash = hypothesis_hash("HNSW indexes must be rebuilt.");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hypothesis_hash("  hnsw  indexes\nmust be REBUILT "));
+        assert_ne!(hash, hypothesis_hash("HNSW indexes must not be rebuilt"));
+    }
+
+    #[tokio::test]
+    async fn test_verification_history_is_appended_and_trended() {
+        use crate::test_support::{call, mem_server_with, test_config};
+
+        let mut config = test_config();
+        config.runtime.persist_verification = true;
+        let server = mem_server_with(config).await;
+        for (session, scoring) in [("s1", "simple"), ("s2", "weighted")] {
+            let out = server
+                .handle_legacymind_think(call(
+                    "think",
+                    json!({"content": "checking the cache", "session_id": session,
+                           "hypothesis": "The cache survives restarts.", "needs_verification": true,
+                           "scoring": scoring}),
+                ))
+                .await
+                .unwrap()
+                .structured_content
+                .unwrap();
+            let calibration = &out["verification"]["calibration"];
+            assert_eq!(calibration["scoring"], scoring);
+            assert_eq!(calibration["supporting_count"], 0);
+            assert!(calibration["supporting_mean_similarity"].is_null());
+        }
+
+        let trend = server
+            .hypothesis_trend(&hypothesis_hash("the cache survives restarts"))
+            .await
+            .unwrap();
+        assert_eq!(trend.count, 2);
+        assert_eq!(
+            trend.hypothesis.as_deref(),
+            Some("The cache survives restarts.")
+        );
+        let sessions: Vec<&str> = trend
+            .series
+            .iter()
+            .map(|p| p["session_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(sessions, ["s1", "s2"]);
+        assert_eq!(trend.series[1]["scoring"], "weighted");
+        assert_eq!(trend.series[0]["confidence_score"], 0.5);
+        assert!(trend.series[0]["thought_id"].is_string());
+        assert!(trend.series[0].get("hypothesis").is_none());
+
+        let via_maintain = server
+            .handle_maintenance_ops(call(
+                "maintain",
+                json!({"subcommand": "hypothesis_trend", "hypothesis": "The cache survives restarts"}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        assert_eq!(via_maintain["trend"]["count"], 2);
+
+        let none = server
+            .hypothesis_trend(&hypothesis_hash("other"))
+            .await
+            .unwrap();
+        assert_eq!((none.count, none.hypothesis), (0, None));
+        assert!(server.hypothesis_trend("not-a-hash").await.is_err());
+    }
+}
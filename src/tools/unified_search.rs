@@ -1,14 +1,26 @@
 //! Unified search over memories (default) and optional thoughts
 
+use crate::embeddings::{EmbedPurpose, Embedder};
 use crate::error::{Result, SurrealMindError};
+use crate::maintenance::embedding_migration::{
+    EmbeddingField, pick_stored_embedding, stored_embeddings,
+};
+use crate::maintenance::observation_provenance::attach_provenance;
+use crate::maintenance::observations::OBSERVATION_TEXT_SQL;
+use crate::maintenance::privacy::privacy_filter;
 use crate::server::SurrealMindServer;
-use chrono::NaiveDate;
+use crate::tools::thinking::Reference;
+use crate::utils::code_refs::CodeRef;
+use crate::utils::dates::{parse_date_bound, parse_date_range};
+use crate::utils::highlight::{DEFAULT_SNIPPET_WINDOW, best_window};
+use crate::utils::tagexpr::TagExpr;
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct UnifiedSearchParams {
     #[serde(default)]
     pub query: Option<serde_json::Value>,
@@ -42,6 +54,14 @@ pub struct UnifiedSearchParams {
     pub branch_from: Option<String>,
     #[serde(default)]
     pub origin: Option<String>,
+    /// ISO 639-1 code (or `und`) the thought's detected language must match
+    #[serde(default)]
+    pub lang: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_f32_forgiving"
+    )]
+    pub min_significance: Option<f32>,
     #[serde(default)]
     pub confidence_gte: Option<f32>,
     #[serde(default)]
@@ -54,16 +74,188 @@ pub struct UnifiedSearchParams {
     pub order: Option<String>,
     #[serde(default)]
     pub forensic: Option<bool>,
+    #[serde(default)]
+    pub chain_summary: Option<bool>,
+    #[serde(default)]
+    pub thought_id: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub max_thoughts: Option<usize>,
+    /// Return the chronological timeline of `entity` instead of searching
+    #[serde(default)]
+    pub entity_timeline: Option<bool>,
+    /// Entity id or name for `entity_timeline`
+    #[serde(default)]
+    pub entity: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub max_events: Option<usize>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub max_mentions: Option<usize>,
+    #[serde(default)]
+    pub mention_threshold: Option<f32>,
+    /// Timeline page start: the `ts` of the last event on the previous page
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub expand_graph: Option<bool>,
+    /// Attach `source_name`/`target_name` to relationship hits (default true)
+    #[serde(default)]
+    pub include_names: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub graph_depth: Option<usize>,
+    #[serde(default)]
+    pub graph_boost: Option<f32>,
+    #[serde(default)]
+    pub min_edge_strength: Option<f32>,
+    /// Only follow and return relationships valid at this date
+    #[serde(default)]
+    pub as_of: Option<String>,
+    #[serde(default)]
+    pub include_superseded: Option<bool>,
+    /// Only thoughts with (true) or without (false) references
+    #[serde(default)]
+    pub has_reference: Option<bool>,
+    /// Only thoughts with a reference whose value contains this substring
+    #[serde(default)]
+    pub reference_contains: Option<String>,
+    /// Only thoughts with a code reference (path, error code, URL) found in their content
+    /// that contains this substring, ignoring case
+    #[serde(default)]
+    pub code_ref_contains: Option<String>,
+    /// Also return thoughts marked `is_private`
+    #[serde(default)]
+    pub include_private: Option<bool>,
+    /// Only pinned thoughts
+    #[serde(default)]
+    pub only_pinned: Option<bool>,
+    /// Boolean tag filter such as `(rust AND surrealdb) OR mcp`, over thought tags and
+    /// memory `data.tags`
+    #[serde(default)]
+    pub tag_expr: Option<String>,
+    /// Halve a thought's similarity for every this many days of age (0 or unset: off)
+    #[serde(default)]
+    pub recency_half_life_days: Option<f32>,
+    /// Add this times the thought's significance to its score (default 0)
+    #[serde(default)]
+    pub significance_weight: Option<f32>,
+    /// Attach each thought's score parts (`sim`, `recency_factor`, `significance_bonus`, `final`)
+    #[serde(default)]
+    pub explain_scores: Option<bool>,
+    /// Highlight window for each thought hit, in characters (default 240)
+    #[serde(
+        default,
+        deserialize_with = "crate::deserializers::de_option_usize_forgiving"
+    )]
+    pub snippet_window: Option<usize>,
+    /// Return each thought's highlight `snippet` instead of its full `content`
+    #[serde(default)]
+    pub snippet_only: Option<bool>,
+    /// Vector field to search (`embedding` or `embedding_v2`); the query is embedded with the
+    /// model stored in that field. Unset searches `embedding` with the active embedder.
+    #[serde(default)]
+    pub embedding_field: Option<EmbeddingField>,
 }
 
 #[derive(Debug, Serialize)]
 struct ThoughtOut {
     id: String,
-    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    /// Byte offsets of the best query-term window in `content`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    span_start: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    span_end: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     similarity: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     significance: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    origin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    superseded_by: Option<String>,
+    /// Superseded thought this successor stands in for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    substituted_for: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    references: Vec<Reference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    code_refs: Vec<CodeRef>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pinned: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<ScoreParts>,
+}
+
+impl ThoughtOut {
+    fn from_row(r: &serde_json::Value) -> Self {
+        let text = |field: &str| r.get(field).and_then(|v| v.as_str()).map(String::from);
+        ThoughtOut {
+            id: text("id").unwrap_or_default(),
+            content: Some(text("content").unwrap_or_default()),
+            span_start: None,
+            span_end: None,
+            snippet: None,
+            similarity: r
+                .get("similarity")
+                .and_then(|v| v.as_f64())
+                .map(|f| f as f32),
+            significance: Some(
+                r.get("significance")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0) as f32,
+            ),
+            session_id: text("session_id"),
+            chain_id: text("chain_id"),
+            origin: text("origin"),
+            lang: text("lang"),
+            superseded_by: text("superseded_by"),
+            substituted_for: text("substituted_for"),
+            references: r
+                .get("references")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+            code_refs: r
+                .get("code_refs")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+            pinned: r.get("pinned").and_then(|v| v.as_bool()).unwrap_or(false),
+            score: None,
+        }
+    }
+
+    /// Attach the best `window`-character span of the content for `query`; with
+    /// `snippet_only`, drop the full content.
+    fn highlight(&mut self, query: &str, window: usize, snippet_only: bool) {
+        let content = self.content.as_deref().unwrap_or_default();
+        if let Some((start, end)) = best_window(content, query, window) {
+            self.span_start = Some(start);
+            self.span_end = Some(end);
+            self.snippet = Some(content[start..end].to_string());
+        }
+        if snippet_only {
+            self.content = None;
+        }
+    }
 }
 
 impl SurrealMindServer {
@@ -72,33 +264,85 @@ impl SurrealMindServer {
         &self,
         request: CallToolRequestParams,
     ) -> Result<CallToolResult> {
-        unified_search_inner(self, request).await
+        let args = request.arguments.ok_or_else(|| SurrealMindError::Mcp {
+            message: "Missing parameters".into(),
+        })?;
+        let params: UnifiedSearchParams = serde_json::from_value(serde_json::Value::Object(args))
+            .map_err(|e| SurrealMindError::Serialization {
+            message: format!("Invalid parameters: {}", e),
+        })?;
+        let out = unified_search(self, params).await?;
+        Ok(CallToolResult::structured(out))
     }
 }
 
-pub async fn unified_search_inner(
+/// Run a unified search, returning `{memories, thoughts?}`, `{chain_summary}`, or
+/// `{entity_timeline}`.
+pub async fn unified_search(
     server: &SurrealMindServer,
-    request: CallToolRequestParams,
-) -> Result<CallToolResult> {
-    let args = request.arguments.ok_or_else(|| SurrealMindError::Mcp {
-        message: "Missing parameters".into(),
-    })?;
-    let params: UnifiedSearchParams = serde_json::from_value(serde_json::Value::Object(args))
-        .map_err(|e| SurrealMindError::Serialization {
-            message: format!("Invalid parameters: {}", e),
-        })?;
-
+    params: UnifiedSearchParams,
+) -> Result<serde_json::Value> {
     // Debug logging for chain_id search
     if let Some(ref cid) = params.chain_id {
         tracing::info!("🔍 Unified search requested with chain_id: {}", cid);
     }
 
+    let include_private = params.include_private.unwrap_or(false);
+    if params.chain_summary.unwrap_or(false) {
+        if params.chain_id.is_none() && params.thought_id.is_none() {
+            return Err(SurrealMindError::Validation {
+                message: "chain_summary requires chain_id or thought_id".into(),
+            });
+        }
+        let summary = server
+            .chain_summary(
+                params.chain_id.as_deref(),
+                params.thought_id.as_deref(),
+                params
+                    .max_thoughts
+                    .unwrap_or(crate::tools::thinking::chain::DEFAULT_CHAIN_MAX_THOUGHTS),
+                include_private,
+            )
+            .await?;
+        return Ok(json!({ "chain_summary": summary }));
+    }
+    if params.entity_timeline.unwrap_or(false) {
+        let Some(entity) = params.entity.as_deref() else {
+            return Err(SurrealMindError::Validation {
+                message: "entity_timeline requires entity".into(),
+            });
+        };
+        let timeline = server
+            .entity_timeline(
+                entity,
+                crate::tools::entity_timeline::TimelineOptions {
+                    max_events: params.max_events,
+                    max_mentions: params.max_mentions,
+                    mention_threshold: params.mention_threshold,
+                    cursor: params.cursor.clone(),
+                    include_private,
+                },
+            )
+            .await?;
+        return Ok(json!({ "entity_timeline": timeline }));
+    }
+
     let target = params.target.unwrap_or_else(|| "mixed".to_string());
     let forensic = params.forensic.unwrap_or(false);
     let include_thoughts = params.include_thoughts.unwrap_or(false);
     let top_k_mem = params.top_k_memories.unwrap_or(10).clamp(1, 50);
     let top_k_th = params.top_k_thoughts.unwrap_or(5).clamp(1, 50);
     let sim_thresh = params.sim_thresh.unwrap_or(0.0).clamp(0.0, 1.0);
+    let ranking = ThoughtRanking::new(params.recency_half_life_days, params.significance_weight)?;
+    let tag_expr = params
+        .tag_expr
+        .as_deref()
+        .map(TagExpr::parse)
+        .transpose()
+        .map_err(|e| SurrealMindError::InvalidParams {
+            message: format!("Invalid tag_expr: {}", e),
+            field: None,
+        })?;
 
     // Clamp confidence bounds to [0,1]
     let confidence_gte = params.confidence_gte.map(|v| v.clamp(0.0, 1.0));
@@ -106,24 +350,20 @@ pub async fn unified_search_inner(
 
     // Parse date bounds into SurrealDB datetime values. The thoughts table stores
     // created_at as datetime, so binding strings here makes the predicate fail.
-    let date_from_bound = params
-        .date_from
-        .as_deref()
-        .map(|d| parse_search_date_bound(d, false, "date_from"))
-        .transpose()?;
-    let date_to_bound = params
-        .date_to
+    let date_range = parse_date_range(
+        params.date_from.as_deref(),
+        params.date_to.as_deref(),
+        ["date_from", "date_to"],
+        chrono::Utc::now(),
+    )?;
+    let date_from_bound = date_range.from.map(surrealdb::types::Datetime::from);
+    let as_of = params
+        .as_of
         .as_deref()
-        .map(|d| parse_search_date_bound(d, true, "date_to"))
-        .transpose()?;
-
-    if let (Some(df), Some(dt)) = (&date_from_bound, &date_to_bound)
-        && df > dt
-    {
-        return Err(SurrealMindError::Serialization {
-            message: "date_from cannot be after date_to".into(),
-        });
-    }
+        .map(|v| parse_date_bound(v, false, "as_of", chrono::Utc::now()))
+        .transpose()?
+        .map(surrealdb::types::Datetime::from);
+    let date_to_bound = date_range.to.map(surrealdb::types::Datetime::from);
 
     // Build a simple name-like predicate from query if available
     let mut name_like: Option<String> = None;
@@ -158,8 +398,13 @@ pub async fn unified_search_inner(
         content = nl.clone();
     }
     let has_query = !content.is_empty();
+    let vectors = QueryVectors::resolve(server, params.embedding_field).await?;
     let q_emb = if has_query {
-        match server.embedder.embed(&content).await {
+        match vectors
+            .embedder
+            .embed_with_purpose(&content, EmbedPurpose::SearchQuery)
+            .await
+        {
             Ok(emb) => Some(emb),
             Err(e) => {
                 tracing::warn!(
@@ -269,10 +514,13 @@ pub async fn unified_search_inner(
 
         if !found_semantic && let Some(ref q_emb_val) = q_emb {
             // Semantic search using embeddings
-            let q_dim = q_emb_val.len() as i64;
             // UPDATED: Order by similarity DESC (not created_at) for semantic search
-            let mut sql = "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at, vector::similarity::cosine(embedding, $q) AS similarity
-                 FROM kg_entities WHERE embedding_dim = $dim AND embedding IS NOT NONE".to_string();
+            let mut sql = format!(
+                "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at, {} AS similarity
+                 FROM kg_entities WHERE {} AND {{filter}}",
+                vectors.similarity_sql(),
+                vectors.metadata_filter()
+            );
 
             if params.chain_id.is_some() {
                 sql.push_str(" AND ");
@@ -284,15 +532,15 @@ pub async fn unified_search_inner(
                 top_k_mem * 3
             ));
 
-            let mut query = server
-                .db
-                .query(sql)
-                .bind(("dim", q_dim))
-                .bind(("q", q_emb_val.clone()));
-            if let Some(ref chain_ids) = chain_filter_ids {
-                query = query.bind(("chain_ids", chain_ids.clone()));
-            }
-            let rows: Vec<serde_json::Value> = query.await?.take(0)?;
+            let rows = kg_similarity_rows(
+                server,
+                &vectors,
+                &sql,
+                top_k_mem * 3,
+                q_emb_val,
+                chain_filter_ids.as_ref(),
+            )
+            .await?;
 
             let mut scored_entities: Vec<serde_json::Value> = Vec::new();
             for row in rows {
@@ -329,7 +577,7 @@ pub async fn unified_search_inner(
             if let Some(ref nl) = name_like {
                 // Fallback to name pattern matching when no embedding available
                 let mut sql =
-                    "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_entities WHERE name ~ $name"
+                    "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_entities WHERE string::contains(string::lowercase(name), string::lowercase($name))"
                         .to_string();
                 if params.chain_id.is_some() {
                     sql.push_str(" AND ");
@@ -376,21 +624,109 @@ pub async fn unified_search_inner(
             }
         }
     }
-    if target == "relationship" || target == "mixed" {
-        let mut sql = "SELECT meta::id(id) as id,
-                    (IF meta::tb(source) IS NOT NONE THEN meta::id(source) ELSE string::concat(source) END) as source_id,
-                    (IF meta::tb(target) IS NOT NONE THEN meta::id(target) ELSE string::concat(target) END) as target_id,
+    if params.expand_graph.unwrap_or(false) && (target == "entity" || target == "mixed") {
+        let expansion = GraphExpansion {
+            depth: params.graph_depth.unwrap_or(1).clamp(1, MAX_GRAPH_DEPTH),
+            boost: params
+                .graph_boost
+                .unwrap_or(server.config.runtime.kg_graph_boost)
+                .clamp(0.0, 1.0),
+            min_edge_strength: params
+                .min_edge_strength
+                .unwrap_or(server.config.runtime.kg_min_edge_strength),
+            as_of,
+            node_budget: server.config.runtime.kg_max_neighbors,
+        };
+        let neighbors = expand_entity_neighbors(server, &items, &expansion).await?;
+        items.extend(neighbors);
+    }
+    let mut found_semantic_edges = false;
+    if (target == "relationship" || target == "mixed")
+        && let Some(ref q_emb_val) = q_emb
+    {
+        // Edges carry no HNSW index, so score every embedded edge (see maintain embed_edges)
+        let mut sql = format!(
+            "SELECT meta::id(id) as id,
+                    (IF type::is_record(source) THEN meta::id(source) ELSE string::concat(source) END) as source_id,
+                    (IF type::is_record(target) THEN meta::id(target) ELSE string::concat(target) END) as target_id,
+                    {EDGE_ENDPOINT_TABLES}, {EDGE_ATTRIBUTE_FIELDS},
+                    rel_type, data, type::string(created_at) as ts_created,
+                    {} AS similarity
+             FROM kg_edges WHERE {} AND {}",
+            vectors.similarity_sql(),
+            vectors.metadata_filter(),
+            vectors.presence_filter()
+        );
+        if params.chain_id.is_some() {
+            sql.push_str(" AND ");
+            sql.push_str(&chain_filter_sql("data."));
+        }
+        if as_of.is_some() {
+            sql.push_str(" AND ");
+            sql.push_str(EDGE_VALID_AT_SQL);
+        }
+        sql.push_str(&format!(" ORDER BY similarity DESC LIMIT {}", top_k_mem));
+        let mut query = vectors.bind(
+            server
+                .db
+                .query(sql)
+                .bind(("dim", q_emb_val.len() as i64))
+                .bind(("q", q_emb_val.clone())),
+        );
+        if let Some(ref chain_ids) = chain_filter_ids {
+            query = query.bind(("chain_ids", chain_ids.clone()));
+        }
+        if let Some(at) = as_of {
+            query = query.bind(("as_of", at));
+        }
+        let rows: Vec<serde_json::Value> = query.await?.take(0)?;
+        let scored: Vec<serde_json::Value> = rows
+            .into_iter()
+            .filter(|r| {
+                r.get("similarity")
+                    .and_then(|v| v.as_f64())
+                    .is_some_and(|sim| sim as f32 >= sim_thresh)
+            })
+            .map(|mut v| {
+                if let Some(obj) = v.as_object_mut() {
+                    obj.insert("kind".to_string(), json!("relationship"));
+                }
+                v
+            })
+            .collect();
+        if !scored.is_empty() {
+            items.extend(scored);
+            found_semantic_edges = true;
+        }
+    }
+    if (target == "relationship" || target == "mixed") && !found_semantic_edges {
+        let mut sql = format!(
+            "SELECT meta::id(id) as id,
+                    (IF type::is_record(source) THEN meta::id(source) ELSE string::concat(source) END) as source_id,
+                    (IF type::is_record(target) THEN meta::id(target) ELSE string::concat(target) END) as target_id,
+                    {EDGE_ENDPOINT_TABLES}, {EDGE_ATTRIBUTE_FIELDS},
                     rel_type, data, type::string(created_at) as ts_created
-             FROM kg_edges".to_string();
+             FROM kg_edges"
+        );
+        let mut filters = Vec::new();
         if params.chain_id.is_some() {
+            filters.push(chain_filter_sql("data."));
+        }
+        if as_of.is_some() {
+            filters.push(EDGE_VALID_AT_SQL.to_string());
+        }
+        if !filters.is_empty() {
             sql.push_str(" WHERE ");
-            sql.push_str(&chain_filter_sql("data."));
+            sql.push_str(&filters.join(" AND "));
         }
         sql.push_str(&format!(" ORDER BY ts_created DESC LIMIT {}", top_k_mem));
         let mut query = server.db.query(sql);
         if let Some(ref chain_ids) = chain_filter_ids {
             query = query.bind(("chain_ids", chain_ids.clone()));
         }
+        if let Some(at) = as_of {
+            query = query.bind(("as_of", at));
+        }
         let rows: Vec<serde_json::Value> = query.await?.take(0)?;
 
         items.extend(rows.into_iter().map(|mut v| {
@@ -400,7 +736,11 @@ pub async fn unified_search_inner(
             v
         }));
     }
+    if target == "relationship" || target == "mixed" {
+        resolve_edge_endpoints(server, &mut items, params.include_names.unwrap_or(true)).await?;
+    }
     if target == "observation" || target == "mixed" {
+        let observations_start = items.len();
         let mut found_semantic_obs = false;
 
         // Direct ID lookup for observations
@@ -413,7 +753,9 @@ pub async fn unified_search_inner(
             };
 
             if table == "kg_observations" {
-                let sql = "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_observations WHERE meta::id(id) = $id LIMIT 1";
+                let sql = format!(
+                    "SELECT meta::id(id) as id, name, data, source_thought_ids, source_thought_id, {OBSERVATION_TEXT_SQL} AS content, type::string(created_at) as created_at FROM kg_observations WHERE meta::id(id) = $id LIMIT 1"
+                );
                 let rows: Vec<serde_json::Value> = server
                     .db
                     .query(sql)
@@ -436,7 +778,7 @@ pub async fn unified_search_inner(
                     v
                 }));
 
-                if !items.is_empty() {
+                if items.len() > observations_start {
                     found_semantic_obs = true;
                 }
             }
@@ -444,9 +786,12 @@ pub async fn unified_search_inner(
 
         if !found_semantic_obs && let Some(ref q_emb_val) = q_emb {
             // Semantic search using embeddings
-            let q_dim = q_emb_val.len() as i64;
-            let mut sql = "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at, vector::similarity::cosine(embedding, $q) AS similarity
-                 FROM kg_observations WHERE embedding_dim = $dim AND embedding IS NOT NONE".to_string();
+            let mut sql = format!(
+                "SELECT meta::id(id) as id, name, data, source_thought_ids, source_thought_id, {OBSERVATION_TEXT_SQL} AS content, type::string(created_at) as created_at, {} AS similarity
+                 FROM kg_observations WHERE {} AND {{filter}}",
+                vectors.similarity_sql(),
+                vectors.metadata_filter()
+            );
 
             if params.chain_id.is_some() {
                 sql.push_str(" AND ");
@@ -458,15 +803,15 @@ pub async fn unified_search_inner(
                 top_k_mem * 3
             ));
 
-            let mut query = server
-                .db
-                .query(sql)
-                .bind(("dim", q_dim))
-                .bind(("q", q_emb_val.clone()));
-            if let Some(ref chain_ids) = chain_filter_ids {
-                query = query.bind(("chain_ids", chain_ids.clone()));
-            }
-            let rows: Vec<serde_json::Value> = query.await?.take(0)?;
+            let rows = kg_similarity_rows(
+                server,
+                &vectors,
+                &sql,
+                top_k_mem * 3,
+                q_emb_val,
+                chain_filter_ids.as_ref(),
+            )
+            .await?;
 
             let mut scored_observations: Vec<serde_json::Value> = Vec::new();
             for row in rows {
@@ -482,7 +827,10 @@ pub async fn unified_search_inner(
                         "kind": "observation",
                         "name": row.get("name"),
                         "data": row.get("data"),
+                        "content": row.get("content"),
                         "created_at": row.get("created_at"),
+                        "source_thought_ids": row.get("source_thought_ids"),
+                        "source_thought_id": row.get("source_thought_id"),
                         "similarity": sim
                     });
                     scored_observations.push(observation_json);
@@ -500,8 +848,10 @@ pub async fn unified_search_inner(
 
         if !found_semantic_obs {
             if let Some(ref nl) = name_like {
-                // Fallback to name pattern matching when no embedding available
-                let mut sql = "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_observations WHERE name ~ $name".to_string();
+                // Fallback to pattern matching on the same text the semantic path returns
+                let mut sql = format!(
+                    "SELECT meta::id(id) as id, name, data, source_thought_ids, source_thought_id, {OBSERVATION_TEXT_SQL} AS content, type::string(created_at) as created_at FROM kg_observations WHERE string::contains(string::lowercase({OBSERVATION_TEXT_SQL}), string::lowercase($name))"
+                );
                 if params.chain_id.is_some() {
                     sql.push_str(" AND ");
                     sql.push_str(&chain_filter_sql(""));
@@ -522,9 +872,9 @@ pub async fn unified_search_inner(
                 }));
             } else {
                 // Fallback to recent items
-                let mut sql =
-                    "SELECT meta::id(id) as id, name, data, type::string(created_at) as created_at FROM kg_observations"
-                        .to_string();
+                let mut sql = format!(
+                    "SELECT meta::id(id) as id, name, data, source_thought_ids, source_thought_id, {OBSERVATION_TEXT_SQL} AS content, type::string(created_at) as created_at FROM kg_observations"
+                );
                 if params.chain_id.is_some() {
                     sql.push_str(" WHERE ");
                     sql.push_str(&chain_filter_sql(""));
@@ -545,6 +895,13 @@ pub async fn unified_search_inner(
                 }));
             }
         }
+
+        // One batched lookup resolves the source thoughts of every observation hit
+        attach_provenance(&server.db.client(), &mut items[observations_start..])
+            .await
+            .map_err(|e| SurrealMindError::Database {
+                message: format!("observation provenance failed: {}", e),
+            })?;
     }
 
     // Note: entity and observation fallback queries use `type::string(created_at) as created_at`
@@ -552,6 +909,9 @@ pub async fn unified_search_inner(
     // uses `ts_created` alias to avoid collision with the raw datetime field.
 
     let mut augmented_items = items;
+    if let Some(expr) = &tag_expr {
+        augmented_items.retain(|item| expr.matches(&item_tags(item)));
+    }
     if forensic {
         augment_with_forensics(server, &mut augmented_items).await?;
     }
@@ -559,6 +919,9 @@ pub async fn unified_search_inner(
     let mut out = serde_json::Map::new();
     let count = augmented_items.len();
     out.insert("memories".into(), json!({"items": augmented_items}));
+    if q_emb.is_some() {
+        out.insert("query_embedding".into(), vectors.telemetry());
+    }
     tracing::debug!("🔍 Unified search found {} memory items", count);
 
     // 2) Thoughts search (optional)
@@ -579,11 +942,15 @@ pub async fn unified_search_inner(
         }
         let has_query = !content.is_empty();
         let q_emb = if has_query {
-            Some(server.embedder.embed(&content).await.map_err(|e| {
-                SurrealMindError::Embedding {
-                    message: e.to_string(),
-                }
-            })?)
+            Some(
+                vectors
+                    .embedder
+                    .embed_with_purpose(&content, EmbedPurpose::SearchQuery)
+                    .await
+                    .map_err(|e| SurrealMindError::Embedding {
+                        message: e.to_string(),
+                    })?,
+            )
         } else {
             None
         };
@@ -595,9 +962,16 @@ pub async fn unified_search_inner(
         );
 
         // Build WHERE clauses - only require embeddings if doing semantic search
-        let mut where_clauses = vec![];
+        let mut where_clauses = vec!["deleted_at = NONE".to_string()];
+        if !include_private {
+            where_clauses.push(privacy_filter(false).to_string());
+        }
         if q_emb.is_some() {
-            where_clauses.push("embedding_dim = $dim AND embedding IS NOT NONE".to_string());
+            where_clauses.push(format!(
+                "{} AND {}",
+                vectors.metadata_filter(),
+                vectors.presence_filter()
+            ));
         }
         let mut binds = serde_json::Map::new();
 
@@ -625,6 +999,19 @@ pub async fn unified_search_inner(
             where_clauses.push("origin = $origin".to_string());
             binds.insert("origin".to_string(), json!(origin));
         }
+        if let Some(lang) = params
+            .lang
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            where_clauses.push("lang = $lang".to_string());
+            binds.insert("lang".to_string(), json!(lang.to_lowercase()));
+        }
+        if let Some(min_sig) = params.min_significance {
+            where_clauses.push("significance >= $min_sig".to_string());
+            binds.insert("min_sig".to_string(), json!(min_sig));
+        }
         if let Some(cgte) = confidence_gte {
             where_clauses.push("confidence IS NOT NULL AND confidence >= $cgte".to_string());
             binds.insert("cgte".to_string(), json!(cgte));
@@ -633,6 +1020,39 @@ pub async fn unified_search_inner(
             where_clauses.push("confidence IS NOT NULL AND confidence <= $clte".to_string());
             binds.insert("clte".to_string(), json!(clte));
         }
+        if params.only_pinned.unwrap_or(false) {
+            where_clauses.push("pinned = true".to_string());
+        }
+        // Candidate pre-filter only; the full expression is checked after the fetch
+        if let Some(any) = tag_expr.as_ref().and_then(TagExpr::required_any) {
+            where_clauses.push("(tags ?? []) CONTAINSANY $tag_any".to_string());
+            binds.insert("tag_any".to_string(), json!(any));
+        }
+        match params.has_reference {
+            Some(true) => where_clauses.push("array::len(references ?? []) > 0".to_string()),
+            Some(false) => where_clauses.push("array::len(references ?? []) = 0".to_string()),
+            None => {}
+        }
+        if let Some(needle) = params
+            .reference_contains
+            .as_deref()
+            .filter(|s| !s.is_empty())
+        {
+            where_clauses.push(
+                "array::len((references ?? [])[WHERE string::contains(value, $ref_contains)]) > 0"
+                    .to_string(),
+            );
+            binds.insert("ref_contains".to_string(), json!(needle));
+        }
+        if let Some(needle) = params
+            .code_ref_contains
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            where_clauses.push(CODE_REF_FILTER_SQL.to_string());
+            binds.insert("code_ref".to_string(), json!(needle.to_lowercase()));
+        }
         if date_from_bound.is_some() {
             where_clauses.push("created_at >= $from_date".to_string());
         }
@@ -640,10 +1060,10 @@ pub async fn unified_search_inner(
             where_clauses.push("created_at <= $to_date".to_string());
         }
 
-        // Add similarity filter if query present
-        if q_emb.is_some() {
-            where_clauses.push("vector::similarity::cosine(embedding, $q) > $sim".to_string());
-        }
+        // Similarity filter if query present; KNN queries apply it to the pool instead
+        let sim_clause = q_emb
+            .is_some()
+            .then(|| format!("{} > $sim", vectors.similarity_sql()));
 
         // Build ORDER BY
         let has_continuity = params.session_id.is_some() || params.chain_id.is_some();
@@ -657,6 +1077,7 @@ pub async fn unified_search_inner(
             match order.as_str() {
                 "created_at_asc" => "ts_created ASC",
                 "created_at_desc" => "ts_created DESC",
+                "final_score" if q_emb.is_none() => "ts_created DESC",
                 _ => "similarity DESC", // fallback
             }
         } else if q_emb.is_some() {
@@ -666,111 +1087,379 @@ pub async fn unified_search_inner(
         };
 
         // Build SELECT — include created_at for ORDER BY (SurrealDB 3.x requires it)
+        let base_fields = "meta::id(id) as id, content, significance, session_id, chain_id, origin, lang, superseded_by, references, code_refs, pinned, tags, type::string(created_at) as ts_created";
         let select_fields = if q_emb.is_some() {
-            "meta::id(id) as id, content, significance, type::string(created_at) as ts_created, vector::similarity::cosine(embedding, $q) AS similarity"
+            format!(
+                "{}, {} AS similarity",
+                base_fields,
+                vectors.similarity_sql()
+            )
         } else {
-            "meta::id(id) as id, content, significance, type::string(created_at) as ts_created"
+            base_fields.to_string()
         };
-        let where_sql = if where_clauses.is_empty() {
-            "true".to_string()
+        // Re-ranking and tag_expr filtering work on a wider pool, then keep the
+        // top_k_thoughts best
+        let rerank = params.order.as_deref() == Some("final_score")
+            || (ranking.is_active() && order_by.starts_with("similarity"));
+        let fetch_k = if rerank || tag_expr.is_some() {
+            top_k_th * RERANK_POOL_FACTOR
         } else {
-            where_clauses.join(" AND ")
+            top_k_th
         };
-        let sql = format!(
-            "SELECT {} FROM thoughts WHERE {} ORDER BY {} LIMIT $k",
-            select_fields, where_sql, order_by
-        );
-
-        // Debug the thoughts query
-        tracing::info!("🔍 Thoughts SQL: {}", sql);
-        tracing::info!("🔍 Thoughts binds: {:?}", binds);
-        tracing::info!(
-            "🔍 Thoughts has_query: {}, chain_id: {:?}",
-            has_query,
-            params.chain_id
-        );
+        // KNN preselection only when no structured filter narrows the candidate set;
+        // otherwise the K nearest rows could all be filtered out.
+        let mut use_knn = q_emb.is_some()
+            && vectors.knn_allowed()
+            && server.vector_index_ready()
+            && binds.is_empty()
+            && params.has_reference.is_none()
+            && !params.only_pinned.unwrap_or(false)
+            && tag_expr.is_none()
+            && date_from_bound.is_none()
+            && date_to_bound.is_none();
+
+        let knn_k = fetch_k * 3;
+
+        let rows: Vec<serde_json::Value> = loop {
+            let mut clauses = where_clauses.clone();
+            if use_knn {
+                clauses.push(server.embedding_filter(knn_k, true));
+            } else if let Some(sim) = &sim_clause {
+                clauses.push(sim.clone());
+            }
+            let where_sql = if clauses.is_empty() {
+                "true".to_string()
+            } else {
+                clauses.join(" AND ")
+            };
+            let sql = format!(
+                "SELECT {} FROM thoughts WHERE {} ORDER BY {} LIMIT $k",
+                select_fields, where_sql, order_by
+            );
+
+            // Debug the thoughts query
+            tracing::info!("🔍 Thoughts SQL: {}", sql);
+            tracing::info!("🔍 Thoughts binds: {:?}", binds);
+            tracing::info!(
+                "🔍 Thoughts has_query: {}, chain_id: {:?}",
+                has_query,
+                params.chain_id
+            );
+
+            let limit = if use_knn { knn_k } else { fetch_k };
+            let mut query = vectors.bind(server.db.query(sql).bind(("k", limit as i64)));
+            if let Some(ref q_emb_val) = q_emb {
+                query = query.bind(("q", q_emb_val.clone()));
+                query = query.bind(("sim", sim_thresh));
+            }
+            let q_dim = if let Some(ref q_emb_val) = q_emb {
+                q_emb_val.len() as i64
+            } else {
+                vectors.dim as i64
+            };
+            query = query.bind(("dim", q_dim));
+            for (k, v) in binds.clone() {
+                query = query.bind((k, v));
+            }
+            if let Some(from_date) = date_from_bound {
+                query = query.bind(("from_date", from_date));
+            }
+            if let Some(to_date) = date_to_bound {
+                query = query.bind(("to_date", to_date));
+            }
 
-        let mut query = server.db.query(sql).bind(("k", top_k_th as i64));
-        if let Some(ref q_emb_val) = q_emb {
-            query = query.bind(("q", q_emb_val.clone()));
-            query = query.bind(("sim", sim_thresh));
-        }
-        let q_dim = if let Some(ref q_emb_val) = q_emb {
-            q_emb_val.len() as i64
+            match query
+                .await
+                .and_then(|mut r| r.take::<Vec<serde_json::Value>>(0))
+            {
+                Ok(pool) if use_knn => {
+                    if knn_pool_short(&pool, sim_thresh, top_k_th) {
+                        tracing::debug!(
+                            rows = pool.len(),
+                            top_k = top_k_th,
+                            "search.thoughts.knn_short_falling_back_to_scan"
+                        );
+                        use_knn = false;
+                        continue;
+                    }
+                    break pool
+                        .into_iter()
+                        .filter(|r| above_threshold(r, sim_thresh))
+                        .take(fetch_k)
+                        .collect();
+                }
+                Ok(rows) => break rows,
+                Err(e) if use_knn => {
+                    server.disable_vector_index(&e);
+                    use_knn = false;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+        let (rows, substituted) = if params.include_superseded.unwrap_or(false) {
+            (rows, 0)
         } else {
-            server.embedder.dimensions() as i64
+            substitute_superseded(
+                server,
+                rows,
+                &select_fields,
+                q_emb.as_deref(),
+                include_private,
+            )
+            .await?
         };
-        query = query.bind(("dim", q_dim));
-        for (k, v) in binds {
-            query = query.bind((k, v));
-        }
-        if let Some(from_date) = date_from_bound {
-            query = query.bind(("from_date", from_date));
+        if substituted > 0 {
+            tracing::info!(substituted, "search.thoughts.superseded_substituted");
         }
-        if let Some(to_date) = date_to_bound {
-            query = query.bind(("to_date", to_date));
+        let explain = params.explain_scores.unwrap_or(false);
+        let snippet_window = params.snippet_window.unwrap_or(DEFAULT_SNIPPET_WINDOW);
+        let snippet_only = params.snippet_only.unwrap_or(false);
+        let now = chrono::Utc::now();
+        let mut scored: Vec<(ThoughtOut, ScoreParts)> = rows
+            .iter()
+            .filter(|r| tag_expr.as_ref().is_none_or(|e| e.matches(&item_tags(r))))
+            .map(|r| {
+                let out = ThoughtOut::from_row(r);
+                let parts = ranking.score(
+                    out.similarity.unwrap_or(0.0),
+                    out.significance.unwrap_or(0.0),
+                    age_days(r, now),
+                );
+                (out, parts)
+            })
+            .collect();
+        if rerank {
+            // Stable, so ties keep the query order
+            scored.sort_by(|a, b| b.1.final_score.total_cmp(&a.1.final_score));
         }
-
-        let mut resp = query.await?;
-
-        let rows: Vec<serde_json::Value> = resp.take(0)?;
-        let results: Vec<ThoughtOut> = rows
+        scored.truncate(top_k_th);
+        let results: Vec<ThoughtOut> = scored
             .into_iter()
-            .map(|r| ThoughtOut {
-                id: r
-                    .get("id")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                content: r
-                    .get("content")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                similarity: r
-                    .get("similarity")
-                    .and_then(|v| v.as_f64())
-                    .map(|f| f as f32),
-                significance: Some(
-                    r.get("significance")
-                        .and_then(|v| v.as_f64())
-                        .unwrap_or(0.0) as f32,
-                ),
+            .map(|(mut out, parts)| {
+                if explain {
+                    out.score = Some(parts);
+                }
+                if has_query {
+                    out.highlight(&content, snippet_window, snippet_only);
+                }
+                out
             })
             .collect();
+        let returned: Vec<String> = results.iter().map(|t| t.id.clone()).collect();
+        server.track_access(&returned);
         out.insert(
             "thoughts".into(),
             json!({
                 "total": results.len(),
                 "top_k": top_k_th,
+                "superseded_substituted": substituted,
                 "results": results
             }),
         );
+        if q_emb.is_some() {
+            out.entry("query_embedding")
+                .or_insert_with(|| vectors.telemetry());
+        }
     }
 
-    Ok(CallToolResult::structured(serde_json::Value::Object(out)))
+    Ok(serde_json::Value::Object(out))
+}
+
+/// A thought's `tags`, or a memory item's `data.tags`.
+fn item_tags(item: &serde_json::Value) -> Vec<String> {
+    item.get("tags")
+        .filter(|t| !t.is_null())
+        .or_else(|| item.get("data").and_then(|d| d.get("tags")))
+        .and_then(|t| serde_json::from_value(t.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// The vector field a search reads and the embedder whose model is stored in it.
+struct QueryVectors {
+    field: EmbeddingField,
+    embedder: Arc<dyn Embedder>,
+    provider: String,
+    model: String,
+    dim: usize,
+    /// Also require `<field>_model = $emb_model`; off for the default search, which has
+    /// always matched on dimension alone
+    match_model: bool,
 }
 
-fn parse_search_date_bound(
-    value: &str,
-    end_of_day: bool,
-    label: &str,
-) -> Result<surrealdb::types::Datetime> {
-    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
-        SurrealMindError::Serialization {
-            message: format!("Invalid {label} format (expected YYYY-MM-DD)"),
+impl QueryVectors {
+    /// The active embedder over `embedding` when `field` is unset. Otherwise the most common
+    /// model stored in `field` that the primary or `[embedding_migration]` embedder produces.
+    async fn resolve(server: &SurrealMindServer, field: Option<EmbeddingField>) -> Result<Self> {
+        let Some(field) = field else {
+            return Ok(Self {
+                field: EmbeddingField::Embedding,
+                embedder: server.embedder.clone(),
+                provider: server.config.system.embedding_provider.clone(),
+                model: server.config.system.embedding_model.clone(),
+                dim: server.embedder.dimensions(),
+                match_model: false,
+            });
+        };
+        let mut configured = vec![(
+            server.embedder.clone(),
+            server.config.system.embedding_provider.clone(),
+            server.config.system.embedding_model.clone(),
+        )];
+        if let Some(target) = &server.embedding_target {
+            configured.push((
+                target.embedder.clone(),
+                target.provider.clone(),
+                target.model.clone(),
+            ));
+        }
+        let stored = stored_embeddings(&server.db.client(), field)
+            .await
+            .map_err(|e| SurrealMindError::Database {
+                message: format!("reading stored {} models failed: {}", field.column(), e),
+            })?;
+        if stored.is_empty() {
+            return Err(SurrealMindError::Validation {
+                message: format!("no stored rows carry {}", field.column()),
+            });
+        }
+        let producible: Vec<(&str, usize)> = configured
+            .iter()
+            .map(|(embedder, _, model)| (model.as_str(), embedder.dimensions()))
+            .collect();
+        let Some((picked, i)) = pick_stored_embedding(&stored, &producible) else {
+            let list = |models: Vec<String>| models.join(", ");
+            return Err(SurrealMindError::Embedding {
+                message: format!(
+                    "{} holds vectors from {}, but the configured embedders only produce {}",
+                    field.column(),
+                    list(
+                        stored
+                            .iter()
+                            .map(|s| format!("{}/{}", s.model, s.dim))
+                            .collect()
+                    ),
+                    list(
+                        producible
+                            .iter()
+                            .map(|(m, d)| format!("{}/{}", m, d))
+                            .collect()
+                    )
+                ),
+            });
+        };
+        let (embedder, provider, _) = configured.swap_remove(i);
+        Ok(Self {
+            field,
+            embedder,
+            provider,
+            model: picked.model.clone(),
+            dim: picked.dim,
+            match_model: true,
+        })
+    }
+
+    fn similarity_sql(&self) -> String {
+        format!("vector::similarity::cosine({}, $q)", self.field.column())
+    }
+
+    /// Rows whose stored vector is comparable with the query (binds `$dim`, `$emb_model`)
+    fn metadata_filter(&self) -> String {
+        if self.match_model {
+            format!(
+                "{} = $dim AND {} = $emb_model",
+                self.field.dim_column(),
+                self.field.model_column()
+            )
+        } else {
+            format!("{} = $dim", self.field.dim_column())
+        }
+    }
+
+    fn presence_filter(&self) -> String {
+        format!("{} IS NOT NONE", self.field.column())
+    }
+
+    /// The HNSW indexes cover only the primary `embedding`
+    fn knn_allowed(&self) -> bool {
+        self.field == EmbeddingField::Embedding
+    }
+
+    fn bind<'a>(
+        &self,
+        query: crate::server::connection::DbQuery<'a>,
+    ) -> crate::server::connection::DbQuery<'a> {
+        if self.match_model {
+            query.bind(("emb_model", self.model.clone()))
+        } else {
+            query
+        }
+    }
+
+    fn telemetry(&self) -> serde_json::Value {
+        json!({
+            "field": self.field.column(),
+            "provider": self.provider,
+            "model": self.model,
+            "dim": self.dim,
+        })
+    }
+}
+
+/// Run a KG similarity query whose `{filter}` placeholder selects embedded rows.
+/// Uses the HNSW KNN operator when the vector index is ready and retries as a full
+/// scan if the KNN query fails. A `chain_ids` restriction always scans, since the K
+/// nearest rows could all be filtered out.
+async fn kg_similarity_rows(
+    server: &SurrealMindServer,
+    vectors: &QueryVectors,
+    sql_template: &str,
+    k: usize,
+    q_emb: &[f32],
+    chain_ids: Option<&Vec<String>>,
+) -> Result<Vec<serde_json::Value>> {
+    let mut use_knn = vectors.knn_allowed() && server.vector_index_ready() && chain_ids.is_none();
+    loop {
+        let filter = if use_knn {
+            server.embedding_filter(k, true)
+        } else {
+            vectors.presence_filter()
+        };
+        let sql = sql_template.replace("{filter}", &filter);
+        let mut query = vectors.bind(
+            server
+                .db
+                .query(sql)
+                .bind(("dim", q_emb.len() as i64))
+                .bind(("q", q_emb.to_vec())),
+        );
+        if let Some(chain_ids) = chain_ids {
+            query = query.bind(("chain_ids", chain_ids.clone()));
+        }
+        match query
+            .await
+            .and_then(|mut r| r.take::<Vec<serde_json::Value>>(0))
+        {
+            Ok(rows) => return Ok(rows),
+            Err(e) if use_knn => {
+                server.disable_vector_index(&e);
+                use_knn = false;
+            }
+            Err(e) => return Err(e.into()),
         }
-    })?;
-    let naive = if end_of_day {
-        date.and_hms_opt(23, 59, 59)
-    } else {
-        date.and_hms_opt(0, 0, 0)
     }
-    .ok_or_else(|| SurrealMindError::Serialization {
-        message: format!("Invalid {label} value"),
-    })?;
+}
 
-    Ok(surrealdb::types::Datetime::from(naive.and_utc()))
+fn above_threshold(row: &serde_json::Value, sim_thresh: f32) -> bool {
+    row.get("similarity")
+        .and_then(|v| v.as_f64())
+        .is_some_and(|sim| sim > f64::from(sim_thresh))
+}
+
+/// Whether a KNN thought pool ran out before `top_k` rows, with every row still above the
+/// threshold, so rows past the K nearest might qualify. A row below the threshold means
+/// every farther row is too, and the pool is complete.
+fn knn_pool_short(pool: &[serde_json::Value], sim_thresh: f32, top_k: usize) -> bool {
+    pool.len() < top_k && pool.iter().all(|r| above_threshold(r, sim_thresh))
 }
 
 /// Helper function to sort entities by similarity (used by both production and tests)
@@ -784,6 +1473,548 @@ fn sort_by_similarity(entities: &mut [serde_json::Value]) {
     });
 }
 
+/// `code_ref_contains`: a code reference whose value, or `value:line`, contains the lowercased
+/// `$code_ref`.
+const CODE_REF_FILTER_SQL: &str = "array::len((code_refs ?? [])[WHERE string::contains(\
+    string::lowercase(IF line != NONE THEN string::concat(value, ':', <string> line) ELSE value END), \
+    $code_ref)]) > 0";
+
+// --- Thought ranking ---
+
+/// Thoughts fetched per requested result when re-ranking.
+const RERANK_POOL_FACTOR: usize = 3;
+
+/// Optional re-ranking of thought hits. The default leaves every score equal to the
+/// similarity, so ordering is unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ThoughtRanking {
+    recency_half_life_days: Option<f32>,
+    significance_weight: f32,
+}
+
+/// How a thought's final score was built: `sim * recency_factor + significance_bonus`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+struct ScoreParts {
+    sim: f32,
+    recency_factor: f32,
+    significance_bonus: f32,
+    #[serde(rename = "final")]
+    final_score: f32,
+}
+
+impl ThoughtRanking {
+    fn new(half_life_days: Option<f32>, significance_weight: Option<f32>) -> Result<Self> {
+        if half_life_days.is_some_and(|d| !d.is_finite() || d < 0.0) {
+            return Err(SurrealMindError::Validation {
+                message: "recency_half_life_days must be a non-negative number".into(),
+            });
+        }
+        let significance_weight = significance_weight.unwrap_or(0.0);
+        if !significance_weight.is_finite() {
+            return Err(SurrealMindError::Validation {
+                message: "significance_weight must be a finite number".into(),
+            });
+        }
+        Ok(Self {
+            recency_half_life_days: half_life_days.filter(|d| *d > 0.0),
+            significance_weight,
+        })
+    }
+
+    fn is_active(&self) -> bool {
+        self.recency_half_life_days.is_some() || self.significance_weight != 0.0
+    }
+
+    /// Score a hit `age_days` old; unknown ages are not decayed.
+    fn score(&self, sim: f32, significance: f32, age_days: Option<f64>) -> ScoreParts {
+        let recency_factor = match (self.recency_half_life_days, age_days) {
+            (Some(half_life), Some(age)) => 0.5_f64.powf(age.max(0.0) / half_life as f64) as f32,
+            _ => 1.0,
+        };
+        let significance_bonus = self.significance_weight * significance;
+        ScoreParts {
+            sim,
+            recency_factor,
+            significance_bonus,
+            final_score: sim * recency_factor + significance_bonus,
+        }
+    }
+}
+
+/// Age in days of a thought row's `ts_created` at `now`.
+fn age_days(row: &serde_json::Value, now: chrono::DateTime<chrono::Utc>) -> Option<f64> {
+    let raw = row.get("ts_created")?.as_str()?;
+    let raw = raw
+        .strip_prefix("d'")
+        .and_then(|r| r.strip_suffix('\''))
+        .unwrap_or(raw);
+    let created = chrono::DateTime::parse_from_rfc3339(raw).ok()?;
+    Some((now - created.with_timezone(&chrono::Utc)).num_seconds() as f64 / 86_400.0)
+}
+
+// --- Graph expansion helpers ---
+
+/// Deepest `graph_depth` accepted.
+const MAX_GRAPH_DEPTH: usize = 3;
+
+/// Score multiplier applied for each hop beyond the first.
+const GRAPH_HOP_DECAY: f32 = 0.5;
+
+struct GraphExpansion {
+    depth: usize,
+    boost: f32,
+    min_edge_strength: f32,
+    /// Skip edges whose `valid_from`..`valid_to` window excludes this instant
+    as_of: Option<surrealdb::types::Datetime>,
+    /// Max neighbors added across all hops
+    node_budget: usize,
+}
+
+/// Entity reached from a direct hit.
+#[derive(Debug, Clone)]
+struct Reached {
+    id: String,
+    name: String,
+    /// Similarity of the direct hit the path started from
+    hit_score: f32,
+    hop: usize,
+    /// `{rel_type, from}` per edge, where `from` names the entity the edge was followed from
+    via: Vec<serde_json::Value>,
+}
+
+impl Reached {
+    fn score(&self, boost: f32) -> f32 {
+        boost * self.hit_score * GRAPH_HOP_DECAY.powi(self.hop.saturating_sub(1) as i32)
+    }
+}
+
+/// Edge between two entities; strength is the edge's `strength` (older edges may only have
+/// `data.strength`), 1.0 when absent.
+#[derive(Debug, Deserialize)]
+struct EdgeRow {
+    source_id: Option<String>,
+    target_id: Option<String>,
+    #[serde(default)]
+    rel_type: Option<String>,
+    #[serde(default)]
+    strength: Option<serde_json::Value>,
+}
+
+/// Follow `edges` (in either direction) one hop out from `frontier`. Nodes already in
+/// `visited` are skipped, so cycles and direct hits are never re-added; at most `budget`
+/// new nodes are returned. Frontier nodes should be ordered best-first so each neighbor
+/// keeps its strongest path.
+fn expand_hop(
+    frontier: &[Reached],
+    edges: &[EdgeRow],
+    visited: &mut HashSet<String>,
+    min_edge_strength: f32,
+    budget: usize,
+) -> Vec<Reached> {
+    let mut next = Vec::new();
+    for node in frontier {
+        for edge in edges {
+            if next.len() >= budget {
+                return next;
+            }
+            let (Some(src), Some(dst)) = (edge.source_id.as_deref(), edge.target_id.as_deref())
+            else {
+                continue;
+            };
+            let neighbor = if src == node.id {
+                dst
+            } else if dst == node.id {
+                src
+            } else {
+                continue;
+            };
+            let strength = edge
+                .strength
+                .as_ref()
+                .and_then(|v| v.as_f64())
+                .unwrap_or(1.0) as f32;
+            if strength < min_edge_strength || !visited.insert(neighbor.to_string()) {
+                continue;
+            }
+            let mut via = node.via.clone();
+            via.push(json!({
+                "rel_type": edge.rel_type.clone().unwrap_or_default(),
+                "from": node.name,
+            }));
+            next.push(Reached {
+                id: neighbor.to_string(),
+                name: String::new(),
+                hit_score: node.hit_score,
+                hop: node.hop + 1,
+                via,
+            });
+        }
+    }
+    next
+}
+
+/// Strength and validity window selected with each edge hit.
+const EDGE_ATTRIBUTE_FIELDS: &str = "(strength ?? data.strength ?? 1.0) AS strength, \
+    (IF valid_from != NONE THEN type::string(valid_from) END) AS valid_from, \
+    (IF valid_to != NONE THEN type::string(valid_to) END) AS valid_to";
+
+/// Edges valid at `$as_of`; open ends of the window always match.
+const EDGE_VALID_AT_SQL: &str =
+    "(valid_from = NONE OR valid_from <= $as_of) AND (valid_to = NONE OR valid_to >= $as_of)";
+
+/// Endpoint tables selected with each edge hit; NONE marks a legacy string endpoint.
+const EDGE_ENDPOINT_TABLES: &str = "(IF type::is_record(source) THEN meta::tb(source) END) AS source_table, \
+     (IF type::is_record(target) THEN meta::tb(target) END) AS target_table";
+
+/// Keys of the kg_entities records that relationship hits point at, deduplicated.
+fn edge_entity_ids(items: &[serde_json::Value]) -> Vec<String> {
+    let mut ids: Vec<String> = items
+        .iter()
+        .filter(|item| item.get("kind").and_then(|k| k.as_str()) == Some("relationship"))
+        .flat_map(|item| {
+            ["source", "target"].into_iter().filter_map(move |end| {
+                (item.get(format!("{}_table", end))?.as_str()? == "kg_entities")
+                    .then(|| item.get(format!("{}_id", end))?.as_str())
+                    .flatten()
+                    .map(str::to_string)
+            })
+        })
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Give each relationship hit `source_name`/`target_name` from `names` (kg_entities key →
+/// name) and `resolved`: whether both endpoints are live entity records. A legacy string
+/// endpoint passes through as its own name. Drops the `*_table` helper fields.
+fn attach_endpoint_names(items: &mut [serde_json::Value], names: Option<&HashMap<String, String>>) {
+    for item in items
+        .iter_mut()
+        .filter(|item| item.get("kind").and_then(|k| k.as_str()) == Some("relationship"))
+    {
+        let Some(obj) = item.as_object_mut() else {
+            continue;
+        };
+        let mut resolved = true;
+        for end in ["source", "target"] {
+            let table = obj.remove(&format!("{}_table", end));
+            let Some(names) = names else {
+                continue;
+            };
+            let id = obj.get(&format!("{}_id", end)).and_then(|v| v.as_str());
+            let name = match table.as_ref().and_then(|t| t.as_str()) {
+                Some("kg_entities") => id.and_then(|id| names.get(id)).map(|n| json!(n)),
+                Some(_) => None,
+                None => id.map(|id| json!(id)),
+            };
+            resolved &= table.as_ref().is_some_and(|t| t == "kg_entities") && name.is_some();
+            obj.insert(
+                format!("{}_name", end),
+                name.unwrap_or(serde_json::Value::Null),
+            );
+        }
+        if names.is_some() {
+            obj.insert("resolved".to_string(), json!(resolved));
+        }
+    }
+}
+
+/// Resolve relationship endpoint names with one kg_entities query (skipped without
+/// `include_names`).
+async fn resolve_edge_endpoints(
+    server: &SurrealMindServer,
+    items: &mut [serde_json::Value],
+    include_names: bool,
+) -> Result<()> {
+    if !include_names {
+        attach_endpoint_names(items, None);
+        return Ok(());
+    }
+    let ids = edge_entity_ids(items);
+    let mut names = HashMap::new();
+    if !ids.is_empty() {
+        let rows: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT meta::id(id) AS id, name FROM kg_entities WHERE id IN $recs")
+            .bind(("recs", entity_records(&ids)))
+            .await?
+            .check()?
+            .take(0)?;
+        names = rows
+            .into_iter()
+            .filter_map(|r| {
+                Some((
+                    r.get("id")?.as_str()?.to_string(),
+                    r.get("name")?.as_str()?.to_string(),
+                ))
+            })
+            .collect();
+    }
+    attach_endpoint_names(items, Some(&names));
+    Ok(())
+}
+
+fn entity_records(ids: &[String]) -> Vec<surrealdb::types::Value> {
+    ids.iter()
+        .map(|id| {
+            surrealdb::types::Value::RecordId(surrealdb::types::RecordId::new(
+                "kg_entities",
+                id.as_str(),
+            ))
+        })
+        .collect()
+}
+
+/// Traverse `kg_edges` out from the entity hits in `items` and return neighbor entities
+/// scored `boost * hit_similarity`, halved per extra hop, each with its `via` path.
+async fn expand_entity_neighbors(
+    server: &SurrealMindServer,
+    items: &[serde_json::Value],
+    expansion: &GraphExpansion,
+) -> Result<Vec<serde_json::Value>> {
+    let mut frontier: Vec<Reached> = items
+        .iter()
+        .filter(|item| item.get("kind").and_then(|k| k.as_str()) == Some("entity"))
+        .filter_map(|item| {
+            let id = item.get("id")?.as_str()?;
+            Some(Reached {
+                id: id.trim_start_matches("kg_entities:").to_string(),
+                name: item
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                hit_score: item
+                    .get("similarity")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0) as f32,
+                hop: 0,
+                via: Vec::new(),
+            })
+        })
+        .collect();
+    frontier.sort_by(|a, b| b.hit_score.total_cmp(&a.hit_score));
+    let mut visited: HashSet<String> = frontier.iter().map(|r| r.id.clone()).collect();
+    let mut out = Vec::new();
+
+    for _ in 0..expansion.depth {
+        let budget = expansion.node_budget.saturating_sub(out.len());
+        if frontier.is_empty() || budget == 0 {
+            break;
+        }
+        let ids: Vec<String> = frontier.iter().map(|r| r.id.clone()).collect();
+        let validity = if expansion.as_of.is_some() {
+            format!(" AND {EDGE_VALID_AT_SQL}")
+        } else {
+            String::new()
+        };
+        let mut query = server
+            .db
+            .query(format!(
+                "SELECT (IF type::is_record(source, 'kg_entities') THEN meta::id(source) END) AS source_id, \
+                        (IF type::is_record(target, 'kg_entities') THEN meta::id(target) END) AS target_id, \
+                        rel_type, (strength ?? data.strength) AS strength \
+                 FROM kg_edges WHERE (source IN $recs OR target IN $recs){validity} LIMIT $lim",
+            ))
+            .bind(("recs", entity_records(&ids)))
+            .bind(("lim", (budget * 10).max(50) as i64));
+        if let Some(at) = expansion.as_of {
+            query = query.bind(("as_of", at));
+        }
+        let edges: Vec<serde_json::Value> = query.await?.check()?.take(0)?;
+        let edges: Vec<EdgeRow> = edges
+            .into_iter()
+            .filter_map(|e| serde_json::from_value(e).ok())
+            .collect();
+
+        let mut next = expand_hop(
+            &frontier,
+            &edges,
+            &mut visited,
+            expansion.min_edge_strength,
+            budget,
+        );
+        if next.is_empty() {
+            break;
+        }
+
+        let next_ids: Vec<String> = next.iter().map(|r| r.id.clone()).collect();
+        let rows: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT meta::id(id) AS id, name, data, type::string(created_at) AS created_at FROM kg_entities WHERE id IN $recs")
+            .bind(("recs", entity_records(&next_ids)))
+            .await?
+            .check()?
+            .take(0)?;
+        let rows: HashMap<String, serde_json::Value> = rows
+            .into_iter()
+            .filter_map(|r| Some((r.get("id")?.as_str()?.to_string(), r)))
+            .collect();
+
+        // Dangling edges point at deleted entities; drop those nodes
+        next.retain_mut(|node| match rows.get(&node.id) {
+            Some(row) => {
+                node.name = row
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                out.push(json!({
+                    "id": node.id,
+                    "kind": "entity",
+                    "name": row.get("name"),
+                    "data": row.get("data"),
+                    "created_at": row.get("created_at"),
+                    "similarity": node.score(expansion.boost),
+                    "hop": node.hop,
+                    "via": node.via,
+                }));
+                true
+            }
+            None => false,
+        });
+        next.sort_by(|a, b| b.hit_score.total_cmp(&a.hit_score));
+        frontier = next;
+    }
+
+    Ok(out)
+}
+
+// --- Revision helpers ---
+
+/// Longest `superseded_by` chain followed when looking for a thought's latest revision.
+const MAX_REVISION_DEPTH: usize = 5;
+
+/// Follow `successors` from `start` to the newest revision, stopping after
+/// `MAX_REVISION_DEPTH` hops or on a cycle. Returns `start` when it has no known successor.
+fn latest_revision<'a>(start: &'a str, successors: &'a HashMap<String, Option<String>>) -> &'a str {
+    let mut current = start;
+    let mut seen = HashSet::from([start]);
+    for _ in 0..MAX_REVISION_DEPTH {
+        match successors.get(current).and_then(|s| s.as_deref()) {
+            Some(next) if seen.insert(next) => current = next,
+            _ => break,
+        }
+    }
+    current
+}
+
+fn thought_records(ids: &[String]) -> Vec<surrealdb::types::Value> {
+    ids.iter()
+        .map(|id| {
+            surrealdb::types::Value::RecordId(surrealdb::types::RecordId::new(
+                "thoughts",
+                id.as_str(),
+            ))
+        })
+        .collect()
+}
+
+/// Replace superseded thoughts in `rows` with their latest revision, in place. Successors
+/// already present (or reached twice) are not repeated, and superseded rows whose chain
+/// hits the depth cap or a cycle are dropped. Rows whose latest revision is soft-deleted or
+/// missing (or private, unless `include_private`) are kept as they are. Returns the rows
+/// and the number of superseded rows resolved to a successor.
+async fn substitute_superseded(
+    server: &SurrealMindServer,
+    rows: Vec<serde_json::Value>,
+    select_fields: &str,
+    q_emb: Option<&[f32]>,
+    include_private: bool,
+) -> Result<(Vec<serde_json::Value>, usize)> {
+    let superseded_by = |row: &serde_json::Value| {
+        row.get("superseded_by")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    let row_id = |row: &serde_json::Value| {
+        row.get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    if rows.iter().all(|r| superseded_by(r).is_none()) {
+        return Ok((rows, 0));
+    }
+
+    // Walk the revision chains breadth-first, one query per level
+    let mut successors: HashMap<String, Option<String>> =
+        rows.iter().map(|r| (row_id(r), superseded_by(r))).collect();
+    let mut frontier: Vec<String> = successors.values().flatten().cloned().collect();
+    for _ in 0..MAX_REVISION_DEPTH {
+        frontier.retain(|id| !successors.contains_key(id));
+        frontier.dedup();
+        if frontier.is_empty() {
+            break;
+        }
+        let links: Vec<serde_json::Value> = server
+            .db
+            .query("SELECT meta::id(id) AS id, superseded_by FROM thoughts WHERE id IN $recs")
+            .bind(("recs", thought_records(&frontier)))
+            .await?
+            .check()?
+            .take(0)?;
+        frontier = links.iter().filter_map(superseded_by).collect();
+        successors.extend(links.iter().map(|r| (row_id(r), superseded_by(r))));
+    }
+
+    let latest: Vec<String> = rows
+        .iter()
+        .filter(|r| superseded_by(r).is_some())
+        .map(|r| latest_revision(&row_id(r), &successors).to_string())
+        .collect();
+    let mut query = server
+        .db
+        .query(format!(
+            "SELECT {select_fields} FROM thoughts WHERE id IN $recs AND deleted_at = NONE AND {}",
+            privacy_filter(include_private)
+        ))
+        .bind(("recs", thought_records(&latest)));
+    if let Some(q) = q_emb {
+        query = query.bind(("q", q.to_vec()));
+    }
+    let fetched: Vec<serde_json::Value> = query.await?.check()?.take(0)?;
+    let fetched: HashMap<String, serde_json::Value> =
+        fetched.into_iter().map(|r| (row_id(&r), r)).collect();
+
+    let mut seen: HashSet<String> = rows
+        .iter()
+        .filter(|r| superseded_by(r).is_none())
+        .map(row_id)
+        .collect();
+    let mut substituted = 0;
+    let mut out = Vec::with_capacity(rows.len());
+    let mut latest = latest.into_iter();
+    for row in rows {
+        if superseded_by(&row).is_none() {
+            out.push(row);
+            continue;
+        }
+        let Some(successor) = latest.next() else {
+            continue;
+        };
+        // A soft-deleted (or missing) latest revision leaves the superseded hit in place
+        // Several superseded hits can share one latest revision, so it is not removed here
+        let Some(mut replacement) = fetched.get(&successor).cloned() else {
+            if seen.insert(row_id(&row)) {
+                out.push(row);
+            }
+            continue;
+        };
+        // Still superseded means the chain hit the depth cap or a cycle
+        if superseded_by(&replacement).is_some() {
+            continue;
+        }
+        substituted += 1;
+        if seen.insert(successor) {
+            replacement["substituted_for"] = json!(row_id(&row));
+            out.push(replacement);
+        }
+    }
+    Ok((out, substituted))
+}
+
 // --- Forensic helpers ---
 async fn augment_with_forensics(
     server: &SurrealMindServer,
@@ -875,29 +2106,402 @@ mod tests {
     use serde_json::json;
 
     #[test]
-    fn search_date_bound_start_is_typed_midnight_utc() {
-        let bound =
-            parse_search_date_bound("2026-06-08", false, "date_from").expect("valid date bound");
+    fn thought_out_carries_continuity_fields() {
+        let out = ThoughtOut::from_row(&json!({
+            "id": "t1",
+            "content": "hello",
+            "significance": 0.7,
+            "session_id": "s1",
+            "chain_id": "c1",
+            "origin": "human"
+        }));
+        assert_eq!(
+            serde_json::to_value(&out).unwrap(),
+            json!({"id": "t1", "content": "hello", "significance": 0.7_f32, "session_id": "s1", "chain_id": "c1", "origin": "human"})
+        );
+
+        // Thoughts without continuity fields serialize as before
+        let bare = ThoughtOut::from_row(&json!({"id": "t2", "content": "x", "session_id": null}));
+        assert_eq!(
+            serde_json::to_value(&bare).unwrap(),
+            json!({"id": "t2", "content": "x", "significance": 0.0})
+        );
+
+        let referenced = ThoughtOut::from_row(&json!({
+            "id": "t3",
+            "content": "x",
+            "references": [{"kind": "file", "value": "/src/lib.rs", "note": null}],
+            "code_refs": [{"kind": "path", "value": "src/lib.rs", "line": 3}, {"kind": "error_code", "value": "E0308", "line": null}]
+        }));
+        let out = serde_json::to_value(&referenced).unwrap();
+        assert_eq!(
+            out["references"],
+            json!([{"kind": "file", "value": "/src/lib.rs"}])
+        );
+        assert_eq!(
+            out["code_refs"],
+            json!([{"kind": "path", "value": "src/lib.rs", "line": 3}, {"kind": "error_code", "value": "E0308"}])
+        );
+    }
+
+    #[test]
+    fn thought_out_highlight_adds_span_and_can_drop_content() {
+        let row = json!({"id": "t1", "content": "Prélude. The cache eviction policy matters."});
+        let mut out = ThoughtOut::from_row(&row);
+        out.highlight("eviction", 24, false);
+        let v = serde_json::to_value(&out).unwrap();
+        assert_eq!(v["snippet"], json!("cache eviction policy"));
+        assert_eq!(v["span_start"], json!(14));
+        assert_eq!(v["span_end"], json!(35));
+        assert!(v.get("content").is_some());
+
+        let mut only = ThoughtOut::from_row(&row);
+        only.highlight("eviction", 24, true);
+        let v = serde_json::to_value(&only).unwrap();
+        assert!(v.get("content").is_none());
+        assert_eq!(v["snippet"], json!("cache eviction policy"));
+    }
+
+    fn edge(src: &str, dst: &str, rel: &str, strength: Option<f64>) -> EdgeRow {
+        EdgeRow {
+            source_id: Some(src.into()),
+            target_id: Some(dst.into()),
+            rel_type: Some(rel.into()),
+            strength: strength.map(|s| json!(s)),
+        }
+    }
+
+    fn hit(id: &str, score: f32) -> Reached {
+        Reached {
+            id: id.into(),
+            name: id.to_uppercase(),
+            hit_score: score,
+            hop: 0,
+            via: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_expand_hop_follows_both_directions_and_skips_visited() {
+        // a -> b, c -> a, b -> a (cycle back to the hit), a -> d (weak)
+        let edges = vec![
+            edge("a", "b", "uses", None),
+            edge("c", "a", "owns", Some(0.9)),
+            edge("b", "a", "used_by", None),
+            edge("a", "d", "mentions", Some(0.1)),
+        ];
+        let frontier = vec![hit("a", 0.8)];
+        let mut visited: HashSet<String> = ["a".to_string()].into();
+        let next = expand_hop(&frontier, &edges, &mut visited, 0.5, 10);
+
+        let ids: Vec<&str> = next.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+        assert_eq!(next[0].via, vec![json!({"rel_type": "uses", "from": "A"})]);
+        assert_eq!(next[1].hop, 1);
+        assert!((next[0].score(0.5) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_expand_hop_respects_budget_and_decays_per_hop() {
+        let edges = vec![edge("b", "e", "next", None), edge("b", "f", "next", None)];
+        let mut b = hit("b", 0.8);
+        b.hop = 1;
+        b.via = vec![json!({"rel_type": "uses", "from": "A"})];
+        let mut visited: HashSet<String> = ["a".to_string(), "b".to_string()].into();
+        let next = expand_hop(&[b], &edges, &mut visited, 0.0, 1);
+
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].hop, 2);
+        assert_eq!(next[0].via.len(), 2);
+        assert_eq!(next[0].via[1], json!({"rel_type": "next", "from": "B"}));
+        assert!((next[0].score(0.5) - 0.2).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_expansion_filters_edges_on_strength_and_validity() {
+        let server = crate::test_support::mem_server().await;
+        // ada -worked_at(2019..2022)-> acme, ada -knows(0.2)-> bob, ada -mentions-> old
+        // (strength only in data, as older writers stored it)
+        server
+            .db
+            .query(
+                "FOR $n IN ['ada', 'acme', 'bob', 'old'] {
+                     CREATE type::record('kg_entities', $n) SET name = $n, data = {}, created_at = time::now();
+                 };
+                 CREATE kg_edges SET created_at = time::now(), source = kg_entities:ada, target = kg_entities:acme,
+                     rel_type = 'worked_at', data = {}, strength = 1.0,
+                     valid_from = d'2019-01-01T00:00:00Z', valid_to = d'2022-12-31T23:59:59Z';
+                 CREATE kg_edges SET created_at = time::now(), source = kg_entities:ada, target = kg_entities:bob,
+                     rel_type = 'knows', data = {}, strength = 0.2;
+                 CREATE kg_edges SET created_at = time::now(), source = kg_entities:old, target = kg_entities:ada,
+                     rel_type = 'mentions', data = {strength: 0.9};",
+            )
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        let search = |extra: serde_json::Value| {
+            let mut args = json!({"query": {"id": "kg_entities:ada"}, "target": "entity", "expand_graph": true});
+            args.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            let server = &server;
+            async move {
+                let found = server
+                    .handle_unified_search(crate::test_support::call("search", args))
+                    .await
+                    .unwrap()
+                    .structured_content
+                    .unwrap();
+                let mut ids: Vec<String> = found["memories"]["items"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|i| {
+                        i["id"]
+                            .as_str()
+                            .unwrap()
+                            .trim_start_matches("kg_entities:")
+                            .to_string()
+                    })
+                    .collect();
+                ids.sort();
+                ids
+            }
+        };
+
+        assert_eq!(search(json!({})).await, vec!["acme", "ada", "bob", "old"]);
+        assert_eq!(
+            search(json!({"min_edge_strength": 0.5})).await,
+            vec!["acme", "ada", "old"]
+        );
+        assert_eq!(
+            search(json!({"min_edge_strength": 0.5, "as_of": "2020-06-01"})).await,
+            vec!["acme", "ada", "old"]
+        );
+        assert_eq!(
+            search(json!({"min_edge_strength": 0.5, "as_of": "2024-01-01"})).await,
+            vec!["ada", "old"]
+        );
+
+        let relationships = server
+            .handle_unified_search(crate::test_support::call(
+                "search",
+                json!({"target": "relationship", "as_of": "2024-01-01"}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        let mut rels: Vec<(String, f64)> = relationships["memories"]["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| {
+                (
+                    i["rel_type"].as_str().unwrap().to_string(),
+                    i["strength"].as_f64().unwrap(),
+                )
+            })
+            .collect();
+        rels.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            rels,
+            vec![("knows".to_string(), 0.2), ("mentions".to_string(), 0.9)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_name_filters_match_case_insensitively_without_embeddings() {
+        let server = crate::test_support::mem_server().await;
+        // No embeddings, so both targets take the pattern fallback
+        server
+            .db
+            .query(
+                "CREATE kg_entities:ada SET name = 'Ada Lovelace', data = {}, created_at = time::now();
+                 CREATE kg_entities:bob SET name = 'Bob', data = {}, created_at = time::now();
+                 CREATE kg_observations:o1 SET name = 'note', content = 'ADA wrote the first program', data = {}, created_at = time::now();
+                 CREATE kg_observations:o2 SET name = 'legacy', data = {content: 'Notes on Ada'}, created_at = time::now();
+                 CREATE kg_observations:o3 SET name = 'other', content = 'Bob fixed the loom', data = {}, created_at = time::now();",
+            )
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let found = server
+            .handle_unified_search(crate::test_support::call(
+                "search",
+                json!({"query": {"name": "ada"}, "target": "mixed"}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        let mut hits: Vec<(String, String)> = found["memories"]["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| {
+                (
+                    i["kind"].as_str().unwrap().to_string(),
+                    i["id"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        hits.sort();
+        assert_eq!(
+            hits,
+            vec![
+                ("entity".to_string(), "ada".to_string()),
+                ("observation".to_string(), "o1".to_string()),
+                ("observation".to_string(), "o2".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_knn_search_falls_back_when_filters_empty_the_pool() {
+        let mut config = crate::test_support::test_config();
+        config.system.use_vector_index = true;
+        let server = crate::test_support::mem_server_with(config).await;
+        assert!(server.vector_index_ready());
+        let embedder = crate::test_support::HashEmbedder {
+            dim: crate::test_support::TEST_EMBED_DIM,
+        };
+        let create = "CREATE type::record('thoughts', $id) SET content = $content, \
+             embedding = $emb, embedding_dim = $dim, embedding_model = 'hash', \
+             deleted_at = IF $deleted THEN time::now() ELSE NONE END, created_at = time::now(), injection_scale = 0, \
+             significance = 0.5, access_count = 0, injected_memories = [];";
+        // Soft-deleted exact matches outnumber the KNN pool (top_k 1 -> 3 neighbours) and ef
+        for i in 0..120 {
+            server
+                .db
+                .query(create)
+                .bind(("id", format!("gone{i}")))
+                .bind(("content", "cache eviction"))
+                .bind(("emb", embedder.vector("cache eviction")))
+                .bind(("dim", crate::test_support::TEST_EMBED_DIM as i64))
+                .bind(("deleted", true))
+                .await
+                .unwrap()
+                .check()
+                .unwrap();
+        }
+        server
+            .db
+            .query(create)
+            .bind(("id", "kept"))
+            .bind(("content", "cache eviction policy"))
+            .bind(("emb", embedder.vector("cache eviction policy")))
+            .bind(("dim", crate::test_support::TEST_EMBED_DIM as i64))
+            .bind(("deleted", false))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let found = server
+            .handle_unified_search(crate::test_support::call(
+                "search",
+                json!({"query": {"text": "cache eviction"}, "include_thoughts": true, "top_k_thoughts": 1}),
+            ))
+            .await
+            .unwrap()
+            .structured_content
+            .unwrap();
+        let ids: Vec<&str> = found["thoughts"]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["kept"]);
+        assert!(server.vector_index_ready(), "fallback is not a KNN failure");
+    }
 
-        assert_eq!(bound.to_string(), "2026-06-08T00:00:00Z");
+    #[test]
+    fn test_knn_pool_short_only_when_every_row_clears_the_threshold() {
+        let pool = |sims: &[f64]| -> Vec<serde_json::Value> {
+            sims.iter().map(|s| json!({"similarity": s})).collect()
+        };
+        // A strict threshold drops the tail of a full pool: no rescan
+        assert!(!knn_pool_short(&pool(&[0.95, 0.6, 0.4]), 0.9, 5));
+        assert!(!knn_pool_short(&pool(&[0.5]), 0.9, 5));
+        // Filters left fewer than top_k rows, all above the threshold: rescan
+        assert!(knn_pool_short(&pool(&[0.95, 0.92]), 0.9, 5));
+        assert!(knn_pool_short(&[], 0.9, 5));
+        assert!(!knn_pool_short(&pool(&[0.95, 0.92]), 0.9, 2));
     }
 
     #[test]
-    fn search_date_bound_end_is_typed_end_of_day_utc() {
-        let bound =
-            parse_search_date_bound("2026-06-11", true, "date_to").expect("valid date bound");
+    fn test_edge_endpoints_resolve_records_and_pass_legacy_strings_through() {
+        let mut items = vec![
+            json!({"kind": "relationship", "id": "e1", "source_id": "a", "target_id": "b",
+                   "source_table": "kg_entities", "target_table": "kg_entities"}),
+            json!({"kind": "relationship", "id": "e2", "source_id": "a", "target_id": "Old Name",
+                   "source_table": "kg_entities", "target_table": null}),
+            json!({"kind": "relationship", "id": "e3", "source_id": "gone", "target_id": "o1",
+                   "source_table": "kg_entities", "target_table": "kg_observations"}),
+            json!({"kind": "entity", "id": "a", "name": "Alpha"}),
+        ];
+        assert_eq!(edge_entity_ids(&items), ["a", "b", "gone"]);
+
+        let names: HashMap<String, String> =
+            [("a".into(), "Alpha".into()), ("b".into(), "Beta".into())].into();
+        attach_endpoint_names(&mut items, Some(&names));
+        assert_eq!(items[0]["source_name"], json!("Alpha"));
+        assert_eq!(items[0]["target_name"], json!("Beta"));
+        assert_eq!(items[0]["resolved"], json!(true));
+        assert!(items[0].get("source_table").is_none());
+        assert_eq!(items[1]["target_name"], json!("Old Name"));
+        assert_eq!(items[1]["resolved"], json!(false));
+        // Deleted entities and non-entity endpoints stay unnamed
+        assert!(items[2]["source_name"].is_null() && items[2]["target_name"].is_null());
+        assert_eq!(items[2]["resolved"], json!(false));
+        assert_eq!(
+            items[3],
+            json!({"kind": "entity", "id": "a", "name": "Alpha"})
+        );
+    }
 
-        assert_eq!(bound.to_string(), "2026-06-11T23:59:59Z");
+    #[test]
+    fn test_edge_endpoints_without_names_only_drop_helper_fields() {
+        let mut items = vec![
+            json!({"kind": "relationship", "source_id": "a", "target_id": "b",
+                                    "source_table": "kg_entities", "target_table": null}),
+        ];
+        attach_endpoint_names(&mut items, None);
+        assert_eq!(
+            items[0],
+            json!({"kind": "relationship", "source_id": "a", "target_id": "b"})
+        );
     }
 
     #[test]
-    fn search_date_bound_rejects_bad_format() {
-        let err = parse_search_date_bound("2026/06/08", false, "date_from")
-            .expect_err("bad format should fail");
+    fn test_latest_revision_follows_chain_with_cap_and_cycle_guard() {
+        let chain = |links: &[(&str, Option<&str>)]| -> HashMap<String, Option<String>> {
+            links
+                .iter()
+                .map(|(id, next)| (id.to_string(), next.map(String::from)))
+                .collect()
+        };
+        let revisions = chain(&[("v1", Some("v2")), ("v2", Some("v3")), ("v3", None)]);
+        assert_eq!(latest_revision("v1", &revisions), "v3");
+        assert_eq!(latest_revision("v3", &revisions), "v3");
+        // Unknown successor: stop at the last known link
+        assert_eq!(latest_revision("v1", &chain(&[("v1", Some("v2"))])), "v2");
 
-        assert!(
-            err.to_string()
-                .contains("Invalid date_from format (expected YYYY-MM-DD)")
+        let cycle = chain(&[("a", Some("b")), ("b", Some("a"))]);
+        assert_eq!(latest_revision("a", &cycle), "b");
+
+        let long: Vec<(String, Option<String>)> = (0..10)
+            .map(|i| (format!("t{i}"), Some(format!("t{}", i + 1))))
+            .collect();
+        let long: HashMap<String, Option<String>> = long.into_iter().collect();
+        assert_eq!(
+            latest_revision("t0", &long),
+            format!("t{MAX_REVISION_DEPTH}")
         );
     }
 
@@ -956,4 +2560,73 @@ mod tests {
         // Verify that new-low-sim (0.60) and oldest-med-sim (0.70) were dropped
         assert_eq!(scored_entities.len(), 3);
     }
+
+    #[test]
+    fn test_default_ranking_is_a_no_op() {
+        let ranking = ThoughtRanking::new(None, None).unwrap();
+        assert!(!ranking.is_active());
+        assert_eq!(ranking, ThoughtRanking::new(Some(0.0), Some(0.0)).unwrap());
+        for (sim, sig, age) in [
+            (0.9, 0.1, Some(270.0)),
+            (0.4, 1.0, Some(0.5)),
+            (0.7, 0.5, None),
+        ] {
+            let parts = ranking.score(sim, sig, age);
+            assert_eq!(parts.final_score, sim);
+            assert_eq!(parts.recency_factor, 1.0);
+            assert_eq!(parts.significance_bonus, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_recency_decay_halves_per_half_life() {
+        let ranking = ThoughtRanking::new(Some(30.0), None).unwrap();
+        assert!(ranking.is_active());
+        let factor = |age: f64| ranking.score(1.0, 0.0, Some(age)).recency_factor;
+        assert!((factor(0.0) - 1.0).abs() < 1e-6);
+        assert!((factor(30.0) - 0.5).abs() < 1e-6);
+        assert!((factor(90.0) - 0.125).abs() < 1e-6);
+        // Clock skew never boosts a hit
+        assert!((factor(-5.0) - 1.0).abs() < 1e-6);
+        assert_eq!(ranking.score(0.8, 0.0, None).final_score, 0.8);
+
+        // Yesterday's relevant thought now beats a slightly closer 9-month-old one
+        let old = ranking.score(0.82, 0.0, Some(270.0));
+        let fresh = ranking.score(0.78, 0.0, Some(1.0));
+        assert!(fresh.final_score > old.final_score);
+    }
+
+    #[test]
+    fn test_significance_bonus_and_validation() {
+        let ranking = ThoughtRanking::new(Some(10.0), Some(0.2)).unwrap();
+        let parts = ranking.score(0.6, 0.5, Some(10.0));
+        assert!((parts.recency_factor - 0.5).abs() < 1e-6);
+        assert!((parts.significance_bonus - 0.1).abs() < 1e-6);
+        assert!((parts.final_score - 0.4).abs() < 1e-6);
+        assert_eq!(
+            serde_json::to_value(parts)
+                .unwrap()
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["final", "recency_factor", "significance_bonus", "sim"]
+        );
+
+        assert!(ThoughtRanking::new(Some(-1.0), None).is_err());
+        assert!(ThoughtRanking::new(Some(f32::NAN), None).is_err());
+        assert!(ThoughtRanking::new(None, Some(f32::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn test_age_days_from_ts_created() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-11T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let row = |ts: &str| json!({"ts_created": ts});
+        assert_eq!(age_days(&row("2026-03-01T12:00:00Z"), now), Some(10.0));
+        assert_eq!(age_days(&row("d'2026-03-10T00:00:00Z'"), now), Some(1.5));
+        assert_eq!(age_days(&row("not a date"), now), None);
+        assert_eq!(age_days(&json!({}), now), None);
+    }
 }
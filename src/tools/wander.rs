@@ -1,4 +1,6 @@
 use crate::error::{Result, SurrealMindError};
+use crate::maintenance::privacy::privacy_filter;
+use crate::maintenance::soft_delete::LIVE_FILTER;
 use crate::server::SurrealMindServer;
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use serde_json::json;
@@ -25,6 +27,10 @@ pub struct WanderParams {
     /// Filter marks for a specific federation member (marks mode)
     #[serde(rename = "for")]
     pub for_member: Option<String>,
+
+    /// Also wander onto thoughts marked `is_private`
+    #[serde(default)]
+    pub include_private: bool,
 }
 
 impl SurrealMindServer {
@@ -63,6 +69,7 @@ impl SurrealMindServer {
         let params: WanderParams = serde_json::from_value(serde_json::Value::Object(args))
             .map_err(|e| SurrealMindError::InvalidParams {
                 message: format!("Invalid parameters: {}", e),
+                field: None,
             })?;
 
         // 1. Determine current context
@@ -73,8 +80,8 @@ impl SurrealMindServer {
                 let res: Vec<serde_json::Value> = self
                     .db
                     .query(format!(
-                        "SELECT {} FROM {} WHERE id = type::record('{}', $id) LIMIT 1",
-                        WANDER_NODE_FIELDS, table, table
+                        "SELECT {} FROM {} WHERE id = type::record('{}', $id) AND {} LIMIT 1",
+                        WANDER_NODE_FIELDS, table, table, LIVE_FILTER
                     ))
                     .bind(("id", short_id))
                     .await?
@@ -90,13 +97,15 @@ impl SurrealMindServer {
                 // Use a safer query that doesn't call meta::id() on NONE
                 let res: Vec<serde_json::Value> = self
                     .db
-                    .query(
-                        "SELECT meta::id(id) as id, meta::tb(id) as table, name, content, data, tags, embedding, mark_type, marked_for, mark_note, marked_by, type::string(marked_at) as marked_at, type::string(created_at) as created_at FROM thoughts, kg_entities, kg_observations
-                         WHERE id = type::record('thoughts', $id)
+                    .query(format!(
+                        "SELECT {} FROM thoughts, kg_entities, kg_observations
+                         WHERE (id = type::record('thoughts', $id)
                             OR id = type::record('kg_entities', $id)
-                            OR id = type::record('kg_observations', $id)
+                            OR id = type::record('kg_observations', $id))
+                            AND {}
                          LIMIT 1",
-                    )
+                        WANDER_NODE_FIELDS, LIVE_FILTER
+                    ))
                     .bind(("id", id.clone()))
                     .await?
                     .take(0)?;
@@ -107,15 +116,16 @@ impl SurrealMindServer {
             // Auto-seed context if mode requires it (Semantic/Meta)
             // This enables "Start Wandering" without needing a specific ID
             if params.mode == "semantic" || params.mode == "meta" {
+                let privacy = privacy_filter(params.include_private);
                 let q = if params.recency_bias {
                     format!(
-                        "SELECT {} FROM thoughts ORDER BY created_at DESC LIMIT 1",
-                        WANDER_NODE_FIELDS
+                        "SELECT {} FROM thoughts WHERE {} AND {} ORDER BY created_at DESC LIMIT 1",
+                        WANDER_NODE_FIELDS, LIVE_FILTER, privacy
                     )
                 } else {
                     format!(
-                        "SELECT {} FROM thoughts, kg_entities, kg_observations ORDER BY rand() LIMIT 1",
-                        WANDER_NODE_FIELDS
+                        "SELECT {} FROM thoughts, kg_entities, kg_observations WHERE {} AND {} ORDER BY rand() LIMIT 1",
+                        WANDER_NODE_FIELDS, LIVE_FILTER, privacy
                     )
                 };
 
@@ -142,9 +152,12 @@ impl SurrealMindServer {
         }
 
         // 2. Traversal Logic
+        let include_private = params.include_private;
         let (next_node, affordances, queue_depth, guidance, message) = match params.mode.as_str() {
             "random" => {
-                let (node, aff) = self.wander_random(&params.visited_ids).await?;
+                let (node, aff) = self
+                    .wander_random(&params.visited_ids, include_private)
+                    .await?;
                 let node_is_none = node.is_none();
                 (
                     node,
@@ -160,7 +173,7 @@ impl SurrealMindServer {
             }
             "semantic" => {
                 let (node, aff) = self
-                    .wander_semantic(&current_node, &params.visited_ids)
+                    .wander_semantic(&current_node, &params.visited_ids, include_private)
                     .await?;
                 let node_is_none = node.is_none();
                 (
@@ -176,7 +189,9 @@ impl SurrealMindServer {
                 )
             }
             "meta" => {
-                let (node, aff) = self.wander_meta(&current_node, &params.visited_ids).await?;
+                let (node, aff) = self
+                    .wander_meta(&current_node, &params.visited_ids, include_private)
+                    .await?;
                 let node_is_none = node.is_none();
                 (
                     node,
@@ -192,7 +207,11 @@ impl SurrealMindServer {
             }
             "marks" => {
                 let (node, depth) = self
-                    .wander_marks(params.for_member.clone(), &params.visited_ids)
+                    .wander_marks(
+                        params.for_member.clone(),
+                        &params.visited_ids,
+                        include_private,
+                    )
                     .await?;
                 let aff = vec![
                     "correct".to_string(),
@@ -238,6 +257,7 @@ impl SurrealMindServer {
     async fn wander_random(
         &self,
         visited: &[String],
+        include_private: bool,
     ) -> Result<(Option<serde_json::Value>, Vec<String>)> {
         // We select from thoughts, kg_entities, or kg_observations
         // For simplicity, let's union or just pick one table randomly?
@@ -253,8 +273,10 @@ impl SurrealMindServer {
         // "SELECT * FROM thoughts, kg_entities, kg_observations ORDER BY rand() LIMIT 1" (SurrealDB might support comma separated targets? Yes.)
 
         let q = format!(
-            "SELECT {} FROM thoughts, kg_entities, kg_observations WHERE meta::id(id) NOT IN $visited ORDER BY rand() LIMIT 1",
-            WANDER_NODE_FIELDS
+            "SELECT {} FROM thoughts, kg_entities, kg_observations WHERE meta::id(id) NOT IN $visited AND {} AND {} ORDER BY rand() LIMIT 1",
+            WANDER_NODE_FIELDS,
+            LIVE_FILTER,
+            privacy_filter(include_private)
         );
         let res: Vec<serde_json::Value> = self
             .db
@@ -278,17 +300,20 @@ impl SurrealMindServer {
         &self,
         current: &Option<serde_json::Value>,
         visited: &[String],
+        include_private: bool,
     ) -> Result<(Option<serde_json::Value>, Vec<String>)> {
         let current = match current {
             Some(c) => c,
-            None => return self.wander_random(visited).await, // Fallback if no start node
+            None => return self.wander_random(visited, include_private).await, // Fallback if no start node
         };
 
         // Check if context has embedding
         let embedding = current.get("embedding").cloned();
         if embedding.is_none() || embedding.as_ref().unwrap().is_null() {
             // No embedding? Can't do semantic. Fallback to meta or random.
-            return self.wander_meta(&Some(current.clone()), visited).await;
+            return self
+                .wander_meta(&Some(current.clone()), visited, include_private)
+                .await;
         }
 
         // Search for nearest neighbors
@@ -302,8 +327,12 @@ impl SurrealMindServer {
                  AND <string>meta::id(id) != $current_id
                  AND embedding != NONE
                  AND type::is_array(embedding)
+                 AND {}
+                 AND {}
                  ORDER BY sim DESC LIMIT 1",
-            WANDER_NODE_FIELDS
+            WANDER_NODE_FIELDS,
+            LIVE_FILTER,
+            privacy_filter(include_private)
         );
 
         let current_id = current
@@ -332,10 +361,11 @@ impl SurrealMindServer {
         &self,
         current: &Option<serde_json::Value>,
         visited: &[String],
+        include_private: bool,
     ) -> Result<(Option<serde_json::Value>, Vec<String>)> {
         let current = match current {
             Some(c) => c,
-            None => return self.wander_random(visited).await,
+            None => return self.wander_random(visited, include_private).await,
         };
 
         // Strategy: Find nodes that share tags or source_thought_id
@@ -349,7 +379,7 @@ impl SurrealMindServer {
 
         // If meta navigation isn't possible, fallback to random
         if tags_val.is_none() {
-            return self.wander_random(visited).await;
+            return self.wander_random(visited, include_private).await;
         }
 
         // Query: Overlap in tags
@@ -359,8 +389,12 @@ impl SurrealMindServer {
                  WHERE meta::id(id) NOT IN $visited
                  AND <string>meta::id(id) != $current_id
                  AND (tags CONTAINSANY $tags OR data.tags CONTAINSANY $tags)
+                 AND {}
+                 AND {}
                  ORDER BY rand() LIMIT 1",
-            WANDER_NODE_FIELDS
+            WANDER_NODE_FIELDS,
+            LIVE_FILTER,
+            privacy_filter(include_private)
         );
 
         let res: Vec<serde_json::Value> = self
@@ -375,7 +409,7 @@ impl SurrealMindServer {
         let node = res.first().cloned();
         if node.is_none() {
             // If no tag overlap, try random fallback
-            return self.wander_random(visited).await;
+            return self.wander_random(visited, include_private).await;
         }
 
         let affordances = vec!["semantic".to_string(), "random".to_string()];
@@ -387,12 +421,15 @@ impl SurrealMindServer {
         &self,
         target_for: Option<String>,
         visited: &[String],
+        include_private: bool,
     ) -> Result<(Option<serde_json::Value>, i64)> {
-        let filter_clause = if target_for.is_some() {
-            "AND marked_for = $target_for"
-        } else {
-            ""
-        };
+        let mut filter_clause = format!("AND {}", LIVE_FILTER);
+        if target_for.is_some() {
+            filter_clause.push_str(" AND marked_for = $target_for");
+        }
+        if !include_private {
+            filter_clause.push_str(&format!(" AND {}", privacy_filter(false)));
+        }
 
         // Fetch next mark (oldest first)
         let query = format!(
@@ -432,6 +469,8 @@ impl SurrealMindServer {
 #[cfg(test)]
 mod tests {
     use super::SurrealMindServer;
+    use crate::test_support::{HashEmbedder, TEST_EMBED_DIM, call, mem_server};
+    use serde_json::{Value, json};
 
     #[test]
     fn normalize_accepts_aliases_and_things() {
@@ -469,4 +508,59 @@ mod tests {
             assert_eq!(got, expected, "input={}", input);
         }
     }
+
+    #[tokio::test]
+    async fn test_wander_skips_soft_deleted_thoughts() {
+        let server = mem_server().await;
+        let emb = HashEmbedder {
+            dim: TEST_EMBED_DIM,
+        }
+        .vector("cache eviction");
+        server
+            .db
+            .query(
+                "CREATE thoughts:live SET content = 'live', tags = ['cache'], marked_for = 'cc', \
+                     marked_at = time::now(), embedding = $emb, created_at = time::now(), \
+                     injection_scale = 0, significance = 0.5, access_count = 0, injected_memories = [];
+                 CREATE thoughts:gone SET content = 'gone', tags = ['cache'], marked_for = 'cc', \
+                     marked_at = time::now() - 1h, embedding = $emb, deleted_at = time::now(), \
+                     created_at = time::now(), injection_scale = 0, significance = 0.5, \
+                     access_count = 0, injected_memories = [];",
+            )
+            .bind(("emb", emb))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        let wander = |args: Value| {
+            let server = server.clone();
+            async move {
+                server
+                    .handle_wander(call("wander", args))
+                    .await
+                    .unwrap()
+                    .structured_content
+                    .unwrap()
+            }
+        };
+
+        for _ in 0..5 {
+            let out = wander(json!({"mode": "random"})).await;
+            assert_eq!(out["current_node"]["id"], "live");
+        }
+        let out = wander(json!({"mode": "semantic", "recency_bias": true})).await;
+        assert_eq!(out["current_node"], Value::Null);
+        let out = wander(json!({"mode": "semantic", "current_thought_id": "thoughts:live"})).await;
+        assert_eq!(out["current_node"], Value::Null);
+        let out = wander(json!({
+            "mode": "meta",
+            "current_thought_id": "thoughts:live",
+            "visited_ids": ["live"]
+        }))
+        .await;
+        assert_eq!(out["current_node"], Value::Null);
+        let out = wander(json!({"mode": "marks"})).await;
+        assert_eq!(out["current_node"]["id"], "live");
+        assert_eq!(out["queue_depth"], 1);
+    }
 }
@@ -0,0 +1,153 @@
+//! Code references found in thought content
+//!
+//! [`extract_code_refs`] scans text for URLs, Rust error codes (`E0308`), and file paths with
+//! an optional `:line` (`src/main.rs:42`, `C:\repo\src\lib.rs:7:5`). A path needs a directory
+//! separator or a line number, so prose like `e.g.` or a bare `docs.rs` is not taken for a
+//! file. Paths inside a URL are not reported separately. Results keep the order of first
+//! appearance, without repeats, and stop at [`MAX_CODE_REFS`].
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Most references kept per thought
+pub const MAX_CODE_REFS: usize = 32;
+
+static URL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"https?://[^\s<>"'`\]\)]+"#).expect("valid url regex"));
+
+static ERROR_CODE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bE\d{4}\b").expect("valid error code regex"));
+
+static PATH_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?:^|[\s(\[{<"'`=,;])((?:[A-Za-z]:[\\/]|\.{1,2}[\\/]|~[\\/]|[\\/])?(?:[\w.\-]+[\\/])*[\w\-.]*[\w\-]\.[A-Za-z][A-Za-z0-9]{0,9})(?::(\d+)(?::\d+)?)?"#,
+    )
+    .expect("valid path regex")
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeRef {
+    /// `path`, `error_code`, or `url`
+    pub kind: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// Every reference in `text`, at most [`MAX_CODE_REFS`].
+pub fn extract_code_refs(text: &str) -> Vec<CodeRef> {
+    let mut found: Vec<(usize, CodeRef)> = Vec::new();
+    let mut url_spans = Vec::new();
+
+    for m in URL_RE.find_iter(text) {
+        let value = m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        url_spans.push(m.range());
+        found.push((m.start(), code_ref("url", value, None)));
+    }
+    let in_url = |at: usize| url_spans.iter().any(|r| r.contains(&at));
+
+    for m in ERROR_CODE_RE.find_iter(text) {
+        if !in_url(m.start()) {
+            found.push((m.start(), code_ref("error_code", m.as_str(), None)));
+        }
+    }
+
+    for caps in PATH_RE.captures_iter(text) {
+        let path = caps.get(1).expect("path group");
+        let line = caps.get(2).and_then(|l| l.as_str().parse().ok());
+        let value = path.as_str().trim_end_matches('.');
+        if in_url(path.start()) || !(value.contains(['/', '\\']) || line.is_some()) {
+            continue;
+        }
+        found.push((path.start(), code_ref("path", value, line)));
+    }
+
+    found.sort_by_key(|(at, _)| *at);
+    let mut refs: Vec<CodeRef> = Vec::new();
+    for (_, r) in found {
+        if !refs.contains(&r) {
+            refs.push(r);
+            if refs.len() == MAX_CODE_REFS {
+                break;
+            }
+        }
+    }
+    refs
+}
+
+fn code_ref(kind: &str, value: &str, line: Option<u32>) -> CodeRef {
+    CodeRef {
+        kind: kind.to_string(),
+        value: value.to_string(),
+        line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refs(text: &str) -> Vec<(String, String, Option<u32>)> {
+        extract_code_refs(text)
+            .into_iter()
+            .map(|r| (r.kind, r.value, r.line))
+            .collect()
+    }
+
+    fn r(kind: &str, value: &str, line: Option<u32>) -> (String, String, Option<u32>) {
+        (kind.to_string(), value.to_string(), line)
+    }
+
+    #[test]
+    fn test_rustc_output_yields_error_codes_and_locations() {
+        let log = "error[E0308]: mismatched types\n  --> src/tools/inner_voice.rs:1432:17\n   |\n\
+                   note: see https://doc.rust-lang.org/error_codes/E0308.html.\n\
+                   Also broke inner_voice.rs:88 and tests/mcp_integration.rs";
+        assert_eq!(
+            refs(log),
+            vec![
+                r("error_code", "E0308", None),
+                r("path", "src/tools/inner_voice.rs", Some(1432)),
+                r(
+                    "url",
+                    "https://doc.rust-lang.org/error_codes/E0308.html",
+                    None
+                ),
+                r("path", "inner_voice.rs", Some(88)),
+                r("path", "tests/mcp_integration.rs", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_windows_and_relative_paths() {
+        let log = r"panicked at C:\Users\sam\repo\src\main.rs:12:5 while reading .\config\surreal_mind.toml (see ../README.md)";
+        assert_eq!(
+            refs(log),
+            vec![
+                r("path", r"C:\Users\sam\repo\src\main.rs", Some(12)),
+                r("path", r".\config\surreal_mind.toml", None),
+                r("path", "../README.md", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prose_is_not_taken_for_paths() {
+        let text = "e.g. bump to v0.8.2, host it on docs.rs, and/or ask about E12345 or XE0308.";
+        assert!(refs(text).is_empty(), "{:?}", refs(text));
+    }
+
+    #[test]
+    fn test_repeats_are_dropped_and_matches_capped() {
+        assert_eq!(refs("E0308 then E0308 again").len(), 1);
+        // Same file at different lines are distinct references
+        assert_eq!(refs("src/a.rs:1 src/a.rs:2 src/a.rs:1").len(), 2);
+
+        let many: String = (0..100).map(|i| format!("src/f{i}.rs:{i} ")).collect();
+        let capped = extract_code_refs(&many);
+        assert_eq!(capped.len(), MAX_CODE_REFS);
+        assert_eq!(capped[0].value, "src/f0.rs");
+    }
+}
@@ -0,0 +1,195 @@
+//! Date bounds for search filters
+//!
+//! [`parse_date_bound`] accepts a calendar date (`YYYY-MM-DD`), an RFC 3339 timestamp with
+//! offset, or a relative form: `now`, `today`, `yesterday`, or `-N` followed by `h`, `d`, or
+//! `w` (hours, days, or weeks before now). Day forms cover the whole UTC day, so as an
+//! upper bound they resolve to the day's last nanosecond; the others are exact instants.
+//! [`parse_date_range`] parses both ends and rejects a range whose start is after its end.
+
+use crate::error::{Result, SurrealMindError};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+const ACCEPTED_FORMS: &str = "YYYY-MM-DD, RFC 3339, now, today, yesterday, or -N[h|d|w]";
+
+/// A resolved date filter; either end may be open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Parse one bound named `label` (used in error messages). `end_of_day` selects the last
+/// instant rather than midnight for day forms.
+pub fn parse_date_bound(
+    value: &str,
+    end_of_day: bool,
+    label: &str,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>> {
+    let invalid = || SurrealMindError::InvalidParams {
+        message: format!(
+            "Invalid {} '{}' (expected {})",
+            label, value, ACCEPTED_FORMS
+        ),
+        field: Some(label.to_string()),
+    };
+    let value = value.trim();
+    let day = match value.to_ascii_lowercase().as_str() {
+        "now" => return Ok(now),
+        "today" => now.date_naive(),
+        "yesterday" => now.date_naive() - Duration::days(1),
+        lower => {
+            if let Some(relative) = lower.strip_prefix('-') {
+                return relative_offset(relative)
+                    .and_then(|offset| now.checked_sub_signed(offset))
+                    .ok_or_else(invalid);
+            }
+            if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+                return Ok(at.with_timezone(&Utc));
+            }
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| invalid())?
+        }
+    };
+    let time = if end_of_day {
+        day.and_hms_nano_opt(23, 59, 59, 999_999_999)
+    } else {
+        day.and_hms_opt(0, 0, 0)
+    };
+    time.map(|t| t.and_utc()).ok_or_else(invalid)
+}
+
+/// `N` followed by `h`, `d`, or `w`
+fn relative_offset(relative: &str) -> Option<Duration> {
+    let unit = relative.chars().last()?;
+    let n: i64 = relative[..relative.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'h' => Duration::try_hours(n),
+        'd' => Duration::try_days(n),
+        'w' => Duration::try_weeks(n),
+        _ => None,
+    }
+}
+
+/// Parse optional `from`/`to` bounds named `names[0]`/`names[1]`; `from` must not be after `to`.
+pub fn parse_date_range(
+    from: Option<&str>,
+    to: Option<&str>,
+    names: [&str; 2],
+    now: DateTime<Utc>,
+) -> Result<DateRange> {
+    let range = DateRange {
+        from: from
+            .map(|v| parse_date_bound(v, false, names[0], now))
+            .transpose()?,
+        to: to
+            .map(|v| parse_date_bound(v, true, names[1], now))
+            .transpose()?,
+    };
+    if let (Some(from), Some(to)) = (range.from, range.to)
+        && from > to
+    {
+        return Err(SurrealMindError::InvalidParams {
+            message: format!(
+                "{} ({}) is after {} ({})",
+                names[0],
+                from.to_rfc3339(),
+                names[1],
+                to.to_rfc3339()
+            ),
+            field: Some(names[1].to_string()),
+        });
+    }
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-06-10T15:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn bound(value: &str, end_of_day: bool) -> String {
+        parse_date_bound(value, end_of_day, "date_from", now())
+            .unwrap()
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn test_calendar_dates_cover_the_whole_day() {
+        assert_eq!(bound("2026-06-08", false), "2026-06-08T00:00:00+00:00");
+        assert_eq!(
+            bound("2026-06-11", true),
+            "2026-06-11T23:59:59.999999999+00:00"
+        );
+    }
+
+    #[test]
+    fn test_rfc3339_is_converted_to_utc() {
+        assert_eq!(
+            bound("2026-06-08T09:00:00+02:00", false),
+            "2026-06-08T07:00:00+00:00"
+        );
+        // An explicit instant is not widened to the end of its day
+        assert_eq!(
+            bound("2026-06-08T07:00:00Z", true),
+            "2026-06-08T07:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_relative_forms_count_back_from_now() {
+        assert_eq!(bound("now", true), "2026-06-10T15:30:00+00:00");
+        assert_eq!(bound("-7d", false), "2026-06-03T15:30:00+00:00");
+        assert_eq!(bound("-12h", false), "2026-06-10T03:30:00+00:00");
+        assert_eq!(bound("-2w", false), "2026-05-27T15:30:00+00:00");
+        assert_eq!(bound("Today", false), "2026-06-10T00:00:00+00:00");
+        assert_eq!(
+            bound("yesterday", true),
+            "2026-06-09T23:59:59.999999999+00:00"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_forms() {
+        for bad in [
+            "2026/06/08",
+            "-7",
+            "-7y",
+            "-d",
+            "2026-06-08T07:00:00",
+            "soon",
+            "",
+        ] {
+            let err = parse_date_bound(bad, false, "date_from", now()).unwrap_err();
+            assert!(
+                matches!(err, SurrealMindError::InvalidParams { .. }),
+                "{bad}: {err}"
+            );
+            assert!(err.to_string().contains("Invalid date_from"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_range_rejects_reversed_bounds() {
+        let names = ["date_from", "date_to"];
+        let err =
+            parse_date_range(Some("2026-06-11"), Some("2026-06-08"), names, now()).unwrap_err();
+        assert!(matches!(err, SurrealMindError::InvalidParams { .. }));
+        assert!(err.to_string().contains("date_from"), "{err}");
+
+        // The same day as both ends is a valid one-day range
+        let day = parse_date_range(Some("2026-06-08"), Some("2026-06-08"), names, now()).unwrap();
+        assert!(day.from < day.to);
+        assert_eq!(
+            parse_date_range(None, Some("-1d"), names, now()).unwrap(),
+            DateRange {
+                from: None,
+                to: Some(now() - Duration::days(1)),
+            }
+        );
+    }
+}
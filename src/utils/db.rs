@@ -1,10 +1,10 @@
 //! Database connection utilities
 
 use crate::error::Result;
+use crate::server::connection::DbEngine;
 use reqwest::Client;
 use std::time::Duration;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client as WsClient;
 
 /// Configuration for HTTP SQL client
 pub struct HttpSqlConfig {
@@ -79,7 +79,7 @@ impl HttpSqlConfig {
 
 /// Upsert a tool session row in a single transaction for continuity tracking.
 pub async fn upsert_tool_session(
-    db: &Surreal<WsClient>,
+    db: &Surreal<DbEngine>,
     tool: String,
     session: String,
     exchange: String,
@@ -0,0 +1,135 @@
+//! Query-term highlighting for search hits
+//!
+//! [`best_window`] picks the word-aligned span of a text with the most query-term hits, so a
+//! client can show the relevant part of a long thought. Offsets are byte offsets into the
+//! text and always fall on word (and so UTF-8 character) boundaries.
+
+/// Default highlight window, in characters.
+pub const DEFAULT_SNIPPET_WINDOW: usize = 240;
+
+/// A word of the text: byte range and character offset of its start and end.
+struct Word {
+    start: usize,
+    end: usize,
+    char_start: usize,
+    char_end: usize,
+}
+
+/// Alphanumeric runs of `text` with their byte and character offsets.
+fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    let mut chars = 0;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), current) {
+            (true, None) => current = Some((i, chars)),
+            (false, Some((start, char_start))) => {
+                words.push(Word {
+                    start,
+                    end: i,
+                    char_start,
+                    char_end: chars,
+                });
+                current = None;
+            }
+            _ => {}
+        }
+        chars += 1;
+    }
+    if let Some((start, char_start)) = current {
+        words.push(Word {
+            start,
+            end: text.len(),
+            char_start,
+            char_end: chars,
+        });
+    }
+    words
+}
+
+/// Byte span `(start, end)` of the word-aligned window of at most `window` characters with
+/// the most case-insensitive `query` term hits, earliest on ties. Without any hit the window
+/// opens the text. A single word longer than `window` is returned whole. `None` when `text`
+/// has no words.
+pub fn best_window(text: &str, query: &str, window: usize) -> Option<(usize, usize)> {
+    let words = words(text);
+    if words.is_empty() {
+        return None;
+    }
+    let terms: Vec<String> = self::words(query)
+        .iter()
+        .map(|w| query[w.start..w.end].to_lowercase())
+        .collect();
+    let hit: Vec<bool> = words
+        .iter()
+        .map(|w| terms.contains(&text[w.start..w.end].to_lowercase()))
+        .collect();
+    let fits = |lo: usize, hi: usize| words[hi].char_end - words[lo].char_start <= window;
+
+    // Densest run of hits that fits, starting at a hit
+    let (mut lo, mut hi, mut best) = (0, 0, 0);
+    let mut end = 0;
+    for start in (0..words.len()).filter(|&i| hit[i]) {
+        end = end.max(start);
+        while end + 1 < words.len() && fits(start, end + 1) {
+            end += 1;
+        }
+        let mut last = end;
+        while !hit[last] {
+            last -= 1;
+        }
+        let hits = hit[start..=last].iter().filter(|&&h| h).count();
+        if hits > best {
+            (lo, hi, best) = (start, last, hits);
+        }
+    }
+
+    // Pad with surrounding words, alternating after and before, while the window allows
+    loop {
+        let mut grew = false;
+        if hi + 1 < words.len() && fits(lo, hi + 1) {
+            hi += 1;
+            grew = true;
+        }
+        if lo > 0 && fits(lo - 1, hi) {
+            lo -= 1;
+            grew = true;
+        }
+        if !grew {
+            break;
+        }
+    }
+    Some((words[lo].start, words[hi].end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet<'a>(text: &'a str, query: &str, window: usize) -> &'a str {
+        let (start, end) = best_window(text, query, window).unwrap();
+        &text[start..end]
+    }
+
+    #[test]
+    fn test_best_window_picks_densest_span() {
+        let text = "cache one. filler filler filler filler filler. cache eviction policy here.";
+        assert_eq!(snippet(text, "Cache eviction", 24), "cache eviction policy");
+        assert_eq!(snippet(text, "unrelated", 15), "cache one");
+        assert_eq!(snippet(text, "cache", 1000), &text[..text.len() - 1]);
+        assert_eq!(best_window("  ...  ", "cache", 10), None);
+    }
+
+    #[test]
+    fn test_best_window_respects_multibyte_boundaries() {
+        let text = "Ünïcödé préambule — 東京 の キャッシュ は 速い ✓ naïve café";
+        let (start, end) = best_window(text, "キャッシュ 速い", 14).unwrap();
+        assert!(text.is_char_boundary(start) && text.is_char_boundary(end));
+        assert_eq!(&text[start..end], "の キャッシュ は 速い");
+        assert!(text[start..end].chars().count() <= 14);
+
+        // Case folding applies beyond ASCII; an oversized word comes back whole
+        assert_eq!(snippet(text, "ÜNÏCÖDÉ", 3), "Ünïcödé");
+        assert_eq!(snippet(text, "CAFÉ", 11), "naïve café");
+    }
+}
@@ -0,0 +1,289 @@
+//! Language detection for thought and observation content
+//!
+//! [`detect_lang`] is a lightweight, offline guess at the ISO 639-1 code of a text. It
+//! first finds the dominant script: a text whose letters are mostly Cyrillic, Greek, Arabic,
+//! Hebrew, Devanagari, Thai, Hangul, or Han/Kana maps straight to that script's main language
+//! (Cyrillic is split into `ru`/`uk` by their distinctive letters). Latin-script text is
+//! scored on common function words for English, German, French, Spanish, Italian,
+//! Portuguese, and Dutch. Anything too short, mixed across scripts, or without a clear
+//! winner (code, lists of names) is [`UNDETERMINED`].
+
+/// Code stored when no language can be told apart
+pub const UNDETERMINED: &str = "und";
+
+/// Fewest letters for an alphabetic script
+const MIN_LETTERS: usize = 12;
+/// Fewest letters for Han, Kana, and Hangul, which pack a word into one or two characters
+const MIN_CJK_LETTERS: usize = 4;
+/// Fewest words for function-word scoring
+const MIN_WORDS: usize = 3;
+/// Fewest function-word hits the winning Latin-script language needs
+const MIN_WORD_HITS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Hangul,
+    /// Han and Kana together; Japanese mixes both
+    Cjk,
+}
+
+const SCRIPTS: [Script; 9] = [
+    Script::Latin,
+    Script::Cyrillic,
+    Script::Greek,
+    Script::Arabic,
+    Script::Hebrew,
+    Script::Devanagari,
+    Script::Thai,
+    Script::Hangul,
+    Script::Cjk,
+];
+
+fn script_of(c: char) -> Option<Script> {
+    if !c.is_alphabetic() {
+        return None;
+    }
+    Some(match c as u32 {
+        0x0041..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+        0x0400..=0x052F => Script::Cyrillic,
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+        0x0600..=0x06FF | 0x0750..=0x077F => Script::Arabic,
+        0x0590..=0x05FF => Script::Hebrew,
+        0x0900..=0x097F => Script::Devanagari,
+        0x0E00..=0x0E7F => Script::Thai,
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF => Script::Cjk,
+        _ => return None,
+    })
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF)
+}
+
+/// Common short words per Latin-script language. Words shared between languages (`de`,
+/// `que`, `en`) score for each, so they only matter alongside distinctive ones.
+const LATIN_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "of", "to", "that", "it", "with", "for", "was", "this",
+            "not", "be", "have", "you", "but", "which",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "mit", "zu", "den", "auf",
+            "ich", "sich", "von", "auch", "wir", "wird",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "une", "des", "du", "dans", "que", "pour", "pas", "ce",
+            "il", "avec", "sur", "de", "nous",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "es", "una", "del", "en", "que", "por", "con", "para", "se",
+            "lo", "como", "de", "pero", "muy",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "gli", "e", "è", "una", "della", "che", "per", "non", "con", "di", "sono",
+            "questo", "anche", "nel", "alla",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "um", "uma", "do", "da", "não", "em", "que", "com", "para", "por", "de",
+            "é", "mais", "também", "são",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "het", "een", "en", "van", "niet", "dat", "op", "met", "voor", "zijn", "ik", "te",
+            "de", "ook", "wordt", "maar",
+        ],
+    ),
+];
+
+/// Russian and Ukrainian letters the other alphabet lacks
+const RU_LETTERS: &[char] = &['ы', 'э', 'ъ', 'ё'];
+const UK_LETTERS: &[char] = &['і', 'ї', 'є', 'ґ'];
+
+/// Best-guess ISO 639-1 code for `text`, or [`UNDETERMINED`].
+pub fn detect_lang(text: &str) -> &'static str {
+    let mut counts = [0usize; SCRIPTS.len()];
+    let mut kana = 0;
+    for c in text.chars() {
+        if let Some(script) = script_of(c) {
+            counts[SCRIPTS.iter().position(|s| *s == script).unwrap_or(0)] += 1;
+            kana += usize::from(is_kana(c));
+        }
+    }
+    let total: usize = counts.iter().sum();
+    let Some((idx, &dominant)) = counts.iter().enumerate().max_by_key(|(_, n)| **n) else {
+        return UNDETERMINED;
+    };
+    // A mixed text has no single language to filter on
+    if dominant * 3 < total * 2 {
+        return UNDETERMINED;
+    }
+    let script = SCRIPTS[idx];
+    let min = match script {
+        Script::Cjk | Script::Hangul => MIN_CJK_LETTERS,
+        _ => MIN_LETTERS,
+    };
+    if dominant < min {
+        return UNDETERMINED;
+    }
+    match script {
+        Script::Latin => latin_lang(text),
+        Script::Cyrillic => cyrillic_lang(text),
+        Script::Greek => "el",
+        Script::Arabic => "ar",
+        Script::Hebrew => "he",
+        Script::Devanagari => "hi",
+        Script::Thai => "th",
+        Script::Hangul => "ko",
+        Script::Cjk if kana > 0 => "ja",
+        Script::Cjk => "zh",
+    }
+}
+
+fn latin_lang(text: &str) -> &'static str {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.len() < MIN_WORDS {
+        return UNDETERMINED;
+    }
+    let mut scores: Vec<(&'static str, usize)> = LATIN_WORDS
+        .iter()
+        .map(|(lang, list)| (*lang, words.iter().filter(|w| list.contains(w)).count()))
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+    match scores.as_slice() {
+        [(lang, best), (_, next), ..] if *best >= MIN_WORD_HITS && best > next => lang,
+        _ => UNDETERMINED,
+    }
+}
+
+fn cyrillic_lang(text: &str) -> &'static str {
+    let lower = text.to_lowercase();
+    let count = |letters: &[char]| lower.chars().filter(|c| letters.contains(c)).count();
+    let (ru, uk) = (count(RU_LETTERS), count(UK_LETTERS));
+    if ru > uk {
+        return "ru";
+    }
+    if uk > ru {
+        return "uk";
+    }
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let hits = |list: &[&str]| words.iter().filter(|w| list.contains(w)).count();
+    let (ru, uk) = (
+        hits(&["что", "это", "как", "он", "она", "они", "его"]),
+        hits(&["що", "це", "як", "він", "вона", "вони", "його"]),
+    );
+    match ru.cmp(&uk) {
+        std::cmp::Ordering::Greater => "ru",
+        std::cmp::Ordering::Less => "uk",
+        std::cmp::Ordering::Equal => UNDETERMINED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_latin_languages_by_function_words() {
+        let cases = [
+            (
+                "en",
+                "The migration is done and the index was rebuilt for this table.",
+            ),
+            (
+                "de",
+                "Die Migration ist fertig und der Index wurde auf die Tabelle neu gebaut.",
+            ),
+            (
+                "fr",
+                "La migration est terminée et les index sont reconstruits dans la base.",
+            ),
+            (
+                "es",
+                "La migración está lista y los índices se reconstruyeron para la tabla.",
+            ),
+            (
+                "it",
+                "La migrazione è finita e gli indici della tabella sono stati ricostruiti.",
+            ),
+            (
+                "pt",
+                "A migração está pronta e os índices da tabela não foram reconstruídos.",
+            ),
+            (
+                "nl",
+                "De migratie is klaar en het index van de tabel wordt niet opnieuw gebouwd.",
+            ),
+        ];
+        for (lang, text) in cases {
+            assert_eq!(detect_lang(text), lang, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_detects_non_latin_scripts() {
+        assert_eq!(
+            detect_lang("Миграция завершена, индексы перестроены, это быстро."),
+            "ru"
+        );
+        assert_eq!(
+            detect_lang("Міграцію завершено, індекси перебудовано, це швидко."),
+            "uk"
+        );
+        assert_eq!(
+            detect_lang("移行が完了しました。索引を再構築します。"),
+            "ja"
+        );
+        assert_eq!(detect_lang("迁移已经完成，索引已重建。"), "zh");
+        assert_eq!(detect_lang("마이그레이션이 완료되었습니다"), "ko");
+        assert_eq!(detect_lang("Η μετάβαση ολοκληρώθηκε με επιτυχία"), "el");
+    }
+
+    #[test]
+    fn test_short_mixed_and_code_are_undetermined() {
+        assert_eq!(detect_lang(""), UNDETERMINED);
+        assert_eq!(detect_lang("ok thanks"), UNDETERMINED);
+        assert_eq!(detect_lang("12345 !!! ---"), UNDETERMINED);
+        assert_eq!(
+            detect_lang("The index was rebuilt. Индексы перестроены быстро."),
+            UNDETERMINED
+        );
+        assert_eq!(
+            detect_lang("fn main() { let x = vec![1, 2]; println!(\"{x:?}\"); }"),
+            UNDETERMINED
+        );
+    }
+}
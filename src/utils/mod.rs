@@ -1,7 +1,13 @@
 //! Utility functions shared across the codebase
 
+pub mod code_refs;
+pub mod dates;
 pub mod db;
+pub mod highlight;
+pub mod lang;
 pub mod math;
+pub mod redaction;
+pub mod tagexpr;
 
 // Re-export commonly used utilities
 pub use db::HttpSqlConfig;
@@ -0,0 +1,307 @@
+//! Boolean tag expressions for search filtering
+//!
+//! [`TagExpr::parse`] reads expressions such as `(rust AND surrealdb) OR mcp`: tag literals
+//! combined with `AND`, `OR`, `NOT`, and parentheses, where `NOT` binds tightest and `OR`
+//! loosest. Keywords are case-insensitive; tags are matched exactly, and a tag that collides
+//! with a keyword or contains spaces or parentheses can be written in double quotes.
+//! Expressions are evaluated in Rust against a candidate's tags, never turned into SQL;
+//! [`TagExpr::required_any`] gives the literals a SQL pre-filter may require.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Deepest parenthesis / `NOT` nesting accepted.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagExpr {
+    Tag(String),
+    Not(Box<TagExpr>),
+    And(Box<TagExpr>, Box<TagExpr>),
+    Or(Box<TagExpr>, Box<TagExpr>),
+}
+
+/// A parse failure at `position`, a 0-based character offset into the expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagExprError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TagExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for TagExprError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Tag(String),
+}
+
+fn error(position: usize, message: impl Into<String>) -> TagExprError {
+    TagExprError {
+        position,
+        message: message.into(),
+    }
+}
+
+/// Tokens with their character offsets.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, TagExprError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' || c == ')' {
+            tokens.push((i, if c == '(' { Token::Open } else { Token::Close }));
+            i += 1;
+        } else if c == '"' {
+            let start = i;
+            let Some(len) = chars[i + 1..].iter().position(|&c| c == '"') else {
+                return Err(error(start, "unterminated quoted tag"));
+            };
+            let tag: String = chars[i + 1..i + 1 + len].iter().collect();
+            if tag.is_empty() {
+                return Err(error(start, "empty quoted tag"));
+            }
+            tokens.push((start, Token::Tag(tag)));
+            i += len + 2;
+        } else {
+            let start = i;
+            while i < chars.len() && !chars[i].is_whitespace() && !"()\"".contains(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let token = match word.to_ascii_uppercase().as_str() {
+                "AND" => Token::And,
+                "OR" => Token::Or,
+                "NOT" => Token::Not,
+                _ => Token::Tag(word),
+            };
+            tokens.push((start, token));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    /// Character length of the input, reported for errors at the end
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.next).map_or(self.end, |(p, _)| *p)
+    }
+
+    fn or(&mut self) -> Result<TagExpr, TagExprError> {
+        let mut expr = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next += 1;
+            expr = TagExpr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<TagExpr, TagExprError> {
+        let mut expr = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.next += 1;
+            expr = TagExpr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<TagExpr, TagExprError> {
+        let position = self.position();
+        match self.peek().cloned() {
+            Some(Token::Not) => {
+                self.next += 1;
+                let inner = self.nested(position, Self::unary)?;
+                Ok(TagExpr::Not(Box::new(inner)))
+            }
+            Some(Token::Open) => {
+                self.next += 1;
+                let inner = self.nested(position, Self::or)?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(match self.peek() {
+                        None => error(position, "unclosed '('"),
+                        Some(_) => error(self.position(), "expected AND, OR, or ')'"),
+                    });
+                }
+                self.next += 1;
+                Ok(inner)
+            }
+            Some(Token::Tag(tag)) => {
+                self.next += 1;
+                Ok(TagExpr::Tag(tag))
+            }
+            Some(Token::Close) => Err(error(position, "unexpected ')'")),
+            _ => Err(error(position, "expected a tag, NOT, or '('")),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        position: usize,
+        parse: fn(&mut Self) -> Result<TagExpr, TagExprError>,
+    ) -> Result<TagExpr, TagExprError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(error(
+                position,
+                format!("expression nested deeper than {MAX_DEPTH}"),
+            ));
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+}
+
+impl TagExpr {
+    /// Parse a tag expression; an empty expression is an error.
+    pub fn parse(input: &str) -> Result<Self, TagExprError> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            next: 0,
+            end: input.chars().count(),
+            depth: 0,
+        };
+        if parser.tokens.is_empty() {
+            return Err(error(0, "empty tag expression"));
+        }
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(Token::Close) => Err(error(parser.position(), "unexpected ')'")),
+            Some(_) => Err(error(parser.position(), "expected AND or OR")),
+        }
+    }
+
+    /// Whether a candidate with `tags` satisfies the expression.
+    pub fn matches(&self, tags: &[String]) -> bool {
+        match self {
+            TagExpr::Tag(tag) => tags.contains(tag),
+            TagExpr::Not(inner) => !inner.matches(tags),
+            TagExpr::And(a, b) => a.matches(tags) && b.matches(tags),
+            TagExpr::Or(a, b) => a.matches(tags) || b.matches(tags),
+        }
+    }
+
+    /// Tags of which every match carries at least one, for a `CONTAINSANY` pre-filter.
+    /// `None` when no such set exists, e.g. under `NOT` or for `x OR NOT y`.
+    pub fn required_any(&self) -> Option<BTreeSet<String>> {
+        match self {
+            TagExpr::Tag(tag) => Some(BTreeSet::from([tag.clone()])),
+            TagExpr::Not(_) => None,
+            TagExpr::Or(a, b) => {
+                let mut any = a.required_any()?;
+                any.extend(b.required_any()?);
+                Some(any)
+            }
+            TagExpr::And(a, b) => match (a.required_any(), b.required_any()) {
+                (Some(a), Some(b)) => Some(if b.len() < a.len() { b } else { a }),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(t: &str) -> Box<TagExpr> {
+        Box::new(TagExpr::Tag(t.into()))
+    }
+
+    fn tags(ts: &[&str]) -> Vec<String> {
+        ts.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_precedence_and_nesting() {
+        // NOT binds tightest, then AND, then OR
+        assert_eq!(
+            TagExpr::parse("a OR b AND NOT c").unwrap(),
+            TagExpr::Or(
+                tag("a"),
+                Box::new(TagExpr::And(tag("b"), Box::new(TagExpr::Not(tag("c")))))
+            )
+        );
+        assert_eq!(
+            TagExpr::parse("(rust and surrealdb) or mcp").unwrap(),
+            TagExpr::Or(
+                Box::new(TagExpr::And(tag("rust"), tag("surrealdb"))),
+                tag("mcp")
+            )
+        );
+        assert_eq!(
+            TagExpr::parse("NOT (a OR b)").unwrap(),
+            TagExpr::Not(Box::new(TagExpr::Or(tag("a"), tag("b"))))
+        );
+        assert_eq!(
+            TagExpr::parse("\"and\" AND \"two words\"").unwrap(),
+            TagExpr::And(tag("and"), tag("two words"))
+        );
+    }
+
+    #[test]
+    fn test_matches_evaluates_against_tags() {
+        let expr = TagExpr::parse("(rust AND surrealdb) OR mcp").unwrap();
+        assert!(expr.matches(&tags(&["rust", "surrealdb"])));
+        assert!(expr.matches(&tags(&["mcp"])));
+        assert!(!expr.matches(&tags(&["rust"])));
+        assert!(!expr.matches(&[]));
+
+        let expr = TagExpr::parse("rust AND NOT NOT draft").unwrap();
+        assert!(expr.matches(&tags(&["rust", "draft"])));
+        assert!(!expr.matches(&tags(&["rust"])));
+        assert!(TagExpr::parse("NOT draft").unwrap().matches(&[]));
+    }
+
+    #[test]
+    fn test_required_any_for_prefilter() {
+        let any = |s: &str| TagExpr::parse(s).unwrap().required_any();
+        let set = |ts: &[&str]| Some(ts.iter().map(|t| t.to_string()).collect());
+        assert_eq!(any("(rust AND surrealdb) OR mcp"), set(&["mcp", "rust"]));
+        assert_eq!(any("a AND NOT b"), set(&["a"]));
+        assert_eq!(any("NOT b"), None);
+        assert_eq!(any("a OR NOT b"), None);
+    }
+
+    #[test]
+    fn test_parse_errors_report_position() {
+        let err = |s: &str| TagExpr::parse(s).unwrap_err();
+        assert_eq!(err("").position, 0);
+        assert_eq!(err("(a OR b").position, 0);
+        assert_eq!(err("a OR").position, 4);
+        assert_eq!(err("a b").position, 2);
+        assert_eq!(err("a AND )").position, 6);
+        assert_eq!(err("a) OR b").position, 1);
+        assert_eq!(err("ü AND \"x").position, 6);
+        assert_eq!(
+            err("(a b)").to_string(),
+            "expected AND, OR, or ')' at position 3"
+        );
+        assert!(err(&"NOT ".repeat(40)).message.contains("nested"));
+    }
+}
@@ -61,6 +61,7 @@ pub fn resolve_workspace(input: &str, map: &WorkspaceMap) -> Result<String> {
     if trimmed.is_empty() {
         return Err(SurrealMindError::InvalidParams {
             message: "cwd cannot be empty".into(),
+            field: None,
         });
     }
 
@@ -97,7 +98,10 @@ pub fn resolve_workspace(input: &str, map: &WorkspaceMap) -> Result<String> {
                 msg.push_str(&format!(". Did you mean '{}'?", closest));
             }
 
-            Err(SurrealMindError::InvalidParams { message: msg })
+            Err(SurrealMindError::InvalidParams {
+                message: msg,
+                field: None,
+            })
         }
     }
 }
@@ -110,6 +114,7 @@ fn expand_tilde(path: &str) -> Result<String> {
 
     let home = std::env::var("HOME").map_err(|_| SurrealMindError::InvalidParams {
         message: "Cannot expand '~': HOME environment variable not set".into(),
+        field: None,
     })?;
 
     if path == "~" {
@@ -123,6 +128,7 @@ fn expand_tilde(path: &str) -> Result<String> {
                 "Unsupported tilde expansion: '{}'. Use '~/' or absolute path.",
                 path
             ),
+            field: None,
         })
     }
 }
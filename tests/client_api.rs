@@ -0,0 +1,99 @@
+#![cfg(feature = "db_integration")]
+
+use surreal_mind::client_api::{
+    KgTarget, NewEntity, NewObservation, NewRelationship, NewThought, SurrealMindClient,
+};
+use surreal_mind::config::Config;
+
+async fn create_test_client() -> SurrealMindClient {
+    let mut config = Config::load().expect("Failed to load config");
+    config.system.database_db = format!("client_api_{}", uuid::Uuid::new_v4().simple());
+    SurrealMindClient::connect(&config)
+        .await
+        .expect("Failed to create client")
+}
+
+#[tokio::test]
+async fn test_client_thought_round_trip() {
+    if std::env::var("RUN_DB_TESTS").is_err() {
+        return;
+    }
+
+    let client = create_test_client().await;
+
+    let created = client
+        .create_thought(NewThought {
+            tags: vec!["client-api".into()],
+            idempotency_key: Some("client-api-round-trip".into()),
+            ..NewThought::new("Websocket reconnects back off exponentially up to a minute")
+        })
+        .await
+        .expect("create_thought should succeed");
+    assert!(!created.id.is_empty());
+    assert!(created.embedding_dim > 0);
+
+    // Same idempotency key returns the same thought
+    let again = client
+        .create_thought(NewThought {
+            idempotency_key: Some("client-api-round-trip".into()),
+            ..NewThought::new("Websocket reconnects back off exponentially up to a minute")
+        })
+        .await
+        .unwrap();
+    assert_eq!(again.id, created.id);
+
+    let hits = client
+        .search_thoughts("websocket reconnect backoff", 5)
+        .await
+        .expect("search_thoughts should succeed");
+    assert!(hits.iter().any(|h| h.id == created.id));
+
+    assert!(client.create_thought(NewThought::new("  ")).await.is_err());
+}
+
+#[tokio::test]
+async fn test_client_kg_create_and_search() {
+    if std::env::var("RUN_DB_TESTS").is_err() {
+        return;
+    }
+
+    let client = create_test_client().await;
+
+    let db = client
+        .create_entity(NewEntity::new("SurrealDB").entity_type("tool"))
+        .await
+        .expect("create_entity should succeed");
+    assert!(db.created);
+    let repo = client
+        .create_entity(NewEntity::new("surreal-mind").entity_type("project"))
+        .await
+        .unwrap();
+
+    // Upsert matches the existing entity by name
+    let again = client
+        .create_entity(NewEntity::new("SurrealDB").entity_type("tool"))
+        .await
+        .unwrap();
+    assert_eq!((again.id.as_str(), again.created), (db.id.as_str(), false));
+
+    let edge = client
+        .create_relationship(NewRelationship::new(&repo.id, "uses", &db.id))
+        .await
+        .expect("create_relationship should succeed");
+    assert!(!edge.id.is_empty());
+    let obs = client
+        .create_observation(NewObservation {
+            confidence: Some(0.8),
+            ..NewObservation::new("SurrealDB 3 dropped the legacy record id syntax")
+        })
+        .await
+        .expect("create_observation should succeed");
+    assert!(obs.created);
+
+    let hits = client
+        .search_kg("SurrealDB", KgTarget::Entity, 5)
+        .await
+        .expect("search_kg should succeed");
+    assert!(hits.iter().all(|h| h.kind == "entity"));
+    assert!(hits.iter().any(|h| h.name.as_deref() == Some("SurrealDB")));
+}
@@ -18,12 +18,16 @@ fn test_list_tools_returns_expected_tools() {
 
     let expected_tools = [
         "think",
+        "think_batch",
         "wander",
         "maintain",
         "rethink",
         "corrections",
         "test_notification",
         "remember",
+        "forget",
+        "export",
+        "import",
         "howto",
         "call_gem",
         "call_cc",
@@ -31,11 +35,13 @@ fn test_list_tools_returns_expected_tools() {
         "call_status",
         "call_jobs",
         "call_cancel",
+        "system_status",
+        "embedding_probe",
     ];
     assert_eq!(
         expected_tools.len(),
-        14,
-        "Tool roster should list entries for all 14 tools"
+        20,
+        "Tool roster should list entries for all 20 tools"
     );
 }
 
@@ -86,7 +92,7 @@ fn test_howto_schema_structure() {
     let expected_schema = json!({
         "type": "object",
         "properties": {
-            "tool": {"type": "string", "enum": ["think", "remember", "search", "maintain", "call_gem", "call_cc", "call_status", "call_jobs", "call_cancel", "wander", "howto", "rethink", "corrections"]},
+            "tool": {"type": "string", "enum": ["think", "remember", "forget", "export", "import", "search", "maintain", "call_gem", "call_cc", "call_status", "call_jobs", "call_cancel", "wander", "howto", "rethink", "corrections"]},
             "format": {"type": "string", "enum": ["compact", "full"], "default": "full"}
         }
     });